@@ -425,3 +425,53 @@ func BenchmarkCIPerformance(b *testing.B) {
 		}
 	}
 }
+
+// scalingSymbolCounts are the workload sizes measured by BenchmarkScaling
+var scalingSymbolCounts = []int{50, 100, 200, 500, 1000}
+
+// BenchmarkScaling measures detection, parsing and compilation separately
+// as the number of symbols grows, so a regression can be traced to a stage.
+// Compare runs with benchstat:
+//
+//	go test -run=^$ -bench=BenchmarkScaling -count=10 . > new.txt
+func BenchmarkScaling(b *testing.B) {
+	for _, count := range scalingSymbolCounts {
+		img, symbols, connections := createLargeTestImage(b, count)
+
+		b.Run(fmt.Sprintf("%d_symbols/detect", count), func(b *testing.B) {
+			d := detector.NewDetector(detector.Config{})
+			b.ReportAllocs()
+			b.ResetTimer()
+			for i := 0; i < b.N; i++ {
+				if _, _, err := d.DetectImage(img); err != nil {
+					b.Fatalf("Detection failed: %v", err)
+				}
+			}
+		})
+
+		// Parsing and compilation use the generated symbols rather than the
+		// detected ones, so their timings do not depend on detection accuracy
+		b.Run(fmt.Sprintf("%d_symbols/parse", count), func(b *testing.B) {
+			b.ReportAllocs()
+			for i := 0; i < b.N; i++ {
+				if _, err := parser.NewParser().Parse(symbols, connections); err != nil {
+					b.Fatalf("Parsing failed: %v", err)
+				}
+			}
+		})
+
+		b.Run(fmt.Sprintf("%d_symbols/compile", count), func(b *testing.B) {
+			program, err := parser.NewParser().Parse(symbols, connections)
+			if err != nil {
+				b.Fatalf("Parsing failed: %v", err)
+			}
+			b.ReportAllocs()
+			b.ResetTimer()
+			for i := 0; i < b.N; i++ {
+				if _, err := compiler.NewCompiler().Compile(program); err != nil {
+					b.Fatalf("Compilation failed: %v", err)
+				}
+			}
+		})
+	}
+}
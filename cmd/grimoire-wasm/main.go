@@ -5,6 +5,8 @@ package main
 
 import (
 	"encoding/base64"
+	"encoding/json"
+	"errors"
 	"fmt"
 	"reflect"
 	"syscall/js"
@@ -12,6 +14,7 @@ import (
 	"github.com/ayutaz/grimoire/internal/compiler"
 	"github.com/ayutaz/grimoire/internal/detector"
 	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/ayutaz/grimoire/pkg/grimoire"
 )
 
 // ProcessImageResult は処理結果を表す構造体
@@ -22,11 +25,16 @@ type ProcessImageResult struct {
 	Code    string `json:"code"`
 }
 
+// defaultCompileTarget はgrimoireCompileで出力形式が省略された場合の形式
+const defaultCompileTarget = "python"
+
 func main() {
 	// WebAssembly用のグローバル関数を登録
 	js.Global().Set("processGrimoireImage", js.FuncOf(processImage))
 	js.Global().Set("validateGrimoireCode", js.FuncOf(validateCode))
 	js.Global().Set("formatGrimoireCode", js.FuncOf(formatCode))
+	js.Global().Set("grimoireDetect", js.FuncOf(detect))
+	js.Global().Set("grimoireCompile", js.FuncOf(compile))
 
 	// プログラムが終了しないようにブロック
 	select {}
@@ -124,6 +132,103 @@ print("Hello from Grimoire!")
 	return createResultWithDebug(true, output, pythonCode, ast, debugInfo, "")
 }
 
+// detect は画像からシンボルと接続を検出する
+// 引数: 画像データ（Uint8ArrayまたはBase64文字列）
+func detect(this js.Value, args []js.Value) interface{} {
+	if len(args) < 1 {
+		return createErrorResult("No image data provided")
+	}
+
+	imageData, err := imageBytes(args[0])
+	if err != nil {
+		return createErrorResult(fmt.Sprintf("Failed to decode image: %v", err))
+	}
+	return detectResult(imageData)
+}
+
+// compile は画像を指定された形式のコードにコンパイルする
+// 引数: 画像データ（Uint8ArrayまたはBase64文字列）、出力形式（省略時は"python"）
+func compile(this js.Value, args []js.Value) interface{} {
+	if len(args) < 1 {
+		return createErrorResult("No image data provided")
+	}
+
+	imageData, err := imageBytes(args[0])
+	if err != nil {
+		return createErrorResult(fmt.Sprintf("Failed to decode image: %v", err))
+	}
+
+	target := defaultCompileTarget
+	if len(args) > 1 && args[1].Type() == js.TypeString {
+		target = args[1].String()
+	}
+	return compileResult(imageData, target)
+}
+
+// detectResult は検出結果をJavaScriptに渡せる形式で返す
+// symbolsとconnectionsはdetector.EncodeJSONと同じ構造で、接続の端点はシンボルの添字
+func detectResult(imageData []byte) map[string]interface{} {
+	symbols, connections, err := grimoire.DetectBytes(imageData, "image")
+	if err != nil {
+		return createErrorResult(err.Error())
+	}
+
+	encoded, err := detector.EncodeJSON(symbols, connections)
+	if err != nil {
+		return createErrorResult(err.Error())
+	}
+	// js.ValueOfが扱える型（map、slice、float64など）に変換する
+	var decoded map[string]interface{}
+	if err := json.Unmarshal(encoded, &decoded); err != nil {
+		return createErrorResult(err.Error())
+	}
+
+	return map[string]interface{}{
+		"success":     true,
+		"symbols":     decoded["symbols"],
+		"connections": decoded["connections"],
+	}
+}
+
+// compileResult は画像をコンパイルした結果をJavaScriptに渡せる形式で返す
+func compileResult(imageData []byte, target string) map[string]interface{} {
+	if target != defaultCompileTarget {
+		return createErrorResult(fmt.Sprintf("Unsupported target: %s", target))
+	}
+
+	symbols, connections, err := grimoire.DetectBytes(imageData, "image")
+	if err != nil {
+		return createErrorResult(err.Error())
+	}
+	program, err := grimoire.Parse(symbols, connections)
+	if err != nil {
+		return createErrorResult(err.Error())
+	}
+	code, err := grimoire.Compile(program)
+	if err != nil {
+		return createErrorResult(err.Error())
+	}
+
+	return map[string]interface{}{
+		"success": true,
+		"code":    code,
+	}
+}
+
+// imageBytes はUint8ArrayまたはBase64文字列から画像データを取り出す
+func imageBytes(v js.Value) ([]byte, error) {
+	if v.Type() == js.TypeString {
+		return base64.StdEncoding.DecodeString(v.String())
+	}
+
+	if !v.InstanceOf(js.Global().Get("Uint8Array")) {
+		return nil, errors.New("image data must be a Uint8Array or a Base64 string")
+	}
+	data := make([]byte, v.Get("length").Int())
+	js.CopyBytesToGo(data, v)
+	return data, nil
+}
+
 // validateCode はGrimoireコードを検証する
 func validateCode(this js.Value, args []js.Value) interface{} {
 	// WebAssembly版では簡易的な実装
@@ -5,6 +5,9 @@ package main
 
 import (
 	"encoding/json"
+	"os"
+	"path/filepath"
+	"strings"
 	"testing"
 )
 
@@ -329,3 +332,44 @@ func TestJSONCompatibility(t *testing.T) {
 		t.Errorf("Expected symbolCount to be 1, got %v", unmarshaled["symbolCount"])
 	}
 }
+
+func TestCompileResult(t *testing.T) {
+	imageData, err := os.ReadFile(filepath.Join("..", "..", "examples", "images", "hello_world.png"))
+	if err != nil {
+		t.Skip("Example image not found")
+	}
+
+	result := compileResult(imageData, defaultCompileTarget)
+	if result["success"] != true {
+		t.Fatalf("Expected success, got error %v", result["error"])
+	}
+	code, _ := result["code"].(string)
+	if !strings.Contains(code, "print(") {
+		t.Errorf("Expected Python code, got %q", code)
+	}
+
+	detected := detectResult(imageData)
+	if detected["success"] != true {
+		t.Fatalf("Expected success, got error %v", detected["error"])
+	}
+	if symbols, ok := detected["symbols"].([]interface{}); !ok || len(symbols) == 0 {
+		t.Errorf("Expected detected symbols, got %v", detected["symbols"])
+	}
+}
+
+func TestCompileResultErrors(t *testing.T) {
+	result := compileResult([]byte("not an image"), "rust")
+	if result["success"] != false || result["error"] != "Unsupported target: rust" {
+		t.Errorf("Expected unsupported target error, got %v", result)
+	}
+
+	result = compileResult([]byte("not an image"), defaultCompileTarget)
+	if result["success"] != false {
+		t.Errorf("Expected failure for invalid image, got %v", result)
+	}
+
+	result = detectResult([]byte("not an image"))
+	if result["success"] != false {
+		t.Errorf("Expected failure for invalid image, got %v", result)
+	}
+}
@@ -25,15 +25,8 @@ func run() int {
 
 	// CLIの実行
 	if err := cli.Execute(version, commit, date); err != nil {
-		// Format error output based on error type
-		if _, ok := err.(*grimoireErrors.EnhancedError); ok {
-			// Enhanced error already has proper formatting
-			fmt.Fprintln(os.Stderr, err)
-		} else {
-			// Legacy error format
-			fmt.Fprintf(os.Stderr, i18n.T("error.error_prefix"), err)
-		}
-		return 1
+		cli.ReportError(os.Stderr, err)
+		return grimoireErrors.ExitCode(err)
 	}
 
 	// デバッグモードの場合は実行時間を表示
@@ -41,5 +34,5 @@ func run() int {
 		fmt.Fprintf(os.Stderr, i18n.T("error.execution_time"), time.Since(start))
 	}
 
-	return 0
+	return grimoireErrors.ExitOK
 }
@@ -0,0 +1,84 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/pkg/grimoire"
+)
+
+// targetPython is the only compile target so far
+const targetPython = "python"
+
+// imageName names the image in error locations
+const imageName = "<image>"
+
+// main is required by -buildmode=c-shared. It lives outside main.go so the
+// package still builds, and its tests run, without cgo.
+func main() {}
+
+// inputError returns the error for image data the caller passed as a NULL
+// pointer or with a negative length, or nil when the data can be read
+func inputError(null bool, length int) error {
+	if !null && length >= 0 {
+		return nil
+	}
+	return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("lib.invalid_input", length)).
+		WithSuggestion(i18n.T("lib.invalid_input_suggestion"))
+}
+
+// panicDiagnostics returns the diagnostics for a panic recovered in an
+// exported function, so that a bug in Grimoire is reported to the host
+// program rather than crashing it
+func panicDiagnostics(errorType grimoireErrors.ErrorType, r interface{}) []byte {
+	return diagnosticsJSON(grimoireErrors.NewError(errorType, i18n.Tf("lib.panic", r)))
+}
+
+// detectJSON detects the symbols in data and encodes the results, or the
+// diagnostics of the failure, as a grimoire.Document
+func detectJSON(data []byte) []byte {
+	symbols, connections, err := grimoire.DetectBytes(data, imageName)
+	if err != nil {
+		return diagnosticsJSON(err)
+	}
+	encoded, err := json.Marshal(grimoire.Document{Symbols: symbols, Connections: connections})
+	if err != nil {
+		return diagnosticsJSON(err)
+	}
+	return encoded
+}
+
+// compileToTarget compiles the image in data to target. On failure the
+// code is empty and the diagnostics are returned as a grimoire.Document.
+func compileToTarget(data []byte, target string) (string, []byte) {
+	if target != targetPython {
+		err := grimoireErrors.NewError(grimoireErrors.ValidationError, fmt.Sprintf("Unsupported target: %s", target))
+		return "", diagnosticsJSON(err)
+	}
+
+	symbols, connections, err := grimoire.DetectBytes(data, imageName)
+	if err != nil {
+		return "", diagnosticsJSON(err)
+	}
+	program, err := grimoire.Parse(symbols, connections)
+	if err != nil {
+		return "", diagnosticsJSON(err)
+	}
+	code, err := grimoire.Compile(program)
+	if err != nil {
+		return "", diagnosticsJSON(err)
+	}
+	return code, nil
+}
+
+// diagnosticsJSON encodes err as a grimoire.Document holding only diagnostics
+func diagnosticsJSON(err error) []byte {
+	encoded, marshalErr := json.Marshal(grimoire.Document{Diagnostics: grimoire.Diagnostics(err)})
+	if marshalErr != nil {
+		// Diagnostics are plain strings, so this cannot happen in practice
+		return []byte(`{"schemaVersion":1}`)
+	}
+	return encoded
+}
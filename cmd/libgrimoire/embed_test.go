@@ -0,0 +1,88 @@
+package main
+
+import (
+	"encoding/json"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/pkg/grimoire"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestDetectJSON(t *testing.T) {
+	data, err := os.ReadFile(filepath.Join("..", "..", "examples", "images", "hello_world.png"))
+	if err != nil {
+		t.Skip("Example image not found")
+	}
+
+	var doc grimoire.Document
+	require.NoError(t, json.Unmarshal(detectJSON(data), &doc))
+	assert.NotEmpty(t, doc.Symbols)
+	assert.Empty(t, doc.Diagnostics)
+
+	require.NoError(t, json.Unmarshal(detectJSON([]byte("not an image")), &doc))
+	assert.Empty(t, doc.Symbols)
+	require.NotEmpty(t, doc.Diagnostics)
+	assert.Equal(t, grimoire.UnsupportedFormat, doc.Diagnostics[0].Type)
+}
+
+func TestCompileToTarget(t *testing.T) {
+	data, err := os.ReadFile(filepath.Join("..", "..", "examples", "images", "hello_world.png"))
+	if err != nil {
+		t.Skip("Example image not found")
+	}
+
+	code, diagnostics := compileToTarget(data, targetPython)
+	require.Nil(t, diagnostics, string(diagnostics))
+	assert.Contains(t, code, "print(")
+
+	code, diagnostics = compileToTarget(data, "lua")
+	assert.Empty(t, code)
+	var doc grimoire.Document
+	require.NoError(t, json.Unmarshal(diagnostics, &doc))
+	require.Len(t, doc.Diagnostics, 1)
+	assert.Equal(t, grimoire.ValidationError, doc.Diagnostics[0].Type)
+	assert.Contains(t, doc.Diagnostics[0].Message, "lua")
+}
+
+func TestInputError(t *testing.T) {
+	assert.NoError(t, inputError(false, 0))
+	assert.NoError(t, inputError(false, 100))
+
+	for _, tt := range []struct {
+		name   string
+		null   bool
+		length int
+	}{
+		{"NULL data", true, 100},
+		{"negative length", false, -1},
+	} {
+		t.Run(tt.name, func(t *testing.T) {
+			err := inputError(tt.null, tt.length)
+			require.Error(t, err)
+			var doc grimoire.Document
+			require.NoError(t, json.Unmarshal(diagnosticsJSON(err), &doc))
+			require.Len(t, doc.Diagnostics, 1)
+			assert.Equal(t, grimoire.ValidationError, doc.Diagnostics[0].Type)
+		})
+	}
+}
+
+func TestPanicDiagnostics(t *testing.T) {
+	recovered := func() (diagnostics []byte) {
+		defer func() {
+			if r := recover(); r != nil {
+				diagnostics = panicDiagnostics(grimoire.CompilationError, r)
+			}
+		}()
+		panic("index out of range")
+	}
+
+	var doc grimoire.Document
+	require.NoError(t, json.Unmarshal(recovered(), &doc))
+	require.Len(t, doc.Diagnostics, 1)
+	assert.Equal(t, grimoire.CompilationError, doc.Diagnostics[0].Type)
+	assert.Contains(t, doc.Diagnostics[0].Message, "index out of range")
+}
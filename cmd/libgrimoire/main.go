@@ -0,0 +1,75 @@
+// Command libgrimoire builds Grimoire as a C shared library so programs
+// written in C or C++, such as game engines, can compile player-drawn magic
+// circles at runtime:
+//
+//	go build -buildmode=c-shared -o libgrimoire.so ./cmd/libgrimoire
+//
+// The build also writes libgrimoire.h declaring the functions below. Every
+// string returned by the library must be released with grimoire_free. Image
+// data passed as NULL or with a negative length, and any panic inside the
+// library, are reported as diagnostics rather than crashing the caller.
+package main
+
+/*
+#include <stdlib.h>
+*/
+import "C"
+
+import (
+	"unsafe"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+)
+
+// grimoire_detect_json detects the symbols in the encoded image of the given
+// length and returns a JSON document with the detection results, or with
+// diagnostics if detection failed. The document has the schema written by
+// grimoire.Document.
+//
+//export grimoire_detect_json
+func grimoire_detect_json(data *C.char, length C.int) (result *C.char) {
+	defer func() {
+		if r := recover(); r != nil {
+			result = C.CString(string(panicDiagnostics(grimoireErrors.ImageProcessingError, r)))
+		}
+	}()
+	if err := inputError(data == nil, int(length)); err != nil {
+		return C.CString(string(diagnosticsJSON(err)))
+	}
+	return C.CString(string(detectJSON(C.GoBytes(unsafe.Pointer(data), length))))
+}
+
+// grimoire_compile_to_target compiles the encoded image of the given length
+// to the target language ("python") and returns the generated code. On
+// failure it returns NULL and, if errorJSON is not NULL, stores a JSON
+// document with diagnostics in *errorJSON.
+//
+//export grimoire_compile_to_target
+func grimoire_compile_to_target(data *C.char, length C.int, target *C.char, errorJSON **C.char) (result *C.char) {
+	fail := func(diagnostics []byte) *C.char {
+		if errorJSON != nil {
+			*errorJSON = C.CString(string(diagnostics))
+		}
+		return nil
+	}
+	defer func() {
+		if r := recover(); r != nil {
+			result = fail(panicDiagnostics(grimoireErrors.CompilationError, r))
+		}
+	}()
+	if err := inputError(data == nil, int(length)); err != nil {
+		return fail(diagnosticsJSON(err))
+	}
+	code, diagnostics := compileToTarget(C.GoBytes(unsafe.Pointer(data), length), C.GoString(target))
+	if diagnostics != nil {
+		return fail(diagnostics)
+	}
+	return C.CString(code)
+}
+
+// grimoire_free releases a string returned by the library
+//
+//export grimoire_free
+func grimoire_free(s *C.char) {
+	C.free(unsafe.Pointer(s))
+}
@@ -0,0 +1,42 @@
+// Package examples ships the sample magic circles in images/ with the
+// grimoire binary, so `grimoire examples run` works without a checkout.
+package examples
+
+import (
+	"embed"
+	"path"
+)
+
+//go:embed images/*.png
+var images embed.FS
+
+// Example is a sample magic circle in the gallery
+type Example struct {
+	Name  string // Name used on the command line
+	Image string // File name of the image in images/
+}
+
+// Gallery lists the examples in the order a new user should try them
+var Gallery = []Example{
+	{Name: "hello-world", Image: "hello_world.png"},
+	{Name: "variables", Image: "variables.png"},
+	{Name: "calculator", Image: "calculator.png"},
+	{Name: "loop", Image: "loop.png"},
+	{Name: "fibonacci", Image: "fibonacci.png"},
+	{Name: "parallel", Image: "parallel.png"},
+}
+
+// Lookup returns the example with the given name
+func Lookup(name string) (Example, bool) {
+	for _, example := range Gallery {
+		if example.Name == name {
+			return example, true
+		}
+	}
+	return Example{}, false
+}
+
+// ImageData returns the encoded image of the example
+func (e Example) ImageData() ([]byte, error) {
+	return images.ReadFile(path.Join("images", e.Image))
+}
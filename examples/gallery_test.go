@@ -0,0 +1,32 @@
+package examples
+
+import (
+	"bytes"
+	"image/png"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestGalleryImages(t *testing.T) {
+	names := make(map[string]bool)
+	for _, example := range Gallery {
+		assert.False(t, names[example.Name], "duplicate example %s", example.Name)
+		names[example.Name] = true
+
+		data, err := example.ImageData()
+		require.NoError(t, err, example.Name)
+		_, err = png.Decode(bytes.NewReader(data))
+		assert.NoError(t, err, example.Name)
+	}
+}
+
+func TestLookup(t *testing.T) {
+	example, ok := Lookup("fibonacci")
+	require.True(t, ok)
+	assert.Equal(t, "fibonacci.png", example.Image)
+
+	_, ok = Lookup("missing")
+	assert.False(t, ok)
+}
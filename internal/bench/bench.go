@@ -0,0 +1,287 @@
+// Package bench measures how long detection, parsing and compilation take
+// on a set of images, and compares the measurements with those saved by
+// another version to report where it got slower or faster.
+package bench
+
+import (
+	"encoding/json"
+	"fmt"
+	"math"
+	"os"
+	"path/filepath"
+	"sort"
+	"time"
+
+	"github.com/ayutaz/grimoire/internal/compiler"
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/ayutaz/grimoire/internal/security"
+)
+
+const (
+	// DefaultIterations is how many times each stage is timed per image
+	DefaultIterations = 10
+	// DefaultThreshold is the relative change of the median below which a
+	// benchmark counts as unchanged
+	DefaultThreshold = 0.05
+	// ReportSchemaVersion is the version of the JSON written by Save. It is
+	// increased whenever the encoding changes incompatibly; adding fields
+	// does not change it.
+	ReportSchemaVersion = 1
+)
+
+// Stages of the pipeline that are timed
+const (
+	StageDetect  = "detect"
+	StageParse   = "parse"
+	StageCompile = "compile"
+)
+
+// Result is how long one stage took on one image
+type Result struct {
+	// Name identifies the benchmark as the stage and the image file name,
+	// as in "detect/hello_world.png"
+	Name       string `json:"name"`
+	Iterations int    `json:"iterations"`
+	// Median and Min are the middle and the fastest time of an iteration,
+	// in nanoseconds
+	Median float64 `json:"medianNs"`
+	Min    float64 `json:"minNs"`
+	// Noise is the median absolute deviation of the times, relative to the
+	// median
+	Noise float64 `json:"noise"`
+}
+
+// Report is the results of a benchmark run, as saved to compare later runs
+// with
+type Report struct {
+	// Version is the version of grimoire that ran the benchmarks
+	Version string    `json:"version"`
+	Created time.Time `json:"created"`
+	Results []Result  `json:"results"`
+}
+
+// reportJSON is the encoded form of a report
+type reportJSON struct {
+	SchemaVersion int `json:"schemaVersion"`
+	*Report
+}
+
+// legacyResultsJSON holds the times of a report saved before the schema was
+// versioned, when they were written in snake case
+type legacyResultsJSON struct {
+	Results []struct {
+		Median float64 `json:"median_ns"`
+		Min    float64 `json:"min_ns"`
+	} `json:"results"`
+}
+
+// Load reads the report saved at path
+func Load(path string) (*Report, error) {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil, err
+	}
+	var report Report
+	decoded := reportJSON{Report: &report}
+	if err := json.Unmarshal(data, &decoded); err != nil {
+		return nil, err
+	}
+	if decoded.SchemaVersion > ReportSchemaVersion {
+		return nil, fmt.Errorf("unsupported benchmark schema version %d (supported: %d)",
+			decoded.SchemaVersion, ReportSchemaVersion)
+	}
+	if decoded.SchemaVersion == 0 {
+		var legacy legacyResultsJSON
+		if err := json.Unmarshal(data, &legacy); err != nil {
+			return nil, err
+		}
+		for i, r := range legacy.Results {
+			if i < len(report.Results) {
+				report.Results[i].Median = r.Median
+				report.Results[i].Min = r.Min
+			}
+		}
+	}
+	return &report, nil
+}
+
+// Save writes the report to path
+func (r *Report) Save(path string) error {
+	data, err := json.MarshalIndent(reportJSON{SchemaVersion: ReportSchemaVersion, Report: r}, "", "  ")
+	if err != nil {
+		return err
+	}
+	return os.WriteFile(path, append(data, '\n'), 0o644)
+}
+
+// Runner times the stages of the pipeline on images
+type Runner struct {
+	Backend detector.Backend
+	// Iterations is how many times each stage is timed
+	Iterations int
+}
+
+// NewRunner creates a runner with the default iterations
+func NewRunner(backend detector.Backend) *Runner {
+	return &Runner{Backend: backend, Iterations: DefaultIterations}
+}
+
+// Run times detection, parsing and compilation of the image at imagePath.
+// The image is decoded once beforehand, so that reading the file is not
+// timed, and each stage starts from the output of the one before.
+func (r *Runner) Run(imagePath string) ([]Result, error) {
+	img, err := security.NewSafeImageDecoder(security.NewImageValidator()).DecodeImage(imagePath)
+	if err != nil {
+		return nil, err
+	}
+	name := filepath.Base(imagePath)
+
+	var symbols []*detector.Symbol
+	var connections []detector.Connection
+	detect, err := r.measure(StageDetect+"/"+name, func() error {
+		var err error
+		symbols, connections, err = r.Backend.DetectImage(img)
+		return err
+	})
+	if err != nil {
+		return nil, err
+	}
+
+	var program *parser.Program
+	parse, err := r.measure(StageParse+"/"+name, func() error {
+		var err error
+		program, err = parser.NewParser().Parse(symbols, connections)
+		return err
+	})
+	if err != nil {
+		return nil, err
+	}
+
+	compile, err := r.measure(StageCompile+"/"+name, func() error {
+		_, err := compiler.NewCompiler().Compile(program)
+		return err
+	})
+	if err != nil {
+		return nil, err
+	}
+	return []Result{detect, parse, compile}, nil
+}
+
+// measure times the iterations of f
+func (r *Runner) measure(name string, f func() error) (Result, error) {
+	samples := make([]float64, max(r.Iterations, 1))
+	for i := range samples {
+		start := time.Now()
+		if err := f(); err != nil {
+			return Result{}, err
+		}
+		samples[i] = float64(time.Since(start).Nanoseconds())
+	}
+	return summarize(name, samples), nil
+}
+
+// summarize returns the result of the times of the iterations of a
+// benchmark
+func summarize(name string, samples []float64) Result {
+	sorted := append([]float64(nil), samples...)
+	sort.Float64s(sorted)
+	median := medianOf(sorted)
+
+	deviations := make([]float64, len(sorted))
+	for i, s := range sorted {
+		deviations[i] = math.Abs(s - median)
+	}
+	sort.Float64s(deviations)
+	noise := 0.0
+	if median > 0 {
+		noise = medianOf(deviations) / median
+	}
+	return Result{Name: name, Iterations: len(samples), Median: median, Min: sorted[0], Noise: noise}
+}
+
+// medianOf returns the median of sorted values
+func medianOf(sorted []float64) float64 {
+	n := len(sorted)
+	if n%2 == 1 {
+		return sorted[n/2]
+	}
+	return (sorted[n/2-1] + sorted[n/2]) / 2
+}
+
+// Verdict is how a benchmark changed from the baseline
+type Verdict string
+
+// Verdicts of a comparison
+const (
+	Regressed Verdict = "regressed"
+	Improved  Verdict = "improved"
+	Unchanged Verdict = "unchanged"
+	// Added benchmarks are missing from the baseline
+	Added Verdict = "added"
+	// Removed benchmarks are missing from the current run
+	Removed Verdict = "removed"
+)
+
+// Comparison is how one benchmark changed from the baseline
+type Comparison struct {
+	Name string
+	// Baseline and Current are the median times, in nanoseconds, or zero
+	// when the benchmark is missing from the run
+	Baseline float64
+	Current  float64
+	// Change is the relative change of the median, positive when slower
+	Change  float64
+	Verdict Verdict
+}
+
+// Compare compares the results of current with those of baseline, in the
+// order of current followed by the benchmarks it no longer has. A change
+// counts when it exceeds both threshold and the noise of the two runs, so
+// that a noisy benchmark is not reported as regressed by chance.
+func Compare(baseline, current *Report, threshold float64) []Comparison {
+	before := make(map[string]Result, len(baseline.Results))
+	for _, r := range baseline.Results {
+		before[r.Name] = r
+	}
+
+	var comparisons []Comparison
+	seen := make(map[string]bool, len(current.Results))
+	for _, r := range current.Results {
+		seen[r.Name] = true
+		old, ok := before[r.Name]
+		if !ok {
+			comparisons = append(comparisons, Comparison{Name: r.Name, Current: r.Median, Verdict: Added})
+			continue
+		}
+		c := Comparison{Name: r.Name, Baseline: old.Median, Current: r.Median, Verdict: Unchanged}
+		if old.Median > 0 {
+			c.Change = r.Median/old.Median - 1
+		}
+		limit := math.Max(threshold, old.Noise+r.Noise)
+		switch {
+		case c.Change > limit:
+			c.Verdict = Regressed
+		case c.Change < -limit:
+			c.Verdict = Improved
+		}
+		comparisons = append(comparisons, c)
+	}
+	for _, r := range baseline.Results {
+		if !seen[r.Name] {
+			comparisons = append(comparisons, Comparison{Name: r.Name, Baseline: r.Median, Verdict: Removed})
+		}
+	}
+	return comparisons
+}
+
+// Count returns how many of the comparisons have the verdict
+func Count(comparisons []Comparison, verdict Verdict) int {
+	n := 0
+	for _, c := range comparisons {
+		if c.Verdict == verdict {
+			n++
+		}
+	}
+	return n
+}
@@ -0,0 +1,143 @@
+package bench
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+	"time"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestSummarize(t *testing.T) {
+	result := summarize("detect/a.png", []float64{5, 1, 3, 2, 4})
+	assert.Equal(t, "detect/a.png", result.Name)
+	assert.Equal(t, 5, result.Iterations)
+	assert.Equal(t, 3.0, result.Median)
+	assert.Equal(t, 1.0, result.Min)
+	assert.InDelta(t, 1.0/3, result.Noise, 1e-9)
+
+	assert.Equal(t, 2.5, summarize("even", []float64{4, 1, 2, 3}).Median)
+}
+
+func TestCompare(t *testing.T) {
+	baseline := &Report{Results: []Result{
+		{Name: "detect/a.png", Median: 100, Noise: 0.01},
+		{Name: "parse/a.png", Median: 100},
+		{Name: "compile/a.png", Median: 100, Noise: 0.1},
+		{Name: "detect/old.png", Median: 50},
+	}}
+	current := &Report{Results: []Result{
+		{Name: "detect/a.png", Median: 120, Noise: 0.01},
+		{Name: "parse/a.png", Median: 90},
+		// Within the noise of the two runs
+		{Name: "compile/a.png", Median: 110, Noise: 0.1},
+		{Name: "detect/new.png", Median: 70},
+	}}
+
+	comparisons := Compare(baseline, current, DefaultThreshold)
+	require.Len(t, comparisons, 5)
+	verdicts := make(map[string]Verdict)
+	for _, c := range comparisons {
+		verdicts[c.Name] = c.Verdict
+	}
+	assert.Equal(t, map[string]Verdict{
+		"detect/a.png":   Regressed,
+		"parse/a.png":    Improved,
+		"compile/a.png":  Unchanged,
+		"detect/new.png": Added,
+		"detect/old.png": Removed,
+	}, verdicts)
+	assert.InDelta(t, 0.2, comparisons[0].Change, 1e-9)
+	assert.Equal(t, "detect/old.png", comparisons[4].Name)
+	assert.Equal(t, 50.0, comparisons[4].Baseline)
+
+	assert.Equal(t, 1, Count(comparisons, Regressed))
+	assert.Equal(t, 0, Count(Compare(baseline, baseline, DefaultThreshold), Regressed))
+}
+
+func TestReportSaveLoad(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "bench.json")
+	report := &Report{
+		Version: "1.2.3",
+		Created: time.Date(2026, 1, 2, 3, 4, 5, 0, time.UTC),
+		Results: []Result{{Name: "detect/a.png", Iterations: 3, Median: 1500, Min: 1200, Noise: 0.1}},
+	}
+	require.NoError(t, report.Save(path))
+
+	loaded, err := Load(path)
+	require.NoError(t, err)
+	assert.Equal(t, report, loaded)
+
+	_, err = Load(filepath.Join(t.TempDir(), "missing.json"))
+	assert.Error(t, err)
+}
+
+// TestReportSchemaSnapshot guards the version 1 schema of saved reports. If
+// it fails, either restore compatibility or increase ReportSchemaVersion and
+// add a new snapshot, keeping the old one loadable.
+func TestReportSchemaSnapshot(t *testing.T) {
+	snapshotPath := filepath.Join("testdata", "report.v1.json")
+	snapshot, err := os.ReadFile(snapshotPath)
+	require.NoError(t, err)
+
+	report := &Report{
+		Version: "1.2.3",
+		Created: time.Date(2026, 1, 2, 3, 4, 5, 0, time.UTC),
+		Results: []Result{{Name: "detect/a.png", Iterations: 3, Median: 1500, Min: 1200, Noise: 0.1}},
+	}
+	path := filepath.Join(t.TempDir(), "bench.json")
+	require.NoError(t, report.Save(path))
+	data, err := os.ReadFile(path)
+	require.NoError(t, err)
+	assert.JSONEq(t, string(snapshot), string(data))
+
+	loaded, err := Load(snapshotPath)
+	require.NoError(t, err)
+	assert.Equal(t, report, loaded)
+}
+
+func TestLoadSchemaVersions(t *testing.T) {
+	dir := t.TempDir()
+
+	// Written before the schema was versioned, in snake case
+	legacy := filepath.Join(dir, "legacy.json")
+	require.NoError(t, os.WriteFile(legacy, []byte(`{"version": "1.0.0", "results": [
+		{"name": "detect/a.png", "iterations": 3, "median_ns": 1500, "min_ns": 1200, "noise": 0.1}]}`), 0o644))
+	report, err := Load(legacy)
+	require.NoError(t, err)
+	require.Len(t, report.Results, 1)
+	assert.Equal(t, 1500.0, report.Results[0].Median)
+	assert.Equal(t, 1200.0, report.Results[0].Min)
+
+	future := filepath.Join(dir, "future.json")
+	require.NoError(t, os.WriteFile(future, []byte(`{"schemaVersion": 2, "results": []}`), 0o644))
+	_, err = Load(future)
+	assert.ErrorContains(t, err, "schema version 2")
+}
+
+func TestRun(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); err != nil {
+		t.Skip("example image not found")
+	}
+	backend, err := detector.NewBackend(detector.BackendSequential, detector.Config{})
+	require.NoError(t, err)
+
+	runner := NewRunner(backend)
+	runner.Iterations = 2
+	results, err := runner.Run(imagePath)
+	require.NoError(t, err)
+	require.Len(t, results, 3)
+	for i, stage := range []string{StageDetect, StageParse, StageCompile} {
+		assert.Equal(t, stage+"/hello_world.png", results[i].Name)
+		assert.Equal(t, 2, results[i].Iterations)
+		assert.Greater(t, results[i].Median, 0.0)
+		assert.LessOrEqual(t, results[i].Min, results[i].Median)
+	}
+
+	_, err = runner.Run(filepath.Join(t.TempDir(), "missing.png"))
+	assert.Error(t, err)
+}
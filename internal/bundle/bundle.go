@@ -0,0 +1,285 @@
+// Package bundle reads and writes .grimoire archives: zip files holding a
+// magic circle's source image together with the symbols detected in it, its
+// syntax tree and its compiled program, so that a spell can be shared and
+// run as one file that records where it came from.
+package bundle
+
+import (
+	"archive/zip"
+	"bytes"
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"io"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+	"time"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/security"
+)
+
+// Extension is the file extension of bundles
+const Extension = ".grimoire"
+
+// FormatVersion is the version of the bundle layout written by Write. It is
+// increased whenever the layout changes incompatibly.
+const FormatVersion = 1
+
+// Entries of a bundle. The source image is stored as SourceEntry followed
+// by the extension of the original file.
+const (
+	ManifestEntry = "manifest.json"
+	SymbolsEntry  = "symbols.json"
+	ProgramEntry  = "program.json"
+	CodeEntry     = "program.py"
+	SourceEntry   = "source"
+)
+
+// maxEntrySize bounds how much of any entry is read, so that a crafted
+// archive cannot exhaust memory
+var maxEntrySize = security.NewImageValidator().MaxFileSize
+
+// Manifest describes a bundle and where its program came from
+type Manifest struct {
+	FormatVersion int `json:"formatVersion"`
+	// Source is the file name of the image the program was compiled from
+	Source string `json:"source"`
+	// Libraries are the file names of the library images linked in
+	Libraries []string `json:"libraries,omitempty"`
+	// GrimoireVersion is the version of Grimoire that compiled the program
+	GrimoireVersion string `json:"grimoireVersion"`
+	// Created is when the bundle was written
+	Created time.Time `json:"created"`
+	// Target is the language of the compiled program
+	Target string `json:"target"`
+	// OptLevel is the optimization level the program was compiled at
+	OptLevel int `json:"optLevel"`
+	// Digests are the SHA-256 digests of the other entries, hex encoded,
+	// by entry name
+	Digests map[string]string `json:"digests"`
+}
+
+// Bundle is the content of a .grimoire archive
+type Bundle struct {
+	Manifest Manifest
+	// Image is the source image, as read from the file named in the manifest
+	Image []byte
+	// Symbols is the detection result, as encoded by detector.EncodeJSON
+	Symbols []byte
+	// Program is the syntax tree, as encoded by parser.EncodeJSON
+	Program []byte
+	// Code is the compiled program
+	Code []byte
+	// Signature signs the program, or is nil when the bundle is unsigned
+	Signature *Signature
+}
+
+// IsBundle reports whether path names a bundle, judging by its extension
+func IsBundle(path string) bool {
+	return strings.EqualFold(filepath.Ext(path), Extension)
+}
+
+// sourceEntry returns the entry name the source image is stored under
+func (b *Bundle) sourceEntry() string {
+	return SourceEntry + strings.ToLower(filepath.Ext(b.Manifest.Source))
+}
+
+// entries returns the entries of the bundle other than the manifest, by name
+func (b *Bundle) entries() map[string][]byte {
+	return map[string][]byte{
+		b.sourceEntry(): b.Image,
+		SymbolsEntry:    b.Symbols,
+		ProgramEntry:    b.Program,
+		CodeEntry:       b.Code,
+	}
+}
+
+// Write writes the bundle to w as a zip archive, filling in the format
+// version and digests of the manifest
+func (b *Bundle) Write(w io.Writer) error {
+	entries := b.entries()
+	b.Manifest.FormatVersion = FormatVersion
+	b.Manifest.Digests = make(map[string]string, len(entries))
+	for name, data := range entries {
+		b.Manifest.Digests[name] = digest(data)
+	}
+	manifest, err := json.MarshalIndent(b.Manifest, "", "  ")
+	if err != nil {
+		return err
+	}
+
+	archive := zip.NewWriter(w)
+	names := make([]string, 0, len(entries))
+	for name := range entries {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	if err := writeEntry(archive, ManifestEntry, manifest, b.Manifest.Created); err != nil {
+		return err
+	}
+	for _, name := range names {
+		if err := writeEntry(archive, name, entries[name], b.Manifest.Created); err != nil {
+			return err
+		}
+	}
+	if b.Signature != nil {
+		signature, err := json.MarshalIndent(b.Signature, "", "  ")
+		if err != nil {
+			return err
+		}
+		if err := writeEntry(archive, SignatureEntry, signature, b.Manifest.Created); err != nil {
+			return err
+		}
+	}
+	return archive.Close()
+}
+
+// WriteFile writes the bundle to the file at path
+func (b *Bundle) WriteFile(path string) error {
+	var buf bytes.Buffer
+	if err := b.Write(&buf); err != nil {
+		return err
+	}
+	if err := os.WriteFile(path, buf.Bytes(), 0o644); err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("bundle.failed_write")).
+			WithInnerError(err).
+			WithLocation(path, 0, 0)
+	}
+	return nil
+}
+
+// writeEntry adds a compressed entry to archive
+func writeEntry(archive *zip.Writer, name string, data []byte, modified time.Time) error {
+	entry, err := archive.CreateHeader(&zip.FileHeader{Name: name, Method: zip.Deflate, Modified: modified})
+	if err != nil {
+		return err
+	}
+	_, err = entry.Write(data)
+	return err
+}
+
+// Read reads a bundle from the zip archive in r, of the given size. Every
+// entry must match its digest in the manifest; the signature, if any, is
+// only checked by Verify.
+func Read(r io.ReaderAt, size int64) (*Bundle, error) {
+	archive, err := zip.NewReader(r, size)
+	if err != nil {
+		return nil, invalid(i18n.T("bundle.not_zip"), err)
+	}
+	files := make(map[string]*zip.File, len(archive.File))
+	for _, f := range archive.File {
+		files[f.Name] = f
+	}
+
+	manifestData, err := readEntry(files, ManifestEntry)
+	if err != nil {
+		return nil, err
+	}
+	b := &Bundle{}
+	if err := json.Unmarshal(manifestData, &b.Manifest); err != nil {
+		return nil, invalid(i18n.T("bundle.invalid_manifest"), err)
+	}
+	if b.Manifest.FormatVersion < 1 || b.Manifest.FormatVersion > FormatVersion {
+		return nil, invalid(i18n.Tf("bundle.unsupported_version",
+			b.Manifest.FormatVersion, FormatVersion), nil)
+	}
+
+	contents := map[string]*[]byte{
+		b.sourceEntry(): &b.Image,
+		SymbolsEntry:    &b.Symbols,
+		ProgramEntry:    &b.Program,
+		CodeEntry:       &b.Code,
+	}
+	for name, content := range contents {
+		data, err := readEntry(files, name)
+		if err != nil {
+			return nil, err
+		}
+		if want, ok := b.Manifest.Digests[name]; !ok || digest(data) != want {
+			return nil, invalid(i18n.Tf("bundle.digest_mismatch", name), nil)
+		}
+		*content = data
+	}
+
+	if _, signed := files[SignatureEntry]; signed {
+		data, err := readEntry(files, SignatureEntry)
+		if err != nil {
+			return nil, err
+		}
+		if b.Signature, err = decodeSignature(data); err != nil {
+			return nil, err
+		}
+	}
+	return b, nil
+}
+
+// ReadFile reads the bundle in the file at path
+func ReadFile(path string) (*Bundle, error) {
+	file, err := os.Open(path)
+	if err != nil {
+		if os.IsNotExist(err) {
+			return nil, grimoireErrors.FileNotFoundError(path)
+		}
+		return nil, grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("bundle.failed_open")).
+			WithInnerError(err).
+			WithLocation(path, 0, 0)
+	}
+	defer file.Close()
+	info, err := file.Stat()
+	if err != nil {
+		return nil, grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("bundle.failed_read")).
+			WithInnerError(err).
+			WithLocation(path, 0, 0)
+	}
+
+	b, err := Read(file, info.Size())
+	if err != nil {
+		if gErr, ok := err.(*grimoireErrors.GrimoireError); ok {
+			return nil, gErr.WithLocation(path, 0, 0)
+		}
+		return nil, err
+	}
+	return b, nil
+}
+
+// readEntry returns the content of the named entry, rejecting missing and
+// oversized entries
+func readEntry(files map[string]*zip.File, name string) ([]byte, error) {
+	f, ok := files[name]
+	if !ok {
+		return nil, invalid(i18n.Tf("bundle.missing_entry", name), nil)
+	}
+	rc, err := f.Open()
+	if err != nil {
+		return nil, invalid(i18n.Tf("bundle.failed_open_entry", name), err)
+	}
+	defer rc.Close()
+	data, err := io.ReadAll(io.LimitReader(rc, maxEntrySize+1))
+	if err != nil {
+		return nil, invalid(i18n.Tf("bundle.failed_read_entry", name), err)
+	}
+	if int64(len(data)) > maxEntrySize {
+		return nil, invalid(i18n.Tf("bundle.entry_too_large", name, maxEntrySize), nil)
+	}
+	return data, nil
+}
+
+// digest returns the hex-encoded SHA-256 digest of data
+func digest(data []byte) string {
+	sum := sha256.Sum256(data)
+	return hex.EncodeToString(sum[:])
+}
+
+// invalid returns the error for a bundle that cannot be read
+func invalid(message string, err error) *grimoireErrors.GrimoireError {
+	e := grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("bundle.invalid", message)).
+		WithSuggestion(i18n.T("bundle.invalid_suggestion"))
+	if err != nil {
+		e = e.WithInnerError(err)
+	}
+	return e
+}
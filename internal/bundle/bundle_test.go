@@ -0,0 +1,152 @@
+package bundle
+
+import (
+	"archive/zip"
+	"bytes"
+	"encoding/json"
+	"path/filepath"
+	"testing"
+	"time"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func sampleBundle() *Bundle {
+	return &Bundle{
+		Manifest: Manifest{
+			Source:          "hello.PNG",
+			Libraries:       []string{"lib.png"},
+			GrimoireVersion: "1.2.3",
+			Created:         time.Date(2024, 1, 2, 3, 4, 5, 0, time.UTC),
+			Target:          "python",
+			OptLevel:        2,
+		},
+		Image:   []byte("image"),
+		Symbols: []byte(`{"symbols":[]}`),
+		Program: []byte(`{"node":"Program"}`),
+		Code:    []byte("print('Hello, World!')\n"),
+	}
+}
+
+// rewrite copies the archive in data, replacing or dropping entries
+func rewrite(t *testing.T, data []byte, replace map[string][]byte) []byte {
+	t.Helper()
+	archive, err := zip.NewReader(bytes.NewReader(data), int64(len(data)))
+	require.NoError(t, err)
+	var buf bytes.Buffer
+	w := zip.NewWriter(&buf)
+	for _, f := range archive.File {
+		content, ok := replace[f.Name]
+		if !ok {
+			content, err = readEntry(map[string]*zip.File{f.Name: f}, f.Name)
+			require.NoError(t, err)
+		} else if content == nil {
+			continue
+		}
+		entry, err := w.Create(f.Name)
+		require.NoError(t, err)
+		_, err = entry.Write(content)
+		require.NoError(t, err)
+	}
+	require.NoError(t, w.Close())
+	return buf.Bytes()
+}
+
+func TestWriteRead(t *testing.T) {
+	var buf bytes.Buffer
+	require.NoError(t, sampleBundle().Write(&buf))
+
+	b, err := Read(bytes.NewReader(buf.Bytes()), int64(buf.Len()))
+	require.NoError(t, err)
+	want := sampleBundle()
+	assert.Equal(t, FormatVersion, b.Manifest.FormatVersion)
+	assert.Equal(t, want.Manifest.Source, b.Manifest.Source)
+	assert.Equal(t, want.Manifest.Libraries, b.Manifest.Libraries)
+	assert.True(t, want.Manifest.Created.Equal(b.Manifest.Created))
+	assert.Equal(t, want.Image, b.Image)
+	assert.Equal(t, want.Symbols, b.Symbols)
+	assert.Equal(t, want.Program, b.Program)
+	assert.Equal(t, want.Code, b.Code)
+	assert.Len(t, b.Manifest.Digests, 4)
+	assert.Contains(t, b.Manifest.Digests, "source.png")
+
+	archive, err := zip.NewReader(bytes.NewReader(buf.Bytes()), int64(buf.Len()))
+	require.NoError(t, err)
+	assert.Equal(t, ManifestEntry, archive.File[0].Name)
+}
+
+func TestReadRejectsTampering(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+	var buf bytes.Buffer
+	require.NoError(t, sampleBundle().Write(&buf))
+
+	tests := []struct {
+		name    string
+		replace map[string][]byte
+		message string
+	}{
+		{"changed code", map[string][]byte{CodeEntry: []byte("import os\n")}, "does not match the digest"},
+		{"missing entry", map[string][]byte{SymbolsEntry: nil}, "has no symbols.json"},
+		{"missing manifest", map[string][]byte{ManifestEntry: nil}, "has no manifest.json"},
+		{"invalid manifest", map[string][]byte{ManifestEntry: []byte("{")}, "not valid JSON"},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			data := rewrite(t, buf.Bytes(), tt.replace)
+			_, err := Read(bytes.NewReader(data), int64(len(data)))
+			require.Error(t, err)
+			errorType, ok := grimoireErrors.GetErrorType(err)
+			assert.True(t, ok)
+			assert.Equal(t, grimoireErrors.ValidationError, errorType)
+			assert.Contains(t, err.Error(), tt.message)
+		})
+	}
+}
+
+func TestReadUnsupportedVersion(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+	var buf bytes.Buffer
+	b := sampleBundle()
+	require.NoError(t, b.Write(&buf))
+	b.Manifest.FormatVersion = FormatVersion + 1
+	manifest, err := json.Marshal(b.Manifest)
+	require.NoError(t, err)
+
+	data := rewrite(t, buf.Bytes(), map[string][]byte{ManifestEntry: manifest})
+	_, err = Read(bytes.NewReader(data), int64(len(data)))
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "Unsupported bundle format version")
+}
+
+func TestReadNotZip(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+	data := []byte("not a zip archive")
+	_, err := Read(bytes.NewReader(data), int64(len(data)))
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "Not a zip archive")
+}
+
+func TestFileRoundTrip(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "hello"+Extension)
+	require.NoError(t, sampleBundle().WriteFile(path))
+	b, err := ReadFile(path)
+	require.NoError(t, err)
+	assert.Equal(t, sampleBundle().Code, b.Code)
+
+	_, err = ReadFile(filepath.Join(t.TempDir(), "missing"+Extension))
+	errorType, _ := grimoireErrors.GetErrorType(err)
+	assert.Equal(t, grimoireErrors.FileNotFound, errorType)
+}
+
+func TestIsBundle(t *testing.T) {
+	assert.True(t, IsBundle("spell.grimoire"))
+	assert.True(t, IsBundle(filepath.Join("dir", "SPELL.GRIMOIRE")))
+	assert.False(t, IsBundle("spell.png"))
+	assert.False(t, IsBundle("grimoire"))
+}
@@ -0,0 +1,183 @@
+package bundle
+
+import (
+	"crypto/ed25519"
+	"crypto/rand"
+	"crypto/sha256"
+	"crypto/x509"
+	"encoding/hex"
+	"encoding/json"
+	"encoding/pem"
+	"fmt"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+)
+
+// SignatureEntry is the entry holding the signature of a signed bundle. It
+// is not covered by the manifest digests, as it signs them.
+const SignatureEntry = "signature.json"
+
+// signatureContext is prefixed to every signed message, so that a signature
+// made for a bundle cannot be passed off as one over anything else
+const signatureContext = "grimoire-bundle-signature-v1"
+
+// signatureAlgorithm is the only algorithm bundles are signed with
+const signatureAlgorithm = "ed25519"
+
+// Signature is an ed25519 signature over the compiled program and syntax
+// tree of a bundle
+type Signature struct {
+	Algorithm string `json:"algorithm"`
+	// PublicKey is the key the signature verifies with
+	PublicKey []byte `json:"publicKey"`
+	// Signature signs the message returned by Bundle.SignedMessage
+	Signature []byte `json:"signature"`
+}
+
+// SignedMessage returns what a signature of the bundle signs: the SHA-256
+// digests of its compiled program and of its syntax tree, with the target
+// the program was compiled to
+func (b *Bundle) SignedMessage() []byte {
+	return []byte(fmt.Sprintf("%s\n%s\n%s\n%s\n", signatureContext, b.Manifest.Target, digest(b.Code), digest(b.Program)))
+}
+
+// Sign signs the bundle with key, replacing any signature it had
+func (b *Bundle) Sign(key ed25519.PrivateKey) {
+	b.Signature = &Signature{
+		Algorithm: signatureAlgorithm,
+		PublicKey: key.Public().(ed25519.PublicKey),
+		Signature: ed25519.Sign(key, b.SignedMessage()),
+	}
+}
+
+// VerifyIntegrity checks that the bundle is signed and that its signature
+// matches its content. The signature carries the key it was made with, so
+// anyone can re-sign a changed bundle: this says nothing of who signed it,
+// which Verify checks as well.
+func (b *Bundle) VerifyIntegrity() error {
+	if b.Signature == nil {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("sign.bundle_unsigned")).
+			WithSuggestion(i18n.T("sign.bundle_unsigned_suggestion"))
+	}
+	if len(b.Signature.PublicKey) != ed25519.PublicKeySize ||
+		!ed25519.Verify(b.Signature.PublicKey, b.SignedMessage(), b.Signature.Signature) {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("sign.signature_mismatch"))
+	}
+	return nil
+}
+
+// Verify checks the integrity of the bundle like VerifyIntegrity and that
+// it is signed with one of the trusted keys. Without trusted keys no signer
+// can be trusted, so it fails.
+func (b *Bundle) Verify(trusted ...ed25519.PublicKey) error {
+	if len(trusted) == 0 {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("sign.no_verification_keys")).
+			WithSuggestion(i18n.T("sign.no_verification_keys_suggestion"))
+	}
+	if err := b.VerifyIntegrity(); err != nil {
+		return err
+	}
+	for _, key := range trusted {
+		if key.Equal(ed25519.PublicKey(b.Signature.PublicKey)) {
+			return nil
+		}
+	}
+	return grimoireErrors.NewError(grimoireErrors.ValidationError,
+		i18n.Tf("sign.untrusted_key", Fingerprint(b.Signature.PublicKey)))
+}
+
+// GenerateKey returns a new ed25519 key for signing bundles
+func GenerateKey() (ed25519.PrivateKey, error) {
+	_, key, err := ed25519.GenerateKey(rand.Reader)
+	return key, err
+}
+
+// Fingerprint returns a short name for a public key: the first 16 hex
+// digits of its SHA-256 digest
+func Fingerprint(key ed25519.PublicKey) string {
+	sum := sha256.Sum256(key)
+	return hex.EncodeToString(sum[:8])
+}
+
+// EncodePrivateKey encodes key as a PKCS #8 PEM block
+func EncodePrivateKey(key ed25519.PrivateKey) ([]byte, error) {
+	der, err := x509.MarshalPKCS8PrivateKey(key)
+	if err != nil {
+		return nil, err
+	}
+	return pem.EncodeToMemory(&pem.Block{Type: "PRIVATE KEY", Bytes: der}), nil
+}
+
+// EncodePublicKey encodes key as a PKIX PEM block
+func EncodePublicKey(key ed25519.PublicKey) ([]byte, error) {
+	der, err := x509.MarshalPKIXPublicKey(key)
+	if err != nil {
+		return nil, err
+	}
+	return pem.EncodeToMemory(&pem.Block{Type: "PUBLIC KEY", Bytes: der}), nil
+}
+
+// DecodePrivateKey decodes an ed25519 key encoded by EncodePrivateKey
+func DecodePrivateKey(data []byte) (ed25519.PrivateKey, error) {
+	block, _ := pem.Decode(data)
+	if block == nil || block.Type != "PRIVATE KEY" {
+		return nil, invalidKey(i18n.T("sign.key_not_private_pem"), nil)
+	}
+	key, err := x509.ParsePKCS8PrivateKey(block.Bytes)
+	if err != nil {
+		return nil, invalidKey(i18n.T("sign.private_key_unparsable"), err)
+	}
+	edKey, ok := key.(ed25519.PrivateKey)
+	if !ok {
+		return nil, invalidKey(i18n.T("sign.key_not_ed25519"), nil)
+	}
+	return edKey, nil
+}
+
+// DecodePublicKey decodes an ed25519 key encoded by EncodePublicKey. A
+// private key is accepted as well, standing for its public half.
+func DecodePublicKey(data []byte) (ed25519.PublicKey, error) {
+	block, _ := pem.Decode(data)
+	if block != nil && block.Type == "PRIVATE KEY" {
+		key, err := DecodePrivateKey(data)
+		if err != nil {
+			return nil, err
+		}
+		return key.Public().(ed25519.PublicKey), nil
+	}
+	if block == nil || block.Type != "PUBLIC KEY" {
+		return nil, invalidKey(i18n.T("sign.key_not_public_pem"), nil)
+	}
+	key, err := x509.ParsePKIXPublicKey(block.Bytes)
+	if err != nil {
+		return nil, invalidKey(i18n.T("sign.public_key_unparsable"), err)
+	}
+	edKey, ok := key.(ed25519.PublicKey)
+	if !ok {
+		return nil, invalidKey(i18n.T("sign.key_not_ed25519"), nil)
+	}
+	return edKey, nil
+}
+
+// decodeSignature parses the content of the signature entry
+func decodeSignature(data []byte) (*Signature, error) {
+	var signature Signature
+	if err := json.Unmarshal(data, &signature); err != nil {
+		return nil, invalid(i18n.T("sign.invalid_signature_json"), err)
+	}
+	if signature.Algorithm != signatureAlgorithm {
+		return nil, invalid(i18n.Tf("sign.unsupported_algorithm", signature.Algorithm), nil)
+	}
+	return &signature, nil
+}
+
+// invalidKey returns the error for a key file that cannot be used
+func invalidKey(message string, err error) *grimoireErrors.GrimoireError {
+	e := grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("sign.invalid_key", message)).
+		WithSuggestion(i18n.T("sign.invalid_key_suggestion"))
+	if err != nil {
+		e = e.WithInnerError(err)
+	}
+	return e
+}
@@ -0,0 +1,84 @@
+package bundle
+
+import (
+	"bytes"
+	"crypto/ed25519"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestSignVerify(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+	key, err := GenerateKey()
+	require.NoError(t, err)
+	other, err := GenerateKey()
+	require.NoError(t, err)
+
+	b := sampleBundle()
+	assert.Error(t, b.VerifyIntegrity(), "an unsigned bundle does not verify")
+	assert.Error(t, b.Verify(key.Public().(ed25519.PublicKey)))
+
+	b.Sign(key)
+	var buf bytes.Buffer
+	require.NoError(t, b.Write(&buf))
+	read, err := Read(bytes.NewReader(buf.Bytes()), int64(buf.Len()))
+	require.NoError(t, err)
+	require.NotNil(t, read.Signature)
+	assert.NoError(t, read.VerifyIntegrity())
+	assert.NoError(t, read.Verify(key.Public().(ed25519.PublicKey)))
+	err = read.Verify()
+	require.Error(t, err, "without trusted keys no signer is trusted")
+	assert.Contains(t, err.Error(), "No trusted keys")
+	err = read.Verify(other.Public().(ed25519.PublicKey))
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "untrusted key")
+
+	// Changing the program, even with its digest updated, breaks the signature
+	read.Code = []byte("import os\n")
+	buf.Reset()
+	require.NoError(t, read.Write(&buf))
+	tampered, err := Read(bytes.NewReader(buf.Bytes()), int64(buf.Len()))
+	require.NoError(t, err)
+	err = tampered.VerifyIntegrity()
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "changed after signing")
+
+	// Re-signed with another key, the changed bundle is whole again but
+	// not signed by a trusted key
+	tampered.Sign(other)
+	assert.NoError(t, tampered.VerifyIntegrity())
+	err = tampered.Verify(key.Public().(ed25519.PublicKey))
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "untrusted key")
+}
+
+func TestKeyEncoding(t *testing.T) {
+	key, err := GenerateKey()
+	require.NoError(t, err)
+
+	private, err := EncodePrivateKey(key)
+	require.NoError(t, err)
+	decoded, err := DecodePrivateKey(private)
+	require.NoError(t, err)
+	assert.True(t, key.Equal(decoded))
+
+	public, err := EncodePublicKey(key.Public().(ed25519.PublicKey))
+	require.NoError(t, err)
+	decodedPublic, err := DecodePublicKey(public)
+	require.NoError(t, err)
+	assert.True(t, decodedPublic.Equal(key.Public()))
+
+	fromPrivate, err := DecodePublicKey(private)
+	require.NoError(t, err)
+	assert.True(t, fromPrivate.Equal(key.Public()))
+
+	_, err = DecodePrivateKey(public)
+	assert.Error(t, err)
+	_, err = DecodePublicKey([]byte("not a key"))
+	assert.Error(t, err)
+	assert.Len(t, Fingerprint(decodedPublic), 16)
+}
@@ -0,0 +1,169 @@
+// Package cache stores compiled programs on disk so that running an
+// unchanged image again skips detection, parsing and compilation.
+//
+// Entries are keyed by a hash of everything that affects the output: the
+// image bytes, the bytes of any library images, the output target and the
+// options used to compile. Each entry is one file in the cache directory.
+package cache
+
+import (
+	"crypto/sha256"
+	"encoding/hex"
+	"errors"
+	"io/fs"
+	"os"
+	"path/filepath"
+	"strings"
+)
+
+// DirEnv overrides the default cache directory
+const DirEnv = "GRIMOIRE_CACHE_DIR"
+
+// entrySuffix is the file extension of cache entries
+const entrySuffix = ".cache"
+
+// Stats describes the contents of a cache
+type Stats struct {
+	Entries int
+	Bytes   int64
+}
+
+// Cache is a directory of compiled programs
+type Cache struct {
+	Dir string
+}
+
+// New creates a cache in dir. The directory is created on the first Put.
+func New(dir string) *Cache {
+	return &Cache{Dir: dir}
+}
+
+// DefaultDir returns $GRIMOIRE_CACHE_DIR, or a grimoire directory in the
+// user's cache directory
+func DefaultDir() (string, error) {
+	if dir := os.Getenv(DirEnv); dir != "" {
+		return dir, nil
+	}
+	dir, err := os.UserCacheDir()
+	if err != nil {
+		return "", err
+	}
+	return filepath.Join(dir, "grimoire"), nil
+}
+
+// Key hashes the given parts into a cache key. Each part is length-prefixed
+// so that moving bytes from one part to the next changes the key.
+func Key(parts ...[]byte) string {
+	h := sha256.New()
+	for _, part := range parts {
+		var length [8]byte
+		for i := range length {
+			length[i] = byte(uint64(len(part)) >> (8 * i))
+		}
+		h.Write(length[:])
+		h.Write(part)
+	}
+	return hex.EncodeToString(h.Sum(nil))
+}
+
+// Get returns the data stored under key
+func (c *Cache) Get(key string) ([]byte, bool) {
+	data, err := os.ReadFile(c.path(key))
+	if err != nil {
+		return nil, false
+	}
+	return data, true
+}
+
+// Put stores data under key. The entry is written to a temporary file and
+// renamed into place, so concurrent readers never see a partial entry.
+func (c *Cache) Put(key string, data []byte) error {
+	if err := os.MkdirAll(c.Dir, 0o755); err != nil {
+		return err
+	}
+	tmp, err := os.CreateTemp(c.Dir, key+".*.tmp")
+	if err != nil {
+		return err
+	}
+	if _, err := tmp.Write(data); err != nil {
+		tmp.Close()
+		os.Remove(tmp.Name())
+		return err
+	}
+	if err := tmp.Close(); err != nil {
+		os.Remove(tmp.Name())
+		return err
+	}
+	if err := os.Rename(tmp.Name(), c.path(key)); err != nil {
+		os.Remove(tmp.Name())
+		return err
+	}
+	return nil
+}
+
+// Delete removes the entry stored under key, reporting whether there was one
+func (c *Cache) Delete(key string) (bool, error) {
+	err := os.Remove(c.path(key))
+	if errors.Is(err, fs.ErrNotExist) {
+		return false, nil
+	}
+	return err == nil, err
+}
+
+// Stats counts the entries in the cache and their total size. A cache
+// whose directory does not exist yet is empty.
+func (c *Cache) Stats() (Stats, error) {
+	var stats Stats
+	entries, err := c.entries()
+	if err != nil {
+		return stats, err
+	}
+	for _, entry := range entries {
+		info, err := entry.Info()
+		if err != nil {
+			continue // Removed while counting
+		}
+		stats.Entries++
+		stats.Bytes += info.Size()
+	}
+	return stats, nil
+}
+
+// Clear removes every entry and returns how many were removed
+func (c *Cache) Clear() (int, error) {
+	entries, err := c.entries()
+	if err != nil {
+		return 0, err
+	}
+	removed := 0
+	for _, entry := range entries {
+		if err := os.Remove(filepath.Join(c.Dir, entry.Name())); err != nil && !errors.Is(err, fs.ErrNotExist) {
+			return removed, err
+		}
+		removed++
+	}
+	return removed, nil
+}
+
+// entries lists the cache entry files, ignoring anything else in the directory
+func (c *Cache) entries() ([]fs.DirEntry, error) {
+	all, err := os.ReadDir(c.Dir)
+	if errors.Is(err, fs.ErrNotExist) {
+		return nil, nil
+	}
+	if err != nil {
+		return nil, err
+	}
+
+	var entries []fs.DirEntry
+	for _, entry := range all {
+		if entry.Type().IsRegular() && strings.HasSuffix(entry.Name(), entrySuffix) {
+			entries = append(entries, entry)
+		}
+	}
+	return entries, nil
+}
+
+func (c *Cache) path(key string) string {
+	return filepath.Join(c.Dir, key+entrySuffix)
+}
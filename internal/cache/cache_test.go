@@ -0,0 +1,88 @@
+package cache
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestKey(t *testing.T) {
+	key := Key([]byte("image"), []byte("python"))
+	assert.Len(t, key, 64)
+	assert.Equal(t, key, Key([]byte("image"), []byte("python")))
+	assert.NotEqual(t, key, Key([]byte("image"), []byte("qr")))
+	// Moving bytes between parts changes the key
+	assert.NotEqual(t, Key([]byte("ab"), []byte("c")), Key([]byte("a"), []byte("bc")))
+}
+
+func TestPutGet(t *testing.T) {
+	c := New(filepath.Join(t.TempDir(), "cache"))
+	key := Key([]byte("image"))
+
+	_, ok := c.Get(key)
+	assert.False(t, ok)
+
+	require.NoError(t, c.Put(key, []byte("print('hi')")))
+	data, ok := c.Get(key)
+	require.True(t, ok)
+	assert.Equal(t, "print('hi')", string(data))
+
+	// Overwriting replaces the entry
+	require.NoError(t, c.Put(key, []byte("print('bye')")))
+	data, _ = c.Get(key)
+	assert.Equal(t, "print('bye')", string(data))
+}
+
+func TestDelete(t *testing.T) {
+	c := New(filepath.Join(t.TempDir(), "cache"))
+	key := Key([]byte("image"))
+
+	deleted, err := c.Delete(key)
+	require.NoError(t, err)
+	assert.False(t, deleted, "a cache without the entry is left alone")
+
+	require.NoError(t, c.Put(key, []byte("print('hi')")))
+	deleted, err = c.Delete(key)
+	require.NoError(t, err)
+	assert.True(t, deleted)
+	_, ok := c.Get(key)
+	assert.False(t, ok)
+}
+
+func TestStatsAndClear(t *testing.T) {
+	dir := filepath.Join(t.TempDir(), "cache")
+	c := New(dir)
+
+	// A cache that was never written to is empty
+	stats, err := c.Stats()
+	require.NoError(t, err)
+	assert.Equal(t, Stats{}, stats)
+
+	require.NoError(t, c.Put(Key([]byte("a")), []byte("12345")))
+	require.NoError(t, c.Put(Key([]byte("b")), []byte("123")))
+	// Files that are not entries are left alone
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "README"), []byte("keep"), 0o644))
+
+	stats, err = c.Stats()
+	require.NoError(t, err)
+	assert.Equal(t, Stats{Entries: 2, Bytes: 8}, stats)
+
+	removed, err := c.Clear()
+	require.NoError(t, err)
+	assert.Equal(t, 2, removed)
+
+	stats, err = c.Stats()
+	require.NoError(t, err)
+	assert.Equal(t, Stats{}, stats)
+	assert.FileExists(t, filepath.Join(dir, "README"))
+}
+
+func TestDefaultDir(t *testing.T) {
+	t.Setenv(DirEnv, "/tmp/grimoire-cache")
+	dir, err := DefaultDir()
+	require.NoError(t, err)
+	assert.Equal(t, "/tmp/grimoire-cache", dir)
+}
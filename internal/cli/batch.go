@@ -0,0 +1,123 @@
+package cli
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+	"sync"
+	"time"
+
+	"github.com/ayutaz/grimoire/internal/conformance"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/spf13/cobra"
+)
+
+// batchResult is the outcome of compiling one image of a batch
+type batchResult struct {
+	path     string
+	code     string
+	err      error
+	duration time.Duration
+}
+
+// batchCommand compiles every image in a directory, several at a time,
+// reporting each file as it finishes and a summary at the end
+func batchCommand(cmd *cobra.Command, args []string) error {
+	dir := args[0]
+	jobs, _ := cmd.Flags().GetInt("jobs")
+	outputDir, _ := cmd.Flags().GetString("output")
+	if jobs < 1 {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("cli.invalid_jobs", jobs))
+	}
+
+	images, err := conformance.Discover(dir)
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("error.file_read_error")).
+			WithInnerError(err).
+			WithLocation(dir, 0, 0)
+	}
+	if len(images) == 0 {
+		fmt.Printf(i18n.T("batch.no_images"), dir)
+		return nil
+	}
+	if outputDir != "" {
+		if err := os.MkdirAll(outputDir, 0o755); err != nil {
+			return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+				WithInnerError(err).
+				WithLocation(outputDir, 0, 0)
+		}
+	}
+
+	start := time.Now()
+	failed := 0
+	compileBatch(images, jobs, func(result batchResult) {
+		name := filepath.Base(result.path)
+		if result.err == nil && outputDir != "" {
+			result.err = writeBatchOutput(outputDir, name, result.code)
+		}
+		if result.err != nil {
+			failed++
+			fmt.Printf(i18n.T("batch.error"), name, result.err)
+			return
+		}
+		fmt.Printf(i18n.T("batch.ok"), name, result.duration.Round(time.Millisecond))
+	})
+
+	fmt.Printf(i18n.T("batch.summary"), len(images)-failed, failed, len(images),
+		time.Since(start).Round(time.Millisecond))
+	if failed > 0 {
+		return grimoireErrors.NewError(grimoireErrors.CompilationError, i18n.Tf("batch.failed", failed))
+	}
+	return nil
+}
+
+// compileBatch compiles images with at most jobs of them in progress at once
+// and calls handle with each result as soon as it is ready, so results
+// arrive in the order the images finish. handle is never called concurrently.
+func compileBatch(images []string, jobs int, handle func(batchResult)) {
+	paths := make(chan string)
+	results := make(chan batchResult)
+
+	var wg sync.WaitGroup
+	for i := 0; i < jobs && i < len(images); i++ {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			for path := range paths {
+				start := time.Now()
+				code, err := processImage(path)
+				if err != nil {
+					err = formatError(err, path)
+				}
+				results <- batchResult{path: path, code: code, err: err, duration: time.Since(start)}
+			}
+		}()
+	}
+
+	go func() {
+		for _, path := range images {
+			paths <- path
+		}
+		close(paths)
+		wg.Wait()
+		close(results)
+	}()
+
+	for result := range results {
+		handle(result)
+	}
+}
+
+// writeBatchOutput writes the code compiled from the image name to a Python
+// file of the same base name in dir
+func writeBatchOutput(dir, name, code string) error {
+	outputPath := filepath.Join(dir, strings.TrimSuffix(name, filepath.Ext(name))+".py")
+	if err := os.WriteFile(outputPath, []byte(code), 0o644); err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+			WithInnerError(err).
+			WithLocation(outputPath, 0, 0)
+	}
+	return nil
+}
@@ -0,0 +1,70 @@
+package cli
+
+import (
+	"bytes"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestBatchCommand(t *testing.T) {
+	data, err := os.ReadFile(filepath.Join("..", "..", "examples", "images", "hello_world.png"))
+	if err != nil {
+		t.Skip("Example image not found")
+	}
+
+	dir := t.TempDir()
+	for _, name := range []string{"a.png", "b.png", "c.png"} {
+		require.NoError(t, os.WriteFile(filepath.Join(dir, name), data, 0o644))
+	}
+	writeBlankImage(t, filepath.Join(dir, "blank.png"), 100, 100)
+	outputDir := filepath.Join(t.TempDir(), "build")
+
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "batch", dir, "--jobs", "2", "-o", outputDir, "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err = Execute("test", "test", "test")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	var buf bytes.Buffer
+	_, readErr := buf.ReadFrom(r)
+	require.NoError(t, readErr)
+	output := buf.String()
+
+	// The blank image has no outer circle, so it cannot be compiled
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "1 image(s) failed to compile")
+	assert.Contains(t, output, "OK    a.png")
+	assert.Contains(t, output, "ERROR blank.png")
+	assert.Contains(t, output, "3 succeeded, 1 failed, 4 total")
+
+	for _, name := range []string{"a.py", "b.py", "c.py"} {
+		code, err := os.ReadFile(filepath.Join(outputDir, name))
+		require.NoError(t, err)
+		assert.NotEmpty(t, code)
+	}
+	assert.NoFileExists(t, filepath.Join(outputDir, "blank.py"))
+}
+
+func TestBatchCommandInvalidJobs(t *testing.T) {
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "batch", t.TempDir(), "--jobs", "0", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	err := Execute("test", "test", "test")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "--jobs must be at least 1")
+}
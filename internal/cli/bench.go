@@ -0,0 +1,131 @@
+package cli
+
+import (
+	"fmt"
+	"path/filepath"
+	"time"
+
+	"github.com/ayutaz/grimoire/internal/bench"
+	"github.com/ayutaz/grimoire/internal/conformance"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/spf13/cobra"
+)
+
+// benchCommand times detection, parsing and compilation of every image in a
+// directory. With --baseline, the times are compared with those saved by
+// an earlier run and each benchmark is reported as regressed, improved or
+// unchanged; with --save, they are saved for later runs to compare with.
+func benchCommand(cmd *cobra.Command, args []string) error {
+	dir := args[0]
+	baselinePath, _ := cmd.Flags().GetString("baseline")
+	savePath, _ := cmd.Flags().GetString("save")
+	iterations, _ := cmd.Flags().GetInt("iterations")
+	threshold, _ := cmd.Flags().GetFloat64("threshold")
+	if iterations < 1 {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("bench.invalid_iterations", iterations))
+	}
+	if threshold < 0 {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("bench.invalid_threshold", threshold))
+	}
+
+	var baseline *bench.Report
+	if baselinePath != "" {
+		var err error
+		if baseline, err = bench.Load(baselinePath); err != nil {
+			return grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("error.file_read_error")).
+				WithInnerError(err).
+				WithLocation(baselinePath, 0, 0)
+		}
+	}
+
+	images, err := conformance.Discover(dir)
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("error.file_read_error")).
+			WithInnerError(err).
+			WithLocation(dir, 0, 0)
+	}
+	if len(images) == 0 {
+		fmt.Printf(i18n.T("batch.no_images"), dir)
+		return nil
+	}
+	backend, err := newBackend()
+	if err != nil {
+		return err
+	}
+
+	runner := bench.NewRunner(backend)
+	runner.Iterations = iterations
+	report := &bench.Report{Version: buildVersion, Created: time.Now().UTC()}
+	failed := 0
+	for _, imagePath := range images {
+		results, err := runner.Run(imagePath)
+		if err != nil {
+			failed++
+			fmt.Printf(i18n.T("batch.error"), filepath.Base(imagePath), err)
+			continue
+		}
+		report.Results = append(report.Results, results...)
+	}
+
+	if baseline != nil {
+		printComparisons(baseline, bench.Compare(baseline, report, threshold/100))
+	} else {
+		fmt.Print(i18n.T("bench.header"))
+		for _, r := range report.Results {
+			fmt.Printf(i18n.T("bench.row"), r.Name, benchTime(r.Median), benchTime(r.Min), 100*r.Noise)
+		}
+	}
+
+	if savePath != "" {
+		if err := report.Save(savePath); err != nil {
+			return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+				WithInnerError(err).
+				WithLocation(savePath, 0, 0)
+		}
+		fmt.Printf(i18n.T("bench.saved"), savePath)
+	}
+	if failed > 0 {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("bench.failed", failed))
+	}
+	return nil
+}
+
+// printComparisons prints the table of how each benchmark changed from the
+// baseline, followed by how many changed each way
+func printComparisons(baseline *bench.Report, comparisons []bench.Comparison) {
+	version := baseline.Version
+	if version == "" {
+		version = "-"
+	}
+	fmt.Printf(i18n.T("bench.baseline"), version, baseline.Created.Format(time.RFC3339))
+	fmt.Print(i18n.T("bench.compare_header"))
+	for _, c := range comparisons {
+		before, after, change := "-", "-", "-"
+		if c.Baseline > 0 {
+			before = benchTime(c.Baseline)
+		}
+		if c.Current > 0 {
+			after = benchTime(c.Current)
+		}
+		if c.Verdict != bench.Added && c.Verdict != bench.Removed {
+			change = fmt.Sprintf("%+.1f%%", 100*c.Change)
+		}
+		fmt.Printf(i18n.T("bench.compare_row"), c.Name, before, after, change, i18n.T("bench.verdict."+string(c.Verdict)))
+	}
+	fmt.Printf(i18n.T("bench.compare_summary"),
+		bench.Count(comparisons, bench.Regressed), bench.Count(comparisons, bench.Improved), bench.Count(comparisons, bench.Unchanged))
+}
+
+// benchTime formats a time in nanoseconds in the unit that suits it
+func benchTime(nanoseconds float64) string {
+	switch {
+	case nanoseconds >= 1e9:
+		return fmt.Sprintf("%.3f s", nanoseconds/1e9)
+	case nanoseconds >= 1e6:
+		return fmt.Sprintf("%.3f ms", nanoseconds/1e6)
+	case nanoseconds >= 1e3:
+		return fmt.Sprintf("%.3f µs", nanoseconds/1e3)
+	}
+	return fmt.Sprintf("%.0f ns", nanoseconds)
+}
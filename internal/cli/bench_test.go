@@ -0,0 +1,87 @@
+package cli
+
+import (
+	"io"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/bench"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// benchOutput runs grimoire bench with args and returns what it printed
+func benchOutput(t *testing.T, args ...string) (string, error) {
+	t.Helper()
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err := runArgs(t, append([]string{"bench"}, args...)...)
+
+	w.Close()
+	os.Stdout = oldStdout
+	output, readErr := io.ReadAll(r)
+	require.NoError(t, readErr)
+	return string(output), err
+}
+
+func TestBenchCommand(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	data, err := os.ReadFile(filepath.Join("..", "..", "examples", "images", "hello_world.png"))
+	if err != nil {
+		t.Skip("example image not found")
+	}
+	dir := t.TempDir()
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "hello_world.png"), data, 0o644))
+	savePath := filepath.Join(t.TempDir(), "old.json")
+
+	output, err := benchOutput(t, dir, "--iterations", "2", "--save", savePath)
+	require.NoError(t, err)
+	assert.Contains(t, output, "detect/hello_world.png")
+	assert.Contains(t, output, "compile/hello_world.png")
+	assert.Contains(t, output, "Results saved to "+savePath)
+
+	saved, err := bench.Load(savePath)
+	require.NoError(t, err)
+	assert.Len(t, saved.Results, 3)
+
+	// A benchmark the baseline lacks is reported as added, one it alone has as removed
+	saved.Results[2].Name = "compile/gone.png"
+	require.NoError(t, saved.Save(savePath))
+	output, err = benchOutput(t, dir, "--iterations", "2", "--baseline", savePath)
+	require.NoError(t, err)
+	assert.Contains(t, output, "Compared with")
+	assert.Contains(t, output, "detect/hello_world.png")
+	assert.Regexp(t, `compile/hello_world.png\s+-\s+\S+ \S+\s+-\s+added`, output)
+	assert.Regexp(t, `compile/gone.png\s+\S+ \S+\s+-\s+-\s+removed`, output)
+	assert.Regexp(t, `\d+ regressed, \d+ improved, \d+ unchanged`, output)
+}
+
+func TestBenchCommandErrors(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	dir := t.TempDir()
+	_, err := benchOutput(t, dir, "--iterations", "0")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "--iterations")
+
+	_, err = benchOutput(t, dir, "--threshold", "-1")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "--threshold")
+
+	_, err = benchOutput(t, dir, "--baseline", filepath.Join(dir, "missing.json"))
+	require.Error(t, err)
+	errType, _ := grimoireErrors.GetErrorType(err)
+	assert.Equal(t, grimoireErrors.FileReadError, errType)
+
+	output, err := benchOutput(t, dir)
+	require.NoError(t, err)
+	assert.Contains(t, output, "No images found")
+}
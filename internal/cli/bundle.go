@@ -0,0 +1,122 @@
+package cli
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+	"time"
+
+	"github.com/ayutaz/grimoire/internal/bundle"
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/parser"
+)
+
+// compileBundle compiles the image at imagePath, linking the library
+// images, into a .grimoire bundle at outputPath, or next to the image when
+// outputPath is empty
+func compileBundle(imagePath string, libraryPaths []string, level int, outputPath string) error {
+	if outputPath == "" {
+		outputPath = bundlePath(imagePath)
+	}
+
+	c, err := compileImage(imagePath, level, libraryPaths...)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+	source, err := readSource(imagePath)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+	symbols, err := detector.EncodeJSON(c.symbols, c.connections)
+	if err != nil {
+		return err
+	}
+	program, err := parser.EncodeJSON(c.program)
+	if err != nil {
+		return err
+	}
+
+	b := &bundle.Bundle{
+		Manifest: bundle.Manifest{
+			Source:          sourceName(imagePath),
+			GrimoireVersion: buildVersion,
+			Created:         time.Now().UTC(),
+			Target:          emitPython,
+			OptLevel:        level,
+		},
+		Image:   source,
+		Symbols: symbols,
+		Program: program,
+		Code:    []byte(c.code),
+	}
+	for _, libPath := range libraryPaths {
+		b.Manifest.Libraries = append(b.Manifest.Libraries, filepath.Base(libPath))
+	}
+	if err := b.WriteFile(outputPath); err != nil {
+		return err
+	}
+	fmt.Printf(i18n.T("bundle.written"), outputPath)
+	return nil
+}
+
+// runBundle runs the program compiled into the bundle at path, once its
+// signature meets the policy. Its libraries were linked when it was
+// compiled, so no others may be given.
+func runBundle(path string, libraryPaths []string, policy signingPolicy) error {
+	if len(libraryPaths) > 0 {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("bundle.no_libraries")).
+			WithLocation(path, 0, 0)
+	}
+	b, err := bundle.ReadFile(path)
+	if err != nil {
+		return err
+	}
+	if policy.required {
+		if err := verifyBundle(b, path, policy.trusted); err != nil {
+			return err
+		}
+	}
+	if b.Manifest.Target != emitPython {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("bundle.unsupported_target", b.Manifest.Target)).
+			WithLocation(path, 0, 0)
+	}
+
+	if err := executePython(string(b.Code)); err != nil {
+		return grimoireErrors.NewError(grimoireErrors.ExecutionError, i18n.T("msg.failed_execute_python")).
+			WithInnerError(err).
+			WithSuggestion(i18n.T("suggest.check_python_installed"))
+	}
+	return nil
+}
+
+// bundlePath returns where compile --bundle writes the bundle of imagePath
+// when no output is given: next to it, with the bundle extension
+func bundlePath(imagePath string) string {
+	if imagePath == stdinPath {
+		return "program" + bundle.Extension
+	}
+	return strings.TrimSuffix(imagePath, filepath.Ext(imagePath)) + bundle.Extension
+}
+
+// sourceName returns the file name recorded for the image at imagePath
+func sourceName(imagePath string) string {
+	if imagePath == stdinPath {
+		return stdinName
+	}
+	return filepath.Base(imagePath)
+}
+
+// readSource returns the content of the image at imagePath, as read from
+// stdin when imagePath is "-"
+func readSource(imagePath string) ([]byte, error) {
+	if imagePath != stdinPath {
+		return os.ReadFile(imagePath)
+	}
+	if err := readStdin(); err != nil {
+		return nil, err
+	}
+	return stdinData, nil
+}
@@ -0,0 +1,52 @@
+package cli
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/bundle"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestCompileBundle(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+	outputPath := filepath.Join(t.TempDir(), "hello.grimoire")
+
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "compile", imagePath, "--bundle", "-o", outputPath, "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	require.NoError(t, Execute("1.2.3", "test", "test"))
+
+	b, err := bundle.ReadFile(outputPath)
+	require.NoError(t, err)
+	assert.Equal(t, "hello_world.png", b.Manifest.Source)
+	assert.Equal(t, "1.2.3", b.Manifest.GrimoireVersion)
+	assert.Equal(t, emitPython, b.Manifest.Target)
+
+	image, err := os.ReadFile(imagePath)
+	require.NoError(t, err)
+	assert.Equal(t, image, b.Image)
+	code, err := processImage(imagePath)
+	require.NoError(t, err)
+	assert.Equal(t, code, string(b.Code))
+	assert.Contains(t, string(b.Symbols), `"outer_circle"`)
+	assert.Contains(t, string(b.Program), `"Program"`)
+}
+
+func TestRunBundleWithLibraries(t *testing.T) {
+	err := runBundle("spell.grimoire", []string{"lib.png"}, signingPolicy{})
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), i18n.T("bundle.no_libraries"))
+}
+
+func TestBundlePath(t *testing.T) {
+	assert.Equal(t, filepath.Join("spells", "hello.grimoire"), bundlePath(filepath.Join("spells", "hello.png")))
+	assert.Equal(t, "program.grimoire", bundlePath(stdinPath))
+}
@@ -0,0 +1,125 @@
+package cli
+
+import (
+	"fmt"
+	"os"
+	"strconv"
+
+	"github.com/ayutaz/grimoire/internal/cache"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/spf13/cobra"
+)
+
+// cacheFormat is part of every cache key; change it when the way programs
+// are cached changes
+const cacheFormat = "grimoire-program-v1"
+
+// buildVersion is the version passed to Execute. It is part of every cache
+// key so that upgrading grimoire never runs a program compiled by an older
+// release.
+var buildVersion string
+
+// cacheEntry is where the compiled program for a set of input images is cached
+type cacheEntry struct {
+	cache *cache.Cache
+	key   string
+}
+
+// programCacheEntry returns the cache entry for running imagePath linked
+// with libraryPaths, or nil when the program cannot be cached because an
+// image is read from stdin or cannot be read, or there is no cache directory
+func programCacheEntry(imagePath string, libraryPaths []string) *cacheEntry {
+	if imagePath == stdinPath {
+		return nil
+	}
+	dir, err := cache.DefaultDir()
+	if err != nil {
+		return nil
+	}
+	data, err := os.ReadFile(imagePath)
+	if err != nil {
+		return nil
+	}
+
+	parts := [][]byte{
+		[]byte(cacheFormat), []byte(buildVersion), []byte(emitPython), []byte(detectorBackend),
+		[]byte(strconv.FormatFloat(minConfidence, 'g', -1, 64)), []byte(preprocessingChain()),
+		[]byte(strconv.Itoa(inkCount)), []byte(strictness), data,
+	}
+	// A program compiled before the image was corrected is not reused
+	corrections, _ := savedCorrections(data)
+	parts = append(parts, corrections)
+	for _, libPath := range libraryPaths {
+		if libPath == stdinPath {
+			return nil
+		}
+		data, err := os.ReadFile(libPath)
+		if err != nil {
+			return nil
+		}
+		// The library path names the library in the generated code
+		parts = append(parts, []byte(libPath), data)
+	}
+	return &cacheEntry{cache: cache.New(dir), key: cache.Key(parts...)}
+}
+
+// get returns the cached program
+func (e *cacheEntry) get() (string, bool) {
+	if e == nil {
+		return "", false
+	}
+	data, ok := e.cache.Get(e.key)
+	return string(data), ok
+}
+
+// put caches the program. Caching is only an optimization, so failures are
+// ignored.
+func (e *cacheEntry) put(code string) {
+	if e != nil {
+		_ = e.cache.Put(e.key, []byte(code))
+	}
+}
+
+// openCache returns the cache in the default directory
+func openCache() (*cache.Cache, error) {
+	dir, err := cache.DefaultDir()
+	if err != nil {
+		return nil, grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("cache.no_directory")).
+			WithInnerError(err).
+			WithSuggestion(i18n.Tf("cache.no_directory_suggestion", cache.DirEnv))
+	}
+	return cache.New(dir), nil
+}
+
+// cacheStatsCommand reports how many programs are cached and their size
+func cacheStatsCommand(_ *cobra.Command, _ []string) error {
+	c, err := openCache()
+	if err != nil {
+		return err
+	}
+	stats, err := c.Stats()
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("error.file_read_error")).
+			WithInnerError(err).
+			WithLocation(c.Dir, 0, 0)
+	}
+	fmt.Printf(i18n.T("cache.stats"), c.Dir, stats.Entries, stats.Bytes)
+	return nil
+}
+
+// cacheClearCommand removes every cached program
+func cacheClearCommand(_ *cobra.Command, _ []string) error {
+	c, err := openCache()
+	if err != nil {
+		return err
+	}
+	removed, err := c.Clear()
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("cache.clear_failed")).
+			WithInnerError(err).
+			WithLocation(c.Dir, 0, 0)
+	}
+	fmt.Printf(i18n.T("cache.cleared"), removed, c.Dir)
+	return nil
+}
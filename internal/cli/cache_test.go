@@ -0,0 +1,119 @@
+package cli
+
+import (
+	"bytes"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/cache"
+	"github.com/ayutaz/grimoire/internal/history"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// TestMain keeps the programs cached by run and the runs recorded in the
+// history out of the user's directories
+func TestMain(m *testing.M) {
+	dir, err := os.MkdirTemp("", "grimoire-cache-*")
+	if err != nil {
+		panic(err)
+	}
+	os.Setenv(cache.DirEnv, dir)
+	os.Setenv(history.DirEnv, filepath.Join(dir, "history"))
+	code := m.Run()
+	os.RemoveAll(dir)
+	os.Exit(code)
+}
+
+func TestProgramCacheEntry(t *testing.T) {
+	t.Setenv(cache.DirEnv, t.TempDir())
+	dir := t.TempDir()
+	image := filepath.Join(dir, "main.png")
+	library := filepath.Join(dir, "lib.png")
+	require.NoError(t, os.WriteFile(image, []byte("main"), 0o644))
+	require.NoError(t, os.WriteFile(library, []byte("lib"), 0o644))
+
+	entry := programCacheEntry(image, nil)
+	require.NotNil(t, entry)
+	_, ok := entry.get()
+	assert.False(t, ok)
+
+	entry.put("print('cached')")
+	code, ok := programCacheEntry(image, nil).get()
+	require.True(t, ok)
+	assert.Equal(t, "print('cached')", code)
+
+	// Libraries, the backend and the image contents are all part of the key
+	assert.NotEqual(t, entry.key, programCacheEntry(image, []string{library}).key)
+	oldBackend := detectorBackend
+	detectorBackend = "parallel"
+	assert.NotEqual(t, entry.key, programCacheEntry(image, nil).key)
+	detectorBackend = oldBackend
+	require.NoError(t, os.WriteFile(image, []byte("changed"), 0o644))
+	assert.NotEqual(t, entry.key, programCacheEntry(image, nil).key)
+
+	// Images that cannot be hashed are not cached
+	assert.Nil(t, programCacheEntry(stdinPath, nil))
+	assert.Nil(t, programCacheEntry(filepath.Join(dir, "missing.png"), nil))
+	assert.Nil(t, programCacheEntry(image, []string{stdinPath}))
+
+	// A nil entry misses and ignores puts
+	var none *cacheEntry
+	none.put("print('lost')")
+	_, ok = none.get()
+	assert.False(t, ok)
+}
+
+func TestCacheCommands(t *testing.T) {
+	dir := t.TempDir()
+	t.Setenv(cache.DirEnv, dir)
+	c := cache.New(dir)
+	require.NoError(t, c.Put(cache.Key([]byte("a")), []byte("print(1)")))
+	require.NoError(t, c.Put(cache.Key([]byte("b")), []byte("print(22)")))
+
+	output := executeCapturingStdout(t, "cache", "stats")
+	assert.Contains(t, output, "Cache directory: "+dir)
+	assert.Contains(t, output, "Entries: 2")
+	assert.Contains(t, output, "Size: 17 bytes")
+
+	output = executeCapturingStdout(t, "cache", "clear")
+	assert.Contains(t, output, "Removed 2 cached program(s)")
+
+	stats, err := c.Stats()
+	require.NoError(t, err)
+	assert.Zero(t, stats.Entries)
+}
+
+// executeCapturingStdout runs the CLI in English and returns its stdout
+func executeCapturingStdout(t *testing.T, args ...string) string {
+	t.Helper()
+	output, err := executeCapturing(t, args...)
+	require.NoError(t, err)
+	return output
+}
+
+// executeCapturing runs the CLI in English and returns its stdout and the
+// error it failed with
+func executeCapturing(t *testing.T, args ...string) (string, error) {
+	t.Helper()
+	oldArgs := os.Args
+	os.Args = append([]string{"grimoire"}, append(args, "--lang", "en")...)
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err := Execute("test", "test", "test")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	var buf bytes.Buffer
+	_, readErr := buf.ReadFrom(r)
+	require.NoError(t, readErr)
+	return buf.String(), err
+}
@@ -0,0 +1,57 @@
+package cli
+
+import (
+	"strings"
+
+	"github.com/ayutaz/grimoire/internal/compiler"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/spf13/cobra"
+)
+
+// allEnvironment is the value of a bare --allow-env, granting every
+// environment variable
+const allEnvironment = "*"
+
+// grantedCapabilities are what programs run by executePython may do
+// through built-in functions, set with --allow-env and --allow-fs
+var grantedCapabilities []string
+
+// capabilityFlags adds the flags granting capabilities to cmd
+func capabilityFlags(cmd *cobra.Command) {
+	cmd.Flags().StringSlice("allow-env", nil, i18n.T("cli.allow_env_flag_description"))
+	cmd.Flags().Lookup("allow-env").NoOptDefVal = allEnvironment
+	cmd.Flags().Bool("allow-fs", false, i18n.T("cli.allow_fs_flag_description"))
+}
+
+// readCapabilityFlags sets the granted capabilities from the flags of cmd
+func readCapabilityFlags(cmd *cobra.Command) {
+	variables, _ := cmd.Flags().GetStringSlice("allow-env")
+	allowFS, _ := cmd.Flags().GetBool("allow-fs")
+	grantedCapabilities = capabilityGrants(variables, allowFS)
+}
+
+// capabilityGrants returns the capabilities granted by the environment
+// variables allowed, where "*" allows all of them, and by allowFS
+func capabilityGrants(variables []string, allowFS bool) []string {
+	var grants []string
+	for _, name := range variables {
+		switch name = strings.TrimSpace(name); name {
+		case "":
+		case allEnvironment:
+			grants = append(grants, compiler.CapabilityEnv)
+		default:
+			grants = append(grants, compiler.CapabilityEnv+":"+name)
+		}
+	}
+	if allowFS {
+		grants = append(grants, compiler.CapabilityFS)
+	}
+	return grants
+}
+
+// capabilityEnvironment returns the environment entry passing the granted
+// capabilities to a program. It is always set, so that a value inherited
+// from the environment grants nothing.
+func capabilityEnvironment() string {
+	return compiler.CapabilitiesVariable + "=" + strings.Join(grantedCapabilities, ",")
+}
@@ -0,0 +1,33 @@
+package cli
+
+import (
+	"testing"
+
+	"github.com/spf13/cobra"
+	"github.com/stretchr/testify/assert"
+)
+
+func TestCapabilityGrants(t *testing.T) {
+	assert.Empty(t, capabilityGrants(nil, false))
+	assert.Equal(t, []string{"env"}, capabilityGrants([]string{"*"}, false))
+	assert.Equal(t, []string{"env:HOME", "env:LANG", "fs"}, capabilityGrants([]string{"HOME", " LANG", ""}, true))
+}
+
+func TestCapabilityFlags(t *testing.T) {
+	defer func() { grantedCapabilities = nil }()
+	tests := []struct {
+		args []string
+		want string
+	}{
+		{nil, "GRIMOIRE_CAPABILITIES="},
+		{[]string{"--allow-env"}, "GRIMOIRE_CAPABILITIES=env"},
+		{[]string{"--allow-env=HOME,LANG", "--allow-fs"}, "GRIMOIRE_CAPABILITIES=env:HOME,env:LANG,fs"},
+	}
+	for _, tt := range tests {
+		cmd := &cobra.Command{}
+		capabilityFlags(cmd)
+		assert.NoError(t, cmd.ParseFlags(tt.args))
+		readCapabilityFlags(cmd)
+		assert.Equal(t, tt.want, capabilityEnvironment())
+	}
+}
@@ -0,0 +1,87 @@
+package cli
+
+import (
+	"os"
+	"os/exec"
+	"path/filepath"
+	"runtime"
+	"strings"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+)
+
+// captureTool is a screenshot tool that lets the user drag out a region of
+// the screen and saves it as a PNG
+type captureTool struct {
+	name string
+	// args are the arguments that save the selected region to path
+	args func(path string) []string
+}
+
+// captureTools are the tools run --capture tries on each platform, in order
+// of preference. Grimoire has no screen access of its own, so it drives
+// whichever of them is installed.
+var captureTools = map[string][]captureTool{
+	"darwin": {
+		{name: "screencapture", args: func(path string) []string { return []string{"-i", "-x", path} }},
+	},
+	"linux": {
+		{name: "gnome-screenshot", args: func(path string) []string { return []string{"-a", "-f", path} }},
+		{name: "spectacle", args: func(path string) []string { return []string{"-r", "-b", "-n", "-o", path} }},
+		{name: "maim", args: func(path string) []string { return []string{"-s", path} }},
+		{name: "scrot", args: func(path string) []string { return []string{"-s", "-o", path} }},
+		{name: "import", args: func(path string) []string { return []string{path} }},
+	},
+}
+
+// findCaptureTool returns the first capture tool of the platform that is
+// installed
+func findCaptureTool() (captureTool, bool) {
+	for _, tool := range captureTools[runtime.GOOS] {
+		if _, err := exec.LookPath(tool.name); err == nil {
+			return tool, true
+		}
+	}
+	return captureTool{}, false
+}
+
+// captureRegion lets the user select a region of the screen and returns the
+// path of the PNG it was saved to, with a function that removes it
+func captureRegion() (string, func(), error) {
+	tool, ok := findCaptureTool()
+	if !ok {
+		var names []string
+		for _, tool := range captureTools[runtime.GOOS] {
+			names = append(names, tool.name)
+		}
+		err := grimoireErrors.NewError(grimoireErrors.ExecutionError, i18n.T("capture.no_tool"))
+		if len(names) > 0 {
+			return "", nil, err.WithSuggestion(i18n.Tf("capture.no_tool_suggestion", strings.Join(names, ", ")))
+		}
+		return "", nil, err
+	}
+
+	dir, err := os.MkdirTemp("", "grimoire-capture-")
+	if err != nil {
+		return "", nil, grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("capture.failed")).
+			WithInnerError(err)
+	}
+	cleanup := func() { os.RemoveAll(dir) }
+	path := filepath.Join(dir, "capture.png")
+
+	cmd := exec.Command(tool.name, tool.args(path)...)
+	cmd.Stderr = os.Stderr
+	if err := cmd.Run(); err != nil {
+		cleanup()
+		return "", nil, grimoireErrors.NewError(grimoireErrors.ExecutionError, i18n.Tf("capture.tool_failed", tool.name)).
+			WithInnerError(err)
+	}
+	// The tools exit successfully without writing anything when the
+	// selection is cancelled
+	if info, err := os.Stat(path); err != nil || info.Size() == 0 {
+		cleanup()
+		return "", nil, grimoireErrors.NewError(grimoireErrors.ExecutionError, i18n.T("capture.cancelled"))
+	}
+	return path, cleanup, nil
+}
@@ -0,0 +1,66 @@
+package cli
+
+import (
+	"os"
+	"path/filepath"
+	"runtime"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// fakeCaptureTool installs, as the only program on PATH, a scrot that runs
+// script with the path to save to in $p
+func fakeCaptureTool(t *testing.T, script string) {
+	t.Helper()
+	if runtime.GOOS != "linux" {
+		t.Skip("The fake capture tool is a shell script standing in for scrot")
+	}
+	dir := t.TempDir()
+	body := "#!/bin/sh\nfor p in \"$@\"; do :; done\n" + script + "\n"
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "scrot"), []byte(body), 0o755))
+	t.Setenv("PATH", dir)
+}
+
+func TestCaptureRegion(t *testing.T) {
+	imagePath, err := filepath.Abs(filepath.Join("..", "..", "examples", "images", "hello_world.png"))
+	require.NoError(t, err)
+	if _, err := os.Stat(imagePath); err != nil {
+		t.Skip("Example image not found")
+	}
+	fakeCaptureTool(t, "/bin/cp '"+imagePath+"' \"$p\"")
+
+	path, cleanup, err := captureRegion()
+	require.NoError(t, err)
+	captured, err := os.ReadFile(path)
+	require.NoError(t, err)
+	original, err := os.ReadFile(imagePath)
+	require.NoError(t, err)
+	assert.Equal(t, original, captured)
+
+	cleanup()
+	_, err = os.Stat(path)
+	assert.True(t, os.IsNotExist(err))
+}
+
+func TestCaptureCancelled(t *testing.T) {
+	fakeCaptureTool(t, "exit 0")
+
+	_, _, err := captureRegion()
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), i18n.T("capture.cancelled"))
+}
+
+func TestCaptureWithoutTool(t *testing.T) {
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "run", "--capture", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	t.Setenv("PATH", t.TempDir())
+
+	err := Execute("test", "test", "test")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "No screen capture tool was found")
+}
@@ -0,0 +1,38 @@
+package cli
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/spf13/cobra"
+)
+
+// checkCommand detects, parses and type checks an image without generating
+// or running code. Each stage stops at its first failure, so problems are
+// reported as quickly as possible.
+func checkCommand(_ *cobra.Command, args []string) error {
+	imagePath := args[0]
+
+	symbols, connections, err := detectSymbols(imagePath)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+
+	p := parser.NewParser().WithStrictness(strictness)
+	program, err := p.ParseResolvingAmbiguity(symbols, connections)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+	reportResolutions(os.Stderr, p.Resolutions(), imagePath)
+	reportWarnings(os.Stderr, p.Warnings(), imagePath)
+
+	if err := parser.TypeCheck(program, p.Roles()); err != nil {
+		return formatError(err, imagePath)
+	}
+
+	fmt.Printf(i18n.T("check.success"), filepath.Base(imagePath), len(symbols), len(connections))
+	return nil
+}
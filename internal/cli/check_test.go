@@ -0,0 +1,56 @@
+package cli
+
+import (
+	"bytes"
+	"os"
+	"path/filepath"
+	"testing"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestCheckCommand(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "check", imagePath, "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err := Execute("test", "test", "test")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	var buf bytes.Buffer
+	_, readErr := buf.ReadFrom(r)
+	require.NoError(t, readErr)
+	output := buf.String()
+
+	require.NoError(t, err)
+	assert.Contains(t, output, "hello_world.png: no problems found")
+	assert.NotContains(t, output, "def ")
+}
+
+func TestCheckCommandBlankImage(t *testing.T) {
+	imagePath := filepath.Join(t.TempDir(), "blank.png")
+	writeBlankImage(t, imagePath, 64, 64)
+
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "check", imagePath}
+	defer func() { os.Args = oldArgs }()
+
+	err := Execute("test", "test", "test")
+	require.Error(t, err)
+	assert.NotEqual(t, grimoireErrors.ExitOK, grimoireErrors.ExitCode(err))
+}
@@ -1,18 +1,25 @@
 package cli
 
 import (
+	"crypto/ed25519"
 	"fmt"
 	"math"
 	"os"
 	"os/exec"
 	"path/filepath"
+	"runtime"
 	"strings"
 
-	"github.com/ayutaz/grimoire/internal/compiler"
+	"github.com/ayutaz/grimoire/internal/bench"
+	"github.com/ayutaz/grimoire/internal/bundle"
+	"github.com/ayutaz/grimoire/internal/conformance"
+	"github.com/ayutaz/grimoire/internal/dataset"
 	"github.com/ayutaz/grimoire/internal/detector"
 	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
 	"github.com/ayutaz/grimoire/internal/i18n"
 	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/ayutaz/grimoire/internal/server"
+	"github.com/ayutaz/grimoire/pkg/grimoire"
 	"github.com/spf13/cobra"
 )
 
@@ -20,6 +27,20 @@ import (
 func Execute(version, commit, date string) error {
 	// Initialize i18n before creating commands
 	i18n.Init()
+	buildVersion = version
+	diagnosticOptions.format = formatText
+	diagnosticOptions.thumbnailDir = ""
+	detectorBackend = grimoire.BackendSequential
+	allowHugeImages = false
+	minConfidence = 0
+	showTimings = false
+	detectionTimeout = 0
+	maxContours, maxContourPoints = 0, 0
+	remoteDetection = false
+	preprocessing = nil
+	inkCount = 0
+	strictness = grimoire.Lenient
+	grantedCapabilities = nil
 
 	rootCmd := &cobra.Command{
 		Use:     "grimoire",
@@ -42,26 +63,119 @@ func Execute(version, commit, date string) error {
 					i18n.SetLanguage(i18n.English)
 				}
 			}
+
+			// Handle diagnostic output flags
+			format, _ := cmd.Flags().GetString("format")
+			switch format {
+			case formatText:
+			case formatJSON:
+				// Keep stderr machine-readable; ReportError prints the error
+				cmd.Root().SilenceErrors = true
+				cmd.Root().SilenceUsage = true
+			default:
+				return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("cli.invalid_format", format))
+			}
+			diagnosticOptions.format = format
+			diagnosticOptions.thumbnailDir, _ = cmd.Flags().GetString("thumbnails")
+
+			// Handle detection backend flag
+			backend, _ := cmd.Flags().GetString("backend")
+			if _, err := grimoire.NewBackend(backend); err != nil {
+				return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("cli.invalid_backend", backend))
+			}
+			detectorBackend = backend
+			allowHugeImages, _ = cmd.Flags().GetBool("allow-huge")
+
+			// Handle confidence threshold flag
+			threshold, _ := cmd.Flags().GetFloat64("min-confidence")
+			if threshold < 0 || threshold > 1 {
+				return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("cli.invalid_min_confidence", threshold))
+			}
+			minConfidence = threshold
+
+			// Handle preprocessing chain flag
+			if chain, _ := cmd.Flags().GetString("preprocess"); chain != "" {
+				steps, err := grimoire.ParsePreprocessing(chain)
+				if err != nil {
+					return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("cli.invalid_preprocess", err))
+				}
+				preprocessing = steps
+			}
+
+			// Handle ink separation flag
+			inks, _ := cmd.Flags().GetInt("inks")
+			if inks < 0 || inks > grimoire.MaxInks {
+				return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("cli.invalid_inks", inks, grimoire.MaxInks))
+			}
+			inkCount = inks
+			showTimings, _ = cmd.Flags().GetBool("timings")
+			remoteDetection, _ = cmd.Flags().GetBool("remote")
+
+			// Handle detection deadline flag
+			timeout, _ := cmd.Flags().GetDuration("detect-timeout")
+			if timeout < 0 {
+				return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("cli.invalid_detect_timeout", timeout))
+			}
+			detectionTimeout = timeout
+
+			// Handle complexity limit flags
+			for flag, limit := range map[string]*int{"max-contours": &maxContours, "max-contour-points": &maxContourPoints} {
+				*limit, _ = cmd.Flags().GetInt(flag)
+				if *limit < 0 {
+					return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("cli.invalid_limit", "--"+flag, *limit))
+				}
+			}
+
+			// Handle the strictness flags
+			strict, _ := cmd.Flags().GetBool("strict")
+			permissive, _ := cmd.Flags().GetBool("permissive")
+			switch {
+			case strict && permissive:
+				return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("cli.strict_and_permissive"))
+			case strict:
+				strictness = grimoire.Strict
+			case permissive:
+				strictness = grimoire.Permissive
+			}
 			return nil
 		},
 	}
 
 	// Run command
 	runCmd := &cobra.Command{
-		Use:   "run [image]",
+		Use:   "run [image] [library images...]",
 		Short: i18n.T("cli.run_description"),
-		Args:  cobra.ExactArgs(1),
-		RunE:  runCommand,
+		Args: func(cmd *cobra.Command, args []string) error {
+			// A captured region takes the place of the image
+			if capture, _ := cmd.Flags().GetBool("capture"); capture {
+				return nil
+			}
+			return cobra.MinimumNArgs(1)(cmd, args)
+		},
+		RunE: runCommand,
 	}
+	runCmd.Flags().Bool("no-cache", false, i18n.T("cli.no_cache_flag_description"))
+	runCmd.Flags().Bool("from-qr", false, i18n.T("cli.from_qr_flag_description"))
+	runCmd.Flags().Bool("profile", false, i18n.T("cli.profile_flag_description"))
+	runCmd.Flags().String("profile-image", "", i18n.T("cli.profile_image_flag_description"))
+	runCmd.Flags().String("animate", "", i18n.T("cli.animate_flag_description"))
+	runCmd.Flags().Bool("capture", false, i18n.T("cli.capture_flag_description"))
+	runCmd.Flags().Bool("require-signed", false, i18n.T("cli.require_signed_flag_description"))
+	runCmd.Flags().StringSlice("trusted-key", nil, i18n.T("cli.trusted_key_flag_description"))
+	capabilityFlags(runCmd)
 
 	// Compile command
 	compileCmd := &cobra.Command{
-		Use:   "compile [image]",
+		Use:   "compile [image] [library images...]",
 		Short: i18n.T("cli.compile_description"),
-		Args:  cobra.ExactArgs(1),
+		Args:  cobra.MinimumNArgs(1),
 		RunE:  compileCommand,
 	}
 	compileCmd.Flags().StringP("output", "o", "", i18n.T("cli.output_flag_description"))
+	compileCmd.Flags().String("emit", emitPython, i18n.T("cli.emit_flag_description"))
+	compileCmd.Flags().IntP("opt-level", "O", 0, i18n.T("cli.opt_level_flag_description"))
+	compileCmd.Flags().Bool("bundle", false, i18n.T("cli.bundle_flag_description"))
+	compileCmd.Flags().String("key", "", i18n.T("cli.qr_key_flag_description"))
 
 	// Debug command
 	debugCmd := &cobra.Command{
@@ -70,6 +184,7 @@ func Execute(version, commit, date string) error {
 		Args:  cobra.ExactArgs(1),
 		RunE:  debugCommand,
 	}
+	debugCmd.Flags().Int("sectors", parser.DefaultSectors, i18n.T("cli.sectors_flag_description"))
 
 	// Validate command
 	validateCmd := &cobra.Command{
@@ -88,6 +203,55 @@ func Execute(version, commit, date string) error {
 	}
 	formatCmd.Flags().StringP("output", "o", "", i18n.T("cli.format_output_flag_description"))
 
+	// Fmt command
+	fmtCmd := &cobra.Command{
+		Use:   "fmt [image]",
+		Short: i18n.T("cli.fmt_description"),
+		Args:  cobra.ExactArgs(1),
+		RunE:  fmtCommand,
+	}
+	fmtCmd.Flags().StringP("output", "o", "", i18n.T("cli.fmt_output_flag_description"))
+
+	// Extract command
+	extractCmd := &cobra.Command{
+		Use:   "extract [image]",
+		Short: i18n.T("cli.extract_description"),
+		Args:  cobra.ExactArgs(1),
+		RunE:  extractCommand,
+	}
+	extractCmd.Flags().String("region", "", i18n.T("cli.extract_region_flag_description"))
+	extractCmd.Flags().String("out", "", i18n.T("cli.extract_out_flag_description"))
+	extractCmd.Flags().StringP("output", "o", "", i18n.T("cli.extract_output_flag_description"))
+
+	// Parse command
+	parseCmd := &cobra.Command{
+		Use:   "parse [image]",
+		Short: i18n.T("cli.parse_description"),
+		Args:  cobra.MaximumNArgs(1),
+		RunE:  parseCommand,
+	}
+	parseCmd.Flags().String("symbols", "", i18n.T("cli.parse_symbols_flag_description"))
+	parseCmd.Flags().Bool("code", false, i18n.T("cli.parse_code_flag_description"))
+
+	// Sign command
+	signCmd := &cobra.Command{
+		Use:   "sign [bundle]",
+		Short: i18n.T("cli.sign_description"),
+		Args:  cobra.ExactArgs(1),
+		RunE:  signCommand,
+	}
+	signCmd.Flags().String("key", "", i18n.T("cli.sign_key_flag_description"))
+	signCmd.Flags().Bool("generate-key", false, i18n.T("cli.generate_key_flag_description"))
+
+	// Verify command
+	verifyCmd := &cobra.Command{
+		Use:   "verify [bundle]",
+		Short: i18n.T("cli.verify_description"),
+		Args:  cobra.ExactArgs(1),
+		RunE:  verifyCommand,
+	}
+	verifyCmd.Flags().StringSlice("key", nil, i18n.T("cli.verify_key_flag_description"))
+
 	// Optimize command
 	optimizeCmd := &cobra.Command{
 		Use:   "optimize [image]",
@@ -97,27 +261,314 @@ func Execute(version, commit, date string) error {
 	}
 	optimizeCmd.Flags().StringP("output", "o", "", i18n.T("cli.optimize_output_flag_description"))
 
+	// Explain command
+	explainCmd := &cobra.Command{
+		Use:   "explain [image]",
+		Short: i18n.T("cli.explain_description"),
+		Args:  cobra.ExactArgs(1),
+		RunE:  explainCommand,
+	}
+	explainCmd.Flags().String("annotate", "", i18n.T("cli.explain_annotate_flag_description"))
+
+	// Check command
+	checkCmd := &cobra.Command{
+		Use:   "check [image]",
+		Short: i18n.T("cli.check_description"),
+		Args:  cobra.ExactArgs(1),
+		RunE:  checkCommand,
+	}
+
+	// Symbols command
+	symbolsCmd := &cobra.Command{
+		Use:   "symbols",
+		Short: i18n.T("cli.symbols_description"),
+		Args:  cobra.NoArgs,
+		RunE:  symbolsCommand,
+	}
+
+	// Stats command
+	statsCmd := &cobra.Command{
+		Use:   "stats [image]",
+		Short: i18n.T("cli.stats_description"),
+		Args:  cobra.ExactArgs(1),
+		RunE:  statsCommand,
+	}
+	statsCmd.Flags().Int("max-complexity", 0, i18n.T("cli.stats_max_complexity_flag_description"))
+	statsCmd.Flags().Int("max-depth", 0, i18n.T("cli.stats_max_depth_flag_description"))
+
+	// Inspect command
+	inspectCmd := &cobra.Command{
+		Use:   "inspect [image]",
+		Short: i18n.T("cli.inspect_description"),
+		Args:  cobra.ExactArgs(1),
+		RunE:  inspectCommand,
+	}
+
+	// Test command
+	testCmd := &cobra.Command{
+		Use:   "test [directory]",
+		Short: i18n.T("cli.test_description"),
+		Args:  cobra.ExactArgs(1),
+		RunE:  testCommand,
+	}
+	testCmd.Flags().Bool("update", false, i18n.T("cli.test_update_flag_description"))
+	testCmd.Flags().String("coverage", "", i18n.T("cli.test_coverage_flag_description"))
+	capabilityFlags(testCmd)
+
+	// Conform command
+	conformCmd := &cobra.Command{
+		Use:   "conform [directory]",
+		Short: i18n.T("cli.conform_description"),
+		Args:  cobra.ExactArgs(1),
+		RunE:  conformCommand,
+	}
+	conformCmd.Flags().Float64("tolerance", conformance.DefaultTolerance, i18n.T("cli.conform_tolerance_flag_description"))
+	conformCmd.Flags().String("reference", detector.BackendSequential, i18n.T("cli.conform_reference_flag_description"))
+	conformCmd.Flags().String("candidate", detector.BackendParallel, i18n.T("cli.conform_candidate_flag_description"))
+	conformCmd.Flags().Bool("round-trip", false, i18n.T("cli.conform_round_trip_flag_description"))
+	conformCmd.Flags().Bool("robustness", false, i18n.T("cli.conform_robustness_flag_description"))
+	conformCmd.Flags().Bool("update", false, i18n.T("cli.conform_update_flag_description"))
+
+	// Bench command
+	benchCmd := &cobra.Command{
+		Use:   "bench [directory]",
+		Short: i18n.T("cli.bench_description"),
+		Args:  cobra.ExactArgs(1),
+		RunE:  benchCommand,
+	}
+	benchCmd.Flags().String("baseline", "", i18n.T("cli.bench_baseline_flag_description"))
+	benchCmd.Flags().String("save", "", i18n.T("cli.bench_save_flag_description"))
+	benchCmd.Flags().Int("iterations", bench.DefaultIterations, i18n.T("cli.bench_iterations_flag_description"))
+	benchCmd.Flags().Float64("threshold", 100*bench.DefaultThreshold, i18n.T("cli.bench_threshold_flag_description"))
+
+	// Batch command
+	batchCmd := &cobra.Command{
+		Use:   "batch [directory]",
+		Short: i18n.T("cli.batch_description"),
+		Args:  cobra.ExactArgs(1),
+		RunE:  batchCommand,
+	}
+	batchCmd.Flags().IntP("jobs", "j", runtime.NumCPU(), i18n.T("cli.batch_jobs_flag_description"))
+	batchCmd.Flags().StringP("output", "o", "", i18n.T("cli.batch_output_flag_description"))
+
+	// Dataset command
+	datasetCmd := &cobra.Command{
+		Use:   "dataset",
+		Short: i18n.T("cli.dataset_description"),
+	}
+	datasetExtractCmd := &cobra.Command{
+		Use:   "extract [directory]",
+		Short: i18n.T("cli.dataset_extract_description"),
+		Args:  cobra.ExactArgs(1),
+		RunE:  datasetExtractCommand,
+	}
+	datasetExtractCmd.Flags().String("out", "", i18n.T("cli.dataset_out_flag_description"))
+	datasetExtractCmd.Flags().Int("size", dataset.DefaultPatchSize, i18n.T("cli.dataset_size_flag_description"))
+	datasetExtractCmd.Flags().Float64("margin", dataset.DefaultMargin, i18n.T("cli.dataset_margin_flag_description"))
+	datasetCmd.AddCommand(datasetExtractCmd)
+
+	// Cache command
+	cacheCmd := &cobra.Command{
+		Use:   "cache",
+		Short: i18n.T("cli.cache_description"),
+	}
+	cacheCmd.AddCommand(&cobra.Command{
+		Use:   "stats",
+		Short: i18n.T("cli.cache_stats_description"),
+		Args:  cobra.NoArgs,
+		RunE:  cacheStatsCommand,
+	}, &cobra.Command{
+		Use:   "clear",
+		Short: i18n.T("cli.cache_clear_description"),
+		Args:  cobra.NoArgs,
+		RunE:  cacheClearCommand,
+	})
+
+	// History command
+	historyCmd := &cobra.Command{
+		Use:   "history",
+		Short: i18n.T("cli.history_description"),
+	}
+	historyReplayCmd := &cobra.Command{
+		Use:   "replay [id]",
+		Short: i18n.T("cli.history_replay_description"),
+		Args:  cobra.ExactArgs(1),
+		RunE:  historyReplayCommand,
+	}
+	capabilityFlags(historyReplayCmd)
+	historyCmd.AddCommand(&cobra.Command{
+		Use:   "list",
+		Short: i18n.T("cli.history_list_description"),
+		Args:  cobra.NoArgs,
+		RunE:  historyListCommand,
+	}, historyReplayCmd)
+
+	// Correct command
+	correctCmd := &cobra.Command{
+		Use:   "correct [image]",
+		Short: i18n.T("cli.correct_description"),
+		Args:  cobra.ExactArgs(1),
+		RunE:  correctCommand,
+	}
+	correctCmd.Flags().String("apply", "", i18n.T("cli.correct_apply_flag_description"))
+	correctCmd.Flags().Bool("clear", false, i18n.T("cli.correct_clear_flag_description"))
+
+	// Diff command
+	diffCmd := &cobra.Command{
+		Use:   "diff [old image] [new image]",
+		Short: i18n.T("cli.diff_description"),
+		Args:  cobra.ExactArgs(2),
+		RunE:  diffCommand,
+	}
+	diffCmd.Flags().String("overlay", "", i18n.T("cli.diff_overlay_flag_description"))
+
+	// Examples command
+	examplesCmd := &cobra.Command{
+		Use:   "examples",
+		Short: i18n.T("cli.examples_description"),
+	}
+	examplesCmd.AddCommand(&cobra.Command{
+		Use:   "list",
+		Short: i18n.T("cli.examples_list_description"),
+		Args:  cobra.NoArgs,
+		RunE:  examplesListCommand,
+	}, &cobra.Command{
+		Use:   "run [name]",
+		Short: i18n.T("cli.examples_run_description"),
+		Args:  cobra.ExactArgs(1),
+		RunE:  examplesRunCommand,
+	})
+
+	// LSP command
+	lspCmd := &cobra.Command{
+		Use:   "lsp",
+		Short: i18n.T("cli.lsp_description"),
+		Args:  cobra.NoArgs,
+		RunE:  lspCommand,
+	}
+
+	// Serve command
+	serveCmd := &cobra.Command{
+		Use:   "serve",
+		Short: i18n.T("cli.serve_description"),
+		Args:  cobra.NoArgs,
+		RunE:  serveCommand,
+	}
+	serveCmd.Flags().String("addr", "127.0.0.1:8080", i18n.T("cli.serve_addr_flag_description"))
+
+	// Daemon command
+	daemonCmd := &cobra.Command{
+		Use:   "daemon",
+		Short: i18n.T("cli.daemon_description"),
+		Args:  cobra.NoArgs,
+		RunE:  daemonCommand,
+	}
+	daemonCmd.Flags().String("socket", server.DefaultSocket(), i18n.T("cli.daemon_socket_flag_description"))
+
 	// Add global language flag
 	rootCmd.PersistentFlags().StringP("lang", "l", "", i18n.T("cli.language_flag_description"))
 
 	// Add global flags (lang flag is already defined above)
 	rootCmd.PersistentFlags().Bool("debug", false, "Enable debug mode with detailed error information")
-
-	rootCmd.AddCommand(runCmd, compileCmd, debugCmd, validateCmd, formatCmd, optimizeCmd)
+	rootCmd.PersistentFlags().String("format", formatText, i18n.T("cli.format_flag_description"))
+	rootCmd.PersistentFlags().String("thumbnails", "", i18n.T("cli.thumbnails_flag_description"))
+	rootCmd.PersistentFlags().String("backend", grimoire.BackendSequential, i18n.T("cli.backend_flag_description"))
+	rootCmd.PersistentFlags().Bool("allow-huge", false, i18n.T("cli.allow_huge_flag_description"))
+	rootCmd.PersistentFlags().Float64("min-confidence", 0, i18n.T("cli.min_confidence_flag_description"))
+	rootCmd.PersistentFlags().String("preprocess", "", i18n.T("cli.preprocess_flag_description"))
+	rootCmd.PersistentFlags().Int("inks", 0, i18n.T("cli.inks_flag_description"))
+	rootCmd.PersistentFlags().Bool("timings", false, i18n.T("cli.timings_flag_description"))
+	rootCmd.PersistentFlags().Duration("detect-timeout", 0, i18n.T("cli.detect_timeout_flag_description"))
+	rootCmd.PersistentFlags().Int("max-contours", 0, i18n.T("cli.max_contours_flag_description"))
+	rootCmd.PersistentFlags().Int("max-contour-points", 0, i18n.T("cli.max_contour_points_flag_description"))
+	rootCmd.PersistentFlags().Bool("remote", false, i18n.T("cli.remote_flag_description"))
+	rootCmd.PersistentFlags().Bool("strict", false, i18n.T("cli.strict_flag_description"))
+	rootCmd.PersistentFlags().Bool("permissive", false, i18n.T("cli.permissive_flag_description"))
+
+	rootCmd.AddCommand(runCmd, compileCmd, debugCmd, validateCmd, formatCmd, fmtCmd, extractCmd, optimizeCmd, explainCmd, symbolsCmd,
+		statsCmd, inspectCmd, testCmd, diffCmd, lspCmd, serveCmd, daemonCmd, checkCmd, conformCmd, benchCmd, batchCmd,
+		cacheCmd, examplesCmd, signCmd, verifyCmd, parseCmd, datasetCmd, correctCmd, historyCmd)
 	return rootCmd.Execute()
 }
 
-func runCommand(_ *cobra.Command, args []string) error {
+func runCommand(cmd *cobra.Command, args []string) error {
+	// With --capture, every argument is a library image
+	if capture, _ := cmd.Flags().GetBool("capture"); capture {
+		path, cleanup, err := captureRegion()
+		if err != nil {
+			return err
+		}
+		defer cleanup()
+		args = append([]string{path}, args...)
+	}
 	imagePath := args[0]
+	readCapabilityFlags(cmd)
 
-	// Process the image
-	code, err := processImage(imagePath)
+	// A bundle carries its compiled program, and only a bundle can be signed
+	policy, err := signingPolicyFlags(cmd)
 	if err != nil {
-		return formatError(err, imagePath)
+		return err
+	}
+	if bundle.IsBundle(imagePath) {
+		return runBundle(imagePath, args[1:], policy)
 	}
 
-	// Execute the generated code
-	if err := executePython(code); err != nil {
+	// With --from-qr, the program embedded by compile --emit qr runs as it
+	// is, once its signature is checked if the policy requires one
+	if fromQR, _ := cmd.Flags().GetBool("from-qr"); fromQR {
+		code, err := trustedEmbeddedProgram(imagePath, policy)
+		if err != nil {
+			recordHistory(historyRun, imagePath, emitPython, nil, "", err)
+			return err
+		}
+		return runProgram(imagePath, nil, code)
+	}
+	if policy.required {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("sign.unsigned_input")).
+			WithLocation(imagePath, 0, 0).
+			WithSuggestion(i18n.T("sign.unsigned_input_suggestion"))
+	}
+
+	// Profiling needs the symbols behind the program, so it always reads the circle
+	profile, _ := cmd.Flags().GetBool("profile")
+	glowPath, _ := cmd.Flags().GetString("profile-image")
+	animationPath, _ := cmd.Flags().GetString("animate")
+	if profile || glowPath != "" || animationPath != "" {
+		return profileCommand(imagePath, args[1:], profileOutputs{
+			report:        profile || glowPath != "",
+			glowPath:      glowPath,
+			animationPath: animationPath,
+		})
+	}
+
+
+	// An unchanged image runs its cached program without being processed
+	var entry *cacheEntry
+	if noCache, _ := cmd.Flags().GetBool("no-cache"); !noCache {
+		entry = programCacheEntry(imagePath, args[1:])
+	}
+	code, cached := entry.get()
+
+	// Process the image, linking any library images
+	var program *grimoire.Program
+	if !cached {
+		c, err := compileImage(imagePath, 0, args[1:]...)
+		if err != nil {
+			recordHistory(historyRun, imagePath, emitPython, nil, "", err)
+			return formatError(err, imagePath)
+		}
+		program, code = c.program, c.code
+		entry.put(code)
+	}
+	return runProgram(imagePath, program, code)
+}
+
+// runProgram executes the code compiled from the image at imagePath and
+// records the run in the history
+func runProgram(imagePath string, program *grimoire.Program, code string) error {
+	err := executePython(code)
+	recordHistory(historyRun, imagePath, emitPython, program, code, err)
+	if err != nil {
 		return grimoireErrors.NewError(grimoireErrors.ExecutionError, i18n.T("msg.failed_execute_python")).
 			WithInnerError(err).
 			WithSuggestion(i18n.T("suggest.check_python_installed"))
@@ -128,12 +579,43 @@ func runCommand(_ *cobra.Command, args []string) error {
 func compileCommand(cmd *cobra.Command, args []string) error {
 	imagePath := args[0]
 	outputPath, _ := cmd.Flags().GetString("output")
+	emit, _ := cmd.Flags().GetString("emit")
+	level, _ := cmd.Flags().GetInt("opt-level")
+	if emit != emitPython && emit != emitQR {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("cli.invalid_emit", emit))
+	}
+	if emit == emitQR && outputPath == "" {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("cli.emit_qr_requires_output"))
+	}
+	if bundled, _ := cmd.Flags().GetBool("bundle"); bundled {
+		if emit != emitPython {
+			return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("bundle.requires_python"))
+		}
+		return compileBundle(imagePath, args[1:], level, outputPath)
+	}
 
-	// Process the image
-	code, err := processImage(imagePath)
+	// Process the image, linking any library images
+	c, err := compileImage(imagePath, level, args[1:]...)
 	if err != nil {
+		recordHistory(historyCompile, imagePath, emit, nil, "", err)
 		return formatError(err, imagePath)
 	}
+	code := c.code
+	recordHistory(historyCompile, imagePath, emit, c.program, code, nil)
+
+	if emit == emitQR {
+		var key ed25519.PrivateKey
+		if keyPath, _ := cmd.Flags().GetString("key"); keyPath != "" {
+			if key, err = readPrivateKey(keyPath); err != nil {
+				return err
+			}
+		}
+		if err := writeQRImage(imagePath, code, outputPath, key); err != nil {
+			return err
+		}
+		fmt.Printf(i18n.T("cli.compile_qr_success"), outputPath)
+		return nil
+	}
 
 	// Output the code
 	if outputPath != "" {
@@ -149,12 +631,21 @@ func compileCommand(cmd *cobra.Command, args []string) error {
 	return nil
 }
 
-func debugCommand(_ *cobra.Command, args []string) error {
+func debugCommand(cmd *cobra.Command, args []string) error {
 	imagePath := args[0]
+	sectors := parser.DefaultSectors
+	if cmd.Flags().Lookup("sectors") != nil {
+		sectors, _ = cmd.Flags().GetInt("sectors")
+	}
+	if sectors < 1 {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("cli.invalid_sectors", sectors))
+	}
 
-	// Detect symbols
-	symbols, connections, err := detector.DetectSymbols(imagePath)
-	if err != nil {
+	// Detect symbols, showing those found before the deadline if it passed
+	symbols, connections, err := detectSymbols(imagePath)
+	if partial, ok := err.(*grimoireErrors.GrimoireError); ok && grimoire.IsPartial(partial) {
+		reportWarnings(os.Stderr, []*grimoireErrors.GrimoireError{partial}, imagePath)
+	} else if err != nil {
 		return formatError(err, imagePath)
 	}
 
@@ -175,29 +666,108 @@ func debugCommand(_ *cobra.Command, args []string) error {
 		}
 	}
 
+	// Where each symbol sits around the outer circle
+	if layout := parser.PolarLayout(symbols, sectors); layout != nil {
+		fmt.Println(i18n.Tf("debug.polar_header", sectors))
+		for i, pos := range layout {
+			fmt.Printf(i18n.T("debug.polar_info"), i, symbols[i].Type, pos.Radius, pos.Angle*180/math.Pi, pos.Sector)
+		}
+	}
+
 	return nil
 }
 
-func processImage(imagePath string) (string, error) {
-	// 1. Detect symbols
-	symbols, connections, err := detector.DetectSymbols(imagePath)
+// processImage compiles the image at imagePath to Python. The function
+// definitions of any library images are linked in ahead of its own.
+func processImage(imagePath string, libraryPaths ...string) (string, error) {
+	return processImageOptimized(imagePath, 0, libraryPaths...)
+}
+
+// processImageOptimized is like processImage but optimizes the program at
+// the given level
+func processImageOptimized(imagePath string, level int, libraryPaths ...string) (string, error) {
+	c, err := compileImage(imagePath, level, libraryPaths...)
 	if err != nil {
-		return "", err // Already formatted error
+		return "", err
 	}
+	return c.code, nil
+}
 
-	// 2. Parse to AST
-	ast, err := parser.Parse(symbols, connections)
+// compilation is what compiling an image made at each stage
+type compilation struct {
+	symbols     []*grimoire.Symbol
+	connections []grimoire.Connection
+	// program is the syntax tree with the libraries linked in
+	program *grimoire.Program
+	code    string
+}
+
+// compileImage compiles the image at imagePath like processImageOptimized,
+// keeping the results of every stage
+func compileImage(imagePath string, level int, libraryPaths ...string) (*compilation, error) {
+	// 1. Parse the libraries
+	libraries, err := parseLibraries(libraryPaths)
 	if err != nil {
-		return "", err // Already formatted error
+		return nil, err
 	}
 
-	// 3. Compile to Python
-	code, err := compiler.Compile(ast)
+	// 2. Detect symbols
+	symbols, connections, err := detectSymbols(imagePath)
 	if err != nil {
-		return "", err // Already formatted error
+		return nil, err // Already formatted error
 	}
 
-	return code, nil
+	// 3. Parse to AST, rereading ambiguous symbols if that makes it valid
+	p := parser.NewParser().WithStrictness(strictness).WithLibraries(libraryPaths...)
+	ast, err := p.ParseResolvingAmbiguity(symbols, connections)
+	if err != nil {
+		return nil, err // Already formatted error
+	}
+	reportResolutions(os.Stderr, p.Resolutions(), imagePath)
+	reportWarnings(os.Stderr, p.Warnings(), imagePath)
+
+	// 4. Link the libraries
+	ast, err = grimoire.Link(ast, libraries)
+	if err != nil {
+		return nil, err
+	}
+
+	// 5. Compile to Python
+	code, err := grimoire.CompileOptimized(ast, level)
+	if err != nil {
+		return nil, err // Already formatted error
+	}
+
+	return &compilation{symbols: symbols, connections: connections, program: ast, code: code}, nil
+}
+
+// parseLibraries detects and parses the library images at libraryPaths
+func parseLibraries(libraryPaths []string) ([]grimoire.Library, error) {
+	libraries := make([]grimoire.Library, 0, len(libraryPaths))
+	for _, libPath := range libraryPaths {
+		symbols, connections, err := detectSymbols(libPath)
+		if err != nil {
+			return nil, libraryError(err, libPath)
+		}
+		program, err := grimoire.Parse(symbols, connections)
+		if err != nil {
+			return nil, libraryError(err, libPath)
+		}
+		libraries = append(libraries, grimoire.Library{Name: libPath, Program: program})
+	}
+	return libraries, nil
+}
+
+// libraryError attributes an error to the library image it came from, so it
+// is not reported against the main image
+func libraryError(err error, libPath string) error {
+	if !grimoireErrors.IsGrimoireError(err) {
+		err = formatError(err, libPath)
+	}
+	if grimoireErr, ok := err.(*grimoireErrors.GrimoireError); ok && grimoireErr.FileName == "" {
+		_ = grimoireErr.WithLocation(libPath, 0, 0)
+	}
+	return err
 }
 
 func executePython(code string) error {
@@ -217,6 +787,7 @@ func executePython(code string) error {
 
 	// Execute the Python code
 	cmd := exec.Command("python3", tmpFile.Name())
+	cmd.Env = append(os.Environ(), capabilityEnvironment())
 	cmd.Stdout = os.Stdout
 	cmd.Stderr = os.Stderr
 
@@ -260,7 +831,7 @@ func validateCommand(_ *cobra.Command, args []string) error {
 	imagePath := args[0]
 
 	// Detect symbols
-	symbols, connections, err := detector.DetectSymbols(imagePath)
+	symbols, connections, err := detectSymbols(imagePath)
 	if err != nil {
 		return formatError(err, imagePath)
 	}
@@ -304,6 +875,14 @@ func validateCommand(_ *cobra.Command, args []string) error {
 		}
 	}
 
+	// Check that each symbol has connections its construct takes
+	var constructErrors []error
+	for _, issue := range parser.CheckConstructs(symbols, connections) {
+		err := constructError(issue, imagePath)
+		issues = append(issues, err.Message+" "+err.Suggestion)
+		constructErrors = append(constructErrors, err)
+	}
+
 	// Output results
 	if len(issues) == 0 {
 		fmt.Println(i18n.T("validate.success"))
@@ -314,7 +893,8 @@ func validateCommand(_ *cobra.Command, args []string) error {
 		for i, issue := range issues {
 			fmt.Printf("%d. %s\n", i+1, issue)
 		}
-		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("validate.failed"))
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("validate.failed")).
+			WithRelated(constructErrors...)
 	}
 
 	return nil
@@ -325,11 +905,10 @@ func formatCommand(cmd *cobra.Command, args []string) error {
 	imagePath := args[0]
 	outputPath, _ := cmd.Flags().GetString("output")
 
-	// For now, format command will analyze and provide suggestions
-	// In a full implementation, this would create a cleaned-up version of the magic circle
+	// format only suggests changes; fmt redraws the circle
 
 	// Detect symbols
-	symbols, connections, err := detector.DetectSymbols(imagePath)
+	symbols, connections, err := detectSymbols(imagePath)
 	if err != nil {
 		return formatError(err, imagePath)
 	}
@@ -396,7 +975,7 @@ func optimizeCommand(cmd *cobra.Command, args []string) error {
 	}
 
 	// Parse to get AST
-	symbols, connections, err := detector.DetectSymbols(imagePath)
+	symbols, connections, err := detectSymbols(imagePath)
 	if err != nil {
 		return formatError(err, imagePath)
 	}
@@ -513,6 +1092,21 @@ func analyzeDefined(stmt parser.Statement, defined map[string]bool) {
 				analyzeDefined(innerStmt, defined)
 			}
 		}
+	case *parser.Block:
+		for _, innerStmt := range s.Body {
+			analyzeDefined(innerStmt, defined)
+		}
+	case *parser.Stage:
+		for _, innerStmt := range s.Body {
+			analyzeDefined(innerStmt, defined)
+		}
+	case *parser.Mirror:
+		if id := s.Item; id != nil {
+			defined[id.Name] = true
+		}
+		for _, innerStmt := range s.Body {
+			analyzeDefined(innerStmt, defined)
+		}
 	}
 }
 
@@ -547,6 +1141,24 @@ func analyzeUsed(stmt parser.Statement, used map[string]bool) {
 				analyzeUsed(innerStmt, used)
 			}
 		}
+	case *parser.Block:
+		for _, innerStmt := range s.Body {
+			analyzeUsed(innerStmt, used)
+		}
+	case *parser.Stage:
+		for _, innerStmt := range s.Body {
+			analyzeUsed(innerStmt, used)
+		}
+	case *parser.Mirror:
+		for _, operand := range s.Operands {
+			analyzeUsedExpr(operand, used)
+		}
+		for _, innerStmt := range s.Body {
+			analyzeUsed(innerStmt, used)
+		}
+	case *parser.Assertion:
+		analyzeUsedExpr(s.Left, used)
+		analyzeUsedExpr(s.Right, used)
 	}
 }
 
@@ -570,6 +1182,11 @@ func analyzeUsedExpr(expr parser.Expression, used map[string]bool) {
 		for _, elem := range e.Elements {
 			analyzeUsedExpr(elem, used)
 		}
+	case *parser.MapLiteral:
+		for _, pair := range e.Pairs {
+			analyzeUsedExpr(pair[0], used)
+			analyzeUsedExpr(pair[1], used)
+		}
 	}
 }
 
@@ -6,11 +6,16 @@ import (
 	"image/color"
 	"image/draw"
 	"image/png"
+	"math"
 	"os"
+	"os/exec"
 	"path/filepath"
 	"strings"
 	"testing"
 
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/render"
 	"github.com/spf13/cobra"
 	"github.com/stretchr/testify/assert"
 	"github.com/stretchr/testify/require"
@@ -706,3 +711,84 @@ func drawTriangle(img *image.RGBA, cx, cy, size int, c color.Color) {
 	drawLine(img, x2, y2, x3, y3, c)
 	drawLine(img, x3, y3, x1, y1, c)
 }
+
+// TestRunCommandWithLibraries runs a drawn program whose main entry calls
+// the hello world example, given as a library image, through an
+// eight-pointed star with a dot inside
+func TestRunCommandWithLibraries(t *testing.T) {
+	if _, err := exec.LookPath("python3"); err != nil {
+		t.Skip("Python3 not available")
+	}
+	libraryPath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(libraryPath); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+
+	at := func(symbolType detector.SymbolType, x, y float64, pattern string) *detector.Symbol {
+		return &detector.Symbol{Type: symbolType, Position: detector.Position{X: x, Y: y}, Size: 20, Pattern: pattern}
+	}
+	entry := at(detector.DoubleCircle, 250, 150, detector.PatternEmpty)
+	call := at(detector.EightPointedStar, 250, 300, detector.PatternDot)
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 250, Y: 250}, Size: 200 * math.Sqrt(math.Pi)},
+		entry, call,
+	}
+	connections := []detector.Connection{{From: entry, To: call, ConnectionType: detector.ConnectionTypeSolid}}
+	opts := render.DefaultOptions()
+	laidOut, straightened := render.Canonical(symbols, connections, opts)
+	imagePath := filepath.Join(t.TempDir(), "main.png")
+	require.NoError(t, writeImage(imagePath, render.Render(laidOut, straightened, opts)))
+
+	code, err := processImage(imagePath, libraryPath)
+	require.NoError(t, err)
+	assert.Less(t, strings.Index(code, "def hello_world("), strings.Index(code, "hello_world()"),
+		"the library is defined before the program calls it")
+
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "run", imagePath, libraryPath}
+	defer func() { os.Args = oldArgs }()
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+	err = Execute("test", "test", "test")
+	w.Close()
+	os.Stdout = oldStdout
+
+	var buf bytes.Buffer
+	_, _ = buf.ReadFrom(r)
+	require.NoError(t, err)
+	assert.Equal(t, "Hello, World!\n", buf.String(), "the library runs when the program calls it")
+}
+
+// TestProcessImageDuplicateLibraries tests that a function defined twice is rejected
+func TestProcessImageDuplicateLibraries(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+
+	_, err := processImage(imagePath, imagePath, imagePath)
+	require.Error(t, err)
+
+	var grimoireErr *grimoireErrors.GrimoireError
+	require.ErrorAs(t, err, &grimoireErr)
+	assert.Equal(t, grimoireErrors.DuplicateDefinition, grimoireErr.Type)
+	assert.Equal(t, grimoireErrors.ExitParseError, grimoireErrors.ExitCode(err))
+}
+
+// TestProcessImageLibraryError tests that library failures name the library image
+func TestProcessImageLibraryError(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+
+	libPath := filepath.Join(t.TempDir(), "missing.png")
+	_, err := processImage(imagePath, libPath)
+	require.Error(t, err)
+
+	diags := grimoireErrors.Diagnostics(formatError(err, imagePath))
+	require.NotEmpty(t, diags)
+	assert.Equal(t, libPath, diags[0].File)
+}
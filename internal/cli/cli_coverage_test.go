@@ -673,3 +673,13 @@ func TestRunCommandExecutionError(t *testing.T) {
 			"Error should be related to Python execution or parsing: %s", errStr)
 	}
 }
+
+// TestDebugCommandInvalidSectors tests that the polar layout needs at least one sector
+func TestDebugCommandInvalidSectors(t *testing.T) {
+	cmd := &cobra.Command{}
+	cmd.Flags().Int("sectors", 0, "")
+
+	err := debugCommand(cmd, []string{"unused.png"})
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "--sectors")
+}
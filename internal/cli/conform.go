@@ -0,0 +1,156 @@
+package cli
+
+import (
+	"fmt"
+	"path/filepath"
+
+	"github.com/ayutaz/grimoire/internal/conformance"
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/spf13/cobra"
+)
+
+// conformCommand runs two detection backends over a directory of fixture
+// images and reports where the candidate diverges from the reference. With
+// --round-trip, each fixture is instead redrawn and read again by the
+// reference, and the fidelity of the round trip is reported. With
+// --robustness, each fixture is degraded step by step instead.
+func conformCommand(cmd *cobra.Command, args []string) error {
+	dir := args[0]
+	tolerance, _ := cmd.Flags().GetFloat64("tolerance")
+	referenceName, _ := cmd.Flags().GetString("reference")
+	candidateName, _ := cmd.Flags().GetString("candidate")
+	roundTrip, _ := cmd.Flags().GetBool("round-trip")
+	robustness, _ := cmd.Flags().GetBool("robustness")
+	update, _ := cmd.Flags().GetBool("update")
+
+	reference, err := detector.NewBackend(referenceName, detector.Config{Debug: false})
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("cli.invalid_backend", referenceName))
+	}
+	candidate, err := detector.NewBackend(candidateName, detector.Config{Debug: false})
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("cli.invalid_backend", candidateName))
+	}
+
+	images, err := conformance.Discover(dir)
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("error.file_read_error")).
+			WithInnerError(err).
+			WithLocation(dir, 0, 0)
+	}
+	if len(images) == 0 {
+		fmt.Printf(i18n.T("conform.no_fixtures"), dir)
+		return nil
+	}
+	if robustness {
+		return robustnessCommand(dir, images, reference, update)
+	}
+
+	// A round trip compares each drawing with its redrawing, both read by the reference
+	astDiffers := "conform.ast_differs"
+	var results []conformance.Result
+	if roundTrip {
+		astDiffers = "conform.ast_differs_round_trip"
+		results = conformance.NewRoundTripper(reference).CheckAll(images)
+	} else {
+		checker := conformance.NewChecker(reference, candidate)
+		checker.Tolerance = tolerance
+		results = checker.CheckAll(images)
+	}
+
+	diverged := 0
+	for _, result := range results {
+		name := filepath.Base(result.Image)
+		switch {
+		case result.Err != nil:
+			diverged++
+			fmt.Printf(i18n.T("conform.error"), name, result.Err)
+		case !result.Conforms():
+			diverged++
+			fmt.Printf(i18n.T("conform.diverge"), name)
+			for _, d := range result.Divergences {
+				if d.Kind == conformance.KindAST {
+					fmt.Print(i18n.T(astDiffers))
+					fmt.Print(indentLines(d.Detail, "      "))
+					continue
+				}
+				fmt.Printf(i18n.T("conform.divergence"), i18n.T("conform.kind."+d.Kind), d.Detail)
+			}
+		default:
+			fmt.Printf(i18n.T("conform.pass"), name)
+		}
+	}
+
+	fmt.Printf(i18n.T("conform.summary"), len(images)-diverged, diverged, len(images))
+	if roundTrip {
+		fmt.Printf(i18n.T("conform.fidelity"), 100*conformance.Fidelity(results))
+	}
+	if diverged > 0 {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("conform.failed", diverged))
+	}
+	return nil
+}
+
+// robustnessCommand degrades each fixture by every perturbation and reports
+// the highest level that still reads as the same program. Levels below the
+// thresholds recorded next to the fixtures fail; with update, the levels
+// measured now are recorded instead.
+func robustnessCommand(dir string, images []string, backend detector.Backend, update bool) error {
+	thresholdsPath := filepath.Join(dir, conformance.ThresholdsFile)
+	thresholds, err := conformance.LoadThresholds(thresholdsPath)
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("error.file_read_error")).
+			WithInnerError(err).
+			WithLocation(thresholdsPath, 0, 0)
+	}
+	recorded := thresholds
+	if update {
+		recorded = conformance.Thresholds{}
+	}
+
+	regressed, failed := 0, 0
+	for _, result := range conformance.NewProber(backend).ProbeAll(images) {
+		name := filepath.Base(result.Image)
+		if result.Err != nil {
+			failed++
+			fmt.Printf(i18n.T("conform.error"), name, result.Err)
+			continue
+		}
+
+		fmt.Printf(i18n.T("conform.robustness_image"), name)
+		worse := make(map[string]bool)
+		for _, perturbation := range recorded.Regressions(result) {
+			worse[perturbation] = true
+		}
+		for _, perturbation := range conformance.Perturbations {
+			level := result.Tolerated[perturbation.Name]
+			if worse[perturbation.Name] {
+				regressed++
+				fmt.Printf(i18n.T("conform.robustness_regressed"), perturbation.Name, level, thresholds[name][perturbation.Name])
+				continue
+			}
+			fmt.Printf(i18n.T("conform.robustness_level"), perturbation.Name, level)
+		}
+		if update {
+			thresholds.Record(result)
+		}
+	}
+
+	if update {
+		if err := thresholds.Save(thresholdsPath); err != nil {
+			return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+				WithInnerError(err).
+				WithLocation(thresholdsPath, 0, 0)
+		}
+		fmt.Printf(i18n.T("conform.thresholds_written"), thresholdsPath)
+	}
+	if failed > 0 {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("conform.failed", failed))
+	}
+	if regressed > 0 {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("conform.robustness_failed", regressed))
+	}
+	return nil
+}
@@ -0,0 +1,118 @@
+package cli
+
+import (
+	"bytes"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestConformCommand(t *testing.T) {
+	data, err := os.ReadFile(filepath.Join("..", "..", "examples", "images", "hello_world.png"))
+	if err != nil {
+		t.Skip("Example image not found")
+	}
+
+	dir := t.TempDir()
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "hello.png"), data, 0o644))
+	writeBlankImage(t, filepath.Join(dir, "blank.png"), 100, 100)
+
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "conform", dir, "--candidate", "sequential", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err = Execute("test", "test", "test")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	var buf bytes.Buffer
+	_, readErr := buf.ReadFrom(r)
+	require.NoError(t, readErr)
+	output := buf.String()
+
+	// The blank image has no outer circle, so the reference cannot detect it
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "1 fixture(s) diverged")
+	assert.Contains(t, output, "CONFORM hello.png")
+	assert.Contains(t, output, "ERROR blank.png")
+	assert.Contains(t, output, "1 conform, 1 diverge, 2 total")
+}
+
+func TestConformCommandInvalidBackend(t *testing.T) {
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "conform", t.TempDir(), "--reference", "gpu", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	err := Execute("test", "test", "test")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), `"gpu"`)
+}
+
+func TestConformCommandRoundTrip(t *testing.T) {
+	dir := t.TempDir()
+	writeBlankImage(t, filepath.Join(dir, "blank.png"), 100, 100)
+
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "conform", dir, "--round-trip", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err := Execute("test", "test", "test")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	var buf bytes.Buffer
+	_, readErr := buf.ReadFrom(r)
+	require.NoError(t, readErr)
+	output := buf.String()
+
+	require.Error(t, err)
+	assert.Contains(t, output, "ERROR blank.png")
+	assert.Contains(t, output, "Round-trip fidelity: 0.0%")
+}
+
+func TestConformCommandRobustness(t *testing.T) {
+	dir := t.TempDir()
+	writeBlankImage(t, filepath.Join(dir, "blank.png"), 100, 100)
+
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "conform", dir, "--robustness", "--update", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err := Execute("test", "test", "test")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	var buf bytes.Buffer
+	_, readErr := buf.ReadFrom(r)
+	require.NoError(t, readErr)
+	output := buf.String()
+
+	// The blank image cannot be read even before it is degraded
+	require.Error(t, err)
+	assert.Contains(t, output, "ERROR blank.png")
+	assert.Contains(t, output, "Thresholds written to")
+	assert.FileExists(t, filepath.Join(dir, "robustness.json"))
+}
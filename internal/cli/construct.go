@@ -0,0 +1,40 @@
+package cli
+
+import (
+	"strings"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/parser"
+)
+
+// constructError describes a symbol whose connections fit no construct,
+// suggesting the nearest valid ones, as an error located at the symbol
+func constructError(issue parser.ConstructIssue, imagePath string) *grimoireErrors.GrimoireError {
+	symbol := issue.Symbol
+	return grimoireErrors.NewError(grimoireErrors.InvalidConnection,
+		i18n.Tf("validate.invalid_construct", symbol.Type, symbol.Position.X, symbol.Position.Y, issue.In, issue.Out)).
+		WithSuggestion(didYouMean(issue)).
+		WithLocation(imagePath, 0, 0).
+		WithRegion(symbol.Position.X, symbol.Position.Y, symbol.Size)
+}
+
+// didYouMean phrases the suggestions of issue as a question, such as "Did
+// you mean a loop (pentagon) or a parallel block (hexagon)?". CheckConstructs
+// always suggests at least one construct.
+func didYouMean(issue parser.ConstructIssue) string {
+	names := make([]string, 0, len(issue.Suggestions))
+	for _, s := range issue.Suggestions {
+		name := i18n.T("construct." + s.Construct.Name)
+		if s.In != issue.In || s.Out != issue.Out {
+			name = i18n.Tf("construct.with_connections", name, s.In, s.Out)
+		}
+		names = append(names, name)
+	}
+	last := len(names) - 1
+	choices := names[last]
+	if last > 0 {
+		choices = strings.Join(names[:last], i18n.T("validate.list_separator")) + i18n.T("validate.or") + choices
+	}
+	return i18n.Tf("validate.did_you_mean", choices)
+}
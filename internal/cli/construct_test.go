@@ -0,0 +1,38 @@
+package cli
+
+import (
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestConstructError(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	triangle := &detector.Symbol{Type: detector.Triangle, Position: detector.Position{X: 120, Y: 80}, Size: 40}
+	symbols := []*detector.Symbol{triangle}
+	var connections []detector.Connection
+	for i := 0; i < 3; i++ {
+		child := &detector.Symbol{Type: detector.Star}
+		symbols = append(symbols, child)
+		connections = append(connections, detector.Connection{From: triangle, To: child})
+	}
+	issues := parser.CheckConstructs(symbols, connections)
+	require.Len(t, issues, 1)
+
+	err := constructError(issues[0], "circle.png")
+	assert.Equal(t, "triangle at (120, 80) cannot take 0 incoming and 3 outgoing connections", err.Message)
+	assert.Equal(t, "Did you mean a loop (pentagon), a parallel block (hexagon) or an if branch (triangle) "+
+		"with 0 incoming and 2 outgoing connections?", err.Suggestion)
+
+	diags := grimoireErrors.Diagnostics(err)
+	require.Len(t, diags, 1)
+	assert.Equal(t, err.Suggestion, diags[0].Help)
+	assert.NotNil(t, diags[0].Region)
+}
@@ -0,0 +1,161 @@
+package cli
+
+import (
+	"bytes"
+	"encoding/json"
+	"fmt"
+	"os"
+	"path/filepath"
+
+	"github.com/ayutaz/grimoire/internal/cache"
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/pkg/grimoire"
+	"github.com/spf13/cobra"
+)
+
+// correctionsDir is the directory in the cache directory where corrected
+// detections are saved, one per image keyed by a hash of its content
+const correctionsDir = "corrections"
+
+// correctCommand prints the detection of an image as JSON for the user to
+// correct. With --apply, the corrected JSON is saved and used in place of
+// detecting the image from then on; with --clear, it is removed.
+func correctCommand(cmd *cobra.Command, args []string) error {
+	imagePath := args[0]
+	applyPath, _ := cmd.Flags().GetString("apply")
+	remove, _ := cmd.Flags().GetBool("clear")
+
+	if !remove && applyPath == "" {
+		symbols, connections, err := detectSymbols(imagePath)
+		if err != nil {
+			return formatError(err, imagePath)
+		}
+		data, err := detector.EncodeJSON(symbols, connections)
+		if err != nil {
+			return err
+		}
+		var indented bytes.Buffer
+		if err := json.Indent(&indented, data, "", "  "); err != nil {
+			return err
+		}
+		fmt.Println(indented.String())
+		return nil
+	}
+
+	source, err := readSource(imagePath)
+	if err != nil {
+		if os.IsNotExist(err) {
+			return grimoireErrors.FileNotFoundError(imagePath)
+		}
+		return grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("error.file_read_error")).
+			WithInnerError(err).
+			WithLocation(imagePath, 0, 0)
+	}
+	corrections, err := correctionCache()
+	if err != nil {
+		return err
+	}
+	key := cache.Key(source)
+
+	if remove {
+		removed, err := corrections.Delete(key)
+		if err != nil {
+			return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("cache.clear_failed")).
+				WithInnerError(err).
+				WithLocation(corrections.Dir, 0, 0)
+		}
+		if removed {
+			fmt.Printf(i18n.T("correct.cleared"), sourceName(imagePath))
+		} else {
+			fmt.Printf(i18n.T("correct.none"), sourceName(imagePath))
+		}
+		return nil
+	}
+
+	data, err := os.ReadFile(applyPath)
+	if err != nil {
+		if os.IsNotExist(err) {
+			return grimoireErrors.FileNotFoundError(applyPath)
+		}
+		return grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("error.file_read_error")).
+			WithInnerError(err).
+			WithLocation(applyPath, 0, 0)
+	}
+	if err := validateCorrections(data); err != nil {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("correct.invalid")).
+			WithInnerError(err).
+			WithLocation(applyPath, 0, 0).
+			WithSuggestion(i18n.T("correct.invalid_suggestion"))
+	}
+	if err := corrections.Put(key, data); err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("correct.failed")).
+			WithInnerError(err).
+			WithLocation(corrections.Dir, 0, 0)
+	}
+	fmt.Printf(i18n.T("correct.saved"), sourceName(imagePath))
+	return nil
+}
+
+// validateCorrections checks that data is a detection every command can
+// read in place of detecting the image
+func validateCorrections(data []byte) error {
+	symbols, _, err := detector.DecodeJSON(data)
+	if err != nil {
+		return err
+	}
+	for i, symbol := range symbols {
+		if symbol == nil {
+			return fmt.Errorf("symbol %d is null", i)
+		}
+	}
+	return nil
+}
+
+// correctionCache returns the cache of corrected detections
+func correctionCache() (*cache.Cache, error) {
+	c, err := openCache()
+	if err != nil {
+		return nil, err
+	}
+	return cache.New(filepath.Join(c.Dir, correctionsDir)), nil
+}
+
+// savedCorrections returns the corrected detection saved for the image with
+// content source, if any
+func savedCorrections(source []byte) ([]byte, bool) {
+	corrections, err := correctionCache()
+	if err != nil {
+		return nil, false
+	}
+	return corrections.Get(cache.Key(source))
+}
+
+// correctedSymbols returns the detection saved for the image at imagePath
+// with grimoire correct, if any, telling the user it is used
+func correctedSymbols(imagePath string) ([]*grimoire.Symbol, []grimoire.Connection, bool) {
+	// Most images have no corrections, so they are not read again to be
+	// hashed unless some are saved
+	corrections, err := correctionCache()
+	if err != nil {
+		return nil, nil, false
+	}
+	if stats, err := corrections.Stats(); err != nil || stats.Entries == 0 {
+		return nil, nil, false
+	}
+	source, err := readSource(imagePath)
+	if err != nil {
+		return nil, nil, false
+	}
+	data, ok := corrections.Get(cache.Key(source))
+	if !ok {
+		return nil, nil, false
+	}
+	symbols, connections, err := detector.DecodeJSON(data)
+	if err != nil {
+		return nil, nil, false
+	}
+	fmt.Fprintf(os.Stderr, i18n.T("correct.using"), sourceName(imagePath))
+	return symbols, connections, true
+}
@@ -0,0 +1,69 @@
+package cli
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/cache"
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestCorrectCommand(t *testing.T) {
+	t.Setenv(cache.DirEnv, t.TempDir())
+	dir := t.TempDir()
+	imagePath := filepath.Join(dir, "blank.png")
+	writeBlankImage(t, imagePath, 200, 200)
+	before := programCacheEntry(imagePath, nil)
+	require.NotNil(t, before)
+
+	// The blank image is corrected to a circle with a star in it
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 100, Y: 100}, Size: 170},
+		{Type: detector.Star, Position: detector.Position{X: 100, Y: 100}, Size: 20},
+	}
+	data, err := detector.EncodeJSON(symbols, nil)
+	require.NoError(t, err)
+	correctionsPath := filepath.Join(dir, "corrections.json")
+	require.NoError(t, os.WriteFile(correctionsPath, data, 0o644))
+
+	output := executeCapturingStdout(t, "correct", imagePath, "--apply", correctionsPath)
+	assert.Contains(t, output, "Saved the corrections for blank.png")
+
+	// Every later run reads the corrections instead of detecting the image
+	output = executeCapturingStdout(t, "correct", imagePath)
+	assert.Contains(t, output, `"type": "outer_circle"`)
+	assert.Contains(t, output, `"type": "star"`)
+	output = executeCapturingStdout(t, "parse", imagePath)
+	assert.Contains(t, output, "OutputStatement")
+	assert.NotEqual(t, before.key, programCacheEntry(imagePath, nil).key, "programs compiled before the correction are not reused")
+
+	output = executeCapturingStdout(t, "correct", imagePath, "--clear")
+	assert.Contains(t, output, "Removed the corrections for blank.png")
+	assert.Equal(t, before.key, programCacheEntry(imagePath, nil).key)
+	output = executeCapturingStdout(t, "correct", imagePath, "--clear")
+	assert.Contains(t, output, "No corrections are saved for blank.png")
+}
+
+func TestCorrectCommandRejectsInvalidCorrections(t *testing.T) {
+	t.Setenv(cache.DirEnv, t.TempDir())
+	dir := t.TempDir()
+	imagePath := filepath.Join(dir, "blank.png")
+	writeBlankImage(t, imagePath, 50, 50)
+
+	invalidPath := filepath.Join(dir, "invalid.json")
+	require.NoError(t, os.WriteFile(invalidPath, []byte(`{"symbols": [{"type": "star"}], "connections": [{"from": 0, "to": 3}]}`), 0o644))
+	err := runArgs(t, "correct", imagePath, "--apply", invalidPath)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "not a valid detection")
+
+	err = runArgs(t, "correct", imagePath, "--apply", filepath.Join(dir, "missing.json"))
+	assert.Error(t, err)
+
+	image, err := os.ReadFile(imagePath)
+	require.NoError(t, err)
+	_, ok := savedCorrections(image)
+	assert.False(t, ok, "nothing was saved")
+}
@@ -0,0 +1,118 @@
+package cli
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"github.com/ayutaz/grimoire/internal/conformance"
+	"github.com/ayutaz/grimoire/internal/dataset"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/pkg/grimoire"
+	"github.com/spf13/cobra"
+)
+
+// datasetExtractCommand crops the symbols of every image in a directory
+// into labeled patches for training. An image labeled by hand, with a
+// LabelMe or COCO file of the same base name next to it, is cropped by its
+// labels instead of by what is detected.
+func datasetExtractCommand(cmd *cobra.Command, args []string) error {
+	dir := args[0]
+	outputDir, _ := cmd.Flags().GetString("out")
+	size, _ := cmd.Flags().GetInt("size")
+	margin, _ := cmd.Flags().GetFloat64("margin")
+	if outputDir == "" {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("dataset.no_out"))
+	}
+	if size < 1 {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("dataset.invalid_size", size))
+	}
+	if margin < 0 {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("dataset.invalid_margin", margin))
+	}
+
+	images, err := conformance.Discover(dir)
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("error.file_read_error")).
+			WithInnerError(err).
+			WithLocation(dir, 0, 0)
+	}
+	if len(images) == 0 {
+		fmt.Printf(i18n.T("batch.no_images"), dir)
+		return nil
+	}
+	if err := os.MkdirAll(outputDir, 0o755); err != nil {
+		return datasetWriteError(err, outputDir)
+	}
+
+	var records []dataset.Record
+	labels := make(map[string]bool)
+	failed := 0
+	for _, imagePath := range images {
+		name := filepath.Base(imagePath)
+		written, labeled, err := extractPatches(imagePath, outputDir, size, margin)
+		records = append(records, written...)
+		if err != nil {
+			failed++
+			fmt.Printf(i18n.T("batch.error"), name, err)
+			continue
+		}
+		origin := i18n.T("dataset.detected")
+		if labeled {
+			origin = i18n.T("dataset.labeled")
+		}
+		fmt.Printf(i18n.T("dataset.image"), name, len(written), origin)
+		for _, r := range written {
+			labels[r.Label] = true
+		}
+	}
+
+	if err := dataset.WriteManifest(outputDir, records); err != nil {
+		return datasetWriteError(err, filepath.Join(outputDir, dataset.ManifestFile))
+	}
+	fmt.Printf(i18n.T("dataset.summary"), len(records), len(labels), len(images)-failed, outputDir)
+	if failed > 0 {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("dataset.failed", failed))
+	}
+	return nil
+}
+
+// extractPatches writes the patches of the image at imagePath into
+// outputDir, reporting whether they were cut by hand-made labels
+func extractPatches(imagePath, outputDir string, size int, margin float64) ([]dataset.Record, bool, error) {
+	img, err := loadImage(imagePath)
+	if err != nil {
+		return nil, false, formatError(err, imagePath)
+	}
+
+	labelsPath := strings.TrimSuffix(imagePath, filepath.Ext(imagePath)) + ".json"
+	_, statErr := os.Stat(labelsPath)
+	labeled := statErr == nil
+	var symbols []*grimoire.Symbol
+	if labeled {
+		symbols, _, err = readAnnotations(labelsPath, imagePath)
+	} else {
+		symbols, _, err = detectSymbols(imagePath)
+		if err != nil {
+			err = formatError(err, imagePath)
+		}
+	}
+	if err != nil {
+		return nil, labeled, err
+	}
+
+	records, err := dataset.Write(outputDir, imagePath, dataset.Crop(img, symbols, size, margin))
+	if err != nil {
+		return records, labeled, datasetWriteError(err, outputDir)
+	}
+	return records, labeled, nil
+}
+
+// datasetWriteError returns the error for a dataset that cannot be written
+func datasetWriteError(err error, path string) error {
+	return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+		WithInnerError(err).
+		WithLocation(path, 0, 0)
+}
@@ -0,0 +1,75 @@
+package cli
+
+import (
+	"io"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestDatasetExtract(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	dir := t.TempDir()
+	outputDir := filepath.Join(t.TempDir(), "dataset")
+
+	// A drawing labeled by hand is cropped by its labels
+	writeBlankImage(t, filepath.Join(dir, "labeled.png"), 400, 400)
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "labeled.json"), []byte(helloWorldLabels), 0o644))
+
+	// Others are cropped by what is detected
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	detected := false
+	if data, err := os.ReadFile(imagePath); err == nil {
+		require.NoError(t, os.WriteFile(filepath.Join(dir, "hello_world.png"), data, 0o644))
+		detected = true
+	}
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+	err := runArgs(t, "dataset", "extract", dir, "--out", outputDir, "--size", "32")
+	w.Close()
+	os.Stdout = oldStdout
+	output, readErr := io.ReadAll(r)
+	require.NoError(t, readErr)
+	require.NoError(t, err)
+
+	assert.Contains(t, string(output), "labeled.png: 2 patches (labeled)")
+	manifest, err := os.ReadFile(filepath.Join(outputDir, "labels.csv"))
+	require.NoError(t, err)
+	assert.Contains(t, string(manifest), "double_circle/labeled_double_circle-")
+	assert.Contains(t, string(manifest), ",star,empty,labeled.png,")
+
+	stars, err := filepath.Glob(filepath.Join(outputDir, "star", "*.png"))
+	require.NoError(t, err)
+	if detected {
+		assert.Contains(t, string(output), "hello_world.png:")
+		assert.Contains(t, string(output), "(detected)")
+		assert.GreaterOrEqual(t, len(stars), 2)
+	} else {
+		assert.Len(t, stars, 1)
+	}
+	labeledStars, err := filepath.Glob(filepath.Join(outputDir, "star", "labeled_*.png"))
+	require.NoError(t, err)
+	assert.Len(t, labeledStars, 1)
+}
+
+func TestDatasetExtractFlags(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	dir := t.TempDir()
+	err := runArgs(t, "dataset", "extract", dir)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "--out")
+
+	err = runArgs(t, "dataset", "extract", dir, "--out", t.TempDir(), "--size", "0")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "--size")
+}
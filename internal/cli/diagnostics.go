@@ -0,0 +1,173 @@
+package cli
+
+import (
+	"fmt"
+	"image"
+	"image/png"
+	"io"
+	"os"
+	"path/filepath"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/pkg/grimoire"
+)
+
+// Diagnostic output formats for the --format flag
+const (
+	formatText = "text"
+	formatJSON = "json"
+)
+
+// thumbnailPadding is the margin, in pixels, kept around a region when cropping a thumbnail
+const thumbnailPadding = 16
+
+// diagnosticOptions controls how ReportError presents errors. It is set
+// from the global flags each time Execute runs.
+var diagnosticOptions struct {
+	format       string
+	thumbnailDir string
+}
+
+// ReportError writes err to w as diagnostics, in the format selected by the
+// --format flag of the last Execute call
+func ReportError(w io.Writer, err error) {
+	diags := grimoireErrors.Diagnostics(err)
+	if diagnosticOptions.thumbnailDir != "" {
+		writeThumbnails(diags, diagnosticOptions.thumbnailDir)
+	}
+
+	if diagnosticOptions.format == formatJSON {
+		_ = grimoireErrors.WriteDiagnosticsJSON(w, diags)
+		return
+	}
+
+	// Debug mode keeps the full error text, including context and stack traces
+	if grimoireErrors.IsDebugMode() {
+		if _, ok := err.(*grimoireErrors.EnhancedError); ok {
+			fmt.Fprintln(w, err)
+		} else {
+			fmt.Fprintf(w, i18n.T("error.error_prefix"), err)
+		}
+		return
+	}
+
+	color := useColor(w)
+	for i, d := range diags {
+		if i > 0 {
+			fmt.Fprintln(w)
+		}
+		d.Render(w, color)
+	}
+}
+
+// reportResolutions warns on w of each ambiguous symbol of the image at
+// imagePath that was read as one of its alternatives, in the format
+// selected by the --format flag
+func reportResolutions(w io.Writer, resolutions []grimoire.Resolution, imagePath string) {
+	if len(resolutions) == 0 {
+		return
+	}
+	warnings := make([]*grimoireErrors.GrimoireError, 0, len(resolutions))
+	for _, r := range resolutions {
+		warnings = append(warnings, r.Warning())
+	}
+	reportWarnings(w, warnings, imagePath)
+}
+
+// reportWarnings reports on w each warning about the image at imagePath,
+// in the format selected by the --format flag
+func reportWarnings(w io.Writer, warnings []*grimoireErrors.GrimoireError, imagePath string) {
+	if len(warnings) == 0 {
+		return
+	}
+	var diags []grimoireErrors.Diagnostic
+	for _, warning := range warnings {
+		for _, d := range grimoireErrors.Diagnostics(warning.WithLocation(imagePath, 0, 0)) {
+			d.Severity = grimoireErrors.SeverityWarning
+			diags = append(diags, d)
+		}
+	}
+
+	if diagnosticOptions.format == formatJSON {
+		_ = grimoireErrors.WriteDiagnosticsJSON(w, diags)
+		return
+	}
+	color := useColor(w)
+	for _, d := range diags {
+		d.Render(w, color)
+	}
+}
+
+// useColor reports whether w is a terminal that should receive ANSI colors
+func useColor(w io.Writer) bool {
+	if os.Getenv("NO_COLOR") != "" {
+		return false
+	}
+	file, ok := w.(*os.File)
+	if !ok {
+		return false
+	}
+	info, err := file.Stat()
+	return err == nil && info.Mode()&os.ModeCharDevice != 0
+}
+
+// writeThumbnails crops the region of each diagnostic out of its image and
+// records the written path. Thumbnails are best effort: diagnostics whose
+// image cannot be read are left without one.
+func writeThumbnails(diags []grimoireErrors.Diagnostic, dir string) {
+	if err := os.MkdirAll(dir, 0o755); err != nil {
+		return
+	}
+
+	images := make(map[string]image.Image)
+	for i := range diags {
+		d := &diags[i]
+		if d.Region == nil || d.File == "" {
+			continue
+		}
+
+		path := d.File
+		if path == stdinName {
+			path = stdinPath
+		}
+		img, ok := images[path]
+		if !ok {
+			img, _ = loadImage(path)
+			images[path] = img
+		}
+		if img == nil {
+			continue
+		}
+
+		thumbnail := filepath.Join(dir, fmt.Sprintf("%s-%d.png", d.Code, i+1))
+		if err := writeThumbnail(img, d.Region, thumbnail); err == nil {
+			d.Thumbnail = thumbnail
+		}
+	}
+}
+
+// writeThumbnail writes the padded region of img as a PNG
+func writeThumbnail(img image.Image, region *grimoireErrors.Region, path string) error {
+	rect := image.Rect(region.X, region.Y, region.X+region.Width, region.Y+region.Height).
+		Inset(-thumbnailPadding).
+		Add(img.Bounds().Min).
+		Intersect(img.Bounds())
+	if rect.Empty() {
+		return fmt.Errorf("region %v is outside the image", rect)
+	}
+
+	cropper, ok := img.(interface {
+		SubImage(r image.Rectangle) image.Image
+	})
+	if !ok {
+		return fmt.Errorf("image type %T cannot be cropped", img)
+	}
+
+	file, err := os.Create(path)
+	if err != nil {
+		return err
+	}
+	defer file.Close()
+	return png.Encode(file, cropper.SubImage(rect))
+}
@@ -0,0 +1,122 @@
+package cli
+
+import (
+	"bytes"
+	"encoding/json"
+	"image"
+	"image/color"
+	"image/png"
+	"os"
+	"path/filepath"
+	"testing"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// writeBlankImage writes a white PNG of the given size
+func writeBlankImage(t *testing.T, path string, width, height int) {
+	t.Helper()
+	img := image.NewRGBA(image.Rect(0, 0, width, height))
+	for y := 0; y < height; y++ {
+		for x := 0; x < width; x++ {
+			img.Set(x, y, color.White)
+		}
+	}
+	f, err := os.Create(path)
+	require.NoError(t, err)
+	defer f.Close()
+	require.NoError(t, png.Encode(f, img))
+}
+
+func TestReportError_JSONFormat(t *testing.T) {
+	imagePath := filepath.Join(t.TempDir(), "blank.png")
+	writeBlankImage(t, imagePath, 100, 100)
+
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "compile", imagePath, "--format", "json", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	err := Execute("test", "test", "test")
+	require.Error(t, err)
+
+	var buf bytes.Buffer
+	ReportError(&buf, err)
+
+	var decoded struct {
+		Diagnostics []grimoireErrors.Diagnostic `json:"diagnostics"`
+	}
+	require.NoError(t, json.Unmarshal(buf.Bytes(), &decoded), buf.String())
+	require.Len(t, decoded.Diagnostics, 1)
+	assert.Equal(t, grimoireErrors.SeverityError, decoded.Diagnostics[0].Severity)
+	assert.Equal(t, grimoireErrors.ErrCodeNoSymbolsDetected, decoded.Diagnostics[0].Code)
+	assert.Equal(t, imagePath, decoded.Diagnostics[0].File)
+	assert.NotEmpty(t, decoded.Diagnostics[0].Help)
+}
+
+func TestReportError_TextFormat(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+	defer grimoireErrors.SetDebugMode(grimoireErrors.IsDebugMode())
+	grimoireErrors.SetDebugMode(false)
+	diagnosticOptions.format = formatText
+
+	err := grimoireErrors.NewError(grimoireErrors.SyntaxError, "Parser encountered errors").WithRelated(
+		grimoireErrors.NewError(grimoireErrors.UnexpectedSymbol, "first"),
+		grimoireErrors.NewError(grimoireErrors.UnbalancedExpression, "second"),
+	)
+
+	var buf bytes.Buffer
+	ReportError(&buf, err)
+	output := buf.String()
+
+	// A buffer is not a terminal, so no colors are used
+	assert.NotContains(t, output, "\x1b[")
+	assert.Contains(t, output, "error[E3002]: first\n")
+	assert.Contains(t, output, "\nerror[E3005]: second\n")
+}
+
+func TestReportError_InvalidFormat(t *testing.T) {
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "symbols", "--format", "xml", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	err := Execute("test", "test", "test")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), `"xml"`)
+}
+
+func TestWriteThumbnails(t *testing.T) {
+	dir := t.TempDir()
+	imagePath := filepath.Join(dir, "circle.png")
+	writeBlankImage(t, imagePath, 200, 100)
+	thumbDir := filepath.Join(dir, "thumbs")
+
+	diags := []grimoireErrors.Diagnostic{
+		{Code: grimoireErrors.ErrCodeUnexpectedSymbol, File: imagePath,
+			Region: &grimoireErrors.Region{X: 180, Y: 40, Width: 40, Height: 40}},
+		{Code: grimoireErrors.ErrCodeNoOuterCircle, File: imagePath},
+		{Code: grimoireErrors.ErrCodeUnexpectedSymbol, File: filepath.Join(dir, "missing.png"),
+			Region: &grimoireErrors.Region{X: 0, Y: 0, Width: 10, Height: 10}},
+	}
+	writeThumbnails(diags, thumbDir)
+
+	expected := filepath.Join(thumbDir, "E3002-1.png")
+	assert.Equal(t, expected, diags[0].Thumbnail)
+	assert.Empty(t, diags[1].Thumbnail)
+	assert.Empty(t, diags[2].Thumbnail)
+
+	f, err := os.Open(expected)
+	require.NoError(t, err)
+	defer f.Close()
+	thumb, err := png.Decode(f)
+	require.NoError(t, err)
+
+	// The padded region is clipped to the image bounds
+	assert.Equal(t, 200-(180-thumbnailPadding), thumb.Bounds().Dx())
+	assert.Equal(t, 40+2*thumbnailPadding, thumb.Bounds().Dy())
+}
@@ -0,0 +1,122 @@
+package cli
+
+import (
+	"fmt"
+	"image/png"
+	"os"
+	"path/filepath"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/imagediff"
+	"github.com/spf13/cobra"
+)
+
+// diffMarkers prefix each change in the textual report
+var diffMarkers = map[imagediff.ChangeKind]string{
+	imagediff.Added:   "+",
+	imagediff.Removed: "-",
+	imagediff.Changed: "~",
+}
+
+// diffCommand reports the semantic differences between two magic circle images
+func diffCommand(cmd *cobra.Command, args []string) error {
+	oldPath, newPath := args[0], args[1]
+	overlayPath, _ := cmd.Flags().GetString("overlay")
+
+	oldSymbols, oldConnections, err := detectSymbols(oldPath)
+	if err != nil {
+		return formatError(err, oldPath)
+	}
+	newSymbols, newConnections, err := detectSymbols(newPath)
+	if err != nil {
+		return formatError(err, newPath)
+	}
+
+	result := imagediff.Compare(oldSymbols, oldConnections, newSymbols, newConnections)
+
+	fmt.Printf(i18n.T("diff.header"), filepath.Base(oldPath), filepath.Base(newPath))
+	if result.Empty() {
+		fmt.Println(i18n.T("diff.no_changes"))
+	} else {
+		printDiff(result)
+	}
+
+	if overlayPath != "" {
+		if err := writeOverlay(oldPath, newPath, overlayPath, result); err != nil {
+			return err
+		}
+		fmt.Printf(i18n.T("diff.overlay_written"), overlayPath)
+	}
+	return nil
+}
+
+// printDiff prints the symbol and connection changes
+func printDiff(result *imagediff.Result) {
+	fmt.Printf(i18n.T("diff.symbols_summary"),
+		result.Count(imagediff.Added), result.Count(imagediff.Removed), result.Count(imagediff.Changed))
+	for _, change := range result.Symbols {
+		switch change.Kind {
+		case imagediff.Changed:
+			fmt.Printf(i18n.T("diff.symbol_changed"), diffMarkers[change.Kind], change.New.Type,
+				change.New.Position.X, change.New.Position.Y, change.Old.Pattern, change.New.Pattern)
+		case imagediff.Removed:
+			printDiffSymbol(change.Kind, change.Old)
+		default:
+			printDiffSymbol(change.Kind, change.New)
+		}
+	}
+
+	if len(result.Connections) > 0 {
+		fmt.Println(i18n.T("diff.connections_header"))
+	}
+	for _, change := range result.Connections {
+		switch change.Kind {
+		case imagediff.Changed:
+			fmt.Printf(i18n.T("diff.connection_changed"), diffMarkers[change.Kind],
+				change.Old.From.Type, change.Old.To.Type, change.Old.ConnectionType, change.New.ConnectionType)
+		case imagediff.Removed:
+			printDiffConnection(change.Kind, change.Old)
+		default:
+			printDiffConnection(change.Kind, change.New)
+		}
+	}
+}
+
+// printDiffSymbol prints an added or removed symbol
+func printDiffSymbol(kind imagediff.ChangeKind, s *detector.Symbol) {
+	fmt.Printf(i18n.T("diff.symbol"), diffMarkers[kind], s.Type, s.Pattern, s.Position.X, s.Position.Y)
+}
+
+// printDiffConnection prints an added or removed connection
+func printDiffConnection(kind imagediff.ChangeKind, c *detector.Connection) {
+	fmt.Printf(i18n.T("diff.connection"), diffMarkers[kind], c.From.Type, c.To.Type, c.ConnectionType)
+}
+
+// writeOverlay renders the annotated overlay of both images as a PNG
+func writeOverlay(oldPath, newPath, overlayPath string, result *imagediff.Result) error {
+	oldImg, err := loadImage(oldPath)
+	if err != nil {
+		return formatError(err, oldPath)
+	}
+	newImg, err := loadImage(newPath)
+	if err != nil {
+		return formatError(err, newPath)
+	}
+
+	file, err := os.Create(overlayPath)
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+			WithInnerError(err).
+			WithLocation(overlayPath, 0, 0)
+	}
+	defer file.Close()
+
+	if err := png.Encode(file, imagediff.Overlay(oldImg, newImg, result)); err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+			WithInnerError(err).
+			WithLocation(overlayPath, 0, 0)
+	}
+	return nil
+}
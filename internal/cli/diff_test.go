@@ -0,0 +1,78 @@
+package cli
+
+import (
+	"bytes"
+	"image/png"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestDiffCommand(t *testing.T) {
+	examples := filepath.Join("..", "..", "examples", "images")
+	helloPath := filepath.Join(examples, "hello_world.png")
+	loopPath := filepath.Join(examples, "loop.png")
+	for _, path := range []string{helloPath, loopPath} {
+		if _, err := os.Stat(path); os.IsNotExist(err) {
+			t.Skip("Example image not found")
+		}
+	}
+
+	tests := []struct {
+		name     string
+		newPath  string
+		contains []string
+	}{
+		{
+			name:     "identical images",
+			newPath:  helloPath,
+			contains: []string{"Diff: hello_world.png -> hello_world.png", "No differences"},
+		},
+		{
+			name:     "different images",
+			newPath:  loopPath,
+			contains: []string{"Diff: hello_world.png -> loop.png", "Symbols:", "added"},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			overlayPath := filepath.Join(t.TempDir(), "diff.png")
+
+			oldArgs := os.Args
+			os.Args = []string{"grimoire", "diff", helloPath, tt.newPath, "--overlay", overlayPath, "--lang", "en"}
+			defer func() { os.Args = oldArgs }()
+			defer i18n.SetLanguage(i18n.GetLanguage())
+
+			oldStdout := os.Stdout
+			r, w, _ := os.Pipe()
+			os.Stdout = w
+
+			err := Execute("test", "test", "test")
+
+			w.Close()
+			os.Stdout = oldStdout
+
+			var buf bytes.Buffer
+			_, readErr := buf.ReadFrom(r)
+			require.NoError(t, readErr)
+			output := buf.String()
+
+			require.NoError(t, err)
+			for _, s := range tt.contains {
+				assert.Contains(t, output, s)
+			}
+			assert.Contains(t, output, "Overlay written to "+overlayPath)
+
+			file, err := os.Open(overlayPath)
+			require.NoError(t, err)
+			defer file.Close()
+			_, err = png.Decode(file)
+			assert.NoError(t, err)
+		})
+	}
+}
@@ -0,0 +1,72 @@
+package cli
+
+import (
+	"fmt"
+	"os"
+
+	"github.com/ayutaz/grimoire/examples"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/spf13/cobra"
+)
+
+// examplesListCommand prints the name and description of every example
+func examplesListCommand(_ *cobra.Command, _ []string) error {
+	fmt.Println(i18n.T("examples.header"))
+	for _, example := range examples.Gallery {
+		fmt.Printf(i18n.T("examples.entry"), example.Name, i18n.T("examples.description."+example.Name))
+	}
+	fmt.Println(i18n.T("examples.run_hint"))
+	return nil
+}
+
+// examplesRunCommand compiles and runs an example from the gallery
+func examplesRunCommand(_ *cobra.Command, args []string) error {
+	example, ok := examples.Lookup(args[0])
+	if !ok {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("cli.unknown_example", args[0])).
+			WithSuggestion(i18n.T("suggest.list_examples"))
+	}
+
+	imagePath, err := writeExampleImage(example)
+	if err != nil {
+		return err
+	}
+	defer os.Remove(imagePath)
+
+	code, err := processImage(imagePath)
+	if err != nil {
+		return formatError(err, example.Image)
+	}
+
+	if err := executePython(code); err != nil {
+		return grimoireErrors.NewError(grimoireErrors.ExecutionError, i18n.T("msg.failed_execute_python")).
+			WithInnerError(err).
+			WithSuggestion(i18n.T("suggest.check_python_installed"))
+	}
+	return nil
+}
+
+// writeExampleImage writes the image of example to a temporary file, since
+// detection reads images from disk, and returns its path
+func writeExampleImage(example examples.Example) (string, error) {
+	data, err := example.ImageData()
+	if err != nil {
+		return "", grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("error.file_read_error")).
+			WithInnerError(err)
+	}
+
+	tmpFile, err := os.CreateTemp("", "grimoire_example_*.png")
+	if err != nil {
+		return "", grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+			WithInnerError(err)
+	}
+	defer tmpFile.Close()
+
+	if _, err := tmpFile.Write(data); err != nil {
+		os.Remove(tmpFile.Name())
+		return "", grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+			WithInnerError(err)
+	}
+	return tmpFile.Name(), nil
+}
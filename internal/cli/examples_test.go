@@ -0,0 +1,77 @@
+package cli
+
+import (
+	"bytes"
+	"os"
+	"testing"
+
+	"github.com/ayutaz/grimoire/examples"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestExamplesList(t *testing.T) {
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "examples", "list", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err := Execute("test", "test", "test")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	var buf bytes.Buffer
+	_, readErr := buf.ReadFrom(r)
+	require.NoError(t, readErr)
+	output := buf.String()
+
+	require.NoError(t, err)
+	for _, example := range examples.Gallery {
+		assert.Contains(t, output, example.Name)
+	}
+	assert.Contains(t, output, "The Fibonacci sequence")
+}
+
+// TestExamplesDescribed ensures every example has a description in both languages
+func TestExamplesDescribed(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	for _, lang := range []i18n.Language{i18n.English, i18n.Japanese} {
+		i18n.SetLanguage(lang)
+		for _, example := range examples.Gallery {
+			key := "examples.description." + example.Name
+			assert.NotEqual(t, key, i18n.T(key), "missing description for %s", example.Name)
+		}
+	}
+}
+
+func TestExamplesRunUnknown(t *testing.T) {
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "examples", "run", "fizzbuzz", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	err := Execute("test", "test", "test")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), `Unknown example "fizzbuzz"`)
+}
+
+func TestWriteExampleImage(t *testing.T) {
+	example, ok := examples.Lookup("hello-world")
+	require.True(t, ok)
+
+	imagePath, err := writeExampleImage(example)
+	require.NoError(t, err)
+	defer os.Remove(imagePath)
+
+	// The written image compiles like the one in the repository
+	code, err := processImage(imagePath)
+	require.NoError(t, err)
+	assert.Contains(t, code, "# Generated by Grimoire")
+}
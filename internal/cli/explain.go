@@ -0,0 +1,115 @@
+package cli
+
+import (
+	"fmt"
+	"path/filepath"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/ayutaz/grimoire/internal/render"
+	"github.com/ayutaz/grimoire/pkg/grimoire"
+	"github.com/spf13/cobra"
+)
+
+// numberedRoles are the roles whose descriptions include their ordinal
+var numberedRoles = map[parser.RoleKind]bool{
+	parser.RoleFunction:     true,
+	parser.RoleOutput:       true,
+	parser.RoleIfBranch:     true,
+	parser.RoleLoop:         true,
+	parser.RoleParallel:     true,
+	parser.RoleBlock:        true,
+	parser.RoleStage:        true,
+	parser.RoleAssignment:   true,
+	parser.RoleOperator:     true,
+	parser.RoleAssertion:    true,
+	parser.RoleFunctionCall: true,
+}
+
+// explainCommand lists every detected symbol with the role the parser
+// assigned to it. With --annotate, the roles are also written below the
+// symbols on a copy of the image.
+func explainCommand(cmd *cobra.Command, args []string) error {
+	imagePath := args[0]
+	annotatePath, _ := cmd.Flags().GetString("annotate")
+
+	symbols, connections, err := detectSymbols(imagePath)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+
+	p := parser.NewParser()
+	_, parseErr := p.Parse(symbols, connections)
+
+	fmt.Printf(i18n.T("explain.header"), filepath.Base(imagePath))
+	for _, role := range p.Roles() {
+		fmt.Printf(i18n.T("explain.symbol_info"),
+			role.Index, role.Symbol.Type, role.Symbol.Position.X, role.Symbol.Position.Y, describeRole(role))
+	}
+	if annotatePath != "" {
+		if err := writeAnnotatedRoles(imagePath, symbols, p.Roles(), annotatePath); err != nil {
+			return err
+		}
+	}
+
+	if parseErr != nil {
+		fmt.Print(i18n.T("explain.parse_failed"))
+		return formatError(parseErr, imagePath)
+	}
+
+	return nil
+}
+
+// describeRole returns a human-readable description of a symbol role,
+// including the statement it belongs to
+func describeRole(role parser.SymbolRole) string {
+	desc := describeRoleKind(role)
+	if role.Port != "" {
+		desc = i18n.Tf("explain.operand", desc, i18n.T("explain.port."+role.Port))
+	}
+	if role.Context != nil {
+		desc = i18n.Tf("explain.in_context", desc, describeRoleKind(*role.Context))
+	}
+	return desc
+}
+
+// describeRoleKind describes a role without its enclosing context
+func describeRoleKind(role parser.SymbolRole) string {
+	key := "explain.role." + string(role.Kind)
+
+	if role.Kind == parser.RoleLiteral {
+		var value interface{} = "?"
+		if lit, ok := role.Node.(*parser.Literal); ok {
+			value = lit.Value
+			if s, isString := value.(string); isString {
+				value = fmt.Sprintf("%q", s)
+			}
+		}
+		return i18n.Tf(key, value)
+	}
+
+	if numberedRoles[role.Kind] {
+		return i18n.Tf(key, role.Ordinal)
+	}
+	return i18n.T(key)
+}
+
+// writeAnnotatedRoles writes a copy of the image at imagePath to path with
+// the number and role of each symbol written below it
+func writeAnnotatedRoles(imagePath string, symbols []*grimoire.Symbol, roles []parser.SymbolRole, path string) error {
+	img, err := loadImage(imagePath)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+	labels := make([]string, len(symbols))
+	for _, role := range roles {
+		if role.Index >= 0 && role.Index < len(labels) {
+			labels[role.Index] = fmt.Sprintf("[%d] %s", role.Index, describeRoleKind(role))
+		}
+	}
+	if err := writeImage(path, render.Annotate(img, symbols, labels)); err != nil {
+		return err
+	}
+	fmt.Printf(i18n.T("explain.annotated"), path)
+	return nil
+}
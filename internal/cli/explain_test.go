@@ -0,0 +1,107 @@
+package cli
+
+import (
+	"bytes"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestExplainCommand(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "explain", imagePath, "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err := Execute("test", "test", "test")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	var buf bytes.Buffer
+	_, readErr := buf.ReadFrom(r)
+	require.NoError(t, readErr)
+	output := buf.String()
+
+	require.NoError(t, err)
+	assert.Contains(t, output, "Interpretation of hello_world.png")
+	assert.Contains(t, output, "program boundary")
+	assert.Contains(t, output, "output #")
+}
+
+func TestExplainCommandAnnotate(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+	annotatedPath := filepath.Join(t.TempDir(), "annotated.png")
+
+	output := executeCapturingStdout(t, "explain", imagePath, "--annotate", annotatedPath)
+	assert.Contains(t, output, "Annotated image written to "+annotatedPath)
+
+	original, err := loadImage(imagePath)
+	require.NoError(t, err)
+	annotated, err := loadImage(annotatedPath)
+	require.NoError(t, err)
+	assert.Equal(t, original.Bounds().Size(), annotated.Bounds().Size())
+}
+
+func TestDescribeRole(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	branch := parser.SymbolRole{Kind: parser.RoleIfBranch, Ordinal: 2}
+
+	tests := []struct {
+		name     string
+		role     parser.SymbolRole
+		expected string
+	}{
+		{
+			name:     "numbered role",
+			role:     branch,
+			expected: "if-branch #2",
+		},
+		{
+			name:     "role with context",
+			role:     parser.SymbolRole{Kind: parser.RoleCondition, Ordinal: 1, Context: &branch},
+			expected: "condition of if-branch #2",
+		},
+		{
+			name:     "string literal",
+			role:     parser.SymbolRole{Kind: parser.RoleLiteral, Node: &parser.Literal{Value: "Text", LiteralType: parser.String}},
+			expected: `literal "Text"`,
+		},
+		{
+			name: "operand",
+			role: parser.SymbolRole{Kind: parser.RoleLiteral, Port: parser.PortRight,
+				Node: &parser.Literal{Value: 2, LiteralType: parser.Integer}},
+			expected: "literal 2 (right operand)",
+		},
+		{
+			name:     "unused symbol",
+			role:     parser.SymbolRole{Kind: parser.RoleUnused},
+			expected: "not used by the program",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			assert.Equal(t, tt.expected, describeRole(tt.role))
+		})
+	}
+}
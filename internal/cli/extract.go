@@ -0,0 +1,135 @@
+package cli
+
+import (
+	"bytes"
+	"fmt"
+	"image"
+	"image/png"
+	"os"
+	"strconv"
+	"strings"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/ayutaz/grimoire/internal/render"
+	"github.com/spf13/cobra"
+)
+
+// extractCommand lifts the statements drawn in a region of an image into a
+// function of their own, drawn as a library image, and writes the program
+// rewritten to call it as AST JSON. As with fmt, the library image is
+// detected again and only written when it reads as the statements lifted
+// out. Linking the library image names the function after its file, as
+// the call expects.
+func extractCommand(cmd *cobra.Command, args []string) error {
+	imagePath := args[0]
+	regionFlag, _ := cmd.Flags().GetString("region")
+	libraryPath, _ := cmd.Flags().GetString("out")
+	outputPath, _ := cmd.Flags().GetString("output")
+
+	region, err := parseRegion(regionFlag)
+	if err != nil {
+		return err
+	}
+	if libraryPath == "" {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("extract.no_out"))
+	}
+
+	symbols, connections, err := detectSymbols(imagePath)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+	p := parser.NewParser().WithStrictness(strictness)
+	program, err := p.Parse(symbols, connections)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+	extraction, err := parser.Extract(program, p.Roles(), connections, region, libraryPath)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+
+	// Draw the function and make sure it reads back as the statements
+	opts := render.DefaultOptions()
+	laidOut, straightened := render.Canonical(extraction.Symbols, extraction.Connections, opts)
+	img := render.Render(laidOut, straightened, opts)
+	expected, err := programJSON(extraction.Symbols, extraction.Connections)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+	backend, err := newBackend()
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+	drawnSymbols, drawnConnections, err := backend.DetectImage(img)
+	var drawn []byte
+	if err == nil {
+		drawn, err = programJSON(drawnSymbols, drawnConnections)
+	}
+	if err != nil || !bytes.Equal(expected, drawn) {
+		extractErr := grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("extract.redraw_failed")).
+			WithSuggestion(i18n.T("extract.redraw_failed_suggestion")).
+			WithLocation(imagePath, 0, 0)
+		if err != nil {
+			extractErr = extractErr.WithInnerError(err)
+		}
+		return extractErr
+	}
+
+	rewritten, err := parser.EncodeJSON(program)
+	if err != nil {
+		return err
+	}
+	if err := writeImage(libraryPath, img); err != nil {
+		return err
+	}
+	if outputPath == "" {
+		fmt.Println(string(rewritten))
+	} else if err := os.WriteFile(outputPath, append(rewritten, '\n'), 0o644); err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+			WithInnerError(err).
+			WithLocation(outputPath, 0, 0)
+	}
+
+	fmt.Fprintf(os.Stderr, i18n.T("extract.written"), len(extraction.Statements), extraction.Name, libraryPath)
+	return nil
+}
+
+// parseRegion parses a region given as x,y,width,height in image pixels
+func parseRegion(value string) (image.Rectangle, error) {
+	invalid := grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("extract.invalid_region", value))
+	fields := strings.Split(value, ",")
+	if len(fields) != 4 {
+		return image.Rectangle{}, invalid
+	}
+	var numbers [4]int
+	for i, field := range fields {
+		n, err := strconv.Atoi(strings.TrimSpace(field))
+		if err != nil {
+			return image.Rectangle{}, invalid
+		}
+		numbers[i] = n
+	}
+	if numbers[2] <= 0 || numbers[3] <= 0 {
+		return image.Rectangle{}, invalid
+	}
+	return image.Rect(numbers[0], numbers[1], numbers[0]+numbers[2], numbers[1]+numbers[3]), nil
+}
+
+// writeImage writes img to path as a PNG
+func writeImage(path string, img image.Image) error {
+	file, err := os.Create(path)
+	if err == nil {
+		err = png.Encode(file, img)
+		if closeErr := file.Close(); err == nil {
+			err = closeErr
+		}
+	}
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+			WithInnerError(err).
+			WithLocation(path, 0, 0)
+	}
+	return nil
+}
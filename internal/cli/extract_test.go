@@ -0,0 +1,33 @@
+package cli
+
+import (
+	"image"
+	"os"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseRegion(t *testing.T) {
+	region, err := parseRegion("10, 20,300,400")
+	require.NoError(t, err)
+	assert.Equal(t, image.Rect(10, 20, 310, 420), region)
+
+	for _, value := range []string{"", "10,20,300", "10,20,0,400", "a,20,300,400", "10,20,300,400,5"} {
+		_, err := parseRegion(value)
+		assert.Error(t, err, value)
+	}
+}
+
+func TestExtractInvalidRegion(t *testing.T) {
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "extract", "circle.png", "--region", "10,20", "--out", "helper.png", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	err := Execute("test", "test", "test")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), `Invalid region "10,20"`)
+}
@@ -0,0 +1,121 @@
+package cli
+
+import (
+	"bytes"
+	"fmt"
+	"image/png"
+	"os"
+	"path/filepath"
+	"strings"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/ayutaz/grimoire/internal/render"
+	"github.com/ayutaz/grimoire/pkg/grimoire"
+	"github.com/spf13/cobra"
+)
+
+// fmtCommand redraws a magic circle in the canonical layout. The redrawn
+// circle is detected again and only written when it parses to the same
+// program as the original. An output ending in .svg is written as SVG,
+// with the AST node of each symbol in its data attributes.
+func fmtCommand(cmd *cobra.Command, args []string) error {
+	imagePath := args[0]
+	outputPath, _ := cmd.Flags().GetString("output")
+	if outputPath == "" {
+		outputPath = formattedPath(imagePath)
+	}
+
+	symbols, connections, err := detectSymbols(imagePath)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+	original, err := programJSON(symbols, connections)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+
+	opts := render.DefaultOptions()
+	laidOut, straightened := render.Canonical(symbols, connections, opts)
+	img := render.Render(laidOut, straightened, opts)
+
+	backend, err := newBackend()
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+	formattedSymbols, formattedConnections, err := backend.DetectImage(img)
+	var formatted []byte
+	if err == nil {
+		formatted, err = programJSON(formattedSymbols, formattedConnections)
+	}
+	if err != nil || !bytes.Equal(original, formatted) {
+		fmtErr := grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("fmt.semantics_changed")).
+			WithSuggestion(i18n.T("fmt.semantics_changed_suggestion")).
+			WithLocation(imagePath, 0, 0)
+		if err != nil {
+			fmtErr = fmtErr.WithInnerError(err)
+		}
+		return fmtErr
+	}
+
+	file, err := os.Create(outputPath)
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+			WithInnerError(err).
+			WithLocation(outputPath, 0, 0)
+	}
+	defer file.Close()
+	if strings.EqualFold(filepath.Ext(outputPath), ".svg") {
+		// Canonical keeps the order of the symbols
+		original := nodeNames(symbols, connections)
+		nodes := make(map[*grimoire.Symbol]string, len(original))
+		for i, s := range symbols {
+			nodes[laidOut[i]] = original[s]
+		}
+		err = render.SVG(file, laidOut, straightened, nodes, opts)
+	} else {
+		err = png.Encode(file, img)
+	}
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+			WithInnerError(err).
+			WithLocation(outputPath, 0, 0)
+	}
+
+	fmt.Printf(i18n.T("fmt.written"), outputPath)
+	return nil
+}
+
+// formattedPath returns where fmt writes the formatted image of imagePath
+// when no output is given: next to it, with .formatted before the extension
+func formattedPath(imagePath string) string {
+	if imagePath == stdinPath {
+		return "formatted.png"
+	}
+	return strings.TrimSuffix(imagePath, filepath.Ext(imagePath)) + ".formatted.png"
+}
+
+// nodeNames returns the name of the AST node built from each symbol: its
+// role and the number grimoire explain gives it, such as assignment-2
+func nodeNames(symbols []*grimoire.Symbol, connections []grimoire.Connection) map[*grimoire.Symbol]string {
+	p := parser.NewParser()
+	_, _ = p.Parse(symbols, connections)
+	names := make(map[*grimoire.Symbol]string)
+	for _, role := range p.Roles() {
+		if role.Node != nil {
+			names[role.Symbol] = fmt.Sprintf("%s-%d", role.Kind, role.Ordinal)
+		}
+	}
+	return names
+}
+
+// programJSON parses symbols and connections and encodes the program, so
+// that two drawings can be compared by what they mean
+func programJSON(symbols []*grimoire.Symbol, connections []grimoire.Connection) ([]byte, error) {
+	program, err := parser.Parse(symbols, connections)
+	if err != nil {
+		return nil, err
+	}
+	return parser.EncodeJSON(program)
+}
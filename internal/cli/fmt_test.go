@@ -0,0 +1,85 @@
+package cli
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/spf13/cobra"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestFmtCommand(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+	outputPath := filepath.Join(t.TempDir(), "formatted.png")
+
+	cmd := &cobra.Command{}
+	cmd.Flags().StringP("output", "o", "", "")
+	require.NoError(t, cmd.Flags().Set("output", outputPath))
+
+	oldStdout := os.Stdout
+	_, w, _ := os.Pipe()
+	os.Stdout = w
+	err := fmtCommand(cmd, []string{imagePath})
+	w.Close()
+	os.Stdout = oldStdout
+
+	// The redrawn circle reads as the same program
+	require.NoError(t, err)
+	require.FileExists(t, outputPath)
+	original, err := detectAndEncode(imagePath)
+	require.NoError(t, err)
+	formatted, err := detectAndEncode(outputPath)
+	require.NoError(t, err)
+	assert.JSONEq(t, string(original), string(formatted))
+}
+
+// detectAndEncode detects the image at path and encodes its program
+func detectAndEncode(path string) ([]byte, error) {
+	symbols, connections, err := detectSymbols(path)
+	if err != nil {
+		return nil, err
+	}
+	return programJSON(symbols, connections)
+}
+
+func TestFmtCommand_SVG(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+	outputPath := filepath.Join(t.TempDir(), "formatted.svg")
+
+	cmd := &cobra.Command{}
+	cmd.Flags().StringP("output", "o", "", "")
+	require.NoError(t, cmd.Flags().Set("output", outputPath))
+
+	oldStdout := os.Stdout
+	_, w, _ := os.Pipe()
+	os.Stdout = w
+	err := fmtCommand(cmd, []string{imagePath})
+	w.Close()
+	os.Stdout = oldStdout
+	require.NoError(t, err, "the hello world example formats cleanly")
+
+	data, err := os.ReadFile(outputPath)
+	require.NoError(t, err)
+	assert.Contains(t, string(data), "<svg")
+	assert.Contains(t, string(data), `data-type="outer_circle"`)
+	assert.Contains(t, string(data), `data-node="main_entry-1"`)
+}
+
+func TestFmtCommand_MissingImage(t *testing.T) {
+	cmd := &cobra.Command{}
+	cmd.Flags().StringP("output", "o", "", "")
+	assert.Error(t, fmtCommand(cmd, []string{"/nonexistent/file.png"}))
+}
+
+func TestFormattedPath(t *testing.T) {
+	assert.Equal(t, filepath.Join("spells", "hello.formatted.png"), formattedPath(filepath.Join("spells", "hello.png")))
+	assert.Equal(t, "formatted.png", formattedPath(stdinPath))
+}
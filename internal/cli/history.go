@@ -0,0 +1,133 @@
+package cli
+
+import (
+	"errors"
+	"fmt"
+	"os"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/history"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/ayutaz/grimoire/pkg/grimoire"
+	"github.com/spf13/cobra"
+)
+
+// Commands recorded in the history
+const (
+	historyRun     = "run"
+	historyCompile = "compile"
+)
+
+// recordHistory records a run or compilation of the image at imagePath when
+// the user opted in to history. program is nil when the image was not
+// parsed, and err is why the run failed. Failing to record never fails the
+// command.
+func recordHistory(command, imagePath, target string, program *grimoire.Program, code string, err error) {
+	if !history.Enabled() {
+		return
+	}
+	dir, dirErr := history.DefaultDir()
+	if dirErr != nil {
+		return
+	}
+
+	entry := &history.Entry{
+		Command: command,
+		Image:   imagePath,
+		Target:  target,
+		Outcome: history.OutcomeOK,
+		Code:    code,
+	}
+	if source, readErr := readSource(imagePath); readErr == nil {
+		entry.ImageHash = history.Hash(source)
+	}
+	if program != nil {
+		if data, encodeErr := parser.EncodeJSON(program); encodeErr == nil {
+			entry.ASTHash = history.Hash(data)
+		}
+	}
+	if err != nil {
+		entry.Outcome = history.OutcomeFailed
+		entry.Error = err.Error()
+	}
+	_ = history.New(dir).Record(entry)
+}
+
+// openHistory returns the history in the default directory
+func openHistory() (*history.History, error) {
+	dir, err := history.DefaultDir()
+	if err != nil {
+		return nil, grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("history.no_directory")).
+			WithInnerError(err).
+			WithSuggestion(i18n.Tf("history.no_directory_suggestion", history.DirEnv))
+	}
+	return history.New(dir), nil
+}
+
+// historyListCommand prints every recorded run, oldest first
+func historyListCommand(_ *cobra.Command, _ []string) error {
+	h, err := openHistory()
+	if err != nil {
+		return err
+	}
+	entries, err := h.List()
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("error.file_read_error")).
+			WithInnerError(err).
+			WithLocation(h.Dir, 0, 0)
+	}
+	if len(entries) == 0 {
+		fmt.Printf(i18n.T("history.empty"), history.EnableEnv)
+		return nil
+	}
+
+	fmt.Println(i18n.T("history.header"))
+	for _, entry := range entries {
+		fmt.Printf(i18n.T("history.entry"), entry.ID, entry.Time.Local().Format("2006-01-02 15:04:05"),
+			entry.Command, entry.Target, entry.Outcome, entry.Image)
+	}
+	return nil
+}
+
+// historyReplayCommand runs the program recorded for a run again, or prints
+// the program recorded for a compilation, exactly as it was generated
+// without reading the image again
+func historyReplayCommand(cmd *cobra.Command, args []string) error {
+	readCapabilityFlags(cmd)
+	h, err := openHistory()
+	if err != nil {
+		return err
+	}
+	entry, err := h.Get(args[0])
+	if errors.Is(err, history.ErrNotFound) {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("history.not_found", args[0])).
+			WithSuggestion(i18n.T("history.not_found_suggestion"))
+	}
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, err.Error())
+	}
+	if entry.Code == "" {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("history.no_code", entry.ID)).
+			WithDetails(entry.Error)
+	}
+
+	// The program is replayed as recorded, but the user is told when the
+	// image it came from has changed since
+	if entry.Image != stdinPath && entry.ImageHash != "" {
+		if source, err := os.ReadFile(entry.Image); err == nil && history.Hash(source) != entry.ImageHash {
+			fmt.Fprintf(os.Stderr, i18n.T("history.image_changed"), entry.Image)
+		}
+	}
+
+	if entry.Command != historyRun {
+		fmt.Print(entry.Code)
+		return nil
+	}
+	if err := executePython(entry.Code); err != nil {
+		return grimoireErrors.NewError(grimoireErrors.ExecutionError, i18n.T("msg.failed_execute_python")).
+			WithInnerError(err).
+			WithSuggestion(i18n.T("suggest.check_python_installed"))
+	}
+	return nil
+}
@@ -0,0 +1,62 @@
+package cli
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/history"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestHistoryCommands(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+	dir := t.TempDir()
+	t.Setenv(history.DirEnv, dir)
+
+	// Nothing is recorded until the user opts in
+	t.Setenv(history.EnableEnv, "")
+	executeCapturingStdout(t, "compile", imagePath)
+	output := executeCapturingStdout(t, "history", "list")
+	assert.Contains(t, output, "No runs are recorded; set GRIMOIRE_HISTORY=1")
+
+	t.Setenv(history.EnableEnv, "1")
+	code := executeCapturingStdout(t, "compile", imagePath)
+	blankPath := filepath.Join(t.TempDir(), "blank.png")
+	writeBlankImage(t, blankPath, 100, 100)
+	assert.Error(t, runArgs(t, "compile", blankPath))
+
+	entries, err := history.New(dir).List()
+	require.NoError(t, err)
+	require.Len(t, entries, 2)
+	compiled, failed := entries[0], entries[1]
+	assert.Equal(t, historyCompile, compiled.Command)
+	assert.Equal(t, emitPython, compiled.Target)
+	assert.Equal(t, history.OutcomeOK, compiled.Outcome)
+	assert.NotEmpty(t, compiled.ImageHash)
+	assert.NotEmpty(t, compiled.ASTHash)
+	assert.Equal(t, code, compiled.Code)
+	assert.Equal(t, history.OutcomeFailed, failed.Outcome)
+	assert.NotEmpty(t, failed.Error)
+	assert.Empty(t, failed.ASTHash)
+
+	output = executeCapturingStdout(t, "history", "list")
+	assert.Contains(t, output, compiled.ID)
+	assert.Contains(t, output, failed.ID)
+	assert.Contains(t, output, "hello_world.png")
+
+	// Replaying a compilation prints the program exactly as it was generated
+	output = executeCapturingStdout(t, "history", "replay", compiled.ID)
+	assert.Equal(t, code, output)
+
+	err = runArgs(t, "history", "replay", failed.ID)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "nothing to replay")
+	err = runArgs(t, "history", "replay", "zzzzzzzz")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "No recorded run has the ID")
+}
@@ -0,0 +1,185 @@
+package cli
+
+import (
+	"bytes"
+	"image"
+	"io"
+	"os"
+	"path/filepath"
+	"strings"
+	"time"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/security"
+	"github.com/ayutaz/grimoire/internal/server"
+	"github.com/ayutaz/grimoire/pkg/grimoire"
+)
+
+// stdinPath is the image argument that reads the image from standard input,
+// e.g. `cat circle.png | grimoire compile -`
+const stdinPath = "-"
+
+// strokeExtension marks files holding a drawing recorded as pen strokes
+// rather than an image
+const strokeExtension = ".json"
+
+// stdinName is used in place of a file name in error locations
+const stdinName = "<stdin>"
+
+var (
+	// stdin is the reader used for the "-" image argument (replaced in tests)
+	stdin io.Reader = os.Stdin
+	// stdinData caches the image read from stdin so commands that detect
+	// more than once (optimize) see the same data each time
+	stdinData []byte
+	// detectorBackend names the detection backend selected with --backend
+	detectorBackend = grimoire.BackendSequential
+	// allowHugeImages lifts the image size limits, set with --allow-huge
+	allowHugeImages bool
+	// minConfidence drops symbols scoring below it, set with --min-confidence
+	minConfidence float64
+	// preprocessing replaces the built-in preprocessing, set with --preprocess
+	preprocessing []grimoire.PreprocessStep
+	// inkCount separates the image into that many inks detected apart, set
+	// with --inks; zero detects the image as it is
+	inkCount int
+	// showTimings prints the metrics of each detection, set with --timings
+	showTimings bool
+	// detectionTimeout bounds how long each detection may take, set with
+	// --detect-timeout; zero leaves it unbounded
+	detectionTimeout time.Duration
+	// maxContours and maxContourPoints reject images too complex to detect,
+	// set with --max-contours and --max-contour-points; zero is no limit
+	maxContours      int
+	maxContourPoints int
+	// remoteDetection detects through the daemon instead of in this
+	// process, set with --remote
+	remoteDetection bool
+	// strictness decides what becomes of shapes that match no symbol, set
+	// with --strict or --permissive
+	strictness = grimoire.Lenient
+)
+
+// newBackend creates the detection backend selected by the global flags
+func newBackend() (grimoire.Backend, error) {
+	if remoteDetection {
+		return server.NewClient(server.DefaultSocket()), nil
+	}
+	cfg := detectorConfig()
+	if showTimings {
+		cfg.Metrics = printMetrics
+	}
+	return grimoire.NewBackendConfig(detectorBackend, cfg)
+}
+
+// detectorConfig returns the detector configuration set by the global flags
+func detectorConfig() grimoire.DetectorConfig {
+	return grimoire.DetectorConfig{
+		AllowHugeImages:  allowHugeImages,
+		MinConfidence:    minConfidence,
+		Preprocessing:    preprocessing,
+		Strictness:       strictness,
+		Timeout:          detectionTimeout,
+		MaxContours:      maxContours,
+		MaxContourPoints: maxContourPoints,
+	}
+}
+
+// preprocessingChain returns the preprocessing chain set with --preprocess,
+// or "" for the built-in preprocessing
+func preprocessingChain() string {
+	steps := make([]string, 0, len(preprocessing))
+	for _, step := range preprocessing {
+		steps = append(steps, step.String())
+	}
+	return strings.Join(steps, ", ")
+}
+
+// detectSymbols detects symbols in the image at imagePath, reading the
+// image from stdin when imagePath is "-"
+func detectSymbols(imagePath string) ([]*grimoire.Symbol, []grimoire.Connection, error) {
+	// Corrections saved with grimoire correct replace detection
+	if symbols, connections, ok := correctedSymbols(imagePath); ok {
+		return symbols, connections, nil
+	}
+	if strings.EqualFold(filepath.Ext(imagePath), strokeExtension) {
+		return detectStrokes(imagePath)
+	}
+	backend, err := newBackend()
+	if err != nil {
+		return nil, nil, err
+	}
+	if inkCount > 0 {
+		img, err := loadImage(imagePath)
+		if err != nil {
+			return nil, nil, err
+		}
+		source := imagePath
+		if imagePath == stdinPath {
+			source = stdinName
+		}
+		return grimoire.DetectInks(backend, img, inkCount, source)
+	}
+	if imagePath != stdinPath {
+		return backend.Detect(imagePath)
+	}
+
+	if err := readStdin(); err != nil {
+		return nil, nil, err
+	}
+	return backend.DetectFromReader(bytes.NewReader(stdinData), stdinName)
+}
+
+// detectStrokes detects the drawing recorded as pen strokes in the file at
+// path
+func detectStrokes(path string) ([]*grimoire.Symbol, []grimoire.Connection, error) {
+	file, err := os.Open(path)
+	if err != nil {
+		if os.IsNotExist(err) {
+			return nil, nil, grimoireErrors.FileNotFoundError(path)
+		}
+		return nil, nil, grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("error.file_read_error")).
+			WithInnerError(err).
+			WithLocation(path, 0, 0)
+	}
+	defer file.Close()
+
+	drawing, err := grimoire.DecodeStrokes(file)
+	if err != nil {
+		return nil, nil, err
+	}
+	return grimoire.DetectStrokes(drawing, detectorConfig(), path)
+}
+
+// loadImage decodes the image at imagePath with the same security checks as
+// detection, reading from stdin when imagePath is "-"
+func loadImage(imagePath string) (image.Image, error) {
+	validator := security.NewImageValidator()
+	validator.AllowHuge = allowHugeImages
+	decoder := security.NewSafeImageDecoder(validator)
+	if imagePath != stdinPath {
+		return decoder.DecodeImage(imagePath)
+	}
+
+	if err := readStdin(); err != nil {
+		return nil, err
+	}
+	return decoder.DecodeReader(bytes.NewReader(stdinData))
+}
+
+// readStdin reads the image from stdin once and caches it in stdinData
+func readStdin() error {
+	if stdinData != nil {
+		return nil
+	}
+
+	// Read one byte past the size limit so the decoder can still reject oversized input
+	limit := security.NewImageValidator().MaxFileSize + 1
+	data, err := io.ReadAll(io.LimitReader(stdin, limit))
+	if err != nil {
+		return err
+	}
+	stdinData = data
+	return nil
+}
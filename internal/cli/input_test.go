@@ -0,0 +1,232 @@
+package cli
+
+import (
+	"bytes"
+	"os"
+	"path/filepath"
+	"testing"
+	"time"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/pkg/grimoire"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// setStdin replaces the stdin reader used for the "-" image argument
+func setStdin(t *testing.T, data []byte) {
+	t.Helper()
+	oldStdin, oldData := stdin, stdinData
+	stdin, stdinData = bytes.NewReader(data), nil
+	t.Cleanup(func() { stdin, stdinData = oldStdin, oldData })
+}
+
+func TestDetectSymbolsFromStdin(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	data, err := os.ReadFile(imagePath)
+	if err != nil {
+		t.Skip("Example image not found")
+	}
+
+	expectedSymbols, expectedConnections, err := detectSymbols(imagePath)
+	require.NoError(t, err)
+
+	setStdin(t, data)
+	symbols, connections, err := detectSymbols(stdinPath)
+	require.NoError(t, err)
+	assert.Len(t, symbols, len(expectedSymbols))
+	assert.Len(t, connections, len(expectedConnections))
+
+	// Stdin is read once, so detecting again must see the same image
+	symbols, _, err = detectSymbols(stdinPath)
+	require.NoError(t, err)
+	assert.Len(t, symbols, len(expectedSymbols))
+}
+
+func TestCompileCommandFromStdin(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	data, err := os.ReadFile(imagePath)
+	if err != nil {
+		t.Skip("Example image not found")
+	}
+
+	expected, err := processImage(imagePath)
+	require.NoError(t, err)
+
+	setStdin(t, data)
+	outputFile := filepath.Join(t.TempDir(), "output.py")
+
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "compile", "-", "-o", outputFile}
+	defer func() { os.Args = oldArgs }()
+
+	err = Execute("test", "test", "test")
+	require.NoError(t, err)
+
+	code, err := os.ReadFile(outputFile)
+	require.NoError(t, err)
+	assert.Equal(t, expected, string(code))
+}
+
+func TestDetectSymbolsFromStdinInvalidData(t *testing.T) {
+	tests := []struct {
+		name string
+		data []byte
+	}{
+		{name: "empty input", data: []byte{}},
+		{name: "not an image", data: []byte("this is not an image at all")},
+		{name: "truncated png", data: []byte{0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00}},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			setStdin(t, tt.data)
+			_, _, err := detectSymbols(stdinPath)
+			assert.Error(t, err)
+		})
+	}
+}
+
+func TestDetectSymbolsWithParallelBackend(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); err != nil {
+		t.Skip("Example image not found")
+	}
+
+	oldBackend := detectorBackend
+	detectorBackend = grimoire.BackendParallel
+	defer func() { detectorBackend = oldBackend }()
+
+	symbols, _, err := detectSymbols(imagePath)
+	require.NoError(t, err)
+	assert.NotEmpty(t, symbols)
+}
+
+func TestInvalidBackend(t *testing.T) {
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "symbols", "--backend", "gpu", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	err := Execute("test", "test", "test")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), `"gpu"`)
+}
+
+func TestInvalidMinConfidence(t *testing.T) {
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "symbols", "--min-confidence", "1.5", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	err := Execute("test", "test", "test")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "Minimum confidence 1.5 is out of range")
+}
+
+func TestInvalidPreprocess(t *testing.T) {
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "symbols", "--preprocess", "close{r=2}", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	err := Execute("test", "test", "test")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "Invalid preprocessing chain")
+}
+
+func TestInvalidInks(t *testing.T) {
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "symbols", "--inks", "9", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	err := Execute("test", "test", "test")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "Ink count 9 is out of range")
+}
+
+func TestStrictAndPermissive(t *testing.T) {
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "symbols", "--strict", "--permissive", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	err := Execute("test", "test", "test")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "--strict and --permissive cannot be used together")
+}
+
+func TestMinConfidenceDropsSymbols(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); err != nil {
+		t.Skip("Example image not found")
+	}
+
+	symbols, _, err := detectSymbols(imagePath)
+	require.NoError(t, err)
+	require.NotEmpty(t, symbols)
+	for _, symbol := range symbols {
+		assert.GreaterOrEqual(t, symbol.Confidence, 0.0)
+		assert.LessOrEqual(t, symbol.Confidence, 1.0)
+	}
+
+	oldMin := minConfidence
+	minConfidence = 1.01
+	defer func() { minConfidence = oldMin }()
+
+	_, _, err = detectSymbols(imagePath)
+	require.Error(t, err, "no symbol can score above 1")
+}
+
+func TestInvalidDetectTimeout(t *testing.T) {
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "symbols", "--detect-timeout", "-1s", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	err := Execute("test", "test", "test")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "--detect-timeout must not be negative")
+}
+
+func TestDetectTimeout(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); err != nil {
+		t.Skip("Example image not found")
+	}
+
+	oldTimeout := detectionTimeout
+	detectionTimeout = time.Nanosecond
+	defer func() { detectionTimeout = oldTimeout }()
+
+	_, _, err := detectSymbols(imagePath)
+	require.Error(t, err)
+	assert.True(t, grimoire.IsPartial(err))
+
+	// debug shows what was found instead of failing
+	assert.NoError(t, runArgs(t, "debug", imagePath, "--detect-timeout", "1ns"))
+	assert.Error(t, runArgs(t, "compile", imagePath, "--detect-timeout", "1ns"))
+}
+
+func TestInvalidComplexityLimit(t *testing.T) {
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "symbols", "--max-contours", "-5", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	err := Execute("test", "test", "test")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "--max-contours must not be negative")
+}
+
+func TestComplexityLimit(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); err != nil {
+		t.Skip("Example image not found")
+	}
+
+	err := runArgs(t, "compile", imagePath, "--max-contours", "1")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "more than the limit of 1")
+}
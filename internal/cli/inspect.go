@@ -0,0 +1,22 @@
+package cli
+
+import (
+	"os"
+	"path/filepath"
+
+	"github.com/ayutaz/grimoire/internal/inspector"
+	"github.com/spf13/cobra"
+)
+
+// inspectCommand opens the interactive inspector for a magic circle image
+func inspectCommand(_ *cobra.Command, args []string) error {
+	imagePath := args[0]
+
+	symbols, connections, err := detectSymbols(imagePath)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+
+	in := inspector.New(filepath.Base(imagePath), symbols, connections)
+	return in.Run(stdin, os.Stdout)
+}
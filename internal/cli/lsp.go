@@ -0,0 +1,15 @@
+package cli
+
+import (
+	"os"
+
+	"github.com/ayutaz/grimoire/internal/lsp"
+	"github.com/spf13/cobra"
+)
+
+// lspCommand serves the language server protocol over stdin and stdout
+func lspCommand(_ *cobra.Command, _ []string) error {
+	server := lsp.NewServer(stdin, os.Stdout)
+	server.DescribeRole = describeRole
+	return server.Run()
+}
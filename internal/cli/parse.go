@@ -0,0 +1,88 @@
+package cli
+
+import (
+	"fmt"
+	"os"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/ayutaz/grimoire/pkg/grimoire"
+	"github.com/spf13/cobra"
+)
+
+// parseCommand prints the syntax tree of the program in an image as JSON,
+// or the code it compiles to with --code. With --symbols the symbols are
+// read from an annotation file instead of being detected, so the parser
+// and compiler can be checked against hand-labeled drawings.
+func parseCommand(cmd *cobra.Command, args []string) error {
+	annotationPath, _ := cmd.Flags().GetString("symbols")
+	showCode, _ := cmd.Flags().GetBool("code")
+	imagePath := ""
+	if len(args) > 0 {
+		imagePath = args[0]
+	}
+
+	source := imagePath
+	var symbols []*grimoire.Symbol
+	var connections []grimoire.Connection
+	var err error
+	switch {
+	case annotationPath != "":
+		source = annotationPath
+		symbols, connections, err = readAnnotations(annotationPath, imagePath)
+	case imagePath != "":
+		symbols, connections, err = detectSymbols(imagePath)
+	default:
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("parse.no_input")).
+			WithSuggestion(i18n.T("parse.no_input_suggestion"))
+	}
+	if err != nil {
+		return formatError(err, source)
+	}
+
+	p := parser.NewParser().WithStrictness(strictness)
+	program, err := p.ParseResolvingAmbiguity(symbols, connections)
+	if err != nil {
+		return formatError(err, source)
+	}
+	reportResolutions(os.Stderr, p.Resolutions(), source)
+	reportWarnings(os.Stderr, p.Warnings(), source)
+
+	if showCode {
+		code, err := grimoire.Compile(program)
+		if err != nil {
+			return formatError(err, source)
+		}
+		fmt.Print(code)
+		return nil
+	}
+	data, err := parser.EncodeJSON(program)
+	if err != nil {
+		return err
+	}
+	fmt.Println(string(data))
+	return nil
+}
+
+// readAnnotations reads the symbols labeled for the image at imagePath in
+// the annotation file at path; imagePath may be empty when the file labels
+// a single image
+func readAnnotations(path, imagePath string) ([]*grimoire.Symbol, []grimoire.Connection, error) {
+	file, err := os.Open(path)
+	if err != nil {
+		if os.IsNotExist(err) {
+			return nil, nil, grimoireErrors.FileNotFoundError(path)
+		}
+		return nil, nil, grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("error.file_read_error")).
+			WithInnerError(err).
+			WithLocation(path, 0, 0)
+	}
+	defer file.Close()
+
+	symbols, connections, err := grimoire.DecodeAnnotations(file, imagePath)
+	if err != nil {
+		return nil, nil, withLocation(err, path)
+	}
+	return symbols, connections, nil
+}
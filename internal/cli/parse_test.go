@@ -0,0 +1,69 @@
+package cli
+
+import (
+	"bytes"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// helloWorldLabels labels the hello world circle in the LabelMe format
+const helloWorldLabels = `{
+  "imagePath": "hello_world.png",
+  "shapes": [
+    {"label": "outer_circle", "points": [[200, 200], [381, 200]], "shape_type": "circle"},
+    {"label": "double_circle", "points": [[200, 110], [226, 110]], "shape_type": "circle"},
+    {"label": "star", "points": [[180, 230], [220, 230], [220, 270], [180, 270]], "shape_type": "polygon"}
+  ]
+}`
+
+// parseOutput runs grimoire parse with args and returns what it printed
+func parseOutput(t *testing.T, args ...string) (string, error) {
+	t.Helper()
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err := runArgs(t, append([]string{"parse"}, args...)...)
+
+	w.Close()
+	os.Stdout = oldStdout
+	var buf bytes.Buffer
+	_, readErr := buf.ReadFrom(r)
+	require.NoError(t, readErr)
+	return buf.String(), err
+}
+
+func TestParseAnnotations(t *testing.T) {
+	labelsPath := filepath.Join(t.TempDir(), "labels.json")
+	require.NoError(t, os.WriteFile(labelsPath, []byte(helloWorldLabels), 0o644))
+
+	output, err := parseOutput(t, "--symbols", labelsPath)
+	require.NoError(t, err)
+	assert.Contains(t, output, `"schemaVersion"`)
+	assert.Contains(t, output, `"OutputStatement"`)
+
+	output, err = parseOutput(t, "--symbols", labelsPath, "--code")
+	require.NoError(t, err)
+	assert.Contains(t, output, `print("Hello, World!")`)
+}
+
+func TestParseAnnotationErrors(t *testing.T) {
+	dir := t.TempDir()
+	_, err := parseOutput(t)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "--symbols")
+
+	_, err = parseOutput(t, "--symbols", filepath.Join(dir, "missing.json"))
+	assert.Error(t, err)
+
+	labelsPath := filepath.Join(dir, "labels.json")
+	require.NoError(t, os.WriteFile(labelsPath, []byte(`{"shapes": [{"label": "dragon", "points": [[0, 0]]}]}`), 0o644))
+	_, err = parseOutput(t, "--symbols", labelsPath)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "dragon")
+	assert.Contains(t, err.Error(), "labels.json")
+}
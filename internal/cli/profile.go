@@ -0,0 +1,139 @@
+package cli
+
+import (
+	"fmt"
+	"image/gif"
+	"image/png"
+	"os"
+
+	"github.com/ayutaz/grimoire/internal/compiler"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/ayutaz/grimoire/internal/profiler"
+	"github.com/ayutaz/grimoire/pkg/grimoire"
+)
+
+// profileOutputs are what profileCommand produces besides the output of the
+// program
+type profileOutputs struct {
+	// report is whether to report how often each symbol ran
+	report bool
+	// glowPath is where to write the image with hot symbols glowing
+	glowPath string
+	// animationPath is where to write the animation of the run
+	animationPath string
+}
+
+// profileCommand runs the image at imagePath under the profiler and reports
+// how often each symbol ran and the time spent in it. The report goes to
+// stderr, so the output of the program is left as it is. With a glowPath,
+// the image is also written there with hot symbols glowing, and with an
+// animationPath, a GIF stepping through the run.
+func profileCommand(imagePath string, libraryPaths []string, outputs profileOutputs) error {
+	libraries, err := parseLibraries(libraryPaths)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+	symbols, connections, err := detectSymbols(imagePath)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+	p := parser.NewParser().WithLibraries(libraryPaths...)
+	program, err := p.Parse(symbols, connections)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+	program, err = grimoire.Link(program, libraries)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+	c := compiler.NewCompiler()
+	code, err := c.Compile(program)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+
+	lines, runErr := profiler.Run(code, os.Stdout, os.Stderr, capabilityEnvironment())
+	if lines == nil {
+		return grimoireErrors.NewError(grimoireErrors.ExecutionError, i18n.T("msg.failed_execute_python")).
+			WithInnerError(runErr).
+			WithSuggestion(i18n.T("suggest.check_python_installed"))
+	}
+
+	// A program that fails part way is still profiled up to the failure
+	entries := profiler.Attribute(p.Roles(), c.SourceMap(), lines)
+	if outputs.report {
+		fmt.Fprint(os.Stderr, i18n.T("profile.header"))
+		for _, e := range entries {
+			fmt.Fprintf(os.Stderr, i18n.T("profile.row"), e.Role.Index, e.Role.Symbol.Type, e.Role.Kind, e.Count, e.Seconds*1000)
+		}
+	}
+
+	if outputs.glowPath != "" {
+		if err := writeGlow(imagePath, outputs.glowPath, entries); err != nil {
+			return err
+		}
+		fmt.Fprintf(os.Stderr, i18n.T("profile.image_written"), outputs.glowPath)
+	}
+
+	if outputs.animationPath != "" {
+		img, err := loadImage(imagePath)
+		if err != nil {
+			return formatError(err, imagePath)
+		}
+		anim := profiler.Animate(img, p.Roles(), c.SourceMap(), lines)
+		if err := writeAnimation(outputs.animationPath, anim); err != nil {
+			return err
+		}
+		fmt.Fprintf(os.Stderr, i18n.T("profile.animation_written"), outputs.animationPath)
+	}
+
+	if runErr != nil {
+		return grimoireErrors.NewError(grimoireErrors.ExecutionError, i18n.T("msg.failed_execute_python")).
+			WithInnerError(runErr)
+	}
+	return nil
+}
+
+// writeGlow writes the image at imagePath to glowPath as a PNG, with the
+// profiled symbols glowing
+func writeGlow(imagePath, glowPath string, entries []profiler.Entry) error {
+	img, err := loadImage(imagePath)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+
+	file, err := os.Create(glowPath)
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+			WithInnerError(err).
+			WithLocation(glowPath, 0, 0)
+	}
+	defer file.Close()
+
+	if err := png.Encode(file, profiler.Glow(img, entries)); err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+			WithInnerError(err).
+			WithLocation(glowPath, 0, 0)
+	}
+	return nil
+}
+
+// writeAnimation writes anim to path as a GIF
+func writeAnimation(path string, anim *gif.GIF) error {
+	file, err := os.Create(path)
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+			WithInnerError(err).
+			WithLocation(path, 0, 0)
+	}
+	defer file.Close()
+
+	if err := gif.EncodeAll(file, anim); err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+			WithInnerError(err).
+			WithLocation(path, 0, 0)
+	}
+	return nil
+}
@@ -0,0 +1,190 @@
+package cli
+
+import (
+	"crypto/ed25519"
+	"crypto/sha256"
+	"encoding/base64"
+	"encoding/hex"
+	"image"
+	"image/color"
+	"image/draw"
+	"image/png"
+	"os"
+	"strings"
+
+	"github.com/ayutaz/grimoire/internal/bundle"
+	"github.com/ayutaz/grimoire/internal/compiler"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/qr"
+)
+
+// Targets for the compile --emit flag
+const (
+	emitPython = "python"
+	emitQR     = "qr"
+)
+
+// qrPayloadHeader starts every program embedded by compile --emit qr, so
+// run --from-qr only considers QR codes that Grimoire wrote. A signed
+// program follows it with a signature line.
+const qrPayloadHeader = "# grimoire\n"
+
+// qrModuleScale is the number of pixels per module of an embedded QR code
+const qrModuleScale = 3
+
+// writeQRImage writes the image at imagePath with code embedded as a QR
+// code in its bottom-right corner, signed with key unless it is nil
+func writeQRImage(imagePath, code, outputPath string, key ed25519.PrivateKey) error {
+	img, err := loadImage(imagePath)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+
+	embedded, err := embedProgram(img, code, key)
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("qr.program_too_large")).
+			WithInnerError(err).
+			WithSuggestion(i18n.T("qr.program_too_large_suggestion"))
+	}
+
+	file, err := os.Create(outputPath)
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+			WithInnerError(err).
+			WithLocation(outputPath, 0, 0)
+	}
+	defer file.Close()
+
+	if err := png.Encode(file, embedded); err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+			WithInnerError(err).
+			WithLocation(outputPath, 0, 0)
+	}
+	return nil
+}
+
+// embedProgram returns a copy of img with the minified code, signed with
+// key unless it is nil, drawn as a QR code in the bottom-right corner. The
+// canvas is widened so the code sits beside the circle rather than over it.
+func embedProgram(img image.Image, code string, key ed25519.PrivateKey) (*image.RGBA, error) {
+	payload := compiler.Minify(code)
+	if key != nil {
+		payload = signedQRPayload(payload, key)
+	}
+	symbol, err := qr.Encode([]byte(qrPayloadHeader+payload), qr.LevelM)
+	if err != nil {
+		return nil, err
+	}
+
+	bounds := img.Bounds()
+	size := symbol.PixelSize(qrModuleScale)
+	canvas := image.NewRGBA(image.Rect(0, 0, bounds.Dx()+size, max(bounds.Dy(), size)))
+	draw.Draw(canvas, canvas.Bounds(), image.NewUniform(color.White), image.Point{}, draw.Src)
+	draw.Draw(canvas, image.Rect(0, 0, bounds.Dx(), bounds.Dy()), img, bounds.Min, draw.Over)
+	symbol.Draw(canvas, image.Pt(bounds.Dx(), canvas.Bounds().Dy()-size), qrModuleScale)
+	return canvas, nil
+}
+
+// embeddedProgram returns the program embedded in the image at imagePath by
+// compile --emit qr, if there is one, with its signature line, if any
+func embeddedProgram(imagePath string) (string, *qrSignature, bool) {
+	img, err := loadImage(imagePath)
+	if err != nil {
+		return "", nil, false
+	}
+
+	data, err := qr.Decode(img)
+	if err != nil || !strings.HasPrefix(string(data), qrPayloadHeader) {
+		return "", nil, false
+	}
+	code := strings.TrimPrefix(string(data), qrPayloadHeader)
+	if !strings.HasPrefix(code, qrSignaturePrefix) {
+		return code, nil, true
+	}
+	line, code, _ := strings.Cut(code, "\n")
+	signature, ok := parseQRSignature(line)
+	if !ok {
+		return "", nil, false
+	}
+	return code, signature, true
+}
+
+// trustedEmbeddedProgram returns the program embedded in the image at
+// imagePath by compile --emit qr, to be run as it is. Anyone can draw a QR
+// code, so when the policy requires a signature, the program must be signed
+// with one of its trusted keys.
+func trustedEmbeddedProgram(imagePath string, policy signingPolicy) (string, error) {
+	code, signature, ok := embeddedProgram(imagePath)
+	if !ok {
+		return "", grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("qr.no_program")).
+			WithLocation(imagePath, 0, 0).
+			WithSuggestion(i18n.T("qr.no_program_suggestion"))
+	}
+	if !policy.required {
+		return code, nil
+	}
+	if signature == nil {
+		return "", grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("qr.unsigned")).
+			WithLocation(imagePath, 0, 0).
+			WithSuggestion(i18n.T("qr.unsigned_suggestion"))
+	}
+	if !ed25519.Verify(signature.publicKey, qrSignedMessage(code), signature.signature) {
+		return "", grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("qr.signature_mismatch")).
+			WithLocation(imagePath, 0, 0)
+	}
+	for _, key := range policy.trusted {
+		if key.Equal(signature.publicKey) {
+			return code, nil
+		}
+	}
+	return "", grimoireErrors.NewError(grimoireErrors.ValidationError,
+		i18n.Tf("qr.untrusted_key", bundle.Fingerprint(signature.publicKey))).
+		WithLocation(imagePath, 0, 0)
+}
+
+// qrSignaturePrefix starts the line after the header of a signed program,
+// followed by the public key and the signature in base64, separated by a
+// space. It is a comment, so the payload still runs as it is.
+const qrSignaturePrefix = "# signature "
+
+// qrSignatureContext is prefixed to every signed message, so that a
+// signature made for a QR code cannot be passed off as one over anything else
+const qrSignatureContext = "grimoire-qr-signature-v1"
+
+// qrSignature is an ed25519 signature over a program embedded in a QR code
+type qrSignature struct {
+	publicKey ed25519.PublicKey
+	signature []byte
+}
+
+// qrSignedMessage returns what the signature of an embedded program signs:
+// the SHA-256 digest of the program
+func qrSignedMessage(code string) []byte {
+	sum := sha256.Sum256([]byte(code))
+	return []byte(qrSignatureContext + "\n" + hex.EncodeToString(sum[:]) + "\n")
+}
+
+// signedQRPayload returns the payload embedding code signed with key
+func signedQRPayload(code string, key ed25519.PrivateKey) string {
+	signature := ed25519.Sign(key, qrSignedMessage(code))
+	return qrSignaturePrefix + base64.StdEncoding.EncodeToString(key.Public().(ed25519.PublicKey)) +
+		" " + base64.StdEncoding.EncodeToString(signature) + "\n" + code
+}
+
+// parseQRSignature reads the signature line of a signed program
+func parseQRSignature(line string) (*qrSignature, bool) {
+	fields := strings.Fields(strings.TrimPrefix(line, qrSignaturePrefix))
+	if len(fields) != 2 {
+		return nil, false
+	}
+	publicKey, err := base64.StdEncoding.DecodeString(fields[0])
+	if err != nil || len(publicKey) != ed25519.PublicKeySize {
+		return nil, false
+	}
+	signature, err := base64.StdEncoding.DecodeString(fields[1])
+	if err != nil {
+		return nil, false
+	}
+	return &qrSignature{publicKey: publicKey, signature: signature}, true
+}
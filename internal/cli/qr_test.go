@@ -0,0 +1,158 @@
+package cli
+
+import (
+	"crypto/ed25519"
+	"image/png"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/bundle"
+	"github.com/ayutaz/grimoire/internal/cache"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestCompileEmitQR(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+
+	outputFile := filepath.Join(t.TempDir(), "embedded.png")
+
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "compile", imagePath, "--emit", "qr", "-o", outputFile}
+	defer func() { os.Args = oldArgs }()
+
+	err := Execute("test", "test", "test")
+	require.NoError(t, err)
+
+	code, signature, ok := embeddedProgram(outputFile)
+	require.True(t, ok)
+	assert.Nil(t, signature, "programs are embedded unsigned without --key")
+	assert.Contains(t, code, "print(")
+
+	// The code sits beside the original image
+	original, err := loadImage(imagePath)
+	require.NoError(t, err)
+	embedded, err := loadImage(outputFile)
+	require.NoError(t, err)
+	assert.Greater(t, embedded.Bounds().Dx(), original.Bounds().Dx())
+
+	// The original image carries no embedded program
+	_, _, ok = embeddedProgram(imagePath)
+	assert.False(t, ok)
+}
+
+func TestRunFromQR(t *testing.T) {
+	if _, err := exec.LookPath("python3"); err != nil {
+		t.Skip("Python3 not available")
+	}
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+	t.Setenv(cache.DirEnv, t.TempDir())
+
+	// A QR code written by compile --emit qr runs with --from-qr
+	printable := filepath.Join(t.TempDir(), "printable.png")
+	executeCapturingStdout(t, "compile", imagePath, "--emit", "qr", "-o", printable)
+	expected := executeCapturingStdout(t, "run", imagePath, "--no-cache")
+	assert.Equal(t, expected, executeCapturingStdout(t, "run", printable, "--from-qr"))
+
+	// The payload runs as it is, whatever the drawing beside it
+	img, err := loadImage(imagePath)
+	require.NoError(t, err)
+	writeEmbedded := func(code string, key ed25519.PrivateKey) string {
+		embedded, err := embedProgram(img, code, key)
+		require.NoError(t, err)
+		path := filepath.Join(t.TempDir(), "embedded.png")
+		file, err := os.Create(path)
+		require.NoError(t, err)
+		require.NoError(t, png.Encode(file, embedded))
+		require.NoError(t, file.Close())
+		return path
+	}
+	payloadPath := writeEmbedded("print('embedded payload')\n", nil)
+
+	output, _ := executeCapturing(t, "run", payloadPath, "--no-cache")
+	assert.NotContains(t, output, "embedded payload", "the QR code is ignored by default")
+	assert.Equal(t, "embedded payload\n", executeCapturingStdout(t, "run", payloadPath, "--from-qr"))
+
+	// With a trusted key, only programs signed with it run
+	dir := t.TempDir()
+	keyPath := filepath.Join(dir, "signing.pem")
+	_, err = generateKey(keyPath)
+	require.NoError(t, err)
+	otherKeyPath := filepath.Join(dir, "other.pem")
+	otherKey, err := generateKey(otherKeyPath)
+	require.NoError(t, err)
+
+	signed := filepath.Join(t.TempDir(), "signed.png")
+	executeCapturingStdout(t, "compile", imagePath, "--emit", "qr", "-o", signed, "--key", keyPath)
+	_, signature, ok := embeddedProgram(signed)
+	require.True(t, ok)
+	require.NotNil(t, signature)
+	assert.Equal(t, expected, executeCapturingStdout(t, "run", signed, "--from-qr", "--trusted-key", keyPath+publicKeySuffix))
+
+	for name, path := range map[string]string{
+		"unsigned":      payloadPath,
+		"untrusted key": writeEmbedded("print('embedded payload')\n", otherKey),
+	} {
+		output, err = executeCapturing(t, "run", path, "--from-qr", "--trusted-key", keyPath+publicKeySuffix)
+		require.Error(t, err, name)
+		assert.Equal(t, grimoireErrors.ExitValidation, grimoireErrors.ExitCode(err), name)
+		assert.NotContains(t, output, "embedded payload", name)
+	}
+
+	// An image without a QR code has nothing to run from it
+	_, err = executeCapturing(t, "run", imagePath, "--from-qr")
+	require.Error(t, err)
+	assert.Equal(t, grimoireErrors.ExitValidation, grimoireErrors.ExitCode(err))
+}
+
+func TestQRSignature(t *testing.T) {
+	key, err := bundle.GenerateKey()
+	require.NoError(t, err)
+	code := "print('signed')\n"
+	line, signedCode, _ := strings.Cut(signedQRPayload(code, key), "\n")
+	assert.Equal(t, code, signedCode)
+
+	signature, ok := parseQRSignature(line)
+	require.True(t, ok)
+	assert.True(t, key.Public().(ed25519.PublicKey).Equal(signature.publicKey))
+	assert.True(t, ed25519.Verify(signature.publicKey, qrSignedMessage(code), signature.signature))
+	assert.False(t, ed25519.Verify(signature.publicKey, qrSignedMessage("print('changed')\n"), signature.signature),
+		"a program changed after signing does not match its signature")
+
+	_, ok = parseQRSignature(qrSignaturePrefix + "not-base64")
+	assert.False(t, ok)
+}
+
+func TestCompileEmitErrors(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+
+	tests := []struct {
+		name string
+		args []string
+	}{
+		{name: "unknown target", args: []string{"grimoire", "compile", imagePath, "--emit", "wasm"}},
+		{name: "qr without output", args: []string{"grimoire", "compile", imagePath, "--emit", "qr"}},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			oldArgs := os.Args
+			os.Args = tt.args
+			defer func() { os.Args = oldArgs }()
+
+			err := Execute("test", "test", "test")
+			require.Error(t, err)
+			assert.Equal(t, grimoireErrors.ExitValidation, grimoireErrors.ExitCode(err))
+		})
+	}
+}
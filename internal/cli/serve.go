@@ -0,0 +1,66 @@
+package cli
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"net/http"
+	"os"
+	"os/signal"
+	"time"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/server"
+	"github.com/spf13/cobra"
+)
+
+// newServer creates a server detecting as the global flags select
+func newServer() (*server.Server, error) {
+	return server.New(detectorBackend, detectorConfig())
+}
+
+// serveCommand compiles images posted over HTTP until the process is stopped
+func serveCommand(cmd *cobra.Command, _ []string) error {
+	addr, _ := cmd.Flags().GetString("addr")
+	s, err := newServer()
+	if err != nil {
+		return err
+	}
+
+	fmt.Fprintf(os.Stderr, i18n.T("serve.listening"), addr)
+	httpServer := &http.Server{Addr: addr, Handler: s.Handler(), ReadHeaderTimeout: 10 * time.Second}
+	if err := httpServer.ListenAndServe(); err != nil {
+		return grimoireErrors.NewError(grimoireErrors.IOError, i18n.Tf("serve.failed", addr)).WithInnerError(err)
+	}
+	return nil
+}
+
+// daemonCommand serves detection on a unix socket for clients run with
+// --remote, until interrupted
+func daemonCommand(cmd *cobra.Command, _ []string) error {
+	socket, _ := cmd.Flags().GetString("socket")
+	s, err := newServer()
+	if err != nil {
+		return err
+	}
+	listener, err := server.Listen(socket)
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.IOError, i18n.Tf("serve.failed", socket)).WithInnerError(err)
+	}
+
+	// Closing the listener on interrupt also removes the socket
+	ctx, stop := signal.NotifyContext(context.Background(), os.Interrupt)
+	defer stop()
+	httpServer := &http.Server{Handler: s.Handler(), ReadHeaderTimeout: 10 * time.Second}
+	go func() {
+		<-ctx.Done()
+		_ = httpServer.Shutdown(context.Background())
+	}()
+
+	fmt.Fprintf(os.Stderr, i18n.T("daemon.listening"), socket)
+	if err := httpServer.Serve(listener); err != nil && !errors.Is(err, http.ErrServerClosed) {
+		return grimoireErrors.NewError(grimoireErrors.IOError, i18n.Tf("serve.failed", socket)).WithInnerError(err)
+	}
+	return nil
+}
@@ -0,0 +1,197 @@
+package cli
+
+import (
+	"crypto/ed25519"
+	"fmt"
+	"os"
+
+	"github.com/ayutaz/grimoire/internal/bundle"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/spf13/cobra"
+)
+
+// publicKeySuffix is appended to the path of a generated private key to
+// name the file its public key is written to
+const publicKeySuffix = ".pub"
+
+// signCommand signs the program and syntax tree of a bundle with an
+// ed25519 key, embedding the signature in the bundle
+func signCommand(cmd *cobra.Command, args []string) error {
+	path := args[0]
+	keyPath, _ := cmd.Flags().GetString("key")
+	generate, _ := cmd.Flags().GetBool("generate-key")
+	if keyPath == "" {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("sign.no_key"))
+	}
+
+	b, err := bundle.ReadFile(path)
+	if err != nil {
+		return err
+	}
+	var key ed25519.PrivateKey
+	if generate {
+		key, err = generateKey(keyPath)
+	} else {
+		key, err = readPrivateKey(keyPath)
+	}
+	if err != nil {
+		return err
+	}
+
+	b.Sign(key)
+	if err := b.WriteFile(path); err != nil {
+		return err
+	}
+	fmt.Printf(i18n.T("sign.signed"), path, bundle.Fingerprint(b.Signature.PublicKey))
+	return nil
+}
+
+// verifyCommand checks the signature of a bundle against the given public
+// keys, or only that it matches the content of the bundle when there are none
+func verifyCommand(cmd *cobra.Command, args []string) error {
+	path := args[0]
+	keyPaths, _ := cmd.Flags().GetStringSlice("key")
+	trusted, err := readPublicKeys(keyPaths)
+	if err != nil {
+		return err
+	}
+
+	b, err := bundle.ReadFile(path)
+	if err != nil {
+		return err
+	}
+
+	// Without keys only the integrity of the bundle can be checked
+	if len(trusted) == 0 {
+		if err := withLocation(b.VerifyIntegrity(), path); err != nil {
+			return err
+		}
+		fmt.Printf(i18n.T("sign.integrity_verified"), path, bundle.Fingerprint(b.Signature.PublicKey))
+		return nil
+	}
+	if err := verifyBundle(b, path, trusted); err != nil {
+		return err
+	}
+	fmt.Printf(i18n.T("sign.verified"), path, bundle.Fingerprint(b.Signature.PublicKey))
+	return nil
+}
+
+// signingPolicy is what run demands of the signature of a program
+type signingPolicy struct {
+	// required refuses programs other than bundles signed with a trusted key
+	required bool
+	// trusted are the keys a required signature must be made with; there is
+	// always at least one when a signature is required
+	trusted []ed25519.PublicKey
+}
+
+// signingPolicyFlags reads the signing policy of run from its flags. A
+// trusted key implies that a signature is required, and requiring one
+// needs a trusted key, since a bundle can be re-signed with any key.
+func signingPolicyFlags(cmd *cobra.Command) (signingPolicy, error) {
+	required, _ := cmd.Flags().GetBool("require-signed")
+	keyPaths, _ := cmd.Flags().GetStringSlice("trusted-key")
+	trusted, err := readPublicKeys(keyPaths)
+	if err != nil {
+		return signingPolicy{}, err
+	}
+	if required && len(trusted) == 0 {
+		return signingPolicy{}, grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("sign.no_trusted_keys")).
+			WithSuggestion(i18n.T("sign.no_trusted_keys_suggestion"))
+	}
+	return signingPolicy{required: required || len(trusted) > 0, trusted: trusted}, nil
+}
+
+// verifyBundle checks that the bundle read from path is signed with one of
+// the trusted keys
+func verifyBundle(b *bundle.Bundle, path string, trusted []ed25519.PublicKey) error {
+	return withLocation(b.Verify(trusted...), path)
+}
+
+// withLocation adds path as the location of err when it is a Grimoire error
+func withLocation(err error, path string) error {
+	if gErr, ok := err.(*grimoireErrors.GrimoireError); ok {
+		return gErr.WithLocation(path, 0, 0)
+	}
+	return err
+}
+
+// generateKey creates a signing key, writing it to keyPath and its public
+// key next to it. An existing key is never overwritten.
+func generateKey(keyPath string) (ed25519.PrivateKey, error) {
+	key, err := bundle.GenerateKey()
+	if err != nil {
+		return nil, err
+	}
+	private, err := bundle.EncodePrivateKey(key)
+	if err != nil {
+		return nil, err
+	}
+	public, err := bundle.EncodePublicKey(key.Public().(ed25519.PublicKey))
+	if err != nil {
+		return nil, err
+	}
+
+	file, err := os.OpenFile(keyPath, os.O_WRONLY|os.O_CREATE|os.O_EXCL, 0o600)
+	if err == nil {
+		_, err = file.Write(private)
+		if closeErr := file.Close(); err == nil {
+			err = closeErr
+		}
+	}
+	if err == nil {
+		err = os.WriteFile(keyPath+publicKeySuffix, public, 0o644)
+	}
+	if err != nil {
+		return nil, grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("sign.failed_write_key")).
+			WithInnerError(err).
+			WithLocation(keyPath, 0, 0)
+	}
+	fmt.Fprintf(os.Stderr, i18n.T("sign.key_generated"), keyPath, keyPath+publicKeySuffix)
+	return key, nil
+}
+
+// readPrivateKey reads the signing key at path
+func readPrivateKey(path string) (ed25519.PrivateKey, error) {
+	data, err := readKeyFile(path)
+	if err != nil {
+		return nil, err
+	}
+	key, err := bundle.DecodePrivateKey(data)
+	if err != nil {
+		return nil, withLocation(err, path)
+	}
+	return key, nil
+}
+
+// readPublicKeys reads the public keys at paths
+func readPublicKeys(paths []string) ([]ed25519.PublicKey, error) {
+	keys := make([]ed25519.PublicKey, 0, len(paths))
+	for _, path := range paths {
+		data, err := readKeyFile(path)
+		if err != nil {
+			return nil, err
+		}
+		key, err := bundle.DecodePublicKey(data)
+		if err != nil {
+			return nil, withLocation(err, path)
+		}
+		keys = append(keys, key)
+	}
+	return keys, nil
+}
+
+// readKeyFile returns the content of the key file at path
+func readKeyFile(path string) ([]byte, error) {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		if os.IsNotExist(err) {
+			return nil, grimoireErrors.FileNotFoundError(path)
+		}
+		return nil, grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("sign.failed_read_key")).
+			WithInnerError(err).
+			WithLocation(path, 0, 0)
+	}
+	return data, nil
+}
@@ -0,0 +1,119 @@
+package cli
+
+import (
+	"crypto/ed25519"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/bundle"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// runArgs runs the CLI with args in English
+func runArgs(t *testing.T, args ...string) error {
+	t.Helper()
+	oldArgs := os.Args
+	os.Args = append(append([]string{"grimoire"}, args...), "--lang", "en")
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	return Execute("test", "test", "test")
+}
+
+func TestSignAndVerify(t *testing.T) {
+	imagePath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(imagePath); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+	dir := t.TempDir()
+	bundlePath := filepath.Join(dir, "hello.grimoire")
+	keyPath := filepath.Join(dir, "signing.pem")
+	otherKeyPath := filepath.Join(dir, "other.pem")
+	require.NoError(t, runArgs(t, "compile", imagePath, "--bundle", "-o", bundlePath))
+
+	// An unsigned bundle neither verifies nor runs with --require-signed
+	assert.Error(t, runArgs(t, "verify", bundlePath))
+	require.NoError(t, runArgs(t, "sign", otherBundle(t, bundlePath), "--key", keyPath, "--generate-key"))
+	err := runArgs(t, "run", bundlePath, "--require-signed", "--trusted-key", keyPath+publicKeySuffix)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "not signed")
+
+	// Requiring a signature needs a key to trust
+	err = runArgs(t, "run", bundlePath, "--require-signed")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "needs a trusted key")
+
+	require.NoError(t, runArgs(t, "sign", bundlePath, "--key", keyPath))
+	assert.FileExists(t, keyPath+publicKeySuffix)
+	assert.Error(t, runArgs(t, "sign", bundlePath, "--key", keyPath, "--generate-key"),
+		"an existing key is not overwritten")
+
+	assert.NoError(t, runArgs(t, "verify", bundlePath))
+	assert.NoError(t, runArgs(t, "verify", bundlePath, "--key", keyPath+publicKeySuffix))
+
+	other, err := bundle.GenerateKey()
+	require.NoError(t, err)
+	otherPEM, err := bundle.EncodePrivateKey(other)
+	require.NoError(t, err)
+	require.NoError(t, os.WriteFile(otherKeyPath, otherPEM, 0o600))
+	err = runArgs(t, "verify", bundlePath, "--key", otherKeyPath)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "untrusted key")
+	err = runArgs(t, "run", bundlePath, "--trusted-key", otherKeyPath)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "untrusted key")
+
+	// Tampering with the program breaks the signature
+	b, err := bundle.ReadFile(bundlePath)
+	require.NoError(t, err)
+	b.Code = append(b.Code, []byte("print('tampered')\n")...)
+	require.NoError(t, b.WriteFile(bundlePath))
+	err = runArgs(t, "verify", bundlePath)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "changed after signing")
+
+	// Re-signed with a foreign key, the tampered bundle is whole again but
+	// neither verifies against nor runs with the trusted key
+	b.Sign(other)
+	require.NoError(t, b.WriteFile(bundlePath))
+	assert.NoError(t, runArgs(t, "verify", bundlePath))
+	err = runArgs(t, "verify", bundlePath, "--key", keyPath+publicKeySuffix)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "untrusted key")
+	err = runArgs(t, "run", bundlePath, "--require-signed", "--trusted-key", keyPath+publicKeySuffix)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "untrusted key")
+}
+
+// otherBundle copies the bundle at path next to it and returns the path of
+// the copy
+func otherBundle(t *testing.T, path string) string {
+	t.Helper()
+	data, err := os.ReadFile(path)
+	require.NoError(t, err)
+	copyPath := filepath.Join(filepath.Dir(path), "other.grimoire")
+	require.NoError(t, os.WriteFile(copyPath, data, 0o644))
+	return copyPath
+}
+
+func TestRunRequireSignedImage(t *testing.T) {
+	key, err := bundle.GenerateKey()
+	require.NoError(t, err)
+	public, err := bundle.EncodePublicKey(key.Public().(ed25519.PublicKey))
+	require.NoError(t, err)
+	keyPath := filepath.Join(t.TempDir(), "trusted.pem.pub")
+	require.NoError(t, os.WriteFile(keyPath, public, 0o644))
+
+	err = runArgs(t, "run", filepath.Join("..", "..", "examples", "images", "hello_world.png"),
+		"--require-signed", "--trusted-key", keyPath)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "Only signed .grimoire bundles")
+}
+
+func TestSignWithoutKey(t *testing.T) {
+	err := runArgs(t, "sign", "hello.grimoire")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "No key given")
+}
@@ -0,0 +1,72 @@
+package cli
+
+import (
+	"fmt"
+	"path/filepath"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/spf13/cobra"
+)
+
+// statsCommand prints statistics of the program in an image, as JSON with
+// --format json. With --max-complexity or --max-depth it fails when the
+// program is over that budget.
+func statsCommand(cmd *cobra.Command, args []string) error {
+	imagePath := args[0]
+	maxComplexity, _ := cmd.Flags().GetInt("max-complexity")
+	maxDepth, _ := cmd.Flags().GetInt("max-depth")
+
+	symbols, connections, err := detectSymbols(imagePath)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+	program, err := parser.Parse(symbols, connections)
+	if err != nil {
+		return formatError(err, imagePath)
+	}
+	stats := parser.ComputeStats(program, symbols)
+
+	if diagnosticOptions.format == formatJSON {
+		data, err := parser.EncodeStatsJSON(stats)
+		if err != nil {
+			return err
+		}
+		fmt.Println(string(data))
+	} else {
+		printStats(filepath.Base(imagePath), stats)
+	}
+
+	var over []error
+	if maxComplexity > 0 && stats.Complexity > maxComplexity {
+		over = append(over, grimoireErrors.NewError(grimoireErrors.ValidationError,
+			i18n.Tf("stats.over_complexity", stats.Complexity, maxComplexity)))
+	}
+	if maxDepth > 0 && stats.MaxDepth > maxDepth {
+		over = append(over, grimoireErrors.NewError(grimoireErrors.ValidationError,
+			i18n.Tf("stats.over_depth", stats.MaxDepth, maxDepth)))
+	}
+	if len(over) > 0 {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("stats.over_budget")).
+			WithLocation(imagePath, 0, 0).
+			WithRelated(over...)
+	}
+	return nil
+}
+
+// printStats prints stats as a table
+func printStats(name string, stats parser.Stats) {
+	fmt.Printf(i18n.T("stats.header"), name)
+	for _, t := range detector.SymbolTypes() {
+		if n := stats.Symbols[t]; n > 0 {
+			fmt.Printf(i18n.T("stats.symbol"), t, n)
+		}
+	}
+	fmt.Printf(i18n.T("stats.functions"), stats.Functions)
+	fmt.Printf(i18n.T("stats.statements"), stats.Statements)
+	fmt.Printf(i18n.T("stats.max_depth"), stats.MaxDepth)
+	fmt.Printf(i18n.T("stats.complexity"), stats.Complexity)
+	fmt.Printf(i18n.T("stats.bytecode"), stats.BytecodeBytes)
+}
@@ -0,0 +1,80 @@
+package cli
+
+import (
+	"fmt"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/spf13/cobra"
+)
+
+// symbolGlyphs are the notation glyphs used in the documentation
+var symbolGlyphs = map[detector.SymbolType]string{
+	detector.OuterCircle:      "◯",
+	detector.Circle:           "○",
+	detector.DoubleCircle:     "◎",
+	detector.Square:           "□",
+	detector.Rectangle:        "▭",
+	detector.Triangle:         "△",
+	detector.Pentagon:         "⬟",
+	detector.Hexagon:          "⬢",
+	detector.Star:             "☆",
+	detector.SixPointedStar:   "✡",
+	detector.EightPointedStar: "✴",
+	detector.Convergence:      "⟐",
+	detector.Divergence:       "⟑",
+	detector.Amplification:    "✦",
+	detector.Distribution:     "⟠",
+	detector.Transfer:         "→",
+	detector.Seal:             "⊗",
+	detector.Circulation:      "↻",
+	detector.Equal:            "=",
+	detector.NotEqual:         "≠",
+	detector.LessThan:         "<",
+	detector.GreaterThan:      ">",
+	detector.LessEqual:        "≤",
+	detector.GreaterEqual:     "≥",
+	detector.LogicalAnd:       "∧",
+	detector.LogicalOr:        "∨",
+	detector.LogicalNot:       "¬",
+	detector.LogicalXor:       "⊻",
+}
+
+// patternGlyphs are the notation glyphs for internal patterns
+var patternGlyphs = map[string]string{
+	detector.PatternEmpty:      " ",
+	detector.PatternDot:        "•",
+	detector.PatternDoubleDot:  "••",
+	detector.PatternTripleDot:  "•••",
+	detector.PatternLines:      "≡",
+	detector.PatternTripleLine: "☰",
+	detector.PatternCross:      "×",
+	detector.PatternHalfCircle: "◐",
+}
+
+// connectionGlyphs are the notation glyphs for connection styles
+var connectionGlyphs = map[string]string{
+	detector.ConnectionTypeSolid:  "─",
+	detector.ConnectionTypeDashed: "╌",
+	detector.ConnectionTypeDotted: "┈",
+}
+
+// symbolsCommand prints the language reference for symbols, patterns and connections
+func symbolsCommand(_ *cobra.Command, _ []string) error {
+	fmt.Println(i18n.T("symbols.symbols_header"))
+	for _, t := range detector.SymbolTypes() {
+		fmt.Printf(i18n.T("symbols.entry"), symbolGlyphs[t], t, i18n.T("symbols.type."+string(t)))
+	}
+
+	fmt.Println(i18n.T("symbols.patterns_header"))
+	for _, p := range detector.Patterns() {
+		fmt.Printf(i18n.T("symbols.entry"), patternGlyphs[p], p, i18n.T("symbols.pattern."+p))
+	}
+
+	fmt.Println(i18n.T("symbols.connections_header"))
+	for _, c := range detector.ConnectionTypes() {
+		fmt.Printf(i18n.T("symbols.entry"), connectionGlyphs[c], c, i18n.T("symbols.connection."+c))
+	}
+
+	return nil
+}
@@ -0,0 +1,64 @@
+package cli
+
+import (
+	"bytes"
+	"os"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestSymbolsCommand(t *testing.T) {
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "symbols", "--lang", "en"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err := Execute("test", "test", "test")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	var buf bytes.Buffer
+	_, readErr := buf.ReadFrom(r)
+	require.NoError(t, readErr)
+	output := buf.String()
+
+	require.NoError(t, err)
+	assert.Contains(t, output, "Main entry point")
+	assert.Contains(t, output, "Boolean false")
+	assert.Contains(t, output, "dashed")
+}
+
+// TestSymbolsReferenceComplete ensures every recognized symbol, pattern and
+// connection style has a glyph and a description in both languages
+func TestSymbolsReferenceComplete(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	for _, lang := range []i18n.Language{i18n.English, i18n.Japanese} {
+		i18n.SetLanguage(lang)
+
+		for _, st := range detector.SymbolTypes() {
+			key := "symbols.type." + string(st)
+			assert.NotEqual(t, key, i18n.T(key), "missing description for %s", st)
+			assert.NotEmpty(t, symbolGlyphs[st], "missing glyph for %s", st)
+		}
+		for _, p := range detector.Patterns() {
+			key := "symbols.pattern." + p
+			assert.NotEqual(t, key, i18n.T(key), "missing description for %s", p)
+			assert.Contains(t, patternGlyphs, p)
+		}
+		for _, c := range detector.ConnectionTypes() {
+			key := "symbols.connection." + c
+			assert.NotEqual(t, key, i18n.T(key), "missing description for %s", c)
+			assert.NotEmpty(t, connectionGlyphs[c], "missing glyph for %s", c)
+		}
+	}
+}
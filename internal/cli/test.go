@@ -0,0 +1,140 @@
+package cli
+
+import (
+	"encoding/json"
+	"fmt"
+	"image/png"
+	"os"
+	"path/filepath"
+	"strings"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/spelltest"
+	"github.com/spf13/cobra"
+)
+
+// newSpellTestRunner creates the runner used by the test command; tests replace it
+var newSpellTestRunner = spelltest.NewRunner
+
+// testCommand runs the golden tests found in a directory of magic circle images
+func testCommand(cmd *cobra.Command, args []string) error {
+	dir := args[0]
+	update, _ := cmd.Flags().GetBool("update")
+	coverageDir, _ := cmd.Flags().GetString("coverage")
+
+	cases, err := spelltest.Discover(dir, update)
+	if err != nil {
+		return grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("error.file_read_error")).
+			WithInnerError(err).
+			WithLocation(dir, 0, 0)
+	}
+	if len(cases) == 0 {
+		fmt.Printf(i18n.T("test.no_cases"), dir)
+		return nil
+	}
+
+	readCapabilityFlags(cmd)
+	runner := newSpellTestRunner(capabilityEnvironment())
+	runner.Update = update
+	runner.Coverage = coverageDir != ""
+
+	failed := 0
+	results := runner.RunAll(cases)
+	for _, result := range results {
+		switch {
+		case result.Err != nil:
+			failed++
+			fmt.Printf(i18n.T("test.error"), result.Case.Name, result.Err)
+		case !result.Passed():
+			failed++
+			fmt.Printf(i18n.T("test.fail"), result.Case.Name)
+			for _, failure := range result.Failures {
+				if failure.Kind == spelltest.FailureAssertion {
+					fmt.Printf(i18n.T("test.assertions_failed"), strings.Count(failure.Diff, "\n"))
+				} else {
+					fmt.Printf(i18n.T("test.mismatch"), i18n.T("test.kind."+failure.Kind))
+				}
+				fmt.Print(indentLines(failure.Diff, "      "))
+			}
+		case update:
+			fmt.Printf(i18n.T("test.updated"), result.Case.Name, strings.Join(result.Updated, ", "))
+		default:
+			fmt.Printf(i18n.T("test.pass"), result.Case.Name)
+		}
+	}
+
+	fmt.Printf(i18n.T("test.summary"), len(cases)-failed, failed, len(cases))
+	if coverageDir != "" {
+		if err := writeCoverage(coverageDir, results); err != nil {
+			return err
+		}
+	}
+	if failed > 0 {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.Tf("test.failed", failed))
+	}
+	return nil
+}
+
+// writeCoverage reports the coverage of results and writes it to dir: an
+// image per case with the symbols that never ran in gray, and a JSON summary
+func writeCoverage(dir string, results []spelltest.Result) error {
+	summary := spelltest.Summarize(results)
+	for _, c := range summary.Cases {
+		fmt.Printf(i18n.T("test.coverage_case"), c.Name, c.Percent, c.Covered, c.Total)
+	}
+	fmt.Printf(i18n.T("test.coverage_total"), summary.Percent, summary.Covered, summary.Total)
+
+	if err := os.MkdirAll(dir, 0o755); err != nil {
+		return coverageWriteError(err, dir)
+	}
+	for _, result := range results {
+		if result.Coverage == nil {
+			continue
+		}
+		img, err := loadImage(result.Case.ImagePath)
+		if err != nil {
+			return formatError(err, result.Case.ImagePath)
+		}
+		path := filepath.Join(dir, result.Case.Name+".coverage.png")
+		file, err := os.Create(path)
+		if err != nil {
+			return coverageWriteError(err, path)
+		}
+		err = png.Encode(file, result.Coverage.Image(img))
+		file.Close()
+		if err != nil {
+			return coverageWriteError(err, path)
+		}
+	}
+
+	data, err := json.MarshalIndent(summary, "", "  ")
+	if err != nil {
+		return err
+	}
+	path := filepath.Join(dir, "coverage.json")
+	if err := os.WriteFile(path, append(data, '\n'), 0o644); err != nil {
+		return coverageWriteError(err, path)
+	}
+	fmt.Printf(i18n.T("test.coverage_written"), dir)
+	return nil
+}
+
+// coverageWriteError reports a coverage file that could not be written
+func coverageWriteError(err error, path string) error {
+	return grimoireErrors.NewError(grimoireErrors.FileWriteError, i18n.T("msg.failed_write_output")).
+		WithInnerError(err).
+		WithLocation(path, 0, 0)
+}
+
+// indentLines prefixes every line of s with indent
+func indentLines(s, indent string) string {
+	var sb strings.Builder
+	for _, line := range strings.SplitAfter(s, "\n") {
+		if line == "" {
+			continue
+		}
+		sb.WriteString(indent + line)
+	}
+	return sb.String()
+}
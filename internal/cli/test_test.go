@@ -0,0 +1,66 @@
+package cli
+
+import (
+	"bytes"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/spelltest"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestTestCommand(t *testing.T) {
+	data, err := os.ReadFile(filepath.Join("..", "..", "examples", "images", "hello_world.png"))
+	if err != nil {
+		t.Skip("Example image not found")
+	}
+
+	dir := t.TempDir()
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "pass.png"), data, 0o644))
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "pass.out"), []byte("Hello, World!\n"), 0o644))
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "fail.png"), data, 0o644))
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "fail.out"), []byte("Goodbye\n"), 0o644))
+
+	var runnerEnv []string
+	oldRunner := newSpellTestRunner
+	newSpellTestRunner = func(env ...string) *spelltest.Runner {
+		runnerEnv = env
+		return &spelltest.Runner{Execute: func(string) (string, error) { return "Hello, World!\n", nil }}
+	}
+	defer func() { newSpellTestRunner = oldRunner }()
+
+	oldArgs := os.Args
+	os.Args = []string{"grimoire", "test", dir, "--lang", "en", "--allow-fs"}
+	defer func() { os.Args = oldArgs }()
+	defer i18n.SetLanguage(i18n.GetLanguage())
+
+	oldStdout := os.Stdout
+	r, w, _ := os.Pipe()
+	os.Stdout = w
+
+	err = Execute("test", "test", "test")
+
+	w.Close()
+	os.Stdout = oldStdout
+
+	var buf bytes.Buffer
+	_, readErr := buf.ReadFrom(r)
+	require.NoError(t, readErr)
+	output := buf.String()
+
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "1 test case(s) failed")
+	assert.Contains(t, output, "PASS pass")
+	assert.Contains(t, output, "FAIL fail")
+	assert.Contains(t, output, "      - Goodbye\n      + Hello, World!\n")
+	assert.Contains(t, output, "1 passed, 1 failed, 2 total")
+	assert.Equal(t, []string{"GRIMOIRE_CAPABILITIES=fs"}, runnerEnv, "programs run with the granted capabilities")
+}
+
+func TestIndentLines(t *testing.T) {
+	assert.Equal(t, "  a\n  b\n", indentLines("a\nb\n", "  "))
+	assert.Equal(t, "", indentLines("", "  "))
+}
@@ -0,0 +1,26 @@
+package cli
+
+import (
+	"fmt"
+	"os"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/pkg/grimoire"
+)
+
+// printMetrics reports the metrics of a detection to stderr, so that the
+// output of the command is left as it is
+func printMetrics(m grimoire.Metrics) {
+	fmt.Fprint(os.Stderr, i18n.T("timings.header"))
+	for _, s := range m.Stages {
+		fmt.Fprintf(os.Stderr, i18n.T("timings.stage"), s.Stage, milliseconds(s.Duration.Seconds()))
+	}
+	fmt.Fprintf(os.Stderr, i18n.T("timings.total"), i18n.T("timings.total_label"), milliseconds(m.Total().Seconds()))
+	fmt.Fprintf(os.Stderr, i18n.T("timings.counts"), m.Contours, m.Candidates, m.Rejected, m.Accepted, m.Connections)
+	fmt.Fprintf(os.Stderr, i18n.T("timings.peak_heap"), float64(m.PeakHeapBytes)/(1<<20))
+}
+
+// milliseconds converts seconds to milliseconds
+func milliseconds(seconds float64) float64 {
+	return seconds * 1000
+}
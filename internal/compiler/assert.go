@@ -0,0 +1,66 @@
+package compiler
+
+import (
+	"fmt"
+
+	"github.com/ayutaz/grimoire/internal/parser"
+)
+
+// AssertionPrefix starts the line a failed assertion writes to stderr,
+// followed by the location of its equals sign and the differing values
+const AssertionPrefix = "assertion failed at "
+
+// assertionRuntime is emitted ahead of programs with assertions. A failed
+// assertion reports itself and lets the program go on, so that one run
+// shows every failure; the program then exits with status 1.
+var assertionRuntime = []string{
+	"import atexit",
+	"import os",
+	"import sys",
+	"",
+	"grimoire_failures = []",
+	"",
+	"",
+	"def grimoire_assert(left, right, location):",
+	"    if left != right:",
+	fmt.Sprintf("        message = %q %% (location, left, right)", AssertionPrefix+"%s: %r != %r"),
+	"        grimoire_failures.append(message)",
+	"        print(message, file=sys.stderr)",
+	"",
+	"",
+	"@atexit.register",
+	"def grimoire_report():",
+	"    if grimoire_failures:",
+	"        sys.stdout.flush()",
+	"        sys.stderr.flush()",
+	"        os._exit(1)",
+	"",
+}
+
+// hasAssertions reports whether any statement of program is an assertion
+func hasAssertions(program *parser.Program) bool {
+	bodies := [][]parser.Statement{program.Globals}
+	for _, fn := range program.Functions {
+		bodies = append(bodies, fn.Body)
+	}
+	if program.MainEntry != nil {
+		bodies = append(bodies, program.MainEntry.Body)
+	}
+
+	found := false
+	for _, body := range bodies {
+		walkStatements(body, func(stmt parser.Statement) {
+			if _, ok := stmt.(*parser.Assertion); ok {
+				found = true
+			}
+		}, nil)
+	}
+	return found
+}
+
+// compileAssertion compiles an assertion
+func (c *Compiler) compileAssertion(stmt *parser.Assertion) {
+	location := fmt.Sprintf("(%.0f, %.0f)", stmt.X, stmt.Y)
+	c.writeLine(fmt.Sprintf("grimoire_assert(%s, %s, %q)",
+		c.compileExpression(stmt.Left), c.compileExpression(stmt.Right), location))
+}
@@ -0,0 +1,117 @@
+package compiler
+
+import (
+	"fmt"
+
+	"github.com/ayutaz/grimoire/internal/parser"
+)
+
+// Built-in functions a program can call without defining them. A function
+// the program defines under the same name takes their place. The parser
+// emits calls to them for eight-pointed stars: env, read_file and
+// has_capability for the pattern drawn inside the star, and eval for an
+// empty star fed by a quote.
+const (
+	// BuiltinEnv returns the value of the environment variable named by its
+	// first argument, or its second argument when the variable is unset.
+	// Reading a variable needs the env capability for it.
+	BuiltinEnv = "env"
+	// BuiltinReadFile returns the text of the file named by its argument.
+	// Reading a file needs the fs capability.
+	BuiltinReadFile = "read_file"
+	// BuiltinHasCapability reports whether the capability named by its
+	// argument was granted to the program
+	BuiltinHasCapability = "has_capability"
+	// BuiltinEval runs the quoted program given as its argument
+	BuiltinEval = "eval"
+)
+
+// CapabilitiesVariable is the environment variable the runtime reads the
+// granted capabilities from, separated by commas: CapabilityEnv grants
+// every environment variable, CapabilityEnv followed by a colon and a name
+// grants only that variable, and CapabilityFS grants file system access.
+const CapabilitiesVariable = "GRIMOIRE_CAPABILITIES"
+
+// Capabilities a program can be granted
+const (
+	CapabilityEnv = "env"
+	CapabilityFS  = "fs"
+)
+
+// builtins maps each built-in function to the runtime function it calls
+var builtins = map[string]string{
+	BuiltinEnv:           "grimoire_env",
+	BuiltinReadFile:      "grimoire_read_file",
+	BuiltinHasCapability: "grimoire_has_capability",
+	BuiltinEval:          "grimoire_eval",
+}
+
+// capabilityRuntime is emitted ahead of programs calling env, read_file or
+// has_capability. Nothing is granted unless the variable says so, so a program
+// run by itself can read no environment variable.
+var capabilityRuntime = []string{
+	"import os",
+	"",
+	"",
+	"def grimoire_capabilities():",
+	fmt.Sprintf("    return [grant for grant in os.environ.get(%q, \"\").split(\",\") if grant]", CapabilitiesVariable),
+	"",
+	"",
+	"def grimoire_has_capability(name):",
+	"    return any(grant == name or grant.startswith(name + \":\") for grant in grimoire_capabilities())",
+	"",
+	"",
+	"def grimoire_env(name, default=None):",
+	"    grants = grimoire_capabilities()",
+	fmt.Sprintf("    if %q not in grants and %q + name not in grants:", CapabilityEnv, CapabilityEnv+":"),
+	"        raise PermissionError(\"reading the environment variable %s needs --allow-env\" % name)",
+	"    return os.environ.get(name, default)",
+	"",
+	"",
+	"def grimoire_read_file(path):",
+	fmt.Sprintf("    if %q not in grimoire_capabilities():", CapabilityFS),
+	"        raise PermissionError(\"reading the file %s needs --allow-fs\" % path)",
+	"    with open(path, encoding=\"utf-8\") as f:",
+	"        return f.read()",
+	"",
+	"",
+}
+
+// calledBuiltins returns the built-in functions program calls without
+// defining them, and the names of the functions it defines
+func calledBuiltins(program *parser.Program) (called, defined map[string]bool) {
+	defined = make(map[string]bool)
+	bodies := [][]parser.Statement{program.Globals}
+	for _, fn := range program.Functions {
+		defined[fn.Name] = true
+		bodies = append(bodies, fn.Body)
+	}
+	if program.MainEntry != nil {
+		if !program.MainEntry.IsMain {
+			defined[program.MainEntry.Name] = true
+		}
+		bodies = append(bodies, program.MainEntry.Body)
+	}
+
+	called = make(map[string]bool)
+	for _, body := range bodies {
+		walkStatements(body, nil, func(expr parser.Expression) {
+			if call, ok := expr.(*parser.FunctionCall); ok && call.Function != nil {
+				if _, builtin := builtins[call.Function.Name]; builtin && !defined[call.Function.Name] {
+					called[call.Function.Name] = true
+				}
+			}
+		})
+	}
+	return called, defined
+}
+
+// builtin returns the runtime function a call to name compiles to, if name
+// is a built-in function the program does not define
+func (c *Compiler) builtin(name string) (string, bool) {
+	if c.defined[name] {
+		return "", false
+	}
+	runtime, ok := builtins[name]
+	return runtime, ok
+}
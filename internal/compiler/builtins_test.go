@@ -0,0 +1,124 @@
+package compiler
+
+import (
+	"os"
+	"os/exec"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// envProgram prints the environment variable GRIMOIRE_TEST_NAME, or
+// "none" when it is unset, and whether the fs capability was granted
+func envProgram(functions ...*parser.FunctionDef) *parser.Program {
+	return builtinProgram(functions,
+		callBuiltin(BuiltinEnv, stringLiteral("GRIMOIRE_TEST_NAME"), stringLiteral("none")),
+		callBuiltin(BuiltinHasCapability, stringLiteral(CapabilityFS)))
+}
+
+// builtinProgram prints the values of the calls given
+func builtinProgram(functions []*parser.FunctionDef, calls ...*parser.FunctionCall) *parser.Program {
+	body := make([]parser.Statement, len(calls))
+	for i, c := range calls {
+		body[i] = &parser.OutputStatement{Value: c}
+	}
+	return &parser.Program{
+		HasOuterCircle: true,
+		Functions:      functions,
+		MainEntry:      &parser.FunctionDef{IsMain: true, Body: body},
+	}
+}
+
+// callBuiltin calls the function name with args
+func callBuiltin(name string, args ...parser.Expression) *parser.FunctionCall {
+	return &parser.FunctionCall{Function: &parser.Identifier{Name: name}, Arguments: args}
+}
+
+// stringLiteral is a string literal of value
+func stringLiteral(value string) *parser.Literal {
+	return &parser.Literal{Value: value, LiteralType: parser.String}
+}
+
+func TestCompileBuiltins(t *testing.T) {
+	code, err := Compile(envProgram())
+	require.NoError(t, err)
+	assert.Contains(t, code, "def grimoire_env(name, default=None):")
+	assert.Contains(t, code, `print(grimoire_env("GRIMOIRE_TEST_NAME", "none"))`)
+	assert.Contains(t, code, `print(grimoire_has_capability("fs"))`)
+
+	// A function of the same name takes the place of the built-in
+	own := &parser.FunctionDef{Name: BuiltinEnv}
+	code, err = Compile(envProgram(own))
+	require.NoError(t, err)
+	assert.Contains(t, code, `print(env("GRIMOIRE_TEST_NAME", "none"))`)
+	assert.Contains(t, code, "def grimoire_has_capability(name):", "has_capability is still built in")
+
+	code, err = Compile(&parser.Program{HasOuterCircle: true, MainEntry: &parser.FunctionDef{IsMain: true}})
+	require.NoError(t, err)
+	assert.NotContains(t, code, "grimoire_env", "programs without built-ins get no runtime")
+}
+
+func TestRunBuiltins(t *testing.T) {
+	if _, err := exec.LookPath("python3"); err != nil {
+		t.Skip("Python3 not available")
+	}
+	code, err := Compile(envProgram())
+	require.NoError(t, err)
+	path := t.TempDir() + "/program.py"
+	require.NoError(t, os.WriteFile(path, []byte(code), 0o600))
+
+	run := func(capabilities string) (string, error) {
+		cmd := exec.Command("python3", path)
+		cmd.Env = append(os.Environ(), "GRIMOIRE_TEST_NAME=grimoire", CapabilitiesVariable+"="+capabilities)
+		out, err := cmd.CombinedOutput()
+		return string(out), err
+	}
+
+	out, err := run("")
+	assert.Error(t, err, "nothing is granted by default")
+	assert.Contains(t, out, "needs --allow-env")
+
+	out, err = run("env:GRIMOIRE_TEST_NAME")
+	require.NoError(t, err, out)
+	assert.Equal(t, "grimoire\nFalse\n", out)
+
+	out, err = run("env:OTHER,fs")
+	assert.Error(t, err, "only the variables granted can be read")
+	assert.Contains(t, out, "needs --allow-env")
+
+	out, err = run("env,fs")
+	require.NoError(t, err, out)
+	assert.Equal(t, "grimoire\nTrue\n", out)
+}
+
+func TestRunReadFile(t *testing.T) {
+	if _, err := exec.LookPath("python3"); err != nil {
+		t.Skip("Python3 not available")
+	}
+	dir := t.TempDir()
+	spell := dir + "/spell.txt"
+	require.NoError(t, os.WriteFile(spell, []byte("abracadabra"), 0o600))
+
+	code, err := Compile(builtinProgram(nil, callBuiltin(BuiltinReadFile, stringLiteral(spell))))
+	require.NoError(t, err)
+	assert.Contains(t, code, "def grimoire_read_file(path):")
+	path := dir + "/program.py"
+	require.NoError(t, os.WriteFile(path, []byte(code), 0o600))
+
+	run := func(capabilities string) (string, error) {
+		cmd := exec.Command("python3", path)
+		cmd.Env = append(os.Environ(), CapabilitiesVariable+"="+capabilities)
+		out, err := cmd.CombinedOutput()
+		return string(out), err
+	}
+
+	out, err := run("env")
+	assert.Error(t, err, "reading files needs the fs capability")
+	assert.Contains(t, out, "needs --allow-fs")
+
+	out, err = run("fs")
+	require.NoError(t, err, out)
+	assert.Equal(t, "abracadabra\n", out)
+}
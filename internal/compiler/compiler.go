@@ -6,24 +6,57 @@ import (
 
 	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
 	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/ident"
 	"github.com/ayutaz/grimoire/internal/parser"
 )
 
 // Compiler generates code from AST
 type Compiler struct {
-	indent    int
-	indentStr string
-	output    strings.Builder
+	indent       int
+	indentStr    string
+	output       strings.Builder
+	line         int
+	sourceMap    map[parser.ASTNode]LineRange
+	names        *ident.Namer // Python names of the identifiers in the AST
+	maxRecursion int          // call depth allowed to programs with recursive functions
+	optimization int          // optimization level; 1 and above inline and simplify
+	tailCalls    map[*parser.ExpressionStatement]bool
+	defined      map[string]bool // functions the program defines, which shadow built-ins
+}
+
+// DefaultMaxRecursion is the call depth allowed to programs with recursive
+// functions, matching the default limit of Python
+const DefaultMaxRecursion = 1000
+
+// LineRange is a 1-based, inclusive range of generated lines
+type LineRange struct {
+	Start int
+	End   int
 }
 
 // NewCompiler creates a new compiler
 func NewCompiler() *Compiler {
 	return &Compiler{
-		indent:    0,
-		indentStr: "    ", // 4 spaces
+		indent:       0,
+		indentStr:    "    ", // 4 spaces
+		names:        ident.NewNamer(),
+		maxRecursion: DefaultMaxRecursion,
 	}
 }
 
+// SetMaxRecursion sets the call depth allowed to programs with recursive
+// functions. Deeper calls stop the program with a RecursionError.
+func (c *Compiler) SetMaxRecursion(depth int) {
+	c.maxRecursion = depth
+}
+
+// SetOptimization sets the optimization level. Level 1 and above inline
+// small functions called from a single place (see Inline), then simplify
+// the result (see Peephole).
+func (c *Compiler) SetOptimization(level int) {
+	c.optimization = level
+}
+
 // Compile generates Python code from AST
 func Compile(ast *parser.Program) (string, error) {
 	compiler := NewCompiler()
@@ -33,6 +66,9 @@ func Compile(ast *parser.Program) (string, error) {
 // Compile performs the compilation
 func (c *Compiler) Compile(ast *parser.Program) (string, error) {
 	c.output.Reset()
+	c.line = 0
+	c.sourceMap = make(map[parser.ASTNode]LineRange)
+	c.names = ident.NewNamer()
 
 	// Validate AST
 	if ast == nil {
@@ -44,11 +80,46 @@ func (c *Compiler) Compile(ast *parser.Program) (string, error) {
 		return "", grimoireErrors.NoOuterCircleError()
 	}
 
+	if c.optimization >= 1 {
+		ast = Peephole(Inline(ast))
+	}
+
 	// Add header comment
 	c.writeLine("#!/usr/bin/env python3")
 	c.writeLine("# Generated by Grimoire")
 	c.writeLine("")
 
+	// Functions that call themselves, directly or through others, run
+	// under an explicit depth limit
+	if len(parser.BuildCallGraph(ast).Cycles()) > 0 {
+		c.writeLine("import sys")
+		c.writeLine(fmt.Sprintf("sys.setrecursionlimit(%d)", c.maxRecursion))
+		c.writeLine("")
+	}
+
+	// Assertions report through a small runtime
+	if hasAssertions(ast) {
+		for _, line := range assertionRuntime {
+			c.writeLine(line)
+		}
+	}
+
+	// Built-in functions call into a small runtime
+	called, defined := calledBuiltins(ast)
+	c.defined = defined
+	if called[BuiltinEnv] || called[BuiltinReadFile] || called[BuiltinHasCapability] {
+		for _, line := range capabilityRuntime {
+			c.writeLine(line)
+		}
+	}
+
+	// Quoted programs are evaluated by another
+	if called[BuiltinEval] || hasQuotes(ast) {
+		for _, line := range quoteRuntime {
+			c.writeLine(line)
+		}
+	}
+
 	// Compile globals
 	for _, stmt := range ast.Globals {
 		if err := c.compileStatement(stmt); err != nil {
@@ -66,6 +137,7 @@ func (c *Compiler) Compile(ast *parser.Program) (string, error) {
 	if ast.MainEntry != nil {
 		if ast.MainEntry.IsMain {
 			// Generate main block
+			start := c.line + 1
 			c.writeLine("if __name__ == \"__main__\":")
 			c.indent++
 			for _, stmt := range ast.MainEntry.Body {
@@ -74,6 +146,7 @@ func (c *Compiler) Compile(ast *parser.Program) (string, error) {
 				}
 			}
 			c.indent--
+			c.mapLines(ast.MainEntry, start)
 		} else {
 			c.compileFunction(ast.MainEntry)
 		}
@@ -86,8 +159,29 @@ func (c *Compiler) Compile(ast *parser.Program) (string, error) {
 	return c.output.String(), nil
 }
 
+// SourceMap returns the generated lines for each statement and function
+// compiled by the last Compile call
+func (c *Compiler) SourceMap() map[parser.ASTNode]LineRange {
+	return c.sourceMap
+}
+
+// mapLines records the lines generated for node since line start
+func (c *Compiler) mapLines(node parser.ASTNode, start int) {
+	if c.sourceMap != nil && c.line >= start {
+		c.sourceMap[node] = LineRange{Start: start, End: c.line}
+	}
+}
+
+// identifier returns the Python name of an identifier in the AST. Names
+// that are not valid Python, such as Japanese labels, are mangled, and
+// distinct names that mangle alike are kept apart.
+func (c *Compiler) identifier(name string) string {
+	return c.names.Name(name)
+}
+
 // writeLine writes a line with proper indentation
 func (c *Compiler) writeLine(line string) {
+	c.line++
 	if line == "" {
 		c.output.WriteString("\n")
 		return
@@ -102,20 +196,32 @@ func (c *Compiler) writeLine(line string) {
 
 // compileFunction compiles a function definition
 func (c *Compiler) compileFunction(fn *parser.FunctionDef) {
+	defer c.mapLines(fn, c.line+1)
+
 	// Function header
 	name := fn.Name
 	if name == "" {
 		name = "anonymous_func"
 	}
+	name = c.identifier(name)
 
 	params := []string{}
 	for _, p := range fn.Parameters {
-		params = append(params, p.Name)
+		params = append(params, c.identifier(p.Name))
 	}
 
 	c.writeLine(fmt.Sprintf("def %s(%s):", name, strings.Join(params, ", ")))
 	c.indent++
 
+	// A function calling itself last loops back to its start instead, so
+	// tail recursion runs in constant stack space
+	c.tailCalls = tailSelfCalls(fn)
+	loops := len(c.tailCalls) > 0
+	if loops {
+		c.writeLine("while True:")
+		c.indent++
+	}
+
 	// Function body
 	if len(fn.Body) == 0 {
 		c.writeLine("pass")
@@ -123,19 +229,25 @@ func (c *Compiler) compileFunction(fn *parser.FunctionDef) {
 		for _, stmt := range fn.Body {
 			if err := c.compileStatement(stmt); err != nil {
 				// Add error context
-				return
+				break
 			}
 		}
 	}
 
+	if loops {
+		c.writeLine("return")
+		c.indent--
+	}
+	c.tailCalls = nil
 	c.indent--
 }
 
 // compileStatement compiles a statement
 func (c *Compiler) compileStatement(stmt parser.Statement) error {
 	if stmt == nil {
-		return grimoireErrors.NewError(grimoireErrors.CompilationError, "Cannot compile nil statement")
+		return grimoireErrors.NewError(grimoireErrors.CompilationError, i18n.T("error.cannot_compile_nil_statement"))
 	}
+	defer c.mapLines(stmt, c.line+1)
 
 	switch s := stmt.(type) {
 	case *parser.OutputStatement:
@@ -150,14 +262,25 @@ func (c *Compiler) compileStatement(stmt parser.Statement) error {
 		c.compileWhileLoop(s)
 	case *parser.ParallelBlock:
 		c.compileParallelBlock(s)
+	case *parser.Block:
+		c.compileBlock(s)
+	case *parser.Stage:
+		c.compileStage(s)
+	case *parser.Mirror:
+		c.compileMirror(s)
+	case *parser.Assertion:
+		c.compileAssertion(s)
 	case *parser.ReturnStatement:
 		c.compileReturnStatement(s)
 	case *parser.ExpressionStatement:
-		expr := c.compileExpression(s.Expression)
-		c.writeLine(expr)
+		if c.tailCalls[s] {
+			c.writeLine("continue")
+		} else {
+			c.writeLine(c.compileExpression(s.Expression))
+		}
 	default:
 		return grimoireErrors.NewError(grimoireErrors.UnsupportedOperation,
-			fmt.Sprintf("Unsupported statement type: %T", stmt))
+			i18n.Tf("error.unsupported_statement", stmt))
 	}
 	return nil
 }
@@ -170,7 +293,7 @@ func (c *Compiler) compileOutputStatement(stmt *parser.OutputStatement) {
 
 // compileAssignment compiles an assignment
 func (c *Compiler) compileAssignment(stmt *parser.Assignment) {
-	target := stmt.Target.Name
+	target := c.identifier(stmt.Target.Name)
 	value := c.compileExpression(stmt.Value)
 	c.writeLine(fmt.Sprintf("%s = %s", target, value))
 }
@@ -206,7 +329,7 @@ func (c *Compiler) compileIfStatement(stmt *parser.IfStatement) {
 
 // compileForLoop compiles a for loop
 func (c *Compiler) compileForLoop(stmt *parser.ForLoop) {
-	counter := stmt.Counter.Name
+	counter := c.identifier(stmt.Counter.Name)
 	start := c.compileExpression(stmt.Start)
 	end := c.compileExpression(stmt.End)
 
@@ -249,6 +372,56 @@ func (c *Compiler) compileWhileLoop(stmt *parser.WhileLoop) {
 	c.indent--
 }
 
+// compileBlock compiles a block in place, marking where it begins
+func (c *Compiler) compileBlock(stmt *parser.Block) {
+	c.writeLine("# Block")
+	if len(stmt.Body) == 0 {
+		c.writeLine("pass")
+		return
+	}
+	for _, s := range stmt.Body {
+		if err := c.compileStatement(s); err != nil {
+			return
+		}
+	}
+}
+
+// compileStage compiles a stage in place, marking where it begins. Stages
+// follow each other in the program, so they run in order.
+func (c *Compiler) compileStage(stmt *parser.Stage) {
+	c.writeLine(fmt.Sprintf("# Stage %d", stmt.Number))
+	if len(stmt.Body) == 0 {
+		c.writeLine("pass")
+		return
+	}
+	for _, s := range stmt.Body {
+		if err := c.compileStatement(s); err != nil {
+			return
+		}
+	}
+}
+
+// compileMirror compiles a mirror as a loop over its operands
+func (c *Compiler) compileMirror(stmt *parser.Mirror) {
+	operands := make([]string, 0, len(stmt.Operands))
+	for _, operand := range stmt.Operands {
+		operands = append(operands, c.compileExpression(operand))
+	}
+	c.writeLine(fmt.Sprintf("for %s in [%s]:", c.identifier(stmt.Item.Name), strings.Join(operands, ", ")))
+
+	c.indent++
+	if len(stmt.Body) == 0 {
+		c.writeLine("pass")
+	} else {
+		for _, s := range stmt.Body {
+			if err := c.compileStatement(s); err != nil {
+				return
+			}
+		}
+	}
+	c.indent--
+}
+
 // compileParallelBlock compiles parallel execution (using threading)
 func (c *Compiler) compileParallelBlock(stmt *parser.ParallelBlock) {
 	c.writeLine("import threading")
@@ -297,13 +470,15 @@ func (c *Compiler) compileExpression(expr parser.Expression) string {
 	case *parser.Literal:
 		return c.compileLiteral(e)
 	case *parser.Identifier:
-		return e.Name
+		return c.identifier(e.Name)
 	case *parser.FunctionCall:
 		return c.compileFunctionCall(e)
 	case *parser.ArrayLiteral:
 		return c.compileArrayLiteral(e)
 	case *parser.MapLiteral:
 		return c.compileMapLiteral(e)
+	case *parser.Quoted:
+		return c.compileQuoted(e)
 	default:
 		// Log warning for unsupported expression type
 		if expr != nil {
@@ -384,7 +559,10 @@ func (c *Compiler) compileFunctionCall(call *parser.FunctionCall) string {
 	for _, arg := range call.Arguments {
 		args = append(args, c.compileExpression(arg))
 	}
-	return fmt.Sprintf("%s(%s)", call.Function.Name, strings.Join(args, ", "))
+	if runtime, ok := c.builtin(call.Function.Name); ok {
+		return fmt.Sprintf("%s(%s)", runtime, strings.Join(args, ", "))
+	}
+	return fmt.Sprintf("%s(%s)", c.identifier(call.Function.Name), strings.Join(args, ", "))
 }
 
 // compileArrayLiteral compiles an array literal
@@ -1,9 +1,12 @@
 package compiler
 
 import (
+	"math"
+	"os/exec"
 	"strings"
 	"testing"
 
+	"github.com/ayutaz/grimoire/internal/i18n"
 	"github.com/ayutaz/grimoire/internal/parser"
 	"github.com/stretchr/testify/assert"
 	"github.com/stretchr/testify/require"
@@ -290,6 +293,442 @@ func TestCompile_ParallelBlock(t *testing.T) {
 	assert.Contains(t, code, "t.join()")
 }
 
+// TestCompile_Stages tests that stages compile in order, each marked
+func TestCompile_Stages(t *testing.T) {
+	ast := &parser.Program{
+		HasOuterCircle: true,
+		MainEntry: &parser.FunctionDef{
+			IsMain: true,
+			Body: []parser.Statement{
+				&parser.Stage{
+					Number: 1,
+					Body: []parser.Statement{
+						&parser.OutputStatement{Value: &parser.Literal{Value: "inner", LiteralType: parser.String}},
+					},
+				},
+				&parser.Stage{
+					Number: 2,
+					Body: []parser.Statement{
+						&parser.OutputStatement{Value: &parser.Literal{Value: "outer", LiteralType: parser.String}},
+					},
+				},
+				&parser.Stage{Number: 3},
+			},
+		},
+	}
+
+	code, err := Compile(ast)
+
+	require.NoError(t, err)
+	assert.Contains(t, code, "    # Stage 1\n    print(\"inner\")\n    # Stage 2\n    print(\"outer\")\n")
+	assert.Contains(t, code, "    # Stage 3\n    pass\n", "an empty stage is still valid Python")
+}
+
+// TestCompile_Block tests that a block compiles in place, marked
+func TestCompile_Block(t *testing.T) {
+	ast := &parser.Program{
+		HasOuterCircle: true,
+		MainEntry: &parser.FunctionDef{
+			IsMain: true,
+			Body: []parser.Statement{
+				&parser.Block{
+					Body: []parser.Statement{
+						&parser.OutputStatement{Value: &parser.Literal{Value: "inside", LiteralType: parser.String}},
+					},
+				},
+				&parser.Block{},
+			},
+		},
+	}
+
+	code, err := Compile(ast)
+
+	require.NoError(t, err)
+	assert.Contains(t, code, "    # Block\n    print(\"inside\")\n")
+	assert.Contains(t, code, "    # Block\n    pass\n", "an empty block is still valid Python")
+}
+
+// TestCompile_Quoted tests that a quoted program compiles to its source,
+// evaluated in a namespace of its own
+func TestCompile_Quoted(t *testing.T) {
+	quoted := &parser.Quoted{Program: &parser.Program{
+		HasOuterCircle: true,
+		MainEntry: &parser.FunctionDef{
+			IsMain: true,
+			Body:   []parser.Statement{&parser.OutputStatement{Value: &parser.Literal{Value: "quoted", LiteralType: parser.String}}},
+		},
+	}}
+	ast := &parser.Program{
+		HasOuterCircle: true,
+		MainEntry: &parser.FunctionDef{
+			IsMain: true,
+			Body: []parser.Statement{
+				&parser.Assignment{Target: &parser.Identifier{Name: "q"}, Value: quoted},
+				&parser.ExpressionStatement{Expression: &parser.FunctionCall{
+					Function:  &parser.Identifier{Name: BuiltinEval},
+					Arguments: []parser.Expression{&parser.Identifier{Name: "q"}},
+				}},
+				&parser.OutputStatement{Value: &parser.Quoted{}},
+			},
+		},
+	}
+
+	code, err := Compile(ast)
+	require.NoError(t, err)
+	assert.Contains(t, code, "def grimoire_eval(quote):")
+	assert.Contains(t, code, `    q = GrimoireQuote("#!/usr/bin/env python3\n# Generated by Grimoire\n\nif __name__ == \"__main__\":\n    print(\"quoted\")\n")`)
+	assert.Contains(t, code, "    grimoire_eval(q)\n")
+	assert.Contains(t, code, `    print(GrimoireQuote(""))`, "an empty quote evaluates to nothing")
+	assert.NotContains(t, code, "grimoire_env", "eval needs no capabilities")
+
+	if _, err := exec.LookPath("python3"); err != nil {
+		t.Skip("Python3 not available")
+	}
+	out, err := exec.Command("python3", "-c", code).CombinedOutput()
+	require.NoError(t, err, string(out))
+	assert.Equal(t, "quoted\n<quoted program>\n", string(out))
+}
+
+// TestCompile_RecursionGuard tests that programs with recursive functions run under a depth limit
+func TestCompile_RecursionGuard(t *testing.T) {
+	call := func(name string) parser.Statement {
+		return &parser.ExpressionStatement{Expression: &parser.FunctionCall{Function: &parser.Identifier{Name: name}}}
+	}
+	ast := &parser.Program{
+		HasOuterCircle: true,
+		Functions: []*parser.FunctionDef{
+			{Name: "even", Body: []parser.Statement{call("odd")}},
+			{Name: "odd", Body: []parser.Statement{call("even")}},
+		},
+	}
+
+	code, err := Compile(ast)
+	require.NoError(t, err)
+	assert.Contains(t, code, "import sys\nsys.setrecursionlimit(1000)\n")
+	assert.Contains(t, code, "def even():\n    odd()\n")
+
+	compiler := NewCompiler()
+	compiler.SetMaxRecursion(50)
+	code, err = compiler.Compile(ast)
+	require.NoError(t, err)
+	assert.Contains(t, code, "sys.setrecursionlimit(50)")
+
+	ast.Functions[1].Body = nil
+	code, err = Compile(ast)
+	require.NoError(t, err)
+	assert.NotContains(t, code, "setrecursionlimit", "no guard without recursion")
+}
+
+// TestCompile_TailCalls tests that a function calling itself last loops instead of recursing
+func TestCompile_TailCalls(t *testing.T) {
+	call := func(name string) *parser.ExpressionStatement {
+		return &parser.ExpressionStatement{Expression: &parser.FunctionCall{Function: &parser.Identifier{Name: name}}}
+	}
+	one := &parser.Literal{Value: 1, LiteralType: parser.Integer}
+	ast := &parser.Program{
+		HasOuterCircle: true,
+		Functions: []*parser.FunctionDef{
+			{Name: "countdown", Body: []parser.Statement{
+				&parser.OutputStatement{Value: one},
+				&parser.IfStatement{
+					Condition:  &parser.Literal{Value: true, LiteralType: parser.Boolean},
+					ThenBranch: []parser.Statement{call("countdown")},
+					ElseBranch: []parser.Statement{call("print")},
+				},
+			}},
+			{Name: "again", Body: []parser.Statement{call("again"), &parser.OutputStatement{Value: one}}},
+		},
+	}
+
+	code, err := Compile(ast)
+	require.NoError(t, err)
+	assert.Contains(t, code, `def countdown():
+    while True:
+        print(1)
+        if True:
+            continue
+        else:
+            print()
+        return
+`)
+	assert.Contains(t, code, "def again():\n    again()\n    print(1)\n", "a call followed by other statements recurses")
+}
+
+// TestCompile_Inline tests that small functions called once are inlined at -O1
+func TestCompile_Inline(t *testing.T) {
+	call := func(name string, args ...parser.Expression) *parser.ExpressionStatement {
+		return &parser.ExpressionStatement{Expression: &parser.FunctionCall{
+			Function:  &parser.Identifier{Name: name},
+			Arguments: args,
+		}}
+	}
+	x := &parser.Identifier{Name: "x", DataType: parser.Integer}
+	one := &parser.Literal{Value: 1, LiteralType: parser.Integer}
+	ast := &parser.Program{
+		HasOuterCircle: true,
+		Functions: []*parser.FunctionDef{
+			{Name: "once", Body: []parser.Statement{
+				&parser.Assignment{Target: x, Value: one},
+				&parser.OutputStatement{Value: x},
+				call("nested"),
+			}},
+			{Name: "nested", Body: []parser.Statement{&parser.OutputStatement{Value: one}}},
+			{Name: "twice", Body: []parser.Statement{&parser.OutputStatement{Value: one}}},
+			{Name: "loop", Body: []parser.Statement{call("loop")}},
+			{
+				Name:       "show",
+				Parameters: []*parser.Parameter{{Name: "n", DataType: parser.Integer}},
+				Body:       []parser.Statement{&parser.OutputStatement{Value: &parser.Identifier{Name: "n"}}},
+			},
+		},
+		MainEntry: &parser.FunctionDef{
+			IsMain: true,
+			Body: []parser.Statement{
+				&parser.Assignment{Target: x, Value: &parser.Literal{Value: 2, LiteralType: parser.Integer}},
+				call("once"),
+				call("twice"),
+				call("twice"),
+				call("loop"),
+				call("show", x),
+				&parser.OutputStatement{Value: x},
+			},
+		},
+	}
+
+	plain, err := Compile(ast)
+	require.NoError(t, err)
+	assert.Contains(t, plain, "def once():", "level 0 inlines nothing")
+
+	c := NewCompiler()
+	c.SetOptimization(1)
+	code, err := c.Compile(ast)
+	require.NoError(t, err)
+	assert.Contains(t, code, `if __name__ == "__main__":
+    x = 2
+    once_x = 1
+    print(once_x)
+    print(1)
+    twice()
+    twice()
+    loop()
+    show_n = x
+    print(show_n)
+    print(x)
+`)
+	assert.NotContains(t, code, "def once():")
+	assert.NotContains(t, code, "def nested():", "calls in an inlined body are inlined too")
+	assert.NotContains(t, code, "def show(")
+	assert.Contains(t, code, "def twice():", "functions called twice stay")
+	assert.Contains(t, code, "def loop():", "recursive functions stay")
+	assert.Len(t, ast.Functions, 5, "the program is not changed")
+}
+
+// TestInline_Scope tests that a function is not inlined where a variable of
+// the caller would hide a global it reads
+func TestInline_Scope(t *testing.T) {
+	total := &parser.Identifier{Name: "total", DataType: parser.Integer}
+	ast := &parser.Program{
+		HasOuterCircle: true,
+		Functions: []*parser.FunctionDef{
+			{Name: "report", Body: []parser.Statement{&parser.OutputStatement{Value: total}}},
+			{Name: "caller", Body: []parser.Statement{
+				&parser.Assignment{Target: total, Value: &parser.Literal{Value: 0, LiteralType: parser.Integer}},
+				&parser.ExpressionStatement{Expression: &parser.FunctionCall{Function: &parser.Identifier{Name: "report"}}},
+			}},
+		},
+	}
+
+	assert.Same(t, ast, Inline(ast))
+}
+
+// TestPeephole tests that constants are folded, dead stores dropped and
+// constant branches threaded
+func TestPeephole(t *testing.T) {
+	integer := func(v int) *parser.Literal { return &parser.Literal{Value: v, LiteralType: parser.Integer} }
+	assign := func(name string, value parser.Expression) *parser.Assignment {
+		return &parser.Assignment{Target: &parser.Identifier{Name: name}, Value: value}
+	}
+	output := func(name string) *parser.OutputStatement {
+		return &parser.OutputStatement{Value: &parser.Identifier{Name: name}}
+	}
+	x := &parser.Identifier{Name: "x"}
+	ast := &parser.Program{
+		HasOuterCircle: true,
+		MainEntry: &parser.FunctionDef{
+			IsMain: true,
+			Body: []parser.Statement{
+				assign("x", &parser.BinaryOp{
+					Left:     &parser.BinaryOp{Left: integer(1), Operator: parser.Add, Right: integer(2)},
+					Operator: parser.Add,
+					Right:    integer(3),
+				}),
+				assign("x", &parser.BinaryOp{Left: x, Operator: parser.Multiply, Right: integer(2)}),
+				assign("y", integer(5)),
+				output("x"),
+				assign("y", integer(7)),
+				output("y"),
+				&parser.IfStatement{
+					Condition:  &parser.BinaryOp{Left: integer(1), Operator: parser.LessThan, Right: integer(2)},
+					ThenBranch: []parser.Statement{output("x")},
+					ElseBranch: []parser.Statement{&parser.OutputStatement{Value: integer(0)}},
+				},
+				&parser.WhileLoop{
+					Condition: &parser.Literal{Value: false, LiteralType: parser.Boolean},
+					Body:      []parser.Statement{output("y")},
+				},
+				assign("z", &parser.FunctionCall{Function: &parser.Identifier{Name: "f"}}),
+				assign("z", integer(1)),
+				output("z"),
+			},
+		},
+	}
+	count := func(program *parser.Program) int {
+		n := 0
+		walkStatements(program.MainEntry.Body, func(parser.Statement) { n++ }, nil)
+		return n
+	}
+
+	simplified := Peephole(ast)
+
+	assert.Equal(t, 14, count(ast), "the program is not changed")
+	assert.Equal(t, 9, count(simplified))
+
+	c := NewCompiler()
+	c.SetOptimization(1)
+	code, err := c.Compile(ast)
+	require.NoError(t, err)
+	assert.Contains(t, code, `if __name__ == "__main__":
+    x = 6
+    x = (x * 2)
+    print(x)
+    y = 7
+    print(y)
+    print(x)
+    z = f()
+    z = 1
+    print(z)
+`)
+}
+
+// TestPeepholeOverflow tests that integer operations overflowing a Go int
+// are left for Python to compute
+func TestPeepholeOverflow(t *testing.T) {
+	integer := func(v int) *parser.Literal { return &parser.Literal{Value: v, LiteralType: parser.Integer} }
+	tests := []struct {
+		name        string
+		left, right int
+		op          parser.OperatorType
+		folded      bool
+		want        int
+	}{
+		{"sum", math.MaxInt - 1, 1, parser.Add, true, math.MaxInt},
+		{"overflowing sum", math.MaxInt, 1, parser.Add, false, 0},
+		{"underflowing sum", math.MinInt, -1, parser.Add, false, 0},
+		{"difference", math.MinInt + 1, 1, parser.Subtract, true, math.MinInt},
+		{"overflowing difference", math.MinInt, 1, parser.Subtract, false, 0},
+		{"negated minimum", 0, math.MinInt, parser.Subtract, false, 0},
+		{"product", math.MinInt / 2, 2, parser.Multiply, true, math.MinInt},
+		{"overflowing product", math.MaxInt/2 + 1, 2, parser.Multiply, false, 0},
+		{"overflowing negative product", math.MinInt/2 - 1, 2, parser.Multiply, false, 0},
+		{"minimum times -1", math.MinInt, -1, parser.Multiply, false, 0},
+		{"product with zero", math.MinInt, 0, parser.Multiply, true, 0},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			folded := foldBinary(tt.op, integer(tt.left), integer(tt.right))
+			if !tt.folded {
+				assert.Nil(t, folded)
+				return
+			}
+			assert.Equal(t, integer(tt.want), folded)
+		})
+	}
+}
+
+// TestCompile_Mirror tests that a mirror loops over its operands
+func TestCompile_Mirror(t *testing.T) {
+	item := &parser.Identifier{Name: "mirrored", DataType: parser.Integer}
+	ast := &parser.Program{
+		HasOuterCircle: true,
+		MainEntry: &parser.FunctionDef{
+			IsMain: true,
+			Body: []parser.Statement{
+				&parser.Mirror{
+					Item: item,
+					Operands: []parser.Expression{
+						&parser.Literal{Value: 1, LiteralType: parser.Integer},
+						&parser.Literal{Value: 2, LiteralType: parser.Integer},
+					},
+					Body: []parser.Statement{&parser.OutputStatement{Value: item}},
+				},
+			},
+		},
+	}
+
+	code, err := Compile(ast)
+
+	require.NoError(t, err)
+	assert.Contains(t, code, "    for mirrored in [1, 2]:\n        print(mirrored)\n")
+}
+
+func TestCompile_Assertion(t *testing.T) {
+	ast := &parser.Program{
+		HasOuterCircle: true,
+		MainEntry: &parser.FunctionDef{
+			IsMain: true,
+			Body: []parser.Statement{
+				&parser.Assertion{
+					Left:  &parser.Literal{Value: 1, LiteralType: parser.Integer},
+					Right: &parser.Literal{Value: 2, LiteralType: parser.Integer},
+					X:     200,
+					Y:     150,
+				},
+			},
+		},
+	}
+
+	code, err := Compile(ast)
+
+	require.NoError(t, err)
+	assert.Contains(t, code, "def grimoire_assert(left, right, location):\n")
+	assert.Contains(t, code, `    grimoire_assert(1, 2, "(200, 150)")`+"\n")
+
+	plain, err := Compile(&parser.Program{HasOuterCircle: true})
+	require.NoError(t, err)
+	assert.NotContains(t, plain, "grimoire_assert", "the runtime is only emitted when needed")
+}
+
+// TestCompile_MangledIdentifiers tests that names Python cannot use are mangled consistently
+func TestCompile_MangledIdentifiers(t *testing.T) {
+	ast := &parser.Program{
+		HasOuterCircle: true,
+		MainEntry: &parser.FunctionDef{
+			IsMain: true,
+			Body: []parser.Statement{
+				&parser.Assignment{
+					Target: &parser.Identifier{Name: "合計"},
+					Value:  &parser.Literal{Value: 1, LiteralType: parser.Integer},
+				},
+				&parser.Assignment{
+					Target: &parser.Identifier{Name: "class"},
+					Value:  &parser.Identifier{Name: "合計"},
+				},
+				&parser.OutputStatement{Value: &parser.Identifier{Name: "class"}},
+			},
+		},
+		Functions: []*parser.FunctionDef{{Name: "あいさつ"}},
+	}
+
+	code, err := Compile(ast)
+
+	require.NoError(t, err)
+	assert.Contains(t, code, "def aisatsu():")
+	assert.Contains(t, code, "u5408_u8a08 = 1")
+	assert.Contains(t, code, "class_ = u5408_u8a08")
+	assert.Contains(t, code, "print(class_)")
+	assert.NotContains(t, code, "合計")
+}
+
 // TestCompile_Functions tests function compilation
 func TestCompile_Functions(t *testing.T) {
 	ast := &parser.Program{
@@ -679,6 +1118,9 @@ func TestCompileExpression_Coverage(t *testing.T) {
 
 // TestCompileStatement_Coverage tests additional statement cases
 func TestCompileStatement_Coverage(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
 	compiler := NewCompiler()
 
 	tests := []struct {
@@ -804,3 +1246,40 @@ func TestCompile_WithFunctionReturn(t *testing.T) {
 	assert.Contains(t, code, "return (x * x)")
 	assert.Contains(t, code, "print(square(5))")
 }
+
+// TestCompile_SourceMap tests that generated lines are mapped back to AST nodes
+func TestCompile_SourceMap(t *testing.T) {
+	output := &parser.OutputStatement{
+		Value: &parser.Literal{Value: 1, LiteralType: parser.Integer},
+	}
+	branch := &parser.IfStatement{
+		Condition:  &parser.Literal{Value: true, LiteralType: parser.Boolean},
+		ThenBranch: []parser.Statement{output},
+	}
+	entry := &parser.FunctionDef{
+		IsMain: true,
+		Body:   []parser.Statement{branch},
+	}
+	ast := &parser.Program{HasOuterCircle: true, MainEntry: entry}
+
+	c := NewCompiler()
+	code, err := c.Compile(ast)
+	require.NoError(t, err)
+
+	lines := strings.Split(code, "\n")
+	sourceMap := c.SourceMap()
+
+	require.Contains(t, sourceMap, parser.ASTNode(output))
+	outputRange := sourceMap[output]
+	assert.Equal(t, outputRange.Start, outputRange.End)
+	assert.Equal(t, "        print(1)", lines[outputRange.Start-1])
+
+	require.Contains(t, sourceMap, parser.ASTNode(branch))
+	branchRange := sourceMap[branch]
+	assert.Equal(t, "    if True:", lines[branchRange.Start-1])
+	assert.Equal(t, outputRange.End, branchRange.End)
+
+	mainRange := sourceMap[entry]
+	assert.Equal(t, `if __name__ == "__main__":`, lines[mainRange.Start-1])
+	assert.Equal(t, branchRange.End, mainRange.End)
+}
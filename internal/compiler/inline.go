@@ -0,0 +1,463 @@
+package compiler
+
+import "github.com/ayutaz/grimoire/internal/parser"
+
+// maxInlineSize is the most statements, nested ones included, a function
+// may have and still be inlined
+const maxInlineSize = 8
+
+// Inline returns a copy of program in which each small function called from
+// a single place, as a statement of its own, is replaced at that place by
+// its body, and its definition dropped. Parameters become assignments of
+// the arguments, and the variables the function assigns are renamed after
+// it, so they stay apart from those of the caller. Recursive functions and
+// functions that return a value are never inlined. program is not changed.
+func Inline(program *parser.Program) *parser.Program {
+	if program == nil {
+		return nil
+	}
+	in := newInliner(program)
+	if len(in.sites) == 0 {
+		return program
+	}
+
+	inlined := *program
+	inlined.Globals = in.statements(program.Globals)
+	if program.MainEntry != nil {
+		main := *program.MainEntry
+		main.Body = in.statements(main.Body)
+		inlined.MainEntry = &main
+	}
+	inlined.Functions = nil
+	for _, fn := range program.Functions {
+		if in.inlined[fn] {
+			continue
+		}
+		kept := *fn
+		kept.Body = in.statements(fn.Body)
+		inlined.Functions = append(inlined.Functions, &kept)
+	}
+	return &inlined
+}
+
+// inliner replaces calls by the bodies of the called functions
+type inliner struct {
+	sites   map[*parser.ExpressionStatement]*parser.FunctionDef // calls to replace, with the function called
+	inlined map[*parser.FunctionDef]bool
+}
+
+// newInliner picks the calls of program to replace
+func newInliner(program *parser.Program) *inliner {
+	in := &inliner{
+		sites:   make(map[*parser.ExpressionStatement]*parser.FunctionDef),
+		inlined: make(map[*parser.FunctionDef]bool),
+	}
+
+	functions := make(map[string]*parser.FunctionDef)
+	defined := make(map[string]int)
+	for _, fn := range program.Functions {
+		if fn.Name != "" {
+			functions[fn.Name] = fn
+			defined[fn.Name]++
+		}
+	}
+	recursive := make(map[string]bool)
+	for _, cycle := range parser.BuildCallGraph(program).Cycles() {
+		for _, name := range cycle {
+			recursive[name] = true
+		}
+	}
+
+	// Count the calls to each function, and remember where the last one was
+	// made from. A nil caller is the top level of the program.
+	type site struct {
+		stmt   *parser.ExpressionStatement
+		caller *parser.FunctionDef
+	}
+	calls := make(map[string]int)
+	sites := make(map[string]site)
+	record := func(caller *parser.FunctionDef, stmts []parser.Statement) {
+		walkStatements(stmts, func(stmt parser.Statement) {
+			if s, ok := stmt.(*parser.ExpressionStatement); ok {
+				if call, ok := s.Expression.(*parser.FunctionCall); ok && call.Function != nil {
+					sites[call.Function.Name] = site{stmt: s, caller: caller}
+				}
+			}
+		}, func(expr parser.Expression) {
+			if call, ok := expr.(*parser.FunctionCall); ok && call.Function != nil {
+				calls[call.Function.Name]++
+			}
+		})
+	}
+	record(nil, program.Globals)
+	for _, fn := range program.Functions {
+		record(fn, fn.Body)
+	}
+	if program.MainEntry != nil {
+		caller := program.MainEntry
+		if caller.IsMain {
+			caller = nil
+		}
+		record(caller, program.MainEntry.Body)
+	}
+
+	for name, fn := range functions {
+		s, ok := sites[name]
+		if !ok || calls[name] != 1 || defined[name] != 1 || recursive[name] {
+			continue
+		}
+		call := s.stmt.Expression.(*parser.FunctionCall)
+		if len(call.Arguments) != len(fn.Parameters) || !inlinable(fn) || !fitsScope(fn, s.caller) {
+			continue
+		}
+		in.sites[s.stmt] = fn
+		in.inlined[fn] = true
+	}
+	return in
+}
+
+// inlinable reports whether the body of fn is small enough to inline and
+// can run in place of the call
+func inlinable(fn *parser.FunctionDef) bool {
+	size, returns := 0, false
+	walkStatements(fn.Body, func(stmt parser.Statement) {
+		size++
+		if _, ok := stmt.(*parser.ReturnStatement); ok {
+			returns = true
+		}
+	}, nil)
+	return size <= maxInlineSize && !returns
+}
+
+// fitsScope reports whether the body of fn means the same inside caller. A
+// variable fn reads without assigning it is a global, which a variable of
+// the same name assigned in caller would hide. A nil caller is the top
+// level, where every variable is global.
+func fitsScope(fn, caller *parser.FunctionDef) bool {
+	if caller == nil {
+		return true
+	}
+	own := localNames(fn)
+	callerNames := localNames(caller)
+	fits := true
+	walkStatements(fn.Body, nil, func(expr parser.Expression) {
+		if id, ok := expr.(*parser.Identifier); ok && !own[id.Name] && callerNames[id.Name] {
+			fits = false
+		}
+	})
+	return fits
+}
+
+// localNames returns the parameters of fn and the variables it assigns
+func localNames(fn *parser.FunctionDef) map[string]bool {
+	names := make(map[string]bool)
+	for _, param := range fn.Parameters {
+		names[param.Name] = true
+	}
+	bind := func(id *parser.Identifier) {
+		if id != nil {
+			names[id.Name] = true
+		}
+	}
+	walkStatements(fn.Body, func(stmt parser.Statement) {
+		switch s := stmt.(type) {
+		case *parser.Assignment:
+			bind(s.Target)
+		case *parser.ForLoop:
+			bind(s.Counter)
+		case *parser.Mirror:
+			bind(s.Item)
+		}
+	}, nil)
+	return names
+}
+
+// statements returns stmts with the chosen calls replaced, copying the
+// statements that contain them
+func (in *inliner) statements(stmts []parser.Statement) []parser.Statement {
+	if stmts == nil {
+		return nil
+	}
+	out := make([]parser.Statement, 0, len(stmts))
+	for _, stmt := range stmts {
+		if s, ok := stmt.(*parser.ExpressionStatement); ok {
+			if fn := in.sites[s]; fn != nil {
+				out = append(out, in.expand(fn, s.Expression.(*parser.FunctionCall))...)
+				continue
+			}
+		}
+		out = append(out, in.statement(stmt))
+	}
+	return out
+}
+
+// statement returns stmt with the chosen calls in its body replaced
+func (in *inliner) statement(stmt parser.Statement) parser.Statement {
+	switch s := stmt.(type) {
+	case *parser.IfStatement:
+		c := *s
+		c.ThenBranch = in.statements(s.ThenBranch)
+		c.ElseBranch = in.statements(s.ElseBranch)
+		return &c
+	case *parser.ForLoop:
+		c := *s
+		c.Body = in.statements(s.Body)
+		return &c
+	case *parser.WhileLoop:
+		c := *s
+		c.Body = in.statements(s.Body)
+		return &c
+	case *parser.ParallelBlock:
+		c := *s
+		c.Branches = make([][]parser.Statement, len(s.Branches))
+		for i, branch := range s.Branches {
+			c.Branches[i] = in.statements(branch)
+		}
+		return &c
+	case *parser.Block:
+		c := *s
+		c.Body = in.statements(s.Body)
+		return &c
+	case *parser.Stage:
+		c := *s
+		c.Body = in.statements(s.Body)
+		return &c
+	case *parser.Mirror:
+		c := *s
+		c.Body = in.statements(s.Body)
+		return &c
+	}
+	return stmt
+}
+
+// expand returns the statements that replace call, a call to fn
+func (in *inliner) expand(fn *parser.FunctionDef, call *parser.FunctionCall) []parser.Statement {
+	rename := make(renamer)
+	for name := range localNames(fn) {
+		rename[name] = fn.Name + "_" + name
+	}
+
+	var out []parser.Statement
+	for i, param := range fn.Parameters {
+		out = append(out, &parser.Assignment{
+			Target: &parser.Identifier{Name: rename[param.Name], DataType: param.DataType},
+			Value:  call.Arguments[i],
+		})
+	}
+	// Calls inside the body are replaced first, as they are found by the
+	// statements of the original body
+	return append(out, rename.statements(in.statements(fn.Body))...)
+}
+
+// renamer copies statements with variables renamed from its keys to its
+// values
+type renamer map[string]string
+
+func (r renamer) identifier(id *parser.Identifier) *parser.Identifier {
+	if id == nil {
+		return nil
+	}
+	name, ok := r[id.Name]
+	if !ok {
+		return id
+	}
+	c := *id
+	c.Name = name
+	return &c
+}
+
+func (r renamer) statements(stmts []parser.Statement) []parser.Statement {
+	if stmts == nil {
+		return nil
+	}
+	out := make([]parser.Statement, len(stmts))
+	for i, stmt := range stmts {
+		out[i] = r.statement(stmt)
+	}
+	return out
+}
+
+func (r renamer) statement(stmt parser.Statement) parser.Statement {
+	switch s := stmt.(type) {
+	case *parser.OutputStatement:
+		c := *s
+		c.Value = r.expression(s.Value)
+		return &c
+	case *parser.Assignment:
+		c := *s
+		c.Target = r.identifier(s.Target)
+		c.Value = r.expression(s.Value)
+		return &c
+	case *parser.IfStatement:
+		c := *s
+		c.Condition = r.expression(s.Condition)
+		c.ThenBranch = r.statements(s.ThenBranch)
+		c.ElseBranch = r.statements(s.ElseBranch)
+		return &c
+	case *parser.ForLoop:
+		c := *s
+		c.Counter = r.identifier(s.Counter)
+		c.Start = r.expression(s.Start)
+		c.End = r.expression(s.End)
+		c.Step = r.expression(s.Step)
+		c.Body = r.statements(s.Body)
+		return &c
+	case *parser.WhileLoop:
+		c := *s
+		c.Condition = r.expression(s.Condition)
+		c.Body = r.statements(s.Body)
+		return &c
+	case *parser.ParallelBlock:
+		c := *s
+		c.Branches = make([][]parser.Statement, len(s.Branches))
+		for i, branch := range s.Branches {
+			c.Branches[i] = r.statements(branch)
+		}
+		return &c
+	case *parser.Block:
+		c := *s
+		c.Body = r.statements(s.Body)
+		return &c
+	case *parser.Stage:
+		c := *s
+		c.Body = r.statements(s.Body)
+		return &c
+	case *parser.Mirror:
+		c := *s
+		c.Item = r.identifier(s.Item)
+		c.Operands = r.expressions(s.Operands)
+		c.Body = r.statements(s.Body)
+		return &c
+	case *parser.Assertion:
+		c := *s
+		c.Left = r.expression(s.Left)
+		c.Right = r.expression(s.Right)
+		return &c
+	case *parser.ExpressionStatement:
+		c := *s
+		c.Expression = r.expression(s.Expression)
+		return &c
+	}
+	return stmt
+}
+
+func (r renamer) expressions(exprs []parser.Expression) []parser.Expression {
+	if exprs == nil {
+		return nil
+	}
+	out := make([]parser.Expression, len(exprs))
+	for i, expr := range exprs {
+		out[i] = r.expression(expr)
+	}
+	return out
+}
+
+func (r renamer) expression(expr parser.Expression) parser.Expression {
+	switch e := expr.(type) {
+	case *parser.Identifier:
+		return r.identifier(e)
+	case *parser.BinaryOp:
+		c := *e
+		c.Left = r.expression(e.Left)
+		c.Right = r.expression(e.Right)
+		return &c
+	case *parser.UnaryOp:
+		c := *e
+		c.Operand = r.expression(e.Operand)
+		return &c
+	case *parser.FunctionCall:
+		c := *e
+		c.Arguments = r.expressions(e.Arguments)
+		return &c
+	case *parser.ArrayLiteral:
+		c := *e
+		c.Elements = r.expressions(e.Elements)
+		return &c
+	case *parser.MapLiteral:
+		c := *e
+		c.Pairs = make([][2]parser.Expression, len(e.Pairs))
+		for i, pair := range e.Pairs {
+			c.Pairs[i] = [2]parser.Expression{r.expression(pair[0]), r.expression(pair[1])}
+		}
+		return &c
+	}
+	return expr
+}
+
+// walkStatements calls statement for every statement in stmts and
+// expression for every expression, nested ones included. Either may be nil.
+func walkStatements(stmts []parser.Statement, statement func(parser.Statement), expression func(parser.Expression)) {
+	var walkExpr func(expr parser.Expression)
+	walkExpr = func(expr parser.Expression) {
+		if expr == nil {
+			return
+		}
+		if expression != nil {
+			expression(expr)
+		}
+		switch e := expr.(type) {
+		case *parser.BinaryOp:
+			walkExpr(e.Left)
+			walkExpr(e.Right)
+		case *parser.UnaryOp:
+			walkExpr(e.Operand)
+		case *parser.FunctionCall:
+			for _, arg := range e.Arguments {
+				walkExpr(arg)
+			}
+		case *parser.ArrayLiteral:
+			for _, elem := range e.Elements {
+				walkExpr(elem)
+			}
+		case *parser.MapLiteral:
+			for _, pair := range e.Pairs {
+				walkExpr(pair[0])
+				walkExpr(pair[1])
+			}
+		}
+	}
+
+	for _, stmt := range stmts {
+		if statement != nil {
+			statement(stmt)
+		}
+		switch s := stmt.(type) {
+		case *parser.OutputStatement:
+			walkExpr(s.Value)
+		case *parser.Assignment:
+			walkExpr(s.Value)
+		case *parser.IfStatement:
+			walkExpr(s.Condition)
+			walkStatements(s.ThenBranch, statement, expression)
+			walkStatements(s.ElseBranch, statement, expression)
+		case *parser.ForLoop:
+			walkExpr(s.Start)
+			walkExpr(s.End)
+			walkExpr(s.Step)
+			walkStatements(s.Body, statement, expression)
+		case *parser.WhileLoop:
+			walkExpr(s.Condition)
+			walkStatements(s.Body, statement, expression)
+		case *parser.ParallelBlock:
+			for _, branch := range s.Branches {
+				walkStatements(branch, statement, expression)
+			}
+		case *parser.Block:
+			walkStatements(s.Body, statement, expression)
+		case *parser.Stage:
+			walkStatements(s.Body, statement, expression)
+		case *parser.Mirror:
+			for _, operand := range s.Operands {
+				walkExpr(operand)
+			}
+			walkStatements(s.Body, statement, expression)
+		case *parser.Assertion:
+			walkExpr(s.Left)
+			walkExpr(s.Right)
+		case *parser.ReturnStatement:
+			walkExpr(s.Value)
+		case *parser.ExpressionStatement:
+			walkExpr(s.Expression)
+		}
+	}
+}
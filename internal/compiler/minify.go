@@ -0,0 +1,30 @@
+package compiler
+
+import (
+	"strings"
+)
+
+// Minify shrinks generated code for embedding: blank lines and comment lines
+// are dropped and each indentation level becomes a single space. It relies
+// on the compiler never emitting multi-line string literals.
+func Minify(code string) string {
+	indentStr := NewCompiler().indentStr
+
+	var b strings.Builder
+	for _, line := range strings.Split(code, "\n") {
+		trimmed := strings.TrimSpace(line)
+		if trimmed == "" || strings.HasPrefix(trimmed, "#") {
+			continue
+		}
+
+		depth := 0
+		for strings.HasPrefix(line, indentStr) {
+			line = line[len(indentStr):]
+			depth++
+		}
+		b.WriteString(strings.Repeat(" ", depth))
+		b.WriteString(strings.TrimRight(line, " \t"))
+		b.WriteString("\n")
+	}
+	return b.String()
+}
@@ -0,0 +1,28 @@
+package compiler
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestMinify(t *testing.T) {
+	code := "#!/usr/bin/env python3\n" +
+		"# Generated by Grimoire\n" +
+		"\n" +
+		"def anonymous_func():\n" +
+		"    for i in range(0, 3, 1):\n" +
+		"        print(\"# not a comment\")  \n" +
+		"\n" +
+		"if __name__ == \"__main__\":\n" +
+		"    print(1)\n"
+
+	expected := "def anonymous_func():\n" +
+		" for i in range(0, 3, 1):\n" +
+		"  print(\"# not a comment\")\n" +
+		"if __name__ == \"__main__\":\n" +
+		" print(1)\n"
+
+	assert.Equal(t, expected, Minify(code))
+	assert.Empty(t, Minify(""))
+}
@@ -0,0 +1,359 @@
+package compiler
+
+import (
+	"math"
+	"math/bits"
+
+	"github.com/ayutaz/grimoire/internal/parser"
+)
+
+// Peephole returns a copy of program simplified statement by statement:
+//   - operations on integer and boolean literals are computed, so a chain
+//     of constant additions becomes a single literal
+//   - an assignment overwritten by the next assignments to the same
+//     variable, with nothing reading it in between, is dropped
+//   - an if statement with a constant condition is replaced by the branch
+//     taken, and a while loop that never runs is dropped
+//
+// Divisions are left alone, as their result depends on Python, and so are
+// operations whose result overflows a Go int, which Python computes
+// exactly. program is not changed.
+func Peephole(program *parser.Program) *parser.Program {
+	if program == nil {
+		return nil
+	}
+	simplified := *program
+	simplified.Globals = peepholeStatements(program.Globals)
+	if program.MainEntry != nil {
+		main := *program.MainEntry
+		main.Body = peepholeStatements(main.Body)
+		simplified.MainEntry = &main
+	}
+	simplified.Functions = make([]*parser.FunctionDef, len(program.Functions))
+	for i, fn := range program.Functions {
+		copied := *fn
+		copied.Body = peepholeStatements(fn.Body)
+		simplified.Functions[i] = &copied
+	}
+	return &simplified
+}
+
+// peepholeStatements simplifies stmts and the statements nested in them
+func peepholeStatements(stmts []parser.Statement) []parser.Statement {
+	if stmts == nil {
+		return nil
+	}
+	out := make([]parser.Statement, 0, len(stmts))
+	for _, stmt := range stmts {
+		switch s := stmt.(type) {
+		case *parser.IfStatement:
+			condition := fold(s.Condition)
+			if taken, ok := boolLiteral(condition); ok {
+				if taken {
+					out = append(out, peepholeStatements(s.ThenBranch)...)
+				} else {
+					out = append(out, peepholeStatements(s.ElseBranch)...)
+				}
+				continue
+			}
+			c := *s
+			c.Condition = condition
+			c.ThenBranch = peepholeStatements(s.ThenBranch)
+			c.ElseBranch = peepholeStatements(s.ElseBranch)
+			out = append(out, &c)
+		case *parser.WhileLoop:
+			condition := fold(s.Condition)
+			if runs, ok := boolLiteral(condition); ok && !runs {
+				continue
+			}
+			c := *s
+			c.Condition = condition
+			c.Body = peepholeStatements(s.Body)
+			out = append(out, &c)
+		default:
+			out = append(out, peepholeStatement(stmt))
+		}
+	}
+	return dropDeadStores(out)
+}
+
+// peepholeStatement simplifies a statement other than an if or while
+func peepholeStatement(stmt parser.Statement) parser.Statement {
+	switch s := stmt.(type) {
+	case *parser.OutputStatement:
+		c := *s
+		c.Value = fold(s.Value)
+		return &c
+	case *parser.Assignment:
+		c := *s
+		c.Value = fold(s.Value)
+		return &c
+	case *parser.ForLoop:
+		c := *s
+		c.Start = fold(s.Start)
+		c.End = fold(s.End)
+		c.Step = fold(s.Step)
+		c.Body = peepholeStatements(s.Body)
+		return &c
+	case *parser.ParallelBlock:
+		c := *s
+		c.Branches = make([][]parser.Statement, len(s.Branches))
+		for i, branch := range s.Branches {
+			c.Branches[i] = peepholeStatements(branch)
+		}
+		return &c
+	case *parser.Block:
+		c := *s
+		c.Body = peepholeStatements(s.Body)
+		return &c
+	case *parser.Stage:
+		c := *s
+		c.Body = peepholeStatements(s.Body)
+		return &c
+	case *parser.Mirror:
+		c := *s
+		c.Operands = make([]parser.Expression, len(s.Operands))
+		for i, operand := range s.Operands {
+			c.Operands[i] = fold(operand)
+		}
+		c.Body = peepholeStatements(s.Body)
+		return &c
+	case *parser.Assertion:
+		c := *s
+		c.Left = fold(s.Left)
+		c.Right = fold(s.Right)
+		return &c
+	case *parser.ReturnStatement:
+		c := *s
+		c.Value = fold(s.Value)
+		return &c
+	case *parser.ExpressionStatement:
+		c := *s
+		c.Expression = fold(s.Expression)
+		return &c
+	}
+	return stmt
+}
+
+// dropDeadStores removes the assignments of stmts whose value is never read
+func dropDeadStores(stmts []parser.Statement) []parser.Statement {
+	out := stmts[:0]
+	for i, stmt := range stmts {
+		if a, ok := stmt.(*parser.Assignment); ok && a.Target != nil && !hasCall(a.Value) && overwritten(a.Target.Name, stmts[i+1:]) {
+			continue
+		}
+		out = append(out, stmt)
+	}
+	return out
+}
+
+// overwritten reports whether the straight run of assignments and outputs
+// starting stmts assigns name before anything could read it. Calls end the
+// run, as the called function may read the variable.
+func overwritten(name string, stmts []parser.Statement) bool {
+	for _, stmt := range stmts {
+		var value parser.Expression
+		switch s := stmt.(type) {
+		case *parser.Assignment:
+			value = s.Value
+		case *parser.OutputStatement:
+			value = s.Value
+		default:
+			return false
+		}
+		if hasCall(value) || reads(value, name) {
+			return false
+		}
+		if a, ok := stmt.(*parser.Assignment); ok && a.Target != nil && a.Target.Name == name {
+			return true
+		}
+	}
+	return false
+}
+
+// hasCall reports whether expr calls a function
+func hasCall(expr parser.Expression) bool {
+	found := false
+	walkExpression(expr, func(e parser.Expression) {
+		if _, ok := e.(*parser.FunctionCall); ok {
+			found = true
+		}
+	})
+	return found
+}
+
+// reads reports whether expr reads the variable name
+func reads(expr parser.Expression, name string) bool {
+	found := false
+	walkExpression(expr, func(e parser.Expression) {
+		if id, ok := e.(*parser.Identifier); ok && id.Name == name {
+			found = true
+		}
+	})
+	return found
+}
+
+// walkExpression calls visit for expr and every expression nested in it
+func walkExpression(expr parser.Expression, visit func(parser.Expression)) {
+	walkStatements([]parser.Statement{&parser.ExpressionStatement{Expression: expr}}, nil, visit)
+}
+
+// fold returns expr with the operations on literals computed
+func fold(expr parser.Expression) parser.Expression {
+	switch e := expr.(type) {
+	case *parser.BinaryOp:
+		left, right := fold(e.Left), fold(e.Right)
+		if folded := foldBinary(e.Operator, left, right); folded != nil {
+			return folded
+		}
+		c := *e
+		c.Left, c.Right = left, right
+		return &c
+	case *parser.UnaryOp:
+		operand := fold(e.Operand)
+		if value, ok := boolLiteral(operand); ok && e.Operator == parser.Not {
+			return &parser.Literal{Value: !value, LiteralType: parser.Boolean}
+		}
+		c := *e
+		c.Operand = operand
+		return &c
+	case *parser.FunctionCall:
+		c := *e
+		c.Arguments = make([]parser.Expression, len(e.Arguments))
+		for i, arg := range e.Arguments {
+			c.Arguments[i] = fold(arg)
+		}
+		return &c
+	case *parser.ArrayLiteral:
+		c := *e
+		c.Elements = make([]parser.Expression, len(e.Elements))
+		for i, elem := range e.Elements {
+			c.Elements[i] = fold(elem)
+		}
+		return &c
+	case *parser.MapLiteral:
+		c := *e
+		c.Pairs = make([][2]parser.Expression, len(e.Pairs))
+		for i, pair := range e.Pairs {
+			c.Pairs[i] = [2]parser.Expression{fold(pair[0]), fold(pair[1])}
+		}
+		return &c
+	}
+	return expr
+}
+
+// foldBinary computes an operation on two literals, or returns nil when the
+// operands are not both literals the operation can be computed for
+func foldBinary(op parser.OperatorType, left, right parser.Expression) parser.Expression {
+	if l, ok := intLiteral(left); ok {
+		r, ok := intLiteral(right)
+		if !ok {
+			return nil
+		}
+		integer := func(v int) parser.Expression { return &parser.Literal{Value: v, LiteralType: parser.Integer} }
+		boolean := func(v bool) parser.Expression { return &parser.Literal{Value: v, LiteralType: parser.Boolean} }
+		checked := func(v int, ok bool) parser.Expression {
+			if !ok {
+				return nil
+			}
+			return integer(v)
+		}
+		switch op {
+		case parser.Add:
+			return checked(addInt(l, r))
+		case parser.Subtract:
+			return checked(subtractInt(l, r))
+		case parser.Multiply:
+			return checked(multiplyInt(l, r))
+		case parser.Equal:
+			return boolean(l == r)
+		case parser.NotEqual:
+			return boolean(l != r)
+		case parser.LessThan:
+			return boolean(l < r)
+		case parser.GreaterThan:
+			return boolean(l > r)
+		case parser.LessEqual:
+			return boolean(l <= r)
+		case parser.GreaterEqual:
+			return boolean(l >= r)
+		}
+		return nil
+	}
+
+	l, ok := boolLiteral(left)
+	if !ok {
+		return nil
+	}
+	r, ok := boolLiteral(right)
+	if !ok {
+		return nil
+	}
+	switch op {
+	case parser.And:
+		return &parser.Literal{Value: l && r, LiteralType: parser.Boolean}
+	case parser.Or:
+		return &parser.Literal{Value: l || r, LiteralType: parser.Boolean}
+	}
+	return nil
+}
+
+// addInt returns l + r, and false when the sum overflows an int
+func addInt(l, r int) (int, bool) {
+	sum := l + r
+	return sum, (sum > l) == (r > 0)
+}
+
+// subtractInt returns l - r, and false when the difference overflows an int
+func subtractInt(l, r int) (int, bool) {
+	difference := l - r
+	return difference, (difference < l) == (r > 0)
+}
+
+// multiplyInt returns l * r, and false when the product overflows an int
+func multiplyInt(l, r int) (int, bool) {
+	hi, lo := bits.Mul64(magnitude(l), magnitude(r))
+	if hi != 0 {
+		return 0, false
+	}
+	if (l < 0) != (r < 0) {
+		// A negative product may reach one past math.MaxInt
+		if lo > uint64(math.MaxInt)+1 {
+			return 0, false
+		}
+		return int(-lo), true
+	}
+	if lo > math.MaxInt {
+		return 0, false
+	}
+	return int(lo), true
+}
+
+// magnitude returns the absolute value of v, which for math.MinInt is one
+// past math.MaxInt
+func magnitude(v int) uint64 {
+	if v < 0 {
+		return -uint64(v)
+	}
+	return uint64(v)
+}
+
+// intLiteral returns the value of an integer literal
+func intLiteral(expr parser.Expression) (int, bool) {
+	lit, ok := expr.(*parser.Literal)
+	if !ok || lit.LiteralType != parser.Integer {
+		return 0, false
+	}
+	value, ok := lit.Value.(int)
+	return value, ok
+}
+
+// boolLiteral returns the value of a boolean literal
+func boolLiteral(expr parser.Expression) (bool, bool) {
+	lit, ok := expr.(*parser.Literal)
+	if !ok || lit.LiteralType != parser.Boolean {
+		return false, false
+	}
+	value, ok := lit.Value.(bool)
+	return value, ok
+}
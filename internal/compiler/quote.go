@@ -0,0 +1,71 @@
+package compiler
+
+import (
+	"fmt"
+
+	"github.com/ayutaz/grimoire/internal/parser"
+)
+
+// quoteRuntime is emitted ahead of programs with quoted programs or calls
+// to eval. A quoted program is compiled to source run by eval in a
+// namespace of its own, as if it ran by itself; each evaluation passes its
+// depth on, so that evaluations nest no deeper than quotes can.
+var quoteRuntime = []string{
+	"grimoire_eval_depth = globals().get(\"grimoire_eval_depth\", 0)",
+	"",
+	"",
+	"class GrimoireQuote:",
+	"    def __init__(self, source):",
+	"        self.source = source",
+	"",
+	"    def __repr__(self):",
+	"        return \"<quoted program>\"",
+	"",
+	"",
+	"def grimoire_eval(quote):",
+	"    if not isinstance(quote, GrimoireQuote):",
+	"        raise TypeError(\"only a quoted program can be evaluated, not %r\" % (quote,))",
+	fmt.Sprintf("    if grimoire_eval_depth >= %d:", parser.MaxQuoteDepth),
+	fmt.Sprintf("        raise RecursionError(\"quoted programs evaluated more than %d deep\")", parser.MaxQuoteDepth),
+	"    exec(quote.source, {\"__name__\": \"__main__\", \"grimoire_eval_depth\": grimoire_eval_depth + 1})",
+	"",
+	"",
+}
+
+// hasQuotes reports whether any expression of program is a quoted program
+func hasQuotes(program *parser.Program) bool {
+	bodies := [][]parser.Statement{program.Globals}
+	for _, fn := range program.Functions {
+		bodies = append(bodies, fn.Body)
+	}
+	if program.MainEntry != nil {
+		bodies = append(bodies, program.MainEntry.Body)
+	}
+
+	found := false
+	for _, body := range bodies {
+		walkStatements(body, nil, func(expr parser.Expression) {
+			if _, ok := expr.(*parser.Quoted); ok {
+				found = true
+			}
+		})
+	}
+	return found
+}
+
+// compileQuoted compiles a quoted program to a value holding its source,
+// compiled with the same settings as the program quoting it
+func (c *Compiler) compileQuoted(quoted *parser.Quoted) string {
+	source := ""
+	if program := quoted.Program; program != nil && (program.MainEntry != nil || len(program.Globals) > 0 || len(program.Functions) > 0) {
+		inner := NewCompiler()
+		inner.SetMaxRecursion(c.maxRecursion)
+		inner.SetOptimization(c.optimization)
+		var err error
+		if source, err = inner.Compile(program); err != nil {
+			c.writeLine(fmt.Sprintf("# Warning: %v", err))
+			return "None"
+		}
+	}
+	return fmt.Sprintf("GrimoireQuote(%q)", source)
+}
@@ -0,0 +1,82 @@
+package compiler
+
+import (
+	"flag"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// Snapshot fixtures are ASTs in the parser's JSON format in snapshotASTDir.
+// Each is compiled by every backend and the output compared with
+// snapshotDir/<fixture><backend suffix>. Run with -update to rewrite the
+// snapshots after an intended codegen change, and review them as a diff.
+const (
+	snapshotASTDir = "testdata/ast"
+	snapshotDir    = "testdata/snapshots"
+
+	// snapshotRuns is how often each fixture is compiled to catch output
+	// that changes between runs
+	snapshotRuns = 3
+)
+
+var updateSnapshots = flag.Bool("update", false, "rewrite backend snapshots with actual output")
+
+// snapshotBackends are the forms of generated code, by snapshot suffix
+var snapshotBackends = []struct {
+	suffix  string
+	compile func(ast *parser.Program) (string, error)
+}{
+	{".py", Compile},
+	{".min.py", func(ast *parser.Program) (string, error) {
+		code, err := Compile(ast)
+		return Minify(code), err
+	}},
+}
+
+func TestBackendSnapshots(t *testing.T) {
+	fixtures, err := filepath.Glob(filepath.Join(snapshotASTDir, "*.json"))
+	require.NoError(t, err)
+	require.NotEmpty(t, fixtures, "no AST fixtures in %s", snapshotASTDir)
+
+	for _, fixture := range fixtures {
+		name := strings.TrimSuffix(filepath.Base(fixture), ".json")
+		t.Run(name, func(t *testing.T) {
+			data, err := os.ReadFile(fixture)
+			require.NoError(t, err)
+
+			for _, backend := range snapshotBackends {
+				snapshot := filepath.Join(snapshotDir, name+backend.suffix)
+
+				var code string
+				for run := 0; run < snapshotRuns; run++ {
+					// Decode afresh so no run sees state left by another
+					node, err := parser.DecodeJSON(data)
+					require.NoError(t, err)
+					program, ok := node.(*parser.Program)
+					require.True(t, ok, "%s does not hold a program", fixture)
+
+					output, err := backend.compile(program)
+					require.NoError(t, err)
+					if run > 0 {
+						require.Equal(t, code, output, "%s output changed between runs", backend.suffix)
+					}
+					code = output
+				}
+
+				if *updateSnapshots {
+					require.NoError(t, os.WriteFile(snapshot, []byte(code), 0o644))
+					continue
+				}
+				expected, err := os.ReadFile(snapshot)
+				require.NoError(t, err, "missing snapshot; run with -update to create it")
+				assert.Equal(t, string(expected), code, "output differs from %s", snapshot)
+			}
+		})
+	}
+}
@@ -0,0 +1,35 @@
+package compiler
+
+import "github.com/ayutaz/grimoire/internal/parser"
+
+// tailSelfCalls returns the calls fn makes to itself as the last thing it
+// does. Functions take no parameters, so such a call restarts the function
+// and can be compiled as a jump back to its start instead of a new call.
+func tailSelfCalls(fn *parser.FunctionDef) map[*parser.ExpressionStatement]bool {
+	calls := make(map[*parser.ExpressionStatement]bool)
+	if fn.Name != "" {
+		collectTailCalls(fn.Name, fn.Body, calls)
+	}
+	return calls
+}
+
+// collectTailCalls adds the calls to name in tail position of stmts to calls
+func collectTailCalls(name string, stmts []parser.Statement, calls map[*parser.ExpressionStatement]bool) {
+	if len(stmts) == 0 {
+		return
+	}
+	switch s := stmts[len(stmts)-1].(type) {
+	case *parser.ExpressionStatement:
+		call, ok := s.Expression.(*parser.FunctionCall)
+		if ok && call.Function != nil && call.Function.Name == name && len(call.Arguments) == 0 {
+			calls[s] = true
+		}
+	case *parser.IfStatement:
+		collectTailCalls(name, s.ThenBranch, calls)
+		collectTailCalls(name, s.ElseBranch, calls)
+	case *parser.Block:
+		collectTailCalls(name, s.Body, calls)
+	case *parser.Stage:
+		collectTailCalls(name, s.Body, calls)
+	}
+}
@@ -0,0 +1,26 @@
+#!/usr/bin/env python3
+# Generated by Grimoire
+
+def add(a, b):
+    return (a + b)
+
+def greet():
+    pass
+
+if __name__ == "__main__":
+    print(add(1, 2))
+    import threading
+    threads = []
+    def branch_0():
+        print("left")
+    threads.append(threading.Thread(target=branch_0))
+    def branch_1():
+        print("right")
+    threads.append(threading.Thread(target=branch_1))
+    for t in threads:
+        t.start()
+    for t in threads:
+        t.join()
+    greet()
+    data = [1, 2.5, "three"]
+    table = {"key": True}
@@ -0,0 +1,13 @@
+total = 0
+if __name__ == "__main__":
+ for i in range(1, 4):
+  total = (total + i)
+ while (total > 0):
+  total = (total - 2)
+  if (total == 2):
+   print("two")
+  else:
+   print(total)
+ for j in range(10, 0, -2):
+  pass
+ print(not False)
@@ -0,0 +1,235 @@
+// Package conformance checks that detection backends agree with each other.
+//
+// Every fixture image is detected by a reference backend and a candidate
+// backend, and the two results are compared by symbol count, symbol types,
+// symbol positions (within a tolerance) and the AST parsed from them.
+//
+// In round-trip mode, each fixture is instead redrawn by the renderer and
+// detected again, and the drawing is compared with its redrawing, which
+// measures the fidelity of the whole render, detect and parse pipeline.
+package conformance
+
+import (
+	"fmt"
+	"math"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/ayutaz/grimoire/internal/spelltest"
+)
+
+// DefaultTolerance is the distance, in pixels, within which matched symbols
+// are considered to be at the same position
+const DefaultTolerance = 2.0
+
+// Divergence kinds
+const (
+	KindDetection = "detection"
+	KindCount     = "count"
+	KindType      = "type"
+	KindPosition  = "position"
+	KindAST       = "ast"
+)
+
+// imageExtensions are the image formats considered when discovering fixtures
+var imageExtensions = map[string]bool{
+	".png": true, ".jpg": true, ".jpeg": true, ".gif": true, ".webp": true,
+}
+
+// Divergence is a difference between the reference and candidate results
+type Divergence struct {
+	Kind   string
+	Detail string
+}
+
+// Result is the outcome of checking one fixture
+type Result struct {
+	Image       string
+	Divergences []Divergence
+	// Err is set when the reference backend could not detect the fixture
+	Err error
+}
+
+// Conforms reports whether the candidate matched the reference
+func (r Result) Conforms() bool {
+	return r.Err == nil && len(r.Divergences) == 0
+}
+
+// Checker compares a candidate backend against a reference backend
+type Checker struct {
+	Reference detector.Backend
+	Candidate detector.Backend
+	// Tolerance is the largest position difference, in pixels, that still conforms
+	Tolerance float64
+}
+
+// NewChecker creates a checker with the default tolerance
+func NewChecker(reference, candidate detector.Backend) *Checker {
+	return &Checker{Reference: reference, Candidate: candidate, Tolerance: DefaultTolerance}
+}
+
+// Discover returns the fixture images in dir, sorted by name
+func Discover(dir string) ([]string, error) {
+	entries, err := os.ReadDir(dir)
+	if err != nil {
+		return nil, err
+	}
+
+	var images []string
+	for _, entry := range entries {
+		if entry.IsDir() || !imageExtensions[strings.ToLower(filepath.Ext(entry.Name()))] {
+			continue
+		}
+		images = append(images, filepath.Join(dir, entry.Name()))
+	}
+	sort.Strings(images)
+	return images, nil
+}
+
+// Check detects imagePath with both backends and reports where they differ
+func (c *Checker) Check(imagePath string) Result {
+	result := Result{Image: imagePath}
+
+	refSymbols, refConnections, err := c.Reference.Detect(imagePath)
+	if err != nil {
+		result.Err = err
+		return result
+	}
+	candSymbols, candConnections, err := c.Candidate.Detect(imagePath)
+	if err != nil {
+		result.add(KindDetection, err.Error())
+		return result
+	}
+
+	if len(refSymbols) != len(candSymbols) {
+		result.add(KindCount, fmt.Sprintf("reference found %d symbols, candidate found %d",
+			len(refSymbols), len(candSymbols)))
+	}
+	compareTypes(&result, refSymbols, candSymbols, "reference", "candidate")
+	c.comparePositions(&result, refSymbols, candSymbols)
+
+	refAST, candAST := encodeAST(refSymbols, refConnections), encodeAST(candSymbols, candConnections)
+	if refAST != candAST {
+		result.add(KindAST, spelltest.Diff(refAST, candAST))
+	}
+	return result
+}
+
+// CheckAll checks every fixture in order
+func (c *Checker) CheckAll(images []string) []Result {
+	results := make([]Result, 0, len(images))
+	for _, image := range images {
+		results = append(results, c.Check(image))
+	}
+	return results
+}
+
+// add records a divergence
+func (r *Result) add(kind, detail string) {
+	r.Divergences = append(r.Divergences, Divergence{Kind: kind, Detail: detail})
+}
+
+// compareTypes reports each symbol type found a different number of times,
+// naming the two results refName and candName
+func compareTypes(result *Result, refSymbols, candSymbols []*detector.Symbol, refName, candName string) {
+	refCounts, candCounts := countTypes(refSymbols), countTypes(candSymbols)
+
+	var types []string
+	for t := range refCounts {
+		types = append(types, string(t))
+	}
+	for t := range candCounts {
+		if _, ok := refCounts[t]; !ok {
+			types = append(types, string(t))
+		}
+	}
+	sort.Strings(types)
+
+	for _, name := range types {
+		t := detector.SymbolType(name)
+		if refCounts[t] != candCounts[t] {
+			result.add(KindType, fmt.Sprintf("%s: %s found %d, %s found %d",
+				t, refName, refCounts[t], candName, candCounts[t]))
+		}
+	}
+}
+
+// comparePositions pairs symbols of the same type, closest pairs first, and
+// reports pairs further apart than the tolerance. Unpaired symbols are
+// already reported by compareTypes.
+func (c *Checker) comparePositions(result *Result, refSymbols, candSymbols []*detector.Symbol) {
+	type pair struct {
+		ref, cand *detector.Symbol
+		distance  float64
+	}
+
+	var pairs []pair
+	for _, r := range refSymbols {
+		for _, s := range candSymbols {
+			if r.Type == s.Type {
+				pairs = append(pairs, pair{ref: r, cand: s, distance: distance(r, s)})
+			}
+		}
+	}
+	sort.SliceStable(pairs, func(i, j int) bool { return pairs[i].distance < pairs[j].distance })
+
+	claimed := make(map[*detector.Symbol]bool)
+	var moved []pair
+	for _, p := range pairs {
+		if claimed[p.ref] || claimed[p.cand] {
+			continue
+		}
+		claimed[p.ref], claimed[p.cand] = true, true
+		if p.distance > c.Tolerance {
+			moved = append(moved, p)
+		}
+	}
+
+	// Report in reference order so the output is stable
+	sort.SliceStable(moved, func(i, j int) bool {
+		return indexOf(refSymbols, moved[i].ref) < indexOf(refSymbols, moved[j].ref)
+	})
+	for _, p := range moved {
+		result.add(KindPosition, fmt.Sprintf("%s at (%.0f, %.0f) is at (%.0f, %.0f) in the candidate, %.1fpx away",
+			p.ref.Type, p.ref.Position.X, p.ref.Position.Y, p.cand.Position.X, p.cand.Position.Y, p.distance))
+	}
+}
+
+// encodeAST parses the detection results and returns the AST as JSON, or
+// the parse error so that backends failing the same way still conform
+func encodeAST(symbols []*detector.Symbol, connections []detector.Connection) string {
+	program, err := parser.Parse(symbols, connections)
+	if err != nil {
+		return "error: " + err.Error() + "\n"
+	}
+	data, err := parser.EncodeJSON(program)
+	if err != nil {
+		return "error: " + err.Error() + "\n"
+	}
+	return string(data) + "\n"
+}
+
+func countTypes(symbols []*detector.Symbol) map[detector.SymbolType]int {
+	counts := make(map[detector.SymbolType]int)
+	for _, s := range symbols {
+		counts[s.Type]++
+	}
+	return counts
+}
+
+func distance(a, b *detector.Symbol) float64 {
+	return math.Hypot(a.Position.X-b.Position.X, a.Position.Y-b.Position.Y)
+}
+
+func indexOf(symbols []*detector.Symbol, target *detector.Symbol) int {
+	for i, s := range symbols {
+		if s == target {
+			return i
+		}
+	}
+	return -1
+}
@@ -0,0 +1,110 @@
+package conformance
+
+import (
+	"errors"
+	"image"
+	"io"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// fixedBackend returns the same detection results for every image
+type fixedBackend struct {
+	symbols []*detector.Symbol
+	err     error
+}
+
+func (b fixedBackend) Detect(string) ([]*detector.Symbol, []detector.Connection, error) {
+	return b.symbols, nil, b.err
+}
+
+func (b fixedBackend) DetectFromReader(io.Reader, string) ([]*detector.Symbol, []detector.Connection, error) {
+	return b.symbols, nil, b.err
+}
+
+func (b fixedBackend) DetectImage(image.Image) ([]*detector.Symbol, []detector.Connection, error) {
+	return b.symbols, nil, b.err
+}
+
+func symbolAt(t detector.SymbolType, x, y float64) *detector.Symbol {
+	return &detector.Symbol{Type: t, Position: detector.Position{X: x, Y: y}, Size: 20}
+}
+
+func TestCheckConforms(t *testing.T) {
+	reference := fixedBackend{symbols: []*detector.Symbol{
+		symbolAt(detector.OuterCircle, 100, 100),
+		symbolAt(detector.Star, 100, 150),
+	}}
+	candidate := fixedBackend{symbols: []*detector.Symbol{
+		symbolAt(detector.OuterCircle, 100, 100),
+		symbolAt(detector.Star, 101, 151),
+	}}
+
+	result := NewChecker(reference, candidate).Check("spell.png")
+	assert.True(t, result.Conforms(), "%v", result.Divergences)
+}
+
+func TestCheckDivergences(t *testing.T) {
+	reference := fixedBackend{symbols: []*detector.Symbol{
+		symbolAt(detector.OuterCircle, 100, 100),
+		symbolAt(detector.Star, 100, 150),
+		symbolAt(detector.Square, 50, 50),
+	}}
+	candidate := fixedBackend{symbols: []*detector.Symbol{
+		symbolAt(detector.OuterCircle, 100, 100),
+		symbolAt(detector.Star, 110, 150),
+	}}
+
+	result := NewChecker(reference, candidate).Check("spell.png")
+	require.False(t, result.Conforms())
+
+	kinds := make(map[string]string)
+	for _, d := range result.Divergences {
+		kinds[d.Kind] = d.Detail
+	}
+	assert.Contains(t, kinds[KindCount], "reference found 3 symbols, candidate found 2")
+	assert.Contains(t, kinds[KindType], "square: reference found 1, candidate found 0")
+	assert.Contains(t, kinds[KindPosition], "10.0px away")
+}
+
+func TestCheckTolerance(t *testing.T) {
+	reference := fixedBackend{symbols: []*detector.Symbol{symbolAt(detector.Star, 100, 150)}}
+	candidate := fixedBackend{symbols: []*detector.Symbol{symbolAt(detector.Star, 105, 150)}}
+
+	checker := NewChecker(reference, candidate)
+	assert.False(t, checker.Check("spell.png").Conforms())
+
+	checker.Tolerance = 5
+	assert.True(t, checker.Check("spell.png").Conforms())
+}
+
+func TestCheckDetectionErrors(t *testing.T) {
+	ok := fixedBackend{symbols: []*detector.Symbol{symbolAt(detector.OuterCircle, 100, 100)}}
+	failing := fixedBackend{err: errors.New("no outer circle")}
+
+	result := NewChecker(failing, ok).Check("spell.png")
+	assert.Error(t, result.Err)
+	assert.False(t, result.Conforms())
+
+	result = NewChecker(ok, failing).Check("spell.png")
+	require.NoError(t, result.Err)
+	require.Len(t, result.Divergences, 1)
+	assert.Equal(t, KindDetection, result.Divergences[0].Kind)
+}
+
+func TestDiscover(t *testing.T) {
+	dir := t.TempDir()
+	for _, name := range []string{"b.png", "a.JPG", "notes.txt"} {
+		require.NoError(t, os.WriteFile(filepath.Join(dir, name), nil, 0o644))
+	}
+	require.NoError(t, os.Mkdir(filepath.Join(dir, "nested.png"), 0o755))
+
+	images, err := Discover(dir)
+	require.NoError(t, err)
+	assert.Equal(t, []string{filepath.Join(dir, "a.JPG"), filepath.Join(dir, "b.png")}, images)
+}
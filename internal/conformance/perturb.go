@@ -0,0 +1,149 @@
+package conformance
+
+import (
+	"bytes"
+	"image"
+	"image/color"
+	"image/draw"
+	"image/jpeg"
+	"math"
+	"math/rand"
+)
+
+// The most each perturbation degrades an image, at level 1
+const (
+	maxNoiseSigma    = 64  // standard deviation of Gaussian noise, in gray levels
+	minJPEGQuality   = 5   // JPEG quality, from 100 at level 0
+	maxRotation      = 20  // rotation, in degrees
+	maxBlurRadius    = 6   // box blur radius, in pixels
+	maxSaltAndPepper = 0.2 // share of pixels turned black or white
+)
+
+// Perturbation degrades an image by a level from 0, which leaves it as it
+// is, to 1, the worst tested
+type Perturbation struct {
+	Name  string
+	Apply func(img *image.Gray, level float64, rng *rand.Rand) *image.Gray
+}
+
+// Perturbations are the ways fixtures are degraded to measure robustness
+var Perturbations = []Perturbation{
+	{Name: "noise", Apply: gaussianNoise},
+	{Name: "jpeg", Apply: jpegArtifacts},
+	{Name: "rotation", Apply: rotate},
+	{Name: "blur", Apply: blur},
+	{Name: "salt_pepper", Apply: saltAndPepper},
+}
+
+// gray returns img in grayscale, with its origin at zero
+func gray(img image.Image) *image.Gray {
+	bounds := img.Bounds()
+	g := image.NewGray(image.Rect(0, 0, bounds.Dx(), bounds.Dy()))
+	draw.Draw(g, g.Bounds(), img, bounds.Min, draw.Src)
+	return g
+}
+
+// gaussianNoise adds noise with a standard deviation growing with level
+func gaussianNoise(img *image.Gray, level float64, rng *rand.Rand) *image.Gray {
+	out := gray(img)
+	sigma := level * maxNoiseSigma
+	for i, v := range out.Pix {
+		out.Pix[i] = clamp(float64(v) + rng.NormFloat64()*sigma)
+	}
+	return out
+}
+
+// jpegArtifacts round-trips img through JPEG at a quality falling with level
+func jpegArtifacts(img *image.Gray, level float64, _ *rand.Rand) *image.Gray {
+	quality := int(math.Round(100 - level*(100-minJPEGQuality)))
+	var buf bytes.Buffer
+	if err := jpeg.Encode(&buf, img, &jpeg.Options{Quality: quality}); err != nil {
+		return gray(img)
+	}
+	decoded, err := jpeg.Decode(&buf)
+	if err != nil {
+		return gray(img)
+	}
+	return gray(decoded)
+}
+
+// rotate turns img about its center by an angle growing with level, filling
+// the corners with white
+func rotate(img *image.Gray, level float64, _ *rand.Rand) *image.Gray {
+	angle := level * maxRotation * math.Pi / 180
+	sin, cos := math.Sin(angle), math.Cos(angle)
+	bounds := img.Bounds()
+	cx, cy := float64(bounds.Dx())/2, float64(bounds.Dy())/2
+
+	out := image.NewGray(image.Rect(0, 0, bounds.Dx(), bounds.Dy()))
+	for y := 0; y < bounds.Dy(); y++ {
+		for x := 0; x < bounds.Dx(); x++ {
+			// The pixel of img that turns into (x, y)
+			dx, dy := float64(x)+0.5-cx, float64(y)+0.5-cy
+			sx := int(math.Floor(cx + dx*cos + dy*sin))
+			sy := int(math.Floor(cy - dx*sin + dy*cos))
+			v := uint8(255)
+			if image.Pt(sx, sy).In(image.Rect(0, 0, bounds.Dx(), bounds.Dy())) {
+				v = img.GrayAt(bounds.Min.X+sx, bounds.Min.Y+sy).Y
+			}
+			out.SetGray(x, y, color.Gray{Y: v})
+		}
+	}
+	return out
+}
+
+// blur averages each pixel with its neighbors within a radius growing with
+// level, horizontally and then vertically
+func blur(img *image.Gray, level float64, _ *rand.Rand) *image.Gray {
+	radius := int(math.Round(level * maxBlurRadius))
+	out := gray(img)
+	if radius == 0 {
+		return out
+	}
+	w, h := out.Bounds().Dx(), out.Bounds().Dy()
+	average := func(at func(i int) uint8, n int) []uint8 {
+		averaged := make([]uint8, n)
+		for i := range averaged {
+			sum, count := 0, 0
+			for j := max(0, i-radius); j <= min(n-1, i+radius); j++ {
+				sum += int(at(j))
+				count++
+			}
+			averaged[i] = uint8(sum / count)
+		}
+		return averaged
+	}
+	for y := 0; y < h; y++ {
+		row := average(func(x int) uint8 { return out.Pix[y*out.Stride+x] }, w)
+		copy(out.Pix[y*out.Stride:], row)
+	}
+	for x := 0; x < w; x++ {
+		column := average(func(y int) uint8 { return out.Pix[y*out.Stride+x] }, h)
+		for y, v := range column {
+			out.Pix[y*out.Stride+x] = v
+		}
+	}
+	return out
+}
+
+// saltAndPepper turns a share of the pixels, growing with level, black or
+// white at random
+func saltAndPepper(img *image.Gray, level float64, rng *rand.Rand) *image.Gray {
+	out := gray(img)
+	share := level * maxSaltAndPepper
+	for i := range out.Pix {
+		if rng.Float64() >= share {
+			continue
+		}
+		out.Pix[i] = 0
+		if rng.Intn(2) == 0 {
+			out.Pix[i] = 255
+		}
+	}
+	return out
+}
+
+// clamp rounds v to a gray level
+func clamp(v float64) uint8 {
+	return uint8(math.Max(0, math.Min(255, math.Round(v))))
+}
@@ -0,0 +1,147 @@
+package conformance
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"image"
+	"math/rand"
+	"os"
+	"path/filepath"
+	"sort"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/security"
+)
+
+const (
+	// DefaultSteps is how many levels of each perturbation are tried
+	DefaultSteps = 10
+	// ThresholdsFile is the name of the file, next to the fixtures, that
+	// records how much of each perturbation every fixture tolerates
+	ThresholdsFile = "robustness.json"
+)
+
+// Robustness is how much of each perturbation one fixture tolerates
+type Robustness struct {
+	Image string
+	// Tolerated is, by perturbation name, the highest level up to which the
+	// fixture still reads as the same program
+	Tolerated map[string]float64
+	// Err is set when the unperturbed fixture could not be read
+	Err error
+}
+
+// Prober degrades fixtures step by step to find where detection fails
+type Prober struct {
+	Backend detector.Backend
+	// Steps is how many levels, evenly spaced up to 1, are tried
+	Steps int
+	// Seed makes random perturbations repeatable
+	Seed int64
+}
+
+// NewProber creates a prober with the default steps
+func NewProber(backend detector.Backend) *Prober {
+	return &Prober{Backend: backend, Steps: DefaultSteps, Seed: 1}
+}
+
+// Probe degrades imagePath by every perturbation, one level after another,
+// until it no longer reads as the same program as the original
+func (p *Prober) Probe(imagePath string) Robustness {
+	result := Robustness{Image: imagePath, Tolerated: make(map[string]float64, len(Perturbations))}
+
+	img, err := security.NewSafeImageDecoder(security.NewImageValidator()).DecodeImage(imagePath)
+	if err != nil {
+		result.Err = err
+		return result
+	}
+	original := gray(img)
+	want, err := p.read(original)
+	if err != nil {
+		result.Err = err
+		return result
+	}
+
+	for _, perturbation := range Perturbations {
+		rng := rand.New(rand.NewSource(p.Seed))
+		tolerated := 0.0
+		for step := 1; step <= p.Steps; step++ {
+			level := float64(step) / float64(p.Steps)
+			if got, err := p.read(perturbation.Apply(original, level, rng)); err != nil || got != want {
+				break
+			}
+			tolerated = level
+		}
+		result.Tolerated[perturbation.Name] = tolerated
+	}
+	return result
+}
+
+// ProbeAll probes every fixture in order
+func (p *Prober) ProbeAll(images []string) []Robustness {
+	results := make([]Robustness, 0, len(images))
+	for _, imagePath := range images {
+		results = append(results, p.Probe(imagePath))
+	}
+	return results
+}
+
+// read detects img and returns how many symbols of each type it found and
+// its AST as JSON, so that images failing to parse the same way still differ
+// by their symbols
+func (p *Prober) read(img image.Image) (string, error) {
+	symbols, connections, err := p.Backend.DetectImage(img)
+	if err != nil {
+		return "", err
+	}
+	return fmt.Sprintln(countTypes(symbols)) + encodeAST(symbols, connections), nil
+}
+
+// Thresholds are the levels each fixture tolerated, by fixture file name
+// and perturbation name
+type Thresholds map[string]map[string]float64
+
+// LoadThresholds reads the thresholds recorded at path. A missing file
+// records none.
+func LoadThresholds(path string) (Thresholds, error) {
+	data, err := os.ReadFile(path)
+	if errors.Is(err, os.ErrNotExist) {
+		return Thresholds{}, nil
+	}
+	if err != nil {
+		return nil, err
+	}
+	thresholds := Thresholds{}
+	if err := json.Unmarshal(data, &thresholds); err != nil {
+		return nil, err
+	}
+	return thresholds, nil
+}
+
+// Save writes the thresholds to path
+func (t Thresholds) Save(path string) error {
+	data, err := json.MarshalIndent(t, "", "  ")
+	if err != nil {
+		return err
+	}
+	return os.WriteFile(path, append(data, '\n'), 0o644)
+}
+
+// Record sets the thresholds of r
+func (t Thresholds) Record(r Robustness) {
+	t[filepath.Base(r.Image)] = r.Tolerated
+}
+
+// Regressions returns, sorted, the perturbations r tolerates less of than
+// recorded
+func (t Thresholds) Regressions(r Robustness) []string {
+	var regressed []string
+	for name, recorded := range t[filepath.Base(r.Image)] {
+		if tolerated, ok := r.Tolerated[name]; ok && tolerated < recorded {
+			regressed = append(regressed, name)
+		}
+	}
+	sort.Strings(regressed)
+	return regressed
+}
@@ -0,0 +1,109 @@
+package conformance
+
+import (
+	"image"
+	"image/color"
+	"image/png"
+	"io"
+	"math/rand"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// square returns a white image with a black square in the middle
+func square() *image.Gray {
+	img := image.NewGray(image.Rect(0, 0, 40, 40))
+	for y := 0; y < 40; y++ {
+		for x := 0; x < 40; x++ {
+			v := uint8(255)
+			if x >= 10 && x < 30 && y >= 10 && y < 30 {
+				v = 0
+			}
+			img.SetGray(x, y, color.Gray{Y: v})
+		}
+	}
+	return img
+}
+
+func TestPerturbations(t *testing.T) {
+	img := square()
+	for _, perturbation := range Perturbations {
+		untouched := perturbation.Apply(img, 0, rand.New(rand.NewSource(1)))
+		if perturbation.Name != "jpeg" {
+			// JPEG loses a little even at full quality
+			assert.Equal(t, img.Pix, untouched.Pix, "%s at level 0", perturbation.Name)
+		}
+		degraded := perturbation.Apply(img, 1, rand.New(rand.NewSource(1)))
+		assert.Equal(t, img.Bounds(), degraded.Bounds(), perturbation.Name)
+		assert.NotEqual(t, img.Pix, degraded.Pix, "%s at level 1", perturbation.Name)
+	}
+}
+
+func TestBlur(t *testing.T) {
+	// A radius of 3 averages the edge of the square over 7 pixels
+	blurred := blur(square(), 0.5, nil)
+	assert.Equal(t, uint8(0), blurred.GrayAt(20, 20).Y)
+	assert.Equal(t, uint8((3*255+4*(3*255/7))/7), blurred.GrayAt(10, 10).Y)
+}
+
+// centerBackend finds a star while the middle of the image stays dark
+type centerBackend struct{}
+
+func (centerBackend) Detect(string) ([]*detector.Symbol, []detector.Connection, error) {
+	return nil, nil, nil
+}
+
+func (centerBackend) DetectFromReader(io.Reader, string) ([]*detector.Symbol, []detector.Connection, error) {
+	return nil, nil, nil
+}
+
+func (centerBackend) DetectImage(img image.Image) ([]*detector.Symbol, []detector.Connection, error) {
+	symbols := []*detector.Symbol{symbolAt(detector.OuterCircle, 20, 20)}
+	if v, _, _, _ := img.At(20, 20).RGBA(); v < 0x8000 {
+		symbols = append(symbols, symbolAt(detector.Star, 20, 20))
+	}
+	return symbols, nil, nil
+}
+
+func TestProbe(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "square.png")
+	file, err := os.Create(path)
+	require.NoError(t, err)
+	require.NoError(t, png.Encode(file, square()))
+	require.NoError(t, file.Close())
+
+	result := NewProber(centerBackend{}).Probe(path)
+
+	require.NoError(t, result.Err)
+	require.Len(t, result.Tolerated, len(Perturbations))
+	assert.Equal(t, 1.0, result.Tolerated["rotation"], "the middle stays dark however far the square turns")
+	for name, level := range result.Tolerated {
+		assert.GreaterOrEqual(t, level, 0.0, name)
+		assert.LessOrEqual(t, level, 1.0, name)
+	}
+
+	missing := NewProber(centerBackend{}).Probe(filepath.Join(t.TempDir(), "missing.png"))
+	assert.Error(t, missing.Err)
+}
+
+func TestThresholds(t *testing.T) {
+	path := filepath.Join(t.TempDir(), ThresholdsFile)
+	thresholds, err := LoadThresholds(path)
+	require.NoError(t, err)
+	assert.Empty(t, thresholds, "a missing file records nothing")
+
+	thresholds.Record(Robustness{Image: "spells/a.png", Tolerated: map[string]float64{"noise": 0.5, "blur": 0.3}})
+	require.NoError(t, thresholds.Save(path))
+	loaded, err := LoadThresholds(path)
+	require.NoError(t, err)
+	assert.Equal(t, thresholds, loaded)
+
+	worse := Robustness{Image: "other/a.png", Tolerated: map[string]float64{"noise": 0.4, "blur": 0.3, "jpeg": 0.1}}
+	assert.Equal(t, []string{"noise"}, loaded.Regressions(worse))
+	assert.Empty(t, loaded.Regressions(Robustness{Image: "b.png", Tolerated: map[string]float64{"noise": 0}}))
+}
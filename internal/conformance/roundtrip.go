@@ -0,0 +1,79 @@
+package conformance
+
+import (
+	"fmt"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/render"
+	"github.com/ayutaz/grimoire/internal/spelltest"
+)
+
+// RoundTripper checks that magic circles redrawn by the renderer read as
+// the same program as the drawings they were redrawn from
+type RoundTripper struct {
+	Backend detector.Backend
+	// Options are what the circles are redrawn with
+	Options render.Options
+}
+
+// NewRoundTripper creates a round-tripper that redraws circles the way
+// grimoire fmt does
+func NewRoundTripper(backend detector.Backend) *RoundTripper {
+	return &RoundTripper{Backend: backend, Options: render.DefaultOptions()}
+}
+
+// Check detects imagePath, redraws it in the canonical layout, detects the
+// redrawing and reports where the two differ. Positions are not compared,
+// since redrawing moves symbols on purpose.
+func (r *RoundTripper) Check(imagePath string) Result {
+	result := Result{Image: imagePath}
+
+	symbols, connections, err := r.Backend.Detect(imagePath)
+	if err != nil {
+		result.Err = err
+		return result
+	}
+	laidOut, straightened := render.Canonical(symbols, connections, r.Options)
+	img := render.Render(laidOut, straightened, r.Options)
+	redrawnSymbols, redrawnConnections, err := r.Backend.DetectImage(img)
+	if err != nil {
+		result.add(KindDetection, err.Error())
+		return result
+	}
+
+	if len(symbols) != len(redrawnSymbols) {
+		result.add(KindCount, fmt.Sprintf("drawing has %d symbols, redrawing has %d",
+			len(symbols), len(redrawnSymbols)))
+	}
+	compareTypes(&result, symbols, redrawnSymbols, "drawing", "redrawing")
+
+	drawnAST, redrawnAST := encodeAST(symbols, connections), encodeAST(redrawnSymbols, redrawnConnections)
+	if drawnAST != redrawnAST {
+		result.add(KindAST, spelltest.Diff(drawnAST, redrawnAST))
+	}
+	return result
+}
+
+// CheckAll round-trips every fixture in order
+func (r *RoundTripper) CheckAll(images []string) []Result {
+	results := make([]Result, 0, len(images))
+	for _, image := range images {
+		results = append(results, r.Check(image))
+	}
+	return results
+}
+
+// Fidelity returns the share of results that conform, from 0 to 1. Without
+// results it is 1.
+func Fidelity(results []Result) float64 {
+	if len(results) == 0 {
+		return 1
+	}
+	conforming := 0
+	for _, result := range results {
+		if result.Conforms() {
+			conforming++
+		}
+	}
+	return float64(conforming) / float64(len(results))
+}
@@ -0,0 +1,121 @@
+package conformance
+
+import (
+	"errors"
+	"image"
+	"io"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// redrawBackend detects drawn in every file and redrawn in every image
+type redrawBackend struct {
+	drawn, redrawn []*detector.Symbol
+	err            error
+}
+
+func (b redrawBackend) Detect(string) ([]*detector.Symbol, []detector.Connection, error) {
+	return b.drawn, nil, nil
+}
+
+func (b redrawBackend) DetectFromReader(io.Reader, string) ([]*detector.Symbol, []detector.Connection, error) {
+	return b.drawn, nil, nil
+}
+
+func (b redrawBackend) DetectImage(image.Image) ([]*detector.Symbol, []detector.Connection, error) {
+	return b.redrawn, nil, b.err
+}
+
+func TestRoundTripConforms(t *testing.T) {
+	backend := redrawBackend{
+		drawn:   []*detector.Symbol{symbolAt(detector.OuterCircle, 100, 100), symbolAt(detector.Star, 100, 150)},
+		redrawn: []*detector.Symbol{symbolAt(detector.OuterCircle, 300, 300), symbolAt(detector.Star, 300, 420)},
+	}
+
+	result := NewRoundTripper(backend).Check("spell.png")
+	assert.True(t, result.Conforms(), "positions are not compared: %v", result.Divergences)
+}
+
+func TestRoundTripDivergences(t *testing.T) {
+	backend := redrawBackend{
+		drawn:   []*detector.Symbol{symbolAt(detector.OuterCircle, 100, 100), symbolAt(detector.Star, 100, 150)},
+		redrawn: []*detector.Symbol{symbolAt(detector.OuterCircle, 300, 300)},
+	}
+
+	result := NewRoundTripper(backend).Check("spell.png")
+	require.False(t, result.Conforms())
+
+	kinds := make(map[string]string)
+	for _, d := range result.Divergences {
+		kinds[d.Kind] = d.Detail
+	}
+	assert.Contains(t, kinds[KindCount], "drawing has 2 symbols, redrawing has 1")
+	assert.Contains(t, kinds[KindType], "star: drawing found 1, redrawing found 0")
+}
+
+func TestRoundTripDetectionError(t *testing.T) {
+	backend := redrawBackend{
+		drawn: []*detector.Symbol{symbolAt(detector.OuterCircle, 100, 100)},
+		err:   errors.New("no outer circle"),
+	}
+
+	result := NewRoundTripper(backend).Check("spell.png")
+	require.NoError(t, result.Err)
+	require.Len(t, result.Divergences, 1)
+	assert.Equal(t, KindDetection, result.Divergences[0].Kind)
+}
+
+func TestFidelity(t *testing.T) {
+	conforming := Result{Image: "a.png"}
+	diverging := Result{Image: "b.png", Divergences: []Divergence{{Kind: KindAST}}}
+	failing := Result{Image: "c.png", Err: errors.New("unreadable")}
+
+	assert.Equal(t, 1.0, Fidelity(nil))
+	assert.InDelta(t, 1.0/3, Fidelity([]Result{conforming, diverging, failing}), 1e-9)
+}
+
+// roundTripExamples are the example images known to read back as drawn
+// once redrawn; a change making one of them diverge is a regression
+var roundTripExamples = map[string]bool{
+	"hello_world.png": true,
+}
+
+// TestRoundTripExamples round-trips the example images through the real
+// detector, requiring those known to round-trip to conform, and reports
+// the fidelity of the pipeline
+func TestRoundTripExamples(t *testing.T) {
+	if testing.Short() {
+		t.Skip("Round-tripping the examples is slow")
+	}
+	images, err := Discover(filepath.Join("..", "..", "examples", "images"))
+	if err != nil || len(images) == 0 {
+		t.Skip("Example images not found")
+	}
+	backend, err := detector.NewBackend(detector.BackendSequential, detector.Config{})
+	require.NoError(t, err)
+
+	results := NewRoundTripper(backend).CheckAll(images)
+	checked := 0
+	for _, result := range results {
+		name := filepath.Base(result.Image)
+		if roundTripExamples[name] {
+			checked++
+			assert.NoError(t, result.Err, name)
+			assert.True(t, result.Conforms(), "%s no longer round-trips: %v", name, result.Divergences)
+			continue
+		}
+		if result.Err != nil {
+			t.Logf("%s: %v", name, result.Err)
+		}
+		for _, d := range result.Divergences {
+			t.Logf("%s: %s: %s", name, d.Kind, d.Detail)
+		}
+	}
+	assert.Equal(t, len(roundTripExamples), checked, "every example known to round-trip was checked")
+	assert.GreaterOrEqual(t, Fidelity(results), float64(len(roundTripExamples))/float64(len(results)))
+	t.Logf("round-trip fidelity: %.1f%% of %d examples", 100*Fidelity(results), len(results))
+}
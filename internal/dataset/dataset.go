@@ -0,0 +1,194 @@
+// Package dataset crops detected or hand-labeled symbols out of drawings
+// into labeled image patches for training symbol classifiers.
+//
+// Patches are written in the class-per-directory layout most training
+// tools read directly, with a CSV manifest describing each of them:
+//
+//	dataset/
+//	  labels.csv
+//	  star/circle_star-1a2b3c4d.png
+//	  square/circle_square-5e6f7a8b.png
+package dataset
+
+import (
+	"encoding/csv"
+	"fmt"
+	"image"
+	"image/color"
+	"image/png"
+	"math"
+	"os"
+	"path/filepath"
+	"strconv"
+	"strings"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// Defaults for Crop
+const (
+	// DefaultPatchSize is the side of a patch in pixels
+	DefaultPatchSize = 64
+	// DefaultMargin is the space left around a symbol, as a fraction of its
+	// extent on each side
+	DefaultMargin = 0.25
+)
+
+// ManifestFile is the name of the manifest written next to the patches
+const ManifestFile = "labels.csv"
+
+// manifestHeader names the columns of the manifest
+var manifestHeader = []string{"file", "label", "pattern", "source", "x", "y", "size", "confidence"}
+
+// Patch is the square of a drawing around one symbol, scaled to a fixed size
+type Patch struct {
+	Symbol *detector.Symbol
+	// Bounds is the square cropped, in the pixels of the drawing
+	Bounds image.Rectangle
+	Image  *image.Gray
+}
+
+// Label returns the class of the patch: the type of its symbol
+func (p Patch) Label() string {
+	return string(p.Symbol.Type)
+}
+
+// Crop cuts a size by size grayscale patch out of img around each symbol.
+// The square cropped holds the symbol with margin of its extent to spare
+// on each side; parts of it outside img are white. The outer circle, which
+// spans the whole drawing, and unknown shapes are left out.
+func Crop(img image.Image, symbols []*detector.Symbol, size int, margin float64) []Patch {
+	var patches []Patch
+	for _, s := range symbols {
+		if s.Type == detector.OuterCircle || s.Type == detector.Unknown {
+			continue
+		}
+		extent := 2 * s.Radius()
+		if s.ListFrame {
+			extent = math.Max(s.Width, s.Height)
+		}
+		half := extent * (1 + 2*margin) / 2
+		bounds := image.Rect(
+			int(math.Floor(s.Position.X-half)), int(math.Floor(s.Position.Y-half)),
+			int(math.Ceil(s.Position.X+half)), int(math.Ceil(s.Position.Y+half)),
+		)
+		if bounds.Empty() {
+			continue
+		}
+		patches = append(patches, Patch{Symbol: s, Bounds: bounds, Image: sample(img, bounds, size)})
+	}
+	return patches
+}
+
+// sample scales the part of img within bounds to a size by size image, each
+// pixel the mean of the pixels it covers
+func sample(img image.Image, bounds image.Rectangle, size int) *image.Gray {
+	patch := image.NewGray(image.Rect(0, 0, size, size))
+	scale := float64(bounds.Dx()) / float64(size)
+	inside := img.Bounds()
+	for y := 0; y < size; y++ {
+		y0 := bounds.Min.Y + int(float64(y)*scale)
+		y1 := max(bounds.Min.Y+int(float64(y+1)*scale), y0+1)
+		for x := 0; x < size; x++ {
+			x0 := bounds.Min.X + int(float64(x)*scale)
+			x1 := max(bounds.Min.X+int(float64(x+1)*scale), x0+1)
+
+			var sum, count uint32
+			for sy := y0; sy < y1; sy++ {
+				for sx := x0; sx < x1; sx++ {
+					gray := uint8(255)
+					if (image.Point{X: sx, Y: sy}).In(inside) {
+						gray = color.GrayModel.Convert(img.At(sx, sy)).(color.Gray).Y
+					}
+					sum += uint32(gray)
+					count++
+				}
+			}
+			patch.SetGray(x, y, color.Gray{Y: uint8(sum / count)})
+		}
+	}
+	return patch
+}
+
+// Record describes one patch written to a dataset
+type Record struct {
+	// File is the path of the patch relative to the dataset directory
+	File       string
+	Label      string
+	Pattern    string
+	Source     string
+	Position   detector.Position
+	Size       float64
+	Confidence float64
+}
+
+// Write saves patches cut from the drawing at source into dir, each in the
+// directory of its label and named after the drawing and the ID of its
+// symbol, and returns their records
+func Write(dir, source string, patches []Patch) ([]Record, error) {
+	base := strings.TrimSuffix(filepath.Base(source), filepath.Ext(source))
+	records := make([]Record, 0, len(patches))
+	for i, p := range patches {
+		id := p.Symbol.ID
+		if id == "" {
+			id = strconv.Itoa(i)
+		}
+		file := filepath.Join(p.Label(), base+"_"+id+".png")
+		if err := writePNG(filepath.Join(dir, file), p.Image); err != nil {
+			return records, err
+		}
+		records = append(records, Record{
+			File:       filepath.ToSlash(file),
+			Label:      p.Label(),
+			Pattern:    p.Symbol.Pattern,
+			Source:     filepath.Base(source),
+			Position:   p.Symbol.Position,
+			Size:       p.Symbol.Size,
+			Confidence: p.Symbol.Confidence,
+		})
+	}
+	return records, nil
+}
+
+// WriteManifest writes the manifest of the records into dir
+func WriteManifest(dir string, records []Record) error {
+	file, err := os.Create(filepath.Join(dir, ManifestFile))
+	if err != nil {
+		return err
+	}
+	w := csv.NewWriter(file)
+	_ = w.Write(manifestHeader)
+	for _, r := range records {
+		_ = w.Write([]string{
+			r.File, r.Label, r.Pattern, r.Source,
+			formatFloat(r.Position.X), formatFloat(r.Position.Y), formatFloat(r.Size), formatFloat(r.Confidence),
+		})
+	}
+	w.Flush()
+	if err := w.Error(); err != nil {
+		file.Close()
+		return err
+	}
+	return file.Close()
+}
+
+// writePNG encodes img as a PNG file at path, creating its directory
+func writePNG(path string, img image.Image) error {
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return err
+	}
+	file, err := os.Create(path)
+	if err != nil {
+		return err
+	}
+	if err := png.Encode(file, img); err != nil {
+		file.Close()
+		return fmt.Errorf("encoding %s: %w", path, err)
+	}
+	return file.Close()
+}
+
+// formatFloat formats a manifest number without trailing zeros
+func formatFloat(f float64) string {
+	return strconv.FormatFloat(f, 'f', -1, 64)
+}
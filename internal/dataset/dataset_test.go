@@ -0,0 +1,80 @@
+package dataset
+
+import (
+	"image"
+	"image/color"
+	"math"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// drawing returns a white image with a black square from (40, 40) to
+// (60, 60)
+func drawing() *image.Gray {
+	img := image.NewGray(image.Rect(0, 0, 100, 100))
+	for y := 0; y < 100; y++ {
+		for x := 0; x < 100; x++ {
+			c := uint8(255)
+			if x >= 40 && x < 60 && y >= 40 && y < 60 {
+				c = 0
+			}
+			img.SetGray(x, y, color.Gray{Y: c})
+		}
+	}
+	return img
+}
+
+func TestCrop(t *testing.T) {
+	// A symbol of radius 10 around the square, and one at the corner
+	square := &detector.Symbol{ID: "square-1", Type: detector.Square, Position: detector.Position{X: 50, Y: 50}, Size: 10 * math.Sqrt(math.Pi)}
+	corner := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 0, Y: 0}, Size: 10 * math.Sqrt(math.Pi)}
+	outer := &detector.Symbol{Type: detector.OuterCircle, Position: detector.Position{X: 50, Y: 50}, Size: 100}
+
+	patches := Crop(drawing(), []*detector.Symbol{outer, square, corner}, 20, 0.5)
+	require.Len(t, patches, 2, "the outer circle is left out")
+
+	// With half the extent to spare on each side, the square fills the
+	// middle half of its patch
+	p := patches[0]
+	assert.Equal(t, "square", p.Label())
+	assert.Equal(t, image.Rect(30, 30, 70, 70), p.Bounds)
+	assert.Equal(t, image.Rect(0, 0, 20, 20), p.Image.Bounds())
+	assert.Equal(t, uint8(0), p.Image.GrayAt(10, 10).Y)
+	assert.Equal(t, uint8(255), p.Image.GrayAt(2, 2).Y)
+
+	// Outside the drawing is white
+	assert.Equal(t, uint8(255), patches[1].Image.GrayAt(0, 0).Y)
+}
+
+func TestWrite(t *testing.T) {
+	dir := t.TempDir()
+	square := &detector.Symbol{ID: "square-1", Type: detector.Square, Position: detector.Position{X: 50, Y: 50},
+		Size: 20, Confidence: 0.9, Pattern: detector.PatternDot}
+	star := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 20, Y: 20}, Size: 20, Confidence: 1}
+
+	records, err := Write(dir, filepath.Join("drawings", "circle.png"), Crop(drawing(), []*detector.Symbol{square, star}, 16, DefaultMargin))
+	require.NoError(t, err)
+	require.Len(t, records, 2)
+	assert.Equal(t, Record{
+		File: "square/circle_square-1.png", Label: "square", Pattern: detector.PatternDot, Source: "circle.png",
+		Position: square.Position, Size: 20, Confidence: 0.9,
+	}, records[0])
+	assert.Equal(t, "star/circle_1.png", records[1].File, "symbols without an ID are numbered")
+	for _, r := range records {
+		assert.FileExists(t, filepath.Join(dir, filepath.FromSlash(r.File)))
+	}
+
+	require.NoError(t, WriteManifest(dir, records))
+	manifest, err := os.ReadFile(filepath.Join(dir, ManifestFile))
+	require.NoError(t, err)
+	lines := strings.Split(strings.TrimSpace(string(manifest)), "\n")
+	require.Len(t, lines, 3)
+	assert.Equal(t, "file,label,pattern,source,x,y,size,confidence", lines[0])
+	assert.Equal(t, "square/circle_square-1.png,square,dot,circle.png,50,50,20,0.9", lines[1])
+}
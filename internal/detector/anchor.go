@@ -0,0 +1,72 @@
+package detector
+
+import (
+	"image"
+	"math"
+)
+
+// Anchor is where a connection meets the outline of a symbol, as the angle
+// in radians from the symbol's center. Angles follow image coordinates: 0
+// points right and π/2 points down, so -π/2 is the top of the symbol.
+type Anchor struct {
+	Angle float64 `json:"angle"`
+}
+
+// Sides of a symbol that an anchor can fall on. In the dialect, a line
+// entering at the top is an input and one leaving at the bottom an output.
+const (
+	AnchorTop    = "top"
+	AnchorRight  = "right"
+	AnchorBottom = "bottom"
+	AnchorLeft   = "left"
+)
+
+// Side returns the side of the symbol the anchor falls on, splitting the
+// outline into quarters centered on each side
+func (a Anchor) Side() string {
+	angle := math.Mod(a.Angle+math.Pi/4, 2*math.Pi)
+	if angle < 0 {
+		angle += 2 * math.Pi
+	}
+	switch int(angle / (math.Pi / 2)) {
+	case 0:
+		return AnchorRight
+	case 1:
+		return AnchorBottom
+	case 2:
+		return AnchorLeft
+	default:
+		return AnchorTop
+	}
+}
+
+// lineAnchor returns where the line from far to near, near being the end
+// closer to symbol, crosses the outline of symbol. A line that misses the
+// outline is anchored in the direction of near.
+func lineAnchor(symbol *Symbol, near, far image.Point) *Anchor {
+	cx, cy := symbol.Position.X, symbol.Position.Y
+	fx, fy := float64(far.X)-cx, float64(far.Y)-cy
+	dx, dy := float64(near.X-far.X), float64(near.Y-far.Y)
+
+	// Solve |far + t*(near - far)| = r for the crossing where the line
+	// enters the outline, or leaves it when far is already inside
+	r := symbol.Radius()
+	a := dx*dx + dy*dy
+	b := 2 * (fx*dx + fy*dy)
+	c := fx*fx + fy*fy - r*r
+	if discriminant := b*b - 4*a*c; a > 0 && discriminant >= 0 {
+		root := math.Sqrt(discriminant)
+		if c < 0 {
+			root = -root
+		}
+		t := (-b - root) / (2 * a)
+		return &Anchor{Angle: math.Atan2(fy+t*dy, fx+t*dx)}
+	}
+	return &Anchor{Angle: math.Atan2(float64(near.Y)-cy, float64(near.X)-cx)}
+}
+
+// centerAnchor returns the anchor of a straight connection from the center
+// of symbol to the center of other
+func centerAnchor(symbol, other *Symbol) *Anchor {
+	return &Anchor{Angle: math.Atan2(other.Position.Y-symbol.Position.Y, other.Position.X-symbol.Position.X)}
+}
@@ -0,0 +1,79 @@
+package detector
+
+import (
+	"image"
+	"math"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestAnchorSide(t *testing.T) {
+	tests := []struct {
+		angle float64
+		side  string
+	}{
+		{0, AnchorRight},
+		{math.Pi / 2, AnchorBottom},
+		{math.Pi, AnchorLeft},
+		{-math.Pi, AnchorLeft},
+		{-math.Pi / 2, AnchorTop},
+		{3 * math.Pi / 2, AnchorTop},
+		{math.Pi / 5, AnchorRight},
+		{-3 * math.Pi / 5, AnchorTop},
+	}
+
+	for _, tt := range tests {
+		assert.Equal(t, tt.side, Anchor{Angle: tt.angle}.Side(), "angle %.2f", tt.angle)
+	}
+}
+
+func TestLineAnchor(t *testing.T) {
+	// An outline radius of 20
+	symbol := &Symbol{Type: Square, Position: Position{X: 100, Y: 100}, Size: 20 * math.Sqrt(math.Pi)}
+
+	// A line arriving from above, ending at the center
+	anchor := lineAnchor(symbol, image.Point{X: 100, Y: 100}, image.Point{X: 100, Y: 20})
+	assert.InDelta(t, -math.Pi/2, anchor.Angle, 1e-9)
+	assert.Equal(t, AnchorTop, anchor.Side())
+
+	// A line from the right that stops short of the outline
+	anchor = lineAnchor(symbol, image.Point{X: 130, Y: 100}, image.Point{X: 200, Y: 100})
+	assert.InDelta(t, 0, anchor.Angle, 1e-9)
+
+	// A line from below, offset from the center, crosses the outline where
+	// it enters rather than at the angle of its end
+	anchor = lineAnchor(symbol, image.Point{X: 110, Y: 100}, image.Point{X: 110, Y: 200})
+	assert.InDelta(t, math.Atan2(math.Sqrt(300), 10), anchor.Angle, 1e-9)
+	assert.Equal(t, AnchorBottom, anchor.Side())
+
+	// A line passing beside the outline points at its near end
+	anchor = lineAnchor(symbol, image.Point{X: 50, Y: 150}, image.Point{X: 50, Y: 250})
+	assert.InDelta(t, math.Atan2(50, -50), anchor.Angle, 1e-9)
+}
+
+func TestCenterAnchor(t *testing.T) {
+	upper := &Symbol{Position: Position{X: 100, Y: 100}}
+	lower := &Symbol{Position: Position{X: 100, Y: 200}}
+
+	assert.Equal(t, AnchorBottom, centerAnchor(upper, lower).Side())
+	assert.Equal(t, AnchorTop, centerAnchor(lower, upper).Side())
+}
+
+func TestAnchorJSONRoundTrip(t *testing.T) {
+	from := &Symbol{Type: Square, Position: Position{X: 100, Y: 100}, Size: 30}
+	to := &Symbol{Type: Star, Position: Position{X: 100, Y: 200}, Size: 30}
+	connections := []Connection{
+		{From: from, To: to, ConnectionType: ConnectionTypeSolid,
+			FromAnchor: &Anchor{Angle: math.Pi / 2}, ToAnchor: &Anchor{Angle: -math.Pi / 2}},
+		{From: to, To: from, ConnectionType: ConnectionTypeDashed},
+	}
+
+	data, err := EncodeJSON([]*Symbol{from, to}, connections)
+	require.NoError(t, err)
+	_, decoded, err := DecodeJSON(data)
+	require.NoError(t, err)
+	assert.Equal(t, connections, decoded)
+	assert.Nil(t, decoded[1].FromAnchor, "a connection without anchors keeps them unknown")
+}
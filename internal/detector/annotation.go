@@ -0,0 +1,295 @@
+package detector
+
+import (
+	"encoding/json"
+	"io"
+	"math"
+	"path/filepath"
+	"sort"
+	"strings"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+)
+
+// connectionLabel labels the annotations of lines between symbols. The
+// connection type may follow it after a colon, as in "connection:dashed".
+const connectionLabel = "connection"
+
+// labelMeFile is the part of a LabelMe annotation file that is read
+type labelMeFile struct {
+	Shapes []labelMeShape `json:"shapes"`
+}
+
+// labelMeShape is one labeled shape of a LabelMe annotation file
+type labelMeShape struct {
+	Label     string       `json:"label"`
+	Points    [][2]float64 `json:"points"`
+	ShapeType string       `json:"shape_type"`
+}
+
+// cocoFile is the part of a COCO annotation file that is read. The
+// segmentation of an annotation holds polygons, or a run-length mask of
+// which only the bounding box is used.
+type cocoFile struct {
+	Images []struct {
+		ID       int    `json:"id"`
+		FileName string `json:"file_name"`
+	} `json:"images"`
+	Annotations []struct {
+		ImageID      int             `json:"image_id"`
+		CategoryID   int             `json:"category_id"`
+		BBox         []float64       `json:"bbox"`
+		Area         float64         `json:"area"`
+		Segmentation json.RawMessage `json:"segmentation"`
+	} `json:"annotations"`
+	Categories []struct {
+		ID   int    `json:"id"`
+		Name string `json:"name"`
+	} `json:"categories"`
+}
+
+// annotatedShape is a labeled shape read from either format: the outline of
+// a symbol, or the path of a connection
+type annotatedShape struct {
+	label  string
+	points []StrokePoint
+	// area is the area the shape encloses
+	area float64
+}
+
+// DecodeAnnotations reads symbols and connections labeled by hand in a
+// LabelMe or COCO annotation file, in place of detecting them. A shape is
+// labeled with its symbol type, optionally followed by its pattern after a
+// colon, as in "square:dot"; lines labeled "connection" join the symbols
+// their ends stop at, from the first point to the last. image names the
+// image of a COCO file labeling several; it may be empty otherwise.
+func DecodeAnnotations(r io.Reader, image string) ([]*Symbol, []Connection, error) {
+	data, err := io.ReadAll(r)
+	if err != nil {
+		return nil, nil, annotationError(i18n.T("detector.annotations_decode_failed")).WithInnerError(err)
+	}
+	var probe struct {
+		Shapes      json.RawMessage `json:"shapes"`
+		Annotations json.RawMessage `json:"annotations"`
+		Categories  json.RawMessage `json:"categories"`
+	}
+	if err := json.Unmarshal(data, &probe); err != nil {
+		return nil, nil, annotationError(i18n.T("detector.annotations_decode_failed")).WithInnerError(err)
+	}
+
+	var shapes []annotatedShape
+	switch {
+	case probe.Shapes != nil:
+		shapes, err = labelMeShapes(data)
+	case probe.Annotations != nil && probe.Categories != nil:
+		shapes, err = cocoShapes(data, image)
+	default:
+		return nil, nil, annotationError(i18n.T("detector.annotations_unknown_format")).
+			WithSuggestion(i18n.T("detector.annotations_unknown_format_suggestion"))
+	}
+	if err != nil {
+		return nil, nil, err
+	}
+	return annotatedSymbols(shapes)
+}
+
+// labelMeShapes reads the shapes of a LabelMe file
+func labelMeShapes(data []byte) ([]annotatedShape, error) {
+	var file labelMeFile
+	if err := json.Unmarshal(data, &file); err != nil {
+		return nil, annotationError(i18n.T("detector.annotations_decode_failed")).WithInnerError(err)
+	}
+
+	shapes := make([]annotatedShape, 0, len(file.Shapes))
+	for _, s := range file.Shapes {
+		points := make([]StrokePoint, len(s.Points))
+		for i, p := range s.Points {
+			points[i] = StrokePoint{X: p[0], Y: p[1]}
+		}
+		if len(points) == 0 {
+			return nil, annotationError(i18n.Tf("detector.annotation_no_points", s.Label))
+		}
+
+		shape := annotatedShape{label: s.Label, points: points}
+		switch s.ShapeType {
+		case "circle":
+			// The center, then a point on the circle
+			if len(points) < 2 {
+				return nil, annotationError(i18n.Tf("detector.annotation_no_points", s.Label))
+			}
+			r := math.Hypot(points[1].X-points[0].X, points[1].Y-points[0].Y)
+			shape.points = []StrokePoint{points[0]}
+			shape.area = math.Pi * r * r
+		case "rectangle":
+			// Two opposite corners
+			if len(points) < 2 {
+				return nil, annotationError(i18n.Tf("detector.annotation_no_points", s.Label))
+			}
+			a, b := points[0], points[1]
+			shape.points = []StrokePoint{a, {X: b.X, Y: a.Y}, b, {X: a.X, Y: b.Y}}
+			shape.area = math.Abs((b.X - a.X) * (b.Y - a.Y))
+		default:
+			shape.area = polygonArea(points)
+		}
+		shapes = append(shapes, shape)
+	}
+	return shapes, nil
+}
+
+// cocoShapes reads the annotations of the named image of a COCO file
+func cocoShapes(data []byte, image string) ([]annotatedShape, error) {
+	var file cocoFile
+	if err := json.Unmarshal(data, &file); err != nil {
+		return nil, annotationError(i18n.T("detector.annotations_decode_failed")).WithInnerError(err)
+	}
+
+	imageID, found := 0, false
+	switch {
+	case image != "":
+		for _, img := range file.Images {
+			if filepath.Base(img.FileName) == filepath.Base(image) {
+				imageID, found = img.ID, true
+				break
+			}
+		}
+		if !found {
+			return nil, annotationError(i18n.Tf("detector.annotation_no_image", filepath.Base(image)))
+		}
+	case len(file.Images) > 1:
+		return nil, annotationError(i18n.Tf("detector.annotation_several_images", len(file.Images))).
+			WithSuggestion(i18n.T("detector.annotation_several_images_suggestion"))
+	case len(file.Images) == 1:
+		imageID, found = file.Images[0].ID, true
+	}
+
+	categories := make(map[int]string, len(file.Categories))
+	for _, c := range file.Categories {
+		categories[c.ID] = c.Name
+	}
+
+	var shapes []annotatedShape
+	for _, a := range file.Annotations {
+		if found && a.ImageID != imageID {
+			continue
+		}
+		label, ok := categories[a.CategoryID]
+		if !ok {
+			return nil, annotationError(i18n.Tf("detector.annotation_unknown_category", a.CategoryID))
+		}
+
+		var segments [][]float64
+		_ = json.Unmarshal(a.Segmentation, &segments)
+		shape := annotatedShape{label: label}
+		if len(segments) > 0 && len(segments[0]) >= 2 {
+			for i := 0; i+1 < len(segments[0]); i += 2 {
+				shape.points = append(shape.points, StrokePoint{X: segments[0][i], Y: segments[0][i+1]})
+			}
+			shape.area = polygonArea(shape.points)
+		} else if len(a.BBox) == 4 {
+			x, y, w, h := a.BBox[0], a.BBox[1], a.BBox[2], a.BBox[3]
+			shape.points = []StrokePoint{{X: x, Y: y}, {X: x + w, Y: y}, {X: x + w, Y: y + h}, {X: x, Y: y + h}}
+			shape.area = w * h
+		} else {
+			return nil, annotationError(i18n.Tf("detector.annotation_no_points", label))
+		}
+		if a.Area > 0 {
+			shape.area = a.Area
+		}
+		shapes = append(shapes, shape)
+	}
+	return shapes, nil
+}
+
+// annotatedSymbols builds symbols and connections from labeled shapes. The
+// symbols are ordered largest first, as detection orders them.
+func annotatedSymbols(shapes []annotatedShape) ([]*Symbol, []Connection, error) {
+	known := make(map[SymbolType]bool)
+	for _, t := range SymbolTypes() {
+		known[t] = true
+	}
+	patterns := make(map[string]bool)
+	for _, p := range Patterns() {
+		patterns[p] = true
+	}
+
+	var symbols []*Symbol
+	var lines []annotatedShape
+	for _, shape := range shapes {
+		name, detail, _ := strings.Cut(strings.TrimSpace(shape.label), ":")
+		if name == connectionLabel {
+			lines = append(lines, shape)
+			continue
+		}
+		symbolType := SymbolType(name)
+		if !known[symbolType] {
+			return nil, nil, annotationError(i18n.Tf("detector.annotation_unknown_label", shape.label)).
+				WithSuggestion(i18n.T("detector.annotation_unknown_label_suggestion"))
+		}
+		pattern := PatternEmpty
+		if detail != "" {
+			if !patterns[detail] {
+				return nil, nil, annotationError(i18n.Tf("detector.annotation_unknown_pattern", shape.label))
+			}
+			pattern = detail
+		}
+
+		center := shapeCenter(shape.points)
+		symbols = append(symbols, &Symbol{
+			Type:       symbolType,
+			Position:   Position{X: center.X, Y: center.Y},
+			Size:       math.Sqrt(shape.area),
+			Confidence: 1,
+			Pattern:    pattern,
+		})
+	}
+	if len(symbols) == 0 {
+		return nil, nil, grimoireErrors.NoSymbolsError()
+	}
+	sort.SliceStable(symbols, func(i, j int) bool { return symbols[i].Size > symbols[j].Size })
+	markRingGlyphs(symbols)
+
+	interior := interiorSymbols(symbols)
+	var connections []Connection
+	for _, line := range lines {
+		from := strokeEndSymbol(interior, line.points[0])
+		to := strokeEndSymbol(interior, line.points[len(line.points)-1])
+		if from == nil || to == nil || from == to {
+			continue
+		}
+		connectionType := ConnectionTypeSolid
+		if _, detail, ok := strings.Cut(line.label, ":"); ok && detail != "" {
+			connectionType = detail
+		}
+		connections = append(connections, Connection{From: from, To: to, ConnectionType: connectionType})
+	}
+	AssignIDs(symbols)
+	return symbols, connections, nil
+}
+
+// shapeCenter returns the mean of the points of a shape
+func shapeCenter(points []StrokePoint) StrokePoint {
+	var center StrokePoint
+	for _, p := range points {
+		center.X += p.X
+		center.Y += p.Y
+	}
+	n := float64(len(points))
+	return StrokePoint{X: center.X / n, Y: center.Y / n}
+}
+
+// polygonArea returns the area enclosed by the polygon through points
+func polygonArea(points []StrokePoint) float64 {
+	area := 0.0
+	for i, p := range points {
+		q := points[(i+1)%len(points)]
+		area += p.X*q.Y - q.X*p.Y
+	}
+	return math.Abs(area) / 2
+}
+
+// annotationError returns the error for an annotation file that cannot be
+// read
+func annotationError(message string) *grimoireErrors.GrimoireError {
+	return grimoireErrors.NewError(grimoireErrors.UnsupportedFormat, message)
+}
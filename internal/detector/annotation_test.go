@@ -0,0 +1,122 @@
+package detector
+
+import (
+	"math"
+	"strings"
+	"testing"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+const labelMeAnnotations = `{
+  "version": "5.2.1",
+  "imagePath": "circle.png",
+  "shapes": [
+    {"label": "star", "points": [[180, 230], [220, 230], [220, 270], [180, 270]], "shape_type": "polygon"},
+    {"label": "outer_circle", "points": [[200, 200], [380, 200]], "shape_type": "circle"},
+    {"label": "square:dot", "points": [[290, 240], [330, 280]], "shape_type": "rectangle"},
+    {"label": "connection", "points": [[310, 240], [221, 250]], "shape_type": "line"}
+  ]
+}`
+
+func TestDecodeAnnotationsLabelMe(t *testing.T) {
+	symbols, connections, err := DecodeAnnotations(strings.NewReader(labelMeAnnotations), "")
+	require.NoError(t, err)
+	require.Len(t, symbols, 3)
+
+	// Largest first, as detection orders them
+	assert.Equal(t, OuterCircle, symbols[0].Type)
+	assert.Equal(t, Position{X: 200, Y: 200}, symbols[0].Position)
+	assert.InDelta(t, 180, symbols[0].Radius(), 1e-9)
+
+	star, square := symbols[1], symbols[2]
+	assert.Equal(t, Star, star.Type)
+	assert.Equal(t, Position{X: 200, Y: 250}, star.Position)
+	assert.InDelta(t, 40, star.Size, 1e-9)
+	assert.Equal(t, PatternEmpty, star.Pattern)
+	assert.Equal(t, Square, square.Type)
+	assert.Equal(t, PatternDot, square.Pattern)
+	assert.Equal(t, 1.0, square.Confidence)
+	assert.NotEmpty(t, square.ID)
+
+	require.Len(t, connections, 1)
+	assert.Same(t, square, connections[0].From)
+	assert.Same(t, star, connections[0].To)
+	assert.Equal(t, "solid", connections[0].ConnectionType)
+}
+
+const cocoAnnotations = `{
+  "images": [{"id": 1, "file_name": "first.png"}, {"id": 2, "file_name": "second.png"}],
+  "categories": [{"id": 1, "name": "outer_circle"}, {"id": 2, "name": "star"}, {"id": 3, "name": "connection:dashed"}],
+  "annotations": [
+    {"id": 1, "image_id": 1, "category_id": 2, "bbox": [0, 0, 10, 10]},
+    {"id": 2, "image_id": 2, "category_id": 1, "bbox": [20, 20, 360, 360], "segmentation": {"counts": "", "size": [400, 400]}},
+    {"id": 3, "image_id": 2, "category_id": 2, "bbox": [100, 100, 40, 40], "segmentation": [[120, 100, 140, 140, 100, 140]]},
+    {"id": 4, "image_id": 2, "category_id": 2, "bbox": [250, 100, 40, 40], "area": 900},
+    {"id": 5, "image_id": 2, "category_id": 3, "segmentation": [[150, 125, 240, 120]]}
+  ]
+}`
+
+func TestDecodeAnnotationsCOCO(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	symbols, connections, err := DecodeAnnotations(strings.NewReader(cocoAnnotations), "images/second.png")
+	require.NoError(t, err)
+	require.Len(t, symbols, 3)
+
+	assert.Equal(t, OuterCircle, symbols[0].Type)
+	assert.Equal(t, Position{X: 200, Y: 200}, symbols[0].Position)
+	assert.InDelta(t, 360, symbols[0].Size, 1e-9, "a mask is read by its bounding box")
+
+	// The segmentation outlines the triangle, and an area given is used
+	assert.Equal(t, Position{X: 270, Y: 120}, symbols[1].Position)
+	assert.InDelta(t, 30, symbols[1].Size, 1e-9)
+	assert.InDelta(t, 120, symbols[2].Position.X, 1e-9)
+	assert.InDelta(t, 380.0/3, symbols[2].Position.Y, 1e-9)
+	assert.InDelta(t, math.Sqrt(800), symbols[2].Size, 1e-9)
+
+	require.Len(t, connections, 1)
+	assert.Same(t, symbols[2], connections[0].From)
+	assert.Same(t, symbols[1], connections[0].To)
+	assert.Equal(t, "dashed", connections[0].ConnectionType)
+
+	_, _, err = DecodeAnnotations(strings.NewReader(cocoAnnotations), "")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "2 images")
+	_, _, err = DecodeAnnotations(strings.NewReader(cocoAnnotations), "third.png")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "third.png")
+}
+
+func TestDecodeAnnotationsErrors(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	tests := []struct {
+		name, data, message string
+	}{
+		{"not json", `{`, "decode"},
+		{"unknown format", `{"objects": []}`, "neither LabelMe nor COCO"},
+		{"unknown label", `{"shapes": [{"label": "dragon", "points": [[0, 0], [1, 1]], "shape_type": "rectangle"}]}`, `"dragon"`},
+		{"unknown pattern", `{"shapes": [{"label": "star:stripes", "points": [[0, 0], [1, 1]], "shape_type": "rectangle"}]}`, `"star:stripes"`},
+		{"no points", `{"shapes": [{"label": "star", "points": [], "shape_type": "polygon"}]}`, "no points"},
+		{"unknown category", `{"images": [], "categories": [], "annotations": [{"category_id": 7, "bbox": [0, 0, 1, 1]}]}`, "category 7"},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			_, _, err := DecodeAnnotations(strings.NewReader(tt.data), "")
+			require.Error(t, err)
+			assert.Contains(t, err.Error(), tt.message)
+			errType, _ := grimoireErrors.GetErrorType(err)
+			assert.Equal(t, grimoireErrors.UnsupportedFormat, errType)
+		})
+	}
+
+	_, _, err := DecodeAnnotations(strings.NewReader(`{"shapes": []}`), "")
+	errType, _ := grimoireErrors.GetErrorType(err)
+	assert.Equal(t, grimoireErrors.NoSymbolsDetected, errType)
+}
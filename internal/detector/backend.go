@@ -0,0 +1,58 @@
+package detector
+
+import (
+	"image"
+	"io"
+	"runtime"
+	"strings"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+)
+
+// Backend is a detection implementation
+type Backend interface {
+	// Detect detects the symbols and connections in the image file at imagePath
+	Detect(imagePath string) ([]*Symbol, []Connection, error)
+	// DetectFromReader detects the symbols and connections in encoded image
+	// data read from r. source names the input in error locations.
+	DetectFromReader(r io.Reader, source string) ([]*Symbol, []Connection, error)
+	// DetectImage detects the symbols and connections in a decoded image
+	DetectImage(img image.Image) ([]*Symbol, []Connection, error)
+}
+
+// imageSource names a decoded image in error locations
+const imageSource = "<image>"
+
+// Detection backends
+const (
+	BackendSequential = "sequential" // Single-threaded Detector
+	BackendParallel   = "parallel"   // Multi-threaded ParallelDetectorV2
+	BackendAuto       = "auto"       // Parallel when more than one CPU is available
+)
+
+// Backends returns the names accepted by NewBackend
+func Backends() []string {
+	return []string{BackendSequential, BackendParallel, BackendAuto}
+}
+
+// NewBackend creates the detection backend with the given name. Auto picks
+// the parallel backend when more than one CPU is available and falls back
+// to the sequential one otherwise.
+func NewBackend(name string, cfg Config) (Backend, error) {
+	switch name {
+	case BackendSequential:
+		return NewDetector(cfg), nil
+	case BackendParallel:
+		return NewParallelDetectorV2(cfg), nil
+	case BackendAuto:
+		if runtime.NumCPU() > 1 {
+			return NewParallelDetectorV2(cfg), nil
+		}
+		return NewDetector(cfg), nil
+	default:
+		return nil, grimoireErrors.NewError(grimoireErrors.ValidationError,
+			i18n.Tf("detector.unknown_backend", name)).
+			WithSuggestion(i18n.Tf("detector.unknown_backend_suggestion", strings.Join(Backends(), ", ")))
+	}
+}
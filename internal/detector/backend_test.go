@@ -0,0 +1,81 @@
+package detector
+
+import (
+	"bytes"
+	"image"
+	"image/png"
+	"os"
+	"path/filepath"
+	"testing"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestNewBackend(t *testing.T) {
+	backend, err := NewBackend(BackendSequential, Config{})
+	require.NoError(t, err)
+	assert.IsType(t, &Detector{}, backend)
+
+	backend, err = NewBackend(BackendParallel, Config{})
+	require.NoError(t, err)
+	assert.IsType(t, &ParallelDetectorV2{}, backend)
+
+	backend, err = NewBackend(BackendAuto, Config{})
+	require.NoError(t, err)
+	assert.NotNil(t, backend)
+
+	_, err = NewBackend("gpu", Config{})
+	var gErr *grimoireErrors.GrimoireError
+	require.ErrorAs(t, err, &gErr)
+	assert.Equal(t, grimoireErrors.ValidationError, gErr.Type)
+	assert.Contains(t, gErr.Message, "gpu")
+}
+
+func TestBackendsDetectExample(t *testing.T) {
+	path := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	data, err := os.ReadFile(path)
+	if err != nil {
+		t.Skip("Example image not found")
+	}
+
+	for _, name := range Backends() {
+		t.Run(name, func(t *testing.T) {
+			backend, err := NewBackend(name, Config{})
+			require.NoError(t, err)
+
+			symbols, _, err := backend.Detect(path)
+			require.NoError(t, err)
+			assert.NotEmpty(t, symbols)
+
+			fromReader, _, err := backend.DetectFromReader(bytes.NewReader(data), "<stdin>")
+			require.NoError(t, err)
+			assert.Len(t, fromReader, len(symbols))
+
+			img, err := png.Decode(bytes.NewReader(data))
+			require.NoError(t, err)
+			fromImage, _, err := backend.DetectImage(img)
+			require.NoError(t, err)
+			assert.Len(t, fromImage, len(symbols))
+		})
+	}
+}
+
+func TestDetectImageErrorLocation(t *testing.T) {
+	white := image.NewGray(image.Rect(0, 0, 100, 100))
+	for i := range white.Pix {
+		white.Pix[i] = 0xff
+	}
+
+	for _, name := range Backends() {
+		backend, err := NewBackend(name, Config{})
+		require.NoError(t, err)
+
+		_, _, err = backend.DetectImage(white)
+		var gErr *grimoireErrors.GrimoireError
+		require.ErrorAs(t, err, &gErr, name)
+		assert.Equal(t, grimoireErrors.NoSymbolsDetected, gErr.Type)
+		assert.Equal(t, imageSource, gErr.FileName)
+	}
+}
@@ -0,0 +1,99 @@
+package detector
+
+import (
+	"image"
+	"math"
+)
+
+// clahe equalizes contrast locally with contrast limited adaptive histogram
+// equalization: the image is split into a grid of tiles x tiles, each tile's
+// histogram is equalized with counts clipped at clipLimit times the mean,
+// and each pixel blends the mappings of the four nearest tiles. Glare and
+// shadow, which shift the brightness of whole regions, are evened out
+// before thresholding, while clipping keeps flat regions from turning into
+// amplified noise.
+func clahe(gray *image.Gray, tiles int, clipLimit float64) *image.Gray {
+	bounds := gray.Bounds()
+	w, h := bounds.Dx(), bounds.Dy()
+	out := image.NewGray(bounds)
+	if w == 0 || h == 0 {
+		return out
+	}
+	tiles = max(1, min(tiles, w, h))
+	tileW, tileH := float64(w)/float64(tiles), float64(h)/float64(tiles)
+
+	// The mapping of each tile, from gray level to equalized level
+	luts := make([][256]uint8, tiles*tiles)
+	for ty := 0; ty < tiles; ty++ {
+		for tx := 0; tx < tiles; tx++ {
+			x0, x1 := int(float64(tx)*tileW), int(float64(tx+1)*tileW)
+			y0, y1 := int(float64(ty)*tileH), int(float64(ty+1)*tileH)
+			var hist [256]int
+			for y := y0; y < y1; y++ {
+				for x := x0; x < x1; x++ {
+					hist[gray.GrayAt(bounds.Min.X+x, bounds.Min.Y+y).Y]++
+				}
+			}
+			luts[ty*tiles+tx] = equalize(hist, (x1-x0)*(y1-y0), clipLimit)
+		}
+	}
+
+	for y := 0; y < h; y++ {
+		// The tiles above and below the pixel, by their centers, and how far
+		// it lies from the first toward the second
+		fy := (float64(y)+0.5)/tileH - 0.5
+		ty0 := clampTile(int(math.Floor(fy)), tiles)
+		ty1 := clampTile(ty0+1, tiles)
+		wy := math.Max(0, math.Min(1, fy-float64(ty0)))
+		for x := 0; x < w; x++ {
+			fx := (float64(x)+0.5)/tileW - 0.5
+			tx0 := clampTile(int(math.Floor(fx)), tiles)
+			tx1 := clampTile(tx0+1, tiles)
+			wx := math.Max(0, math.Min(1, fx-float64(tx0)))
+
+			v := gray.GrayAt(bounds.Min.X+x, bounds.Min.Y+y).Y
+			top := (1-wx)*float64(luts[ty0*tiles+tx0][v]) + wx*float64(luts[ty0*tiles+tx1][v])
+			bottom := (1-wx)*float64(luts[ty1*tiles+tx0][v]) + wx*float64(luts[ty1*tiles+tx1][v])
+			out.Pix[y*out.Stride+x] = uint8(math.Round((1-wy)*top + wy*bottom))
+		}
+	}
+	return out
+}
+
+// equalize returns the mapping that equalizes a histogram of n pixels after
+// clipping each count at clipLimit times the mean and spreading the excess
+// over all levels. A clipLimit of zero or less does not clip.
+func equalize(hist [256]int, n int, clipLimit float64) [256]uint8 {
+	var lut [256]uint8
+	if n == 0 {
+		return lut
+	}
+	if clipLimit > 0 {
+		limit := max(1, int(clipLimit*float64(n)/256))
+		excess := 0
+		for i, count := range hist {
+			if count > limit {
+				excess += count - limit
+				hist[i] = limit
+			}
+		}
+		for i := range hist {
+			hist[i] += excess / 256
+			if i < excess%256 {
+				hist[i]++
+			}
+		}
+	}
+
+	cumulative := 0
+	for i, count := range hist {
+		cumulative += count
+		lut[i] = uint8(math.Round(float64(cumulative) * 255 / float64(n)))
+	}
+	return lut
+}
+
+// clampTile keeps a tile index inside a grid of tiles
+func clampTile(i, tiles int) int {
+	return max(0, min(i, tiles-1))
+}
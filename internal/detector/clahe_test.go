@@ -0,0 +1,56 @@
+package detector
+
+import (
+	"image"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestEqualize(t *testing.T) {
+	// Half the pixels at each of two levels spread to the ends of the range
+	var hist [256]int
+	hist[100], hist[101] = 50, 50
+	lut := equalize(hist, 100, 0)
+	assert.Equal(t, uint8(128), lut[100])
+	assert.Equal(t, uint8(255), lut[101])
+	assert.Equal(t, uint8(0), lut[99])
+
+	// Clipping limits how far a single level is stretched
+	clipped := equalize(hist, 100, 2)
+	assert.Less(t, clipped[101]-clipped[99], lut[101]-lut[99])
+	assert.Equal(t, uint8(255), clipped[255])
+}
+
+func TestCLAHEEvensOutGlare(t *testing.T) {
+	// A dark stroke on a background that brightens from left to right, as
+	// under glare: the stroke on the right is lighter than the background
+	// on the left, so no single threshold separates them
+	img := image.NewGray(image.Rect(0, 0, 128, 64))
+	for y := 0; y < 64; y++ {
+		for x := 0; x < 128; x++ {
+			v := 60 + x*150/128
+			if x%32 == 16 {
+				v -= 50
+			}
+			img.Pix[y*img.Stride+x] = uint8(v)
+		}
+	}
+	require.Greater(t, img.GrayAt(112, 0).Y, img.GrayAt(4, 0).Y)
+
+	out := clahe(img, 4, 4)
+	for _, x := range []int{16, 48, 80, 112} {
+		assert.Less(t, out.GrayAt(x, 32).Y, out.GrayAt(x+4, 32).Y, "stroke at %d", x)
+		assert.Less(t, out.GrayAt(x, 32).Y, uint8(128), "stroke at %d", x)
+	}
+}
+
+func TestPreprocessingCLAHE(t *testing.T) {
+	steps, err := ParsePreprocessing("clahe{clip=3,tiles=4}, adaptive_threshold{block=31}")
+	require.NoError(t, err)
+	assert.Equal(t, "clahe{clip=3,tiles=4}", steps[0].String())
+
+	got := NewDetector(Config{Preprocessing: steps}).preprocessImage(whiteImage())
+	assert.Equal(t, whiteImage().Bounds(), got.Bounds())
+}
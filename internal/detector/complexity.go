@@ -0,0 +1,61 @@
+package detector
+
+import (
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+)
+
+// Complexity is what is measured of the contours of an image to keep
+// detection within the limits of a detector
+type Complexity struct {
+	// Contours is how many contours were found, the outer circle included
+	Contours int
+	// Points is the most points traced around one contour
+	Points int
+}
+
+// ComplexityError is the cause of a TooComplex error: the complexity of the
+// image and the limits it exceeds, for callers rejecting such images to
+// report them. A zero limit is no limit.
+type ComplexityError struct {
+	Measured Complexity
+	Limits   Complexity
+}
+
+func (e *ComplexityError) Error() string {
+	if e.Limits.Contours > 0 && e.Measured.Contours > e.Limits.Contours {
+		return i18n.Tf("detector.too_many_contours", e.Measured.Contours, e.Limits.Contours)
+	}
+	return i18n.Tf("detector.contour_too_long", e.Measured.Points, e.Limits.Points)
+}
+
+// exceeded reports whether the complexity exceeds any limit
+func (e *ComplexityError) exceeded() bool {
+	return (e.Limits.Contours > 0 && e.Measured.Contours > e.Limits.Contours) ||
+		(e.Limits.Points > 0 && e.Measured.Points > e.Limits.Points)
+}
+
+// measureComplexity returns the complexity of contours
+func measureComplexity(contours []Contour) Complexity {
+	c := Complexity{Contours: len(contours)}
+	for _, contour := range contours {
+		c.Points = max(c.Points, len(contour.Points))
+	}
+	return c
+}
+
+// checkComplexity returns a TooComplex error if contours exceed the limits
+// of the detector, so that an abusive image is rejected before the contours
+// are classified
+func (d *Detector) checkComplexity(contours []Contour) error {
+	cause := &ComplexityError{
+		Measured: measureComplexity(contours),
+		Limits:   Complexity{Contours: d.maxContours, Points: d.maxContourPoints},
+	}
+	if !cause.exceeded() {
+		return nil
+	}
+	return grimoireErrors.NewError(grimoireErrors.TooComplex, i18n.T("detector.too_complex")).
+		WithInnerError(cause).
+		WithSuggestion(i18n.T("detector.too_complex_suggestion"))
+}
@@ -0,0 +1,78 @@
+package detector
+
+import (
+	"context"
+	"image"
+	"testing"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// noisyPipeline returns stages that find count contours of points points
+// each, failing the test if any are classified
+func noisyPipeline(t *testing.T, d *Detector, count, points int) pipeline {
+	p := d.sequentialPipeline()
+	p.findContours = func(binary *image.Gray) []Contour {
+		contours := make([]Contour, count)
+		for i := range contours {
+			contours[i].Points = make([]image.Point, points)
+		}
+		return contours
+	}
+	p.detectSymbols = func(ctx context.Context, contours []Contour, binary *image.Gray) []*Symbol {
+		t.Error("contours past the limits were classified")
+		return nil
+	}
+	return p
+}
+
+func TestComplexityLimits(t *testing.T) {
+	tests := []struct {
+		name     string
+		config   Config
+		count    int
+		points   int
+		rejected bool
+	}{
+		{"no limits", Config{}, 500, 500, false},
+		{"too many contours", Config{MaxContours: 100}, 101, 10, true},
+		{"contours at the limit", Config{MaxContours: 100, MaxContourPoints: 10}, 100, 10, false},
+		{"contour too long", Config{MaxContourPoints: 1000}, 3, 1001, true},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			d := NewDetector(tt.config)
+			p := noisyPipeline(t, d, tt.count, tt.points)
+			if !tt.rejected {
+				p.detectSymbols = func(context.Context, []Contour, *image.Gray) []*Symbol { return nil }
+			}
+
+			_, _, err := d.run(context.Background(), p, whiteImage())
+			if !tt.rejected {
+				assert.NoError(t, err)
+				return
+			}
+			var gErr *grimoireErrors.GrimoireError
+			require.ErrorAs(t, err, &gErr)
+			assert.Equal(t, grimoireErrors.TooComplex, gErr.Type)
+			assert.NotEmpty(t, gErr.Suggestion)
+
+			var complexity *ComplexityError
+			require.ErrorAs(t, err, &complexity)
+			assert.Equal(t, Complexity{Contours: tt.count, Points: tt.points}, complexity.Measured)
+			assert.Equal(t, Complexity{Contours: tt.config.MaxContours, Points: tt.config.MaxContourPoints}, complexity.Limits)
+		})
+	}
+}
+
+func TestComplexityErrorMessage(t *testing.T) {
+	contours := &ComplexityError{Measured: Complexity{Contours: 20, Points: 5}, Limits: Complexity{Contours: 10}}
+	assert.Contains(t, contours.Error(), "20")
+	assert.Contains(t, contours.Error(), "10")
+
+	points := &ComplexityError{Measured: Complexity{Contours: 2, Points: 900}, Limits: Complexity{Contours: 10, Points: 500}}
+	assert.Contains(t, points.Error(), "900")
+	assert.Contains(t, points.Error(), "500")
+}
@@ -0,0 +1,262 @@
+package detector
+
+import (
+	"image"
+	"math"
+	"slices"
+	"sort"
+)
+
+// confidenceModel weighs the measurements behind the confidence of one
+// symbol type. The weights sum to one, so an ideal drawing of any type
+// scores 1 and a score means the same thing whatever the type.
+type confidenceModel struct {
+	circularity float64 // Weight of circularity close to the ideal shape's
+	regularity  float64 // Weight of the vertex count and equal side lengths
+	symmetry    float64 // Weight of the symmetry of the outline
+	vertices    int     // Vertices of the ideal polygon, 0 for curves
+	order       int     // Order of rotational symmetry, 0 for mirror symmetry
+	ideal       float64 // Circularity of the ideal shape
+}
+
+// starInnerRatio is the ratio of inner to outer radius of an ideal star
+const starInnerRatio = 0.4
+
+// symmetryTolerance is how far, in pixels, a rotated or reflected outline
+// point may land from the outline and still count as symmetric
+const symmetryTolerance = 2
+
+// Shapes scoring below ambiguousBelow get alternatives: the other shapes
+// that score no more than ambiguityMargin less
+const (
+	ambiguousBelow  = 0.9
+	ambiguityMargin = 0.1
+)
+
+// ambiguousShapes are the shapes a borderline outline can be mistaken
+// between, such as a circle and a hexagon
+var ambiguousShapes = []SymbolType{Circle, Triangle, Square, Pentagon, Hexagon, Star, EightPointedStar}
+
+// circleModel scores circles by circularity and rotational symmetry
+var circleModel = confidenceModel{circularity: 0.6, symmetry: 0.4, order: 8, ideal: 1}
+
+// mirrorModel scores operators and other line drawings, which have no ideal
+// outline to compare against but are drawn symmetric about an axis
+var mirrorModel = confidenceModel{symmetry: 1}
+
+// rectangleModel scores rectangles by their symmetry under a half turn. The
+// sides and circularity of a rectangle depend on its proportions, which
+// have no ideal.
+var rectangleModel = confidenceModel{symmetry: 1, order: 2}
+
+// confidenceModels holds the model of every type with an ideal shape
+var confidenceModels = map[SymbolType]confidenceModel{
+	OuterCircle:      circleModel,
+	Circle:           circleModel,
+	DoubleCircle:     circleModel,
+	Triangle:         polygonModel(3),
+	Square:           polygonModel(4),
+	Rectangle:        rectangleModel,
+	Pentagon:         polygonModel(5),
+	Hexagon:          polygonModel(6),
+	Star:             starModel(5),
+	SixPointedStar:   starModel(6),
+	EightPointedStar: starModel(8),
+}
+
+// polygonModel scores a regular polygon with the given number of sides
+func polygonModel(sides int) confidenceModel {
+	n := float64(sides)
+	return confidenceModel{
+		circularity: 0.3,
+		regularity:  0.4,
+		symmetry:    0.3,
+		vertices:    sides,
+		order:       sides,
+		ideal:       math.Pi / (n * math.Tan(math.Pi/n)),
+	}
+}
+
+// starModel scores a star with the given number of points. A star's
+// circularity depends on how deep its points are, so it weighs less.
+func starModel(points int) confidenceModel {
+	return confidenceModel{
+		circularity: 0.2,
+		regularity:  0.4,
+		symmetry:    0.4,
+		vertices:    2 * points,
+		order:       points,
+		ideal:       starCircularity(points, starInnerRatio),
+	}
+}
+
+// starCircularity returns the circularity of a star with the given number
+// of points and ratio of inner to outer radius
+func starCircularity(points int, innerRatio float64) float64 {
+	n := float64(points)
+	area := n * innerRatio * math.Sin(math.Pi/n)
+	edge := math.Sqrt(1 + innerRatio*innerRatio - 2*innerRatio*math.Cos(math.Pi/n))
+	perimeter := 2 * n * edge
+	return 4 * math.Pi * area / (perimeter * perimeter)
+}
+
+// confidence scores from 0 to 1 how closely contour matches the ideal
+// shape of symbolType
+func (d *Detector) confidence(symbolType SymbolType, contour Contour) float64 {
+	model, ok := confidenceModels[symbolType]
+	if !ok {
+		model = mirrorModel
+	}
+
+	score := 0.0
+	if model.circularity > 0 {
+		score += model.circularity * closeness(contour.Circularity, model.ideal)
+	}
+	if model.regularity > 0 {
+		score += model.regularity * d.vertexRegularity(contour, model.vertices)
+	}
+	if model.symmetry > 0 {
+		score += model.symmetry * outlineSymmetry(contour, model.order)
+	}
+	return clamp01(score)
+}
+
+// alternatives returns the other shapes contour scores nearly as well as, or
+// better than, symbolType, best first. A shape scoring ambiguousBelow or
+// more is not second-guessed.
+func (d *Detector) alternatives(symbolType SymbolType, contour Contour, confidence float64) []Alternative {
+	if confidence >= ambiguousBelow || !slices.Contains(ambiguousShapes, symbolType) {
+		return nil
+	}
+	var alternatives []Alternative
+	for _, t := range ambiguousShapes {
+		if t == symbolType {
+			continue
+		}
+		if score := d.confidence(t, contour); score >= confidence-ambiguityMargin {
+			alternatives = append(alternatives, Alternative{Type: t, Confidence: score})
+		}
+	}
+	sort.SliceStable(alternatives, func(i, j int) bool {
+		return alternatives[i].Confidence > alternatives[j].Confidence
+	})
+	return alternatives
+}
+
+// confident reports whether symbol meets the minimum confidence
+func (d *Detector) confident(symbol *Symbol) bool {
+	return symbol.Confidence >= d.minConfidence
+}
+
+// dropUnconfident removes the symbols below the minimum confidence
+func (d *Detector) dropUnconfident(symbols []*Symbol) []*Symbol {
+	if d.minConfidence <= 0 {
+		return symbols
+	}
+	kept := symbols[:0]
+	for _, symbol := range symbols {
+		if d.confident(symbol) {
+			kept = append(kept, symbol)
+		}
+	}
+	return kept
+}
+
+// closeness is 1 when value equals ideal and falls linearly to 0 at zero
+// or twice the ideal
+func closeness(value, ideal float64) float64 {
+	return clamp01(1 - math.Abs(value-ideal)/ideal)
+}
+
+// vertexRegularity scores the approximated polygon of contour by how close
+// its vertex count is to vertices and how equal its sides are
+func (d *Detector) vertexRegularity(contour Contour, vertices int) float64 {
+	approx := d.approximatePolygon(contour)
+	if len(approx) < 3 {
+		return 0
+	}
+
+	// A traced outline ends beside its first point, which can leave a vertex
+	// next to the first one; merge vertices closer than a short side
+	minSide := math.Max(contour.Perimeter*0.02, 1.5)
+	corners := []image.Point{approx[0]}
+	for _, pt := range approx[1:] {
+		if distance(pt, corners[len(corners)-1]) > minSide {
+			corners = append(corners, pt)
+		}
+	}
+	if len(corners) > 1 && distance(corners[len(corners)-1], corners[0]) <= minSide {
+		corners = corners[:len(corners)-1]
+	}
+
+	n := len(corners)
+	if n < 3 {
+		return 0
+	}
+	count := float64(min(n, vertices)) / float64(max(n, vertices))
+
+	sides := make([]float64, n)
+	mean := 0.0
+	for i := range corners {
+		sides[i] = distance(corners[i], corners[(i+1)%n])
+		mean += sides[i]
+	}
+	mean /= float64(n)
+	if mean == 0 {
+		return 0
+	}
+
+	variance := 0.0
+	for _, side := range sides {
+		variance += (side - mean) * (side - mean)
+	}
+	stdDev := math.Sqrt(variance / float64(n))
+	return count * clamp01(1-stdDev/mean)
+}
+
+// outlineSymmetry returns the fraction of outline points that land on the
+// outline when rotated by 1/order of a turn about the center, or for order
+// 0 when reflected across the better of the vertical and horizontal axes
+func outlineSymmetry(contour Contour, order int) float64 {
+	if len(contour.Points) == 0 {
+		return 0
+	}
+
+	// Mark the pixels within the tolerance of the outline
+	bounds := contour.getBoundingBox().Inset(-symmetryTolerance)
+	width, height := bounds.Dx(), bounds.Dy()
+	near := make([]bool, width*height)
+	for _, pt := range contour.Points {
+		for dy := -symmetryTolerance; dy <= symmetryTolerance; dy++ {
+			for dx := -symmetryTolerance; dx <= symmetryTolerance; dx++ {
+				near[(pt.Y+dy-bounds.Min.Y)*width+pt.X+dx-bounds.Min.X] = true
+			}
+		}
+	}
+
+	cx, cy := float64(contour.Center.X), float64(contour.Center.Y)
+	matching := func(transform func(x, y float64) (float64, float64)) float64 {
+		hits := 0
+		for _, pt := range contour.Points {
+			x, y := transform(float64(pt.X)-cx, float64(pt.Y)-cy)
+			px := int(math.Round(x+cx)) - bounds.Min.X
+			py := int(math.Round(y+cy)) - bounds.Min.Y
+			if px >= 0 && px < width && py >= 0 && py < height && near[py*width+px] {
+				hits++
+			}
+		}
+		return float64(hits) / float64(len(contour.Points))
+	}
+
+	if order == 0 {
+		vertical := matching(func(x, y float64) (float64, float64) { return -x, y })
+		horizontal := matching(func(x, y float64) (float64, float64) { return x, -y })
+		return math.Max(vertical, horizontal)
+	}
+	sin, cos := math.Sincos(2 * math.Pi / float64(order))
+	return matching(func(x, y float64) (float64, float64) { return x*cos - y*sin, x*sin + y*cos })
+}
+
+func clamp01(x float64) float64 {
+	return math.Max(0, math.Min(1, x))
+}
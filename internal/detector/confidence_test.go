@@ -0,0 +1,142 @@
+package detector
+
+import (
+	"image"
+	"math"
+	"math/rand"
+	"testing"
+	"testing/quick"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// outlineContour returns the contour of an outline with its properties
+func outlineContour(points []image.Point) Contour {
+	contour := Contour{Points: points}
+	contour.calculateProperties()
+	return contour
+}
+
+// stretched scales points horizontally about cx
+func stretched(points []image.Point, cx int, factor float64) []image.Point {
+	result := make([]image.Point, len(points))
+	for i, pt := range points {
+		result[i] = image.Point{X: cx + int(math.Round(float64(pt.X-cx)*factor)), Y: pt.Y}
+	}
+	return result
+}
+
+func TestConfidenceModelWeightsSumToOne(t *testing.T) {
+	models := map[string]confidenceModel{"mirror": mirrorModel}
+	for symbolType, model := range confidenceModels {
+		models[string(symbolType)] = model
+	}
+
+	for name, model := range models {
+		assert.InDelta(t, 1, model.circularity+model.regularity+model.symmetry, 1e-9, name)
+	}
+}
+
+func TestIdealCircularity(t *testing.T) {
+	assert.InDelta(t, math.Pi/4, polygonModel(4).ideal, 1e-9)
+	assert.InDelta(t, 0.288, starCircularity(5, starInnerRatio), 0.001)
+	assert.Less(t, polygonModel(3).ideal, polygonModel(6).ideal)
+}
+
+func TestConfidenceIdealShapes(t *testing.T) {
+	d := NewDetector(Config{})
+	tests := []struct {
+		name       string
+		symbolType SymbolType
+		shape      shape
+	}{
+		{"circle", Circle, shape{kind: shapeCircle, cx: 300, cy: 300, radius: 100}},
+		{"square", Square, shape{kind: shapePolygon, sides: 4, cx: 300, cy: 300, radius: 100, angle: math.Pi / 4}},
+		{"hexagon", Hexagon, shape{kind: shapePolygon, sides: 6, cx: 300, cy: 300, radius: 100}},
+		{"star", Star, shape{kind: shapeStar, sides: 5, cx: 300, cy: 300, radius: 100, inner: 40, angle: -math.Pi / 2}},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			ideal := outlineContour(tt.shape.outline())
+			distorted := outlineContour(stretched(tt.shape.outline(), 300, 1.6))
+
+			idealScore := d.confidence(tt.symbolType, ideal)
+			assert.Greater(t, idealScore, 0.75, "an ideal %s should score high", tt.name)
+			assert.Greater(t, idealScore, d.confidence(tt.symbolType, distorted),
+				"a stretched %s should score lower", tt.name)
+		})
+	}
+}
+
+func TestConfidenceDependsOnType(t *testing.T) {
+	d := NewDetector(Config{})
+	circle := outlineContour(shape{kind: shapeCircle, cx: 300, cy: 300, radius: 100}.outline())
+	triangle := outlineContour(shape{kind: shapePolygon, sides: 3, cx: 300, cy: 300, radius: 100}.outline())
+
+	assert.Greater(t, d.confidence(Circle, circle), d.confidence(Triangle, circle))
+	assert.Greater(t, d.confidence(Triangle, triangle), d.confidence(Circle, triangle))
+}
+
+func TestConfidenceMirrorSymmetry(t *testing.T) {
+	// An arrow pointing right is symmetric about the horizontal axis only
+	arrow := outlineContour([]image.Point{
+		{100, 100}, {150, 100}, {150, 80}, {190, 120}, {150, 160}, {150, 140}, {100, 140},
+	})
+	lopsided := outlineContour([]image.Point{
+		{100, 100}, {180, 100}, {190, 110}, {120, 160}, {100, 150},
+	})
+
+	assert.InDelta(t, 1, outlineSymmetry(arrow, 0), 0.01)
+	assert.Greater(t, outlineSymmetry(arrow, 0), outlineSymmetry(lopsided, 0))
+}
+
+func TestPropertyConfidenceInRange(t *testing.T) {
+	d := NewDetector(Config{})
+	property := func(s shape) bool {
+		contour := outlineContour(s.outline())
+		for _, symbolType := range SymbolTypes() {
+			score := d.confidence(symbolType, contour)
+			if score < 0 || score > 1 || math.IsNaN(score) {
+				return false
+			}
+		}
+		return true
+	}
+	require.NoError(t, quick.Check(property, &quick.Config{MaxCount: 20, Rand: rand.New(rand.NewSource(1))}))
+}
+
+func TestDropUnconfident(t *testing.T) {
+	symbols := []*Symbol{
+		{Type: OuterCircle, Confidence: 0.95},
+		{Type: Star, Confidence: 0.4},
+		{Type: Square, Confidence: 0.6},
+	}
+
+	kept := NewDetector(Config{}).dropUnconfident(append([]*Symbol(nil), symbols...))
+	assert.Equal(t, symbols, kept, "no minimum keeps every symbol")
+
+	kept = NewDetector(Config{MinConfidence: 0.6}).dropUnconfident(append([]*Symbol(nil), symbols...))
+	assert.Equal(t, []*Symbol{symbols[0], symbols[2]}, kept)
+}
+
+func TestAlternatives(t *testing.T) {
+	d := NewDetector(Config{})
+	// An octagon is borderline between a circle and the polygons
+	octagon := outlineContour(shape{kind: shapePolygon, sides: 8, cx: 300, cy: 300, radius: 100}.outline())
+
+	// Every other shape scores at least a confidence of zero
+	alternatives := d.alternatives(Circle, octagon, 0)
+	require.Len(t, alternatives, len(ambiguousShapes)-1)
+	for i, alt := range alternatives {
+		assert.NotEqual(t, Circle, alt.Type)
+		assert.Equal(t, d.confidence(alt.Type, octagon), alt.Confidence)
+		if i > 0 {
+			assert.GreaterOrEqual(t, alternatives[i-1].Confidence, alt.Confidence, "best first")
+		}
+	}
+
+	assert.Nil(t, d.alternatives(Circle, octagon, ambiguousBelow), "confident shapes are not second-guessed")
+	assert.Nil(t, d.alternatives(Divergence, octagon, 0), "operators have no alternative shapes")
+}
@@ -1,6 +1,7 @@
 package detector
 
 import (
+	"context"
 	"fmt"
 	"image"
 	"math"
@@ -9,6 +10,13 @@ import (
 
 // detectConnections detects connections between symbols
 func (d *Detector) detectConnections(binary *image.Gray, symbols []*Symbol) []Connection {
+	return d.detectConnectionsContext(context.Background(), binary, symbols)
+}
+
+// detectConnectionsContext detects connections like detectConnections, but
+// stops checking lines once ctx is done, returning the connections found
+// so far
+func (d *Detector) detectConnectionsContext(ctx context.Context, binary *image.Gray, symbols []*Symbol) []Connection {
 	connections := []Connection{}
 
 	// Find lines using improved edge detection
@@ -23,8 +31,19 @@ func (d *Detector) detectConnections(binary *image.Gray, symbols []*Symbol) []Co
 		fmt.Printf("Detected %d lines\n", len(lines))
 	}
 
+	// Trace curves first, so straight pieces of a curve are not attached to
+	// whatever symbols happen to lie near their ends
+	curves := d.traceCurves(binary, symbols)
+
 	// For each line, check if it connects symbols
 	for _, line := range lines {
+		if ctx.Err() != nil {
+			break
+		}
+		if onCurve(curves, line) {
+			continue
+		}
+
 		// Find symbols near line endpoints
 		fromSymbol := d.findNearestSymbol(line.Start, symbols)
 		toSymbol := d.findNearestSymbol(line.End, symbols)
@@ -44,11 +63,20 @@ func (d *Detector) detectConnections(binary *image.Gray, symbols []*Symbol) []Co
 				// Determine direction
 				from, to := d.determineConnectionDirection(fromSymbol, toSymbol)
 
+				// Anchor each end where the line meets the symbol
+				fromAnchor := lineAnchor(fromSymbol, line.Start, line.End)
+				toAnchor := lineAnchor(toSymbol, line.End, line.Start)
+				if from != fromSymbol {
+					fromAnchor, toAnchor = toAnchor, fromAnchor
+				}
+
 				conn := Connection{
 					From:           from,
 					To:             to,
 					ConnectionType: connType,
 					Properties:     make(map[string]interface{}),
+					FromAnchor:     fromAnchor,
+					ToAnchor:       toAnchor,
 				}
 
 				connections = append(connections, conn)
@@ -62,6 +90,19 @@ func (d *Detector) detectConnections(binary *image.Gray, symbols []*Symbol) []Co
 		}
 	}
 
+	// Add the curves, unless a line already connects the same symbols
+	for _, c := range curves {
+		if d.alreadyConnected(connections, c.from, c.to) {
+			continue
+		}
+		conn := d.curveConnection(c)
+		connections = append(connections, conn)
+
+		if os.Getenv("GRIMOIRE_DEBUG") != "" {
+			fmt.Printf("Curved connection added: %s -> %s (%d pixels)\n", conn.From.Type, conn.To.Type, len(c.path))
+		}
+	}
+
 	return connections
 }
 
@@ -391,6 +432,11 @@ const (
 	ConnectionTypeDotted = "dotted"
 )
 
+// ConnectionTypes returns every connection style the detector can recognize
+func ConnectionTypes() []string {
+	return []string{ConnectionTypeSolid, ConnectionTypeDashed, ConnectionTypeDotted}
+}
+
 // determineConnectionType determines the type of connection
 func (d *Detector) determineConnectionType(line Line, binary *image.Gray) string {
 	// Sample points along the line
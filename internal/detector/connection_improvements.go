@@ -1,6 +1,7 @@
 package detector
 
 import (
+	"context"
 	"image"
 	"image/color"
 	"math"
@@ -167,16 +168,21 @@ func (d *Detector) linesAreSimilar(l1, l2 Line) bool {
 	return false
 }
 
-// improvedDetectConnections enhances connection detection with better diagonal support
-func (d *Detector) improvedDetectConnections(binary *image.Gray, symbols []*Symbol) []Connection {
+// improvedDetectConnections enhances connection detection with better
+// diagonal support. It stops once ctx is done, returning the connections
+// found so far.
+func (d *Detector) improvedDetectConnections(ctx context.Context, binary *image.Gray, symbols []*Symbol) []Connection {
 	connections := []Connection{}
 
 	// First, use the standard connection detection
-	connections = append(connections, d.detectConnections(binary, symbols)...)
+	connections = append(connections, d.detectConnectionsContext(ctx, binary, symbols)...)
 
 	// Additionally, check for direct symbol-to-symbol diagonal connections
 	// This helps when the line detection misses some diagonal connections
 	for i, sym1 := range symbols {
+		if ctx.Err() != nil {
+			break
+		}
 		for j := i + 1; j < len(symbols); j++ {
 			sym2 := symbols[j]
 
@@ -195,6 +201,8 @@ func (d *Detector) improvedDetectConnections(binary *image.Gray, symbols []*Symb
 					To:             to,
 					ConnectionType: "solid",
 					Properties:     make(map[string]interface{}),
+					FromAnchor:     centerAnchor(from, to),
+					ToAnchor:       centerAnchor(to, from),
 				}
 
 				connections = append(connections, conn)
@@ -1,6 +1,7 @@
 package detector
 
 import (
+	"context"
 	"image"
 	"math"
 	"sort"
@@ -14,25 +15,34 @@ type ContourPoint struct {
 
 // findContours finds all contours in a binary image using improved algorithm
 func (d *Detector) findContours(binary *image.Gray) []Contour {
+	return d.findContoursContext(context.Background(), binary)
+}
+
+// findContoursContext finds contours like findContours, but stops scanning
+// once ctx is done, returning the contours found so far
+func (d *Detector) findContoursContext(ctx context.Context, binary *image.Gray) []Contour {
 	bounds := binary.Bounds()
-	visited := make(map[image.Point]bool)
+	buffers := d.contourScratch.get(bounds)
+	defer d.contourScratch.put(buffers)
 	var contours []Contour
 
 	// Scan for all contours
-	for y := bounds.Min.Y; y < bounds.Max.Y; y++ {
+	for y := bounds.Min.Y; y < bounds.Max.Y && ctx.Err() == nil; y++ {
 		for x := bounds.Min.X; x < bounds.Max.X; x++ {
 			pt := image.Point{X: x, Y: y}
 
 			// Skip if already visited or not a foreground pixel
-			if visited[pt] || binary.GrayAt(x, y).Y != 255 {
+			if buffers.visited(pt) || binary.GrayAt(x, y).Y != 255 {
 				continue
 			}
 
 			// Found a new contour, trace it
-			contour := d.traceContour(binary, pt, visited)
+			contour := d.traceContour(binary, pt, buffers)
 			if len(contour.Points) >= 10 { // Minimum points for a valid contour
 				contour.calculateProperties()
 				if contour.Area >= float64(d.minContourArea) {
+					// Only kept contours get their own copy of the traced points
+					contour.Points = append([]image.Point(nil), contour.Points...)
 					contours = append(contours, contour)
 				}
 			}
@@ -53,10 +63,12 @@ func (d *Detector) findContours(binary *image.Gray) []Contour {
 	return mergedContours
 }
 
-// traceContour traces a contour using Moore neighborhood tracing
-func (d *Detector) traceContour(binary *image.Gray, start image.Point, visited map[image.Point]bool) Contour {
+// traceContour traces a contour using Moore neighborhood tracing. The
+// points of the returned contour are in buffers and are overwritten by the
+// next trace.
+func (d *Detector) traceContour(binary *image.Gray, start image.Point, buffers *contourBuffers) Contour {
 	bounds := binary.Bounds()
-	points := []image.Point{}
+	points := buffers.points[:0]
 
 	// Direction vectors for 8-connectivity (clockwise from right)
 	dirs := []image.Point{
@@ -66,7 +78,7 @@ func (d *Detector) traceContour(binary *image.Gray, start image.Point, visited m
 
 	current := start
 	points = append(points, current)
-	visited[current] = true
+	buffers.visit(current)
 
 	// Find initial direction
 	var dir int
@@ -90,9 +102,9 @@ func (d *Detector) traceContour(binary *image.Gray, start image.Point, visited m
 			checkDir := (dir + i) % 8
 			next := image.Point{X: current.X + dirs[checkDir].X, Y: current.Y + dirs[checkDir].Y}
 
-			if d.isValidContourPoint(binary, next, bounds) && !visited[next] {
+			if d.isValidContourPoint(binary, next, bounds) && !buffers.visited(next) {
 				points = append(points, next)
-				visited[next] = true
+				buffers.visit(next)
 				current = next
 				dir = checkDir
 				found = true
@@ -104,9 +116,9 @@ func (d *Detector) traceContour(binary *image.Gray, start image.Point, visited m
 			// Try to find any connected pixel
 			for i := 0; i < 8; i++ {
 				next := image.Point{X: current.X + dirs[i].X, Y: current.Y + dirs[i].Y}
-				if d.isValidContourPoint(binary, next, bounds) && !visited[next] {
+				if d.isValidContourPoint(binary, next, bounds) && !buffers.visited(next) {
 					points = append(points, next)
-					visited[next] = true
+					buffers.visit(next)
 					current = next
 					dir = i
 					found = true
@@ -128,8 +140,9 @@ func (d *Detector) traceContour(binary *image.Gray, start image.Point, visited m
 	}
 
 	// Fill any remaining connected pixels
-	d.fillContourRegion(binary, points, visited, bounds)
+	d.fillContourRegion(binary, points, buffers, bounds)
 
+	buffers.points = points
 	return Contour{Points: points}
 }
 
@@ -145,7 +158,7 @@ func (d *Detector) isValidContourPoint(binary *image.Gray, pt image.Point, bound
 // fillContourRegion fills any remaining pixels in the contour region
 func (d *Detector) fillContourRegion(
 	binary *image.Gray, contourPoints []image.Point,
-	visited map[image.Point]bool, bounds image.Rectangle) {
+	buffers *contourBuffers, bounds image.Rectangle) {
 	// Create a bounding box for the contour
 	if len(contourPoints) == 0 {
 		return
@@ -173,8 +186,8 @@ func (d *Detector) fillContourRegion(
 	for y := minY; y <= maxY; y++ {
 		for x := minX; x <= maxX; x++ {
 			pt := image.Point{X: x, Y: y}
-			if !visited[pt] && d.isValidContourPoint(binary, pt, bounds) {
-				visited[pt] = true
+			if !buffers.visited(pt) && d.isValidContourPoint(binary, pt, bounds) {
+				buffers.visit(pt)
 			}
 		}
 	}
@@ -0,0 +1,373 @@
+package detector
+
+import (
+	"image"
+	"math"
+)
+
+// Line detection only finds connections drawn as straight strokes. A
+// connection bent around another symbol is lost, or split into straight
+// pieces whose ends attach to whatever symbols lie nearby. Curves are
+// traced instead along the skeleton of the binary image: its strokes
+// thinned to one pixel, with the symbols cut out so that each remaining
+// path runs from one symbol to another.
+
+const (
+	// minCurveLength is the number of skeleton pixels a path needs to be
+	// taken as a connection
+	minCurveLength = 20
+	// curveSymbolMargin widens the outline circle of a symbol when cutting it
+	// out of the skeleton, since the corners of a shape reach past it
+	curveSymbolMargin = 1.3
+	// curveEndSlack is how far past the cut around a symbol a path may end
+	// and still reach the symbol, in pixels
+	curveEndSlack = 4.0
+	// curveMinBend is how far a path must stray from the chord between its
+	// ends, relative to the chord, to count as curved. Straighter paths are
+	// left to line detection.
+	curveMinBend = 0.1
+	// curveLineTolerance is how far a line may lie from a curve and still be
+	// taken as a piece of it, in pixels. Lines are found on the edges of a
+	// stroke, so they run beside its skeleton.
+	curveLineTolerance = 5
+	// curveTangentSpan is how many pixels back along a path its direction is
+	// measured over
+	curveTangentSpan = 8
+)
+
+// curve is a curved path of the skeleton running between two symbols
+type curve struct {
+	path     []image.Point
+	from, to *Symbol
+	bounds   image.Rectangle
+}
+
+// traceCurves returns the curved strokes of binary that run between two
+// symbols
+func (d *Detector) traceCurves(binary *image.Gray, symbols []*Symbol) []curve {
+	s := thin(binary)
+	s.cut(symbols)
+
+	// Trace only from the symbols, so that a spur left by thinning does not
+	// start a trace that runs off along the stroke it sticks out of
+	atSymbol := func(p image.Point) bool {
+		return curveEndSymbol(p, symbols) != nil
+	}
+
+	var curves []curve
+	for _, path := range s.paths(atSymbol) {
+		if len(path) < minCurveLength || !bent(path) {
+			continue
+		}
+		from := curveEndSymbol(path[0], symbols)
+		to := curveEndSymbol(path[len(path)-1], symbols)
+		if to == nil || from == to {
+			continue
+		}
+		bounds := image.Rectangle{Min: path[0], Max: path[0]}
+		for _, p := range path {
+			bounds = bounds.Union(image.Rectangle{Min: p, Max: p.Add(image.Point{X: 1, Y: 1})})
+		}
+		curves = append(curves, curve{path: path, from: from, to: to, bounds: bounds})
+	}
+	return curves
+}
+
+// curveConnection returns the connection the curve draws, anchored where
+// each end of the curve arrives at its symbol
+func (d *Detector) curveConnection(c curve) Connection {
+	from, to := d.determineConnectionDirection(c.from, c.to)
+
+	first, last := c.path[0], c.path[len(c.path)-1]
+	fromAnchor := lineAnchor(c.from, first, c.path[min(curveTangentSpan, len(c.path)-1)])
+	toAnchor := lineAnchor(c.to, last, c.path[max(len(c.path)-1-curveTangentSpan, 0)])
+	if from != c.from {
+		fromAnchor, toAnchor = toAnchor, fromAnchor
+	}
+
+	return Connection{
+		From:           from,
+		To:             to,
+		ConnectionType: ConnectionTypeSolid,
+		Properties:     make(map[string]interface{}),
+		FromAnchor:     fromAnchor,
+		ToAnchor:       toAnchor,
+	}
+}
+
+// covers reports whether line is a straight piece of the curve, running
+// along it or into the symbols at its ends
+func (c curve) covers(line Line) bool {
+	mid := image.Point{X: (line.Start.X + line.End.X) / 2, Y: (line.Start.Y + line.End.Y) / 2}
+	return c.near(line.Start) && c.near(mid) && c.near(line.End)
+}
+
+// near reports whether p lies on the curve or within the cut around either
+// of its symbols
+func (c curve) near(p image.Point) bool {
+	for _, symbol := range []*Symbol{c.from, c.to} {
+		gap := math.Hypot(float64(p.X)-symbol.Position.X, float64(p.Y)-symbol.Position.Y) - cutRadius(symbol)
+		if gap <= curveLineTolerance {
+			return true
+		}
+	}
+
+	if !p.In(c.bounds.Inset(-curveLineTolerance)) {
+		return false
+	}
+	for _, q := range c.path {
+		if math.Hypot(float64(p.X-q.X), float64(p.Y-q.Y)) <= curveLineTolerance {
+			return true
+		}
+	}
+	return false
+}
+
+// onCurve reports whether line is a piece of any of curves
+func onCurve(curves []curve, line Line) bool {
+	for _, c := range curves {
+		if c.covers(line) {
+			return true
+		}
+	}
+	return false
+}
+
+// bent reports whether path strays from the chord between its ends by more
+// than curveMinBend of the chord
+func bent(path []image.Point) bool {
+	a, b := path[0], path[len(path)-1]
+	dx, dy := float64(b.X-a.X), float64(b.Y-a.Y)
+	chord := math.Hypot(dx, dy)
+	if chord == 0 {
+		return true
+	}
+
+	deviation := 0.0
+	for _, p := range path {
+		deviation = math.Max(deviation, math.Abs(dx*float64(p.Y-a.Y)-dy*float64(p.X-a.X))/chord)
+	}
+	return deviation > curveMinBend*chord
+}
+
+// cutRadius is the radius of the circle cut out of the skeleton around
+// symbol, covering its outline and the width of its strokes
+func cutRadius(symbol *Symbol) float64 {
+	return symbol.Radius()*curveSymbolMargin + 2
+}
+
+// curveEndSymbol returns the symbol whose cut end lies on, or nil when it
+// ends away from every symbol
+func curveEndSymbol(end image.Point, symbols []*Symbol) *Symbol {
+	var nearest *Symbol
+	best := math.Inf(1)
+	for _, symbol := range symbols {
+		if symbol.Type == OuterCircle {
+			continue
+		}
+		gap := math.Hypot(float64(end.X)-symbol.Position.X, float64(end.Y)-symbol.Position.Y) - cutRadius(symbol)
+		if gap <= curveEndSlack && gap < best {
+			best = gap
+			nearest = symbol
+		}
+	}
+	return nearest
+}
+
+// skeleton is a one pixel wide thinning of the strokes of a binary image
+type skeleton struct {
+	bounds image.Rectangle
+	on     []bool
+}
+
+// ring lists the offsets of the eight neighbors of a pixel clockwise from
+// the one above, the order thinning walks them in
+var ring = [8]image.Point{
+	{X: 0, Y: -1}, {X: 1, Y: -1}, {X: 1, Y: 0}, {X: 1, Y: 1},
+	{X: 0, Y: 1}, {X: -1, Y: 1}, {X: -1, Y: 0}, {X: -1, Y: -1},
+}
+
+// thin reduces the foreground strokes of binary to one pixel wide lines
+// with the Zhang-Suen algorithm, which peels border pixels off in two
+// alternating passes until none can go without breaking a stroke
+func thin(binary *image.Gray) *skeleton {
+	bounds := binary.Bounds()
+	s := &skeleton{bounds: bounds, on: make([]bool, bounds.Dx()*bounds.Dy())}
+
+	var pixels []image.Point
+	for y := bounds.Min.Y; y < bounds.Max.Y; y++ {
+		for x := bounds.Min.X; x < bounds.Max.X; x++ {
+			if binary.GrayAt(x, y).Y > 128 {
+				s.on[s.index(image.Point{X: x, Y: y})] = true
+				pixels = append(pixels, image.Point{X: x, Y: y})
+			}
+		}
+	}
+
+	var removed []image.Point
+	for changed := true; changed; {
+		changed = false
+		for pass := 0; pass < 2; pass++ {
+			removed = removed[:0]
+			kept := pixels[:0]
+			for _, p := range pixels {
+				if s.removable(p, pass) {
+					removed = append(removed, p)
+				} else {
+					kept = append(kept, p)
+				}
+			}
+			for _, p := range removed {
+				s.on[s.index(p)] = false
+			}
+			pixels = kept
+			changed = changed || len(removed) > 0
+		}
+	}
+	return s
+}
+
+// removable reports whether thinning pass may remove the pixel at p: it
+// lies on the border of a stroke, is not the end of one, and removing it
+// leaves its neighbors connected
+func (s *skeleton) removable(p image.Point, pass int) bool {
+	var n [8]bool
+	count := 0
+	for i, o := range ring {
+		n[i] = s.at(p.Add(o))
+		if n[i] {
+			count++
+		}
+	}
+	if count < 2 || count > 6 {
+		return false
+	}
+
+	transitions := 0
+	for i := range n {
+		if !n[i] && n[(i+1)%len(n)] {
+			transitions++
+		}
+	}
+	if transitions != 1 {
+		return false
+	}
+
+	up, right, down, left := n[0], n[2], n[4], n[6]
+	if pass == 0 {
+		return !(up && right && down) && !(right && down && left)
+	}
+	return !(up && right && left) && !(up && down && left)
+}
+
+// cut clears the skeleton inside the outline of each symbol and along the
+// outer circle, so that the strokes of symbols drop out and connections end
+// where they meet them
+func (s *skeleton) cut(symbols []*Symbol) {
+	for _, symbol := range symbols {
+		cx, cy := symbol.Position.X, symbol.Position.Y
+		inner, outer := 0.0, cutRadius(symbol)
+		if symbol.Type == OuterCircle {
+			// Only the ring itself, which may pass close to the symbols
+			radius := symbol.Radius()
+			inner, outer = radius-ringBand(symbol), radius+ringBand(symbol)
+		}
+
+		for y := int(cy - outer); y <= int(cy+outer); y++ {
+			for x := int(cx - outer); x <= int(cx+outer); x++ {
+				p := image.Point{X: x, Y: y}
+				if dist := math.Hypot(float64(x)-cx, float64(y)-cy); dist >= inner && dist <= outer && p.In(s.bounds) {
+					s.on[s.index(p)] = false
+				}
+			}
+		}
+	}
+}
+
+// paths traces the skeleton from each end point accepted by start, in scan
+// order, to the other end of its stroke. Closed loops have no end point and
+// are skipped.
+func (s *skeleton) paths(start func(p image.Point) bool) [][]image.Point {
+	visited := make([]bool, len(s.on))
+	var paths [][]image.Point
+	for y := s.bounds.Min.Y; y < s.bounds.Max.Y; y++ {
+		for x := s.bounds.Min.X; x < s.bounds.Max.X; x++ {
+			p := image.Point{X: x, Y: y}
+			if s.on[s.index(p)] && !visited[s.index(p)] && s.end(p) && start(p) {
+				paths = append(paths, s.trace(p, visited))
+			}
+		}
+	}
+	return paths
+}
+
+// trace follows the skeleton from start while unvisited pixels continue it.
+// Where strokes cross it keeps to the branch that bends least, so a curve
+// passing over another line stays on its own stroke.
+func (s *skeleton) trace(start image.Point, visited []bool) []image.Point {
+	path := []image.Point{start}
+	visited[s.index(start)] = true
+
+	for current := start; ; {
+		back := path[max(len(path)-1-curveTangentSpan, 0)]
+		dx, dy := float64(current.X-back.X), float64(current.Y-back.Y)
+		heading := math.Hypot(dx, dy)
+
+		var candidates []image.Point
+		best, bestScore := -1, math.Inf(-1)
+		for _, o := range ring {
+			next := current.Add(o)
+			if !s.at(next) || visited[s.index(next)] {
+				continue
+			}
+			// Prefer going straight on, then edge neighbors over corners
+			length := math.Hypot(float64(o.X), float64(o.Y))
+			score := -length / 100
+			if heading > 0 {
+				score += (dx*float64(o.X) + dy*float64(o.Y)) / (heading * length)
+			}
+			candidates = append(candidates, next)
+			if score > bestScore {
+				best, bestScore = len(candidates)-1, score
+			}
+		}
+		if best < 0 || bestScore < -0.3 {
+			return path
+		}
+
+		// Pixels beside the step are corners of the same stroke
+		next := candidates[best]
+		for _, c := range candidates {
+			if step := c.Sub(next); step.X >= -1 && step.X <= 1 && step.Y >= -1 && step.Y <= 1 {
+				visited[s.index(c)] = true
+			}
+		}
+		path = append(path, next)
+		current = next
+	}
+}
+
+// end reports whether p is the end of a stroke: its neighbors on the
+// skeleton are one pixel, or two that touch each other at a step
+func (s *skeleton) end(p image.Point) bool {
+	count, runs := 0, 0
+	for i, o := range ring {
+		if s.at(p.Add(o)) {
+			count++
+			if !s.at(p.Add(ring[(i+len(ring)-1)%len(ring)])) {
+				runs++
+			}
+		}
+	}
+	return (count == 1 || count == 2) && runs == 1
+}
+
+// at reports whether p is on the skeleton
+func (s *skeleton) at(p image.Point) bool {
+	return p.In(s.bounds) && s.on[s.index(p)]
+}
+
+// index returns the position of p in the pixels of the skeleton
+func (s *skeleton) index(p image.Point) int {
+	return (p.Y-s.bounds.Min.Y)*s.bounds.Dx() + p.X - s.bounds.Min.X
+}
@@ -0,0 +1,130 @@
+package detector
+
+import (
+	"image"
+	"image/color"
+	"math"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// strokeArc draws the arc of the circle around center from one angle to
+// another as a binary stroke of the given width
+func strokeArc(img *image.Gray, center image.Point, radius, from, to, width float64) {
+	bounds := img.Bounds()
+	for y := bounds.Min.Y; y < bounds.Max.Y; y++ {
+		for x := bounds.Min.X; x < bounds.Max.X; x++ {
+			dx, dy := float64(x-center.X), float64(y-center.Y)
+			angle := math.Atan2(dy, dx)
+			if angle < from {
+				angle += 2 * math.Pi
+			}
+			if math.Abs(math.Hypot(dx, dy)-radius) <= width/2 && angle <= to {
+				img.SetGray(x, y, color.Gray{Y: 255})
+			}
+		}
+	}
+}
+
+func TestThin(t *testing.T) {
+	binary := image.NewGray(image.Rect(0, 0, 100, 100))
+	for y := 47; y <= 53; y++ {
+		for x := 10; x < 90; x++ {
+			binary.SetGray(x, y, color.Gray{Y: 255})
+		}
+	}
+
+	s := thin(binary)
+	for x := 20; x < 80; x++ {
+		count := 0
+		for y := 0; y < 100; y++ {
+			if s.at(image.Point{X: x, Y: y}) {
+				count++
+			}
+		}
+		assert.Equal(t, 1, count, "column %d", x)
+	}
+}
+
+func TestBent(t *testing.T) {
+	var straight, arc []image.Point
+	for i := 0; i <= 40; i++ {
+		straight = append(straight, image.Point{X: i, Y: i / 10})
+		angle := math.Pi * float64(i) / 40
+		arc = append(arc, image.Point{X: int(20 - 20*math.Cos(angle)), Y: int(-20 * math.Sin(angle))})
+	}
+
+	assert.False(t, bent(straight))
+	assert.True(t, bent(arc))
+}
+
+// arcAround returns a binary drawing of a square and a star joined by an arc
+// passing above a circle between them
+func arcAround() (*image.Gray, []*Symbol) {
+	binary := image.NewGray(image.Rect(0, 0, 400, 300))
+	strokeArc(binary, image.Point{X: 200, Y: 200}, 100, -math.Pi, 0, 5)
+
+	symbols := []*Symbol{
+		{Type: Square, Position: Position{X: 100, Y: 200}, Size: 30},
+		{Type: Circle, Position: Position{X: 200, Y: 200}, Size: 30},
+		{Type: Star, Position: Position{X: 300, Y: 200}, Size: 30},
+	}
+	return binary, symbols
+}
+
+func TestTraceCurves(t *testing.T) {
+	binary, symbols := arcAround()
+	d := NewDetector(Config{})
+
+	curves := d.traceCurves(binary, symbols)
+	require.Len(t, curves, 1)
+	assert.ElementsMatch(t, []*Symbol{symbols[0], symbols[2]}, []*Symbol{curves[0].from, curves[0].to})
+
+	top := curves[0].path[0]
+	for _, p := range curves[0].path {
+		if p.Y < top.Y {
+			top = p
+		}
+	}
+	assert.LessOrEqual(t, top.Y, 101, "the curve follows the arc rather than its chord")
+
+	// A line along the top of the arc is a piece of it, one across the
+	// middle is not
+	assert.True(t, curves[0].covers(Line{Start: image.Point{X: 180, Y: 102}, End: image.Point{X: 220, Y: 102}}))
+	assert.False(t, curves[0].covers(Line{Start: image.Point{X: 150, Y: 150}, End: image.Point{X: 250, Y: 150}}))
+}
+
+func TestDetectCurvedConnection(t *testing.T) {
+	binary, symbols := arcAround()
+	d := NewDetector(Config{})
+
+	connections := d.detectConnections(binary, symbols)
+	require.Len(t, connections, 1)
+
+	conn := connections[0]
+	assert.ElementsMatch(t, []*Symbol{symbols[0], symbols[2]}, []*Symbol{conn.From, conn.To},
+		"the arc connects its ends, not the circle it passes")
+
+	// The arc leaves upward from both symbols
+	require.NotNil(t, conn.FromAnchor)
+	require.NotNil(t, conn.ToAnchor)
+	assert.Equal(t, AnchorTop, conn.FromAnchor.Side())
+	assert.Equal(t, AnchorTop, conn.ToAnchor.Side())
+}
+
+func TestStraightStrokeIsNotCurve(t *testing.T) {
+	binary := image.NewGray(image.Rect(0, 0, 400, 300))
+	for y := 198; y <= 202; y++ {
+		for x := 100; x <= 300; x++ {
+			binary.SetGray(x, y, color.Gray{Y: 255})
+		}
+	}
+	symbols := []*Symbol{
+		{Type: Square, Position: Position{X: 100, Y: 200}, Size: 30},
+		{Type: Star, Position: Position{X: 300, Y: 200}, Size: 30},
+	}
+
+	assert.Empty(t, NewDetector(Config{}).traceCurves(binary, symbols), "straight strokes are left to line detection")
+}
@@ -2,18 +2,23 @@ package detector
 
 import (
 	"bytes"
+	"context"
+	"errors"
 	"fmt"
 	"image"
 	"image/color"
 	_ "image/gif"  // for gif image format support
 	_ "image/jpeg" // for jpeg image format support
 	_ "image/png"  // for png image format support
+	"io"
 	"math"
 	"os"
 	"path/filepath"
 	"strings"
+	"time"
 
 	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
 	"github.com/ayutaz/grimoire/internal/security"
 	_ "golang.org/x/image/webp" // for webp image format support
 )
@@ -23,9 +28,40 @@ import (
 // Config holds detector configuration
 type Config struct {
 	Debug bool
+	// Progress, if set, is called as detection moves through its stages
+	Progress ProgressFunc
+	// Metrics, if set, is called with the timings and counts of every
+	// detection that finishes
+	Metrics MetricsFunc
+	// Recognizers classify contours before the built-in shape classifier
+	Recognizers []SymbolRecognizer
+	// Preprocessing, if set, replaces the built-in preprocessing with a
+	// chain of steps, as parsed by ParsePreprocessing
+	Preprocessing []PreprocessStep
+	// AllowHugeImages lifts the limits on image dimensions and decoded size,
+	// for trusted images on machines with the memory to detect them
+	AllowHugeImages bool
+	// MinConfidence drops symbols whose confidence is below it. Confidence
+	// runs from 0 to 1 and is comparable across symbol types.
+	MinConfidence float64
+	// Strictness decides whether shapes matching no symbol are dropped,
+	// rejected or kept for the parser to warn of
+	Strictness Strictness
+	// Timeout, if set, bounds how long one detection may take. Past it,
+	// detection returns the symbols found so far with a PartialDetection
+	// error instead of running on.
+	Timeout time.Duration
+	// MaxContours and MaxContourPoints, if set, bound how many contours an
+	// image may have and how many points one contour may trace. An image
+	// past either is rejected with a TooComplex error before its contours
+	// are classified.
+	MaxContours      int
+	MaxContourPoints int
 }
 
-// Detector handles symbol detection from images
+// Detector handles symbol detection from images. A Detector keeps no state
+// between calls other than recycled scratch buffers, so one instance may be
+// shared by many goroutines.
 type Detector struct {
 	minContourArea    int
 	circleThreshold   float64
@@ -34,6 +70,18 @@ type Detector struct {
 	adaptiveBlockSize int
 	morphKernelSize   int
 	debug             bool
+	progress          ProgressFunc
+	metrics           MetricsFunc
+	recognizers       []SymbolRecognizer
+	preprocessing     []PreprocessStep
+	allowHugeImages   bool
+	minConfidence     float64
+	strictness        Strictness
+	timeout           time.Duration
+	maxContours       int
+	maxContourPoints  int
+	scratch           *grayPool
+	contourScratch    *contourPool
 }
 
 // NewDetector creates a new detector with default settings
@@ -46,6 +94,18 @@ func NewDetector(cfg Config) *Detector {
 		adaptiveBlockSize: 11,
 		morphKernelSize:   2, // Reduced to prevent breaking thin lines
 		debug:             cfg.Debug,
+		progress:          cfg.Progress,
+		metrics:           cfg.Metrics,
+		recognizers:       cfg.Recognizers,
+		preprocessing:     cfg.Preprocessing,
+		allowHugeImages:   cfg.AllowHugeImages,
+		minConfidence:     cfg.MinConfidence,
+		strictness:        cfg.Strictness,
+		timeout:           cfg.Timeout,
+		maxContours:       cfg.MaxContours,
+		maxContourPoints:  cfg.MaxContourPoints,
+		scratch:           &grayPool{},
+		contourScratch:    &contourPool{},
 	}
 }
 
@@ -57,69 +117,55 @@ func DetectSymbols(imagePath string) ([]*Symbol, []Connection, error) {
 
 // Detect performs symbol detection on the image
 func (d *Detector) Detect(imagePath string) ([]*Symbol, []Connection, error) {
+	return d.DetectContext(context.Background(), imagePath)
+}
+
+// DetectContext performs symbol detection on the image, returning a Cancelled
+// error if ctx is done before detection finishes
+func (d *Detector) DetectContext(ctx context.Context, imagePath string) ([]*Symbol, []Connection, error) {
 	// Load and validate image
 	img, err := d.loadAndValidateImage(imagePath)
 	if err != nil {
 		return nil, nil, err
 	}
 
-	// Convert to grayscale
-	gray := d.toGrayscale(img)
-
-	// Preprocess image
-	binary := d.preprocessImage(gray)
-
-	// Try to find outer circle in original grayscale image
-	outerCircle := d.findOuterCircleFromGrayscale(gray)
-
-	// Find contours
-	contours := d.findContours(binary)
+	return d.detectImage(ctx, d.sequentialPipeline(), img, imagePath)
+}
 
-	// Add outer circle if found
-	if outerCircle != nil {
-		contours = append([]Contour{*outerCircle}, contours...)
+// DetectFromReader detects symbols from encoded image data read from r.
+// The image format is sniffed from the data, so r may be a pipe such as stdin.
+// source names the input in error locations (e.g. "<stdin>").
+func (d *Detector) DetectFromReader(r io.Reader, source string) ([]*Symbol, []Connection, error) {
+	img, err := d.imageDecoder().DecodeReader(r)
+	if err != nil {
+		return nil, nil, convertImageError(err, source)
 	}
 
-	// Debug: print contour information
-	if os.Getenv("GRIMOIRE_DEBUG") != "" {
-		d.DebugPrintContours(contours)
-		// Save preprocessed image for debugging
-		if err := d.DebugSaveContours(binary, contours, "debug_binary.png"); err != nil {
-			fmt.Printf("Failed to save debug contours: %v\n", err)
-		}
-		// Print detailed info for specific contours
-		fmt.Println("\nDebug: Checking contours in square regions:")
-		for i, contour := range contours {
-			// Check both square regions
-			if (contour.Center.X > 350 && contour.Center.X < 400 &&
-				contour.Center.Y > 170 && contour.Center.Y < 220) ||
-				(contour.Center.X > 200 && contour.Center.X < 250 &&
-					contour.Center.Y > 170 && contour.Center.Y < 220) {
-				bbox := contour.getBoundingBox()
-				// approximatePolygon is in shape_classifier.go
-				vertices := len(contour.Points)
-				fmt.Printf("[%d] Square region candidate:\n", i)
-				fmt.Printf("  Center: (%d,%d)\n", contour.Center.X, contour.Center.Y)
-				fmt.Printf("  BBox: (%d,%d,%d,%d), w=%d, h=%d\n",
-					bbox.Min.X, bbox.Min.Y, bbox.Max.X, bbox.Max.Y,
-					bbox.Dx(), bbox.Dy())
-				fmt.Printf("  Area: %.1f, Perimeter: %.1f\n", contour.Area, contour.Perimeter)
-				fmt.Printf("  Circularity: %.2f, Aspect: %.2f\n",
-					contour.Circularity, contour.getAspectRatio())
-				fmt.Printf("  Points: %d\n", vertices)
-			}
-		}
-		fmt.Println()
-	}
+	return d.detectImage(context.Background(), d.sequentialPipeline(), img, source)
+}
 
-	// Detect symbols from contours
-	symbols := d.detectSymbolsFromContours(contours, binary)
+// DetectImage detects symbols in an already decoded image, such as a camera
+// frame. Errors are located at imageSource since there is no file name.
+func (d *Detector) DetectImage(img image.Image) ([]*Symbol, []Connection, error) {
+	return d.DetectImageContext(context.Background(), img)
+}
 
-	// Deduplicate nearby stars
-	symbols = d.deduplicateNearbyStars(symbols)
+// DetectImageContext detects symbols in an already decoded image, returning
+// a Cancelled error if ctx is done before detection finishes
+func (d *Detector) DetectImageContext(ctx context.Context, img image.Image) ([]*Symbol, []Connection, error) {
+	return d.detectImage(ctx, d.sequentialPipeline(), img, imageSource)
+}
 
-	// Detect connections
-	connections := d.improvedDetectConnections(binary, symbols)
+// detectImage runs a detection pipeline on a decoded image
+func (d *Detector) detectImage(ctx context.Context, p pipeline, img image.Image, imagePath string) ([]*Symbol, []Connection, error) {
+	symbols, connections, err := d.run(ctx, p, img)
+	if partial, ok := asPartial(err, imagePath); ok {
+		// What was found before the deadline may not make a valid drawing
+		return symbols, connections, partial
+	}
+	if err != nil {
+		return nil, nil, err
+	}
 
 	// Validate detection results
 	if err := d.validateResults(symbols, imagePath); err != nil {
@@ -129,42 +175,34 @@ func (d *Detector) Detect(imagePath string) ([]*Symbol, []Connection, error) {
 	return symbols, connections, nil
 }
 
-// DetectFromBytes detects symbols from image bytes
+// IsPartial reports whether err came with the symbols found before
+// detection ran past its timeout, rather than in place of any result
+func IsPartial(err error) bool {
+	errType, ok := grimoireErrors.GetErrorType(err)
+	return ok && errType == grimoireErrors.PartialDetection
+}
+
+// asPartial returns err located at imagePath if it reports a detection
+// stopped at its deadline
+func asPartial(err error, imagePath string) (*grimoireErrors.GrimoireError, bool) {
+	partial, ok := err.(*grimoireErrors.GrimoireError)
+	if !ok || partial.Type != grimoireErrors.PartialDetection {
+		return nil, false
+	}
+	return partial.WithLocation(imagePath, 0, 0), true
+}
+
+// DetectFromBytes detects symbols from image bytes. Unlike Detect, the
+// results are not validated, so an image without symbols is not an error.
 func (d *Detector) DetectFromBytes(imageData []byte) ([]*Symbol, []Connection, error) {
 	// Decode image from bytes
 	img, _, err := image.Decode(bytes.NewReader(imageData))
 	if err != nil {
-		return nil, nil, fmt.Errorf("failed to decode image: %w", err)
+		return nil, nil, grimoireErrors.NewError(grimoireErrors.ImageProcessingError, i18n.T("detector.decode_failed")).
+			WithInnerError(err)
 	}
 
-	// Convert to grayscale
-	gray := d.toGrayscale(img)
-
-	// Preprocess image
-	binary := d.preprocessImage(gray)
-
-	// Try to find outer circle in original grayscale image
-	outerCircle := d.findOuterCircleFromGrayscale(gray)
-
-	// Find contours
-	contours := d.findContours(binary)
-
-	// Add outer circle if found
-	if outerCircle != nil {
-		contours = append([]Contour{*outerCircle}, contours...)
-	}
-
-	// Detect symbols from contours
-	symbols := d.detectSymbolsFromContours(contours, binary)
-
-	// Find connections between symbols
-	// TODO: Implement connection detection for WebAssembly
-	connections := []Connection{}
-
-	// Deduplicate nearby stars
-	symbols = d.deduplicateNearbyStars(symbols)
-
-	return symbols, connections, nil
+	return d.run(context.Background(), d.sequentialPipeline(), img)
 }
 
 // toGrayscale converts an image to grayscale
@@ -184,48 +222,87 @@ func (d *Detector) toGrayscale(img image.Image) *image.Gray {
 }
 
 // detectSymbolsFromContours analyzes contours to identify symbols
-func (d *Detector) detectSymbolsFromContours(contours []Contour, binary *image.Gray) []*Symbol {
+func (d *Detector) detectSymbolsFromContours(ctx context.Context, contours []Contour, binary *image.Gray) []*Symbol {
+	return d.classifyContours(ctx, contours, binary, nil)
+}
+
+// classifyContours analyzes contours to identify symbols. found, if not nil,
+// is called with each symbol as soon as it is accepted; classification stops
+// early when it returns false, or when ctx is done.
+func (d *Detector) classifyContours(ctx context.Context, contours []Contour, binary *image.Gray, found func(*Symbol) bool) []*Symbol {
 	symbols := make([]*Symbol, 0)
+	stopped := false
+	add := func(symbol *Symbol) {
+		symbols = append(symbols, symbol)
+		if found != nil && !found(symbol) {
+			stopped = true
+		}
+	}
 
 	// First, look for the outer circle
 	var outerCircle *Symbol
 	for _, contour := range contours {
+		if ctx.Err() != nil {
+			break
+		}
 		if contour.Area < float64(d.minContourArea) {
 			continue
 		}
 
 		// Classify contour
-		symbolType := d.classifyContour(contour)
+		symbolType := d.classifyContour(contour, binary)
 		if symbolType == OuterCircle {
 			outerCircle = &Symbol{
 				Type:       symbolType,
 				Position:   Position{X: float64(contour.Center.X), Y: float64(contour.Center.Y)},
 				Size:       math.Sqrt(contour.Area),
-				Confidence: contour.Circularity,
+				Confidence: d.confidence(symbolType, contour),
 				Pattern:    "empty",
 				Properties: make(map[string]interface{}),
 			}
-			symbols = append(symbols, outerCircle)
+			add(outerCircle)
 			break
 		}
 	}
 
 	// Then detect other symbols
 	for _, contour := range contours {
+		if stopped || ctx.Err() != nil {
+			break
+		}
 		if contour.Area < float64(d.minContourArea) {
 			continue
 		}
 
-		// Skip if it's the outer circle
-		symbolType := d.classifyContour(contour)
-		if symbolType == OuterCircle || symbolType == Unknown {
-			if os.Getenv("GRIMOIRE_DEBUG") != "" && symbolType == Unknown {
+		// Wide rectangles frame lists of the symbols inside them
+		if frame := d.listFrame(contour, binary); frame != nil {
+			add(frame)
+			continue
+		}
+
+		// Skip if it's the outer circle, keeping circles drawn inside it
+		// around its center as stage rings
+		symbolType := d.classifyContour(contour, binary)
+		if symbolType == OuterCircle {
+			if ring := d.stageRing(outerCircle, contour); ring != nil {
+				add(ring)
+			}
+			continue
+		}
+		if symbolType == Unknown {
+			if os.Getenv("GRIMOIRE_DEBUG") != "" {
 				fmt.Printf("Unknown symbol at (%d,%d), area=%.2f, circularity=%.2f\n",
 					contour.Center.X, contour.Center.Y, contour.Area, contour.Circularity)
 			}
-			continue
+			if !d.keepsUnknown() {
+				continue
+			}
 		}
 
+		// Score the classified shape before it is renamed below
+		confidence := d.confidence(symbolType, contour)
+		alternatives := d.alternatives(symbolType, contour, confidence)
+
 		// Detect internal pattern for shapes that can contain patterns
 		pattern := PatternEmpty
 		if symbolType == Square || symbolType == Circle || symbolType == Pentagon ||
@@ -244,13 +321,23 @@ func (d *Detector) detectSymbolsFromContours(contours []Contour, binary *image.G
 			symbolType = Star
 		}
 
+		for i := range alternatives {
+			if alternatives[i].Type == Circle && pattern == PatternDot {
+				alternatives[i].Type = DoubleCircle
+			}
+		}
+
 		symbol := &Symbol{
-			Type:       symbolType,
-			Position:   Position{X: float64(contour.Center.X), Y: float64(contour.Center.Y)},
-			Size:       math.Sqrt(contour.Area),
-			Confidence: 0.7,
-			Pattern:    pattern,
-			Properties: make(map[string]interface{}),
+			Type:         symbolType,
+			Position:     Position{X: float64(contour.Center.X), Y: float64(contour.Center.Y)},
+			Size:         math.Sqrt(contour.Area),
+			Confidence:   confidence,
+			Pattern:      pattern,
+			Properties:   make(map[string]interface{}),
+			Alternatives: alternatives,
+		}
+		if symbolType == Rectangle {
+			symbol.Width, symbol.Height = rectangleExtent(contour)
 		}
 
 		if os.Getenv("GRIMOIRE_DEBUG") != "" && pattern != "empty" {
@@ -269,39 +356,65 @@ func (d *Detector) detectSymbolsFromContours(contours []Contour, binary *image.G
 					// This fixes the issue where hello-world.png star at (496,449) was being filtered out
 					// The star is ~247 pixels from center, and outer circle radius is ~442, so 247/442 = 56%
 					if centerDist < outerCircle.Size*0.8 { // Within 80% of radius from center
-						symbols = append(symbols, symbol)
+						add(symbol)
 					} else if os.Getenv("GRIMOIRE_DEBUG") != "" {
 						fmt.Printf("Star at (%.0f,%.0f) filtered out: distance %.1f > threshold %.1f\n",
 							symbol.Position.X, symbol.Position.Y, centerDist, outerCircle.Size*0.8)
 					}
 				} else {
-					symbols = append(symbols, symbol)
+					add(symbol)
 				}
 			}
 		} else {
-			symbols = append(symbols, symbol)
+			add(symbol)
 		}
 	}
 
 	return symbols
 }
 
-// classifyContour determines the type of symbol from contour shape
-func (d *Detector) classifyContour(contour Contour) SymbolType {
+// classifyContour determines the type of symbol from contour shape,
+// consulting the registered recognizers first
+func (d *Detector) classifyContour(contour Contour, binary *image.Gray) SymbolType {
+	if symbolType, ok := d.recognize(contour, binary); ok {
+		return symbolType
+	}
 	return d.classifyShape(contour)
 }
 
-// preprocessImage applies preprocessing steps to improve detection
+// preprocessImage applies preprocessing steps to improve detection.
+// Thumbnails take a lighter path, selected automatically, unless a chain of
+// steps is configured.
 func (d *Detector) preprocessImage(gray *image.Gray) *image.Gray {
+	if len(d.preprocessing) > 0 {
+		return d.runPreprocessing(gray)
+	}
+	if isThumbnail(gray.Bounds()) {
+		return d.preprocessThumbnail(gray)
+	}
+	return d.preprocessFull(gray)
+}
+
+// preprocessThumbnail binarizes a thumbnail with a single global threshold.
+// A thumbnail has too few pixels for noise to matter, and blurring or
+// closing would merge its symbols, so both are skipped.
+func (d *Detector) preprocessThumbnail(gray *image.Gray) *image.Gray {
+	return globalThreshold(gray, d.binaryThreshold)
+}
+
+// preprocessFull blurs, adaptively thresholds and closes the image
+func (d *Detector) preprocessFull(gray *image.Gray) *image.Gray {
 	// Apply Gaussian blur to reduce noise
-	blurred := gaussianBlur(gray, d.blurKernelSize)
+	blurred := d.scratch.get(gray.Bounds())
+	gaussianBlurInto(blurred, gray, d.blurKernelSize)
 
 	// Apply adaptive threshold with adjusted constant
 	binary := adaptiveThreshold(blurred, d.adaptiveBlockSize, 5) // Increased constant for better edge preservation
+	d.scratch.put(blurred)
 
 	// Apply morphological operations to clean up
 	// Only apply closing to connect nearby components
-	binary = morphologyClose(binary, d.morphKernelSize)
+	binary = morphologyClose(binary, d.morphKernelSize, d.scratch)
 	// Skip opening to avoid breaking thin lines
 	// binary = morphologyOpen(binary, d.morphKernelSize)
 
@@ -332,7 +445,7 @@ func (d *Detector) findOuterCircleFromGrayscale(gray *image.Gray) *Contour {
 	bounds := gray.Bounds()
 	width := bounds.Dx()
 	height := bounds.Dy()
-	center := image.Point{X: width / 2, Y: height / 2}
+	center := image.Point{X: bounds.Min.X + width/2, Y: bounds.Min.Y + height/2}
 
 	// Scan from center outward to find the circle
 	maxRadius := min(width, height) / 2
@@ -349,7 +462,7 @@ func (d *Detector) findOuterCircleFromGrayscale(gray *image.Gray) *Contour {
 			x := center.X + int(float64(r)*math.Cos(angle))
 			y := center.Y + int(float64(r)*math.Sin(angle))
 
-			if x >= 0 && x < width && y >= 0 && y < height {
+			if (image.Point{X: x, Y: y}).In(bounds) {
 				// Check if pixel is dark (part of circle)
 				if gray.GrayAt(x, y).Y < 128 {
 					blackPixels++
@@ -426,65 +539,91 @@ func (d *Detector) deduplicateNearbyStars(symbols []*Symbol) []*Symbol {
 
 // loadAndValidateImage loads and validates the image file with security checks
 func (d *Detector) loadAndValidateImage(imagePath string) (image.Image, error) {
-	// Create image validator with default settings
-	validator := security.NewImageValidator()
-
-	// Create safe image decoder
-	decoder := security.NewSafeImageDecoder(validator)
-
 	// Decode image with all security validations
-	img, err := decoder.DecodeImage(imagePath)
+	img, err := d.imageDecoder().DecodeImage(imagePath)
 	if err != nil {
-		// Convert security errors to grimoire errors for consistency
-		errStr := err.Error()
+		return nil, convertImageError(err, imagePath)
+	}
 
-		// Check for file not found errors
-		if strings.Contains(errStr, "file not found") || os.IsNotExist(err) {
-			return nil, grimoireErrors.FileNotFoundError(imagePath)
-		}
-		if strings.Contains(errStr, "unsupported file extension") || strings.Contains(errStr, "unsupported file format") {
-			ext := filepath.Ext(imagePath)
-			return nil, grimoireErrors.UnsupportedFormatError(ext).
-				WithDetails(fmt.Sprintf("File: %s", filepath.Base(imagePath)))
-		}
+	return img, nil
+}
 
-		if strings.Contains(errStr, "path traversal") {
-			// Don't expose the actual path in error message for security
-			safeFileName := filepath.Base(imagePath)
-			if strings.Contains(safeFileName, "..") {
-				safeFileName = "invalid-path"
-			}
-			return nil, grimoireErrors.NewError(grimoireErrors.ValidationError, "Invalid file path detected").
-				WithLocation(safeFileName, 0, 0).
-				WithSuggestion("Use a valid file path without directory traversal attempts")
-		}
+// imageDecoder returns a decoder with the default limits, lifted if the
+// detector allows huge images
+func (d *Detector) imageDecoder() *security.SafeImageDecoder {
+	validator := security.NewImageValidator()
+	validator.AllowHuge = d.allowHugeImages
+	return security.NewSafeImageDecoder(validator)
+}
 
-		if strings.Contains(errStr, "exceeds maximum") || strings.Contains(errStr, "exceeds safe limits") {
-			return nil, grimoireErrors.NewError(grimoireErrors.ValidationError, "Image exceeds size limits").
-				WithInnerError(err).
-				WithLocation(imagePath, 0, 0).
-				WithSuggestion("Use a smaller image (max 50MB file size, 10000x10000 pixels)")
-		}
+// convertImageError converts security validation errors to grimoire errors for consistency
+func convertImageError(err error, imagePath string) error {
+	errStr := err.Error()
+
+	// Check for file not found errors
+	if strings.Contains(errStr, "file not found") || os.IsNotExist(err) {
+		return grimoireErrors.FileNotFoundError(imagePath)
+	}
+	if strings.Contains(errStr, "unsupported file extension") || strings.Contains(errStr, "unsupported file format") {
+		ext := filepath.Ext(imagePath)
+		return grimoireErrors.UnsupportedFormatError(ext).
+			WithDetails(i18n.Tf("detail.file", filepath.Base(imagePath)))
+	}
 
-		// Check for permission errors
-		if strings.Contains(errStr, "permission denied") || strings.Contains(errStr, "access is denied") {
-			return nil, grimoireErrors.NewError(grimoireErrors.FileReadError, "Failed to read image file").
-				WithInnerError(err).
-				WithLocation(imagePath, 0, 0)
+	if strings.Contains(errStr, "path traversal") {
+		// Don't expose the actual path in error message for security
+		safeFileName := filepath.Base(imagePath)
+		if strings.Contains(safeFileName, "..") {
+			safeFileName = "invalid-path"
 		}
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("detector.invalid_path")).
+			WithLocation(safeFileName, 0, 0).
+			WithSuggestion(i18n.T("detector.invalid_path_suggestion"))
+	}
 
-		// Generic image processing error
-		return nil, grimoireErrors.NewError(grimoireErrors.ImageProcessingError, "Failed to validate and decode image").
+	if errors.Is(err, security.ErrImageTooLarge) {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("detector.image_too_large")).
 			WithInnerError(err).
 			WithLocation(imagePath, 0, 0).
-			WithSuggestion("Ensure the image is a valid PNG or JPEG file and not corrupted")
+			WithSuggestion(i18n.T("detector.image_too_large_suggestion"))
 	}
 
-	return img, nil
+	if strings.Contains(errStr, "exceeds maximum") {
+		return grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("detector.image_exceeds_limits")).
+			WithInnerError(err).
+			WithLocation(imagePath, 0, 0).
+			WithSuggestion(i18n.T("detector.image_exceeds_limits_suggestion"))
+	}
+
+	// Check for permission errors
+	if strings.Contains(errStr, "permission denied") || strings.Contains(errStr, "access is denied") {
+		return grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("detector.read_failed")).
+			WithInnerError(err).
+			WithLocation(imagePath, 0, 0)
+	}
+
+	// Generic image processing error
+	return grimoireErrors.NewError(grimoireErrors.ImageProcessingError, i18n.T("detector.validate_failed")).
+		WithInnerError(err).
+		WithLocation(imagePath, 0, 0).
+		WithSuggestion(i18n.T("detector.validate_failed_suggestion"))
 }
 
-// validateResults validates the detection results
+// validateResults validates the detection results, rejecting shapes that
+// match no symbol in strict mode
 func (d *Detector) validateResults(symbols []*Symbol, imagePath string) error {
+	if err := validateSymbols(symbols, imagePath); err != nil {
+		return err
+	}
+	if d.strictness == Strict {
+		return unrecognizedError(symbols, imagePath)
+	}
+	return nil
+}
+
+// validateSymbols returns an error unless symbols hold a program: at least
+// one symbol, among them the outer circle
+func validateSymbols(symbols []*Symbol, imagePath string) error {
 	if len(symbols) == 0 {
 		return grimoireErrors.NoSymbolsError().
 			WithLocation(imagePath, 0, 0)
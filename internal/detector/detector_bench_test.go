@@ -1,6 +1,7 @@
 package detector
 
 import (
+	"context"
 	"fmt"
 	"image"
 	"image/color"
@@ -75,7 +76,7 @@ func BenchmarkProcessImage(b *testing.B) {
 			for i := 0; i < b.N; i++ {
 				binary := detector.preprocessImage(img)
 				contours := detector.findContours(binary)
-				_ = detector.detectSymbolsFromContours(contours, binary)
+				_ = detector.detectSymbolsFromContours(context.Background(), contours, binary)
 			}
 		})
 	}
@@ -91,6 +92,25 @@ func BenchmarkPreprocessing(b *testing.B) {
 	}
 }
 
+// BenchmarkPreprocessThumbnail compares the thumbnail path with the full
+// preprocessing it replaces
+func BenchmarkPreprocessThumbnail(b *testing.B) {
+	img := createBenchmarkImage(thumbnailSize)
+	detector := NewDetector(Config{})
+
+	b.Run("thumbnail", func(b *testing.B) {
+		for i := 0; i < b.N; i++ {
+			_ = detector.preprocessThumbnail(img)
+		}
+	})
+
+	b.Run("full", func(b *testing.B) {
+		for i := 0; i < b.N; i++ {
+			_ = detector.preprocessFull(img)
+		}
+	})
+}
+
 func BenchmarkFindContours(b *testing.B) {
 	img := createBenchmarkImage(800)
 	detector := NewDetector(Config{})
@@ -102,6 +122,44 @@ func BenchmarkFindContours(b *testing.B) {
 	}
 }
 
+// BenchmarkContourBufferReuse compares the allocations of tracing with
+// recycled buffers against a new detector, and so new buffers, every time
+func BenchmarkContourBufferReuse(b *testing.B) {
+	binary := NewDetector(Config{}).preprocessImage(createBenchmarkImage(800))
+
+	b.Run("reused", func(b *testing.B) {
+		detector := NewDetector(Config{})
+		b.ReportAllocs()
+		b.ResetTimer()
+		for i := 0; i < b.N; i++ {
+			_ = detector.findContours(binary)
+		}
+	})
+
+	b.Run("fresh", func(b *testing.B) {
+		b.ReportAllocs()
+		for i := 0; i < b.N; i++ {
+			_ = NewDetector(Config{}).findContours(binary)
+		}
+	})
+}
+
+// BenchmarkStreamFrames measures the allocations per frame of a stream
+// detector, which reuses its buffers from one frame to the next
+func BenchmarkStreamFrames(b *testing.B) {
+	frame := createBenchmarkImage(800)
+	stream := NewStreamDetector(Config{})
+	ctx := context.Background()
+
+	b.ReportAllocs()
+	b.ResetTimer()
+	for i := 0; i < b.N; i++ {
+		if _, err := stream.Process(ctx, frame); err != nil {
+			b.Fatal(err)
+		}
+	}
+}
+
 func BenchmarkClassifyShape(b *testing.B) {
 	// Create different contour types
 	contours := []Contour{
@@ -1,22 +1,25 @@
 package detector
 
 import (
+	"context"
 	"image"
 	"math"
 	"runtime"
 	"sync"
 )
 
-// ParallelDetector is an optimized detector using parallel processing
+// ParallelDetector is an optimized detector using parallel processing. It
+// may be shared by many goroutines; its cache is locked.
 type ParallelDetector struct {
 	*Detector
 	numWorkers int
 	cache      *DetectorCache
 }
 
-// DetectorCache stores intermediate results for reuse
+// DetectorCache stores intermediate results for reuse. Every access updates
+// the eviction order, so reads take the lock exclusively too.
 type DetectorCache struct {
-	mu           sync.RWMutex
+	mu           sync.Mutex
 	preprocessed map[string]*image.Gray
 	contours     map[string][]Contour
 	symbols      map[string][]*Symbol
@@ -45,7 +48,7 @@ func NewDetectorCache(maxSize int) *DetectorCache {
 }
 
 // detectSymbolsFromContoursParallel processes contours in parallel
-func (d *ParallelDetector) detectSymbolsFromContoursParallel(contours []Contour, binary *image.Gray) []*Symbol {
+func (d *ParallelDetector) detectSymbolsFromContoursParallel(ctx context.Context, contours []Contour, binary *image.Gray) []*Symbol {
 	if len(contours) == 0 {
 		return []*Symbol{}
 	}
@@ -53,16 +56,19 @@ func (d *ParallelDetector) detectSymbolsFromContoursParallel(contours []Contour,
 	// First, find outer circle (sequential)
 	var outerCircle *Symbol
 	for _, contour := range contours {
+		if ctx.Err() != nil {
+			break
+		}
 		if contour.Area < float64(d.minContourArea) {
 			continue
 		}
-		symbolType := d.classifyContour(contour)
+		symbolType := d.classifyContour(contour, binary)
 		if symbolType == OuterCircle {
 			outerCircle = &Symbol{
 				Type:       symbolType,
 				Position:   Position{X: float64(contour.Center.X), Y: float64(contour.Center.Y)},
 				Size:       math.Sqrt(contour.Area),
-				Confidence: contour.Circularity,
+				Confidence: d.confidence(symbolType, contour),
 				Pattern:    "empty",
 				Properties: make(map[string]interface{}),
 			}
@@ -89,12 +95,23 @@ func (d *ParallelDetector) detectSymbolsFromContoursParallel(contours []Contour,
 			defer wg.Done()
 			for idx := range workChan {
 				contour := contours[idx]
-				if contour.Area < float64(d.minContourArea) {
+				if contour.Area < float64(d.minContourArea) || ctx.Err() != nil {
+					continue
+				}
+
+				if frame := d.listFrame(contour, binary); frame != nil {
+					resultChan <- symbolResult{symbol: frame, index: idx}
 					continue
 				}
 
-				symbolType := d.classifyContour(contour)
-				if symbolType == OuterCircle || symbolType == Unknown {
+				symbolType := d.classifyContour(contour, binary)
+				if symbolType == OuterCircle {
+					if ring := d.stageRing(outerCircle, contour); ring != nil {
+						resultChan <- symbolResult{symbol: ring, index: idx}
+					}
+					continue
+				}
+				if symbolType == Unknown && !d.keepsUnknown() {
 					continue
 				}
 
@@ -105,13 +122,18 @@ func (d *ParallelDetector) detectSymbolsFromContoursParallel(contours []Contour,
 					pattern = d.detectInternalPattern(contour, binary)
 				}
 
+				confidence := d.confidence(symbolType, contour)
 				symbol := &Symbol{
-					Type:       symbolType,
-					Position:   Position{X: float64(contour.Center.X), Y: float64(contour.Center.Y)},
-					Size:       math.Sqrt(contour.Area),
-					Confidence: 0.7,
-					Pattern:    pattern,
-					Properties: make(map[string]interface{}),
+					Type:         symbolType,
+					Position:     Position{X: float64(contour.Center.X), Y: float64(contour.Center.Y)},
+					Size:         math.Sqrt(contour.Area),
+					Confidence:   confidence,
+					Pattern:      pattern,
+					Properties:   make(map[string]interface{}),
+					Alternatives: d.alternatives(symbolType, contour, confidence),
+				}
+				if symbolType == Rectangle {
+					symbol.Width, symbol.Height = rectangleExtent(contour)
 				}
 
 				// Check if within outer circle
@@ -159,8 +181,9 @@ func (d *ParallelDetector) detectSymbolsFromContoursParallel(contours []Contour,
 	return symbols
 }
 
-// findContoursParallel finds contours using parallel processing
-func (d *ParallelDetector) findContoursParallel(binary *image.Gray) []Contour {
+// findContoursParallel finds contours using parallel processing, each strip
+// stopping once ctx is done
+func (d *ParallelDetector) findContoursParallel(ctx context.Context, binary *image.Gray) []Contour {
 	bounds := binary.Bounds()
 	height := bounds.Dy()
 
@@ -200,7 +223,7 @@ func (d *ParallelDetector) findContoursParallel(binary *image.Gray) []Contour {
 			}
 
 			// Find contours in strip
-			stripContours := d.findContours(subImage)
+			stripContours := d.findContoursContext(ctx, subImage)
 
 			// Adjust Y coordinates
 			for i := range stripContours {
@@ -230,6 +253,12 @@ func (d *ParallelDetector) findContoursParallel(binary *image.Gray) []Contour {
 
 // Detect performs optimized symbol detection
 func (d *ParallelDetector) Detect(imagePath string) ([]*Symbol, []Connection, error) {
+	return d.DetectContext(context.Background(), imagePath)
+}
+
+// DetectContext performs optimized symbol detection, returning a Cancelled
+// error if ctx is done before detection finishes
+func (d *ParallelDetector) DetectContext(ctx context.Context, imagePath string) ([]*Symbol, []Connection, error) {
 	// Check cache first
 	if cached := d.cache.getSymbols(imagePath); cached != nil {
 		// Still need the binary image for connection detection
@@ -244,7 +273,7 @@ func (d *ParallelDetector) Detect(imagePath string) ([]*Symbol, []Connection, er
 			binary = d.preprocessImage(gray)
 			d.cache.setPreprocessed(imagePath, binary)
 		}
-		connections := d.improvedDetectConnections(binary, cached)
+		connections := d.improvedDetectConnections(ctx, binary, cached)
 		return cached, connections, nil
 	}
 
@@ -254,51 +283,42 @@ func (d *ParallelDetector) Detect(imagePath string) ([]*Symbol, []Connection, er
 		return nil, nil, err
 	}
 
-	// Convert to grayscale
-	gray := d.toGrayscale(img)
-
-	// Check cache for preprocessed image
-	binary := d.cache.getPreprocessed(imagePath)
-	if binary == nil {
-		binary = d.preprocessImage(gray)
-		d.cache.setPreprocessed(imagePath, binary)
+	symbols, connections, err := d.run(ctx, pipeline{
+		preprocess: func(gray *image.Gray) *image.Gray {
+			// Check cache for preprocessed image
+			binary := d.cache.getPreprocessed(imagePath)
+			if binary == nil {
+				binary = d.preprocessImage(gray)
+				d.cache.setPreprocessed(imagePath, binary)
+			}
+			return binary
+		},
+		findContours:      d.findContoursParallel,
+		detectSymbols:     d.detectSymbolsFromContoursParallel,
+		detectConnections: d.improvedDetectConnections,
+	}, img)
+	if partial, ok := asPartial(err, imagePath); ok {
+		return symbols, connections, partial
 	}
-
-	// Find outer circle
-	outerCircle := d.findOuterCircleFromGrayscale(gray)
-
-	// Find contours in parallel
-	contours := d.findContoursParallel(binary)
-
-	// Add outer circle if found
-	if outerCircle != nil {
-		contours = append([]Contour{*outerCircle}, contours...)
+	if err != nil {
+		return nil, nil, err
 	}
 
-	// Detect symbols in parallel
-	symbols := d.detectSymbolsFromContoursParallel(contours, binary)
-
-	// Deduplicate nearby stars
-	symbols = d.deduplicateNearbyStars(symbols)
-
-	// Cache symbols
-	d.cache.setSymbols(imagePath, symbols)
-
-	// Detect connections
-	connections := d.improvedDetectConnections(binary, symbols)
-
 	// Validate results
 	if err := d.validateResults(symbols, imagePath); err != nil {
 		return nil, nil, err
 	}
 
+	// Cache symbols once they are known to be valid
+	d.cache.setSymbols(imagePath, symbols)
+
 	return symbols, connections, nil
 }
 
 // Cache methods
 func (c *DetectorCache) getPreprocessed(key string) *image.Gray {
-	c.mu.RLock()
-	defer c.mu.RUnlock()
+	c.mu.Lock()
+	defer c.mu.Unlock()
 	c.updateAccessOrder(key)
 	return c.preprocessed[key]
 }
@@ -317,8 +337,8 @@ func (c *DetectorCache) setPreprocessed(key string, img *image.Gray) {
 }
 
 func (c *DetectorCache) getSymbols(key string) []*Symbol {
-	c.mu.RLock()
-	defer c.mu.RUnlock()
+	c.mu.Lock()
+	defer c.mu.Unlock()
 	c.updateAccessOrder(key)
 	return c.symbols[key]
 }
@@ -1,6 +1,7 @@
 package detector
 
 import (
+	"context"
 	"fmt"
 	"image"
 	"image/color"
@@ -83,7 +84,7 @@ func BenchmarkContourDetection(b *testing.B) {
 			detector := NewParallelDetectorV2(Config{})
 			b.ResetTimer()
 			for i := 0; i < b.N; i++ {
-				_ = detector.findContoursOptimized(binary)
+				_ = detector.findContoursOptimized(context.Background(), binary)
 			}
 		})
 	}
@@ -101,7 +102,7 @@ func BenchmarkSymbolDetection(b *testing.B) {
 			detector := NewDetector(Config{})
 			b.ResetTimer()
 			for i := 0; i < b.N; i++ {
-				_ = detector.detectSymbolsFromContours(contours, binary)
+				_ = detector.detectSymbolsFromContours(context.Background(), contours, binary)
 			}
 		})
 
@@ -109,7 +110,7 @@ func BenchmarkSymbolDetection(b *testing.B) {
 			detector := NewParallelDetectorV2(Config{})
 			b.ResetTimer()
 			for i := 0; i < b.N; i++ {
-				_ = detector.detectSymbolsOptimized(contours, binary)
+				_ = detector.detectSymbolsOptimized(context.Background(), contours, binary)
 			}
 		})
 	}
@@ -135,7 +136,7 @@ func BenchmarkConnectionDetection(b *testing.B) {
 			detector := NewDetector(Config{})
 			b.ResetTimer()
 			for i := 0; i < b.N; i++ {
-				_ = detector.improvedDetectConnections(binary, symbols)
+				_ = detector.improvedDetectConnections(context.Background(), binary, symbols)
 			}
 		})
 
@@ -143,7 +144,7 @@ func BenchmarkConnectionDetection(b *testing.B) {
 			detector := NewParallelDetectorV2(Config{})
 			b.ResetTimer()
 			for i := 0; i < b.N; i++ {
-				_ = detector.detectConnectionsOptimized(binary, symbols)
+				_ = detector.detectConnectionsOptimized(context.Background(), binary, symbols)
 			}
 		})
 	}
@@ -159,7 +160,7 @@ func BenchmarkMemoryAllocation(b *testing.B) {
 		b.ResetTimer()
 		for i := 0; i < b.N; i++ {
 			detector := NewDetector(Config{})
-			_ = detector.detectSymbolsFromContours(contours, binary)
+			_ = detector.detectSymbolsFromContours(context.Background(), contours, binary)
 		}
 	})
 
@@ -168,7 +169,7 @@ func BenchmarkMemoryAllocation(b *testing.B) {
 		b.ResetTimer()
 		detector := NewParallelDetectorV2(Config{})
 		for i := 0; i < b.N; i++ {
-			_ = detector.detectSymbolsOptimized(contours, binary)
+			_ = detector.detectSymbolsOptimized(context.Background(), contours, binary)
 		}
 		detector.Cleanup()
 	})
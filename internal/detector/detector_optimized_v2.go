@@ -1,20 +1,19 @@
 package detector
 
 import (
+	"context"
 	"image"
+	"io"
 	"runtime"
 	"sync"
-	"sync/atomic"
 )
 
-// ParallelDetectorV2 is an improved parallel detector with better memory management
+// ParallelDetectorV2 is an improved parallel detector with better memory
+// management. Like Detector, it holds no per-call state and may be shared
+// between goroutines.
 type ParallelDetectorV2 struct {
 	*Detector
-	workerCount       int
-	contourPool       *sync.Pool
-	symbolPool        *sync.Pool
-	bufferPool        *sync.Pool
-	atomicSymbolCount int64
+	workerCount int
 }
 
 // NewParallelDetectorV2 creates an improved parallel detector
@@ -27,88 +26,74 @@ func NewParallelDetectorV2(cfg Config) *ParallelDetectorV2 {
 	return &ParallelDetectorV2{
 		Detector:    NewDetector(cfg),
 		workerCount: workerCount,
-		contourPool: &sync.Pool{
-			New: func() interface{} {
-				return &Contour{
-					Points: make([]image.Point, 0, 100),
-				}
-			},
-		},
-		symbolPool: &sync.Pool{
-			New: func() interface{} {
-				return &Symbol{
-					Properties: make(map[string]interface{}),
-				}
-			},
-		},
-		bufferPool: &sync.Pool{
-			New: func() interface{} {
-				// Pre-allocate buffer for image processing
-				return make([]byte, 4096)
-			},
-		},
 	}
 }
 
 // Detect performs optimized parallel detection
 func (pd *ParallelDetectorV2) Detect(imagePath string) ([]*Symbol, []Connection, error) {
+	return pd.DetectContext(context.Background(), imagePath)
+}
+
+// DetectContext performs optimized parallel detection, returning a Cancelled
+// error if ctx is done before detection finishes
+func (pd *ParallelDetectorV2) DetectContext(ctx context.Context, imagePath string) ([]*Symbol, []Connection, error) {
 	// Load and validate image
 	img, err := pd.loadAndValidateImage(imagePath)
 	if err != nil {
 		return nil, nil, err
 	}
 
-	// Convert to grayscale
-	gray := pd.toGrayscale(img)
-
-	// Preprocess image
-	binary := pd.preprocessImage(gray)
-
-	// Reset atomic counter
-	atomic.StoreInt64(&pd.atomicSymbolCount, 0)
-
-	// Find contours with memory-efficient parallel processing
-	contours := pd.findContoursOptimized(binary)
+	return pd.detectImage(ctx, pd.parallelPipeline(), img, imagePath)
+}
 
-	// Try to find outer circle
-	outerCircle := pd.findOuterCircleFromGrayscale(gray)
-	if outerCircle != nil {
-		contours = append([]Contour{*outerCircle}, contours...)
+// DetectFromReader performs optimized parallel detection on encoded image
+// data read from r. source names the input in error locations.
+func (pd *ParallelDetectorV2) DetectFromReader(r io.Reader, source string) ([]*Symbol, []Connection, error) {
+	img, err := pd.imageDecoder().DecodeReader(r)
+	if err != nil {
+		return nil, nil, convertImageError(err, source)
 	}
 
-	// Detect symbols from contours with object pooling
-	symbols := pd.detectSymbolsOptimized(contours, binary)
+	return pd.detectImage(context.Background(), pd.parallelPipeline(), img, source)
+}
 
-	// Deduplicate nearby stars
-	symbols = pd.deduplicateNearbyStars(symbols)
+// DetectImage performs optimized parallel detection on a decoded image
+func (pd *ParallelDetectorV2) DetectImage(img image.Image) ([]*Symbol, []Connection, error) {
+	return pd.DetectImageContext(context.Background(), img)
+}
 
-	// Detect connections with optimized algorithm
-	connections := pd.detectConnectionsOptimized(binary, symbols)
+// DetectImageContext performs optimized parallel detection on a decoded
+// image, returning a Cancelled error if ctx is done before detection finishes
+func (pd *ParallelDetectorV2) DetectImageContext(ctx context.Context, img image.Image) ([]*Symbol, []Connection, error) {
+	return pd.detectImage(ctx, pd.parallelPipeline(), img, imageSource)
+}
 
-	// Validate results
-	if err := pd.validateResults(symbols, imagePath); err != nil {
-		return nil, nil, err
+// parallelPipeline returns the parallel detection stages
+func (pd *ParallelDetectorV2) parallelPipeline() pipeline {
+	return pipeline{
+		preprocess:        pd.preprocessImage,
+		findContours:      pd.findContoursOptimized,
+		detectSymbols:     pd.detectSymbolsOptimized,
+		detectConnections: pd.detectConnectionsOptimized,
 	}
-
-	return symbols, connections, nil
 }
 
 // findContoursOptimized finds contours with better memory management
-func (pd *ParallelDetectorV2) findContoursOptimized(binary *image.Gray) []Contour {
+func (pd *ParallelDetectorV2) findContoursOptimized(ctx context.Context, binary *image.Gray) []Contour {
 	// Use standard detector for the actual contour finding
 	// The optimization is in the parallel symbol detection and connection detection
-	return pd.Detector.findContours(binary)
+	return pd.Detector.findContoursContext(ctx, binary)
 }
 
 // detectSymbolsOptimized detects symbols with object pooling
-func (pd *ParallelDetectorV2) detectSymbolsOptimized(contours []Contour, binary *image.Gray) []*Symbol {
+func (pd *ParallelDetectorV2) detectSymbolsOptimized(ctx context.Context, contours []Contour, binary *image.Gray) []*Symbol {
 	if len(contours) == 0 {
 		return nil
 	}
 
 	// For small contour counts, use single-threaded approach
 	if len(contours) < 50 {
-		return pd.detectSymbolsFromContours(contours, binary)
+		return pd.detectSymbolsFromContours(ctx, contours, binary)
 	}
 
 	// Preallocate result slice
@@ -136,23 +121,31 @@ func (pd *ParallelDetectorV2) detectSymbolsOptimized(contours []Contour, binary
 			batchSymbols := make([]*Symbol, 0, len(batch))
 
 			for _, contour := range batch {
+				if ctx.Err() != nil {
+					break
+				}
 				if contour.Area < float64(pd.Detector.minContourArea) {
 					continue
 				}
 
 				// Classify contour
-				symbolType := pd.classifyContour(contour)
-				if symbolType == Unknown {
+				symbolType := pd.classifyContour(contour, binary)
+				if symbolType == Unknown && !pd.keepsUnknown() {
 					continue
 				}
 
 				// Create new symbol instead of using pool to avoid race conditions
+				confidence := pd.confidence(symbolType, contour)
 				symbol := &Symbol{
-					Type:       symbolType,
-					Position:   Position{X: float64(contour.Center.X), Y: float64(contour.Center.Y)},
-					Size:       contour.getEquivalentRadius(),
-					Confidence: contour.Circularity,
-					Properties: make(map[string]interface{}),
+					Type:         symbolType,
+					Position:     Position{X: float64(contour.Center.X), Y: float64(contour.Center.Y)},
+					Size:         contour.getEquivalentRadius(),
+					Confidence:   confidence,
+					Properties:   make(map[string]interface{}),
+					Alternatives: pd.alternatives(symbolType, contour, confidence),
+				}
+				if symbolType == Rectangle {
+					symbol.Width, symbol.Height = rectangleExtent(contour)
 				}
 
 				// Detect pattern
@@ -177,14 +170,12 @@ func (pd *ParallelDetectorV2) detectSymbolsOptimized(contours []Contour, binary
 
 	wg.Wait()
 
-	// Update atomic counter
-	atomic.AddInt64(&pd.atomicSymbolCount, int64(len(results)))
-
 	return results
 }
 
-// detectConnectionsOptimized detects connections with spatial indexing
-func (pd *ParallelDetectorV2) detectConnectionsOptimized(binary *image.Gray, symbols []*Symbol) []Connection {
+// detectConnectionsOptimized detects connections with spatial indexing,
+// stopping once ctx is done
+func (pd *ParallelDetectorV2) detectConnectionsOptimized(ctx context.Context, binary *image.Gray, symbols []*Symbol) []Connection {
 	if len(symbols) < 2 {
 		return nil
 	}
@@ -214,7 +205,7 @@ func (pd *ParallelDetectorV2) detectConnectionsOptimized(binary *image.Gray, sym
 			go func(startIdx, endIdx int) {
 				defer wg.Done()
 
-				localConnections := pd.detectConnectionsBatch(binary, symbols, spatialIndex, startIdx, endIdx)
+				localConnections := pd.detectConnectionsBatch(ctx, binary, symbols, spatialIndex, startIdx, endIdx)
 
 				if len(localConnections) > 0 {
 					connectionsMutex.Lock()
@@ -227,7 +218,7 @@ func (pd *ParallelDetectorV2) detectConnectionsOptimized(binary *image.Gray, sym
 		wg.Wait()
 	} else {
 		// Single-threaded for small symbol counts
-		connections = pd.improvedDetectConnections(binary, symbols)
+		connections = pd.improvedDetectConnections(ctx, binary, symbols)
 	}
 
 	return connections
@@ -325,12 +316,12 @@ func (si *SpatialIndex) getNearbySymbols(pos Position, maxDist float64) []*Symbo
 }
 
 // detectConnectionsBatch detects connections for a batch of symbols
-func (pd *ParallelDetectorV2) detectConnectionsBatch(binary *image.Gray, symbols []*Symbol,
+func (pd *ParallelDetectorV2) detectConnectionsBatch(ctx context.Context, binary *image.Gray, symbols []*Symbol,
 	spatialIndex *SpatialIndex, startIdx, endIdx int) []Connection {
 
 	connections := make([]Connection, 0)
 
-	for i := startIdx; i < endIdx && i < len(symbols); i++ {
+	for i := startIdx; i < endIdx && i < len(symbols) && ctx.Err() == nil; i++ {
 		fromSymbol := symbols[i]
 		if fromSymbol.Type == OuterCircle {
 			continue
@@ -357,6 +348,8 @@ func (pd *ParallelDetectorV2) detectConnectionsBatch(binary *image.Gray, symbols
 					From:           fromSymbol,
 					To:             toSymbol,
 					ConnectionType: connectionType,
+					FromAnchor:     centerAnchor(fromSymbol, toSymbol),
+					ToAnchor:       centerAnchor(toSymbol, fromSymbol),
 				})
 			}
 		}
@@ -2,6 +2,8 @@ package detector
 
 import (
 	"bytes"
+	"encoding/binary"
+	"hash/crc32"
 	"image"
 	"image/color"
 	"image/png"
@@ -9,6 +11,7 @@ import (
 	"path/filepath"
 	"testing"
 
+	"github.com/ayutaz/grimoire/internal/i18n"
 	"github.com/stretchr/testify/assert"
 	"github.com/stretchr/testify/require"
 )
@@ -53,7 +56,26 @@ func createTestPNGFile(t *testing.T, path string, width, height int) {
 	require.NoError(t, err)
 }
 
+// pngHeaderOnly returns a PNG signature and header chunk declaring an RGBA
+// image of the given size, without any pixel data
+func pngHeaderOnly(width, height uint32) []byte {
+	ihdr := make([]byte, 17)
+	copy(ihdr, "IHDR")
+	binary.BigEndian.PutUint32(ihdr[4:], width)
+	binary.BigEndian.PutUint32(ihdr[8:], height)
+	ihdr[12] = 8 // Bit depth
+	ihdr[13] = 6 // RGBA
+
+	data := []byte{0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A}
+	data = binary.BigEndian.AppendUint32(data, 13)
+	data = append(data, ihdr...)
+	return binary.BigEndian.AppendUint32(data, crc32.ChecksumIEEE(ihdr))
+}
+
 func TestDetector_SecurityValidation(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
 	detector := NewDetector(Config{})
 	tempDir := t.TempDir()
 
@@ -101,10 +123,20 @@ func TestDetector_SecurityValidation(t *testing.T) {
 	})
 
 	t.Run("Image dimension limits", func(t *testing.T) {
-		// This test would require creating an actual large PNG
-		// For now, we'll test that the validation is in place
-		// In a real scenario, you'd create a 10001x10001 image
-		t.Skip("Skipping large image test to save resources")
+		// Only the header is needed, since limits are checked before decoding
+		hugePath := filepath.Join(tempDir, "30000x30000.png")
+		require.NoError(t, os.WriteFile(hugePath, pngHeaderOnly(30000, 30000), 0644))
+
+		_, _, err := detector.Detect(hugePath)
+		require.Error(t, err)
+		assert.Contains(t, err.Error(), "too large to decode safely")
+		assert.Contains(t, err.Error(), "--allow-huge")
+
+		// Allowing huge images gets past the limits to decoding, which then
+		// fails because the file has no pixel data
+		_, _, err = NewDetector(Config{AllowHugeImages: true}).Detect(hugePath)
+		require.Error(t, err)
+		assert.NotContains(t, err.Error(), "too large")
 	})
 
 	t.Run("Invalid file format with correct extension", func(t *testing.T) {
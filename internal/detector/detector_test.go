@@ -113,6 +113,24 @@ func TestPreprocessImage(t *testing.T) {
 	assert.Equal(t, gray.Bounds(), binary.Bounds())
 }
 
+// TestPreprocessThumbnail tests that small images take the thumbnail path
+func TestPreprocessThumbnail(t *testing.T) {
+	detector := NewDetector(Config{})
+
+	thumbnail := detector.toGrayscale(createTestImageWithCircle(200, 200, 90))
+	binary := detector.preprocessImage(thumbnail)
+	assert.Equal(t, globalThreshold(thumbnail, detector.binaryThreshold).Pix, binary.Pix)
+	// The circle outline is foreground and the paper is background
+	assert.Equal(t, uint8(255), binary.GrayAt(190, 100).Y)
+	assert.Equal(t, uint8(0), binary.GrayAt(100, 100).Y)
+
+	// One side over the limit is enough to take the full path
+	assert.True(t, isThumbnail(image.Rect(0, 0, thumbnailSize, thumbnailSize)))
+	assert.False(t, isThumbnail(image.Rect(0, 0, thumbnailSize+1, 10)))
+	wide := detector.toGrayscale(createTestImageWithCircle(thumbnailSize+1, 200, 90))
+	assert.Equal(t, detector.preprocessFull(wide).Pix, detector.preprocessImage(wide).Pix)
+}
+
 // Helper functions
 
 func createTestImage(width, height int) *image.RGBA {
@@ -426,3 +444,23 @@ func abs(x int) int {
 	}
 	return x
 }
+
+// TestDetectFromBytesMatchesDetect tests that detecting from bytes finds the
+// same symbols and connections as detecting from a file
+func TestDetectFromBytesMatchesDetect(t *testing.T) {
+	path := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	data, err := os.ReadFile(path)
+	if err != nil {
+		t.Skip("Example image not found")
+	}
+
+	d := NewDetector(Config{})
+	symbols, connections, err := d.Detect(path)
+	require.NoError(t, err)
+
+	fromBytes, fromBytesConnections, err := d.DetectFromBytes(data)
+	require.NoError(t, err)
+
+	assert.Equal(t, symbols, fromBytes)
+	assert.Equal(t, connections, fromBytesConnections)
+}
@@ -0,0 +1,46 @@
+package detector
+
+import (
+	"bytes"
+	"image"
+	"image/gif"
+	"image/jpeg"
+	"image/png"
+	"testing"
+)
+
+// FuzzDetectFromReader feeds arbitrary bytes to the image loader and the
+// detector, which must return symbols or an error without panicking
+func FuzzDetectFromReader(f *testing.F) {
+	circle := createTestImageWithCircle(120, 120, 50)
+	for _, encode := range []func(*bytes.Buffer, image.Image) error{
+		func(buf *bytes.Buffer, img image.Image) error { return png.Encode(buf, img) },
+		func(buf *bytes.Buffer, img image.Image) error { return jpeg.Encode(buf, img, nil) },
+		func(buf *bytes.Buffer, img image.Image) error { return gif.Encode(buf, img, nil) },
+	} {
+		var buf bytes.Buffer
+		if err := encode(&buf, circle); err != nil {
+			f.Fatal(err)
+		}
+		f.Add(buf.Bytes())
+		// Truncated in the middle of the pixel data
+		f.Add(buf.Bytes()[:buf.Len()/2])
+	}
+
+	var tiny bytes.Buffer
+	if err := png.Encode(&tiny, image.NewGray(image.Rect(0, 0, 1, 1))); err != nil {
+		f.Fatal(err)
+	}
+	f.Add(tiny.Bytes())
+	f.Add(pngHeaderOnly(30000, 30000))
+	f.Add([]byte{})
+	f.Add([]byte("not an image"))
+
+	d := NewDetector(Config{})
+	f.Fuzz(func(t *testing.T, data []byte) {
+		symbols, _, err := d.DetectFromReader(bytes.NewReader(data), "fuzz.png")
+		if err == nil && len(symbols) == 0 {
+			t.Fatal("DetectFromReader returned neither symbols nor an error")
+		}
+	})
+}
@@ -0,0 +1,233 @@
+package detector
+
+import (
+	"image"
+	"image/color"
+	"math"
+	"math/rand"
+	"reflect"
+	"testing"
+	"testing/quick"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// Shape kinds generated for property tests
+const (
+	shapeCircle  = "circle"
+	shapePolygon = "polygon"
+	shapeStar    = "star"
+)
+
+// circleSegments is how many edges approximate a circle
+const circleSegments = 720
+
+// shape is a randomly generated circle, regular polygon or star
+type shape struct {
+	kind   string
+	sides  int     // Vertices of a polygon or points of a star
+	cx, cy float64 // Center
+	radius float64 // Circumradius
+	inner  float64 // Inner radius of a star
+	angle  float64 // Rotation in radians
+}
+
+// randomShape returns a shape whose circumradius is between minRadius and
+// maxRadius, placed so that it fits in [0, extent) on both axes
+func randomShape(r *rand.Rand, minRadius, maxRadius, extent float64) shape {
+	s := shape{
+		radius: minRadius + r.Float64()*(maxRadius-minRadius),
+		angle:  r.Float64() * 2 * math.Pi,
+	}
+	switch r.Intn(3) {
+	case 0:
+		s.kind = shapeCircle
+	case 1:
+		s.kind = shapePolygon
+		s.sides = 3 + r.Intn(6)
+	default:
+		s.kind = shapeStar
+		s.sides = 5 + r.Intn(4)
+		s.inner = s.radius * (0.4 + r.Float64()*0.2)
+	}
+	margin := s.radius + 2
+	s.cx = margin + r.Float64()*(extent-2*margin)
+	s.cy = margin + r.Float64()*(extent-2*margin)
+	return s
+}
+
+// Generate implements quick.Generator with shapes large enough that
+// rounding the outline to pixels changes the area by less than 2%
+func (shape) Generate(r *rand.Rand, _ int) reflect.Value {
+	s := randomShape(r, 150, 250, 1000)
+	if s.kind == shapeStar {
+		// Stars have the longest outline for their area
+		s = randomShape(r, 250, 400, 1000)
+		for s.kind != shapeStar {
+			s = randomShape(r, 250, 400, 1000)
+		}
+	}
+	return reflect.ValueOf(s)
+}
+
+// vertices returns the corners of the shape in order
+func (s shape) vertices() [][2]float64 {
+	var radii []float64
+	switch s.kind {
+	case shapeCircle:
+		radii = make([]float64, circleSegments)
+		for i := range radii {
+			radii[i] = s.radius
+		}
+	case shapePolygon:
+		radii = make([]float64, s.sides)
+		for i := range radii {
+			radii[i] = s.radius
+		}
+	default:
+		radii = make([]float64, 2*s.sides)
+		for i := range radii {
+			radii[i] = s.radius
+			if i%2 == 1 {
+				radii[i] = s.inner
+			}
+		}
+	}
+
+	vertices := make([][2]float64, len(radii))
+	for i, radius := range radii {
+		theta := s.angle + 2*math.Pi*float64(i)/float64(len(radii))
+		vertices[i] = [2]float64{s.cx + radius*math.Cos(theta), s.cy + radius*math.Sin(theta)}
+	}
+	return vertices
+}
+
+// outline returns pixels along the outline about one pixel apart, in order,
+// like a traced contour. Every edge gets the same number of samples, so the
+// samples are as symmetric as the shape.
+func (s shape) outline() []image.Point {
+	vertices := s.vertices()
+	first, second := vertices[0], vertices[1]
+	steps := int(math.Ceil(math.Hypot(second[0]-first[0], second[1]-first[1])))
+
+	points := make([]image.Point, 0, len(vertices)*steps)
+	for i, from := range vertices {
+		to := vertices[(i+1)%len(vertices)]
+		for step := 0; step < steps; step++ {
+			t := float64(step) / float64(steps)
+			points = append(points, image.Point{
+				X: int(math.Round(from[0] + t*(to[0]-from[0]))),
+				Y: int(math.Round(from[1] + t*(to[1]-from[1]))),
+			})
+		}
+	}
+	return points
+}
+
+// area returns the exact area of the shape
+func (s shape) area() float64 {
+	switch s.kind {
+	case shapeCircle:
+		return math.Pi * s.radius * s.radius
+	case shapePolygon:
+		n := float64(s.sides)
+		return n / 2 * s.radius * s.radius * math.Sin(2*math.Pi/n)
+	default:
+		n := float64(s.sides)
+		return n * s.radius * s.inner * math.Sin(math.Pi/n)
+	}
+}
+
+// contains reports whether (x, y) is inside the shape, by the even-odd rule
+func (s shape) contains(x, y float64) bool {
+	vertices := s.vertices()
+	inside := false
+	for i, a := range vertices {
+		b := vertices[(i+len(vertices)-1)%len(vertices)]
+		if (a[1] > y) != (b[1] > y) && x < a[0]+(y-a[1])*(b[0]-a[0])/(b[1]-a[1]) {
+			inside = !inside
+		}
+	}
+	return inside
+}
+
+// render draws the shape filled in black on a white image
+func (s shape) render(size int) *image.RGBA {
+	img := createTestImage(size, size)
+	for y := 0; y < size; y++ {
+		for x := 0; x < size; x++ {
+			if s.contains(float64(x)+0.5, float64(y)+0.5) {
+				img.Set(x, y, color.Black)
+			}
+		}
+	}
+	return img
+}
+
+// propertyConfig runs each property on a fixed sequence of shapes, so that
+// a failure can be reproduced
+func propertyConfig(count int) *quick.Config {
+	return &quick.Config{MaxCount: count, Rand: rand.New(rand.NewSource(1))}
+}
+
+func TestPropertyContourAreaMatchesAnalyticArea(t *testing.T) {
+	property := func(s shape) bool {
+		contour := Contour{Points: s.outline()}
+		contour.calculateProperties()
+		return math.Abs(contour.Area-s.area()) <= 0.02*s.area()
+	}
+	require.NoError(t, quick.Check(property, propertyConfig(200)))
+}
+
+func TestPropertyContourCenterIsShapeCenter(t *testing.T) {
+	property := func(s shape) bool {
+		contour := Contour{Points: s.outline()}
+		contour.calculateProperties()
+		return math.Hypot(float64(contour.Center.X)-s.cx, float64(contour.Center.Y)-s.cy) <= 2
+	}
+	require.NoError(t, quick.Check(property, propertyConfig(200)))
+}
+
+func TestPropertyCircleIsRoundAndCircular(t *testing.T) {
+	property := func(s shape) bool {
+		s.kind = shapeCircle
+		contour := Contour{Points: s.outline()}
+		contour.calculateProperties()
+		return math.Abs(contour.getAspectRatio()-1) <= 0.02 &&
+			math.Abs(contour.getEquivalentRadius()-s.radius) <= 0.02*s.radius
+	}
+	require.NoError(t, quick.Check(property, propertyConfig(100)))
+}
+
+func TestPropertyRenderedContoursStayOnTheShape(t *testing.T) {
+	const size = 200 // A thumbnail, so the drawn pixels are exactly the foreground
+	d := NewDetector(Config{})
+
+	property := func(seed int64) bool {
+		s := randomShape(rand.New(rand.NewSource(seed)), 20, 60, size)
+		binary := d.preprocessImage(d.toGrayscale(s.render(size)))
+
+		for _, contour := range d.findContours(binary) {
+			for _, pt := range contour.Points {
+				if !s.contains(float64(pt.X)+0.5, float64(pt.Y)+0.5) {
+					return false
+				}
+			}
+		}
+		return true
+	}
+	require.NoError(t, quick.Check(property, propertyConfig(20)))
+}
+
+func TestShapeGeneratorCoversEveryKind(t *testing.T) {
+	r := rand.New(rand.NewSource(1))
+	seen := make(map[string]bool)
+	for i := 0; i < 50; i++ {
+		s := shape{}.Generate(r, 0).Interface().(shape)
+		seen[s.kind] = true
+		assert.Greater(t, s.cx-s.radius, 0.0)
+		assert.Greater(t, s.cy-s.radius, 0.0)
+	}
+	assert.Equal(t, map[string]bool{shapeCircle: true, shapePolygon: true, shapeStar: true}, seen)
+}
@@ -0,0 +1,103 @@
+package detector
+
+import (
+	"fmt"
+	"hash/fnv"
+	"math"
+	"sort"
+)
+
+// IDGrid is the size, in pixels, of the cells positions are rounded to
+// before they are hashed into symbol IDs, so that a symbol redrawn a few
+// pixels away usually keeps its ID even without a previous result
+const IDGrid = 16.0
+
+// AssignIDs gives every symbol an ID made from its type and its position
+// rounded to IDGrid, so that detecting the same image again yields the same
+// IDs. Symbols that would share an ID are told apart by a suffix, in
+// reading order.
+func AssignIDs(symbols []*Symbol) {
+	ReconcileIDs(symbols, nil, 0)
+}
+
+// ReconcileIDs assigns symbol IDs like AssignIDs, except that a symbol
+// matching one of previous keeps that symbol's ID. A symbol matches the
+// closest symbol of the same type within maxDistance pixels, closest pairs
+// first, as StreamDetector matches frames. This keeps IDs stable across
+// small edits that move a symbol into another grid cell.
+func ReconcileIDs(symbols, previous []*Symbol, maxDistance float64) {
+	var known []*Symbol
+	for _, p := range previous {
+		if p.ID != "" {
+			known = append(known, p)
+		}
+	}
+	matches := matchNearest(known, symbols, maxDistance)
+
+	taken := make(map[string]bool, len(symbols))
+	var fresh []*Symbol
+	for _, s := range symbols {
+		if p, ok := matches[s]; ok {
+			s.ID = p.ID
+			taken[s.ID] = true
+		} else {
+			fresh = append(fresh, s)
+		}
+	}
+
+	sort.SliceStable(fresh, func(i, j int) bool {
+		if fresh[i].Position.Y != fresh[j].Position.Y {
+			return fresh[i].Position.Y < fresh[j].Position.Y
+		}
+		return fresh[i].Position.X < fresh[j].Position.X
+	})
+	for _, s := range fresh {
+		id := positionID(s)
+		s.ID = id
+		for n := 2; taken[s.ID]; n++ {
+			s.ID = fmt.Sprintf("%s-%d", id, n)
+		}
+		taken[s.ID] = true
+	}
+}
+
+// positionID returns the ID of a symbol from its type and rounded position
+func positionID(s *Symbol) string {
+	h := fnv.New32a()
+	fmt.Fprintf(h, "%s:%d:%d", s.Type, int(math.Round(s.Position.X/IDGrid)), int(math.Round(s.Position.Y/IDGrid)))
+	return fmt.Sprintf("%s-%08x", s.Type, h.Sum32())
+}
+
+// matchNearest pairs symbols of current with symbols of the same type in
+// previous within maxDistance pixels, closest pairs first, and returns the
+// previous symbol each matched symbol of current was paired with
+func matchNearest(previous, current []*Symbol, maxDistance float64) map[*Symbol]*Symbol {
+	type pair struct {
+		previous, current *Symbol
+		distance          float64
+	}
+	var pairs []pair
+	for _, p := range previous {
+		for _, c := range current {
+			if p.Type != c.Type {
+				continue
+			}
+			d := math.Hypot(p.Position.X-c.Position.X, p.Position.Y-c.Position.Y)
+			if d <= maxDistance {
+				pairs = append(pairs, pair{previous: p, current: c, distance: d})
+			}
+		}
+	}
+	sort.SliceStable(pairs, func(i, j int) bool { return pairs[i].distance < pairs[j].distance })
+
+	matches := make(map[*Symbol]*Symbol)
+	claimed := make(map[*Symbol]bool)
+	for _, p := range pairs {
+		if _, ok := matches[p.current]; ok || claimed[p.previous] {
+			continue
+		}
+		matches[p.current] = p.previous
+		claimed[p.previous] = true
+	}
+	return matches
+}
@@ -0,0 +1,67 @@
+package detector
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestAssignIDs(t *testing.T) {
+	star := streamSymbol(Star, 100, 100)
+	square := streamSymbol(Square, 200, 100)
+	AssignIDs([]*Symbol{star, square})
+	assert.Regexp(t, `^star-[0-9a-f]{8}$`, star.ID)
+	assert.Regexp(t, `^square-[0-9a-f]{8}$`, square.ID)
+
+	// The same symbols detected again, in another order and a pixel away,
+	// get the same IDs
+	again := []*Symbol{streamSymbol(Square, 201, 100), streamSymbol(Star, 100, 99)}
+	AssignIDs(again)
+	assert.Equal(t, square.ID, again[0].ID)
+	assert.Equal(t, star.ID, again[1].ID)
+
+	// Symbols of the same type in one cell are numbered in reading order
+	lower, upper := streamSymbol(Star, 100, 101), streamSymbol(Star, 101, 100)
+	AssignIDs([]*Symbol{lower, upper})
+	assert.Equal(t, star.ID, upper.ID)
+	assert.Equal(t, star.ID+"-2", lower.ID)
+}
+
+func TestReconcileIDs(t *testing.T) {
+	star := streamSymbol(Star, 100, 100)
+	circle := streamSymbol(Circle, 300, 300)
+	previous := []*Symbol{star, circle}
+	AssignIDs(previous)
+
+	// A star moved across a grid cell keeps its ID; a new star next to
+	// where the circle was does not take the circle's ID
+	moved := streamSymbol(Star, 100+IDGrid, 100)
+	added := streamSymbol(Star, 300, 300)
+	ReconcileIDs([]*Symbol{added, moved}, previous, DefaultMatchDistance)
+	assert.Equal(t, star.ID, moved.ID)
+	assert.NotEqual(t, circle.ID, added.ID)
+	assert.NotEqual(t, moved.ID, added.ID)
+
+	// Without the previous result the moved star would be renamed
+	alone := streamSymbol(Star, 100+IDGrid, 100)
+	AssignIDs([]*Symbol{alone})
+	assert.NotEqual(t, star.ID, alone.ID)
+
+	// Beyond the match distance a symbol is new
+	far := streamSymbol(Star, 100, 100+2*DefaultMatchDistance)
+	ReconcileIDs([]*Symbol{far}, previous, DefaultMatchDistance)
+	assert.NotEqual(t, star.ID, far.ID)
+}
+
+func TestReconcileIDsAvoidsTakenIDs(t *testing.T) {
+	// A second star drawn next to the first falls in its cell; the closer
+	// one keeps the ID and the other is told apart from it
+	first := streamSymbol(Star, 100, 100)
+	AssignIDs([]*Symbol{first})
+
+	drifted := streamSymbol(Star, 103, 100)
+	drawn := streamSymbol(Star, 100, 101)
+	ReconcileIDs([]*Symbol{drawn, drifted}, []*Symbol{first}, DefaultMatchDistance)
+	assert.Equal(t, first.ID, drawn.ID, "the closest star keeps the ID")
+	assert.Equal(t, first.ID+"-2", drifted.ID)
+}
@@ -6,10 +6,10 @@ import (
 	"math"
 )
 
-// gaussianBlur applies a simple Gaussian blur to an image
-func gaussianBlur(img *image.Gray, kernelSize int) *image.Gray {
+// gaussianBlurInto applies a simple Gaussian blur to img, writing every
+// pixel of blurred, which must have the same bounds
+func gaussianBlurInto(blurred, img *image.Gray, kernelSize int) {
 	bounds := img.Bounds()
-	blurred := image.NewGray(bounds)
 
 	// Simple box blur as approximation of Gaussian
 	radius := kernelSize / 2
@@ -34,8 +34,6 @@ func gaussianBlur(img *image.Gray, kernelSize int) *image.Gray {
 			}
 		}
 	}
-
-	return blurred
 }
 
 // adaptiveThreshold applies adaptive thresholding to create a binary image
@@ -74,9 +72,38 @@ func adaptiveThreshold(gray *image.Gray, blockSize int, c int) *image.Gray {
 	return binary
 }
 
-// morphologyClose performs morphological closing (dilation followed by erosion)
-func morphologyClose(binary *image.Gray, kernelSize int) *image.Gray {
-	dilated := dilate(binary, kernelSize)
+// thumbnailSize is the largest width and height preprocessed as a thumbnail
+const thumbnailSize = 256
+
+// isThumbnail reports whether an image is small enough for the thumbnail path
+func isThumbnail(bounds image.Rectangle) bool {
+	return bounds.Dx() <= thumbnailSize && bounds.Dy() <= thumbnailSize
+}
+
+// globalThreshold creates a binary image in which pixels darker than
+// threshold are foreground
+func globalThreshold(gray *image.Gray, threshold uint8) *image.Gray {
+	bounds := gray.Bounds()
+	binary := image.NewGray(bounds)
+
+	for y := bounds.Min.Y; y < bounds.Max.Y; y++ {
+		for x := bounds.Min.X; x < bounds.Max.X; x++ {
+			if gray.GrayAt(x, y).Y < threshold {
+				binary.SetGray(x, y, color.Gray{255}) // White for foreground
+			}
+		}
+	}
+
+	return binary
+}
+
+// morphologyClose performs morphological closing (dilation followed by
+// erosion), taking the intermediate image from scratch
+func morphologyClose(binary *image.Gray, kernelSize int, scratch *grayPool) *image.Gray {
+	dilated := scratch.get(binary.Bounds())
+	defer scratch.put(dilated)
+
+	dilateInto(dilated, binary, kernelSize)
 	return erode(dilated, kernelSize)
 }
 
@@ -116,10 +143,10 @@ func erode(binary *image.Gray, kernelSize int) *image.Gray {
 	return result
 }
 
-// dilate performs morphological dilation
-func dilate(binary *image.Gray, kernelSize int) *image.Gray {
+// dilateInto performs morphological dilation, writing every pixel of result,
+// which must have the same bounds as binary
+func dilateInto(result, binary *image.Gray, kernelSize int) {
 	bounds := binary.Bounds()
-	result := image.NewGray(bounds)
 	radius := kernelSize / 2
 
 	for y := bounds.Min.Y; y < bounds.Max.Y; y++ {
@@ -148,8 +175,6 @@ func dilate(binary *image.Gray, kernelSize int) *image.Gray {
 			}
 		}
 	}
-
-	return result
 }
 
 // distance calculates the Euclidean distance between two points
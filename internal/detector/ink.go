@@ -0,0 +1,242 @@
+package detector
+
+import (
+	"context"
+	"fmt"
+	"image"
+	"image/color"
+	"math"
+	"math/rand"
+	"sort"
+)
+
+const (
+	// MaxInks is how many inks an image can be separated into at most
+	MaxInks = 8
+	// inkSamples is about how many pixels the ink colors are learned from
+	inkSamples = 20000
+	// inkIterations is how many rounds of k-means refine the ink colors
+	inkIterations = 20
+)
+
+// InkLayer is the part of an image drawn in one ink
+type InkLayer struct {
+	// Color is the mean color of the ink
+	Color color.RGBA
+	// Image has the pixels of the ink black and all others white, the size
+	// of the separated image
+	Image *image.Gray
+}
+
+// SeparateInks splits img into one layer per ink by clustering its colors
+// with k-means, learning inks+1 colors of which the lightest is taken to be
+// the paper. Layers are ordered from the darkest ink. The clustering is
+// seeded, so the same image always separates the same way.
+func SeparateInks(img image.Image, inks int) []InkLayer {
+	inks = max(1, min(inks, MaxInks))
+	bounds := img.Bounds()
+	pixels := make([][3]float64, 0, bounds.Dx()*bounds.Dy())
+	for y := bounds.Min.Y; y < bounds.Max.Y; y++ {
+		for x := bounds.Min.X; x < bounds.Max.X; x++ {
+			pixels = append(pixels, rgbOf(img.At(x, y)))
+		}
+	}
+	if len(pixels) == 0 {
+		return nil
+	}
+
+	centers := kMeans(pixels, inks+1, rand.New(rand.NewSource(1)))
+	sort.Slice(centers, func(i, j int) bool { return luminance(centers[i]) < luminance(centers[j]) })
+	inkCenters := centers[:len(centers)-1]
+
+	layers := make([]InkLayer, len(inkCenters))
+	for i, c := range inkCenters {
+		layer := image.NewGray(image.Rect(0, 0, bounds.Dx(), bounds.Dy()))
+		for j := range layer.Pix {
+			layer.Pix[j] = 0xff
+		}
+		layers[i] = InkLayer{
+			Color: color.RGBA{R: uint8(math.Round(c[0])), G: uint8(math.Round(c[1])), B: uint8(math.Round(c[2])), A: 0xff},
+			Image: layer,
+		}
+	}
+	for i, p := range pixels {
+		if nearest := nearestCenter(centers, p); nearest < len(inkCenters) {
+			x, y := i%bounds.Dx(), i/bounds.Dx()
+			layers[nearest].Image.Pix[y*layers[nearest].Image.Stride+x] = 0
+		}
+	}
+	return layers
+}
+
+// kMeans returns k centers of pixels, starting from k-means++ seeds on a
+// sample and refining them on it
+func kMeans(pixels [][3]float64, k int, rng *rand.Rand) [][3]float64 {
+	sample := pixels
+	if step := len(pixels) / inkSamples; step > 1 {
+		sample = make([][3]float64, 0, inkSamples+1)
+		for i := 0; i < len(pixels); i += step {
+			sample = append(sample, pixels[i])
+		}
+	}
+
+	// k-means++: each further seed is picked with probability growing with
+	// its squared distance from the nearest seed so far
+	centers := [][3]float64{sample[rng.Intn(len(sample))]}
+	distances := make([]float64, len(sample))
+	for len(centers) < k {
+		total := 0.0
+		for i, p := range sample {
+			distances[i] = colorDistance(p, centers[nearestCenter(centers, p)])
+			total += distances[i]
+		}
+		if total == 0 {
+			// Fewer distinct colors than clusters
+			break
+		}
+		target := rng.Float64() * total
+		chosen := len(sample) - 1
+		for i, d := range distances {
+			if target -= d; target <= 0 {
+				chosen = i
+				break
+			}
+		}
+		centers = append(centers, sample[chosen])
+	}
+
+	for iteration := 0; iteration < inkIterations; iteration++ {
+		sums := make([][3]float64, len(centers))
+		counts := make([]int, len(centers))
+		for _, p := range sample {
+			c := nearestCenter(centers, p)
+			for ch := range p {
+				sums[c][ch] += p[ch]
+			}
+			counts[c]++
+		}
+		moved := false
+		for c := range centers {
+			if counts[c] == 0 {
+				continue
+			}
+			mean := [3]float64{sums[c][0] / float64(counts[c]), sums[c][1] / float64(counts[c]), sums[c][2] / float64(counts[c])}
+			if colorDistance(mean, centers[c]) > 0.25 {
+				moved = true
+			}
+			centers[c] = mean
+		}
+		if !moved {
+			break
+		}
+	}
+	return centers
+}
+
+// nearestCenter returns the index of the center closest to p
+func nearestCenter(centers [][3]float64, p [3]float64) int {
+	nearest, best := 0, math.Inf(1)
+	for i, c := range centers {
+		if d := colorDistance(p, c); d < best {
+			nearest, best = i, d
+		}
+	}
+	return nearest
+}
+
+// colorDistance returns the squared distance between two colors
+func colorDistance(a, b [3]float64) float64 {
+	dr, dg, db := a[0]-b[0], a[1]-b[1], a[2]-b[2]
+	return dr*dr + dg*dg + db*db
+}
+
+// luminance returns how light a color looks
+func luminance(c [3]float64) float64 {
+	return 0.299*c[0] + 0.587*c[1] + 0.114*c[2]
+}
+
+// rgbOf returns the 8-bit channels of c
+func rgbOf(c color.Color) [3]float64 {
+	r, g, b, _ := c.RGBA()
+	return [3]float64{float64(r >> 8), float64(g >> 8), float64(b >> 8)}
+}
+
+// layerDetector is implemented by the in-process backends, which can detect
+// a layer that does not hold a whole program by itself
+type layerDetector interface {
+	detectLayer(img image.Image) ([]*Symbol, []Connection, error)
+}
+
+func (d *Detector) detectLayer(img image.Image) ([]*Symbol, []Connection, error) {
+	return d.run(context.Background(), d.sequentialPipeline(), img)
+}
+
+func (pd *ParallelDetectorV2) detectLayer(img image.Image) ([]*Symbol, []Connection, error) {
+	return pd.run(context.Background(), pd.parallelPipeline(), img)
+}
+
+// DetectInks separates img into inks layers, detects each with backend and
+// merges the results, so that strokes of different colors are read apart
+// even where they overlap. Each symbol records its ink as "#rrggbb" in the
+// ink property and the index of its layer in the layer property. Only the
+// largest outer circle is kept, and connections join symbols of the same
+// ink. source names the image in errors.
+func DetectInks(backend Backend, img image.Image, inks int, source string) ([]*Symbol, []Connection, error) {
+	var symbols []*Symbol
+	var connections []Connection
+	var outer *Symbol
+	ld, inProcess := backend.(layerDetector)
+	for i, layer := range SeparateInks(img, inks) {
+		detect := backend.DetectImage
+		if inProcess {
+			detect = ld.detectLayer
+		}
+		layerSymbols, layerConnections, err := detect(layer.Image)
+		if err != nil && inProcess {
+			return nil, nil, err
+		}
+		if err != nil {
+			// Other backends only accept layers that hold a whole program
+			continue
+		}
+
+		ink := fmt.Sprintf("#%02x%02x%02x", layer.Color.R, layer.Color.G, layer.Color.B)
+		for _, s := range layerSymbols {
+			if s.Properties == nil {
+				s.Properties = make(map[string]interface{})
+			}
+			s.Properties["ink"] = ink
+			s.Properties["layer"] = i
+			if s.Type == OuterCircle {
+				if outer == nil || s.Size > outer.Size {
+					outer = s
+				}
+				continue
+			}
+			symbols = append(symbols, s)
+		}
+		connections = append(connections, layerConnections...)
+	}
+	if outer != nil {
+		symbols = append([]*Symbol{outer}, symbols...)
+	}
+
+	// Drop connections to the outer circles that were not kept
+	kept := make(map[*Symbol]bool, len(symbols))
+	for _, s := range symbols {
+		kept[s] = true
+	}
+	joined := connections[:0]
+	for _, conn := range connections {
+		if kept[conn.From] && kept[conn.To] {
+			joined = append(joined, conn)
+		}
+	}
+
+	if err := validateSymbols(symbols, source); err != nil {
+		return nil, nil, err
+	}
+	// IDs were assigned per layer, so symbols of different inks may share one
+	AssignIDs(symbols)
+	return symbols, joined, nil
+}
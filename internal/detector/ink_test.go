@@ -0,0 +1,84 @@
+package detector
+
+import (
+	"image"
+	"image/color"
+	"image/draw"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestSeparateInks(t *testing.T) {
+	black := color.RGBA{A: 0xff}
+	red := color.RGBA{R: 220, G: 30, B: 30, A: 0xff}
+	blue := color.RGBA{R: 30, G: 30, B: 220, A: 0xff}
+
+	img := image.NewRGBA(image.Rect(0, 0, 200, 200))
+	draw.Draw(img, img.Bounds(), image.NewUniform(color.White), image.Point{}, draw.Src)
+	draw.Draw(img, image.Rect(20, 20, 80, 80), image.NewUniform(black), image.Point{}, draw.Src)
+	draw.Draw(img, image.Rect(60, 60, 140, 140), image.NewUniform(red), image.Point{}, draw.Src)
+	// Blue drawn over the red, where the strokes overlap
+	draw.Draw(img, image.Rect(120, 120, 180, 180), image.NewUniform(blue), image.Point{}, draw.Src)
+
+	layers := SeparateInks(img, 3)
+	require.Len(t, layers, 3)
+
+	// From the darkest ink
+	assert.Equal(t, black, layers[0].Color)
+	assert.Equal(t, blue, layers[1].Color)
+	assert.Equal(t, red, layers[2].Color)
+
+	inkAt := func(x, y int) []bool {
+		var in []bool
+		for _, layer := range layers {
+			in = append(in, layer.Image.GrayAt(x, y).Y == 0)
+		}
+		return in
+	}
+	assert.Equal(t, []bool{true, false, false}, inkAt(30, 30))
+	assert.Equal(t, []bool{false, false, true}, inkAt(100, 100))
+	assert.Equal(t, []bool{false, true, false}, inkAt(130, 130))
+	assert.Equal(t, []bool{false, false, false}, inkAt(190, 10))
+}
+
+func TestSeparateInksIsRepeatable(t *testing.T) {
+	img := image.NewRGBA(image.Rect(0, 0, 64, 64))
+	for i := range img.Pix {
+		img.Pix[i] = uint8(i * 7)
+	}
+	first := SeparateInks(img, 4)
+	second := SeparateInks(img, 4)
+	require.Len(t, second, len(first))
+	for i := range first {
+		assert.Equal(t, first[i].Color, second[i].Color)
+		assert.Equal(t, first[i].Image.Pix, second[i].Image.Pix)
+	}
+}
+
+func TestDetectInks(t *testing.T) {
+	path := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(path); err != nil {
+		t.Skip("Example image not found")
+	}
+	d := NewDetector(Config{})
+	img, err := d.loadAndValidateImage(path)
+	require.NoError(t, err)
+
+	symbols, _, err := DetectInks(d, img, 1, path)
+	require.NoError(t, err)
+	require.NotEmpty(t, symbols)
+	assert.Equal(t, OuterCircle, symbols[0].Type)
+	for _, s := range symbols {
+		assert.Equal(t, 0, s.Properties["layer"])
+		assert.Regexp(t, `^#[0-9a-f]{6}$`, s.Properties["ink"])
+	}
+}
+
+func TestDetectInksWithoutProgram(t *testing.T) {
+	_, _, err := DetectInks(NewDetector(Config{}), whiteImage(), 2, "blank.png")
+	assert.Error(t, err)
+}
@@ -0,0 +1,76 @@
+package detector
+
+import (
+	"context"
+	"image"
+)
+
+// SymbolOrder selects when DetectEach yields symbols
+type SymbolOrder int
+
+const (
+	// OrderFinal yields the symbols after detection, in the order Detect
+	// returns them
+	OrderFinal SymbolOrder = iota
+	// OrderStreaming yields each symbol as soon as it is classified. A star
+	// yielded this way may later be merged into a nearby duplicate, so the
+	// final results can contain fewer symbols.
+	OrderStreaming
+)
+
+// DetectEach detects the symbols in img and calls yield with each one, so
+// that a UI can show results before detection of a large image finishes.
+// Returning false from yield stops detection. Results are not validated,
+// so an image without symbols yields nothing and is not an error. Past the
+// detector's timeout, the symbols found so far are yielded and a
+// PartialDetection error is returned.
+func (d *Detector) DetectEach(ctx context.Context, img image.Image, order SymbolOrder, yield func(*Symbol) bool) error {
+	ctx, cancel := context.WithCancel(ctx)
+	defer cancel()
+
+	p := d.sequentialPipeline()
+	stopped := false
+	if order == OrderStreaming {
+		working := unscaled
+		p.scaled = func(s scaling) { working = s }
+		p.detectSymbols = func(ctx context.Context, contours []Contour, binary *image.Gray) []*Symbol {
+			// The outer circle is classified first, so glyphs on it can be
+			// marked before they are yielded
+			var outerCircle *Symbol
+			return d.classifyContours(ctx, contours, binary, func(symbol *Symbol) bool {
+				if symbol.Type == OuterCircle {
+					outerCircle = symbol
+				}
+				symbol.OnRing = onRing(outerCircle, symbol)
+				if !d.confident(symbol) {
+					return true
+				}
+				restored := working.restored(symbol)
+				if !yield(&restored) {
+					stopped = true
+					cancel()
+				}
+				return !stopped
+			})
+		}
+	}
+
+	symbols, _, err := d.run(ctx, p, img)
+	if stopped {
+		// The remaining stages were cancelled on the caller's behalf
+		return nil
+	}
+	if err != nil && !IsPartial(err) {
+		return err
+	}
+
+	// Symbols found before the timeout are yielded before it is reported
+	if order == OrderFinal {
+		for _, symbol := range symbols {
+			if !yield(symbol) {
+				break
+			}
+		}
+	}
+	return err
+}
@@ -0,0 +1,55 @@
+package detector
+
+import (
+	"context"
+	"testing"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestDetectEach(t *testing.T) {
+	img := createTestImageWithCircle(200, 200, 90)
+	d := NewDetector(Config{})
+	expected, _, err := d.run(context.Background(), d.sequentialPipeline(), img)
+	require.NoError(t, err)
+	require.NotEmpty(t, expected)
+
+	for _, order := range []SymbolOrder{OrderFinal, OrderStreaming} {
+		var symbols []*Symbol
+		require.NoError(t, d.DetectEach(context.Background(), img, order, func(s *Symbol) bool {
+			symbols = append(symbols, s)
+			return true
+		}))
+		// No stars to merge, so streaming yields the same symbols
+		assert.Equal(t, expected, symbols, "order %d", order)
+	}
+}
+
+func TestDetectEachStopsEarly(t *testing.T) {
+	img := createTestImageWithCircle(200, 200, 90)
+	d := NewDetector(Config{})
+
+	for _, order := range []SymbolOrder{OrderFinal, OrderStreaming} {
+		calls := 0
+		require.NoError(t, d.DetectEach(context.Background(), img, order, func(*Symbol) bool {
+			calls++
+			return false
+		}))
+		assert.Equal(t, 1, calls, "order %d", order)
+	}
+}
+
+func TestDetectEachCancelled(t *testing.T) {
+	ctx, cancel := context.WithCancel(context.Background())
+	cancel()
+
+	err := NewDetector(Config{}).DetectEach(ctx, createTestImage(50, 50), OrderStreaming, func(*Symbol) bool {
+		t.Error("yield called after cancellation")
+		return true
+	})
+	var gErr *grimoireErrors.GrimoireError
+	require.ErrorAs(t, err, &gErr)
+	assert.Equal(t, grimoireErrors.Cancelled, gErr.Type)
+}
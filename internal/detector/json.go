@@ -0,0 +1,93 @@
+package detector
+
+import (
+	"encoding/json"
+	"fmt"
+)
+
+// JSONSchemaVersion is the version of the JSON written by EncodeJSON. It is
+// increased whenever the encoding changes incompatibly; adding fields does
+// not change it.
+const JSONSchemaVersion = 1
+
+// connectionJSON is a connection whose endpoints are indexes into the
+// symbol list, so decoding restores shared symbol pointers
+type connectionJSON struct {
+	From           int                    `json:"from"`
+	To             int                    `json:"to"`
+	ConnectionType string                 `json:"connectionType"`
+	Properties     map[string]interface{} `json:"properties,omitempty"`
+	FromAnchor     *Anchor                `json:"fromAnchor,omitempty"`
+	ToAnchor       *Anchor                `json:"toAnchor,omitempty"`
+}
+
+// resultsJSON is the encoded form of detection results
+type resultsJSON struct {
+	SchemaVersion int              `json:"schemaVersion"`
+	Symbols       []*Symbol        `json:"symbols"`
+	Connections   []connectionJSON `json:"connections"`
+}
+
+// EncodeJSON encodes detection results. Every connection must join two of
+// the given symbols.
+func EncodeJSON(symbols []*Symbol, connections []Connection) ([]byte, error) {
+	index := make(map[*Symbol]int, len(symbols))
+	for i, s := range symbols {
+		index[s] = i
+	}
+
+	encoded := resultsJSON{
+		SchemaVersion: JSONSchemaVersion,
+		Symbols:       symbols,
+		Connections:   make([]connectionJSON, 0, len(connections)),
+	}
+	if encoded.Symbols == nil {
+		encoded.Symbols = []*Symbol{}
+	}
+	for i, conn := range connections {
+		from, okFrom := index[conn.From]
+		to, okTo := index[conn.To]
+		if !okFrom || !okTo {
+			return nil, fmt.Errorf("connection %d joins a symbol that is not in the symbol list", i)
+		}
+		encoded.Connections = append(encoded.Connections, connectionJSON{
+			From:           from,
+			To:             to,
+			ConnectionType: conn.ConnectionType,
+			Properties:     conn.Properties,
+			FromAnchor:     conn.FromAnchor,
+			ToAnchor:       conn.ToAnchor,
+		})
+	}
+	return json.Marshal(encoded)
+}
+
+// DecodeJSON decodes detection results encoded by EncodeJSON, including
+// those written before the schema was versioned. Newer schema versions are
+// rejected.
+func DecodeJSON(data []byte) ([]*Symbol, []Connection, error) {
+	var decoded resultsJSON
+	if err := json.Unmarshal(data, &decoded); err != nil {
+		return nil, nil, err
+	}
+	if decoded.SchemaVersion > JSONSchemaVersion {
+		return nil, nil, fmt.Errorf("unsupported detection schema version %d (supported: %d)",
+			decoded.SchemaVersion, JSONSchemaVersion)
+	}
+
+	connections := make([]Connection, 0, len(decoded.Connections))
+	for i, conn := range decoded.Connections {
+		if conn.From < 0 || conn.From >= len(decoded.Symbols) || conn.To < 0 || conn.To >= len(decoded.Symbols) {
+			return nil, nil, fmt.Errorf("connection %d refers to a missing symbol", i)
+		}
+		connections = append(connections, Connection{
+			From:           decoded.Symbols[conn.From],
+			To:             decoded.Symbols[conn.To],
+			ConnectionType: conn.ConnectionType,
+			Properties:     conn.Properties,
+			FromAnchor:     conn.FromAnchor,
+			ToAnchor:       conn.ToAnchor,
+		})
+	}
+	return decoded.Symbols, connections, nil
+}
@@ -0,0 +1,77 @@
+package detector
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestEncodeJSONRoundTrip(t *testing.T) {
+	outer := &Symbol{Type: OuterCircle, Position: Position{X: 200, Y: 200}, Size: 180, Confidence: 0.95, Pattern: PatternEmpty}
+	star := &Symbol{Type: Star, Position: Position{X: 200, Y: 250}, Size: 20, Confidence: 0.8, Pattern: PatternDot,
+		Properties: map[string]interface{}{"points": 5.0}}
+	symbols := []*Symbol{outer, star}
+	connections := []Connection{{From: outer, To: star, ConnectionType: "solid"}}
+
+	data, err := EncodeJSON(symbols, connections)
+	require.NoError(t, err)
+	assert.Contains(t, string(data), `"type":"outer_circle"`)
+	assert.Contains(t, string(data), `"from":0,"to":1`)
+
+	decodedSymbols, decodedConnections, err := DecodeJSON(data)
+	require.NoError(t, err)
+	assert.Equal(t, symbols, decodedSymbols)
+	assert.Equal(t, connections, decodedConnections)
+
+	// Connections point at the decoded symbols rather than copies
+	assert.Same(t, decodedSymbols[0], decodedConnections[0].From)
+	assert.Same(t, decodedSymbols[1], decodedConnections[0].To)
+}
+
+func TestEncodeJSONErrors(t *testing.T) {
+	stray := &Symbol{Type: Circle}
+	_, err := EncodeJSON(nil, []Connection{{From: stray, To: stray}})
+	assert.Error(t, err)
+
+	_, _, err = DecodeJSON([]byte(`{"symbols": [], "connections": [{"from": 0, "to": 1}]}`))
+	assert.Error(t, err)
+}
+
+// snapshotSymbols are the detection results stored in testdata/detection.v1.json
+func snapshotSymbols() ([]*Symbol, []Connection) {
+	outer := &Symbol{Type: OuterCircle, Position: Position{X: 200, Y: 200}, Size: 180, Confidence: 0.95, Pattern: PatternEmpty}
+	star := &Symbol{Type: Star, Position: Position{X: 200, Y: 250}, Size: 20, Confidence: 0.8, Pattern: PatternDot,
+		Properties: map[string]interface{}{"points": 5.0}}
+	return []*Symbol{outer, star}, []Connection{{From: outer, To: star, ConnectionType: "solid"}}
+}
+
+// TestJSONSchemaSnapshot guards the version 1 schema. If it fails, either
+// restore compatibility or increase JSONSchemaVersion and add a new snapshot,
+// keeping the old one decodable.
+func TestJSONSchemaSnapshot(t *testing.T) {
+	snapshot, err := os.ReadFile(filepath.Join("testdata", "detection.v1.json"))
+	require.NoError(t, err)
+
+	symbols, connections := snapshotSymbols()
+	data, err := EncodeJSON(symbols, connections)
+	require.NoError(t, err)
+	assert.JSONEq(t, string(snapshot), string(data))
+
+	decodedSymbols, decodedConnections, err := DecodeJSON(snapshot)
+	require.NoError(t, err)
+	assert.Equal(t, symbols, decodedSymbols)
+	assert.Equal(t, connections, decodedConnections)
+}
+
+func TestDecodeJSONSchemaVersions(t *testing.T) {
+	// Written before the schema was versioned
+	symbols, _, err := DecodeJSON([]byte(`{"symbols": [{"type": "star"}], "connections": []}`))
+	require.NoError(t, err)
+	assert.Len(t, symbols, 1)
+
+	_, _, err = DecodeJSON([]byte(`{"schemaVersion": 2, "symbols": [], "connections": []}`))
+	assert.ErrorContains(t, err, "schema version 2")
+}
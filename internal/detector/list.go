@@ -0,0 +1,60 @@
+package detector
+
+import (
+	"image"
+	"math"
+)
+
+// Lists are drawn as a wide rectangle around a row of symbols. The frame is
+// kept as a square marked ListFrame, with its extent, so that the parser can
+// find the symbols inside it.
+const (
+	// minListAspect is the smallest ratio of width to height of a list frame
+	minListAspect = 2.5
+	// minListFill is how much of its bounding box the outline of a list
+	// frame encloses, telling a rectangle from other wide shapes
+	minListFill = 0.85
+	// maxListInk is how much of the inside of a list frame may be inked,
+	// telling a frame from a solid bar
+	maxListInk = 0.5
+)
+
+// listFrame returns the list frame drawn by contour, or nil when contour is
+// not a wide, hollow rectangle
+func (d *Detector) listFrame(contour Contour, binary *image.Gray) *Symbol {
+	bbox := contour.getBoundingBox()
+	width, height := float64(bbox.Dx()), float64(bbox.Dy())
+	if height == 0 || width/height < minListAspect || contour.Area < width*height*minListFill {
+		return nil
+	}
+
+	// Look inside the frame, clear of its outline
+	inset := bbox.Dy() / 4
+	inside := bbox.Inset(inset).Intersect(binary.Bounds())
+	if inside.Empty() {
+		return nil
+	}
+	ink := 0
+	for y := inside.Min.Y; y < inside.Max.Y; y++ {
+		for x := inside.Min.X; x < inside.Max.X; x++ {
+			if binary.GrayAt(x, y).Y == 255 {
+				ink++
+			}
+		}
+	}
+	if float64(ink) > float64(inside.Dx()*inside.Dy())*maxListInk {
+		return nil
+	}
+
+	return &Symbol{
+		Type:       Square,
+		Position:   Position{X: float64(bbox.Min.X) + width/2, Y: float64(bbox.Min.Y) + height/2},
+		Size:       math.Sqrt(contour.Area),
+		Confidence: d.confidence(Square, contour),
+		Pattern:    PatternEmpty,
+		Properties: make(map[string]interface{}),
+		ListFrame:  true,
+		Width:      width,
+		Height:     height,
+	}
+}
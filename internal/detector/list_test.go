@@ -0,0 +1,55 @@
+package detector
+
+import (
+	"image"
+	"image/color"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// inkRect inks r on binary, only its outline when hollow
+func inkRect(binary *image.Gray, r image.Rectangle, hollow bool) {
+	for y := r.Min.Y; y < r.Max.Y; y++ {
+		for x := r.Min.X; x < r.Max.X; x++ {
+			edge := x < r.Min.X+2 || x >= r.Max.X-2 || y < r.Min.Y+2 || y >= r.Max.Y-2
+			if edge || !hollow {
+				binary.SetGray(x, y, color.Gray{Y: 255})
+			}
+		}
+	}
+}
+
+// rectContour returns the outline of r as a contour
+func rectContour(r image.Rectangle) Contour {
+	return outlineContour([]image.Point{
+		r.Min, {X: r.Max.X - 1, Y: r.Min.Y}, r.Max.Sub(image.Point{X: 1, Y: 1}), {X: r.Min.X, Y: r.Max.Y - 1},
+	})
+}
+
+func TestListFrame(t *testing.T) {
+	d := NewDetector(Config{})
+	binary := image.NewGray(image.Rect(0, 0, 400, 300))
+
+	frame := image.Rect(50, 50, 250, 100)
+	inkRect(binary, frame, true)
+	inkRect(binary, image.Rect(70, 65, 90, 85), false) // an element inside
+	list := d.listFrame(rectContour(frame), binary)
+	require.NotNil(t, list)
+	assert.Equal(t, Square, list.Type)
+	assert.True(t, list.ListFrame)
+	assert.Equal(t, Position{X: 150, Y: 75}, list.Position)
+	assert.Equal(t, 200.0, list.Width)
+	assert.Equal(t, 50.0, list.Height)
+
+	bar := image.Rect(50, 150, 250, 170)
+	inkRect(binary, bar, false)
+	assert.Nil(t, d.listFrame(rectContour(bar), binary), "a solid bar")
+
+	square := image.Rect(300, 50, 350, 100)
+	inkRect(binary, square, true)
+	assert.Nil(t, d.listFrame(rectContour(square), binary), "a square")
+
+	assert.Nil(t, d.listFrame(outlineContour(createCircleContour(150, 230, 30).Points), binary))
+}
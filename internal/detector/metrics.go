@@ -0,0 +1,95 @@
+package detector
+
+import (
+	"runtime"
+	"time"
+)
+
+// MetricsFunc receives the metrics of each detection that finishes
+type MetricsFunc func(m Metrics)
+
+// Metrics describe how one detection went
+type Metrics struct {
+	// Stages is the time spent in each stage, in the order they ran
+	Stages []StageTiming
+	// Contours is how many contours were found, the outer circle included
+	Contours int
+	// Candidates is how many symbols were recognized from the contours
+	Candidates int
+	// Rejected is how many candidates were dropped as unconfident or as
+	// duplicates
+	Rejected int
+	// Accepted is how many symbols detection returned
+	Accepted int
+	// Connections is how many connections were found between them
+	Connections int
+	// PeakHeapBytes is the most heap memory in use at any stage boundary
+	PeakHeapBytes uint64
+}
+
+// StageTiming is the time spent in one stage
+type StageTiming struct {
+	Stage    Stage
+	Duration time.Duration
+}
+
+// Total returns the time spent in all stages
+func (m Metrics) Total() time.Duration {
+	var total time.Duration
+	for _, s := range m.Stages {
+		total += s.Duration
+	}
+	return total
+}
+
+// recorder collects the metrics of one run. A nil recorder records
+// nothing, so that detection without a MetricsFunc pays nothing for it.
+type recorder struct {
+	metrics Metrics
+	stage   Stage
+	started time.Time
+}
+
+// newRecorder returns a recorder when the detector reports metrics
+func (d *Detector) newRecorder() *recorder {
+	if d.metrics == nil {
+		return nil
+	}
+	return &recorder{}
+}
+
+// enter ends the timing of the current stage and starts that of stage
+func (r *recorder) enter(stage Stage) {
+	if r == nil {
+		return
+	}
+	now := time.Now()
+	if r.stage != "" {
+		r.metrics.Stages = append(r.metrics.Stages, StageTiming{Stage: r.stage, Duration: now.Sub(r.started)})
+	}
+	r.stage, r.started = stage, now
+
+	var mem runtime.MemStats
+	runtime.ReadMemStats(&mem)
+	r.metrics.PeakHeapBytes = max(r.metrics.PeakHeapBytes, mem.HeapAlloc)
+}
+
+// count records how many contours, candidates, symbols and connections a
+// run found
+func (r *recorder) count(contours, candidates, accepted, connections int) {
+	if r == nil {
+		return
+	}
+	r.metrics.Contours = contours
+	r.metrics.Candidates = candidates
+	r.metrics.Rejected = candidates - accepted
+	r.metrics.Accepted = accepted
+	r.metrics.Connections = connections
+}
+
+// report passes the metrics to the detector's MetricsFunc
+func (d *Detector) report(r *recorder) {
+	if r != nil {
+		d.metrics(r.metrics)
+	}
+}
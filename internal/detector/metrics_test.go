@@ -0,0 +1,55 @@
+package detector
+
+import (
+	"context"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestMetricsTimeEachStage(t *testing.T) {
+	var reports []Metrics
+	d := NewDetector(Config{Metrics: func(m Metrics) { reports = append(reports, m) }})
+
+	_, _, err := d.run(context.Background(), d.sequentialPipeline(), whiteImage())
+	require.NoError(t, err)
+	require.Len(t, reports, 1)
+
+	m := reports[0]
+	var stages []Stage
+	for _, s := range m.Stages {
+		stages = append(stages, s.Stage)
+		assert.GreaterOrEqual(t, s.Duration.Nanoseconds(), int64(0))
+	}
+	assert.Equal(t, []Stage{StagePreprocess, StageOuterCircle, StageContours, StageSymbols, StageConnections}, stages)
+	assert.Equal(t, m.Stages[0].Duration+m.Stages[1].Duration+m.Stages[2].Duration+m.Stages[3].Duration+m.Stages[4].Duration, m.Total())
+	assert.Positive(t, m.PeakHeapBytes)
+	assert.Zero(t, m.Accepted)
+}
+
+func TestMetricsCountSymbols(t *testing.T) {
+	path := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	for _, name := range Backends() {
+		t.Run(name, func(t *testing.T) {
+			var m Metrics
+			backend, err := NewBackend(name, Config{Metrics: func(got Metrics) { m = got }})
+			require.NoError(t, err)
+
+			symbols, connections, err := backend.Detect(path)
+			if err != nil {
+				t.Skip("Example image not found")
+			}
+			assert.Equal(t, len(symbols), m.Accepted)
+			assert.Equal(t, len(connections), m.Connections)
+			assert.Equal(t, m.Candidates-m.Accepted, m.Rejected)
+			assert.Positive(t, m.Contours)
+		})
+	}
+}
+
+func TestNoMetricsWithoutFunc(t *testing.T) {
+	d := NewDetector(Config{})
+	assert.Nil(t, d.newRecorder())
+}
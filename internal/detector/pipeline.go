@@ -0,0 +1,181 @@
+package detector
+
+import (
+	"context"
+	"fmt"
+	"image"
+	"os"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+)
+
+// pipeline holds the stages of detection. Every detector runs the same
+// sequence through Detector.run and differs only in how it implements the
+// stages, so fixes to the sequence itself land once. findContours,
+// detectSymbols and detectConnections stop once their ctx is done,
+// returning what they found so far.
+type pipeline struct {
+	preprocess        func(gray *image.Gray) *image.Gray
+	findContours      func(ctx context.Context, binary *image.Gray) []Contour
+	detectSymbols     func(ctx context.Context, contours []Contour, binary *image.Gray) []*Symbol
+	detectConnections func(ctx context.Context, binary *image.Gray, symbols []*Symbol) []Connection
+
+	// scaled, if set, is called with the working scale before preprocessing,
+	// for stages that report symbols before run maps them back
+	scaled func(s scaling)
+}
+
+// sequentialPipeline returns the single-threaded stages of the base detector
+func (d *Detector) sequentialPipeline() pipeline {
+	return pipeline{
+		preprocess:        d.preprocessImage,
+		findContours:      d.findContoursContext,
+		detectSymbols:     d.detectSymbolsFromContours,
+		detectConnections: d.improvedDetectConnections,
+	}
+}
+
+// run detects the symbols in img and the connections between them. Results
+// are not validated; callers decide whether an empty result is an error.
+// ctx is checked between stages, so a cancelled detection stops at the next one.
+// Past the detector's timeout, detection instead skips the stages left and
+// returns what it found with a PartialDetection error.
+func (d *Detector) run(ctx context.Context, p pipeline, img image.Image) ([]*Symbol, []Connection, error) {
+	rec := d.newRecorder()
+	enter := func(stage Stage) error {
+		rec.enter(stage)
+		return d.enterStage(ctx, stage)
+	}
+	deadline := ctx
+	if d.timeout > 0 {
+		var cancel context.CancelFunc
+		deadline, cancel = context.WithTimeout(ctx, d.timeout)
+		defer cancel()
+	}
+	expired := func() bool { return ctx.Err() == nil && deadline.Err() != nil }
+
+	if err := enter(StagePreprocess); err != nil {
+		return nil, nil, err
+	}
+
+	// Convert to grayscale and scale down to the working size
+	gray := d.toGrayscale(img)
+	working := d.workingScale(gray)
+	gray = working.apply(gray)
+	if p.scaled != nil {
+		p.scaled(working)
+	}
+
+	// Preprocess image
+	binary := p.preprocess(gray)
+
+	if err := enter(StageOuterCircle); err != nil {
+		return nil, nil, err
+	}
+
+	// Try to find outer circle in original grayscale image
+	outerCircle := d.findOuterCircleFromGrayscale(gray)
+
+	if err := enter(StageContours); err != nil {
+		return nil, nil, err
+	}
+	if expired() {
+		return nil, nil, grimoireErrors.PartialDetectionError(d.timeout, 0)
+	}
+
+	// Find contours, as many as the deadline leaves time for
+	contours := p.findContours(deadline, binary)
+	if expired() {
+		return nil, nil, grimoireErrors.PartialDetectionError(d.timeout, 0)
+	}
+
+	// Add outer circle if found
+	if outerCircle != nil {
+		contours = append([]Contour{*outerCircle}, contours...)
+	}
+	if err := d.checkComplexity(contours); err != nil {
+		return nil, nil, err
+	}
+
+	if os.Getenv("GRIMOIRE_DEBUG") != "" {
+		d.debugContours(binary, contours)
+	}
+
+	if err := enter(StageSymbols); err != nil {
+		return nil, nil, err
+	}
+
+	// Detect symbols from contours, as many as the deadline leaves time for
+	symbols := p.detectSymbols(deadline, contours, binary)
+	candidates := len(symbols)
+	symbols = d.dropUnconfident(symbols)
+
+	// Deduplicate nearby stars
+	symbols = d.deduplicateNearbyStars(symbols)
+	markRingGlyphs(symbols)
+
+	if err := enter(StageConnections); err != nil {
+		return nil, nil, err
+	}
+
+	if expired() {
+		// The deadline passed while classifying symbols, so connections are
+		// left out
+		working.restore(symbols)
+		AssignIDs(symbols)
+		return symbols, nil, grimoireErrors.PartialDetectionError(d.timeout, len(symbols))
+	}
+
+	// Detect connections between the symbols of the program
+	connections := p.detectConnections(deadline, binary, interiorSymbols(symbols))
+
+	// Connections hold the symbols, so mapping them back moves both
+	working.restore(symbols)
+	AssignIDs(symbols)
+
+	if expired() {
+		// The deadline passed while tracing connections, so the ones found
+		// are incomplete and left out
+		return symbols, nil, grimoireErrors.PartialDetectionError(d.timeout, len(symbols))
+	}
+
+	if err := enter(StageDone); err != nil {
+		return nil, nil, err
+	}
+	rec.count(len(contours), candidates, len(symbols), len(connections))
+	d.report(rec)
+
+	return symbols, connections, nil
+}
+
+// debugContours prints contour information and saves the preprocessed image
+func (d *Detector) debugContours(binary *image.Gray, contours []Contour) {
+	d.DebugPrintContours(contours)
+	// Save preprocessed image for debugging
+	if err := d.DebugSaveContours(binary, contours, "debug_binary.png"); err != nil {
+		fmt.Printf("Failed to save debug contours: %v\n", err)
+	}
+	// Print detailed info for specific contours
+	fmt.Println("\nDebug: Checking contours in square regions:")
+	for i, contour := range contours {
+		// Check both square regions
+		if (contour.Center.X > 350 && contour.Center.X < 400 &&
+			contour.Center.Y > 170 && contour.Center.Y < 220) ||
+			(contour.Center.X > 200 && contour.Center.X < 250 &&
+				contour.Center.Y > 170 && contour.Center.Y < 220) {
+			bbox := contour.getBoundingBox()
+			// approximatePolygon is in shape_classifier.go
+			vertices := len(contour.Points)
+			fmt.Printf("[%d] Square region candidate:\n", i)
+			fmt.Printf("  Center: (%d,%d)\n", contour.Center.X, contour.Center.Y)
+			fmt.Printf("  BBox: (%d,%d,%d,%d), w=%d, h=%d\n",
+				bbox.Min.X, bbox.Min.Y, bbox.Max.X, bbox.Max.Y,
+				bbox.Dx(), bbox.Dy())
+			fmt.Printf("  Area: %.1f, Perimeter: %.1f\n", contour.Area, contour.Perimeter)
+			fmt.Printf("  Circularity: %.2f, Aspect: %.2f\n",
+				contour.Circularity, contour.getAspectRatio())
+			fmt.Printf("  Points: %d\n", vertices)
+		}
+	}
+	fmt.Println()
+}
@@ -0,0 +1,260 @@
+package detector
+
+import (
+	"errors"
+	"image"
+	"image/color"
+	"math"
+	"sort"
+	"strconv"
+	"strings"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+)
+
+// PreprocessStep is one step of a preprocessing chain, such as
+// adaptive_threshold{block=31}. Parameters left out take their defaults.
+type PreprocessStep struct {
+	Name   string
+	Params map[string]float64
+}
+
+// String returns the step as it is written in a chain
+func (s PreprocessStep) String() string {
+	if len(s.Params) == 0 {
+		return s.Name
+	}
+	names := make([]string, 0, len(s.Params))
+	for name := range s.Params {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	params := make([]string, 0, len(names))
+	for _, name := range names {
+		params = append(params, name+"="+strconv.FormatFloat(s.Params[name], 'g', -1, 64))
+	}
+	return s.Name + "{" + strings.Join(params, ",") + "}"
+}
+
+// preprocessStep is the definition of a step a chain can name
+type preprocessStep struct {
+	// params are the parameters the step takes, with their defaults
+	params map[string]float64
+	// binarizes is set for steps that turn a grayscale image into the
+	// binary one contours are traced in
+	binarizes bool
+	// needsBinary is set for steps that work on a binary image only
+	needsBinary bool
+	apply       func(d *Detector, img *image.Gray, p map[string]float64) *image.Gray
+}
+
+// preprocessSteps are the steps a chain can name. Images reach
+// preprocessing in grayscale, so grayscale is accepted and does nothing.
+var preprocessSteps = map[string]preprocessStep{
+	"grayscale": {
+		apply: func(_ *Detector, img *image.Gray, _ map[string]float64) *image.Gray { return img },
+	},
+	"invert": {
+		apply: func(_ *Detector, img *image.Gray, _ map[string]float64) *image.Gray { return invert(img) },
+	},
+	"blur": {
+		params: map[string]float64{"r": 1},
+		apply: func(d *Detector, img *image.Gray, p map[string]float64) *image.Gray {
+			blurred := image.NewGray(img.Bounds())
+			gaussianBlurInto(blurred, img, kernel(p["r"]))
+			return blurred
+		},
+	},
+	"clahe": {
+		params: map[string]float64{"clip": 2, "tiles": 8},
+		apply: func(_ *Detector, img *image.Gray, p map[string]float64) *image.Gray {
+			return clahe(img, int(p["tiles"]), p["clip"])
+		},
+	},
+	"threshold": {
+		params:    map[string]float64{"t": 128},
+		binarizes: true,
+		apply: func(_ *Detector, img *image.Gray, p map[string]float64) *image.Gray {
+			return globalThreshold(img, uint8(math.Max(0, math.Min(255, p["t"]))))
+		},
+	},
+	"adaptive_threshold": {
+		params:    map[string]float64{"block": 11, "c": 5},
+		binarizes: true,
+		apply: func(_ *Detector, img *image.Gray, p map[string]float64) *image.Gray {
+			return adaptiveThreshold(img, int(p["block"]), int(p["c"]))
+		},
+	},
+	"close": {
+		params:      map[string]float64{"r": 1},
+		needsBinary: true,
+		apply: func(d *Detector, img *image.Gray, p map[string]float64) *image.Gray {
+			return morphologyClose(img, kernel(p["r"]), d.scratch)
+		},
+	},
+	"open": {
+		params:      map[string]float64{"r": 1},
+		needsBinary: true,
+		apply: func(_ *Detector, img *image.Gray, p map[string]float64) *image.Gray {
+			eroded := erode(img, kernel(p["r"]))
+			opened := image.NewGray(img.Bounds())
+			dilateInto(opened, eroded, kernel(p["r"]))
+			return opened
+		},
+	},
+	"dilate": {
+		params:      map[string]float64{"r": 1},
+		needsBinary: true,
+		apply: func(_ *Detector, img *image.Gray, p map[string]float64) *image.Gray {
+			dilated := image.NewGray(img.Bounds())
+			dilateInto(dilated, img, kernel(p["r"]))
+			return dilated
+		},
+	},
+	"erode": {
+		params:      map[string]float64{"r": 1},
+		needsBinary: true,
+		apply: func(_ *Detector, img *image.Gray, p map[string]float64) *image.Gray {
+			return erode(img, kernel(p["r"]))
+		},
+	},
+}
+
+// PreprocessStepNames returns the names of the steps a chain can use, sorted
+func PreprocessStepNames() []string {
+	names := make([]string, 0, len(preprocessSteps))
+	for name := range preprocessSteps {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	return names
+}
+
+// ParsePreprocessing parses a preprocessing chain such as
+//
+//	grayscale, adaptive_threshold{block=31}, close{r=2}
+//
+// Steps are separated by commas or spaces; brackets and quotes around them
+// are ignored, so a list copied from a config file parses too. A chain must
+// binarize the image before any morphology and by its end.
+func ParsePreprocessing(chain string) ([]PreprocessStep, error) {
+	var steps []PreprocessStep
+	binary := false
+	for _, field := range splitChain(chain) {
+		step, err := parsePreprocessStep(field)
+		if err != nil {
+			return nil, err
+		}
+		def := preprocessSteps[step.Name]
+		if def.needsBinary && !binary {
+			return nil, errors.New(i18n.Tf("detector.preprocess_needs_binary", step.Name))
+		}
+		binary = binary || def.binarizes
+		steps = append(steps, step)
+	}
+	if len(steps) == 0 {
+		return nil, errors.New(i18n.T("detector.preprocess_empty"))
+	}
+	if !binary {
+		return nil, errors.New(i18n.T("detector.preprocess_no_threshold"))
+	}
+	return steps, nil
+}
+
+// splitChain splits a chain into its steps at the separators outside braces
+func splitChain(chain string) []string {
+	var fields []string
+	var field strings.Builder
+	depth := 0
+	flush := func() {
+		if field.Len() > 0 {
+			fields = append(fields, field.String())
+			field.Reset()
+		}
+	}
+	for _, r := range chain {
+		switch {
+		case r == '{':
+			depth++
+		case r == '}':
+			depth--
+		case depth == 0 && (r == ',' || r == ' ' || r == '\t' || r == '\n'):
+			flush()
+			continue
+		case r == '[' || r == ']' || r == '"' || r == '\'':
+			continue
+		}
+		field.WriteRune(r)
+	}
+	flush()
+	return fields
+}
+
+// parsePreprocessStep parses one step, name{param=value,...}
+func parsePreprocessStep(field string) (PreprocessStep, error) {
+	name, rest, hasParams := strings.Cut(field, "{")
+	def, ok := preprocessSteps[name]
+	if !ok {
+		return PreprocessStep{}, errors.New(i18n.Tf("detector.preprocess_unknown_step",
+			name, strings.Join(PreprocessStepNames(), ", ")))
+	}
+	step := PreprocessStep{Name: name}
+	if !hasParams {
+		return step, nil
+	}
+	body, ok := strings.CutSuffix(rest, "}")
+	if !ok {
+		return PreprocessStep{}, errors.New(i18n.Tf("detector.preprocess_unclosed", field))
+	}
+
+	step.Params = make(map[string]float64)
+	for _, param := range strings.Split(body, ",") {
+		key, value, ok := strings.Cut(strings.TrimSpace(param), "=")
+		if !ok {
+			return PreprocessStep{}, errors.New(i18n.Tf("detector.preprocess_param_form", param, name))
+		}
+		if _, known := def.params[key]; !known {
+			return PreprocessStep{}, errors.New(i18n.Tf("detector.preprocess_unknown_param", name, key))
+		}
+		v, err := strconv.ParseFloat(value, 64)
+		if err != nil || v < 0 {
+			return PreprocessStep{}, errors.New(i18n.Tf("detector.preprocess_param_value", key, name, value))
+		}
+		step.Params[key] = v
+	}
+	return step, nil
+}
+
+// runPreprocessing applies the configured chain to gray
+func (d *Detector) runPreprocessing(gray *image.Gray) *image.Gray {
+	img := gray
+	for _, step := range d.preprocessing {
+		def := preprocessSteps[step.Name]
+		params := make(map[string]float64, len(def.params))
+		for key, value := range def.params {
+			params[key] = value
+		}
+		for key, value := range step.Params {
+			params[key] = value
+		}
+		img = def.apply(d, img, params)
+	}
+	return img
+}
+
+// kernel returns the size of the square kernel of radius r
+func kernel(r float64) int {
+	return 2*int(r) + 1
+}
+
+// invert swaps dark and light
+func invert(img *image.Gray) *image.Gray {
+	bounds := img.Bounds()
+	inverted := image.NewGray(bounds)
+	for y := bounds.Min.Y; y < bounds.Max.Y; y++ {
+		for x := bounds.Min.X; x < bounds.Max.X; x++ {
+			inverted.SetGray(x, y, color.Gray{Y: 255 - img.GrayAt(x, y).Y})
+		}
+	}
+	return inverted
+}
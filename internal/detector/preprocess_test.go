@@ -0,0 +1,79 @@
+package detector
+
+import (
+	"image"
+	"image/color"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParsePreprocessing(t *testing.T) {
+	steps, err := ParsePreprocessing("grayscale, adaptive_threshold{block=31}, close{r=2}")
+	require.NoError(t, err)
+	assert.Equal(t, []PreprocessStep{
+		{Name: "grayscale"},
+		{Name: "adaptive_threshold", Params: map[string]float64{"block": 31}},
+		{Name: "close", Params: map[string]float64{"r": 2}},
+	}, steps)
+	assert.Equal(t, "adaptive_threshold{block=31}", steps[1].String())
+
+	// A list copied from a config file reads the same
+	listed, err := ParsePreprocessing(`["grayscale", "adaptive_threshold{block=31}", "close{r=2}"]`)
+	require.NoError(t, err)
+	assert.Equal(t, steps, listed)
+}
+
+func TestParsePreprocessingErrors(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	for chain, message := range map[string]string{
+		"":                             "empty",
+		"blur, sharpen":                "unknown preprocessing step",
+		"threshold{level=3}":           "no parameter",
+		"threshold{t=dark}":            "non-negative number",
+		"threshold{t=3":                "unclosed",
+		"threshold{t=100,}":            "name=value",
+		"close{r=1}, threshold":        "needs a binary image",
+		"grayscale, blur{r=2}, invert": "must threshold",
+	} {
+		_, err := ParsePreprocessing(chain)
+		if assert.Error(t, err, chain) {
+			assert.Contains(t, err.Error(), message, chain)
+		}
+	}
+}
+
+func TestPreprocessingMatchesBuiltIn(t *testing.T) {
+	// Larger than a thumbnail, so the built-in path blurs, thresholds and closes
+	img := image.NewGray(image.Rect(0, 0, 300, 300))
+	for i := range img.Pix {
+		img.Pix[i] = 0xff
+	}
+	for y := 100; y < 200; y++ {
+		for x := 100; x < 200; x++ {
+			if (x-150)*(x-150)+(y-150)*(y-150) < 40*40 {
+				img.SetGray(x, y, color.Gray{Y: 0})
+			}
+		}
+	}
+
+	steps, err := ParsePreprocessing("blur{r=1}, adaptive_threshold{block=11,c=5}, close{r=1}")
+	require.NoError(t, err)
+	want := NewDetector(Config{}).preprocessImage(img)
+	got := NewDetector(Config{Preprocessing: steps}).preprocessImage(img)
+	assert.Equal(t, want.Pix, got.Pix)
+}
+
+func TestPreprocessingInvert(t *testing.T) {
+	img := image.NewGray(image.Rect(0, 0, 2, 1))
+	img.Pix = []uint8{0, 200}
+
+	steps, err := ParsePreprocessing("invert, threshold{t=128}")
+	require.NoError(t, err)
+	got := NewDetector(Config{Preprocessing: steps}).preprocessImage(img)
+	assert.Equal(t, []uint8{0, 255}, got.Pix)
+}
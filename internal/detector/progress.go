@@ -0,0 +1,47 @@
+package detector
+
+import (
+	"context"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+)
+
+// Stage names a step of detection reported to a ProgressFunc
+type Stage string
+
+// Detection stages, in the order they run
+const (
+	StagePreprocess  Stage = "preprocess"
+	StageOuterCircle Stage = "outer_circle"
+	StageContours    Stage = "contours"
+	StageSymbols     Stage = "symbols"
+	StageConnections Stage = "connections"
+	StageDone        Stage = "done"
+)
+
+// ProgressFunc receives the stage detection is entering and a rough
+// percentage of the work already done
+type ProgressFunc func(stage Stage, percent int)
+
+// stagePercent is the share of the work done before each stage, measured
+// roughly on the example images
+var stagePercent = map[Stage]int{
+	StagePreprocess:  0,
+	StageOuterCircle: 15,
+	StageContours:    30,
+	StageSymbols:     45,
+	StageConnections: 65,
+	StageDone:        100,
+}
+
+// enterStage returns a Cancelled error if ctx is done, and otherwise reports
+// the stage to the progress callback
+func (d *Detector) enterStage(ctx context.Context, stage Stage) error {
+	if err := ctx.Err(); err != nil {
+		return grimoireErrors.CancelledError(err)
+	}
+	if d.progress != nil {
+		d.progress(stage, stagePercent[stage])
+	}
+	return nil
+}
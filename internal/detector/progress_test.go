@@ -0,0 +1,70 @@
+package detector
+
+import (
+	"context"
+	"image"
+	"testing"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func whiteImage() *image.Gray {
+	img := image.NewGray(image.Rect(0, 0, 100, 100))
+	for i := range img.Pix {
+		img.Pix[i] = 0xff
+	}
+	return img
+}
+
+func TestProgressReportsStagesInOrder(t *testing.T) {
+	var stages []Stage
+	var percents []int
+	d := NewDetector(Config{Progress: func(stage Stage, percent int) {
+		stages = append(stages, stage)
+		percents = append(percents, percent)
+	}})
+
+	_, _, err := d.run(context.Background(), d.sequentialPipeline(), whiteImage())
+	require.NoError(t, err)
+
+	assert.Equal(t, []Stage{StagePreprocess, StageOuterCircle, StageContours, StageSymbols, StageConnections, StageDone}, stages)
+	assert.IsIncreasing(t, percents)
+	assert.Equal(t, 100, percents[len(percents)-1])
+}
+
+func TestCancelBetweenStages(t *testing.T) {
+	ctx, cancel := context.WithCancel(context.Background())
+	defer cancel()
+
+	var stages []Stage
+	d := NewDetector(Config{Progress: func(stage Stage, percent int) {
+		stages = append(stages, stage)
+		if stage == StageContours {
+			cancel()
+		}
+	}})
+
+	_, _, err := d.DetectImageContext(ctx, whiteImage())
+	require.Error(t, err)
+
+	var gErr *grimoireErrors.GrimoireError
+	require.ErrorAs(t, err, &gErr)
+	assert.Equal(t, grimoireErrors.Cancelled, gErr.Type)
+	assert.ErrorIs(t, err, context.Canceled)
+	assert.Equal(t, StageContours, stages[len(stages)-1])
+}
+
+func TestCancelledBeforeStart(t *testing.T) {
+	ctx, cancel := context.WithCancel(context.Background())
+	cancel()
+
+	called := false
+	d := NewParallelDetectorV2(Config{Progress: func(Stage, int) { called = true }})
+	_, _, err := d.DetectImageContext(ctx, whiteImage())
+	var gErr *grimoireErrors.GrimoireError
+	require.ErrorAs(t, err, &gErr)
+	assert.Equal(t, grimoireErrors.Cancelled, gErr.Type)
+	assert.False(t, called)
+}
@@ -0,0 +1,137 @@
+package detector
+
+import (
+	"image"
+	"math"
+	"slices"
+)
+
+// Quadrilaterals are told apart by the lengths of their sides and a
+// histogram of their interior angles. A square has equal sides and right
+// angles, a rectangle right angles and two pairs of equal opposite sides,
+// and a rhombus equal sides without right angles.
+
+// quadKind is the kind of a quadrilateral
+type quadKind int
+
+// Kinds of quadrilaterals
+const (
+	quadOther quadKind = iota
+	quadSquare
+	quadRectangle
+	quadRhombus
+)
+
+const (
+	// angleBin is the width, in degrees, of a bin of the angle histogram
+	angleBin = 15
+	// angleBins is how many bins the angle histogram has, the last one for
+	// straight angles
+	angleBins = 180/angleBin + 1
+	// straightAngle is the interior angle, in degrees, above which a vertex
+	// of the approximated polygon is a bend along a side, not a corner
+	straightAngle = 160
+	// maxSideSpread is the largest ratio of two sides that count as equal
+	maxSideSpread = 1.3
+	// minRectangleAspect and maxRectangleAspect bound the ratio of the long
+	// sides of a rectangle to its short ones. Wider rectangles are read as
+	// list frames, or as equals signs, whose bars trace the same outline.
+	minRectangleAspect = 1.4
+	maxRectangleAspect = 2.0
+	// minRectangleSide is the shortest side, in pixels, of a rectangle.
+	// Smaller quadrilaterals are too coarse to tell from a distorted square.
+	minRectangleSide = 20
+)
+
+// classifyQuadrilateral tells whether the polygon approx, once the bends
+// along its sides are dropped, is a square, a rectangle or a rhombus
+func (d *Detector) classifyQuadrilateral(approx []image.Point) quadKind {
+	corners := d.quadCorners(approx)
+	if len(corners) != 4 {
+		return quadOther
+	}
+
+	sides := make([]float64, 4)
+	angles := make([]float64, 4)
+	for i := range corners {
+		sides[i] = distance(corners[i], corners[(i+1)%4])
+		angles[i] = d.cornerAngle(corners[(i+3)%4], corners[i], corners[(i+1)%4])
+	}
+	shortest := slices.Min(sides)
+	if shortest == 0 {
+		return quadOther
+	}
+	equal := func(a, b float64) bool {
+		return math.Max(a, b)/math.Min(a, b) <= maxSideSpread
+	}
+	equalSides := slices.Max(sides)/shortest <= maxSideSpread
+
+	if !rightAngled(angleHistogram(angles), len(angles)) {
+		if equalSides {
+			return quadRhombus
+		}
+		return quadOther
+	}
+	if equalSides {
+		return quadSquare
+	}
+	if equal(sides[0], sides[2]) && equal(sides[1], sides[3]) && shortest >= minRectangleSide {
+		aspect := (sides[0] + sides[2]) / (sides[1] + sides[3])
+		if aspect < 1 {
+			aspect = 1 / aspect
+		}
+		if aspect >= minRectangleAspect && aspect <= maxRectangleAspect {
+			return quadRectangle
+		}
+	}
+	return quadOther
+}
+
+// quadCorners returns the vertices of approx without the bends along its
+// sides, such as the vertex a traced outline can leave beside its first
+// point. The straightest bend is dropped first, until four vertices are
+// left or none is straight enough.
+func (d *Detector) quadCorners(approx []image.Point) []image.Point {
+	corners := append([]image.Point(nil), approx...)
+	for len(corners) > 4 {
+		n := len(corners)
+		straightest, widest := -1, float64(straightAngle)
+		for i := range corners {
+			if angle := d.cornerAngle(corners[(i+n-1)%n], corners[i], corners[(i+1)%n]); angle > widest {
+				straightest, widest = i, angle
+			}
+		}
+		if straightest < 0 {
+			break
+		}
+		corners = slices.Delete(corners, straightest, straightest+1)
+	}
+	return corners
+}
+
+// cornerAngle returns the interior angle at curr, in degrees from 0 to 180
+func (d *Detector) cornerAngle(prev, curr, next image.Point) float64 {
+	return math.Abs(d.calculateAngle(prev, curr, next)) * 180 / math.Pi
+}
+
+// angleHistogram counts angles, in degrees, in bins of angleBin
+func angleHistogram(angles []float64) [angleBins]int {
+	var histogram [angleBins]int
+	for _, angle := range angles {
+		histogram[min(int(angle/angleBin), angleBins-1)]++
+	}
+	return histogram
+}
+
+// rightAngled reports whether all n angles counted in histogram fall in
+// the bins either side of 90 degrees
+func rightAngled(histogram [angleBins]int, n int) bool {
+	return histogram[90/angleBin-1]+histogram[90/angleBin] == n
+}
+
+// rectangleExtent returns the width and height of the rectangle traced by
+// contour, from its bounding box
+func rectangleExtent(contour Contour) (width, height float64) {
+	bbox := contour.getBoundingBox()
+	return float64(bbox.Dx()), float64(bbox.Dy())
+}
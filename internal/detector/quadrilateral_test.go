@@ -0,0 +1,57 @@
+package detector
+
+import (
+	"image"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestClassifyQuadrilateral(t *testing.T) {
+	d := NewDetector(Config{})
+	tests := []struct {
+		name     string
+		vertices []image.Point
+		expected quadKind
+	}{
+		{"square", []image.Point{{0, 0}, {100, 0}, {100, 100}, {0, 100}}, quadSquare},
+		{"square on a corner", []image.Point{{50, 0}, {100, 50}, {50, 100}, {0, 50}}, quadSquare},
+		{"square with a bend along a side", []image.Point{{0, 0}, {50, 1}, {100, 0}, {100, 100}, {0, 100}}, quadSquare},
+		{"rectangle", []image.Point{{0, 0}, {120, 0}, {120, 70}, {0, 70}}, quadRectangle},
+		{"tall rectangle", []image.Point{{0, 0}, {60, 0}, {60, 100}, {0, 100}}, quadRectangle},
+		{"tilted rectangle", []image.Point{{0, 50}, {100, 0}, {130, 60}, {30, 110}}, quadRectangle},
+		{"rhombus", []image.Point{{0, 0}, {100, 0}, {150, 87}, {50, 87}}, quadRhombus},
+		{"nearly square", []image.Point{{0, 0}, {110, 0}, {110, 95}, {0, 95}}, quadSquare},
+		{"bar", []image.Point{{0, 0}, {200, 0}, {200, 40}, {0, 40}}, quadOther},
+		{"small rectangle", []image.Point{{0, 0}, {24, 0}, {24, 12}, {0, 12}}, quadOther},
+		{"trapezoid", []image.Point{{0, 0}, {120, 0}, {90, 70}, {30, 70}}, quadOther},
+		{"triangle", []image.Point{{0, 0}, {100, 0}, {50, 80}}, quadOther},
+	}
+	for _, tc := range tests {
+		t.Run(tc.name, func(t *testing.T) {
+			assert.Equal(t, tc.expected, d.classifyQuadrilateral(tc.vertices))
+		})
+	}
+}
+
+func TestAngleHistogram(t *testing.T) {
+	histogram := angleHistogram([]float64{0, 80, 95, 179.9, 180})
+	assert.Equal(t, 1, histogram[0])
+	assert.Equal(t, 1, histogram[5])
+	assert.Equal(t, 1, histogram[6])
+	assert.Equal(t, 1, histogram[11])
+	assert.Equal(t, 1, histogram[12])
+
+	assert.True(t, rightAngled(angleHistogram([]float64{80, 100, 88, 92}), 4))
+	assert.False(t, rightAngled(angleHistogram([]float64{60, 120, 60, 120}), 4))
+}
+
+func TestClassifyShapeRectangle(t *testing.T) {
+	d := NewDetector(Config{})
+	assert.Equal(t, Rectangle, d.classifyShape(rectContour(image.Rect(100, 100, 220, 170))))
+	assert.Equal(t, Square, d.classifyShape(rectContour(image.Rect(100, 100, 160, 160))))
+
+	width, height := rectangleExtent(rectContour(image.Rect(100, 100, 220, 170)))
+	assert.Equal(t, 120.0, width)
+	assert.Equal(t, 70.0, height)
+}
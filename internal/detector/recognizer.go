@@ -0,0 +1,46 @@
+package detector
+
+import "image"
+
+// SymbolRecognizer classifies contours the built-in shape classifier does
+// not know, so new glyphs can be supported without changing the detector.
+// Recognizers are consulted in order before the built-in classifier, and
+// the first to recognize a contour decides its type.
+//
+// Recognize may be called from several goroutines at once by the parallel
+// backends, so implementations must be safe for concurrent use.
+type SymbolRecognizer interface {
+	// Recognize returns the type of the symbol outlined by contour and true,
+	// or false to leave the contour to the next recognizer. roi is the
+	// binarized image cropped to the contour's bounding box, with dark
+	// pixels set to 255 and in the coordinates of the full image.
+	Recognize(contour Contour, roi *image.Gray) (SymbolType, bool)
+}
+
+// RecognizerFunc adapts a function to a SymbolRecognizer
+type RecognizerFunc func(contour Contour, roi *image.Gray) (SymbolType, bool)
+
+// Recognize calls f(contour, roi)
+func (f RecognizerFunc) Recognize(contour Contour, roi *image.Gray) (SymbolType, bool) {
+	return f(contour, roi)
+}
+
+// recognize asks the registered recognizers to classify contour
+func (d *Detector) recognize(contour Contour, binary *image.Gray) (SymbolType, bool) {
+	if len(d.recognizers) == 0 {
+		return Unknown, false
+	}
+
+	bounds := contour.getBoundingBox().Intersect(binary.Bounds())
+	roi, ok := binary.SubImage(bounds).(*image.Gray)
+	if !ok {
+		return Unknown, false
+	}
+
+	for _, r := range d.recognizers {
+		if symbolType, ok := r.Recognize(contour, roi); ok {
+			return symbolType, true
+		}
+	}
+	return Unknown, false
+}
@@ -0,0 +1,76 @@
+package detector
+
+import (
+	"context"
+	"image"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+const customGlyph SymbolType = "rune"
+
+func TestRecognizerClassifiesContours(t *testing.T) {
+	var rois []image.Rectangle
+	d := NewDetector(Config{Recognizers: []SymbolRecognizer{
+		RecognizerFunc(func(contour Contour, roi *image.Gray) (SymbolType, bool) {
+			rois = append(rois, roi.Bounds())
+			return customGlyph, true
+		}),
+	}})
+
+	symbols, _, err := d.run(context.Background(), d.sequentialPipeline(), createTestImageWithCircle(200, 200, 90))
+	require.NoError(t, err)
+	require.NotEmpty(t, symbols)
+	assert.Equal(t, customGlyph, symbols[0].Type)
+
+	// The region of interest is cropped to the contour, in image coordinates
+	require.NotEmpty(t, rois)
+	for _, roi := range rois {
+		assert.True(t, roi.In(image.Rect(0, 0, 200, 200)), "%v", roi)
+		assert.False(t, roi.Empty())
+	}
+}
+
+func TestRecognizerFallsBackToBuiltIn(t *testing.T) {
+	img := createTestImageWithCircle(200, 200, 90)
+	d := NewDetector(Config{})
+	expected, _, err := d.run(context.Background(), d.sequentialPipeline(), img)
+	require.NoError(t, err)
+
+	calls := 0
+	d = NewDetector(Config{Recognizers: []SymbolRecognizer{
+		RecognizerFunc(func(Contour, *image.Gray) (SymbolType, bool) {
+			calls++
+			return Unknown, false
+		}),
+	}})
+	symbols, _, err := d.run(context.Background(), d.sequentialPipeline(), img)
+	require.NoError(t, err)
+	assert.Equal(t, expected, symbols)
+	assert.Positive(t, calls)
+}
+
+func TestRecognizersInOrder(t *testing.T) {
+	first := RecognizerFunc(func(contour Contour, _ *image.Gray) (SymbolType, bool) {
+		return customGlyph, contour.Area > 1000
+	})
+	second := RecognizerFunc(func(Contour, *image.Gray) (SymbolType, bool) {
+		return Seal, true
+	})
+	binary := image.NewGray(image.Rect(0, 0, 200, 200))
+
+	for _, d := range []*Detector{
+		NewDetector(Config{Recognizers: []SymbolRecognizer{first, second}}),
+		NewParallelDetectorV2(Config{Recognizers: []SymbolRecognizer{first, second}}).Detector,
+	} {
+		large := createSquareContour(10, 10, 50)
+		large.Area = 2500
+		small := createSquareContour(10, 10, 20)
+		small.Area = 400
+
+		assert.Equal(t, customGlyph, d.classifyContour(large, binary))
+		assert.Equal(t, Seal, d.classifyContour(small, binary))
+	}
+}
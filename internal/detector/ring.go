@@ -0,0 +1,86 @@
+package detector
+
+import "math"
+
+// Glyphs drawn on the outer circle itself, touching the ring, declare
+// program metadata such as the language version rather than being part of
+// the program. They are marked OnRing and left out of connection detection.
+
+// ringBand is how far from the outer circle a stroke may lie and still be
+// on it, allowing for the width of the ring and a circle not quite round
+func ringBand(outerCircle *Symbol) float64 {
+	return outerCircle.Radius()*0.05 + 3
+}
+
+// onRing reports whether the outline of symbol reaches the outer circle
+func onRing(outerCircle, symbol *Symbol) bool {
+	if outerCircle == nil || symbol.Type == OuterCircle {
+		return false
+	}
+	dist := math.Hypot(symbol.Position.X-outerCircle.Position.X, symbol.Position.Y-outerCircle.Position.Y)
+	return math.Abs(dist-outerCircle.Radius()) <= symbol.Radius()+ringBand(outerCircle)
+}
+
+// markRingGlyphs flags the symbols sitting on the outer circle
+func markRingGlyphs(symbols []*Symbol) {
+	var outerCircle *Symbol
+	for _, symbol := range symbols {
+		if symbol.Type == OuterCircle {
+			outerCircle = symbol
+			break
+		}
+	}
+	for _, symbol := range symbols {
+		symbol.OnRing = onRing(outerCircle, symbol)
+	}
+}
+
+// interiorSymbols returns the symbols inside the outer circle, leaving out
+// the glyphs on it and the stage rings, which no connection ends at
+func interiorSymbols(symbols []*Symbol) []*Symbol {
+	interior := make([]*Symbol, 0, len(symbols))
+	for _, symbol := range symbols {
+		if !symbol.OnRing && !symbol.StageRing {
+			interior = append(interior, symbol)
+		}
+	}
+	return interior
+}
+
+// Stage rings are circles drawn inside the outer circle around its center.
+// They are large enough to be classified as outer circles themselves, so a
+// contour taken for a second outer circle becomes a stage ring when it is
+// concentric with the first and clear of it.
+const (
+	// stageRingOffset is how far the center of a stage ring may be from
+	// that of the outer circle, as a fraction of the outer radius
+	stageRingOffset = 0.1
+	// minStageRing is the smallest stage ring as a fraction of the outer
+	// radius, below which a centered circle is an ordinary symbol
+	minStageRing = 0.2
+)
+
+// stageRing returns the stage ring drawn by contour inside outerCircle, or
+// nil when contour is not concentric with it
+func (d *Detector) stageRing(outerCircle *Symbol, contour Contour) *Symbol {
+	if outerCircle == nil {
+		return nil
+	}
+	ring := &Symbol{
+		Type:       Circle,
+		Position:   Position{X: float64(contour.Center.X), Y: float64(contour.Center.Y)},
+		Size:       math.Sqrt(contour.Area),
+		Confidence: d.confidence(Circle, contour),
+		Pattern:    PatternEmpty,
+		Properties: make(map[string]interface{}),
+		StageRing:  true,
+	}
+
+	outerRadius := outerCircle.Radius()
+	offset := math.Hypot(ring.Position.X-outerCircle.Position.X, ring.Position.Y-outerCircle.Position.Y)
+	if offset > outerRadius*stageRingOffset || ring.Radius() < outerRadius*minStageRing ||
+		ring.Radius() > outerRadius-2*ringBand(outerCircle) {
+		return nil
+	}
+	return ring
+}
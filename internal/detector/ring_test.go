@@ -0,0 +1,59 @@
+package detector
+
+import (
+	"math"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestMarkRingGlyphs(t *testing.T) {
+	// An outer circle of radius 200 around (250, 250)
+	outerCircle := &Symbol{Type: OuterCircle, Position: Position{X: 250, Y: 250}, Size: 200 * math.Sqrt(math.Pi)}
+	top := &Symbol{Type: Square, Position: Position{X: 250, Y: 60}, Size: 20}
+	outside := &Symbol{Type: Hexagon, Position: Position{X: 458, Y: 250}, Size: 20}
+	inside := &Symbol{Type: Square, Position: Position{X: 250, Y: 200}, Size: 20}
+	nearRing := &Symbol{Type: Star, Position: Position{X: 250, Y: 400}, Size: 20}
+
+	symbols := []*Symbol{outerCircle, top, outside, inside, nearRing}
+	markRingGlyphs(symbols)
+
+	assert.False(t, outerCircle.OnRing)
+	assert.True(t, top.OnRing, "a glyph inside touching the ring")
+	assert.True(t, outside.OnRing, "a glyph outside touching the ring")
+	assert.False(t, inside.OnRing)
+	assert.False(t, nearRing.OnRing, "a symbol close to the ring but clear of it")
+
+	assert.Equal(t, []*Symbol{outerCircle, inside, nearRing}, interiorSymbols(symbols))
+}
+
+func TestMarkRingGlyphsWithoutOuterCircle(t *testing.T) {
+	symbols := []*Symbol{{Type: Square, Position: Position{X: 250, Y: 60}, Size: 20}}
+	markRingGlyphs(symbols)
+	assert.False(t, symbols[0].OnRing)
+}
+
+func TestStageRing(t *testing.T) {
+	// An outer circle of radius 200 around (250, 250)
+	outerCircle := &Symbol{Type: OuterCircle, Position: Position{X: 250, Y: 250}, Size: 200 * math.Sqrt(math.Pi)}
+	d := NewDetector(Config{})
+	ring := func(cx, cy, r int) *Symbol {
+		return d.stageRing(outerCircle, outlineContour(createCircleContour(cx, cy, r).Points))
+	}
+
+	stage := ring(250, 250, 100)
+	require.NotNil(t, stage)
+	assert.Equal(t, Circle, stage.Type)
+	assert.True(t, stage.StageRing)
+	assert.InDelta(t, 100, stage.Radius(), 2)
+
+	assert.NotNil(t, ring(255, 245, 120), "a ring drawn slightly off center")
+	assert.Nil(t, ring(330, 250, 100), "a circle away from the center")
+	assert.Nil(t, ring(250, 250, 30), "a small circle at the center")
+	assert.Nil(t, ring(250, 250, 196), "the outer circle itself")
+	assert.Nil(t, d.stageRing(nil, outlineContour(createCircleContour(250, 250, 100).Points)))
+
+	assert.Equal(t, []*Symbol{outerCircle}, interiorSymbols([]*Symbol{outerCircle, stage}),
+		"no connection ends at a stage ring")
+}
@@ -0,0 +1,186 @@
+package detector
+
+import (
+	"image"
+	"image/color"
+	"math"
+)
+
+// workingDiameter is the outer circle diameter, in pixels, that the pixel
+// thresholds of detection are tuned for: the minimum contour area, the
+// kernel sizes and the distances used to classify and connect symbols.
+// Drawings with a larger outer circle are scaled down to it, so a circle
+// scanned at 600 DPI is detected like the same circle at 150 DPI. Smaller
+// drawings are not scaled up, which would add no detail.
+const workingDiameter = 1000
+
+// coarseFactor is the resolution, relative to the original image, of the
+// coarse pass that locates the outer circle of a large scan
+const coarseFactor = 0.25
+
+// coarseMinCircle is the smallest outer circle the coarse pass accepts, as
+// a fraction of the smaller side of the image. A smaller circular contour
+// is more likely a symbol of a drawing whose outer circle is broken, and
+// cropping to it would lose the rest of the drawing.
+const coarseMinCircle = 0.25
+
+// scaling maps a drawing to and from the working size
+type scaling struct {
+	factor float64         // Working size over original size, at most 1
+	origin image.Point     // Top left of the part of the original image detected
+	crop   image.Rectangle // Part of the original image detected, or empty for all of it
+}
+
+// unscaled is the scaling of a drawing already at the working size
+var unscaled = scaling{factor: 1}
+
+// workingScale returns the scaling that brings the drawing in gray to the
+// working size. The outer circle gives the drawing's diameter; when it is
+// not found, the larger side of the box around all dark pixels does. When
+// the coarse pass locates the outer circle of a large scan, only the part
+// of the image around it is detected, so that the pixels outside it are
+// not scanned at full resolution.
+func (d *Detector) workingScale(gray *image.Gray) scaling {
+	crop := d.locateOuterCircle(gray)
+	drawing := gray.SubImage(crop).(*image.Gray)
+
+	var diameter float64
+	if outerCircle := d.findOuterCircleFromGrayscale(drawing); outerCircle != nil {
+		diameter = 2 * outerCircle.getEquivalentRadius()
+	} else {
+		ink := inkBounds(drawing, d.binaryThreshold)
+		diameter = float64(max(ink.Dx(), ink.Dy()))
+	}
+
+	factor := 1.0
+	if diameter > workingDiameter {
+		factor = workingDiameter / diameter
+	}
+	if factor == 1 && crop == gray.Bounds() {
+		return unscaled
+	}
+	s := scaling{factor: factor, origin: crop.Min}
+	if crop != gray.Bounds() {
+		s.crop = crop
+	}
+	return s
+}
+
+// locateOuterCircle finds the outer circle of a large scan in a coarse
+// pass at a quarter of its resolution, and returns the box around it plus
+// a margin. It returns the bounds of gray when the image is small enough
+// to detect whole or the outer circle is not found.
+func (d *Detector) locateOuterCircle(gray *image.Gray) image.Rectangle {
+	bounds := gray.Bounds()
+	side := min(bounds.Dx(), bounds.Dy())
+	if side < workingDiameter {
+		return bounds
+	}
+
+	coarse := scaling{factor: coarseFactor, origin: bounds.Min}.apply(gray)
+	contours := d.findContours(globalThreshold(coarse, d.binaryThreshold))
+	if len(contours) == 0 {
+		return bounds
+	}
+	// The outer circle holds the whole drawing, so it is the largest contour
+	largest := &contours[0]
+	for i := range contours {
+		if contours[i].Area > largest.Area {
+			largest = &contours[i]
+		}
+	}
+	box := largest.getBoundingBox()
+	if !d.isOuterCircle(*largest) {
+		return bounds
+	}
+	if float64(max(box.Dx(), box.Dy()))/coarseFactor < coarseMinCircle*float64(side) {
+		return bounds
+	}
+
+	// Back to original pixels, with a margin for the pixels a coarse pixel
+	// covers and for ink just outside the circle
+	circle := image.Rect(
+		int(float64(box.Min.X)/coarseFactor), int(float64(box.Min.Y)/coarseFactor),
+		int(float64(box.Max.X)/coarseFactor), int(float64(box.Max.Y)/coarseFactor),
+	).Add(bounds.Min)
+	margin := int(2/coarseFactor) + max(circle.Dx(), circle.Dy())/50
+	return circle.Inset(-margin).Intersect(bounds)
+}
+
+// apply returns gray cropped and scaled to the working size, each pixel the
+// mean of the pixels it covers
+func (s scaling) apply(gray *image.Gray) *image.Gray {
+	if !s.crop.Empty() {
+		gray = gray.SubImage(s.crop).(*image.Gray)
+	} else if s.factor == 1 {
+		return gray
+	}
+
+	bounds := gray.Bounds()
+	width := int(math.Ceil(float64(bounds.Dx()) * s.factor))
+	height := int(math.Ceil(float64(bounds.Dy()) * s.factor))
+	scaled := image.NewGray(image.Rect(0, 0, width, height))
+
+	// source returns the first original pixel covered by working pixel i
+	source := func(i, limit int) int {
+		return min(int(float64(i)/s.factor), limit)
+	}
+	for y := 0; y < height; y++ {
+		y0, y1 := source(y, bounds.Dy()), source(y+1, bounds.Dy())
+		for x := 0; x < width; x++ {
+			x0, x1 := source(x, bounds.Dx()), source(x+1, bounds.Dx())
+
+			var sum, count uint32
+			for sy := y0; sy < max(y1, y0+1) && sy < bounds.Dy(); sy++ {
+				for sx := x0; sx < max(x1, x0+1) && sx < bounds.Dx(); sx++ {
+					sum += uint32(gray.GrayAt(bounds.Min.X+sx, bounds.Min.Y+sy).Y)
+					count++
+				}
+			}
+			if count > 0 {
+				scaled.SetGray(x, y, color.Gray{uint8(sum / count)})
+			}
+		}
+	}
+	return scaled
+}
+
+// restore maps the positions and sizes of symbols detected at the working
+// size back to the original image
+func (s scaling) restore(symbols []*Symbol) {
+	if s == unscaled {
+		return
+	}
+	for _, symbol := range symbols {
+		*symbol = s.restored(symbol)
+	}
+}
+
+// restored returns a copy of symbol with its position and size mapped back
+// to the original image
+func (s scaling) restored(symbol *Symbol) Symbol {
+	restored := *symbol
+	restored.Position = Position{
+		X: symbol.Position.X/s.factor + float64(s.origin.X),
+		Y: symbol.Position.Y/s.factor + float64(s.origin.Y),
+	}
+	restored.Size = symbol.Size / s.factor
+	restored.Width = symbol.Width / s.factor
+	restored.Height = symbol.Height / s.factor
+	return restored
+}
+
+// inkBounds returns the smallest rectangle holding every pixel of gray
+// darker than threshold, or an empty rectangle if there are none
+func inkBounds(gray *image.Gray, threshold uint8) image.Rectangle {
+	bounds := gray.Bounds()
+	ink := image.Rectangle{}
+	for y := bounds.Min.Y; y < bounds.Max.Y; y++ {
+		for x := bounds.Min.X; x < bounds.Max.X; x++ {
+			if gray.GrayAt(x, y).Y < threshold {
+				ink = ink.Union(image.Rect(x, y, x+1, y+1))
+			}
+		}
+	}
+	return ink
+}
@@ -0,0 +1,211 @@
+package detector
+
+import (
+	"context"
+	"image"
+	"image/color"
+	"image/draw"
+	"math"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// scaledDrawing draws a 400 pixel magic circle holding a square and a
+// triangle, at scale times the resolution, as if scanned at a higher DPI
+func scaledDrawing(scale int) *image.RGBA {
+	size := 400 * scale
+	img := createTestImage(size, size)
+	square := shape{kind: shapePolygon, sides: 4, cx: 200, cy: 130, radius: 40, angle: math.Pi / 4}
+	triangle := shape{kind: shapePolygon, sides: 3, cx: 200, cy: 270, radius: 45, angle: -math.Pi / 2}
+
+	// outlined reports whether (u, v) is on the 5 pixel thick outline of s
+	outlined := func(s shape, u, v float64) bool {
+		if math.Hypot(u-s.cx, v-s.cy) > s.radius+1 {
+			return false
+		}
+		inner := s
+		inner.radius -= 5
+		return s.contains(u, v) && !inner.contains(u, v)
+	}
+
+	for y := 0; y < size; y++ {
+		for x := 0; x < size; x++ {
+			u, v := (float64(x)+0.5)/float64(scale), (float64(y)+0.5)/float64(scale)
+			r := math.Hypot(u-200, v-200)
+			if (r >= 190 && r <= 196) || outlined(square, u, v) || outlined(triangle, u, v) {
+				img.Set(x, y, color.Black)
+			}
+		}
+	}
+	return img
+}
+
+func TestWorkingScaleKeepsSmallDrawings(t *testing.T) {
+	d := NewDetector(Config{})
+	assert.Equal(t, unscaled, d.workingScale(d.toGrayscale(createTestImageWithCircle(200, 200, 90))))
+	assert.Equal(t, unscaled, d.workingScale(d.toGrayscale(createTestImage(3000, 3000))),
+		"a blank image has no drawing to scale")
+}
+
+func TestWorkingScaleFromOuterCircle(t *testing.T) {
+	d := NewDetector(Config{})
+	s := d.workingScale(d.toGrayscale(scaledDrawing(4)))
+	// The outer circle is about 4 * 2 * 190 pixels across
+	assert.InDelta(t, workingDiameter/(4*2*190.0), s.factor, 0.02)
+}
+
+func TestWorkingScaleFromInk(t *testing.T) {
+	// Without an outer circle, the drawing's extent gives the scale
+	gray := image.NewGray(image.Rect(0, 0, 3000, 3000))
+	for i := range gray.Pix {
+		gray.Pix[i] = 255
+	}
+	for x := 500; x < 2500; x++ {
+		gray.SetGray(x, 1000, color.Gray{0})
+	}
+
+	s := NewDetector(Config{}).workingScale(gray)
+	assert.InDelta(t, 0.5, s.factor, 1e-9)
+}
+
+func TestInkBounds(t *testing.T) {
+	gray := image.NewGray(image.Rect(0, 0, 10, 10))
+	for i := range gray.Pix {
+		gray.Pix[i] = 255
+	}
+	assert.True(t, inkBounds(gray, 128).Empty())
+
+	gray.SetGray(2, 3, color.Gray{0})
+	gray.SetGray(6, 8, color.Gray{100})
+	gray.SetGray(9, 9, color.Gray{200})
+	assert.Equal(t, image.Rect(2, 3, 7, 9), inkBounds(gray, 128))
+}
+
+func TestScalingApplyAveragesPixels(t *testing.T) {
+	// A sub-image, so the original does not start at the origin
+	full := image.NewGray(image.Rect(0, 0, 6, 6))
+	for i := range full.Pix {
+		full.Pix[i] = uint8(i * 7)
+	}
+	gray := full.SubImage(image.Rect(2, 2, 6, 6)).(*image.Gray)
+
+	scaled := scaling{factor: 0.5, origin: gray.Bounds().Min}.apply(gray)
+	require.Equal(t, image.Rect(0, 0, 2, 2), scaled.Bounds())
+	mean := func(x, y int) uint8 {
+		sum := int(gray.GrayAt(x, y).Y) + int(gray.GrayAt(x+1, y).Y) +
+			int(gray.GrayAt(x, y+1).Y) + int(gray.GrayAt(x+1, y+1).Y)
+		return uint8(sum / 4)
+	}
+	assert.Equal(t, mean(2, 2), scaled.GrayAt(0, 0).Y)
+	assert.Equal(t, mean(4, 2), scaled.GrayAt(1, 0).Y)
+	assert.Equal(t, mean(2, 4), scaled.GrayAt(0, 1).Y)
+	assert.Equal(t, mean(4, 4), scaled.GrayAt(1, 1).Y)
+
+	assert.Same(t, gray, unscaled.apply(gray))
+}
+
+func TestScalingRestore(t *testing.T) {
+	s := scaling{factor: 0.25, origin: image.Point{X: 10, Y: 20}}
+	symbol := &Symbol{Type: Square, Position: Position{X: 100, Y: 50}, Size: 30, Confidence: 0.9, Width: 40, Height: 10}
+
+	restored := s.restored(symbol)
+	assert.Equal(t, Position{X: 410, Y: 220}, restored.Position)
+	assert.Equal(t, 120.0, restored.Size)
+	assert.Equal(t, 160.0, restored.Width)
+	assert.Equal(t, 40.0, restored.Height)
+	assert.Equal(t, 0.9, restored.Confidence)
+	assert.Equal(t, Position{X: 100, Y: 50}, symbol.Position, "restored leaves the symbol alone")
+
+	s.restore([]*Symbol{symbol})
+	assert.Equal(t, restored, *symbol)
+}
+
+// offCenterScan places the drawing at scale 3 on a larger page, away from
+// its center, as if a small circle were scanned with the whole page
+func offCenterScan() (*image.RGBA, image.Point) {
+	offset := image.Point{X: 1000, Y: 600}
+	drawing := scaledDrawing(3)
+	page := createTestImage(2400, 2000)
+	draw.Draw(page, drawing.Bounds().Add(offset), drawing, image.Point{}, draw.Src)
+	return page, offset
+}
+
+func TestLocateOuterCircle(t *testing.T) {
+	d := NewDetector(Config{})
+	page, offset := offCenterScan()
+	crop := d.locateOuterCircle(d.toGrayscale(page))
+
+	// The circle is 3 * 2 * 196 pixels across, 12 pixels in from the drawing
+	circle := image.Rect(12, 12, 1188, 1188).Add(offset)
+	assert.True(t, circle.In(crop), "crop %v holds circle %v", crop, circle)
+	assert.Less(t, crop.Dx(), 1300)
+	assert.Less(t, crop.Dy(), 1300)
+
+	s := d.workingScale(d.toGrayscale(page))
+	assert.Equal(t, crop, s.crop)
+	assert.Equal(t, crop.Min, s.origin)
+	// Only the crop is scaled to the working size
+	working := s.apply(d.toGrayscale(page)).Bounds()
+	assert.Equal(t, image.Point{}, working.Min)
+	assert.InDelta(t, float64(crop.Dx())*s.factor, float64(working.Dx()), 1)
+}
+
+func TestLocateOuterCircleKeepsWholeImage(t *testing.T) {
+	d := NewDetector(Config{})
+	// Too small for a coarse pass
+	small := d.toGrayscale(createTestImageWithCircle(600, 600, 250))
+	assert.Equal(t, small.Bounds(), d.locateOuterCircle(small))
+
+	// No outer circle to crop to
+	blank := d.toGrayscale(createTestImage(2000, 2000))
+	assert.Equal(t, blank.Bounds(), d.locateOuterCircle(blank))
+}
+
+func TestDetectCroppedToOuterCircle(t *testing.T) {
+	if testing.Short() {
+		t.Skip("renders large images")
+	}
+
+	// Symbols found in the crop are mapped back to the whole page
+	d := NewDetector(Config{})
+	alone, _, err := d.run(context.Background(), d.sequentialPipeline(), scaledDrawing(3))
+	require.NoError(t, err)
+	require.NotEmpty(t, alone)
+	page, offset := offCenterScan()
+	placed, _, err := d.run(context.Background(), d.sequentialPipeline(), page)
+	require.NoError(t, err)
+
+	require.Len(t, placed, len(alone))
+	for i := range alone {
+		assert.Equal(t, alone[i].Type, placed[i].Type)
+		assert.InDelta(t, alone[i].Position.X+float64(offset.X), placed[i].Position.X, 9, "symbol %d", i)
+		assert.InDelta(t, alone[i].Position.Y+float64(offset.Y), placed[i].Position.Y, 9, "symbol %d", i)
+	}
+}
+
+func TestDetectIndependentOfResolution(t *testing.T) {
+	if testing.Short() {
+		t.Skip("renders large images")
+	}
+
+	// Both scans are larger than the working size, so both are detected at it
+	d := NewDetector(Config{})
+	detect := func(scale int) []*Symbol {
+		symbols, _, err := d.run(context.Background(), d.sequentialPipeline(), scaledDrawing(scale))
+		require.NoError(t, err)
+		require.NotEmpty(t, symbols)
+		return symbols
+	}
+	low, high := detect(3), detect(6)
+
+	require.Len(t, high, len(low))
+	for i := range low {
+		assert.Equal(t, low[i].Type, high[i].Type)
+		// Compare in the coordinates of the 400 pixel drawing
+		assert.InDelta(t, low[i].Position.X/3, high[i].Position.X/6, 3, "symbol %d", i)
+		assert.InDelta(t, low[i].Position.Y/3, high[i].Position.Y/6, 3, "symbol %d", i)
+		assert.InDelta(t, low[i].Size/3, high[i].Size/6, low[i].Size/3*0.1, "symbol %d", i)
+	}
+}
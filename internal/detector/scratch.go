@@ -0,0 +1,78 @@
+package detector
+
+import (
+	"image"
+	"sync"
+)
+
+// grayPool recycles the intermediate images of preprocessing so that a
+// detector shared by many goroutines does not allocate them on every call.
+// It is safe for concurrent use.
+type grayPool struct {
+	pool sync.Pool
+}
+
+// get returns an image with the given bounds. Its pixels are not cleared,
+// so callers must write every pixel before reading any.
+func (p *grayPool) get(bounds image.Rectangle) *image.Gray {
+	size := bounds.Dx() * bounds.Dy()
+	if img, ok := p.pool.Get().(*image.Gray); ok && cap(img.Pix) >= size {
+		img.Pix = img.Pix[:size]
+		img.Stride = bounds.Dx()
+		img.Rect = bounds
+		return img
+	}
+	return image.NewGray(bounds)
+}
+
+// put returns img to the pool. img must not be used afterwards.
+func (p *grayPool) put(img *image.Gray) {
+	p.pool.Put(img)
+}
+
+// contourBuffers is the scratch state of one findContours call
+type contourBuffers struct {
+	bounds image.Rectangle
+	seen   []bool        // Pixels already traced or filled, row by row
+	points []image.Point // Points of the contour being traced
+}
+
+// visited reports whether pt, which must be within bounds, has been visited
+func (b *contourBuffers) visited(pt image.Point) bool {
+	return b.seen[(pt.Y-b.bounds.Min.Y)*b.bounds.Dx()+pt.X-b.bounds.Min.X]
+}
+
+// visit marks pt, which must be within bounds, as visited
+func (b *contourBuffers) visit(pt image.Point) {
+	b.seen[(pt.Y-b.bounds.Min.Y)*b.bounds.Dx()+pt.X-b.bounds.Min.X] = true
+}
+
+// contourPool recycles the buffers of contour tracing, so that neither the
+// contours of one image nor successive frames of a StreamDetector allocate
+// them again. It is safe for concurrent use.
+type contourPool struct {
+	pool sync.Pool
+}
+
+// get returns buffers for an image with the given bounds and nothing visited
+func (p *contourPool) get(bounds image.Rectangle) *contourBuffers {
+	b, ok := p.pool.Get().(*contourBuffers)
+	if !ok {
+		b = &contourBuffers{}
+	}
+	size := bounds.Dx() * bounds.Dy()
+	if cap(b.seen) >= size {
+		b.seen = b.seen[:size]
+		clear(b.seen)
+	} else {
+		b.seen = make([]bool, size)
+	}
+	b.bounds = bounds
+	b.points = b.points[:0]
+	return b
+}
+
+// put returns b to the pool. b must not be used afterwards.
+func (p *contourPool) put(b *contourBuffers) {
+	p.pool.Put(b)
+}
@@ -0,0 +1,114 @@
+package detector
+
+import (
+	"context"
+	"image"
+	"sync"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestGrayPoolReusesBuffers(t *testing.T) {
+	var p grayPool
+
+	large := p.get(image.Rect(0, 0, 40, 30))
+	assert.Equal(t, image.Rect(0, 0, 40, 30), large.Bounds())
+	assert.Len(t, large.Pix, 40*30)
+	p.put(large)
+
+	// A smaller image fits in any recycled buffer
+	small := p.get(image.Rect(5, 5, 15, 10))
+	assert.Equal(t, image.Rect(5, 5, 15, 10), small.Bounds())
+	assert.Len(t, small.Pix, 10*5)
+	assert.Equal(t, 10, small.Stride)
+	small.SetGray(14, 9, small.GrayAt(14, 9))
+	p.put(small)
+
+	huge := p.get(image.Rect(0, 0, 100, 100))
+	assert.Len(t, huge.Pix, 100*100)
+}
+
+func TestContourPoolClearsVisited(t *testing.T) {
+	var p contourPool
+	bounds := image.Rect(10, 20, 30, 40)
+
+	buffers := p.get(bounds)
+	assert.False(t, buffers.visited(image.Pt(29, 39)))
+	buffers.visit(image.Pt(29, 39))
+	buffers.visit(image.Pt(10, 20))
+	assert.True(t, buffers.visited(image.Pt(29, 39)))
+	assert.False(t, buffers.visited(image.Pt(11, 20)))
+	buffers.points = append(buffers.points, image.Pt(10, 20))
+	p.put(buffers)
+
+	buffers = p.get(bounds)
+	assert.False(t, buffers.visited(image.Pt(29, 39)))
+	assert.False(t, buffers.visited(image.Pt(10, 20)))
+	assert.Empty(t, buffers.points)
+}
+
+func TestFindContoursCopiesKeptPoints(t *testing.T) {
+	d := NewDetector(Config{})
+	binary := d.preprocessImage(d.toGrayscale(createTestImageWithCircle(200, 200, 90)))
+
+	first := d.findContours(binary)
+	require.NotEmpty(t, first)
+	points := append([]image.Point(nil), first[0].Points...)
+
+	// Tracing again reuses the buffers but must not disturb earlier results
+	second := d.findContours(binary)
+	assert.Equal(t, points, first[0].Points)
+	assert.Equal(t, first, second)
+}
+
+func TestDetectorSharedBetweenGoroutines(t *testing.T) {
+	img := createTestImageWithCircle(200, 200, 90)
+	d := NewDetector(Config{})
+
+	expected, expectedConnections, err := d.run(context.Background(), d.sequentialPipeline(), img)
+	require.NoError(t, err)
+
+	var wg sync.WaitGroup
+	results := make([][]*Symbol, 8)
+	connections := make([][]Connection, 8)
+	for i := range results {
+		wg.Add(1)
+		go func(i int) {
+			defer wg.Done()
+			results[i], connections[i], _ = d.run(context.Background(), d.sequentialPipeline(), img)
+		}(i)
+	}
+	wg.Wait()
+
+	for i := range results {
+		assert.Equal(t, expected, results[i])
+		assert.Equal(t, expectedConnections, connections[i])
+	}
+}
+
+func TestParallelBackendSharedBetweenGoroutines(t *testing.T) {
+	img := createTestImageWithCircle(200, 200, 90)
+	pd := NewParallelDetectorV2(Config{})
+
+	expected, _, err := pd.DetectImage(img)
+	require.NoError(t, err)
+
+	var wg sync.WaitGroup
+	errs := make([]error, 8)
+	results := make([][]*Symbol, 8)
+	for i := range results {
+		wg.Add(1)
+		go func(i int) {
+			defer wg.Done()
+			results[i], _, errs[i] = pd.DetectImage(img)
+		}(i)
+	}
+	wg.Wait()
+
+	for i := range results {
+		require.NoError(t, errs[i])
+		assert.Equal(t, expected, results[i])
+	}
+}
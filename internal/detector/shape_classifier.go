@@ -34,6 +34,13 @@ func (d *Detector) classifyShape(contour Contour) SymbolType {
 		}
 	}
 
+	// Rectangles are told from squares by their sides and angles before the
+	// square checks below, which accept moderately wide shapes
+	quad := d.classifyQuadrilateral(approx)
+	if quad == quadRectangle {
+		return Rectangle
+	}
+
 	// Check for squares before star detection
 	// First check for small squares with low circularity
 	// Exclude triangles (3 vertices) and high circularity shapes (circles) from this check
@@ -131,8 +138,9 @@ func (d *Detector) classifyShape(contour Contour) SymbolType {
 
 	// Check for 4 vertices first (square detection)
 	if vertices == 4 {
-		// First try standard square detection
-		if d.isSquare(approx) {
+		// First try standard square detection. A rhombus is a square drawn
+		// askew.
+		if d.isSquare(approx) || quad == quadRhombus {
 			return Square
 		}
 		// Check if it's actually a rounded square misclassified as circle
@@ -0,0 +1,187 @@
+package detector
+
+import (
+	"context"
+	"errors"
+	"image"
+	"io"
+	"math"
+	"sort"
+)
+
+// Defaults for StreamDetector
+const (
+	DefaultMatchDistance = 20.0 // Pixels a symbol may move between frames and keep its ID
+	DefaultMoveThreshold = 2.0  // Pixels a symbol must move before a moved event
+)
+
+// FrameSource supplies successive frames, such as those of a camera.
+// NextFrame returns io.EOF when there are no more frames.
+type FrameSource interface {
+	NextFrame() (image.Image, error)
+}
+
+// EventKind is what happened to a tracked symbol
+type EventKind string
+
+// Event kinds
+const (
+	SymbolAppeared EventKind = "appeared"
+	SymbolMoved    EventKind = "moved"
+	SymbolRemoved  EventKind = "removed"
+)
+
+// Event reports a change to a tracked symbol between frames
+type Event struct {
+	Kind EventKind
+	// ID identifies the symbol for as long as it stays in view
+	ID     int
+	Symbol *Symbol
+	// From is the last reported position of a moved symbol
+	From Position
+	// Frame counts the frames processed, starting at 1
+	Frame int
+}
+
+// TrackedSymbol is a symbol currently in view and its stable ID
+type TrackedSymbol struct {
+	ID     int
+	Symbol *Symbol
+}
+
+// track is the tracking state of one symbol
+type track struct {
+	TrackedSymbol
+	reported Position
+}
+
+// StreamDetector detects symbols in successive frames and tracks them, so
+// that each symbol keeps the same ID while it moves. A symbol is matched to
+// the closest symbol of the same type in the previous frame within
+// MatchDistance. Unlike Detect, a frame without symbols is not an error.
+//
+// A StreamDetector is not safe for concurrent use; feed it frames in order.
+type StreamDetector struct {
+	// MatchDistance is the furthest, in pixels, a symbol may move between
+	// frames and still be the same symbol
+	MatchDistance float64
+	// MoveThreshold is how far, in pixels, a symbol must move from its last
+	// reported position before a moved event, so that jitter is ignored
+	MoveThreshold float64
+
+	detector    *Detector
+	tracks      []*track
+	connections []Connection
+	nextID      int
+	frame       int
+}
+
+// NewStreamDetector creates a stream detector with the default distances
+func NewStreamDetector(cfg Config) *StreamDetector {
+	return &StreamDetector{
+		MatchDistance: DefaultMatchDistance,
+		MoveThreshold: DefaultMoveThreshold,
+		detector:      NewDetector(cfg),
+		nextID:        1,
+	}
+}
+
+// Process detects the symbols in the next frame and returns what changed:
+// removed symbols, then moved ones, then new ones, each in ID order
+func (s *StreamDetector) Process(ctx context.Context, frame image.Image) ([]Event, error) {
+	symbols, connections, err := s.detector.run(ctx, s.detector.sequentialPipeline(), frame)
+	if err != nil {
+		return nil, err
+	}
+	s.connections = connections
+	return s.update(symbols), nil
+}
+
+// update matches the symbols of a new frame to the tracked symbols
+func (s *StreamDetector) update(symbols []*Symbol) []Event {
+	s.frame++
+
+	previous := make([]*Symbol, len(s.tracks))
+	for i, t := range s.tracks {
+		previous[i] = t.Symbol
+	}
+	matches := matchNearest(previous, symbols, s.MatchDistance)
+	matched := make(map[*Symbol]*Symbol, len(matches))
+	for symbol, p := range matches {
+		matched[p] = symbol
+	}
+
+	var removed, moved, appeared []Event
+	kept := make([]*track, 0, len(symbols))
+	for _, t := range s.tracks {
+		symbol, ok := matched[t.Symbol]
+		if !ok {
+			removed = append(removed, s.event(SymbolRemoved, t))
+			continue
+		}
+		t.Symbol = symbol
+		if math.Hypot(symbol.Position.X-t.reported.X, symbol.Position.Y-t.reported.Y) > s.MoveThreshold {
+			event := s.event(SymbolMoved, t)
+			event.From = t.reported
+			moved = append(moved, event)
+			t.reported = symbol.Position
+		}
+		kept = append(kept, t)
+	}
+	for _, symbol := range symbols {
+		if _, ok := matches[symbol]; ok {
+			continue
+		}
+		t := &track{TrackedSymbol: TrackedSymbol{ID: s.nextID, Symbol: symbol}, reported: symbol.Position}
+		s.nextID++
+		kept = append(kept, t)
+		appeared = append(appeared, s.event(SymbolAppeared, t))
+	}
+	s.tracks = kept
+
+	events := make([]Event, 0, len(removed)+len(moved)+len(appeared))
+	events = append(events, removed...)
+	events = append(events, moved...)
+	return append(events, appeared...)
+}
+
+// Run processes every frame from src, passing each event to handle, until
+// src returns io.EOF or ctx is done
+func (s *StreamDetector) Run(ctx context.Context, src FrameSource, handle func(Event)) error {
+	for {
+		frame, err := src.NextFrame()
+		if errors.Is(err, io.EOF) {
+			return nil
+		}
+		if err != nil {
+			return err
+		}
+
+		events, err := s.Process(ctx, frame)
+		if err != nil {
+			return err
+		}
+		for _, event := range events {
+			handle(event)
+		}
+	}
+}
+
+// Symbols returns the symbols in view after the last frame, in ID order
+func (s *StreamDetector) Symbols() []TrackedSymbol {
+	symbols := make([]TrackedSymbol, len(s.tracks))
+	for i, t := range s.tracks {
+		symbols[i] = t.TrackedSymbol
+	}
+	sort.Slice(symbols, func(i, j int) bool { return symbols[i].ID < symbols[j].ID })
+	return symbols
+}
+
+// Connections returns the connections detected in the last frame
+func (s *StreamDetector) Connections() []Connection {
+	return s.connections
+}
+
+func (s *StreamDetector) event(kind EventKind, t *track) Event {
+	return Event{Kind: kind, ID: t.ID, Symbol: t.Symbol, Frame: s.frame}
+}
@@ -0,0 +1,113 @@
+package detector
+
+import (
+	"context"
+	"image"
+	"io"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func streamSymbol(symbolType SymbolType, x, y float64) *Symbol {
+	return &Symbol{Type: symbolType, Position: Position{X: x, Y: y}}
+}
+
+func TestStreamTracking(t *testing.T) {
+	s := NewStreamDetector(Config{})
+
+	star := streamSymbol(Star, 100, 100)
+	square := streamSymbol(Square, 200, 100)
+	events := s.update([]*Symbol{star, square})
+	require.Len(t, events, 2)
+	assert.Equal(t, Event{Kind: SymbolAppeared, ID: 1, Symbol: star, Frame: 1}, events[0])
+	assert.Equal(t, Event{Kind: SymbolAppeared, ID: 2, Symbol: square, Frame: 1}, events[1])
+
+	// Jitter below the move threshold is not reported
+	events = s.update([]*Symbol{streamSymbol(Square, 201, 100), streamSymbol(Star, 100, 101)})
+	assert.Empty(t, events)
+
+	// A symbol that moves keeps its ID; one that leaves is removed and a
+	// new one gets a fresh ID
+	movedStar := streamSymbol(Star, 110, 100)
+	circle := streamSymbol(Circle, 300, 300)
+	events = s.update([]*Symbol{circle, movedStar})
+	require.Len(t, events, 3)
+	assert.Equal(t, SymbolRemoved, events[0].Kind)
+	assert.Equal(t, 2, events[0].ID)
+	assert.Equal(t, Event{Kind: SymbolMoved, ID: 1, Symbol: movedStar, From: Position{X: 100, Y: 100}, Frame: 3}, events[1])
+	assert.Equal(t, Event{Kind: SymbolAppeared, ID: 3, Symbol: circle, Frame: 3}, events[2])
+
+	assert.Equal(t, []TrackedSymbol{{ID: 1, Symbol: movedStar}, {ID: 3, Symbol: circle}}, s.Symbols())
+}
+
+func TestStreamTrackingMatchesClosestOfSameType(t *testing.T) {
+	s := NewStreamDetector(Config{})
+	s.update([]*Symbol{streamSymbol(Star, 100, 100), streamSymbol(Star, 130, 100)})
+
+	// Both stars shift right; each keeps the ID of the star it is closest to
+	left, right := streamSymbol(Star, 105, 100), streamSymbol(Star, 135, 100)
+	s.update([]*Symbol{right, left})
+	assert.Equal(t, []TrackedSymbol{{ID: 1, Symbol: left}, {ID: 2, Symbol: right}}, s.Symbols())
+
+	// A symbol further than the match distance is a different symbol
+	far := streamSymbol(Star, 105, 200)
+	events := s.update([]*Symbol{far, right})
+	require.Len(t, events, 2)
+	assert.Equal(t, SymbolRemoved, events[0].Kind)
+	assert.Equal(t, 1, events[0].ID)
+	assert.Equal(t, SymbolAppeared, events[1].Kind)
+	assert.Equal(t, 3, events[1].ID)
+}
+
+// frameSlice is a FrameSource over fixed frames
+type frameSlice []image.Image
+
+func (f *frameSlice) NextFrame() (image.Image, error) {
+	if len(*f) == 0 {
+		return nil, io.EOF
+	}
+	frame := (*f)[0]
+	*f = (*f)[1:]
+	return frame, nil
+}
+
+func TestStreamRun(t *testing.T) {
+	circle := createTestImageWithCircle(200, 200, 90)
+	frames := frameSlice{circle, circle, createTestImage(200, 200)}
+
+	var events []Event
+	s := NewStreamDetector(Config{})
+	require.NoError(t, s.Run(context.Background(), &frames, func(e Event) {
+		events = append(events, e)
+	}))
+
+	// Everything seen in the first frame appears, stays put in the second
+	// and is removed when the third frame is blank
+	require.NotEmpty(t, events)
+	appeared := 0
+	for _, e := range events {
+		switch e.Kind {
+		case SymbolAppeared:
+			assert.Equal(t, 1, e.Frame)
+			appeared++
+		case SymbolRemoved:
+			assert.Equal(t, 3, e.Frame)
+		default:
+			t.Errorf("unexpected event %+v", e)
+		}
+	}
+	assert.Len(t, events, 2*appeared)
+	assert.Empty(t, s.Symbols())
+}
+
+func TestStreamRunCancelled(t *testing.T) {
+	ctx, cancel := context.WithCancel(context.Background())
+	cancel()
+
+	frames := frameSlice{createTestImage(50, 50)}
+	err := NewStreamDetector(Config{}).Run(ctx, &frames, func(Event) {})
+	require.Error(t, err)
+	assert.Empty(t, frames)
+}
@@ -0,0 +1,52 @@
+package detector
+
+import (
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+)
+
+// Strictness decides what becomes of contours inside the outer circle that
+// match no symbol
+type Strictness string
+
+const (
+	// Lenient drops unrecognized contours during detection without a word,
+	// while the parser still reads any Unknown symbol it is given as part of
+	// an expression. This is the default.
+	Lenient Strictness = ""
+	// Strict keeps unrecognized contours as Unknown symbols and fails
+	// detection and parsing on any of them
+	Strict Strictness = "strict"
+	// Permissive keeps unrecognized contours as Unknown symbols so that the
+	// parser can warn of each before ignoring it
+	Permissive Strictness = "permissive"
+)
+
+// keepsUnknown reports whether contours that match no symbol are kept as
+// Unknown symbols rather than dropped
+func (d *Detector) keepsUnknown() bool {
+	return d.strictness == Strict || d.strictness == Permissive
+}
+
+// unrecognizedError returns an error listing the Unknown symbols, or nil
+// when there are none
+func unrecognizedError(symbols []*Symbol, imagePath string) error {
+	var related []error
+	for _, sym := range symbols {
+		if sym.Type != Unknown {
+			continue
+		}
+		related = append(related, grimoireErrors.NewError(grimoireErrors.InvalidSymbolShape,
+			i18n.Tf("detector.unrecognized_shape", sym.Position.X, sym.Position.Y)).
+			WithLocation(imagePath, 0, 0).
+			WithRegion(sym.Position.X, sym.Position.Y, sym.Size))
+	}
+	if len(related) == 0 {
+		return nil
+	}
+	return grimoireErrors.NewError(grimoireErrors.InvalidSymbolShape,
+		i18n.Tf("detector.unrecognized_shapes", len(related))).
+		WithLocation(imagePath, 0, 0).
+		WithSuggestion(i18n.T("detector.unrecognized_shapes_suggestion")).
+		WithRelated(related...)
+}
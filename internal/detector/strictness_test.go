@@ -0,0 +1,40 @@
+package detector
+
+import (
+	"testing"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestValidateResultsStrictness(t *testing.T) {
+	symbols := []*Symbol{
+		{Type: OuterCircle, Position: Position{X: 200, Y: 200}, Size: 180},
+		{Type: Star, Position: Position{X: 200, Y: 260}, Size: 30},
+		{Type: Unknown, Position: Position{X: 120, Y: 150}, Size: 20},
+	}
+
+	for _, strictness := range []Strictness{Lenient, Permissive} {
+		d := NewDetector(Config{Strictness: strictness})
+		assert.NoError(t, d.validateResults(symbols, "circle.png"), strictness)
+	}
+
+	d := NewDetector(Config{Strictness: Strict})
+	err := d.validateResults(symbols, "circle.png")
+	assertErrorType(t, err, grimoireErrors.InvalidSymbolShape)
+	var gErr *grimoireErrors.GrimoireError
+	require.ErrorAs(t, err, &gErr)
+	require.Len(t, gErr.Related, 1)
+	require.ErrorAs(t, gErr.Related[0], &gErr)
+	require.NotNil(t, gErr.Region)
+	assert.Equal(t, 110, gErr.Region.X)
+
+	assert.NoError(t, d.validateResults(symbols[:2], "circle.png"))
+}
+
+func TestKeepsUnknown(t *testing.T) {
+	assert.False(t, NewDetector(Config{}).keepsUnknown())
+	assert.True(t, NewDetector(Config{Strictness: Strict}).keepsUnknown())
+	assert.True(t, NewDetector(Config{Strictness: Permissive}).keepsUnknown())
+}
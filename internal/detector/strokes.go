@@ -0,0 +1,249 @@
+package detector
+
+import (
+	"context"
+	"encoding/json"
+	"image"
+	"io"
+	"math"
+	"sort"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/security"
+)
+
+const (
+	// strokePenRadius is the radius strokes are drawn with when rasterized
+	// for the internal patterns of symbols
+	strokePenRadius = 1
+	// strokeMargin pads a drawing without a size around its strokes
+	strokeMargin = 10
+	// strokeClosure is how near, at least, the end of a stroke must come to
+	// its start for the stroke to outline a shape
+	strokeClosure = 8.0
+	// strokeReach is how far the end of a line may stop short of a symbol
+	// and still connect to it
+	strokeReach = 20.0
+)
+
+// StrokePoint is a sample of the pen. T is when it was taken, in
+// milliseconds, when the source records it.
+type StrokePoint struct {
+	X float64 `json:"x"`
+	Y float64 `json:"y"`
+	T float64 `json:"t,omitempty"`
+}
+
+// Stroke is the path of the pen from putting it down to lifting it
+type Stroke struct {
+	Points []StrokePoint `json:"points"`
+}
+
+// StrokeDrawing is a magic circle recorded as pen strokes, as a drawing
+// tablet or an interactive client captures it, in place of an image. Width
+// and Height, when set, give the size of the canvas.
+type StrokeDrawing struct {
+	Width   int      `json:"width,omitempty"`
+	Height  int      `json:"height,omitempty"`
+	Strokes []Stroke `json:"strokes"`
+}
+
+// DecodeStrokes reads a stroke drawing encoded as JSON, such as
+//
+//	{"width": 800, "height": 800, "strokes": [{"points": [{"x": 10, "y": 20, "t": 0}, ...]}]}
+func DecodeStrokes(r io.Reader) (*StrokeDrawing, error) {
+	var drawing StrokeDrawing
+	if err := json.NewDecoder(r).Decode(&drawing); err != nil {
+		return nil, grimoireErrors.NewError(grimoireErrors.UnsupportedFormat, i18n.T("detector.strokes_decode_failed")).
+			WithInnerError(err)
+	}
+	if len(drawing.Strokes) == 0 {
+		return nil, grimoireErrors.NoSymbolsError()
+	}
+	return &drawing, nil
+}
+
+// DetectStrokes builds symbols and connections from the geometry of pen
+// strokes rather than from pixels. A stroke that ends where it started
+// outlines a symbol and is classified like a traced contour; any other
+// stroke is a line, connecting the symbols its ends stop at in the
+// direction the pen moved. Shapes drawn in several strokes are not joined.
+// source names the drawing in errors.
+func (d *Detector) DetectStrokes(drawing *StrokeDrawing, source string) ([]*Symbol, []Connection, error) {
+	canvas := strokeCanvas(drawing)
+	if pixels := int64(canvas.Dx()) * int64(canvas.Dy()); pixels > security.NewImageValidator().MaxPixels && !d.allowHugeImages {
+		return nil, nil, grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("detector.stroke_canvas_too_large")).
+			WithLocation(source, 0, 0).
+			WithSuggestion(i18n.T("detector.stroke_canvas_too_large_suggestion"))
+	}
+	if !strokesWithin(drawing, canvas) {
+		return nil, nil, grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("detector.strokes_outside_canvas")).
+			WithLocation(source, 0, 0)
+	}
+	binary := rasterizeStrokes(drawing, canvas)
+
+	var contours []Contour
+	var lines []int // indices of the strokes that are lines
+	for i, stroke := range drawing.Strokes {
+		if len(stroke.Points) == 0 {
+			continue
+		}
+		if !strokeClosed(stroke) {
+			lines = append(lines, i)
+			continue
+		}
+		contour := Contour{Points: strokePixels(stroke)}
+		contour.calculateProperties()
+		contours = append(contours, contour)
+	}
+	// The outer circle is looked for among the first contours
+	sort.SliceStable(contours, func(i, j int) bool { return contours[i].Area > contours[j].Area })
+
+	symbols := d.classifyContours(context.Background(), contours, binary, nil)
+	symbols = d.dropUnconfident(symbols)
+	symbols = d.deduplicateNearbyStars(symbols)
+	markRingGlyphs(symbols)
+
+	connections := connectStrokes(interiorSymbols(symbols), drawing.Strokes, lines)
+	AssignIDs(symbols)
+	if err := d.validateResults(symbols, source); err != nil {
+		return nil, nil, err
+	}
+	return symbols, connections, nil
+}
+
+// strokeClosed reports whether a stroke ends near enough to its start to
+// outline a shape
+func strokeClosed(stroke Stroke) bool {
+	if len(stroke.Points) < 3 {
+		return false
+	}
+	length := 0.0
+	for i := 1; i < len(stroke.Points); i++ {
+		length += pointDistance(stroke.Points[i-1], stroke.Points[i])
+	}
+	gap := pointDistance(stroke.Points[0], stroke.Points[len(stroke.Points)-1])
+	return gap <= math.Max(strokeClosure, 0.1*length) && gap < length/3
+}
+
+// strokePixels returns the pixels the pen passed over, one step apart, as
+// contour tracing would
+func strokePixels(stroke Stroke) []image.Point {
+	var points []image.Point
+	add := func(x, y float64) {
+		p := image.Pt(int(math.Round(x)), int(math.Round(y)))
+		if len(points) == 0 || points[len(points)-1] != p {
+			points = append(points, p)
+		}
+	}
+	add(stroke.Points[0].X, stroke.Points[0].Y)
+	for i := 1; i < len(stroke.Points); i++ {
+		a, b := stroke.Points[i-1], stroke.Points[i]
+		steps := int(math.Ceil(pointDistance(a, b)))
+		for s := 1; s <= steps; s++ {
+			t := float64(s) / float64(steps)
+			add(a.X+(b.X-a.X)*t, a.Y+(b.Y-a.Y)*t)
+		}
+	}
+	return points
+}
+
+// strokeCanvas returns the drawing's canvas, or the strokes' extent when it
+// has no size
+func strokeCanvas(drawing *StrokeDrawing) image.Rectangle {
+	if drawing.Width > 0 && drawing.Height > 0 {
+		return image.Rect(0, 0, drawing.Width, drawing.Height)
+	}
+	width, height := 0.0, 0.0
+	for _, stroke := range drawing.Strokes {
+		for _, p := range stroke.Points {
+			width, height = math.Max(width, p.X), math.Max(height, p.Y)
+		}
+	}
+	// Clamped so that a stray point cannot overflow the size
+	limit := float64(security.NewImageValidator().MaxPixels)
+	return image.Rect(0, 0, int(math.Min(limit, math.Ceil(width)))+strokeMargin, int(math.Min(limit, math.Ceil(height)))+strokeMargin)
+}
+
+// strokesWithin reports whether every point of the drawing lies on canvas,
+// give or take strokeMargin
+func strokesWithin(drawing *StrokeDrawing, canvas image.Rectangle) bool {
+	for _, stroke := range drawing.Strokes {
+		for _, p := range stroke.Points {
+			// Written so that NaN fails too
+			if !(p.X >= -strokeMargin && p.X <= float64(canvas.Max.X+strokeMargin) &&
+				p.Y >= -strokeMargin && p.Y <= float64(canvas.Max.Y+strokeMargin)) {
+				return false
+			}
+		}
+	}
+	return true
+}
+
+// rasterizeStrokes draws every stroke as foreground on a binary image of
+// canvas
+func rasterizeStrokes(drawing *StrokeDrawing, canvas image.Rectangle) *image.Gray {
+	binary := image.NewGray(canvas)
+	for _, stroke := range drawing.Strokes {
+		if len(stroke.Points) == 0 {
+			continue
+		}
+		for _, p := range strokePixels(stroke) {
+			for dy := -strokePenRadius; dy <= strokePenRadius; dy++ {
+				for dx := -strokePenRadius; dx <= strokePenRadius; dx++ {
+					if q := p.Add(image.Pt(dx, dy)); q.In(binary.Bounds()) {
+						binary.Pix[q.Y*binary.Stride+q.X] = 255
+					}
+				}
+			}
+		}
+	}
+	return binary
+}
+
+// connectStrokes connects, for each of the strokes that lines indexes, the
+// symbol nearest its start to the one nearest its end. Each connection
+// records the index of its stroke in the stroke property. The outer circle
+// frames the program and is not connected.
+func connectStrokes(symbols []*Symbol, strokes []Stroke, lines []int) []Connection {
+	var connections []Connection
+	for _, i := range lines {
+		line := strokes[i]
+		from := strokeEndSymbol(symbols, line.Points[0])
+		to := strokeEndSymbol(symbols, line.Points[len(line.Points)-1])
+		if from == nil || to == nil || from == to {
+			continue
+		}
+		connections = append(connections, Connection{
+			From:           from,
+			To:             to,
+			ConnectionType: "solid",
+			Properties:     map[string]interface{}{"stroke": i},
+		})
+	}
+	return connections
+}
+
+// strokeEndSymbol returns the symbol whose edge is nearest p, or nil when
+// none is within strokeReach
+func strokeEndSymbol(symbols []*Symbol, p StrokePoint) *Symbol {
+	var nearest *Symbol
+	best := strokeReach
+	for _, s := range symbols {
+		if s.Type == OuterCircle {
+			continue
+		}
+		// Size is the square root of the area, about 1.8 radii of a circle
+		edge := math.Hypot(s.Position.X-p.X, s.Position.Y-p.Y) - s.Size*0.56
+		if edge <= best {
+			nearest, best = s, edge
+		}
+	}
+	return nearest
+}
+
+// pointDistance returns the distance between two pen samples
+func pointDistance(a, b StrokePoint) float64 {
+	return math.Hypot(b.X-a.X, b.Y-a.Y)
+}
@@ -0,0 +1,99 @@
+package detector
+
+import (
+	"math"
+	"strings"
+	"testing"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// circleStroke returns a closed stroke around (cx, cy), sampled every few
+// pixels as a tablet would
+func circleStroke(cx, cy, r float64) Stroke {
+	var points []StrokePoint
+	steps := int(2 * math.Pi * r / 3)
+	for i := 0; i <= steps; i++ {
+		angle := 2 * math.Pi * float64(i) / float64(steps)
+		points = append(points, StrokePoint{X: cx + r*math.Cos(angle), Y: cy + r*math.Sin(angle), T: float64(i)})
+	}
+	return Stroke{Points: points}
+}
+
+func lineStroke(x0, y0, x1, y1 float64) Stroke {
+	return Stroke{Points: []StrokePoint{{X: x0, Y: y0}, {X: (x0 + x1) / 2, Y: (y0 + y1) / 2}, {X: x1, Y: y1}}}
+}
+
+func strokeDrawing(line Stroke) *StrokeDrawing {
+	return &StrokeDrawing{
+		Width:  800,
+		Height: 800,
+		Strokes: []Stroke{
+			circleStroke(400, 400, 300),
+			circleStroke(300, 400, 30),
+			circleStroke(500, 400, 30),
+			line,
+		},
+	}
+}
+
+func TestDetectStrokes(t *testing.T) {
+	d := NewDetector(Config{})
+	symbols, connections, err := d.DetectStrokes(strokeDrawing(lineStroke(332, 400, 468, 400)), "drawing.json")
+	require.NoError(t, err)
+	require.NotEmpty(t, symbols)
+	assert.Equal(t, OuterCircle, symbols[0].Type)
+
+	require.Len(t, connections, 1)
+	assert.Less(t, connections[0].From.Position.X, 400.0)
+	assert.Greater(t, connections[0].To.Position.X, 400.0)
+	assert.Equal(t, 3, connections[0].Properties["stroke"])
+}
+
+func TestDetectStrokesFollowsThePen(t *testing.T) {
+	d := NewDetector(Config{})
+	_, connections, err := d.DetectStrokes(strokeDrawing(lineStroke(468, 400, 332, 400)), "drawing.json")
+	require.NoError(t, err)
+	require.Len(t, connections, 1)
+	assert.Greater(t, connections[0].From.Position.X, 400.0)
+	assert.Less(t, connections[0].To.Position.X, 400.0)
+}
+
+func TestDecodeStrokes(t *testing.T) {
+	drawing, err := DecodeStrokes(strings.NewReader(`{"width": 100, "height": 80, "strokes": [{"points": [{"x": 1, "y": 2, "t": 3}]}]}`))
+	require.NoError(t, err)
+	assert.Equal(t, 100, drawing.Width)
+	assert.Equal(t, 80, drawing.Height)
+	assert.Equal(t, []Stroke{{Points: []StrokePoint{{X: 1, Y: 2, T: 3}}}}, drawing.Strokes)
+
+	_, err = DecodeStrokes(strings.NewReader(`{"strokes": `))
+	assertErrorType(t, err, grimoireErrors.UnsupportedFormat)
+
+	_, err = DecodeStrokes(strings.NewReader(`{"strokes": []}`))
+	assertErrorType(t, err, grimoireErrors.NoSymbolsDetected)
+}
+
+func TestDetectStrokesRejectsUnsafeDrawings(t *testing.T) {
+	d := NewDetector(Config{})
+
+	huge := &StrokeDrawing{Strokes: []Stroke{lineStroke(0, 0, 1e9, 1e9)}}
+	_, _, err := d.DetectStrokes(huge, "drawing.json")
+	assertErrorType(t, err, grimoireErrors.ValidationError)
+
+	outside := &StrokeDrawing{Width: 100, Height: 100, Strokes: []Stroke{lineStroke(10, 10, 500, 10)}}
+	_, _, err = d.DetectStrokes(outside, "drawing.json")
+	assertErrorType(t, err, grimoireErrors.ValidationError)
+
+	nan := &StrokeDrawing{Width: 100, Height: 100, Strokes: []Stroke{lineStroke(10, 10, math.NaN(), 10)}}
+	_, _, err = d.DetectStrokes(nan, "drawing.json")
+	assertErrorType(t, err, grimoireErrors.ValidationError)
+}
+
+func assertErrorType(t *testing.T, err error, errorType grimoireErrors.ErrorType) {
+	t.Helper()
+	var gErr *grimoireErrors.GrimoireError
+	require.ErrorAs(t, err, &gErr)
+	assert.Equal(t, errorType, gErr.Type)
+}
@@ -0,0 +1,124 @@
+package detector
+
+import (
+	"context"
+	"image"
+	"testing"
+	"time"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// slowPipeline returns stages that classify one star only once ctx is done,
+// as if the contours took longer than any deadline
+func slowPipeline(t *testing.T, d *Detector) pipeline {
+	p := d.sequentialPipeline()
+	p.detectSymbols = func(ctx context.Context, contours []Contour, binary *image.Gray) []*Symbol {
+		<-ctx.Done()
+		return []*Symbol{{Type: Star, Position: Position{X: 50, Y: 50}, Size: 20, Confidence: 1, Pattern: PatternEmpty}}
+	}
+	p.detectConnections = func(ctx context.Context, binary *image.Gray, symbols []*Symbol) []Connection {
+		t.Error("connections were detected past the deadline")
+		return nil
+	}
+	return p
+}
+
+func TestTimeoutReturnsPartialSymbols(t *testing.T) {
+	d := NewDetector(Config{Timeout: 100 * time.Millisecond})
+
+	symbols, connections, err := d.run(context.Background(), slowPipeline(t, d), whiteImage())
+	require.Error(t, err)
+	assert.True(t, IsPartial(err))
+	require.Len(t, symbols, 1)
+	assert.Equal(t, Star, symbols[0].Type)
+	assert.NotEmpty(t, symbols[0].ID)
+	assert.Nil(t, connections)
+}
+
+func TestTimeoutWhileFindingContours(t *testing.T) {
+	d := NewDetector(Config{Timeout: 100 * time.Millisecond})
+	p := d.sequentialPipeline()
+	p.findContours = func(ctx context.Context, binary *image.Gray) []Contour {
+		<-ctx.Done()
+		return nil
+	}
+	p.detectSymbols = func(ctx context.Context, contours []Contour, binary *image.Gray) []*Symbol {
+		t.Error("symbols were classified past the deadline")
+		return nil
+	}
+
+	symbols, connections, err := d.run(context.Background(), p, whiteImage())
+	assert.True(t, IsPartial(err))
+	assert.Empty(t, symbols)
+	assert.Nil(t, connections)
+}
+
+func TestTimeoutWhileDetectingConnections(t *testing.T) {
+	d := NewDetector(Config{Timeout: 100 * time.Millisecond})
+	p := d.sequentialPipeline()
+	p.detectSymbols = func(ctx context.Context, contours []Contour, binary *image.Gray) []*Symbol {
+		return []*Symbol{{Type: Star, Position: Position{X: 50, Y: 50}, Size: 20, Confidence: 1, Pattern: PatternEmpty}}
+	}
+	p.detectConnections = func(ctx context.Context, binary *image.Gray, symbols []*Symbol) []Connection {
+		<-ctx.Done()
+		return []Connection{{From: symbols[0], To: symbols[0], ConnectionType: "solid"}}
+	}
+
+	// The connections found before the deadline are incomplete, so none
+	// are returned
+	symbols, connections, err := d.run(context.Background(), p, whiteImage())
+	assert.True(t, IsPartial(err))
+	require.Len(t, symbols, 1)
+	assert.NotEmpty(t, symbols[0].ID)
+	assert.Nil(t, connections)
+}
+
+func TestTimeoutSkipsValidation(t *testing.T) {
+	// Nothing is found in time, which is reported as partial rather than
+	// as an image without symbols
+	d := NewDetector(Config{Timeout: time.Nanosecond})
+	symbols, _, err := d.DetectImage(whiteImage())
+	require.Error(t, err)
+	assert.True(t, IsPartial(err))
+	assert.Empty(t, symbols)
+
+	var gErr *grimoireErrors.GrimoireError
+	require.ErrorAs(t, err, &gErr)
+	assert.Equal(t, imageSource, gErr.FileName)
+	assert.NotEmpty(t, gErr.Suggestion)
+}
+
+func TestTimeoutNotReached(t *testing.T) {
+	d := NewDetector(Config{Timeout: time.Minute})
+	_, _, err := d.run(context.Background(), d.sequentialPipeline(), whiteImage())
+	require.NoError(t, err)
+	assert.False(t, IsPartial(err))
+	assert.False(t, IsPartial(grimoireErrors.NoSymbolsError()))
+}
+
+func TestCancelledWithTimeout(t *testing.T) {
+	// Cancelling the caller's context is not a timeout
+	ctx, cancel := context.WithCancel(context.Background())
+	cancel()
+
+	d := NewDetector(Config{Timeout: time.Minute})
+	symbols, _, err := d.run(ctx, slowPipeline(t, d), whiteImage())
+	var gErr *grimoireErrors.GrimoireError
+	require.ErrorAs(t, err, &gErr)
+	assert.Equal(t, grimoireErrors.Cancelled, gErr.Type)
+	assert.Nil(t, symbols)
+}
+
+func TestDetectEachReportsTimeout(t *testing.T) {
+	d := NewDetector(Config{Timeout: time.Nanosecond})
+	var yielded []*Symbol
+	err := d.DetectEach(context.Background(), whiteImage(), OrderFinal, func(s *Symbol) bool {
+		yielded = append(yielded, s)
+		return true
+	})
+	assert.True(t, IsPartial(err))
+	assert.Empty(t, yielded)
+}
@@ -1,5 +1,7 @@
 package detector
 
+import "math"
+
 // SymbolType represents the type of detected symbol
 type SymbolType string
 
@@ -9,6 +11,7 @@ const (
 	Circle           SymbolType = "circle"
 	DoubleCircle     SymbolType = "double_circle"
 	Square           SymbolType = "square"
+	Rectangle        SymbolType = "rectangle"
 	Triangle         SymbolType = "triangle"
 	Pentagon         SymbolType = "pentagon"
 	Hexagon          SymbolType = "hexagon"
@@ -56,20 +59,70 @@ const (
 	PatternHalfCircle = "half_circle"
 )
 
+// SymbolTypes returns every symbol type the detector can recognize, in reference order
+func SymbolTypes() []SymbolType {
+	return []SymbolType{
+		OuterCircle, Circle, DoubleCircle, Square, Rectangle, Triangle, Pentagon, Hexagon,
+		Star, SixPointedStar, EightPointedStar,
+		Convergence, Divergence, Amplification, Distribution, Transfer, Seal, Circulation,
+		Equal, NotEqual, LessThan, GreaterThan, LessEqual, GreaterEqual,
+		LogicalAnd, LogicalOr, LogicalNot, LogicalXor,
+	}
+}
+
+// Patterns returns every internal pattern the detector can recognize
+func Patterns() []string {
+	return []string{
+		PatternEmpty, PatternDot, PatternDoubleDot, PatternTripleDot,
+		PatternLines, PatternTripleLine, PatternCross, PatternHalfCircle,
+	}
+}
+
 // Position represents a position in the image
 type Position struct {
-	X float64
-	Y float64
+	X float64 `json:"x"`
+	Y float64 `json:"y"`
 }
 
 // Symbol represents a detected symbol in the image
 type Symbol struct {
-	Type       SymbolType
-	Position   Position
-	Size       float64
-	Confidence float64
-	Pattern    string // Internal pattern (dots, lines, etc.)
-	Properties map[string]interface{}
+	// ID names the symbol across detections of the same image, as assigned
+	// by AssignIDs or ReconcileIDs
+	ID         string                 `json:"id,omitempty"`
+	Type       SymbolType             `json:"type"`
+	Position   Position               `json:"position"`
+	Size       float64                `json:"size"`
+	Confidence float64                `json:"confidence"`
+	Pattern    string                 `json:"pattern"` // Internal pattern (dots, lines, etc.)
+	Properties map[string]interface{} `json:"properties,omitempty"`
+
+	// OnRing is set for glyphs drawn on the outer circle, which declare
+	// program metadata instead of being part of the program
+	OnRing bool `json:"onRing,omitempty"`
+	// StageRing is set for circles drawn concentric with the outer circle,
+	// which divide the program into bands run from the innermost outward
+	StageRing bool `json:"stageRing,omitempty"`
+	// ListFrame is set for wide rectangles drawn around a row of symbols,
+	// which form a list of them. Width and Height are the extent of a list
+	// frame or a rectangle.
+	ListFrame bool    `json:"listFrame,omitempty"`
+	Width     float64 `json:"width,omitempty"`
+	Height    float64 `json:"height,omitempty"`
+	// Alternatives are the other types a borderline shape scores nearly as
+	// well as, best first, so that the parser can fall back to one of them
+	Alternatives []Alternative `json:"alternatives,omitempty"`
+}
+
+// Alternative is another reading of an ambiguous symbol
+type Alternative struct {
+	Type       SymbolType `json:"type"`
+	Confidence float64    `json:"confidence"`
+}
+
+// Radius approximates the outline of the symbol by a circle with its area.
+// Size is the square root of the contour area.
+func (s *Symbol) Radius() float64 {
+	return s.Size / math.Sqrt(math.Pi)
 }
 
 // Connection represents a connection between symbols
@@ -78,6 +131,11 @@ type Connection struct {
 	To             *Symbol
 	ConnectionType string // solid, dashed, wavy, etc.
 	Properties     map[string]interface{}
+
+	// FromAnchor and ToAnchor are where the line meets each symbol, or nil
+	// when unknown
+	FromAnchor *Anchor
+	ToAnchor   *Anchor
 }
 
 // DetectionResult contains all detected symbols and connections
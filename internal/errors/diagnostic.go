@@ -0,0 +1,165 @@
+package errors
+
+import (
+	"encoding/json"
+	stderrors "errors"
+	"fmt"
+	"io"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+)
+
+// Severity is how serious a diagnostic is
+type Severity string
+
+const (
+	SeverityError   Severity = "error"
+	SeverityWarning Severity = "warning"
+)
+
+// unknownErrorCode is used for errors that are not GrimoireErrors
+const unknownErrorCode ErrorCode = "E0000"
+
+// ANSI escape sequences used when rendering with color
+const (
+	ansiReset  = "\x1b[0m"
+	ansiBold   = "\x1b[1m"
+	ansiRed    = "\x1b[1;31m"
+	ansiYellow = "\x1b[1;33m"
+	ansiBlue   = "\x1b[34m"
+	ansiCyan   = "\x1b[36m"
+)
+
+// Diagnostic is a structured, renderable description of a problem
+type Diagnostic struct {
+	Code     ErrorCode `json:"code"`
+	Severity Severity  `json:"severity"`
+	Type     ErrorType `json:"type,omitempty"`
+	Message  string    `json:"message"`
+	Details  string    `json:"details,omitempty"`
+	Help     string    `json:"help,omitempty"`
+	File     string    `json:"file,omitempty"`
+	Region   *Region   `json:"region,omitempty"`
+	Cause    string    `json:"cause,omitempty"`
+	// Thumbnail is the path of a cropped image of Region, when one was written
+	Thumbnail string `json:"thumbnail,omitempty"`
+}
+
+// Diagnostics converts an error into diagnostics. Aggregate errors produce
+// one diagnostic per related error; anything else produces exactly one.
+func Diagnostics(err error) []Diagnostic {
+	if err == nil {
+		return nil
+	}
+
+	var ge *GrimoireError
+	code := unknownErrorCode
+	file := ""
+	if enhanced, ok := err.(*EnhancedError); ok {
+		ge = enhanced.GrimoireError
+		code = enhanced.Code
+		if input, ok := enhanced.Context["input_file"].(string); ok {
+			file = input
+		}
+	} else if !stderrors.As(err, &ge) {
+		return []Diagnostic{{Code: code, Severity: SeverityError, Message: err.Error()}}
+	}
+
+	if ge.FileName != "" {
+		file = ge.FileName
+	}
+	if len(ge.Related) > 0 {
+		var diags []Diagnostic
+		for _, related := range ge.Related {
+			for _, d := range Diagnostics(related) {
+				if d.File == "" {
+					d.File = file
+				}
+				diags = append(diags, d)
+			}
+		}
+		return diags
+	}
+
+	if code == unknownErrorCode {
+		if c, ok := errorCodeMap[ge.Type]; ok {
+			code = c
+		}
+	}
+	d := Diagnostic{
+		Code:     code,
+		Severity: SeverityError,
+		Type:     ge.Type,
+		Message:  ge.Message,
+		Details:  ge.Details,
+		Help:     ge.Suggestion,
+		File:     file,
+		Region:   ge.Region,
+	}
+	if ge.InnerError != nil {
+		d.Cause = ge.InnerError.Error()
+	}
+	return []Diagnostic{d}
+}
+
+// Render writes the diagnostic in a human-readable form, optionally colored
+func (d Diagnostic) Render(w io.Writer, color bool) {
+	paint := func(style, s string) string {
+		if !color {
+			return s
+		}
+		return style + s + ansiReset
+	}
+
+	severityStyle := ansiRed
+	if d.Severity == SeverityWarning {
+		severityStyle = ansiYellow
+	}
+	fmt.Fprintf(w, "%s%s\n",
+		paint(severityStyle, fmt.Sprintf("%s[%s]", i18n.T("diagnostic.severity."+string(d.Severity)), d.Code)),
+		paint(ansiBold, ": "+d.Message))
+
+	arrow := paint(ansiBlue, "  -->")
+	switch {
+	case d.File != "" && d.Region != nil:
+		fmt.Fprintf(w, "%s %s @ (%d, %d) %dx%d\n", arrow, d.File,
+			d.Region.X, d.Region.Y, d.Region.Width, d.Region.Height)
+	case d.File != "":
+		fmt.Fprintf(w, "%s %s\n", arrow, d.File)
+	case d.Region != nil:
+		fmt.Fprintf(w, "%s (%d, %d) %dx%d\n", arrow,
+			d.Region.X, d.Region.Y, d.Region.Width, d.Region.Height)
+	}
+
+	if d.Details != "" {
+		fmt.Fprintf(w, "%s %s\n", paint(ansiBlue, "   ="), d.Details)
+	}
+	if d.Cause != "" {
+		fmt.Fprintf(w, "%s %s\n", paint(ansiBlue, "   ="), i18n.Tf("diagnostic.cause", d.Cause))
+	}
+	if d.Help != "" {
+		fmt.Fprintf(w, "   %s %s\n", paint(ansiCyan, i18n.T("diagnostic.help")+":"), d.Help)
+	}
+	if d.Thumbnail != "" {
+		fmt.Fprintf(w, "   %s %s\n", paint(ansiCyan, i18n.T("diagnostic.thumbnail")+":"), d.Thumbnail)
+	}
+}
+
+// DiagnosticsSchemaVersion is the version of the JSON written by
+// WriteDiagnosticsJSON. It is increased whenever the encoding changes
+// incompatibly; adding fields does not change it.
+const DiagnosticsSchemaVersion = 1
+
+// WriteDiagnosticsJSON writes diagnostics as a JSON document of the form
+// {"schemaVersion": 1, "diagnostics": [...]}
+func WriteDiagnosticsJSON(w io.Writer, diags []Diagnostic) error {
+	if diags == nil {
+		diags = []Diagnostic{}
+	}
+	encoder := json.NewEncoder(w)
+	encoder.SetIndent("", "  ")
+	return encoder.Encode(struct {
+		SchemaVersion int          `json:"schemaVersion"`
+		Diagnostics   []Diagnostic `json:"diagnostics"`
+	}{SchemaVersion: DiagnosticsSchemaVersion, Diagnostics: diags})
+}
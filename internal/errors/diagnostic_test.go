@@ -0,0 +1,177 @@
+package errors
+
+import (
+	"bytes"
+	"encoding/json"
+	"fmt"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestDiagnostics(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	unexpected := UnexpectedSymbolError("square", "statement symbol", 100, 120).WithRegion(100, 120, 40)
+	aggregate := NewError(SyntaxError, "Parser encountered errors").WithRelated(unexpected)
+	enhanced := NewEnhancedError(NewError(NoOuterCircle, "No outer circle detected")).
+		WithContext("input_file", "circle.png")
+
+	tests := []struct {
+		name     string
+		err      error
+		expected []Diagnostic
+	}{
+		{
+			name: "grimoire error with region",
+			err:  unexpected,
+			expected: []Diagnostic{{
+				Code:     ErrCodeUnexpectedSymbol,
+				Severity: SeverityError,
+				Type:     UnexpectedSymbol,
+				Message:  unexpected.Message,
+				Details:  unexpected.Details,
+				Help:     unexpected.Suggestion,
+				Region:   &Region{X: 80, Y: 100, Width: 40, Height: 40},
+			}},
+		},
+		{
+			name: "aggregate error expands related errors",
+			err:  NewEnhancedError(aggregate).WithContext("input_file", "circle.png"),
+			expected: []Diagnostic{{
+				Code:     ErrCodeUnexpectedSymbol,
+				Severity: SeverityError,
+				Type:     UnexpectedSymbol,
+				Message:  unexpected.Message,
+				Details:  unexpected.Details,
+				Help:     unexpected.Suggestion,
+				File:     "circle.png",
+				Region:   &Region{X: 80, Y: 100, Width: 40, Height: 40},
+			}},
+		},
+		{
+			name: "enhanced error uses input file",
+			err:  enhanced,
+			expected: []Diagnostic{{
+				Code:     ErrCodeNoOuterCircle,
+				Severity: SeverityError,
+				Type:     NoOuterCircle,
+				Message:  "No outer circle detected",
+				Details:  enhanced.Details,
+				Help:     enhanced.Suggestion,
+				File:     "circle.png",
+			}},
+		},
+		{
+			name: "plain error",
+			err:  fmt.Errorf("boom"),
+			expected: []Diagnostic{{
+				Code:     "E0000",
+				Severity: SeverityError,
+				Message:  "boom",
+			}},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			assert.Equal(t, tt.expected, Diagnostics(tt.err))
+		})
+	}
+
+	assert.Nil(t, Diagnostics(nil))
+}
+
+func TestDiagnostic_Render(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	d := Diagnostic{
+		Code:      ErrCodeUnexpectedSymbol,
+		Severity:  SeverityError,
+		Message:   "Unexpected symbol",
+		Details:   "Expected a statement",
+		Help:      "Move the symbol",
+		File:      "circle.png",
+		Region:    &Region{X: 80, Y: 100, Width: 40, Height: 40},
+		Thumbnail: "thumbs/E3002-1.png",
+	}
+
+	var plain bytes.Buffer
+	d.Render(&plain, false)
+	assert.Equal(t, "error[E3002]: Unexpected symbol\n"+
+		"  --> circle.png @ (80, 100) 40x40\n"+
+		"   = Expected a statement\n"+
+		"   help: Move the symbol\n"+
+		"   thumbnail: thumbs/E3002-1.png\n", plain.String())
+
+	var colored bytes.Buffer
+	d.Render(&colored, true)
+	assert.Contains(t, colored.String(), ansiRed+"error[E3002]"+ansiReset)
+	assert.Contains(t, colored.String(), ansiCyan+"help:"+ansiReset)
+
+	var warning bytes.Buffer
+	Diagnostic{Code: "E6001", Severity: SeverityWarning, Message: "Check this"}.Render(&warning, true)
+	assert.Contains(t, warning.String(), ansiYellow+"warning[E6001]"+ansiReset)
+}
+
+func TestWriteDiagnosticsJSON(t *testing.T) {
+	var buf bytes.Buffer
+	diags := []Diagnostic{{
+		Code:     ErrCodeNoOuterCircle,
+		Severity: SeverityError,
+		Type:     NoOuterCircle,
+		Message:  "No outer circle detected",
+		Region:   &Region{X: 1, Y: 2, Width: 3, Height: 4},
+	}}
+	require.NoError(t, WriteDiagnosticsJSON(&buf, diags))
+
+	var decoded struct {
+		Diagnostics []map[string]interface{} `json:"diagnostics"`
+	}
+	require.NoError(t, json.Unmarshal(buf.Bytes(), &decoded))
+	require.Len(t, decoded.Diagnostics, 1)
+	assert.Equal(t, "E2002", decoded.Diagnostics[0]["code"])
+	assert.Equal(t, "error", decoded.Diagnostics[0]["severity"])
+	assert.Equal(t, map[string]interface{}{"x": 1.0, "y": 2.0, "width": 3.0, "height": 4.0},
+		decoded.Diagnostics[0]["region"])
+	assert.NotContains(t, decoded.Diagnostics[0], "help")
+
+	buf.Reset()
+	require.NoError(t, WriteDiagnosticsJSON(&buf, nil))
+	assert.JSONEq(t, `{"schemaVersion": 1, "diagnostics": []}`, buf.String())
+}
+
+// TestDiagnosticsJSONSchemaSnapshot guards the version 1 diagnostics schema.
+// If it fails, either restore compatibility or increase
+// DiagnosticsSchemaVersion and add a new snapshot.
+func TestDiagnosticsJSONSchemaSnapshot(t *testing.T) {
+	snapshot, err := os.ReadFile(filepath.Join("testdata", "diagnostics.v1.json"))
+	require.NoError(t, err)
+
+	diags := []Diagnostic{{
+		Code:     ErrCodeNoOuterCircle,
+		Severity: SeverityError,
+		Type:     NoOuterCircle,
+		Message:  "No outer circle detected",
+		Help:     "Draw a large circle around the whole program",
+		File:     "spell.png",
+		Region:   &Region{X: 1, Y: 2, Width: 3, Height: 4},
+	}}
+	var buf bytes.Buffer
+	require.NoError(t, WriteDiagnosticsJSON(&buf, diags))
+	assert.JSONEq(t, string(snapshot), buf.String())
+
+	var decoded struct {
+		SchemaVersion int          `json:"schemaVersion"`
+		Diagnostics   []Diagnostic `json:"diagnostics"`
+	}
+	require.NoError(t, json.Unmarshal(snapshot, &decoded))
+	assert.Equal(t, DiagnosticsSchemaVersion, decoded.SchemaVersion)
+	assert.Equal(t, diags, decoded.Diagnostics)
+}
@@ -2,7 +2,9 @@ package errors
 
 import (
 	"fmt"
+	"math"
 	"strings"
+	"time"
 
 	"github.com/ayutaz/grimoire/internal/i18n"
 )
@@ -22,6 +24,8 @@ const (
 	NoOuterCircle        ErrorType = "NO_OUTER_CIRCLE"
 	InvalidSymbolShape   ErrorType = "INVALID_SYMBOL_SHAPE"
 	ImageProcessingError ErrorType = "IMAGE_PROCESSING_ERROR"
+	PartialDetection     ErrorType = "PARTIAL_DETECTION"
+	TooComplex           ErrorType = "TOO_COMPLEX"
 
 	// Parser errors
 	SyntaxError          ErrorType = "SYNTAX_ERROR"
@@ -29,6 +33,8 @@ const (
 	MissingMainEntry     ErrorType = "MISSING_MAIN_ENTRY"
 	InvalidConnection    ErrorType = "INVALID_CONNECTION"
 	UnbalancedExpression ErrorType = "UNBALANCED_EXPRESSION"
+	DuplicateDefinition  ErrorType = "DUPLICATE_DEFINITION"
+	TypeError            ErrorType = "TYPE_ERROR"
 
 	// Compiler errors
 	CompilationError     ErrorType = "COMPILATION_ERROR"
@@ -42,6 +48,9 @@ const (
 
 	// I/O errors
 	IOError ErrorType = "IO_ERROR"
+
+	// Cancellation
+	Cancelled ErrorType = "CANCELLED"
 )
 
 // GrimoireError represents a custom error with context
@@ -54,6 +63,18 @@ type GrimoireError struct {
 	Column     int
 	FileName   string
 	InnerError error
+	// Region is the area of the image the error refers to, if known
+	Region *Region
+	// Related holds the individual errors an aggregate error was built from
+	Related []error
+}
+
+// Region is a rectangle in image pixel coordinates
+type Region struct {
+	X      int `json:"x"`
+	Y      int `json:"y"`
+	Width  int `json:"width"`
+	Height int `json:"height"`
 }
 
 // Error implements the error interface
@@ -135,6 +156,24 @@ func (e *GrimoireError) WithInnerError(err error) *GrimoireError {
 	return e
 }
 
+// WithRegion sets the image region to a square of the given size centered on x, y,
+// matching how the detector reports symbol positions and sizes
+func (e *GrimoireError) WithRegion(x, y, size float64) *GrimoireError {
+	e.Region = &Region{
+		X:      int(math.Round(x - size/2)),
+		Y:      int(math.Round(y - size/2)),
+		Width:  int(math.Round(size)),
+		Height: int(math.Round(size)),
+	}
+	return e
+}
+
+// WithRelated records the individual errors an aggregate error was built from
+func (e *GrimoireError) WithRelated(errs ...error) *GrimoireError {
+	e.Related = append(e.Related, errs...)
+	return e
+}
+
 // Helper functions for common errors
 
 // FileNotFoundError creates a file not found error
@@ -175,6 +214,25 @@ func UnexpectedSymbolError(symbolType, expected string, x, y float64) *GrimoireE
 		WithSuggestion(i18n.T("suggest.check_symbol_placement"))
 }
 
+// DuplicateDefinitionError creates an error for a function defined by more than one image
+func DuplicateDefinitionError(name, first, second string) *GrimoireError {
+	return NewError(DuplicateDefinition, i18n.Tf("msg.duplicate_definition", name, first, second)).
+		WithSuggestion(i18n.T("suggest.rename_library"))
+}
+
+// CancelledError creates an error for work stopped because its context was done
+func CancelledError(cause error) *GrimoireError {
+	return NewError(Cancelled, i18n.T("msg.cancelled")).
+		WithInnerError(cause)
+}
+
+// PartialDetectionError creates the error returned along with the symbols
+// found before detection ran past its timeout
+func PartialDetectionError(timeout time.Duration, found int) *GrimoireError {
+	return NewError(PartialDetection, i18n.Tf("msg.partial_detection", timeout, found)).
+		WithSuggestion(i18n.T("suggest.raise_timeout"))
+}
+
 // IsGrimoireError checks if an error is a GrimoireError
 func IsGrimoireError(err error) bool {
 	_, ok := err.(*GrimoireError)
@@ -208,6 +266,10 @@ func getLocalizedErrorType(errorType ErrorType) string {
 		return i18n.T("error.invalid_symbol_shape")
 	case ImageProcessingError:
 		return i18n.T("error.image_processing_error")
+	case PartialDetection:
+		return i18n.T("error.partial_detection")
+	case TooComplex:
+		return i18n.T("error.too_complex")
 	case SyntaxError:
 		return i18n.T("error.syntax_error")
 	case UnexpectedSymbol:
@@ -218,6 +280,10 @@ func getLocalizedErrorType(errorType ErrorType) string {
 		return i18n.T("error.invalid_connection")
 	case UnbalancedExpression:
 		return i18n.T("error.unbalanced_expression")
+	case DuplicateDefinition:
+		return i18n.T("error.duplicate_definition")
+	case TypeError:
+		return i18n.T("error.type_error")
 	case CompilationError:
 		return i18n.T("error.compilation_error")
 	case UnsupportedOperation:
@@ -228,6 +294,8 @@ func getLocalizedErrorType(errorType ErrorType) string {
 		return i18n.T("error.validation_error")
 	case IOError:
 		return i18n.T("error.io_error")
+	case Cancelled:
+		return i18n.T("error.cancelled")
 	default:
 		return string(errorType)
 	}
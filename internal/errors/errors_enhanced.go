@@ -22,6 +22,8 @@ const (
 	ErrCodeNoOuterCircle        ErrorCode = "E2002"
 	ErrCodeInvalidSymbolShape   ErrorCode = "E2003"
 	ErrCodeImageProcessingError ErrorCode = "E2004"
+	ErrCodePartialDetection     ErrorCode = "E2005"
+	ErrCodeTooComplex           ErrorCode = "E2006"
 
 	// Parser error codes (3000-3999)
 	ErrCodeSyntaxError          ErrorCode = "E3001"
@@ -29,6 +31,8 @@ const (
 	ErrCodeMissingMainEntry     ErrorCode = "E3003"
 	ErrCodeInvalidConnection    ErrorCode = "E3004"
 	ErrCodeUnbalancedExpression ErrorCode = "E3005"
+	ErrCodeDuplicateDefinition  ErrorCode = "E3006"
+	ErrCodeTypeError            ErrorCode = "E3007"
 
 	// Compiler error codes (4000-4999)
 	ErrCodeCompilationError     ErrorCode = "E4001"
@@ -42,6 +46,9 @@ const (
 
 	// I/O error codes (7000-7999)
 	ErrCodeIOError ErrorCode = "E7001"
+
+	// Cancellation error codes (8000-8999)
+	ErrCodeCancelled ErrorCode = "E8001"
 )
 
 // errorCodeMap maps ErrorType to ErrorCode
@@ -54,16 +61,21 @@ var errorCodeMap = map[ErrorType]ErrorCode{
 	NoOuterCircle:        ErrCodeNoOuterCircle,
 	InvalidSymbolShape:   ErrCodeInvalidSymbolShape,
 	ImageProcessingError: ErrCodeImageProcessingError,
+	PartialDetection:     ErrCodePartialDetection,
+	TooComplex:           ErrCodeTooComplex,
 	SyntaxError:          ErrCodeSyntaxError,
 	UnexpectedSymbol:     ErrCodeUnexpectedSymbol,
 	MissingMainEntry:     ErrCodeMissingMainEntry,
 	InvalidConnection:    ErrCodeInvalidConnection,
 	UnbalancedExpression: ErrCodeUnbalancedExpression,
+	DuplicateDefinition:  ErrCodeDuplicateDefinition,
+	TypeError:            ErrCodeTypeError,
 	CompilationError:     ErrCodeCompilationError,
 	UnsupportedOperation: ErrCodeUnsupportedOperation,
 	ExecutionError:       ErrCodeExecutionError,
 	ValidationError:      ErrCodeValidationError,
 	IOError:              ErrCodeIOError,
+	Cancelled:            ErrCodeCancelled,
 }
 
 // StackFrame represents a single frame in the stack trace
@@ -0,0 +1,62 @@
+package errors
+
+import (
+	stderrors "errors"
+)
+
+// Exit codes returned by the grimoire binary. They are part of the CLI's
+// public contract so that wrappers can branch on the class of failure.
+const (
+	ExitOK            = 0 // Success
+	ExitFailure       = 1 // Unclassified failure, including command-line usage errors
+	ExitIOError       = 2 // The image or an output file could not be read, written or decoded
+	ExitNoOuterCircle = 3 // No outer circle was detected (including images with no symbols)
+	ExitParseError    = 4 // The symbols could not be parsed into a program
+	ExitValidation    = 5 // Input or program validation failed
+	ExitCompileError  = 6 // The program could not be compiled
+	ExitRuntimeError  = 7 // The generated program failed while running
+)
+
+// exitCodeMap maps each ErrorType to its exit code
+var exitCodeMap = map[ErrorType]int{
+	FileNotFound:         ExitIOError,
+	UnsupportedFormat:    ExitIOError,
+	FileReadError:        ExitIOError,
+	FileWriteError:       ExitIOError,
+	ImageProcessingError: ExitIOError,
+	IOError:              ExitIOError,
+	NoSymbolsDetected:    ExitNoOuterCircle,
+	NoOuterCircle:        ExitNoOuterCircle,
+	InvalidSymbolShape:   ExitParseError,
+	SyntaxError:          ExitParseError,
+	UnexpectedSymbol:     ExitParseError,
+	MissingMainEntry:     ExitParseError,
+	InvalidConnection:    ExitParseError,
+	UnbalancedExpression: ExitParseError,
+	DuplicateDefinition:  ExitParseError,
+	TypeError:            ExitParseError,
+	ValidationError:      ExitValidation,
+	CompilationError:     ExitCompileError,
+	UnsupportedOperation: ExitCompileError,
+	ExecutionError:       ExitRuntimeError,
+	Cancelled:            ExitFailure,
+}
+
+// ExitCode returns the process exit code for err
+func ExitCode(err error) int {
+	if err == nil {
+		return ExitOK
+	}
+
+	var ge *GrimoireError
+	if enhanced, ok := err.(*EnhancedError); ok {
+		ge = enhanced.GrimoireError
+	} else if !stderrors.As(err, &ge) {
+		return ExitFailure
+	}
+
+	if code, ok := exitCodeMap[ge.Type]; ok {
+		return code
+	}
+	return ExitFailure
+}
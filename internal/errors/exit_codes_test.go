@@ -0,0 +1,44 @@
+package errors
+
+import (
+	"fmt"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestExitCode(t *testing.T) {
+	tests := []struct {
+		name     string
+		err      error
+		expected int
+	}{
+		{"success", nil, ExitOK},
+		{"plain error", fmt.Errorf("unknown command"), ExitFailure},
+		{"file not found", FileNotFoundError("missing.png"), ExitIOError},
+		{"image decode failure", NewError(ImageProcessingError, "bad image"), ExitIOError},
+		{"no outer circle", NoOuterCircleError(), ExitNoOuterCircle},
+		{"no symbols", NoSymbolsError(), ExitNoOuterCircle},
+		{"parse error", UnexpectedSymbolError("square", "statement", 0, 0), ExitParseError},
+		{"missing main entry", NewError(MissingMainEntry, "no main"), ExitParseError},
+		{"validation error", NewError(ValidationError, "invalid"), ExitValidation},
+		{"compile error", NewError(UnsupportedOperation, "unsupported"), ExitCompileError},
+		{"runtime error", NewError(ExecutionError, "python failed"), ExitRuntimeError},
+		{"enhanced error", NewEnhancedError(NoOuterCircleError()), ExitNoOuterCircle},
+		{"wrapped error", fmt.Errorf("context: %w", NewError(CompilationError, "failed")), ExitCompileError},
+		{"unknown type", NewError(ErrorType("SOMETHING_ELSE"), "odd"), ExitFailure},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			assert.Equal(t, tt.expected, ExitCode(tt.err))
+		})
+	}
+}
+
+func TestExitCode_EveryErrorTypeIsClassified(t *testing.T) {
+	for errorType := range errorCodeMap {
+		_, ok := exitCodeMap[errorType]
+		assert.True(t, ok, "error type %s has no exit code", errorType)
+	}
+}
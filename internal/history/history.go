@@ -0,0 +1,214 @@
+// Package history keeps a local record of the programs compiled and run,
+// so that a run can be reproduced exactly later. Nothing is recorded unless
+// the user opts in, and the record never leaves the machine.
+//
+// Each entry is one JSON file in the history directory, holding the hashes
+// of the image and of its syntax tree, the output target, the outcome and
+// the generated program. Only the most recent entries are kept.
+package history
+
+import (
+	"crypto/rand"
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io/fs"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+	"time"
+)
+
+// EnableEnv opts in to recording history when set to anything but "" or "0"
+const EnableEnv = "GRIMOIRE_HISTORY"
+
+// DirEnv overrides the default history directory
+const DirEnv = "GRIMOIRE_HISTORY_DIR"
+
+// entrySuffix is the file extension of history entries
+const entrySuffix = ".json"
+
+// DefaultLimit is how many entries a history keeps
+const DefaultLimit = 1000
+
+// Outcomes of a recorded run
+const (
+	OutcomeOK     = "ok"
+	OutcomeFailed = "failed"
+)
+
+// ErrNotFound is returned by Get when no entry has the ID
+var ErrNotFound = errors.New("no such history entry")
+
+// Entry records one compilation or run
+type Entry struct {
+	ID      string    `json:"id"`
+	Time    time.Time `json:"time"`
+	Command string    `json:"command"`
+	// Image is the path of the image as given on the command line
+	Image     string `json:"image"`
+	ImageHash string `json:"imageHash"`
+	// ASTHash is empty when the program was not parsed, such as when a
+	// cached program was run
+	ASTHash string `json:"astHash,omitempty"`
+	Target  string `json:"target"`
+	Outcome string `json:"outcome"`
+	// Error is why the run failed
+	Error string `json:"error,omitempty"`
+	// Code is the generated program, which a replay runs again
+	Code string `json:"code,omitempty"`
+}
+
+// History is a directory of recorded runs
+type History struct {
+	Dir string
+	// Limit is how many entries Record keeps, removing the oldest beyond
+	// it; 0 keeps every entry
+	Limit int
+}
+
+// New creates a history in dir keeping DefaultLimit entries. The directory
+// is created on the first Record.
+func New(dir string) *History {
+	return &History{Dir: dir, Limit: DefaultLimit}
+}
+
+// Enabled reports whether the user opted in to recording history
+func Enabled() bool {
+	value := os.Getenv(EnableEnv)
+	return value != "" && value != "0"
+}
+
+// DefaultDir returns $GRIMOIRE_HISTORY_DIR, or .grimoire/history in the
+// user's home directory
+func DefaultDir() (string, error) {
+	if dir := os.Getenv(DirEnv); dir != "" {
+		return dir, nil
+	}
+	home, err := os.UserHomeDir()
+	if err != nil {
+		return "", err
+	}
+	return filepath.Join(home, ".grimoire", "history"), nil
+}
+
+// Hash returns the hash recorded for data, such as the bytes of an image
+func Hash(data []byte) string {
+	sum := sha256.Sum256(data)
+	return hex.EncodeToString(sum[:])
+}
+
+// Record saves entry, giving it an ID and the current time when it has none,
+// then removes the oldest entries beyond the limit of the history
+func (h *History) Record(entry *Entry) error {
+	if entry.ID == "" {
+		var id [4]byte
+		if _, err := rand.Read(id[:]); err != nil {
+			return err
+		}
+		entry.ID = hex.EncodeToString(id[:])
+	}
+	if entry.Time.IsZero() {
+		entry.Time = time.Now().UTC()
+	}
+	data, err := json.MarshalIndent(entry, "", "  ")
+	if err != nil {
+		return err
+	}
+	if err := os.MkdirAll(h.Dir, 0o700); err != nil {
+		return err
+	}
+	if err := os.WriteFile(filepath.Join(h.Dir, entry.ID+entrySuffix), data, 0o600); err != nil {
+		return err
+	}
+	return h.prune()
+}
+
+// prune removes the oldest entries beyond the limit
+func (h *History) prune() error {
+	if h.Limit <= 0 {
+		return nil
+	}
+	entries, err := h.List()
+	if err != nil {
+		return err
+	}
+	for len(entries) > h.Limit {
+		err := os.Remove(filepath.Join(h.Dir, entries[0].ID+entrySuffix))
+		if err != nil && !errors.Is(err, fs.ErrNotExist) {
+			return err
+		}
+		entries = entries[1:]
+	}
+	return nil
+}
+
+// List returns every entry, oldest first. A history whose directory does
+// not exist yet is empty.
+func (h *History) List() ([]*Entry, error) {
+	files, err := os.ReadDir(h.Dir)
+	if errors.Is(err, fs.ErrNotExist) {
+		return nil, nil
+	}
+	if err != nil {
+		return nil, err
+	}
+
+	var entries []*Entry
+	for _, file := range files {
+		if !file.Type().IsRegular() || !strings.HasSuffix(file.Name(), entrySuffix) {
+			continue
+		}
+		entry, err := h.read(filepath.Join(h.Dir, file.Name()))
+		if err != nil {
+			continue // Not an entry, or removed while listing
+		}
+		entries = append(entries, entry)
+	}
+	sort.SliceStable(entries, func(i, j int) bool {
+		return entries[i].Time.Before(entries[j].Time)
+	})
+	return entries, nil
+}
+
+// Get returns the entry whose ID is id or starts with it, which must name a
+// single entry
+func (h *History) Get(id string) (*Entry, error) {
+	entries, err := h.List()
+	if err != nil {
+		return nil, err
+	}
+	var found *Entry
+	for _, entry := range entries {
+		if id == "" || !strings.HasPrefix(entry.ID, id) {
+			continue
+		}
+		if entry.ID == id {
+			return entry, nil
+		}
+		if found != nil {
+			return nil, fmt.Errorf("history entry %q is ambiguous: %s and %s match", id, found.ID, entry.ID)
+		}
+		found = entry
+	}
+	if found == nil {
+		return nil, ErrNotFound
+	}
+	return found, nil
+}
+
+// read reads the entry in the file at path
+func (h *History) read(path string) (*Entry, error) {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil, err
+	}
+	var entry Entry
+	if err := json.Unmarshal(data, &entry); err != nil {
+		return nil, err
+	}
+	return &entry, nil
+}
@@ -0,0 +1,113 @@
+package history
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestRecordAndList(t *testing.T) {
+	h := New(filepath.Join(t.TempDir(), "history"))
+	entries, err := h.List()
+	require.NoError(t, err)
+	assert.Empty(t, entries, "a history without a directory is empty")
+
+	later := &Entry{Time: time.Date(2026, 1, 2, 0, 0, 0, 0, time.UTC), Command: "run", Image: "b.png", Outcome: OutcomeOK, Code: "print(2)"}
+	earlier := &Entry{Time: time.Date(2026, 1, 1, 0, 0, 0, 0, time.UTC), Command: "compile", Image: "a.png", Outcome: OutcomeFailed, Error: "no symbols"}
+	require.NoError(t, h.Record(later))
+	require.NoError(t, h.Record(earlier))
+	assert.Len(t, later.ID, 8)
+	assert.NotEqual(t, later.ID, earlier.ID)
+
+	// Anything else in the directory is ignored
+	require.NoError(t, os.WriteFile(filepath.Join(h.Dir, "notes.txt"), []byte("x"), 0o600))
+
+	entries, err = h.List()
+	require.NoError(t, err)
+	assert.Equal(t, []*Entry{earlier, later}, entries, "oldest first")
+
+	// A new entry is stamped with the time it was recorded
+	now := &Entry{Command: "run"}
+	require.NoError(t, h.Record(now))
+	assert.False(t, now.Time.IsZero())
+}
+
+func TestRecordPrunesOldest(t *testing.T) {
+	h := New(t.TempDir())
+	assert.Equal(t, DefaultLimit, h.Limit)
+	h.Limit = 3
+
+	start := time.Date(2026, 1, 1, 0, 0, 0, 0, time.UTC)
+	var ids []string
+	for i := 0; i < 5; i++ {
+		entry := &Entry{Time: start.Add(time.Duration(i) * time.Hour), Command: "run"}
+		require.NoError(t, h.Record(entry))
+		ids = append(ids, entry.ID)
+	}
+
+	entries, err := h.List()
+	require.NoError(t, err)
+	require.Len(t, entries, 3, "only the most recent entries are kept")
+	for i, entry := range entries {
+		assert.Equal(t, ids[i+2], entry.ID)
+	}
+	_, err = h.Get(ids[0])
+	assert.ErrorIs(t, err, ErrNotFound)
+
+	// Without a limit every entry is kept
+	h.Limit = 0
+	require.NoError(t, h.Record(&Entry{Command: "run"}))
+	entries, err = h.List()
+	require.NoError(t, err)
+	assert.Len(t, entries, 4)
+}
+
+func TestGet(t *testing.T) {
+	h := New(t.TempDir())
+	require.NoError(t, h.Record(&Entry{ID: "abc123", Command: "run"}))
+	require.NoError(t, h.Record(&Entry{ID: "abd456", Command: "compile"}))
+
+	entry, err := h.Get("abc123")
+	require.NoError(t, err)
+	assert.Equal(t, "run", entry.Command)
+
+	entry, err = h.Get("abd")
+	require.NoError(t, err, "a unique prefix names an entry")
+	assert.Equal(t, "compile", entry.Command)
+
+	_, err = h.Get("ab")
+	assert.ErrorContains(t, err, "ambiguous")
+	_, err = h.Get("fff")
+	assert.ErrorIs(t, err, ErrNotFound)
+	_, err = h.Get("")
+	assert.ErrorIs(t, err, ErrNotFound)
+}
+
+func TestEnabledAndDefaultDir(t *testing.T) {
+	t.Setenv(EnableEnv, "")
+	assert.False(t, Enabled(), "history is opt-in")
+	t.Setenv(EnableEnv, "0")
+	assert.False(t, Enabled())
+	t.Setenv(EnableEnv, "1")
+	assert.True(t, Enabled())
+
+	t.Setenv(DirEnv, "/tmp/grimoire-history")
+	dir, err := DefaultDir()
+	require.NoError(t, err)
+	assert.Equal(t, "/tmp/grimoire-history", dir)
+
+	t.Setenv(DirEnv, "")
+	dir, err = DefaultDir()
+	require.NoError(t, err)
+	assert.Equal(t, filepath.Join(".grimoire", "history"), filepath.Join(filepath.Base(filepath.Dir(dir)), filepath.Base(dir)))
+}
+
+func TestHash(t *testing.T) {
+	assert.Len(t, Hash([]byte("image")), 64)
+	assert.Equal(t, Hash([]byte("image")), Hash([]byte("image")))
+	assert.NotEqual(t, Hash([]byte("image")), Hash([]byte("other")))
+}
@@ -138,17 +138,24 @@ func (l *Localizer) loadMessages() {
 		{ID: "error.no_outer_circle", En: "NO_OUTER_CIRCLE", Ja: "外周円が検出されません"},
 		{ID: "error.invalid_symbol_shape", En: "INVALID_SYMBOL_SHAPE", Ja: "無効なシンボル形状"},
 		{ID: "error.image_processing_error", En: "IMAGE_PROCESSING_ERROR", Ja: "画像処理エラー"},
+		{ID: "error.partial_detection", En: "PARTIAL_DETECTION", Ja: "検出の打ち切り"},
+		{ID: "error.too_complex", En: "TOO_COMPLEX", Ja: "複雑すぎる画像"},
 		{ID: "error.syntax_error", En: "SYNTAX_ERROR", Ja: "構文エラー"},
 		{ID: "error.unexpected_symbol", En: "UNEXPECTED_SYMBOL", Ja: "予期しないシンボル"},
 		{ID: "error.missing_main_entry", En: "MISSING_MAIN_ENTRY", Ja: "メインエントリーポイントが見つかりません"},
 		{ID: "error.cannot_compile_nil_ast", En: "Cannot compile nil AST", Ja: "nilのASTはコンパイルできません"},
+		{ID: "error.cannot_compile_nil_statement", En: "Cannot compile nil statement", Ja: "nil の文はコンパイルできません"},
+		{ID: "error.unsupported_statement", En: "Unsupported statement type: %T", Ja: "サポートされていない文の種類: %T"},
 		{ID: "error.invalid_connection", En: "INVALID_CONNECTION", Ja: "無効な接続"},
 		{ID: "error.unbalanced_expression", En: "UNBALANCED_EXPRESSION", Ja: "式のバランスが取れていません"},
+		{ID: "error.duplicate_definition", En: "DUPLICATE_DEFINITION", Ja: "重複した定義"},
+		{ID: "error.type_error", En: "TYPE_ERROR", Ja: "型エラー"},
 		{ID: "error.compilation_error", En: "COMPILATION_ERROR", Ja: "コンパイルエラー"},
 		{ID: "error.unsupported_operation", En: "UNSUPPORTED_OPERATION", Ja: "サポートされていない操作"},
 		{ID: "error.execution_error", En: "EXECUTION_ERROR", Ja: "実行エラー"},
 		{ID: "error.validation_error", En: "VALIDATION_ERROR", Ja: "検証エラー"},
 		{ID: "error.io_error", En: "IO_ERROR", Ja: "I/Oエラー"},
+		{ID: "error.cancelled", En: "CANCELLED", Ja: "キャンセル"},
 
 		// Error messages
 		{ID: "msg.image_file_not_found", En: "Image file not found: %s", Ja: "画像ファイルが見つかりません: %s"},
@@ -156,6 +163,11 @@ func (l *Localizer) loadMessages() {
 		{ID: "msg.no_symbols_detected", En: "No symbols were detected in the image", Ja: "画像内にシンボルが検出されませんでした"},
 		{ID: "msg.no_outer_circle", En: "No outer circle detected in the magic diagram", Ja: "魔法陣に外周円が検出されませんでした"},
 		{ID: "msg.unexpected_symbol", En: "Unexpected symbol: %s", Ja: "予期しないシンボル: %s"},
+		{ID: "msg.duplicate_definition", En: "Function %s is defined in both %s and %s",
+			Ja: "関数 %s が %s と %s の両方で定義されています"},
+		{ID: "msg.cancelled", En: "Processing was cancelled", Ja: "処理がキャンセルされました"},
+		{ID: "msg.partial_detection", En: "Detection stopped after %v with %d symbols found so far",
+			Ja: "検出を %v で打ち切りました（それまでに見つかったシンボル: %d 個）"},
 		{ID: "msg.failed_execute_python", En: "Failed to execute generated Python code", Ja: "生成されたPythonコードの実行に失敗しました"},
 		{ID: "msg.failed_write_output", En: "Failed to write output file", Ja: "出力ファイルの書き込みに失敗しました"},
 		{ID: "msg.error_occurred", En: "An error occurred", Ja: "エラーが発生しました"},
@@ -172,8 +184,12 @@ func (l *Localizer) loadMessages() {
 		{ID: "suggest.draw_clear_circle", En: "Draw a clear circle around your entire program", Ja: "プログラム全体を囲む明確な円を描いてください"},
 		{ID: "suggest.check_symbol_placement", En: "Check the symbol placement and connections in your diagram",
 			Ja: "図のシンボルの配置と接続を確認してください"},
+		{ID: "suggest.raise_timeout", En: "Raise the limit with --detect-timeout, or reduce noise in the image so fewer shapes are found",
+			Ja: "--detect-timeout で制限を延ばすか、画像のノイズを減らして検出される図形を少なくしてください"},
 		{ID: "suggest.check_python_installed", En: "Check that Python 3 is installed and in your PATH",
 			Ja: "Python 3がインストールされ、PATHに含まれていることを確認してください"},
+		{ID: "suggest.rename_library", En: "Rename one of the library images so each defines a unique function",
+			Ja: "各ライブラリ画像が一意の関数を定義するように、いずれかの画像の名前を変更してください"},
 
 		// Enhanced error hints
 		{ID: "error.hint.missing_main_entry",
@@ -211,6 +227,156 @@ func (l *Localizer) loadMessages() {
 		{ID: "detail.symbol_type_at_position", En: "Symbol type: %s at position (%.0f, %.0f)",
 			Ja: "シンボルタイプ: %s 位置: (%.0f, %.0f)"},
 		{ID: "detail.expected_at_position", En: "Expected: %s at position (%.0f, %.0f)", Ja: "期待される値: %s 位置: (%.0f, %.0f)"},
+		{ID: "detail.at_position", En: "At position (%.0f, %.0f)", Ja: "位置: (%.0f, %.0f)"},
+		{ID: "detail.file", En: "File: %s", Ja: "ファイル: %s"},
+
+		// Detector diagnostics
+		{ID: "detector.unknown_backend", En: "Unknown detector backend: %s", Ja: "不明な検出バックエンド: %s"},
+		{ID: "detector.unknown_backend_suggestion", En: "Use one of: %s", Ja: "次のいずれかを指定してください: %s"},
+		{ID: "detector.too_complex", En: "The image has too many shapes to detect within the configured limits",
+			Ja: "画像の図形が多すぎるため、設定された上限内で検出できません"},
+		{ID: "detector.too_complex_suggestion", En: "Remove noise from the image, or raise --max-contours and --max-contour-points if it is trusted",
+			Ja: "画像のノイズを取り除くか、信頼できる画像であれば --max-contours と --max-contour-points を引き上げてください"},
+		{ID: "detector.too_many_contours", En: "%d contours found, more than the limit of %d",
+			Ja: "輪郭が %d 個見つかり、上限の %d 個を超えています"},
+		{ID: "detector.contour_too_long", En: "a contour of %d points found, more than the limit of %d",
+			Ja: "%d 点からなる輪郭が見つかり、上限の %d 点を超えています"},
+		{ID: "detector.decode_failed", En: "Failed to decode image", Ja: "画像のデコードに失敗しました"},
+		{ID: "detector.invalid_path", En: "Invalid file path detected", Ja: "不正なファイルパスが検出されました"},
+		{ID: "detector.invalid_path_suggestion", En: "Use a valid file path without directory traversal attempts",
+			Ja: "ディレクトリトラバーサルを含まない正しいファイルパスを指定してください"},
+		{ID: "detector.image_too_large", En: "Image is too large to decode safely", Ja: "画像が大きすぎるため安全にデコードできません"},
+		{ID: "detector.image_too_large_suggestion", En: "Use a smaller image, or allow huge images (--allow-huge) if the image is trusted",
+			Ja: "小さい画像を使うか、信頼できる画像であれば巨大な画像を許可してください（--allow-huge）"},
+		{ID: "detector.image_exceeds_limits", En: "Image exceeds size limits", Ja: "画像がサイズ制限を超えています"},
+		{ID: "detector.image_exceeds_limits_suggestion", En: "Use a smaller image (max 50MB file size, 10000x10000 pixels)",
+			Ja: "小さい画像を使ってください（最大ファイルサイズ50MB、10000x10000ピクセル）"},
+		{ID: "detector.read_failed", En: "Failed to read image file", Ja: "画像ファイルの読み込みに失敗しました"},
+		{ID: "detector.validate_failed", En: "Failed to validate and decode image", Ja: "画像の検証とデコードに失敗しました"},
+		{ID: "detector.validate_failed_suggestion", En: "Ensure the image is a valid PNG or JPEG file and not corrupted",
+			Ja: "画像が破損していない正しいPNGまたはJPEGファイルであることを確認してください"},
+		{ID: "detector.unrecognized_shape", En: "Unrecognized shape at (%.0f, %.0f)", Ja: "(%.0f, %.0f) の図形を認識できません"},
+		{ID: "detector.unrecognized_shapes", En: "%d shapes inside the outer circle match no symbol",
+			Ja: "外周円の内側の %d 個の図形がどのシンボルにも一致しません"},
+		{ID: "detector.unrecognized_shapes_suggestion", En: "Redraw the shapes more clearly, or use --permissive to ignore them",
+			Ja: "図形をよりはっきり描き直すか、--permissive で無視してください"},
+		{ID: "detector.strokes_decode_failed", En: "Failed to decode strokes", Ja: "ストロークのデコードに失敗しました"},
+		{ID: "detector.stroke_canvas_too_large", En: "Stroke canvas is too large to draw safely",
+			Ja: "ストロークのキャンバスが大きすぎるため安全に描画できません"},
+		{ID: "detector.stroke_canvas_too_large_suggestion", En: "Keep strokes within a smaller canvas, or allow huge images (--allow-huge) if the drawing is trusted",
+			Ja: "ストロークを小さいキャンバスに収めるか、信頼できる描画であれば巨大な画像を許可してください（--allow-huge）"},
+		{ID: "detector.strokes_outside_canvas", En: "Strokes go outside the canvas", Ja: "ストロークがキャンバスの外にはみ出しています"},
+		{ID: "detector.preprocess_needs_binary", En: "%s needs a binary image; threshold the image before it",
+			Ja: "%s には二値画像が必要です。その前に画像を二値化してください"},
+		{ID: "detector.preprocess_empty", En: "empty preprocessing chain", Ja: "前処理チェーンが空です"},
+		{ID: "detector.preprocess_no_threshold", En: "preprocessing must threshold the image with threshold or adaptive_threshold",
+			Ja: "前処理では threshold または adaptive_threshold で画像を二値化する必要があります"},
+		{ID: "detector.preprocess_unknown_step", En: "unknown preprocessing step %q (available: %s)",
+			Ja: "不明な前処理ステップ %q（利用可能: %s）"},
+		{ID: "detector.preprocess_unclosed", En: "unclosed parameters in %q", Ja: "%q のパラメータが閉じられていません"},
+		{ID: "detector.preprocess_param_form", En: "parameter %q of %s is not of the form name=value",
+			Ja: "%[2]s のパラメータ %[1]q が 名前=値 の形式ではありません"},
+		{ID: "detector.preprocess_unknown_param", En: "%s has no parameter %q", Ja: "%s にはパラメータ %q がありません"},
+		{ID: "detector.preprocess_param_value", En: "parameter %s of %s must be a non-negative number, not %q",
+			Ja: "%[2]s のパラメータ %[1]s には %[3]q ではなく0以上の数値を指定してください"},
+		{ID: "detector.annotations_decode_failed", En: "Failed to decode the annotation file", Ja: "アノテーションファイルのデコードに失敗しました"},
+		{ID: "detector.annotations_unknown_format", En: "The annotation file is neither LabelMe nor COCO",
+			Ja: "アノテーションファイルが LabelMe と COCO のどちらの形式でもありません"},
+		{ID: "detector.annotations_unknown_format_suggestion", En: "Export the labels as LabelMe JSON (with shapes) or COCO JSON (with annotations and categories)",
+			Ja: "ラベルを LabelMe 形式（shapes を含む）または COCO 形式（annotations と categories を含む）のJSONで書き出してください"},
+		{ID: "detector.annotation_no_points", En: "Annotation %q has no points", Ja: "アノテーション %q に座標がありません"},
+		{ID: "detector.annotation_no_image", En: "The annotation file labels no image named %s", Ja: "アノテーションファイルに %s という画像のラベルがありません"},
+		{ID: "detector.annotation_several_images", En: "The annotation file labels %d images", Ja: "アノテーションファイルには %d 枚の画像のラベルがあります"},
+		{ID: "detector.annotation_several_images_suggestion", En: "Name the image to read the labels of", Ja: "ラベルを読み取る画像を指定してください"},
+		{ID: "detector.annotation_unknown_category", En: "Annotation refers to unknown category %d", Ja: "アノテーションが不明なカテゴリ %d を参照しています"},
+		{ID: "detector.annotation_unknown_label", En: "Unknown annotation label %q", Ja: "不明なアノテーションラベル %q です"},
+		{ID: "detector.annotation_unknown_label_suggestion", En: "Label shapes with a symbol type such as star or square:dot, or connection for lines; see grimoire symbols",
+			Ja: "図形には star や square:dot のようなシンボルの種類、線には connection をラベルとして付けてください（grimoire symbols を参照）"},
+		{ID: "detector.annotation_unknown_pattern", En: "Unknown pattern in annotation label %q", Ja: "アノテーションラベル %q の模様が不明です"},
+
+		// Parser diagnostics
+		{ID: "parser.no_symbols", En: "No symbols to parse", Ja: "解析するシンボルがありません"},
+		{ID: "parser.no_symbols_detail", En: "The input contains no detected symbols", Ja: "入力に検出されたシンボルが含まれていません"},
+		{ID: "parser.nil_symbol", En: "Symbol list contains a nil symbol", Ja: "シンボルリストに nil のシンボルが含まれています"},
+		{ID: "parser.nil_symbol_detail", En: "Symbol %d is nil", Ja: "シンボル %d が nil です"},
+		{ID: "parser.empty_program", En: "Empty program", Ja: "空のプログラムです"},
+		{ID: "parser.empty_program_detail", En: "No symbols found inside the outer circle", Ja: "外周円の内側にシンボルが見つかりません"},
+		{ID: "parser.errors", En: "Parser encountered errors:", Ja: "構文解析でエラーが発生しました:"},
+		{ID: "parser.panic", En: "Panic during parsing: %v", Ja: "構文解析中にパニックが発生しました: %v"},
+		{ID: "parser.missing_operands", En: "Binary operator %s requires two operands, found %d; %s",
+			Ja: "二項演算子 %s には2つのオペランドが必要ですが、%d 個しかありません。%s"},
+		{ID: "parser.missing_operands_suggestion", En: "Connect the left operand to the left side of the operator and the right operand to its right side",
+			Ja: "左のオペランドを演算子の左側に、右のオペランドを右側に接続してください"},
+		{ID: "parser.quote_too_deep", En: "Quoted programs are nested more than %d deep",
+			Ja: "引用されたプログラムの入れ子が %d 段を超えています"},
+		{ID: "parser.quote_too_deep_suggestion", En: "Draw the innermost program directly instead of quoting it",
+			Ja: "最も内側のプログラムは引用せずに直接描いてください"},
+		{ID: "parser.unknown_builtin", En: "No built-in function is called by an eight-pointed star with a %s pattern at (%.0f, %.0f)",
+			Ja: "%s パターンの八芒星（位置: (%.0f, %.0f)）が呼び出す組み込み関数はありません"},
+		{ID: "parser.unknown_builtin_suggestion", En: "Draw lines, triple lines, a cross or a half circle inside the star, or leave it empty and connect a quote to it",
+			Ja: "星の中に線・三本線・クロス・半円を描くか、空にして引用を接続してください"},
+		{ID: "parser.unknown_library", En: "The eight-pointed star with a %s pattern at (%.0f, %.0f) calls a library image, but %d were given",
+			Ja: "%s パターンの八芒星（位置: (%.0f, %.0f)）はライブラリ画像を呼び出しますが、指定されたライブラリ画像は %d 個です"},
+		{ID: "parser.unknown_library_suggestion", En: "Pass the library images after the main image; one, two or three dots call the first, second or third",
+			Ja: "メイン画像の後にライブラリ画像を指定してください。ドット1つ・2つ・3つがそれぞれ1番目・2番目・3番目を呼び出します"},
+		{ID: "parser.port_left", En: "left", Ja: "左"},
+		{ID: "parser.port_right", En: "right", Ja: "右"},
+		{ID: "parser.ports_and", En: " and ", Ja: "と"},
+		{ID: "parser.port_unconnected", En: "its %s port is unconnected", Ja: "%sのポートが未接続です"},
+		{ID: "parser.ports_unconnected", En: "its %s ports are unconnected", Ja: "%sのポートが未接続です"},
+		{ID: "parser.ambiguous", En: "Ambiguous %s read as %s", Ja: "曖昧な %s を %s として読み取りました"},
+		{ID: "parser.ambiguous_detail", En: "As %s (confidence %.2f) the program is invalid; as %s (confidence %.2f) it is valid",
+			Ja: "%s（信頼度 %.2f）としてはプログラムが不正で、%s（信頼度 %.2f）としては正しくなります"},
+		{ID: "parser.ambiguous_suggestion", En: "Redraw the shape more clearly if this is not what was meant",
+			Ja: "意図と異なる場合は図形をよりはっきり描き直してください"},
+		{ID: "parser.header_unrecognized_glyph", En: "Unrecognized header glyph %s on the outer circle",
+			Ja: "外周円上のヘッダー記号 %s を認識できません"},
+		{ID: "parser.header_unrecognized_glyph_suggestion", En: "Mark the language version with a square, or require functions, loops or parallel blocks with a circle, pentagon or hexagon",
+			Ja: "言語バージョンは四角形で、関数・ループ・並列ブロックの要求は円・五角形・六角形で示してください"},
+		{ID: "parser.header_version_no_dots", En: "Version square on the outer circle has no dots", Ja: "外周円上のバージョンの四角形に点がありません"},
+		{ID: "parser.header_version_no_dots_suggestion", En: "Mark the language version with dots inside the square",
+			Ja: "四角形の中の点で言語バージョンを示してください"},
+		{ID: "parser.header_versions_conflict", En: "Outer circle declares both language version %d and %d",
+			Ja: "外周円で言語バージョン %d と %d の両方が宣言されています"},
+		{ID: "parser.header_versions_conflict_suggestion", En: "Keep a single version square on the outer circle",
+			Ja: "外周円上のバージョンの四角形は1つにしてください"},
+		{ID: "parser.header_version_unsupported", En: "Program requires language version %d, but this compiler supports up to version %d",
+			Ja: "プログラムは言語バージョン %d を必要としますが、このコンパイラはバージョン %d までしか対応していません"},
+		{ID: "parser.header_version_unsupported_suggestion", En: "Update grimoire, or lower the version marked on the outer circle",
+			Ja: "grimoire を更新するか、外周円に示したバージョンを下げてください"},
+		{ID: "parser.link_nil_program", En: "Cannot link nil program", Ja: "nil のプログラムはリンクできません"},
+		{ID: "parser.link_errors", En: "Linker encountered errors:", Ja: "リンクでエラーが発生しました:"},
+		{ID: "parser.type_errors", En: "Type checker encountered errors:", Ja: "型検査でエラーが発生しました:"},
+		{ID: "parser.loop_bounds_type", En: "Loop bounds must be integers, found %s", Ja: "ループの範囲は整数である必要がありますが、%s です"},
+		{ID: "parser.operand_types", En: "Cannot apply %s to %s and %s", Ja: "%[2]s と %[3]s に %[1]s は適用できません"},
+		{ID: "parser.operand_types_suggestion", En: "Connect values of matching types to the symbol", Ja: "型の合う値をシンボルに接続してください"},
+		{ID: "parser.extract_nil_program", En: "Cannot extract from nil program", Ja: "nil のプログラムからは切り出せません"},
+		{ID: "parser.extract_empty", En: "No statement is drawn inside the region", Ja: "領域の内側に文が描かれていません"},
+		{ID: "parser.extract_empty_suggestion", En: "Select a region around the statements to extract",
+			Ja: "切り出す文を囲む領域を選択してください"},
+		{ID: "parser.extract_symbol_not_statement", En: "The %s at (%.0f, %.0f) is inside the region but not part of the statements in it",
+			Ja: "(%[2].0f, %[3].0f) の %[1]s は領域の内側にありますが、領域内の文の一部ではありません"},
+		{ID: "parser.extract_symbol_outside", En: "The %s at (%.0f, %.0f) belongs to a statement inside the region but is drawn outside it",
+			Ja: "(%[2].0f, %[3].0f) の %[1]s は領域内の文に属していますが、領域の外側に描かれています"},
+		{ID: "parser.extract_different_blocks", En: "The statements inside the region belong to different blocks",
+			Ja: "領域内の文が異なるブロックに属しています"},
+		{ID: "parser.extract_different_blocks_suggestion", En: "Select statements of one function, branch or loop body",
+			Ja: "1つの関数・分岐・ループ本体の文を選択してください"},
+		{ID: "parser.extract_not_in_program", En: "The statements inside the region are not part of the program",
+			Ja: "領域内の文がプログラムの一部ではありません"},
+		{ID: "parser.extract_separated", En: "The statements inside the region are separated by statements outside it",
+			Ja: "領域内の文の間に領域外の文があります"},
+		{ID: "parser.extract_separated_suggestion", En: "Select statements that run one after another", Ja: "続けて実行される文を選択してください"},
+		{ID: "parser.extract_reads_outside", En: "The statements inside the region read %s, which is assigned outside it",
+			Ja: "領域内の文が、領域外で代入される %s を読み取っています"},
+		{ID: "parser.extract_assigns_read_outside", En: "The statements inside the region assign %s, which is read outside it",
+			Ja: "領域内の文が、領域外で読み取られる %s に代入しています"},
+		{ID: "parser.extract_shared_variables", En: "The statements inside the region share variables with the rest of the program",
+			Ja: "領域内の文がプログラムの残りの部分と変数を共有しています"},
+		{ID: "parser.extract_not_self_contained", En: "The symbols inside the region are not read as the same statements on their own",
+			Ja: "領域内のシンボルだけでは同じ文として読み取れません"},
+		{ID: "parser.extract_not_self_contained_suggestion", En: "Include the symbols feeding the statements in the region, or draw the statements apart from the rest",
+			Ja: "文に入力するシンボルも領域に含めるか、文を他から離して描いてください"},
 
 		// CLI messages
 		{ID: "cli.description_short", En: "A visual programming language using magic circles", Ja: "魔法陣を使用するビジュアルプログラミング言語"},
@@ -224,9 +390,50 @@ func (l *Localizer) loadMessages() {
 			Ja: "Grimoireプログラムをデバッグ（検出されたシンボルを表示）"},
 		{ID: "cli.validate_description", En: "Validate a magic circle for correctness", Ja: "魔法陣の正しさを検証"},
 		{ID: "cli.format_description", En: "Format and beautify a magic circle", Ja: "魔法陣をフォーマットして美しくする"},
+		{ID: "cli.fmt_description", En: "Redraw a magic circle in the canonical layout", Ja: "魔法陣を標準のレイアウトで描き直す"},
 		{ID: "cli.optimize_description", En: "Optimize a magic circle program", Ja: "魔法陣プログラムを最適化"},
 		{ID: "cli.output_flag_description", En: "Output file path", Ja: "出力ファイルパス"},
+		{ID: "cli.opt_level_flag_description", En: "Optimization level (1 or higher inlines small functions called once and folds constants)",
+			Ja: "最適化レベル（1 以上で一度だけ呼ばれる小さな関数のインライン展開と定数の畳み込み）"},
+		{ID: "cli.bundle_flag_description",
+			En: "Write a .grimoire bundle of the image, its symbols, AST and compiled program (default: <image>.grimoire)",
+			Ja: "画像・シンボル・AST・コンパイル済みプログラムをまとめた .grimoire バンドルを書き出す（デフォルト: <画像>.grimoire）"},
 		{ID: "cli.format_output_flag_description", En: "Output formatted image path", Ja: "フォーマット済み画像の出力パス"},
+		{ID: "cli.fmt_output_flag_description", En: "Output image path, PNG or .svg (default: <image>.formatted.png)",
+			Ja: "出力画像のパス、PNG または .svg（デフォルト: <画像>.formatted.png）"},
+		{ID: "cli.extract_description", En: "Lift the statements in a region into a function drawn as a library image",
+			Ja: "領域内の文を関数として切り出し、ライブラリ画像として描く"},
+		{ID: "cli.extract_region_flag_description", En: "Region holding the statements, as x,y,width,height in pixels",
+			Ja: "切り出す文を囲む領域（ピクセル単位の x,y,幅,高さ）"},
+		{ID: "cli.extract_out_flag_description", En: "Path of the library image to draw the function in",
+			Ja: "関数を描くライブラリ画像のパス"},
+		{ID: "cli.extract_output_flag_description", En: "Write the rewritten program as AST JSON to this file instead of stdout",
+			Ja: "書き換えたプログラムのAST JSONを標準出力の代わりにこのファイルへ書き出す"},
+		{ID: "cli.parse_description", En: "Print the syntax tree of a program as JSON",
+			Ja: "プログラムの構文木をJSONで表示する"},
+		{ID: "cli.parse_symbols_flag_description", En: "Read the symbols from a LabelMe or COCO annotation file instead of detecting them",
+			Ja: "シンボルを検出せずに LabelMe または COCO のアノテーションファイルから読み込む"},
+		{ID: "cli.parse_code_flag_description", En: "Print the generated code instead of the syntax tree",
+			Ja: "構文木の代わりに生成コードを表示する"},
+		{ID: "cli.sign_description", En: "Sign the program of a .grimoire bundle with an ed25519 key",
+			Ja: ".grimoire バンドルのプログラムに ed25519 鍵で署名する"},
+		{ID: "cli.sign_key_flag_description", En: "Private key file (PEM) to sign with",
+			Ja: "署名に使う秘密鍵ファイル（PEM）"},
+		{ID: "cli.generate_key_flag_description", En: "Create a new key at --key, with its public key at <key>.pub, and sign with it",
+			Ja: "--key に新しい鍵を作成し（公開鍵は <鍵>.pub）、その鍵で署名する"},
+		{ID: "cli.verify_description", En: "Check the signature of a .grimoire bundle",
+			Ja: ".grimoire バンドルの署名を検証する"},
+		{ID: "cli.verify_key_flag_description", En: "Public key file (PEM) the bundle must be signed with (repeatable; without it only the integrity of the bundle is checked)",
+			Ja: "バンドルの署名に使われているべき公開鍵ファイル（PEM、複数指定可。省略時は内容との一致だけを確認）"},
+		{ID: "cli.allow_env_flag_description",
+			En: "Let the program read these environment variables with env (comma separated; without a value, all of them)",
+			Ja: "プログラムが env で読める環境変数（カンマ区切り、値を省略するとすべて）"},
+		{ID: "cli.allow_fs_flag_description", En: "Grant the program the fs capability, letting read_file read files",
+			Ja: "プログラムに fs 権限を与え、read_file でファイルを読めるようにする"},
+		{ID: "cli.require_signed_flag_description", En: "Only run .grimoire bundles signed with a key given with --trusted-key",
+			Ja: "--trusted-key で指定した鍵で署名された .grimoire バンドルだけを実行する"},
+		{ID: "cli.trusted_key_flag_description", En: "Only run bundles signed with this public key file (repeatable; implies --require-signed)",
+			Ja: "この公開鍵ファイルで署名されたバンドルだけを実行する（複数指定可、--require-signed を含む）"},
 		{ID: "cli.optimize_output_flag_description", En: "Output optimized code path (use - for stdout)",
 			Ja: "最適化されたコードの出力パス（標準出力には-を使用）"},
 		{ID: "cli.language_flag_description", En: "Language (en/ja)", Ja: "言語 (en/ja)"},
@@ -241,11 +448,33 @@ func (l *Localizer) loadMessages() {
 			En: "  [%d] Type: %-15s Position: (%.0f, %.0f) Size: %.1f Pattern: %s\n",
 			Ja: "  [%d] タイプ: %-15s 位置: (%.0f, %.0f) サイズ: %.1f パターン: %s\n"},
 		{ID: "debug.connection_info", En: "  [%d] %s -> %s (%s)\n", Ja: "  [%d] %s -> %s (%s)\n"},
+		{ID: "debug.polar_header", En: "\nPolar layout (%d sectors):", Ja: "\n極座標レイアウト（%dセクター）:"},
+		{ID: "debug.polar_info", En: "  [%d] %-15s radius: %.2f angle: %5.1f° sector: %d\n",
+			Ja: "  [%d] %-15s 半径: %.2f 角度: %5.1f° セクター: %d\n"},
+		{ID: "cli.sectors_flag_description", En: "Number of sectors the outer circle is divided into for the polar layout",
+			Ja: "極座標レイアウトで外円を分割するセクター数"},
+		{ID: "cli.invalid_sectors", En: "--sectors must be at least 1, got %d",
+			Ja: "--sectors には1以上を指定してください（指定値: %d）"},
 
 		// Validate messages
 		{ID: "validate.no_outer_circle", En: "No outer magic circle found", Ja: "外周の魔法陣が見つかりません"},
 		{ID: "validate.no_main_function", En: "No main function (double circle) found", Ja: "メイン関数（二重円）が見つかりません"},
 		{ID: "validate.orphaned_symbol", En: "Orphaned symbol: %s at (%.0f, %.0f)", Ja: "孤立したシンボル: %s 位置: (%.0f, %.0f)"},
+		{ID: "validate.invalid_construct", En: "%s at (%.0f, %.0f) cannot take %d incoming and %d outgoing connections",
+			Ja: "位置 (%[2].0f, %[3].0f) の %[1]s は入力 %[4]d・出力 %[5]d の接続をとれません"},
+		{ID: "validate.did_you_mean", En: "Did you mean %s?", Ja: "%s のつもりですか？"},
+		{ID: "validate.list_separator", En: ", ", Ja: "、"},
+		{ID: "validate.or", En: " or ", Ja: "、または"},
+		{ID: "construct.if", En: "an if branch (triangle)", Ja: "条件分岐（三角形）"},
+		{ID: "construct.loop", En: "a loop (pentagon)", Ja: "ループ（五角形）"},
+		{ID: "construct.parallel", En: "a parallel block (hexagon)", Ja: "並列ブロック（六角形）"},
+		{ID: "construct.output", En: "an output (star)", Ja: "出力（星）"},
+		{ID: "construct.add", En: "an addition", Ja: "加算"},
+		{ID: "construct.subtract", En: "a subtraction", Ja: "減算"},
+		{ID: "construct.multiply", En: "a multiplication", Ja: "乗算"},
+		{ID: "construct.divide", En: "a division", Ja: "除算"},
+		{ID: "construct.with_connections", En: "%s with %d incoming and %d outgoing connections",
+			Ja: "入力 %[2]d・出力 %[3]d の%[1]s"},
 		{ID: "validate.success", En: "✓ Magic circle is valid!\n", Ja: "✓ 魔法陣は有効です！\n"},
 		{ID: "validate.symbols_found", En: "  - Symbols found: %d\n", Ja: "  - シンボル数: %d\n"},
 		{ID: "validate.connections_found", En: "  - Connections found: %d\n", Ja: "  - 接続数: %d\n"},
@@ -258,8 +487,100 @@ func (l *Localizer) loadMessages() {
 		{ID: "format.straighten_connection", En: "Straighten connection between %s and %s", Ja: "%sと%s間の接続を真っ直ぐにしてください"},
 		{ID: "format.well_formatted", En: "✓ Magic circle is well-formatted!\n", Ja: "✓ 魔法陣は適切にフォーマットされています！\n"},
 		{ID: "format.suggestions", En: "Formatting suggestions:\n", Ja: "フォーマットの提案:\n"},
-		{ID: "format.output_note", En: "\nNote: Automatic formatting to %s is not yet implemented.\n",
-			Ja: "\n注意: %sへの自動フォーマットはまだ実装されていません。\n"},
+		{ID: "format.output_note", En: "\nNote: format only suggests changes and does not write %s; grimoire fmt writes a redrawn image.\n",
+			Ja: "\n注意: format は提案のみで %s には書き出しません。描き直した画像は grimoire fmt で書き出せます。\n"},
+
+		// Fmt messages
+		{ID: "fmt.written", En: "Formatted magic circle written to %s\n", Ja: "整形した魔法陣を %s に出力しました\n"},
+		{ID: "fmt.semantics_changed", En: "The redrawn magic circle would not be read as the same program",
+			Ja: "描き直した魔法陣が同じプログラムとして読み取れません"},
+		{ID: "fmt.semantics_changed_suggestion", En: "The image was not written; check the layout with grimoire format instead",
+			Ja: "画像は書き出していません。grimoire format でレイアウトを確認してください"},
+
+		// Bundle messages
+		{ID: "bundle.written", En: "Bundle written to %s\n", Ja: "バンドルを %s に出力しました\n"},
+		{ID: "bundle.requires_python", En: "--bundle compiles to Python and cannot be combined with --emit",
+			Ja: "--bundle は Python にコンパイルするため --emit と組み合わせられません"},
+		{ID: "bundle.no_libraries", En: "A bundle has its libraries linked in already; run it without library images",
+			Ja: "バンドルにはライブラリがリンク済みです。ライブラリ画像を指定せずに実行してください"},
+		{ID: "bundle.unsupported_target", En: "The bundle holds a program compiled to %q, which cannot be run",
+			Ja: "バンドルのプログラムは %q 向けにコンパイルされており、実行できません"},
+		{ID: "bundle.failed_write", En: "Failed to write bundle", Ja: "バンドルの書き込みに失敗しました"},
+		{ID: "bundle.failed_open", En: "Failed to open bundle", Ja: "バンドルを開けませんでした"},
+		{ID: "bundle.failed_read", En: "Failed to read bundle", Ja: "バンドルの読み込みに失敗しました"},
+		{ID: "bundle.invalid", En: "Invalid bundle: %s", Ja: "不正なバンドルです: %s"},
+		{ID: "bundle.invalid_suggestion", En: "Create the bundle again with grimoire compile --bundle",
+			Ja: "grimoire compile --bundle でバンドルを作り直してください"},
+		{ID: "bundle.not_zip", En: "Not a zip archive", Ja: "zip アーカイブではありません"},
+		{ID: "bundle.invalid_manifest", En: "The manifest is not valid JSON", Ja: "マニフェストが正しいJSONではありません"},
+		{ID: "bundle.unsupported_version", En: "Unsupported bundle format version %d (supported: %d)",
+			Ja: "未対応のバンドル形式バージョン %d です（対応: %d）"},
+		{ID: "bundle.digest_mismatch", En: "Entry %s does not match the digest in the manifest",
+			Ja: "エントリ %s がマニフェストのダイジェストと一致しません"},
+		{ID: "bundle.missing_entry", En: "The bundle has no %s", Ja: "バンドルに %s がありません"},
+		{ID: "bundle.failed_open_entry", En: "Failed to open %s", Ja: "%s を開けませんでした"},
+		{ID: "bundle.failed_read_entry", En: "Failed to read %s", Ja: "%s の読み込みに失敗しました"},
+		{ID: "bundle.entry_too_large", En: "Entry %s exceeds the size limit of %d bytes",
+			Ja: "エントリ %s がサイズ上限の %d バイトを超えています"},
+
+		// Sign messages
+		{ID: "sign.signed", En: "Signed %s with key %s\n", Ja: "%s に鍵 %s で署名しました\n"},
+		{ID: "sign.verified", En: "%s: signature OK, signed with key %s\n", Ja: "%s: 署名は正当です（鍵 %s）\n"},
+		{ID: "sign.key_generated", En: "Key written to %s, public key to %s\n", Ja: "鍵を %s に、公開鍵を %s に出力しました\n"},
+		{ID: "sign.no_key", En: "No key given; pass --key, with --generate-key to create one",
+			Ja: "鍵が指定されていません。--key を指定してください（新しく作る場合は --generate-key も）"},
+		{ID: "sign.failed_write_key", En: "Failed to write key", Ja: "鍵の書き込みに失敗しました"},
+		{ID: "sign.failed_read_key", En: "Failed to read key", Ja: "鍵の読み込みに失敗しました"},
+		{ID: "sign.unsigned_input", En: "Only signed .grimoire bundles can be run with --require-signed",
+			Ja: "--require-signed では署名された .grimoire バンドルしか実行できません"},
+		{ID: "sign.unsigned_input_suggestion", En: "Compile the image with --bundle and sign it with grimoire sign",
+			Ja: "画像を --bundle でコンパイルし、grimoire sign で署名してください"},
+		{ID: "sign.integrity_verified", En: "%s: signature matches the content, signed with key %s; pass --key to check who signed it\n",
+			Ja: "%s: 署名は内容と一致します（鍵 %s）。署名者を確認するには --key を指定してください\n"},
+		{ID: "sign.no_trusted_keys", En: "--require-signed needs a trusted key, since anyone can re-sign a bundle with their own key",
+			Ja: "バンドルは誰でも自分の鍵で署名し直せるため、--require-signed には信頼する鍵が必要です"},
+		{ID: "sign.no_trusted_keys_suggestion", En: "Pass the public key of the signer with --trusted-key",
+			Ja: "署名者の公開鍵を --trusted-key で指定してください"},
+		{ID: "sign.bundle_unsigned", En: "The bundle is not signed", Ja: "バンドルは署名されていません"},
+		{ID: "sign.bundle_unsigned_suggestion", En: "Sign it with grimoire sign", Ja: "grimoire sign で署名してください"},
+		{ID: "sign.signature_mismatch", En: "The signature of the bundle does not match its program; it was changed after signing",
+			Ja: "バンドルの署名がプログラムと一致しません。署名後に変更されています"},
+		{ID: "sign.no_verification_keys", En: "No trusted keys to verify the signature against",
+			Ja: "署名を検証するための信頼する鍵がありません"},
+		{ID: "sign.no_verification_keys_suggestion", En: "Pass the public key of the signer",
+			Ja: "署名者の公開鍵を指定してください"},
+		{ID: "sign.untrusted_key", En: "The bundle is signed with an untrusted key (%s)",
+			Ja: "バンドルは信頼されていない鍵（%s）で署名されています"},
+		{ID: "sign.invalid_signature_json", En: "The signature is not valid JSON", Ja: "署名が正しいJSONではありません"},
+		{ID: "sign.unsupported_algorithm", En: "Unsupported signature algorithm %q", Ja: "未対応の署名アルゴリズム %q です"},
+		{ID: "sign.invalid_key", En: "Invalid key: %s", Ja: "不正な鍵です: %s"},
+		{ID: "sign.invalid_key_suggestion", En: "Create a key with grimoire sign --generate-key",
+			Ja: "grimoire sign --generate-key で鍵を作成してください"},
+		{ID: "sign.key_not_private_pem", En: "expected a PEM encoded PRIVATE KEY",
+			Ja: "PEM形式の PRIVATE KEY が必要です"},
+		{ID: "sign.key_not_public_pem", En: "expected a PEM encoded PUBLIC KEY",
+			Ja: "PEM形式の PUBLIC KEY が必要です"},
+		{ID: "sign.private_key_unparsable", En: "cannot parse the private key", Ja: "秘密鍵を解析できません"},
+		{ID: "sign.public_key_unparsable", En: "cannot parse the public key", Ja: "公開鍵を解析できません"},
+		{ID: "sign.key_not_ed25519", En: "not an ed25519 key", Ja: "ed25519 の鍵ではありません"},
+
+		// Parse messages
+		{ID: "parse.no_input", En: "Give an image or an annotation file with --symbols",
+			Ja: "画像、または --symbols でアノテーションファイルを指定してください"},
+		{ID: "parse.no_input_suggestion", En: "For example: grimoire parse --symbols labels.json",
+			Ja: "例: grimoire parse --symbols labels.json"},
+
+		// Extract messages
+		{ID: "extract.invalid_region", En: "Invalid region %q (use x,y,width,height with a positive width and height)",
+			Ja: "領域 %q が正しくありません（x,y,幅,高さ の形式で、幅と高さは正の値を指定してください）"},
+		{ID: "extract.no_out", En: "Give the library image to draw the function in with --out",
+			Ja: "関数を描くライブラリ画像を --out で指定してください"},
+		{ID: "extract.redraw_failed", En: "The drawn function would not be read as the statements lifted out",
+			Ja: "描いた関数が切り出した文として読み取れません"},
+		{ID: "extract.redraw_failed_suggestion", En: "Nothing was written; select a region holding fewer or more widely spaced statements",
+			Ja: "何も書き出していません。文の数を減らすか、間隔の広い文を囲む領域を指定してください"},
+		{ID: "extract.written", En: "Extracted %d statements into %s, drawn in %s\n",
+			Ja: "%d 個の文を関数 %s として切り出し、%s に描きました\n"},
 
 		// Optimize messages
 		{ID: "optimize.analyzing", En: "Analyzing program for optimization opportunities...\n",
@@ -271,6 +592,503 @@ func (l *Localizer) loadMessages() {
 		{ID: "optimize.optimized_code", En: "Optimized code:", Ja: "最適化されたコード:"},
 		{ID: "optimize.saved_to", En: "Optimized code saved to: %s\n", Ja: "最適化されたコードを保存しました: %s\n"},
 
+		// Explain messages
+		{ID: "cli.explain_description", En: "Explain how each detected symbol was interpreted",
+			Ja: "検出された各シンボルの解釈を説明"},
+		{ID: "cli.explain_annotate_flag_description", En: "Also write a copy of the image with the role of each symbol written below it",
+			Ja: "各シンボルの役割をその下に書き込んだ画像のコピーも出力する"},
+		{ID: "explain.annotated", En: "\nAnnotated image written to %s\n", Ja: "\n注釈付きの画像を %s に出力しました\n"},
+		{ID: "explain.header", En: "\n=== Interpretation of %s ===\n", Ja: "\n=== %s の解釈 ===\n"},
+		{ID: "explain.symbol_info", En: "  [%d] %s at (%.0f, %.0f): %s\n", Ja: "  [%d] %s 位置: (%.0f, %.0f): %s\n"},
+		{ID: "explain.in_context", En: "%s of %s", Ja: "%[2]sの%[1]s"},
+		{ID: "explain.parse_failed", En: "\nParsing stopped with errors; roles above are partial.\n",
+			Ja: "\n構文解析がエラーで停止したため、上記の役割は一部のみです。\n"},
+		{ID: "explain.role.program_boundary", En: "program boundary", Ja: "プログラムの境界"},
+		{ID: "explain.role.header", En: "program header glyph", Ja: "プログラムヘッダーの刻印"},
+		{ID: "explain.role.main_entry", En: "main entry point", Ja: "メインエントリーポイント"},
+		{ID: "explain.role.function", En: "function #%d", Ja: "関数 #%d"},
+		{ID: "explain.role.output", En: "output #%d", Ja: "出力 #%d"},
+		{ID: "explain.role.if_branch", En: "if-branch #%d", Ja: "条件分岐 #%d"},
+		{ID: "explain.role.condition", En: "condition", Ja: "条件"},
+		{ID: "explain.role.loop", En: "loop #%d", Ja: "ループ #%d"},
+		{ID: "explain.role.loop_counter", En: "counter", Ja: "カウンター"},
+		{ID: "explain.role.parallel", En: "parallel block #%d", Ja: "並列ブロック #%d"},
+		{ID: "explain.role.block", En: "block #%d", Ja: "ブロック #%d"},
+		{ID: "explain.role.stage", En: "ring closing stage %d", Ja: "ステージ%dを囲む環"},
+		{ID: "explain.role.assignment", En: "assignment #%d", Ja: "代入 #%d"},
+		{ID: "explain.role.literal", En: "literal %v", Ja: "リテラル %v"},
+		{ID: "explain.role.list", En: "list", Ja: "リスト"},
+		{ID: "explain.role.map", En: "map", Ja: "辞書"},
+		{ID: "explain.role.quote", En: "quoted program", Ja: "引用されたプログラム"},
+		{ID: "explain.role.assertion", En: "assertion #%d", Ja: "アサーション #%d"},
+		{ID: "explain.role.operator", En: "operator #%d", Ja: "演算子 #%d"},
+		{ID: "explain.role.function_call", En: "function call #%d", Ja: "関数呼び出し #%d"},
+		{ID: "explain.operand", En: "%s (%s operand)", Ja: "%s（%sオペランド）"},
+		{ID: "explain.port.left", En: "left", Ja: "左"},
+		{ID: "explain.port.right", En: "right", Ja: "右"},
+		{ID: "explain.role.unused", En: "not used by the program", Ja: "プログラムでは使用されていません"},
+
+		// Symbol reference messages
+		{ID: "cli.symbols_description", En: "Show the reference table of recognized symbols, patterns and connections",
+			Ja: "認識される記号・パターン・接続の一覧を表示"},
+		{ID: "symbols.symbols_header", En: "Symbols:", Ja: "記号:"},
+		{ID: "symbols.patterns_header", En: "\nInternal patterns:", Ja: "\n図形内パターン:"},
+		{ID: "symbols.connections_header", En: "\nConnection styles:", Ja: "\n接続スタイル:"},
+		{ID: "symbols.entry", En: "  %-3s %-20s %s\n", Ja: "  %-3s %-20s %s\n"},
+		{ID: "symbols.type.outer_circle", En: "Program boundary (required around every program)", Ja: "プログラムの境界（すべてのプログラムに必須）"},
+		{ID: "symbols.type.circle", En: "Function / scope; a function call inside expressions", Ja: "関数/スコープ（式の中では関数呼び出し）"},
+		{ID: "symbols.type.double_circle", En: "Main entry point", Ja: "メインエントリーポイント"},
+		{ID: "symbols.type.square", En: "Variable / data; the value comes from its internal pattern", Ja: "変数/データ（値は図形内パターンで決まる）"},
+		{ID: "symbols.type.rectangle", En: "Block; the statements connected below it run in order", Ja: "ブロック（下に接続された文を順に実行する）"},
+		{ID: "symbols.type.triangle", En: "If-branch; left children run when true, right children otherwise",
+			Ja: "条件分岐（左側の子は真のとき、右側の子はそれ以外のときに実行）"},
+		{ID: "symbols.type.pentagon", En: "Loop; a connected square sets the repeat count", Ja: "ループ（接続された四角形が繰り返し回数を決める）"},
+		{ID: "symbols.type.hexagon", En: "Parallel block", Ja: "並列処理ブロック"},
+		{ID: "symbols.type.star", En: "Output / print", Ja: "出力/表示"},
+		{ID: "symbols.type.six_pointed_star", En: "Output (treated as a star)", Ja: "出力（星として扱われる）"},
+		{ID: "symbols.type.eight_pointed_star", En: "Built-in call (lines inside call env, triple lines read_file, a cross or half circle has_capability for env or fs, empty fed by a quote eval)",
+			Ja: "組み込み関数の呼び出し（中に線があると env、三本線があると read_file、クロスか半円があると env か fs の has_capability、空で引用が接続されていると eval）"},
+		{ID: "symbols.type.convergence", En: "Addition", Ja: "加算"},
+		{ID: "symbols.type.divergence", En: "Subtraction", Ja: "減算"},
+		{ID: "symbols.type.amplification", En: "Multiplication", Ja: "乗算"},
+		{ID: "symbols.type.distribution", En: "Division", Ja: "除算"},
+		{ID: "symbols.type.transfer", En: "Assignment", Ja: "代入"},
+		{ID: "symbols.type.seal", En: "Constant (reserved)", Ja: "定数（予約済み）"},
+		{ID: "symbols.type.circulation", En: "Loop operator (reserved)", Ja: "ループ演算子（予約済み）"},
+		{ID: "symbols.type.equal", En: "Equal (==)", Ja: "等しい (==)"},
+		{ID: "symbols.type.not_equal", En: "Not equal (!=)", Ja: "等しくない (!=)"},
+		{ID: "symbols.type.less_than", En: "Less than (<)", Ja: "より小さい (<)"},
+		{ID: "symbols.type.greater_than", En: "Greater than (>)", Ja: "より大きい (>)"},
+		{ID: "symbols.type.less_equal", En: "Less than or equal (<=)", Ja: "以下 (<=)"},
+		{ID: "symbols.type.greater_equal", En: "Greater than or equal (>=)", Ja: "以上 (>=)"},
+		{ID: "symbols.type.logical_and", En: "Logical AND (reserved)", Ja: "論理積（予約済み）"},
+		{ID: "symbols.type.logical_or", En: "Logical OR (reserved)", Ja: "論理和（予約済み）"},
+		{ID: "symbols.type.logical_not", En: "Logical NOT (reserved)", Ja: "論理否定（予約済み）"},
+		{ID: "symbols.type.logical_xor", En: "Logical XOR (reserved)", Ja: "排他的論理和（予約済み）"},
+		{ID: "symbols.pattern.empty", En: "Integer 0", Ja: "整数 0"},
+		{ID: "symbols.pattern.dot", En: "Integer 1 (a dotted circle is the main entry)", Ja: "整数 1（点入りの円はメインエントリー）"},
+		{ID: "symbols.pattern.double_dot", En: "Integer 2", Ja: "整数 2"},
+		{ID: "symbols.pattern.triple_dot", En: "Integer 3", Ja: "整数 3"},
+		{ID: "symbols.pattern.lines", En: "String", Ja: "文字列"},
+		{ID: "symbols.pattern.triple_line", En: "String", Ja: "文字列"},
+		{ID: "symbols.pattern.cross", En: "Boolean true", Ja: "真偽値 true"},
+		{ID: "symbols.pattern.half_circle", En: "Boolean false", Ja: "真偽値 false"},
+		{ID: "symbols.connection.solid", En: "Flow between symbols", Ja: "シンボル間の流れ"},
+		{ID: "symbols.connection.dashed", En: "Flow between symbols (same meaning as solid)", Ja: "シンボル間の流れ（実線と同じ意味）"},
+		{ID: "symbols.connection.dotted", En: "Flow between symbols (same meaning as solid)", Ja: "シンボル間の流れ（実線と同じ意味）"},
+
+		// Inspect messages
+		{ID: "cli.inspect_description", En: "Inspect symbols, AST, diagnostics and generated code side by side",
+			Ja: "シンボル・AST・診断・生成コードを並べて確認"},
+		{ID: "inspect.title", En: "\n=== Inspecting %s ===\n", Ja: "\n=== %s の検査 ===\n"},
+		{ID: "inspect.symbols_header", En: "Symbols", Ja: "シンボル"},
+		{ID: "inspect.ast_header", En: "AST", Ja: "AST"},
+		{ID: "inspect.diagnostics_header", En: "Diagnostics", Ja: "診断"},
+		{ID: "inspect.code_header", En: "Generated code", Ja: "生成コード"},
+		{ID: "inspect.no_diagnostics", En: "(no problems found)", Ja: "（問題は見つかりませんでした）"},
+		{ID: "inspect.unused_symbol", En: "%s at (%.0f, %.0f) is not used by the program",
+			Ja: "%s 位置: (%.0f, %.0f) はプログラムで使用されていません"},
+		{ID: "inspect.prompt", En: "\n[s]ymbols [a]st [d]iagnostics [c]ode  j/k move  ? help  q quit > ",
+			Ja: "\n[s]シンボル [a]AST [d]診断 [c]コード  j/k 移動  ? ヘルプ  q 終了 > "},
+		{ID: "inspect.keys", En: "\n[s]ymbols [a]st [d]iagnostics [c]ode  ↑/↓ move  ←/→ pane  ? help  q quit",
+			Ja: "\n[s]シンボル [a]AST [d]診断 [c]コード  ↑/↓ 移動  ←/→ ペイン  ? ヘルプ  q 終了"},
+		{ID: "inspect.help",
+			En: "Keys: s/a/d/c switch pane, Tab, → or n next pane, ←, Shift+Tab or p previous pane, ↑/↓ or k/j move up/down, g/G first/last row, q quit.\n" +
+				"In a terminal keys act as they are pressed; when the input is piped, they take effect at the end of each line (e.g. \"jjj\").\n" +
+				"'>' marks the cursor, '*' marks related entries.",
+			Ja: "キー: s/a/d/c ペイン切替、Tab・→・n で次のペイン、←・Shift+Tab・p で前のペイン、↑/↓ または k/j で上下移動、g/G で先頭/末尾、q で終了。\n" +
+				"端末ではキーを押すとすぐに反映され、入力をパイプで渡したときは行ごとに反映されます（例: \"jjj\"）。\n" +
+				"'>' はカーソル、'*' は関連する項目を示します。"},
+
+		// Test messages
+		{ID: "cli.test_description", En: "Run golden tests for a directory of magic circle images",
+			Ja: "魔法陣画像のディレクトリに対してゴールデンテストを実行"},
+		{ID: "cli.test_update_flag_description", En: "Rewrite expectation files with the actual results",
+			Ja: "期待値ファイルを実際の結果で書き換える"},
+		{ID: "test.no_cases", En: "No test cases found in %s\n", Ja: "%s にテストケースが見つかりません\n"},
+		{ID: "test.pass", En: "PASS %s\n", Ja: "成功 %s\n"},
+		{ID: "test.fail", En: "FAIL %s\n", Ja: "失敗 %s\n"},
+		{ID: "test.error", En: "ERROR %s: %v\n", Ja: "エラー %s: %v\n"},
+		{ID: "test.updated", En: "UPDATED %s: %s\n", Ja: "更新 %s: %s\n"},
+		{ID: "test.mismatch", En: "    %s differs (- expected, + actual):\n", Ja: "    %sが異なります（- 期待値, + 実際）:\n"},
+		{ID: "test.kind.output", En: "output", Ja: "出力"},
+		{ID: "test.kind.ast", En: "AST", Ja: "AST"},
+		{ID: "test.assertions_failed", En: "    %d assertion(s) failed:\n", Ja: "    %d 件のアサーションが失敗しました:\n"},
+		{ID: "test.summary", En: "\n%d passed, %d failed, %d total\n", Ja: "\n成功 %d, 失敗 %d, 合計 %d\n"},
+		{ID: "test.failed", En: "%d test case(s) failed", Ja: "%d 件のテストケースが失敗しました"},
+		{ID: "cli.test_coverage_flag_description", En: "Record which symbols ran and write coverage images and coverage.json to this directory",
+			Ja: "実行されたシンボルを記録し、カバレッジ画像と coverage.json をこのディレクトリに出力"},
+		{ID: "test.coverage_case", En: "  %s: %.1f%% (%d/%d symbols)\n", Ja: "  %s: %.1f%%（シンボル %d/%d）\n"},
+		{ID: "test.coverage_total", En: "Coverage: %.1f%% (%d/%d symbols)\n", Ja: "カバレッジ: %.1f%%（シンボル %d/%d）\n"},
+		{ID: "test.coverage_written", En: "Coverage written to %s\n", Ja: "カバレッジを %s に出力しました\n"},
+
+		// Diff messages
+		{ID: "cli.diff_description", En: "Show the symbol and connection changes between two magic circle images",
+			Ja: "2つの魔法陣画像間のシンボルと接続の変更を表示"},
+		{ID: "cli.diff_overlay_flag_description", En: "Write an annotated overlay PNG of both images to this path",
+			Ja: "両画像を重ねて注釈を付けたPNGをこのパスに出力"},
+		{ID: "diff.header", En: "\n=== Diff: %s -> %s ===\n", Ja: "\n=== 差分: %s -> %s ===\n"},
+		{ID: "diff.no_changes", En: "No differences", Ja: "差分はありません"},
+		{ID: "diff.symbols_summary", En: "Symbols: %d added, %d removed, %d changed\n",
+			Ja: "シンボル: 追加 %d, 削除 %d, 変更 %d\n"},
+		{ID: "diff.symbol", En: "  %s %s (%s) at (%.0f, %.0f)\n", Ja: "  %s %s (%s) 位置: (%.0f, %.0f)\n"},
+		{ID: "diff.symbol_changed", En: "  %s %s at (%.0f, %.0f): pattern %s -> %s\n",
+			Ja: "  %s %s 位置: (%.0f, %.0f): パターン %s -> %s\n"},
+		{ID: "diff.connections_header", En: "Connections:", Ja: "接続:"},
+		{ID: "diff.connection", En: "  %s %s -> %s (%s)\n", Ja: "  %s %s -> %s (%s)\n"},
+		{ID: "diff.connection_changed", En: "  %s %s -> %s: %s -> %s\n", Ja: "  %s %s -> %s: %s -> %s\n"},
+		{ID: "diff.overlay_written", En: "\nOverlay written to %s\n", Ja: "\nオーバーレイを %s に出力しました\n"},
+
+		// Batch command
+		{ID: "cli.batch_description", En: "Compile every image in a directory concurrently",
+			Ja: "ディレクトリ内のすべての画像を並行してコンパイル"},
+		{ID: "cli.batch_jobs_flag_description", En: "Number of images to compile at once",
+			Ja: "同時にコンパイルする画像の数"},
+		{ID: "cli.batch_output_flag_description", En: "Directory to write a Python file for each image to",
+			Ja: "画像ごとのPythonファイルを出力するディレクトリ"},
+		{ID: "cli.invalid_jobs", En: "--jobs must be at least 1, got %d",
+			Ja: "--jobs には1以上を指定してください（指定値: %d）"},
+		{ID: "batch.no_images", En: "No images found in %s\n", Ja: "%s に画像が見つかりません\n"},
+		{ID: "batch.ok", En: "OK    %s (%v)\n", Ja: "成功 %s (%v)\n"},
+		{ID: "batch.error", En: "ERROR %s: %v\n", Ja: "エラー %s: %v\n"},
+		{ID: "batch.summary", En: "\n%d succeeded, %d failed, %d total in %v\n",
+			Ja: "\n成功 %d, 失敗 %d, 合計 %d（%v）\n"},
+		{ID: "batch.failed", En: "%d image(s) failed to compile", Ja: "%d 件の画像のコンパイルに失敗しました"},
+
+		// Bench command
+		{ID: "cli.bench_description", En: "Time detection, parsing and compilation of every image in a directory",
+			Ja: "ディレクトリ内の各画像の検出・構文解析・コンパイルの所要時間を計測"},
+		{ID: "cli.bench_baseline_flag_description", En: "Results saved by an earlier run to compare with",
+			Ja: "比較対象となる、以前の実行で保存した結果"},
+		{ID: "cli.bench_save_flag_description", En: "File to save the results to, for later runs to compare with",
+			Ja: "後の実行で比較するために結果を保存するファイル"},
+		{ID: "cli.bench_iterations_flag_description", En: "How many times each stage is timed per image",
+			Ja: "画像ごとに各段階を計測する回数"},
+		{ID: "cli.bench_threshold_flag_description", En: "Change of the median, in percent, below which a benchmark is unchanged",
+			Ja: "変化なしとみなす中央値の変化率（パーセント）"},
+		{ID: "bench.invalid_iterations", En: "--iterations must be at least 1, got %d",
+			Ja: "--iterations には1以上を指定してください（指定値: %d）"},
+		{ID: "bench.invalid_threshold", En: "--threshold must not be negative, got %g",
+			Ja: "--threshold には0以上を指定してください（指定値: %g）"},
+		{ID: "bench.header", En: "\nbenchmark                                  median          min   noise\n",
+			Ja: "\nベンチマーク                               中央値         最小 ばらつき\n"},
+		{ID: "bench.row", En: "%-36s %12s %12s %6.1f%%\n", Ja: "%-36s %12s %12s %6.1f%%\n"},
+		{ID: "bench.baseline", En: "\nCompared with %s from %s\n", Ja: "\n%[2]s の %[1]s と比較\n"},
+		{ID: "bench.compare_header", En: "benchmark                                baseline      current   change\n",
+			Ja: "ベンチマーク                               比較元         今回     変化\n"},
+		{ID: "bench.compare_row", En: "%-36s %12s %12s %8s  %s\n", Ja: "%-36s %12s %12s %8s  %s\n"},
+		{ID: "bench.verdict.regressed", En: "regressed", Ja: "悪化"},
+		{ID: "bench.verdict.improved", En: "improved", Ja: "改善"},
+		{ID: "bench.verdict.unchanged", En: "unchanged", Ja: "変化なし"},
+		{ID: "bench.verdict.added", En: "added", Ja: "追加"},
+		{ID: "bench.verdict.removed", En: "removed", Ja: "削除"},
+		{ID: "bench.compare_summary", En: "\n%d regressed, %d improved, %d unchanged\n",
+			Ja: "\n悪化 %d, 改善 %d, 変化なし %d\n"},
+		{ID: "bench.saved", En: "\nResults saved to %s\n", Ja: "\n結果を %s に保存しました\n"},
+		{ID: "bench.failed", En: "%d image(s) could not be benchmarked", Ja: "%d 件の画像を計測できませんでした"},
+
+		// Dataset command
+		{ID: "cli.dataset_description", En: "Build training data for symbol classifiers",
+			Ja: "シンボル分類器の学習データを作成"},
+		{ID: "cli.dataset_extract_description", En: "Crop the symbols of every image in a directory into labeled patches",
+			Ja: "ディレクトリ内の各画像のシンボルをラベル付きの画像パッチに切り出す"},
+		{ID: "cli.dataset_out_flag_description", En: "Directory to write the patches and labels.csv to",
+			Ja: "パッチと labels.csv を書き出すディレクトリ"},
+		{ID: "cli.dataset_size_flag_description", En: "Side of each patch in pixels",
+			Ja: "パッチ一辺のピクセル数"},
+		{ID: "cli.dataset_margin_flag_description", En: "Space around each symbol, as a fraction of its size",
+			Ja: "シンボルの周囲に残す余白（シンボルの大きさに対する割合）"},
+		{ID: "dataset.no_out", En: "Give the directory to write the dataset to with --out",
+			Ja: "データセットの出力先ディレクトリを --out で指定してください"},
+		{ID: "dataset.invalid_size", En: "--size must be at least 1, got %d",
+			Ja: "--size には1以上を指定してください（指定値: %d）"},
+		{ID: "dataset.invalid_margin", En: "--margin must not be negative, got %g",
+			Ja: "--margin には0以上を指定してください（指定値: %g）"},
+		{ID: "dataset.detected", En: "detected", Ja: "検出"},
+		{ID: "dataset.labeled", En: "labeled", Ja: "ラベル"},
+		{ID: "dataset.image", En: "OK    %s: %d patches (%s)\n", Ja: "成功 %s: %d 個のパッチ（%s）\n"},
+		{ID: "dataset.summary", En: "\nWrote %d patches of %d labels from %d images to %s\n",
+			Ja: "\n%[3]d 枚の画像から %[2]d 種類のラベルのパッチを %[1]d 個 %[4]s に書き出しました\n"},
+		{ID: "dataset.failed", En: "%d image(s) could not be cropped", Ja: "%d 件の画像を切り出せませんでした"},
+
+		// Cache command
+		{ID: "cli.cache_description", En: "Manage the cache of compiled programs used by run",
+			Ja: "run が使用するコンパイル済みプログラムのキャッシュを管理"},
+		{ID: "cli.cache_stats_description", En: "Show the number and size of cached programs",
+			Ja: "キャッシュされたプログラムの数とサイズを表示"},
+		{ID: "cli.cache_clear_description", En: "Remove every cached program",
+			Ja: "キャッシュされたプログラムをすべて削除"},
+		{ID: "cli.no_cache_flag_description", En: "Always process the image instead of running a cached program",
+			Ja: "キャッシュされたプログラムを使わず常に画像を処理する"},
+		{ID: "cache.stats", En: "Cache directory: %s\nEntries: %d\nSize: %d bytes\n",
+			Ja: "キャッシュディレクトリ: %s\nエントリ数: %d\nサイズ: %d バイト\n"},
+		{ID: "cache.cleared", En: "Removed %d cached program(s) from %s\n",
+			Ja: "%[2]s から %[1]d 件のキャッシュされたプログラムを削除しました\n"},
+		{ID: "cache.clear_failed", En: "Failed to clear the cache", Ja: "キャッシュの削除に失敗しました"},
+		{ID: "cache.no_directory", En: "No cache directory is available", Ja: "キャッシュディレクトリがありません"},
+		{ID: "cache.no_directory_suggestion", En: "Set %s to the directory to cache programs in",
+			Ja: "%s にキャッシュ用のディレクトリを設定してください"},
+
+		// Correct command
+		{ID: "cli.correct_description", En: "Print the detected symbols as JSON to correct misdetections, or save corrected ones",
+			Ja: "誤検出を修正するため検出したシンボルをJSONで表示、または修正したものを保存"},
+		{ID: "cli.correct_apply_flag_description", En: "Save the corrected detection in this file, used in place of detecting the image from now on",
+			Ja: "このファイルの修正済みの検出結果を保存し、以後は画像を検出する代わりに使用する"},
+		{ID: "cli.correct_clear_flag_description", En: "Remove the corrections saved for the image",
+			Ja: "画像に保存された修正を削除する"},
+		{ID: "correct.saved", En: "Saved the corrections for %s; they replace its detection from now on\n",
+			Ja: "%s の修正を保存しました。以後は検出の代わりに使用されます\n"},
+		{ID: "correct.cleared", En: "Removed the corrections for %s\n", Ja: "%s の修正を削除しました\n"},
+		{ID: "correct.none", En: "No corrections are saved for %s\n", Ja: "%s に保存された修正はありません\n"},
+		{ID: "correct.using", En: "Using the corrections saved for %s\n", Ja: "%s に保存された修正を使用します\n"},
+		{ID: "correct.invalid", En: "The corrections are not a valid detection", Ja: "修正が正しい検出結果ではありません"},
+		{ID: "correct.invalid_suggestion", En: "Edit the JSON printed by grimoire correct, keeping its schemaVersion, symbols and connections",
+			Ja: "grimoire correct が表示したJSONを、schemaVersion・symbols・connections を保ったまま編集してください"},
+		{ID: "correct.failed", En: "Failed to save the corrections", Ja: "修正の保存に失敗しました"},
+
+		// History command
+		{ID: "cli.history_description", En: "List and replay the runs recorded when GRIMOIRE_HISTORY is set",
+			Ja: "GRIMOIRE_HISTORY を設定したときに記録された実行の一覧表示と再実行"},
+		{ID: "cli.history_list_description", En: "List the recorded runs and compilations, oldest first",
+			Ja: "記録された実行とコンパイルを古い順に一覧表示"},
+		{ID: "cli.history_replay_description", En: "Run the recorded program again, or print it for a compilation",
+			Ja: "記録されたプログラムを再実行する（コンパイルの場合は表示する）"},
+		{ID: "history.header", En: "ID        Time                 Command  Target  Outcome  Image",
+			Ja: "ID        日時                 コマンド  出力先  結果     画像"},
+		{ID: "history.entry", En: "%-8s  %-19s  %-7s  %-6s  %-7s  %s\n", Ja: "%-8s  %-19s  %-8s  %-6s  %-7s  %s\n"},
+		{ID: "history.empty", En: "No runs are recorded; set %s=1 to record them\n",
+			Ja: "記録された実行はありません。記録するには %s=1 を設定してください\n"},
+		{ID: "history.no_directory", En: "No history directory is available", Ja: "履歴ディレクトリがありません"},
+		{ID: "history.no_directory_suggestion", En: "Set %s to the directory to record history in",
+			Ja: "%s に履歴用のディレクトリを設定してください"},
+		{ID: "history.not_found", En: "No recorded run has the ID %s", Ja: "ID %s の実行は記録されていません"},
+		{ID: "history.not_found_suggestion", En: "Run grimoire history list to see the recorded IDs",
+			Ja: "grimoire history list で記録された ID を確認してください"},
+		{ID: "history.no_code", En: "Run %s failed before a program was generated, so there is nothing to replay",
+			Ja: "実行 %s はプログラムの生成前に失敗したため、再実行できません"},
+		{ID: "history.image_changed", En: "Warning: %s has changed since it was recorded; replaying the recorded program\n",
+			Ja: "警告: %s は記録後に変更されています。記録されたプログラムを再実行します\n"},
+
+		// Profiling
+		{ID: "cli.profile_flag_description", En: "Report how often each symbol ran and the time spent in it",
+			Ja: "各シンボルの実行回数と実行時間を報告する"},
+		{ID: "cli.profile_image_flag_description", En: "Write a copy of the image where hot symbols glow brighter (implies --profile)",
+			Ja: "実行時間の長いシンボルほど明るく光る画像のコピーを書き出す（--profile を含む）"},
+		{ID: "cli.animate_flag_description", En: "Write a GIF stepping through the run with the active symbol lit up in each frame",
+			Ja: "実行中のシンボルをフレームごとに光らせて実行の流れをたどるGIFを書き出す"},
+		{ID: "profile.header", En: "\nProfile:\n  symbol  type              role              count    time (ms)\n",
+			Ja: "\nプロファイル:\n  シンボル  種類              役割              回数     時間 (ms)\n"},
+		{ID: "profile.row", En: "  #%-5d  %-16s  %-16s  %7d  %11.3f\n", Ja: "  #%-7d  %-16s  %-16s  %7d  %11.3f\n"},
+		{ID: "profile.image_written", En: "\nProfile image written to %s\n", Ja: "\nプロファイル画像を %s に出力しました\n"},
+		{ID: "profile.animation_written", En: "\nExecution animation written to %s\n", Ja: "\n実行アニメーションを %s に出力しました\n"},
+
+		// Program statistics
+		{ID: "cli.stats_description", En: "Summarize the program in an image: symbols, functions, nesting and complexity",
+			Ja: "画像のプログラムを要約（シンボル数・関数数・ネストの深さ・複雑度など）"},
+		{ID: "cli.stats_max_complexity_flag_description", En: "Fail when the cyclomatic complexity is above this (0 for no limit)",
+			Ja: "循環的複雑度がこの値を超えたら失敗する（0 で無制限）"},
+		{ID: "cli.stats_max_depth_flag_description", En: "Fail when blocks nest deeper than this (0 for no limit)",
+			Ja: "ブロックのネストがこの深さを超えたら失敗する（0 で無制限）"},
+		{ID: "stats.header", En: "Program statistics for %s\n  Symbols:\n", Ja: "%s のプログラム統計\n  シンボル:\n"},
+		{ID: "stats.symbol", En: "    %-18s %5d\n", Ja: "    %-18s %5d\n"},
+		{ID: "stats.functions", En: "  Functions            %5d\n", Ja: "  関数                 %5d\n"},
+		{ID: "stats.statements", En: "  Statements           %5d\n", Ja: "  文                   %5d\n"},
+		{ID: "stats.max_depth", En: "  Max nesting depth    %5d\n", Ja: "  最大ネスト           %5d\n"},
+		{ID: "stats.complexity", En: "  Cyclomatic complexity %4d\n", Ja: "  循環的複雑度         %5d\n"},
+		{ID: "stats.bytecode", En: "  Bytecode (estimate)  %5d bytes\n", Ja: "  バイトコード（推定） %5d バイト\n"},
+		{ID: "stats.over_budget", En: "The program is over its complexity budget", Ja: "プログラムが複雑度の上限を超えています"},
+		{ID: "stats.over_complexity", En: "Cyclomatic complexity %d is above the limit of %d",
+			Ja: "循環的複雑度 %d が上限 %d を超えています"},
+		{ID: "stats.over_depth", En: "Nesting depth %d is above the limit of %d", Ja: "ネストの深さ %d が上限 %d を超えています"},
+
+		// Screen capture
+		{ID: "cli.capture_flag_description", En: "Select a region of the screen and run the circle in it instead of an image file",
+			Ja: "画像ファイルの代わりに、画面上で選択した範囲の魔法陣を実行する"},
+		{ID: "capture.no_tool", En: "No screen capture tool was found", Ja: "画面キャプチャツールが見つかりません"},
+		{ID: "capture.no_tool_suggestion", En: "Install one of: %s", Ja: "次のいずれかをインストールしてください: %s"},
+		{ID: "capture.tool_failed", En: "Screen capture with %s failed", Ja: "%s による画面キャプチャに失敗しました"},
+		{ID: "capture.failed", En: "Failed to capture the screen", Ja: "画面のキャプチャに失敗しました"},
+		{ID: "capture.cancelled", En: "Screen capture was cancelled", Ja: "画面キャプチャがキャンセルされました"},
+
+		// Diagnostic messages
+		{ID: "cli.conform_description", En: "Check that detection backends agree on a directory of fixture images",
+			Ja: "ディレクトリ内のフィクスチャ画像で検出バックエンドの結果が一致するか確認"},
+		{ID: "cli.conform_tolerance_flag_description", En: "Largest symbol position difference in pixels that still conforms",
+			Ja: "一致とみなすシンボル位置の最大差（ピクセル）"},
+		{ID: "cli.conform_reference_flag_description", En: "Backend whose results are treated as correct",
+			Ja: "正しい結果とみなすバックエンド"},
+		{ID: "cli.conform_candidate_flag_description", En: "Backend checked against the reference",
+			Ja: "基準と比較するバックエンド"},
+		{ID: "cli.conform_round_trip_flag_description", En: "Redraw each fixture with the renderer and check that the redrawing reads as the same program",
+			Ja: "各フィクスチャをレンダラーで描き直し、描き直した画像が同じプログラムとして読めるか確認"},
+		{ID: "cli.conform_robustness_flag_description", En: "Degrade each fixture with noise, JPEG artifacts, rotation, blur and salt-and-pepper, and report the level at which it stops reading the same",
+			Ja: "各フィクスチャにノイズ・JPEGの劣化・回転・ぼかし・ごま塩ノイズを加え、同じプログラムとして読めなくなる程度を報告"},
+		{ID: "cli.conform_update_flag_description", En: "With --robustness, record the measured levels as the thresholds to keep",
+			Ja: "--robustness と併用し、測定した程度を維持すべきしきい値として記録"},
+		{ID: "conform.no_fixtures", En: "No fixture images found in %s\n", Ja: "%s にフィクスチャ画像が見つかりません\n"},
+		{ID: "conform.pass", En: "CONFORM %s\n", Ja: "一致 %s\n"},
+		{ID: "conform.diverge", En: "DIVERGE %s\n", Ja: "不一致 %s\n"},
+		{ID: "conform.error", En: "ERROR %s: %v\n", Ja: "エラー %s: %v\n"},
+		{ID: "conform.divergence", En: "    %s: %s\n", Ja: "    %s: %s\n"},
+		{ID: "conform.ast_differs", En: "    AST differs (- reference, + candidate):\n", Ja: "    ASTが異なります（- 基準, + 比較対象）:\n"},
+		{ID: "conform.ast_differs_round_trip", En: "    AST differs (- drawing, + redrawing):\n", Ja: "    ASTが異なります（- 元の画像, + 描き直した画像）:\n"},
+		{ID: "conform.kind.detection", En: "detection failed", Ja: "検出失敗"},
+		{ID: "conform.kind.count", En: "symbol count", Ja: "シンボル数"},
+		{ID: "conform.kind.type", En: "symbol type", Ja: "シンボルの種類"},
+		{ID: "conform.kind.position", En: "position", Ja: "位置"},
+		{ID: "conform.summary", En: "\n%d conform, %d diverge, %d total\n", Ja: "\n一致 %d, 不一致 %d, 合計 %d\n"},
+		{ID: "conform.fidelity", En: "Round-trip fidelity: %.1f%%\n", Ja: "往復の忠実度: %.1f%%\n"},
+		{ID: "conform.robustness_image", En: "%s tolerates up to:\n", Ja: "%s が耐えられる程度:\n"},
+		{ID: "conform.robustness_level", En: "    %-12s %.2f\n", Ja: "    %-12s %.2f\n"},
+		{ID: "conform.robustness_regressed", En: "    %-12s %.2f, down from %.2f\n", Ja: "    %-12s %.2f（記録は %.2f）\n"},
+		{ID: "conform.thresholds_written", En: "\nThresholds written to %s\n", Ja: "\nしきい値を %s に出力しました\n"},
+		{ID: "conform.robustness_failed", En: "%d threshold(s) regressed", Ja: "%d 件のしきい値を下回りました"},
+		{ID: "conform.failed", En: "%d fixture(s) diverged", Ja: "%d 件のフィクスチャが一致しませんでした"},
+		{ID: "cli.format_flag_description", En: "Error output format: text or json",
+			Ja: "エラーの出力形式: text または json"},
+		{ID: "cli.thumbnails_flag_description", En: "Write a cropped image of each error location to this directory",
+			Ja: "各エラー箇所を切り出した画像をこのディレクトリに出力"},
+		{ID: "cli.invalid_format", En: "Unknown output format %q (use text or json)",
+			Ja: "不明な出力形式 %q です（text または json を指定してください）"},
+		{ID: "cli.backend_flag_description", En: "Detection backend: sequential, parallel or auto",
+			Ja: "検出バックエンド: sequential、parallel または auto"},
+		{ID: "cli.allow_huge_flag_description", En: "Accept images beyond the default size limits (only for trusted images)",
+			Ja: "既定のサイズ制限を超える画像を受け付ける（信頼できる画像のみ）"},
+		{ID: "cli.invalid_backend", En: "Unknown detection backend %q (use sequential, parallel or auto)",
+			Ja: "不明な検出バックエンド %q です（sequential、parallel または auto を指定してください）"},
+		{ID: "cli.min_confidence_flag_description", En: "Drop symbols whose confidence (0 to 1) is below this value",
+			Ja: "信頼度（0〜1）がこの値未満のシンボルを除外する"},
+		{ID: "cli.invalid_min_confidence", En: "Minimum confidence %g is out of range (use a value from 0 to 1)",
+			Ja: "最小信頼度 %g が範囲外です（0〜1 の値を指定してください）"},
+		{ID: "cli.preprocess_flag_description",
+			En: "Preprocess with a chain of steps instead of the built-in one, e.g. \"blur{r=1}, adaptive_threshold{block=31}, close{r=2}\"",
+			Ja: "組み込みの前処理の代わりに指定した手順で前処理する（例: \"blur{r=1}, adaptive_threshold{block=31}, close{r=2}\"）"},
+		{ID: "cli.invalid_preprocess", En: "Invalid preprocessing chain: %v", Ja: "前処理の指定が正しくありません: %v"},
+		{ID: "cli.inks_flag_description",
+			En: "Separate the image into this many inks by color and detect each apart, for overlapping strokes of different colors",
+			Ja: "画像を色でこの数のインクに分け、それぞれを別に検出する（色の違う線が重なる場合に使用）"},
+		{ID: "cli.invalid_inks", En: "Ink count %d is out of range (use 0 to %d)", Ja: "インク数 %d が範囲外です（0〜%d の値を指定してください）"},
+		{ID: "cli.strict_flag_description", En: "Fail on any shape inside the circle that matches no symbol",
+			Ja: "円の中にどのシンボルにも当てはまらない図形があればエラーにする"},
+		{ID: "cli.permissive_flag_description", En: "Warn of shapes that match no symbol and symbols out of place, and ignore them",
+			Ja: "どのシンボルにも当てはまらない図形や置き場所の誤ったシンボルを警告して無視する"},
+		{ID: "cli.strict_and_permissive", En: "--strict and --permissive cannot be used together",
+			Ja: "--strict と --permissive は同時に指定できません"},
+		{ID: "cli.timings_flag_description", En: "Print the time spent in each detection stage and the contours and symbols found",
+			Ja: "検出の各段階にかかった時間と、見つかった輪郭・シンボルの数を表示する"},
+		{ID: "cli.detect_timeout_flag_description", En: "Stop detection after this long with the symbols found so far (e.g. 30s; 0 for no limit)",
+			Ja: "この時間を過ぎたら、それまでに見つかったシンボルで検出を打ち切る（例: 30s、0 で無制限）"},
+		{ID: "cli.max_contours_flag_description", En: "Reject images with more contours than this (0 for no limit)",
+			Ja: "輪郭がこの数を超える画像を拒否する（0 で無制限）"},
+		{ID: "cli.max_contour_points_flag_description", En: "Reject images with a contour of more points than this (0 for no limit)",
+			Ja: "この点数を超える輪郭を含む画像を拒否する（0 で無制限）"},
+		{ID: "cli.invalid_limit", En: "%s must not be negative, got %d", Ja: "%s には0以上を指定してください（指定値: %d）"},
+		{ID: "cli.invalid_detect_timeout", En: "--detect-timeout must not be negative, got %v",
+			Ja: "--detect-timeout には0以上を指定してください（指定値: %v）"},
+		{ID: "timings.header", En: "\nDetection timings:\n", Ja: "\n検出の所要時間:\n"},
+		{ID: "timings.stage", En: "  %-14s %9.2f ms\n", Ja: "  %-14s %9.2f ms\n"},
+		{ID: "timings.total", En: "  %-14s %9.2f ms\n", Ja: "  %-14s %9.2f ms\n"},
+		{ID: "timings.total_label", En: "total", Ja: "合計"},
+		{ID: "timings.counts", En: "  contours %d, candidates %d, rejected %d, accepted %d, connections %d\n",
+			Ja: "  輪郭 %d, 候補 %d, 除外 %d, 採用 %d, 接続 %d\n"},
+		{ID: "timings.peak_heap", En: "  peak heap %.1f MiB\n", Ja: "  ヒープ使用量の最大 %.1f MiB\n"},
+		{ID: "diagnostic.severity.error", En: "error", Ja: "エラー"},
+		{ID: "diagnostic.severity.warning", En: "warning", Ja: "警告"},
+		{ID: "diagnostic.help", En: "help", Ja: "ヘルプ"},
+		{ID: "diagnostic.thumbnail", En: "thumbnail", Ja: "サムネイル"},
+		{ID: "diagnostic.cause", En: "caused by: %s", Ja: "原因: %s"},
+
+		// Language server messages
+		{ID: "cli.lsp_description", En: "Start a language server for magic circle images over stdio",
+			Ja: "標準入出力で魔法陣画像の言語サーバーを起動"},
+		{ID: "lsp.invalid_symbol", En: "Unrecognized symbol", Ja: "認識できないシンボルです"},
+		{ID: "cli.serve_description", En: "Compile images posted over HTTP and export metrics for monitoring",
+			Ja: "HTTPで送られた画像をコンパイルし、監視用のメトリクスを公開"},
+		{ID: "cli.serve_addr_flag_description", En: "Address to listen on; only localhost by default, pass :8080 to listen on every interface",
+			Ja: "待ち受けるアドレス（デフォルトはlocalhostのみ。すべてのインターフェースで待ち受けるには :8080 を指定）"},
+		{ID: "serve.listening", En: "Listening on %s (POST /compile, GET /metrics)\n",
+			Ja: "%s で待ち受けています（POST /compile, GET /metrics）\n"},
+		{ID: "serve.failed", En: "Failed to serve on %s", Ja: "%s での待ち受けに失敗しました"},
+		{ID: "cli.daemon_description", En: "Keep a detector running on a unix socket for commands run with --remote",
+			Ja: "--remote 付きで実行したコマンドのために、UNIXソケットで検出器を常駐させる"},
+		{ID: "cli.daemon_socket_flag_description", En: "Unix socket to listen on (default $GRIMOIRE_SOCKET)",
+			Ja: "待ち受けるUNIXソケット（デフォルトは $GRIMOIRE_SOCKET）"},
+		{ID: "cli.remote_flag_description", En: "Detect through the running grimoire daemon instead of in this process",
+			Ja: "このプロセスではなく、起動中の grimoire daemon で検出する"},
+		{ID: "daemon.listening", En: "Daemon listening on %s\n", Ja: "%s でデーモンが待ち受けています\n"},
+		{ID: "remote.unreachable", En: "Could not reach the daemon on %s; start it with grimoire daemon",
+			Ja: "%s のデーモンに接続できません。grimoire daemon で起動してください"},
+		{ID: "remote.failed", En: "The daemon answered %s", Ja: "デーモンの応答: %s"},
+		{ID: "lsp.unconnected_symbol", En: "%s is not connected to any other symbol",
+			Ja: "%s はどのシンボルにも接続されていません"},
+
+		// Check command
+		{ID: "cli.check_description", En: "Check an image for errors without generating code",
+			Ja: "コードを生成せずに画像のエラーをチェックします"},
+		{ID: "check.success", En: "✓ %s: no problems found (%d symbols, %d connections)\n",
+			Ja: "✓ %s: 問題は見つかりませんでした（シンボル %d 個、接続 %d 個）\n"},
+
+		// QR code embedding
+		{ID: "cli.emit_flag_description", En: "Output target: python, or qr to embed the program in a copy of the image",
+			Ja: "出力形式: python、または画像のコピーにプログラムを埋め込む qr"},
+		{ID: "cli.invalid_emit", En: "Unknown emit target %q (use python or qr)",
+			Ja: "不明な出力形式 %q です（python または qr を指定してください）"},
+		{ID: "cli.emit_qr_requires_output", En: "--emit qr requires an output image path (-o)",
+			Ja: "--emit qr には出力画像のパス（-o）が必要です"},
+		{ID: "cli.compile_qr_success", En: "Wrote image with embedded program to %s\n",
+			Ja: "プログラムを埋め込んだ画像を %s に書き込みました\n"},
+		{ID: "qr.program_too_large", En: "The compiled program is too large to fit in a QR code",
+			Ja: "コンパイルされたプログラムが大きすぎてQRコードに収まりません"},
+		{ID: "qr.program_too_large_suggestion", En: "Split the program into library images or compile to a Python file instead",
+			Ja: "プログラムをライブラリ画像に分割するか、Pythonファイルにコンパイルしてください"},
+		{ID: "cli.from_qr_flag_description", En: "Run the program embedded by compile --emit qr instead of the drawing; with --trusted-key, only if it is signed with a trusted key",
+			Ja: "図形の代わりに compile --emit qr で埋め込まれたプログラムを実行（--trusted-key を指定すると信頼する鍵で署名されている場合のみ）"},
+		{ID: "cli.qr_key_flag_description", En: "Sign the program embedded by --emit qr with this ed25519 private key",
+			Ja: "--emit qr で埋め込むプログラムにこの ed25519 秘密鍵で署名"},
+		{ID: "qr.no_program", En: "The image has no program embedded by compile --emit qr",
+			Ja: "画像に compile --emit qr で埋め込まれたプログラムがありません"},
+		{ID: "qr.no_program_suggestion", En: "Run the image without --from-qr to compile its drawing",
+			Ja: "--from-qr を付けずに実行すると図形がコンパイルされます"},
+		{ID: "qr.unsigned", En: "The program embedded in the QR code is not signed",
+			Ja: "QRコードに埋め込まれたプログラムは署名されていません"},
+		{ID: "qr.unsigned_suggestion", En: "Embed it signed with compile --emit qr --key, or run the image without --from-qr to compile its drawing",
+			Ja: "compile --emit qr --key で署名して埋め込むか、--from-qr を付けずに実行して図形をコンパイルしてください"},
+		{ID: "qr.signature_mismatch", En: "The signature of the program embedded in the QR code does not match it; it was changed after signing",
+			Ja: "QRコードに埋め込まれたプログラムの署名が一致しません。署名後に変更されています"},
+		{ID: "qr.untrusted_key", En: "The program embedded in the QR code is signed with an untrusted key (%s)",
+			Ja: "QRコードに埋め込まれたプログラムは信頼されていない鍵（%s）で署名されています"},
+
+		// Example gallery
+		{ID: "cli.examples_description", En: "List and run the sample magic circles shipped with Grimoire",
+			Ja: "Grimoireに同梱されたサンプル魔法陣の一覧表示と実行"},
+		{ID: "cli.examples_list_description", En: "List the sample magic circles", Ja: "サンプル魔法陣を一覧表示"},
+		{ID: "cli.examples_run_description", En: "Compile and run a sample magic circle", Ja: "サンプル魔法陣をコンパイルして実行"},
+		{ID: "cli.unknown_example", En: "Unknown example %q", Ja: "不明なサンプル %q です"},
+		{ID: "suggest.list_examples", En: "Run 'grimoire examples list' to see the available examples",
+			Ja: "'grimoire examples list' で利用できるサンプルを確認してください"},
+		{ID: "examples.header", En: "Examples:", Ja: "サンプル:"},
+		{ID: "examples.entry", En: "  %-12s %s\n", Ja: "  %-12s %s\n"},
+		{ID: "examples.run_hint", En: "\nRun one with: grimoire examples run <name>", Ja: "\n実行方法: grimoire examples run <名前>"},
+		{ID: "examples.description.hello-world", En: "The smallest program: a main entry that outputs a star",
+			Ja: "最小のプログラム: メインエントリーから星を出力"},
+		{ID: "examples.description.variables", En: "Variables holding values of each data type",
+			Ja: "各データ型の値を持つ変数"},
+		{ID: "examples.description.calculator", En: "Arithmetic on numbers with operator symbols",
+			Ja: "演算記号による数値の計算"},
+		{ID: "examples.description.loop", En: "Repeating statements with a loop",
+			Ja: "ループによる繰り返し"},
+		{ID: "examples.description.fibonacci", En: "The Fibonacci sequence",
+			Ja: "フィボナッチ数列"},
+		{ID: "examples.description.parallel", En: "Branches that run in parallel",
+			Ja: "並列に実行される分岐"},
+
+		// C library messages
+		{ID: "lib.invalid_input", En: "Invalid image data: the pointer is NULL or the length %d is negative",
+			Ja: "画像データが不正です: ポインタが NULL か、長さ %d が負です"},
+		{ID: "lib.invalid_input_suggestion", En: "Pass a pointer to the encoded image and its length in bytes",
+			Ja: "エンコードされた画像へのポインタと、そのバイト数を渡してください"},
+		{ID: "lib.panic", En: "Internal error in libgrimoire: %v", Ja: "libgrimoire の内部エラー: %v"},
+
 		// Error formatting
 		{ID: "error.at_location", En: "  at %s:%d:%d", Ja: "  場所: %s:%d:%d"},
 		{ID: "error.at_line", En: "  at %s:%d", Ja: "  場所: %s:%d"},
@@ -2,6 +2,8 @@ package i18n
 
 import (
 	"os"
+	"strconv"
+	"strings"
 	"testing"
 )
 
@@ -154,6 +156,66 @@ func TestTranslateF(t *testing.T) {
 	}
 }
 
+func TestMessagesMatch(t *testing.T) {
+	localizer := NewLocalizer(English)
+	localizer.loadMessages()
+
+	for id, msg := range localizer.messages {
+		if msg.En == "" || msg.Ja == "" {
+			t.Errorf("%s is missing a translation", id)
+			continue
+		}
+		en, ja := formatVerbs(msg.En), formatVerbs(msg.Ja)
+		if len(en) != len(ja) {
+			t.Errorf("%s: English uses arguments %v, Japanese %v", id, en, ja)
+			continue
+		}
+		for arg, verb := range en {
+			if ja[arg] != verb {
+				t.Errorf("%s: English uses arguments %v, Japanese %v", id, en, ja)
+				break
+			}
+		}
+	}
+}
+
+// formatVerbs returns the verb each argument of format is formatted with,
+// following explicit argument indexes such as %[2]s
+func formatVerbs(format string) map[int]byte {
+	verbs := make(map[int]byte)
+	arg := 0
+	for i := 0; i < len(format); i++ {
+		if format[i] != '%' {
+			continue
+		}
+		i++
+		if i < len(format) && format[i] == '%' {
+			continue
+		}
+		for i < len(format) && strings.IndexByte("-+# 0", format[i]) >= 0 {
+			i++
+		}
+		if i < len(format) && format[i] == '[' {
+			end := strings.IndexByte(format[i:], ']')
+			if end < 0 {
+				break
+			}
+			if n, err := strconv.Atoi(format[i+1 : i+end]); err == nil {
+				arg = n - 1
+			}
+			i += end + 1
+		}
+		for i < len(format) && (format[i] == '.' || (format[i] >= '0' && format[i] <= '9')) {
+			i++
+		}
+		if i < len(format) {
+			verbs[arg] = format[i]
+			arg++
+		}
+	}
+	return verbs
+}
+
 func TestGlobalFunctions(t *testing.T) {
 	// Set to Japanese
 	SetLanguage(Japanese)
@@ -0,0 +1,127 @@
+// Package ident turns arbitrary labels, such as file names written in
+// Japanese, into identifiers every code generator can emit. Mangling is
+// deterministic, so the same label always yields the same identifier.
+package ident
+
+import (
+	"fmt"
+	"strings"
+	"unicode"
+)
+
+// keywords are the reserved words of Python, which cannot be identifiers
+var keywords = map[string]bool{
+	"False": true, "None": true, "True": true, "and": true, "as": true,
+	"assert": true, "async": true, "await": true, "break": true, "class": true,
+	"continue": true, "def": true, "del": true, "elif": true, "else": true,
+	"except": true, "finally": true, "for": true, "from": true, "global": true,
+	"if": true, "import": true, "in": true, "is": true, "lambda": true,
+	"nonlocal": true, "not": true, "or": true, "pass": true, "raise": true,
+	"return": true, "try": true, "while": true, "with": true, "yield": true,
+}
+
+// tokenKind classifies what was last written to an identifier, to decide
+// where separators go
+type tokenKind int
+
+const (
+	separatorToken tokenKind = iota
+	wordToken
+	escapeToken
+)
+
+// Mangle returns an ASCII identifier for label. ASCII letters and digits
+// are kept and other characters become underscores. Kana are written in
+// romaji, and other letters and digits, such as kanji, as their code point
+// in hex (u6f22), so different labels rarely share an identifier. A leading
+// digit is prefixed and a keyword suffixed with an underscore.
+func Mangle(label string) string {
+	var b strings.Builder
+	last := separatorToken
+	write := func(s string, kind tokenKind) {
+		if kind == escapeToken && last != separatorToken || kind == wordToken && last == escapeToken {
+			b.WriteByte('_')
+		}
+		b.WriteString(s)
+		last = kind
+	}
+
+	runes := []rune(label)
+	for i := 0; i < len(runes); i++ {
+		r := fold(runes[i])
+		switch {
+		case r == '_':
+			write("_", separatorToken)
+		case r < unicode.MaxASCII && (unicode.IsLetter(r) || unicode.IsDigit(r)):
+			write(string(r), wordToken)
+		case isKana(r):
+			end := i + 1
+			for end < len(runes) && isKana(fold(runes[end])) {
+				end++
+			}
+			write(romaji(runes[i:end]), wordToken)
+			i = end - 1
+		case unicode.IsLetter(r) || unicode.IsDigit(r):
+			write(fmt.Sprintf("u%04x", r), escapeToken)
+		default:
+			write("_", separatorToken)
+		}
+	}
+
+	name := b.String()
+	if name == "" || unicode.IsDigit(rune(name[0])) {
+		name = "_" + name
+	}
+	if keywords[name] {
+		name += "_"
+	}
+	return name
+}
+
+// fold maps full-width ASCII, common in Japanese text, to ASCII
+func fold(r rune) rune {
+	switch {
+	case r >= 0xFF01 && r <= 0xFF5E:
+		return r - 0xFEE0
+	case r == 0x3000: // Ideographic space
+		return ' '
+	}
+	return r
+}
+
+// Namer hands out a distinct identifier for each label. A label asked for
+// again gets the identifier it got before, and a label that mangles to an
+// identifier already taken gets a numeric suffix, in the order labels are
+// first named.
+type Namer struct {
+	names map[string]string
+	taken map[string]bool
+}
+
+// NewNamer creates a namer that never hands out the reserved identifiers
+func NewNamer(reserved ...string) *Namer {
+	n := &Namer{
+		names: make(map[string]string),
+		taken: make(map[string]bool),
+	}
+	for _, name := range reserved {
+		n.taken[name] = true
+	}
+	return n
+}
+
+// Name returns the identifier for label
+func (n *Namer) Name(label string) string {
+	if name, ok := n.names[label]; ok {
+		return name
+	}
+
+	base := Mangle(label)
+	name := base
+	for i := 2; n.taken[name]; i++ {
+		name = fmt.Sprintf("%s_%d", base, i)
+	}
+	n.names[label] = name
+	n.taken[name] = true
+	return name
+}
@@ -0,0 +1,124 @@
+package ident
+
+import (
+	"regexp"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+// identifier matches the ASCII identifiers every backend accepts
+var identifier = regexp.MustCompile(`^[A-Za-z_][A-Za-z0-9_]*$`)
+
+func TestMangle(t *testing.T) {
+	tests := []struct {
+		label string
+		want  string
+	}{
+		// ASCII
+		{"helpers", "helpers"},
+		{"my-lib.v2", "my_lib_v2"},
+		{"my spell", "my_spell"},
+		{"snake_case", "snake_case"},
+		{"2fast", "_2fast"},
+		{"", "_"},
+		{"!", "_"},
+		{"class", "class_"},
+		{"None", "None_"},
+		{"print", "print"},
+
+		// Kana
+		{"あいさつ", "aisatsu"},
+		{"カタカナ", "katakana"},
+		{"きゃく", "kyaku"},
+		{"しゃしん", "shashin"},
+		{"チョコ", "choko"},
+		{"じゅう", "juu"},
+		{"きって", "kitte"},
+		{"マッチ", "matchi"},
+		{"コーヒー", "koohii"},
+		{"ファイル", "fairu"},
+		{"ティー", "tii"},
+		{"っ", "_"},
+		{"ー", "_"},
+
+		// Other scripts are escaped by code point
+		{"挨拶", "u6328_u62f6"},
+		{"魔法陣2", "u9b54_u6cd5_u9663_2"},
+		{"math計算", "math_u8a08_u7b97"},
+		{"計算_v2", "u8a08_u7b97_v2"},
+		{"呪文の書", "u546a_u6587_no_u66f8"},
+		{"café", "caf_u00e9"},
+		{"٣", "u0663"},
+
+		// Full-width ASCII and spaces
+		{"ＡＢＣ１２３", "ABC123"},
+		{"魔法　陣", "u9b54_u6cd5_u9663"},
+		{"１ばん", "_1ban"},
+		{"・", "_"},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.label, func(t *testing.T) {
+			got := Mangle(tt.label)
+			assert.Equal(t, tt.want, got)
+			assert.Regexp(t, identifier, got)
+			assert.Equal(t, got, Mangle(got), "mangling an identifier leaves it as it is")
+		})
+	}
+}
+
+func TestKanaRomajiCoversHiragana(t *testing.T) {
+	assert.Len(t, kanaRomaji, lastHiragana-firstHiragana+1)
+	assert.Equal(t, "n", kanaRomaji['ん'-firstHiragana])
+	assert.Equal(t, "ke", kanaRomaji[lastHiragana-firstHiragana])
+
+	for r := rune(firstKatakana); r <= lastKatakana; r++ {
+		assert.Equal(t, romaji([]rune{r - katakanaOffset}), romaji([]rune{r}), "katakana %c", r)
+	}
+}
+
+func TestNamer(t *testing.T) {
+	n := NewNamer("print")
+
+	assert.Equal(t, "u6328_u62f6", n.Name("挨拶"))
+	assert.Equal(t, "u6328_u62f6", n.Name("挨拶"), "the same label keeps its name")
+	assert.Equal(t, "u6328_u62f6_2", n.Name("挨 拶"), "a colliding label is suffixed")
+	assert.Equal(t, "u6328_u62f6_3", n.Name("挨_拶"))
+	assert.Equal(t, "u6328_u62f6_2", n.Name("挨 拶"))
+
+	assert.Equal(t, "print_2", n.Name("print"), "reserved names are never handed out")
+	assert.Equal(t, "a_b", n.Name("a-b"))
+	assert.Equal(t, "a_b_2", n.Name("a_b"))
+}
+
+func TestNamerIsDeterministic(t *testing.T) {
+	labels := []string{"a b", "a-b", "a_b", "挨拶", "挨 拶", "class", "class_"}
+	name := func() []string {
+		n := NewNamer()
+		names := make([]string, len(labels))
+		for i, label := range labels {
+			names[i] = n.Name(label)
+		}
+		return names
+	}
+
+	first := name()
+	assert.Equal(t, first, name())
+	assert.Equal(t, []string{"a_b", "a_b_2", "a_b_3", "u6328_u62f6", "u6328_u62f6_2", "class_", "class__2"}, first)
+}
+
+func FuzzMangle(f *testing.F) {
+	for _, seed := range []string{"", "helpers", "あいさつ", "挨拶", "ＡＢＣ", "っー", "ゃゅょ", "9\x00\xff"} {
+		f.Add(seed)
+	}
+	f.Fuzz(func(t *testing.T, label string) {
+		got := Mangle(label)
+		if !identifier.MatchString(got) || keywords[got] {
+			t.Fatalf("Mangle(%q) = %q is not an identifier", label, got)
+		}
+		if again := Mangle(got); again != got {
+			t.Fatalf("Mangle(%q) = %q, but Mangle(%q) = %q", label, got, got, again)
+		}
+	})
+}
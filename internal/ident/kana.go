@@ -0,0 +1,93 @@
+package ident
+
+import "strings"
+
+// Kana code points. Katakana are hiragana shifted by katakanaOffset.
+const (
+	firstHiragana  = 0x3041 // ぁ
+	lastHiragana   = 0x3096 // ゖ
+	firstKatakana  = 0x30A1 // ァ
+	lastKatakana   = 0x30F6 // ヶ
+	katakanaOffset = firstKatakana - firstHiragana
+	longVowelMark  = 0x30FC // ー
+)
+
+// kanaRomaji spells each hiragana from firstHiragana in Hepburn romaji.
+// Small kana are spelled as their full-size forms; romaji combines them
+// with the kana before.
+var kanaRomaji = [...]string{
+	"a", "a", "i", "i", "u", "u", "e", "e", "o", "o",
+	"ka", "ga", "ki", "gi", "ku", "gu", "ke", "ge", "ko", "go",
+	"sa", "za", "shi", "ji", "su", "zu", "se", "ze", "so", "zo",
+	"ta", "da", "chi", "ji", "tsu", "tsu", "zu", "te", "de", "to", "do",
+	"na", "ni", "nu", "ne", "no",
+	"ha", "ba", "pa", "hi", "bi", "pi", "fu", "bu", "pu", "he", "be", "pe", "ho", "bo", "po",
+	"ma", "mi", "mu", "me", "mo",
+	"ya", "ya", "yu", "yu", "yo", "yo",
+	"ra", "ri", "ru", "re", "ro",
+	"wa", "wa", "i", "e", "o", "n",
+	"vu", "ka", "ke",
+}
+
+// isKana reports whether r is a hiragana, katakana or the long vowel mark
+func isKana(r rune) bool {
+	return r >= firstHiragana && r <= lastHiragana ||
+		r >= firstKatakana && r <= lastKatakana ||
+		r == longVowelMark
+}
+
+// romaji spells a run of kana in romaji. Small ya, yu and yo after an
+// i-row kana form a contracted sound (きゃ kya, しゃ sha), small vowels
+// replace the vowel before (ファ fa), a small tsu doubles the consonant
+// after it (きって kitte), and the long vowel mark repeats the vowel before.
+func romaji(kana []rune) string {
+	var out []byte
+	double := false
+	for _, r := range kana {
+		if r >= firstKatakana && r <= lastKatakana {
+			r -= katakanaOffset
+		}
+
+		switch r {
+		case 'っ':
+			double = true
+			continue
+		case longVowelMark:
+			if n := len(out); n > 0 && isVowel(out[n-1]) {
+				out = append(out, out[n-1])
+			}
+			continue
+		case 'ゃ', 'ゅ', 'ょ':
+			if n := len(out); n >= 2 && out[n-1] == 'i' {
+				out = out[:n-1]
+				if s := string(out); !strings.HasSuffix(s, "sh") && !strings.HasSuffix(s, "ch") && !strings.HasSuffix(s, "j") {
+					out = append(out, 'y')
+				}
+				out = append(out, kanaRomaji[r-firstHiragana][1])
+				continue
+			}
+		case 'ぁ', 'ぃ', 'ぅ', 'ぇ', 'ぉ':
+			if n := len(out); n >= 2 && isVowel(out[n-1]) && !isVowel(out[n-2]) {
+				out[n-1] = kanaRomaji[r-firstHiragana][0]
+				continue
+			}
+		}
+
+		syllable := kanaRomaji[r-firstHiragana]
+		if double && !isVowel(syllable[0]) {
+			if strings.HasPrefix(syllable, "ch") {
+				out = append(out, 't')
+			} else {
+				out = append(out, syllable[0])
+			}
+		}
+		double = false
+		out = append(out, syllable...)
+	}
+	return string(out)
+}
+
+// isVowel reports whether c is a romaji vowel
+func isVowel(c byte) bool {
+	return strings.IndexByte("aeiou", c) >= 0
+}
@@ -0,0 +1,178 @@
+// Package imagediff compares the programs drawn in two magic circle images.
+//
+// Symbols are matched by type and position rather than by pixels, so a
+// redrawn circle that moved slightly is the same symbol, while a circle
+// replaced by a square is one removal and one addition.
+package imagediff
+
+import (
+	"math"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// ChangeKind describes how an element differs between the two images
+type ChangeKind string
+
+const (
+	Added   ChangeKind = "added"
+	Removed ChangeKind = "removed"
+	Changed ChangeKind = "changed"
+)
+
+// minMatchDistance is the smallest distance, in pixels, within which two
+// symbols of the same type are considered the same symbol
+const minMatchDistance = 8.0
+
+// SymbolChange is a symbol that differs between the two images.
+// Old is nil for additions and New is nil for removals.
+type SymbolChange struct {
+	Kind ChangeKind
+	Old  *detector.Symbol
+	New  *detector.Symbol
+}
+
+// ConnectionChange is a connection that differs between the two images.
+// Old is nil for additions and New is nil for removals.
+type ConnectionChange struct {
+	Kind ChangeKind
+	Old  *detector.Connection
+	New  *detector.Connection
+}
+
+// Result holds the differences between two images
+type Result struct {
+	Symbols     []SymbolChange
+	Connections []ConnectionChange
+}
+
+// Empty reports whether the two images describe the same program
+func (r *Result) Empty() bool {
+	return len(r.Symbols) == 0 && len(r.Connections) == 0
+}
+
+// Count returns the number of symbol changes of the given kind
+func (r *Result) Count(kind ChangeKind) int {
+	count := 0
+	for _, change := range r.Symbols {
+		if change.Kind == kind {
+			count++
+		}
+	}
+	return count
+}
+
+// Compare computes the semantic differences from the old detection results to the new ones
+func Compare(oldSymbols []*detector.Symbol, oldConnections []detector.Connection,
+	newSymbols []*detector.Symbol, newConnections []detector.Connection,
+) *Result {
+	result := &Result{}
+	matches := matchSymbols(oldSymbols, newSymbols)
+
+	matched := make(map[*detector.Symbol]bool)
+	for _, old := range oldSymbols {
+		match, ok := matches[old]
+		switch {
+		case !ok:
+			result.Symbols = append(result.Symbols, SymbolChange{Kind: Removed, Old: old})
+		case match.Pattern != old.Pattern:
+			result.Symbols = append(result.Symbols, SymbolChange{Kind: Changed, Old: old, New: match})
+		}
+		if ok {
+			matched[match] = true
+		}
+	}
+	for _, s := range newSymbols {
+		if !matched[s] {
+			result.Symbols = append(result.Symbols, SymbolChange{Kind: Added, New: s})
+		}
+	}
+
+	result.Connections = compareConnections(matches, oldConnections, newConnections)
+	return result
+}
+
+// matchSymbols pairs each old symbol with the nearest unclaimed new symbol
+// of the same type, closest pairs first
+func matchSymbols(oldSymbols, newSymbols []*detector.Symbol) map[*detector.Symbol]*detector.Symbol {
+	type candidate struct {
+		oldSymbol *detector.Symbol
+		newSymbol *detector.Symbol
+		distance  float64
+	}
+
+	var candidates []candidate
+	for _, o := range oldSymbols {
+		for _, n := range newSymbols {
+			if o.Type != n.Type {
+				continue
+			}
+			distance := math.Hypot(o.Position.X-n.Position.X, o.Position.Y-n.Position.Y)
+			if distance <= matchDistance(o, n) {
+				candidates = append(candidates, candidate{oldSymbol: o, newSymbol: n, distance: distance})
+			}
+		}
+	}
+
+	// Insertion sort keeps equal distances in input order so results are stable
+	for i := 1; i < len(candidates); i++ {
+		for j := i; j > 0 && candidates[j].distance < candidates[j-1].distance; j-- {
+			candidates[j], candidates[j-1] = candidates[j-1], candidates[j]
+		}
+	}
+
+	matches := make(map[*detector.Symbol]*detector.Symbol)
+	claimed := make(map[*detector.Symbol]bool)
+	for _, c := range candidates {
+		if _, done := matches[c.oldSymbol]; done || claimed[c.newSymbol] {
+			continue
+		}
+		matches[c.oldSymbol] = c.newSymbol
+		claimed[c.newSymbol] = true
+	}
+	return matches
+}
+
+// matchDistance is how far apart two symbols may be and still match: half
+// the larger symbol, so any overlap counts
+func matchDistance(a, b *detector.Symbol) float64 {
+	return math.Max(minMatchDistance, math.Max(a.Size, b.Size)/2)
+}
+
+// compareConnections compares connections through the symbol matching
+func compareConnections(matches map[*detector.Symbol]*detector.Symbol,
+	oldConnections, newConnections []detector.Connection,
+) []ConnectionChange {
+	type endpoints struct{ from, to *detector.Symbol }
+
+	byEndpoints := make(map[endpoints]int)
+	for i, conn := range newConnections {
+		byEndpoints[endpoints{conn.From, conn.To}] = i
+	}
+
+	var changes []ConnectionChange
+	seen := make(map[int]bool)
+	for i := range oldConnections {
+		old := &oldConnections[i]
+		from, fromOK := matches[old.From]
+		to, toOK := matches[old.To]
+
+		j, found := byEndpoints[endpoints{from, to}]
+		if !fromOK || !toOK || !found || seen[j] {
+			changes = append(changes, ConnectionChange{Kind: Removed, Old: old})
+			continue
+		}
+
+		seen[j] = true
+		if newConnections[j].ConnectionType != old.ConnectionType {
+			changes = append(changes, ConnectionChange{Kind: Changed, Old: old, New: &newConnections[j]})
+		}
+	}
+
+	for i := range newConnections {
+		if !seen[i] {
+			changes = append(changes, ConnectionChange{Kind: Added, New: &newConnections[i]})
+		}
+	}
+	return changes
+}
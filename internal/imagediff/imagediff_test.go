@@ -0,0 +1,130 @@
+package imagediff
+
+import (
+	"image"
+	"image/color"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func symbol(t detector.SymbolType, x, y float64, pattern string) *detector.Symbol {
+	return &detector.Symbol{Type: t, Position: detector.Position{X: x, Y: y}, Size: 20, Pattern: pattern}
+}
+
+func TestCompare_Identical(t *testing.T) {
+	outer := symbol(detector.OuterCircle, 100, 100, detector.PatternEmpty)
+	star := symbol(detector.Star, 100, 120, detector.PatternEmpty)
+	conns := []detector.Connection{{From: outer, To: star, ConnectionType: detector.ConnectionTypeSolid}}
+
+	// Redrawn slightly off position is still the same program
+	outer2 := symbol(detector.OuterCircle, 101, 99, detector.PatternEmpty)
+	star2 := symbol(detector.Star, 103, 121, detector.PatternEmpty)
+	conns2 := []detector.Connection{{From: outer2, To: star2, ConnectionType: detector.ConnectionTypeSolid}}
+
+	result := Compare([]*detector.Symbol{outer, star}, conns, []*detector.Symbol{outer2, star2}, conns2)
+	assert.True(t, result.Empty())
+}
+
+func TestCompare_Changes(t *testing.T) {
+	entry := symbol(detector.DoubleCircle, 100, 50, detector.PatternEmpty)
+	square := symbol(detector.Square, 100, 100, detector.PatternDot)
+	star := symbol(detector.Star, 100, 150, detector.PatternEmpty)
+	oldSymbols := []*detector.Symbol{entry, square, star}
+	oldConns := []detector.Connection{
+		{From: entry, To: square, ConnectionType: detector.ConnectionTypeSolid},
+		{From: square, To: star, ConnectionType: detector.ConnectionTypeSolid},
+	}
+
+	entry2 := symbol(detector.DoubleCircle, 100, 50, detector.PatternEmpty)
+	square2 := symbol(detector.Square, 100, 100, detector.PatternLines)
+	circle := symbol(detector.Circle, 200, 100, detector.PatternDot)
+	newSymbols := []*detector.Symbol{entry2, square2, circle}
+	newConns := []detector.Connection{
+		{From: entry2, To: square2, ConnectionType: detector.ConnectionTypeDashed},
+		{From: square2, To: circle, ConnectionType: detector.ConnectionTypeSolid},
+	}
+
+	result := Compare(oldSymbols, oldConns, newSymbols, newConns)
+	require.False(t, result.Empty())
+
+	require.Len(t, result.Symbols, 3)
+	assert.Equal(t, SymbolChange{Kind: Changed, Old: square, New: square2}, result.Symbols[0])
+	assert.Equal(t, SymbolChange{Kind: Removed, Old: star}, result.Symbols[1])
+	assert.Equal(t, SymbolChange{Kind: Added, New: circle}, result.Symbols[2])
+	assert.Equal(t, 1, result.Count(Added))
+	assert.Equal(t, 1, result.Count(Removed))
+	assert.Equal(t, 1, result.Count(Changed))
+
+	require.Len(t, result.Connections, 3)
+	assert.Equal(t, Changed, result.Connections[0].Kind)
+	assert.Equal(t, detector.ConnectionTypeDashed, result.Connections[0].New.ConnectionType)
+	assert.Equal(t, Removed, result.Connections[1].Kind)
+	assert.Equal(t, star, result.Connections[1].Old.To)
+	assert.Equal(t, Added, result.Connections[2].Kind)
+	assert.Equal(t, circle, result.Connections[2].New.To)
+}
+
+func TestCompare_NearestMatchWins(t *testing.T) {
+	a := symbol(detector.Star, 100, 100, detector.PatternEmpty)
+	b := symbol(detector.Star, 110, 100, detector.PatternEmpty)
+	near := symbol(detector.Star, 111, 100, detector.PatternEmpty)
+
+	// b is the nearest old symbol to the only new one, so a is the removal
+	result := Compare([]*detector.Symbol{a, b}, nil, []*detector.Symbol{near}, nil)
+	require.Len(t, result.Symbols, 1)
+	assert.Equal(t, SymbolChange{Kind: Removed, Old: a}, result.Symbols[0])
+}
+
+func TestOverlay(t *testing.T) {
+	oldImg := image.NewGray(image.Rect(0, 0, 100, 100))
+	newImg := image.NewGray(image.Rect(0, 0, 120, 80))
+	for y := 0; y < 100; y++ {
+		for x := 0; x < 120; x++ {
+			oldImg.SetGray(x, y, color.Gray{Y: 255})
+			newImg.SetGray(x, y, color.Gray{Y: 255})
+		}
+	}
+	oldImg.SetGray(5, 5, color.Gray{Y: 0})
+
+	removed := symbol(detector.Square, 30, 30, detector.PatternEmpty)
+	added := symbol(detector.Circle, 80, 30, detector.PatternEmpty)
+	from := symbol(detector.Star, 10, 70, detector.PatternEmpty)
+	to := symbol(detector.Star, 90, 70, detector.PatternEmpty)
+	result := &Result{
+		Symbols: []SymbolChange{{Kind: Removed, Old: removed}, {Kind: Added, New: added}},
+		Connections: []ConnectionChange{
+			{Kind: Added, New: &detector.Connection{From: from, To: to}},
+		},
+	}
+
+	canvas := Overlay(oldImg, newImg, result)
+	assert.Equal(t, image.Rect(0, 0, 120, 100), canvas.Bounds())
+
+	// Drawings are faded rather than hidden
+	assert.Equal(t, color.RGBA{R: 170, G: 170, B: 170, A: 255}, canvas.RGBAAt(5, 5))
+	assert.Equal(t, color.RGBA{R: 255, G: 255, B: 255, A: 255}, canvas.RGBAAt(60, 90))
+
+	// Box edges are half the symbol size plus the margin from the center
+	assert.Equal(t, RemovedColor, canvas.RGBAAt(16, 30))
+	assert.Equal(t, AddedColor, canvas.RGBAAt(80, 16))
+	assert.Equal(t, ChangedColor, canvas.RGBAAt(50, 70))
+
+	// Each box is labeled with the type of its symbol below it
+	assert.True(t, tinted(canvas, image.Rect(5, 46, 55, 66)), "label of the removed square")
+	assert.False(t, tinted(canvas, image.Rect(5, 20, 15, 40)), "left of the box")
+}
+
+// tinted reports whether any pixel of area is colored rather than gray
+func tinted(canvas *image.RGBA, area image.Rectangle) bool {
+	for y := area.Min.Y; y < area.Max.Y; y++ {
+		for x := area.Min.X; x < area.Max.X; x++ {
+			if c := canvas.RGBAAt(x, y); c.R != c.G || c.G != c.B {
+				return true
+			}
+		}
+	}
+	return false
+}
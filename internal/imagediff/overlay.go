@@ -0,0 +1,127 @@
+package imagediff
+
+import (
+	"image"
+	"image/color"
+	"math"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/render"
+)
+
+// Annotation colors
+var (
+	AddedColor   = color.RGBA{R: 0, G: 170, B: 0, A: 255}
+	RemovedColor = color.RGBA{R: 220, G: 0, B: 0, A: 255}
+	ChangedColor = color.RGBA{R: 230, G: 170, B: 0, A: 255}
+)
+
+const (
+	// fadeFactor controls how much the drawings are lightened so annotations stand out
+	fadeFactor = 3
+	// boxMargin is the space, in pixels, between a symbol and its box
+	boxMargin = 4
+	// strokeWidth is the width, in pixels, of boxes and connection lines
+	strokeWidth = 3
+)
+
+// Overlay draws both images on top of each other, faded, and annotates the
+// differences: added symbols are boxed in green, removed symbols in red,
+// symbols whose pattern changed in yellow, and every changed connection is
+// traced in yellow. Each boxed symbol is labeled with its type below the box.
+func Overlay(oldImg, newImg image.Image, result *Result) *image.RGBA {
+	bounds := image.Rect(0, 0,
+		max(oldImg.Bounds().Dx(), newImg.Bounds().Dx()),
+		max(oldImg.Bounds().Dy(), newImg.Bounds().Dy()))
+	canvas := image.NewRGBA(bounds)
+
+	for y := 0; y < bounds.Dy(); y++ {
+		for x := 0; x < bounds.Dx(); x++ {
+			v := min(grayAt(oldImg, x, y), grayAt(newImg, x, y))
+			v = 255 - (255-v)/fadeFactor
+			canvas.Set(x, y, color.RGBA{R: v, G: v, B: v, A: 255})
+		}
+	}
+
+	for _, change := range result.Connections {
+		for _, conn := range []*detector.Connection{change.Old, change.New} {
+			if conn != nil && conn.From != nil && conn.To != nil {
+				drawLine(canvas, conn.From.Position, conn.To.Position, ChangedColor)
+			}
+		}
+	}
+
+	text := render.NewText(render.DefaultTextSize)
+	for _, change := range result.Symbols {
+		switch change.Kind {
+		case Added:
+			drawBox(canvas, text, change.New, AddedColor)
+		case Removed:
+			drawBox(canvas, text, change.Old, RemovedColor)
+		case Changed:
+			drawBox(canvas, text, change.New, ChangedColor)
+		}
+	}
+
+	return canvas
+}
+
+// grayAt returns the luminance of img at x, y relative to its origin, or
+// white outside the image
+func grayAt(img image.Image, x, y int) uint8 {
+	b := img.Bounds()
+	p := image.Pt(b.Min.X+x, b.Min.Y+y)
+	if !p.In(b) {
+		return 255
+	}
+	return color.GrayModel.Convert(img.At(p.X, p.Y)).(color.Gray).Y
+}
+
+// drawBox outlines the area covered by a symbol and writes its type below
+func drawBox(canvas *image.RGBA, text *render.Text, symbol *detector.Symbol, c color.RGBA) {
+	half := symbol.Size/2 + boxMargin
+	x0 := int(math.Round(symbol.Position.X - half))
+	y0 := int(math.Round(symbol.Position.Y - half))
+	x1 := int(math.Round(symbol.Position.X + half))
+	y1 := int(math.Round(symbol.Position.Y + half))
+
+	for w := 0; w < strokeWidth; w++ {
+		for x := x0; x <= x1; x++ {
+			setPixel(canvas, x, y0+w, c)
+			setPixel(canvas, x, y1-w, c)
+		}
+		for y := y0; y <= y1; y++ {
+			setPixel(canvas, x0+w, y, c)
+			setPixel(canvas, x1-w, y, c)
+		}
+	}
+	text.Label(canvas, string(symbol.Type), image.Pt((x0+x1)/2, y1+2), c)
+}
+
+// drawLine draws a thick straight line between two positions
+func drawLine(canvas *image.RGBA, from, to detector.Position, c color.RGBA) {
+	dx := to.X - from.X
+	dy := to.Y - from.Y
+	steps := int(math.Ceil(math.Max(math.Abs(dx), math.Abs(dy))))
+	if steps == 0 {
+		steps = 1
+	}
+
+	for i := 0; i <= steps; i++ {
+		t := float64(i) / float64(steps)
+		cx := int(math.Round(from.X + dx*t))
+		cy := int(math.Round(from.Y + dy*t))
+		for oy := -strokeWidth / 2; oy <= strokeWidth/2; oy++ {
+			for ox := -strokeWidth / 2; ox <= strokeWidth/2; ox++ {
+				setPixel(canvas, cx+ox, cy+oy, c)
+			}
+		}
+	}
+}
+
+// setPixel sets a pixel, ignoring coordinates outside the canvas
+func setPixel(canvas *image.RGBA, x, y int, c color.RGBA) {
+	if image.Pt(x, y).In(canvas.Bounds()) {
+		canvas.SetRGBA(x, y, c)
+	}
+}
@@ -0,0 +1,543 @@
+// Package inspector implements the interactive `grimoire inspect` view.
+//
+// The inspector shows four panes (symbols, AST, diagnostics and generated
+// code) and cross-highlights the entries related to the current selection,
+// so moving through one pane shows where the same construct lives in the
+// others. In a terminal on Unix it switches the terminal to raw mode, acts
+// on each key as it is pressed, arrow keys included, and redraws only the
+// lines that changed. When the input is not a terminal, it reads
+// single-key commands line by line instead, so it still works in pipes.
+package inspector
+
+import (
+	"bufio"
+	"bytes"
+	"fmt"
+	"io"
+	"os"
+	"strings"
+
+	"github.com/ayutaz/grimoire/internal/compiler"
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/parser"
+)
+
+// Pane identifies one of the inspector panes
+type Pane int
+
+// Inspector panes, in display order
+const (
+	PaneSymbols Pane = iota
+	PaneAST
+	PaneDiagnostics
+	PaneCode
+	paneCount
+)
+
+// Diagnostic is a problem found while inspecting an image
+type Diagnostic struct {
+	Message string
+	// Symbol is the index of the related symbol, or -1 if there is none
+	Symbol int
+}
+
+// astRow is one line of the rendered AST tree
+type astRow struct {
+	depth int
+	label string
+	node  parser.ASTNode
+}
+
+// Inspector holds the state of an inspection session
+type Inspector struct {
+	name        string
+	roles       []parser.SymbolRole
+	astRows     []astRow
+	diagnostics []Diagnostic
+	codeLines   []string
+	sourceMap   map[parser.ASTNode]compiler.LineRange
+	pane        Pane
+	cursor      [paneCount]int
+}
+
+// New parses and compiles the detected symbols and prepares the panes.
+// Parse and compile failures are shown as diagnostics instead of being returned.
+func New(name string, symbols []*detector.Symbol, connections []detector.Connection) *Inspector {
+	in := &Inspector{name: name}
+
+	p := parser.NewParser()
+	program, err := p.Parse(symbols, connections)
+	in.roles = p.Roles()
+	if err != nil {
+		in.diagnostics = append(in.diagnostics, Diagnostic{Message: err.Error(), Symbol: -1})
+	}
+
+	for _, role := range in.roles {
+		if role.Kind == parser.RoleUnused {
+			in.diagnostics = append(in.diagnostics, Diagnostic{
+				Message: i18n.Tf("inspect.unused_symbol", role.Symbol.Type,
+					role.Symbol.Position.X, role.Symbol.Position.Y),
+				Symbol: role.Index,
+			})
+		}
+	}
+
+	if program != nil {
+		in.addNode(program, 0)
+
+		c := compiler.NewCompiler()
+		code, err := c.Compile(program)
+		if err != nil {
+			in.diagnostics = append(in.diagnostics, Diagnostic{Message: err.Error(), Symbol: -1})
+		} else {
+			in.codeLines = strings.Split(strings.TrimRight(code, "\n"), "\n")
+			in.sourceMap = c.SourceMap()
+		}
+	}
+
+	return in
+}
+
+// Run renders the inspector to out and processes commands read from in until
+// the user quits or the input ends. A terminal is read key by key in raw
+// mode, and anything else line by line.
+func (in *Inspector) Run(input io.Reader, out io.Writer) error {
+	if file, ok := input.(*os.File); ok && isTerminal(int(file.Fd())) {
+		if restore, err := makeRaw(int(file.Fd())); err == nil {
+			err := in.runKeys(input, out)
+			if restoreErr := restore(); err == nil {
+				err = restoreErr
+			}
+			return err
+		}
+	}
+	return in.runLines(input, out)
+}
+
+// runKeys processes keys read from input as they are pressed, redrawing
+// only the lines of the panes that changed
+func (in *Inspector) runKeys(input io.Reader, out io.Writer) error {
+	reader := bufio.NewReader(input)
+	s := &screen{out: out}
+	help := false
+	for {
+		if err := s.draw(in.frame(help)); err != nil {
+			return err
+		}
+		key, err := readKey(reader)
+		if err != nil {
+			s.end()
+			if err == io.EOF {
+				return nil
+			}
+			return err
+		}
+		help = key == '?'
+		if !in.HandleKey(key) {
+			s.end()
+			return nil
+		}
+	}
+}
+
+// frame returns everything shown while reading keys in raw mode: the panes,
+// the keys and, after '?', the help
+func (in *Inspector) frame(help bool) string {
+	var buf bytes.Buffer
+	in.Render(&buf)
+	fmt.Fprint(&buf, i18n.T("inspect.keys"))
+	if help {
+		fmt.Fprint(&buf, "\n"+i18n.T("inspect.help"))
+	}
+	return buf.String()
+}
+
+// readKey reads one key pressed in raw mode. Arrow keys read as the
+// commands they stand for: up and down as k and j, right as Tab and left as
+// p, as does Shift+Tab. Ctrl+C and Ctrl+D read as q.
+func readKey(reader *bufio.Reader) (rune, error) {
+	key, _, err := reader.ReadRune()
+	if err != nil {
+		return 0, err
+	}
+	switch key {
+	case 0x03, 0x04:
+		return 'q', nil
+	case 0x1b:
+		return readEscape(reader)
+	}
+	return key, nil
+}
+
+// readEscape reads the rest of an escape sequence, which is ESC [ or ESC O
+// followed by the final byte naming the key
+func readEscape(reader *bufio.Reader) (rune, error) {
+	prefix, _, err := reader.ReadRune()
+	if err != nil {
+		return 0, err
+	}
+	if prefix != '[' && prefix != 'O' {
+		return prefix, nil
+	}
+	final, _, err := reader.ReadRune()
+	if err != nil {
+		return 0, err
+	}
+	switch final {
+	case 'A':
+		return 'k', nil
+	case 'B':
+		return 'j', nil
+	case 'C':
+		return '\t', nil
+	case 'D', 'Z':
+		return 'p', nil
+	}
+	return 0, nil
+}
+
+// runLines processes commands read from input line by line
+func (in *Inspector) runLines(input io.Reader, out io.Writer) error {
+	scanner := bufio.NewScanner(input)
+	for {
+		in.Render(out)
+		fmt.Fprint(out, i18n.T("inspect.prompt"))
+
+		if !scanner.Scan() {
+			fmt.Fprintln(out)
+			return scanner.Err()
+		}
+		for _, key := range scanner.Text() {
+			if !in.HandleKey(key) {
+				return nil
+			}
+			if key == '?' {
+				fmt.Fprintln(out, i18n.T("inspect.help"))
+			}
+		}
+	}
+}
+
+// HandleKey applies a single-key command and reports whether to continue
+func (in *Inspector) HandleKey(key rune) bool {
+	switch key {
+	case 'q':
+		return false
+	case 's':
+		in.pane = PaneSymbols
+	case 'a':
+		in.pane = PaneAST
+	case 'd':
+		in.pane = PaneDiagnostics
+	case 'c':
+		in.pane = PaneCode
+	case '\t', 'n':
+		in.pane = (in.pane + 1) % paneCount
+	case 'p':
+		in.pane = (in.pane + paneCount - 1) % paneCount
+	case 'j':
+		in.move(1)
+	case 'k':
+		in.move(-1)
+	case 'g':
+		in.cursor[in.pane] = 0
+	case 'G':
+		in.move(in.rowCount(in.pane))
+	}
+	return true
+}
+
+// Select moves the cursor of a pane to row, clamped to the pane's rows
+func (in *Inspector) Select(pane Pane, row int) {
+	in.pane = pane
+	in.cursor[pane] = 0
+	in.move(row)
+}
+
+// move moves the cursor of the active pane by delta rows
+func (in *Inspector) move(delta int) {
+	rows := in.rowCount(in.pane)
+	cur := in.cursor[in.pane] + delta
+	if cur >= rows {
+		cur = rows - 1
+	}
+	if cur < 0 {
+		cur = 0
+	}
+	in.cursor[in.pane] = cur
+}
+
+// rowCount returns the number of selectable rows in a pane
+func (in *Inspector) rowCount(pane Pane) int {
+	switch pane {
+	case PaneSymbols:
+		return len(in.roles)
+	case PaneAST:
+		return len(in.astRows)
+	case PaneDiagnostics:
+		return len(in.diagnostics)
+	default:
+		return len(in.codeLines)
+	}
+}
+
+// focus returns the symbol index (or -1) and AST node selected in the active pane
+func (in *Inspector) focus() (int, parser.ASTNode) {
+	cur := in.cursor[in.pane]
+	if cur >= in.rowCount(in.pane) {
+		return -1, nil
+	}
+
+	switch in.pane {
+	case PaneSymbols:
+		return cur, in.roles[cur].Node
+	case PaneAST:
+		node := in.astRows[cur].node
+		return in.symbolFor(node), node
+	case PaneDiagnostics:
+		symbol := in.diagnostics[cur].Symbol
+		if symbol < 0 || symbol >= len(in.roles) {
+			return -1, nil
+		}
+		return symbol, in.roles[symbol].Node
+	default:
+		node := in.statementAtLine(cur + 1)
+		return in.symbolFor(node), node
+	}
+}
+
+// symbolFor returns the index of the symbol an AST node was built from, or -1
+func (in *Inspector) symbolFor(node parser.ASTNode) int {
+	if node == nil {
+		return -1
+	}
+	for _, role := range in.roles {
+		if role.Node == node {
+			return role.Index
+		}
+	}
+	return -1
+}
+
+// statementAtLine returns the innermost node whose generated code covers line
+func (in *Inspector) statementAtLine(line int) parser.ASTNode {
+	var best parser.ASTNode
+	bestSize := -1
+	for node, r := range in.sourceMap {
+		if line < r.Start || line > r.End {
+			continue
+		}
+		if size := r.End - r.Start; bestSize < 0 || size < bestSize {
+			best, bestSize = node, size
+		}
+	}
+	return best
+}
+
+// codeRange returns the generated lines for the focused node. Expressions
+// have no lines of their own, so the enclosing statement is used instead.
+func (in *Inspector) codeRange(symbol int, node parser.ASTNode) (compiler.LineRange, bool) {
+	if r, ok := in.sourceMap[node]; ok && node != nil {
+		return r, true
+	}
+	if symbol < 0 {
+		return compiler.LineRange{}, false
+	}
+	for ctx := in.roles[symbol].Context; ctx != nil; ctx = ctx.Context {
+		if r, ok := in.sourceMap[ctx.Node]; ok && ctx.Node != nil {
+			return r, true
+		}
+	}
+	return compiler.LineRange{}, false
+}
+
+// Render writes all panes to out, marking the cursor with '>' and entries
+// related to the selection with '*'
+func (in *Inspector) Render(out io.Writer) {
+	symbol, node := in.focus()
+	lines, hasLines := in.codeRange(symbol, node)
+
+	fmt.Fprintf(out, i18n.T("inspect.title"), in.name)
+
+	in.renderHeader(out, PaneSymbols, "inspect.symbols_header")
+	for i, role := range in.roles {
+		in.renderRow(out, PaneSymbols, i, i == symbol, fmt.Sprintf("[%d] %-16s (%.0f, %.0f) %s",
+			role.Index, role.Symbol.Type, role.Symbol.Position.X, role.Symbol.Position.Y, role.Kind))
+	}
+
+	in.renderHeader(out, PaneAST, "inspect.ast_header")
+	for i, row := range in.astRows {
+		highlighted := node != nil && row.node == node
+		in.renderRow(out, PaneAST, i, highlighted, strings.Repeat("  ", row.depth)+row.label)
+	}
+
+	in.renderHeader(out, PaneDiagnostics, "inspect.diagnostics_header")
+	if len(in.diagnostics) == 0 {
+		fmt.Fprintln(out, "    "+i18n.T("inspect.no_diagnostics"))
+	}
+	for i, diag := range in.diagnostics {
+		highlighted := symbol >= 0 && diag.Symbol == symbol
+		in.renderRow(out, PaneDiagnostics, i, highlighted, diag.Message)
+	}
+
+	in.renderHeader(out, PaneCode, "inspect.code_header")
+	for i, line := range in.codeLines {
+		highlighted := hasLines && i+1 >= lines.Start && i+1 <= lines.End
+		in.renderRow(out, PaneCode, i, highlighted, fmt.Sprintf("%3d | %s", i+1, line))
+	}
+}
+
+// renderHeader writes a pane title, marking the active pane
+func (in *Inspector) renderHeader(out io.Writer, pane Pane, titleID string) {
+	marker := " "
+	if pane == in.pane {
+		marker = "▶"
+	}
+	fmt.Fprintf(out, "\n%s %s\n", marker, i18n.T(titleID))
+}
+
+// renderRow writes one pane row with its cursor and highlight markers
+func (in *Inspector) renderRow(out io.Writer, pane Pane, row int, highlighted bool, text string) {
+	cursor := " "
+	if pane == in.pane && row == in.cursor[pane] {
+		cursor = ">"
+	}
+	mark := " "
+	if highlighted {
+		mark = "*"
+	}
+	fmt.Fprintf(out, "  %s%s %s\n", cursor, mark, text)
+}
+
+// addNode appends an AST node and its children to the tree rows
+func (in *Inspector) addNode(node parser.ASTNode, depth int) {
+	switch n := node.(type) {
+	case *parser.Program:
+		in.astRows = append(in.astRows, astRow{depth, "Program", n})
+		if n.Header != nil {
+			in.addNode(n.Header, depth+1)
+		}
+		if n.MainEntry != nil {
+			in.addNode(n.MainEntry, depth+1)
+		}
+		for _, fn := range n.Functions {
+			in.addNode(fn, depth+1)
+		}
+		in.addStatements(n.Globals, depth+1)
+	case *parser.ProgramHeader:
+		label := fmt.Sprintf("Header v%d", n.Version)
+		if len(n.Capabilities) > 0 {
+			label += " requires " + strings.Join(n.Capabilities, ", ")
+		}
+		in.astRows = append(in.astRows, astRow{depth, label, n})
+	case *parser.FunctionDef:
+		label := "Function " + n.Name
+		if n.IsMain {
+			label = "Main"
+		} else if n.Name == "" {
+			label = "Function (anonymous)"
+		}
+		in.astRows = append(in.astRows, astRow{depth, label, n})
+		in.addStatements(n.Body, depth+1)
+	case *parser.OutputStatement:
+		in.astRows = append(in.astRows, astRow{depth, "Output", n})
+		in.addExpression(n.Value, depth+1)
+	case *parser.Assignment:
+		in.astRows = append(in.astRows, astRow{depth, "Assign " + n.Target.Name, n})
+		in.addExpression(n.Value, depth+1)
+	case *parser.IfStatement:
+		in.astRows = append(in.astRows, astRow{depth, "If", n})
+		in.addExpression(n.Condition, depth+1)
+		in.astRows = append(in.astRows, astRow{depth + 1, "Then", nil})
+		in.addStatements(n.ThenBranch, depth+2)
+		if len(n.ElseBranch) > 0 {
+			in.astRows = append(in.astRows, astRow{depth + 1, "Else", nil})
+			in.addStatements(n.ElseBranch, depth+2)
+		}
+	case *parser.ForLoop:
+		in.astRows = append(in.astRows, astRow{depth, "For " + n.Counter.Name, n})
+		in.addExpression(n.End, depth+1)
+		in.addStatements(n.Body, depth+1)
+	case *parser.WhileLoop:
+		in.astRows = append(in.astRows, astRow{depth, "While", n})
+		in.addExpression(n.Condition, depth+1)
+		in.addStatements(n.Body, depth+1)
+	case *parser.ParallelBlock:
+		in.astRows = append(in.astRows, astRow{depth, "Parallel", n})
+		for i, branch := range n.Branches {
+			in.astRows = append(in.astRows, astRow{depth + 1, fmt.Sprintf("Branch %d", i), nil})
+			in.addStatements(branch, depth+2)
+		}
+	case *parser.Block:
+		in.astRows = append(in.astRows, astRow{depth, "Block", n})
+		in.addStatements(n.Body, depth+1)
+	case *parser.Stage:
+		in.astRows = append(in.astRows, astRow{depth, fmt.Sprintf("Stage %d", n.Number), n})
+		in.addStatements(n.Body, depth+1)
+	case *parser.Mirror:
+		in.astRows = append(in.astRows, astRow{depth, "Mirror " + n.Item.Name, n})
+		for _, operand := range n.Operands {
+			in.addExpression(operand, depth+1)
+		}
+		in.addStatements(n.Body, depth+1)
+	case *parser.Assertion:
+		in.astRows = append(in.astRows, astRow{depth, "Assert", n})
+		in.addExpression(n.Left, depth+1)
+		in.addExpression(n.Right, depth+1)
+	case *parser.ReturnStatement:
+		in.astRows = append(in.astRows, astRow{depth, "Return", n})
+		if n.Value != nil {
+			in.addExpression(n.Value, depth+1)
+		}
+	case *parser.ExpressionStatement:
+		in.astRows = append(in.astRows, astRow{depth, "Expression", n})
+		in.addExpression(n.Expression, depth+1)
+	case parser.Expression:
+		in.addExpression(n, depth)
+	}
+}
+
+// addStatements appends a statement list to the tree rows
+func (in *Inspector) addStatements(stmts []parser.Statement, depth int) {
+	for _, stmt := range stmts {
+		if stmt != nil {
+			in.addNode(stmt, depth)
+		}
+	}
+}
+
+// addExpression appends an expression and its operands to the tree rows
+func (in *Inspector) addExpression(expr parser.Expression, depth int) {
+	switch e := expr.(type) {
+	case *parser.BinaryOp:
+		in.astRows = append(in.astRows, astRow{depth, "BinaryOp " + string(e.Operator), e})
+		in.addExpression(e.Left, depth+1)
+		in.addExpression(e.Right, depth+1)
+	case *parser.UnaryOp:
+		in.astRows = append(in.astRows, astRow{depth, "UnaryOp " + string(e.Operator), e})
+		in.addExpression(e.Operand, depth+1)
+	case *parser.Literal:
+		in.astRows = append(in.astRows, astRow{depth, fmt.Sprintf("Literal %#v", e.Value), e})
+	case *parser.Identifier:
+		in.astRows = append(in.astRows, astRow{depth, "Identifier " + e.Name, e})
+	case *parser.FunctionCall:
+		in.astRows = append(in.astRows, astRow{depth, "Call " + e.Function.Name, e})
+		for _, arg := range e.Arguments {
+			in.addExpression(arg, depth+1)
+		}
+	case *parser.ArrayLiteral:
+		in.astRows = append(in.astRows, astRow{depth, "Array", e})
+		for _, elem := range e.Elements {
+			in.addExpression(elem, depth+1)
+		}
+	case *parser.MapLiteral:
+		in.astRows = append(in.astRows, astRow{depth, "Map", e})
+		for _, pair := range e.Pairs {
+			in.addExpression(pair[0], depth+1)
+			in.addExpression(pair[1], depth+1)
+		}
+	case *parser.Quoted:
+		in.astRows = append(in.astRows, astRow{depth, "Quoted", e})
+	}
+}
@@ -0,0 +1,154 @@
+package inspector
+
+import (
+	"bytes"
+	"strings"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func testProgram() ([]*detector.Symbol, []detector.Connection) {
+	outer := &detector.Symbol{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180}
+	entry := &detector.Symbol{Type: detector.DoubleCircle, Position: detector.Position{X: 200, Y: 60}}
+	counter := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 200, Y: 100}, Pattern: detector.PatternTripleDot}
+	loop := &detector.Symbol{Type: detector.Pentagon, Position: detector.Position{X: 200, Y: 150}}
+	output := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 200, Y: 220}}
+	stray := &detector.Symbol{Type: detector.Hexagon, Position: detector.Position{X: 320, Y: 320}}
+
+	symbols := []*detector.Symbol{outer, entry, counter, loop, output, stray}
+	connections := []detector.Connection{
+		{From: entry, To: loop},
+		{From: counter, To: loop},
+		{From: loop, To: output},
+	}
+	return symbols, connections
+}
+
+// markedLines returns the rendered lines that carry the '*' highlight marker
+func markedLines(in *Inspector) []string {
+	var buf bytes.Buffer
+	in.Render(&buf)
+
+	var marked []string
+	for _, line := range strings.Split(buf.String(), "\n") {
+		if len(line) > 3 && strings.HasPrefix(line, "  ") && line[3] == '*' {
+			marked = append(marked, line)
+		}
+	}
+	return marked
+}
+
+func TestInspector_SymbolSelectionHighlightsCode(t *testing.T) {
+	symbols, connections := testProgram()
+	in := New("test.png", symbols, connections)
+	require.NotEmpty(t, in.codeLines)
+
+	// Select the star
+	in.Select(PaneSymbols, 4)
+
+	marked := strings.Join(markedLines(in), "\n")
+	assert.Contains(t, marked, "[4] star")
+	assert.Contains(t, marked, "Output")
+	assert.Contains(t, marked, "print(")
+	assert.NotContains(t, marked, "for i in range")
+}
+
+func TestInspector_CodeSelectionHighlightsSymbol(t *testing.T) {
+	symbols, connections := testProgram()
+	in := New("test.png", symbols, connections)
+
+	loopLine := -1
+	for i, line := range in.codeLines {
+		if strings.Contains(line, "for i in range") {
+			loopLine = i
+		}
+	}
+	require.GreaterOrEqual(t, loopLine, 0)
+
+	in.Select(PaneCode, loopLine)
+
+	marked := strings.Join(markedLines(in), "\n")
+	assert.Contains(t, marked, "[3] pentagon")
+	assert.Contains(t, marked, "For i")
+}
+
+func TestInspector_UnusedSymbolDiagnostic(t *testing.T) {
+	symbols, connections := testProgram()
+	in := New("test.png", symbols, connections)
+
+	require.Len(t, in.diagnostics, 1)
+	assert.Equal(t, 5, in.diagnostics[0].Symbol)
+
+	in.Select(PaneDiagnostics, 0)
+	marked := strings.Join(markedLines(in), "\n")
+	assert.Contains(t, marked, "[5] hexagon")
+}
+
+func TestInspector_Run(t *testing.T) {
+	symbols, connections := testProgram()
+	in := New("test.png", symbols, connections)
+
+	var out bytes.Buffer
+	err := in.Run(strings.NewReader("cjjj\nq\n"), &out)
+	require.NoError(t, err)
+
+	assert.Equal(t, PaneCode, in.pane)
+	assert.Equal(t, 3, in.cursor[PaneCode])
+	assert.Contains(t, out.String(), "test.png")
+}
+
+func TestInspector_MoveClampsCursor(t *testing.T) {
+	symbols, connections := testProgram()
+	in := New("test.png", symbols, connections)
+
+	in.HandleKey('k')
+	assert.Equal(t, 0, in.cursor[PaneSymbols])
+
+	in.HandleKey('G')
+	assert.Equal(t, len(symbols)-1, in.cursor[PaneSymbols])
+
+	in.HandleKey('j')
+	assert.Equal(t, len(symbols)-1, in.cursor[PaneSymbols])
+
+	assert.False(t, in.HandleKey('q'))
+}
+
+func TestInspector_RunKeys(t *testing.T) {
+	symbols, connections := testProgram()
+	in := New("test.png", symbols, connections)
+
+	// Down twice, right to the AST pane, down once, Shift+Tab back, quit
+	var out bytes.Buffer
+	err := in.runKeys(strings.NewReader("\x1b[B\x1bOB\x1b[Cj\x1b[Zq"), &out)
+	require.NoError(t, err)
+
+	assert.Equal(t, PaneSymbols, in.pane)
+	assert.Equal(t, 2, in.cursor[PaneSymbols])
+	assert.Equal(t, 1, in.cursor[PaneAST])
+	assert.Contains(t, out.String(), "test.png")
+
+	// Ctrl+C quits too
+	assert.NoError(t, in.runKeys(strings.NewReader("\x03j"), &out))
+	assert.Equal(t, 2, in.cursor[PaneSymbols])
+}
+
+func TestScreen_RedrawsChangedLines(t *testing.T) {
+	var out bytes.Buffer
+	s := &screen{out: &out}
+
+	require.NoError(t, s.draw("title\n> one\n  two\n"))
+	assert.Equal(t, "\x1b[H\x1b[2J\x1b[1;1Htitle\x1b[K\x1b[2;1H> one\x1b[K\x1b[3;1H  two\x1b[K", out.String())
+
+	// Only the lines the cursor moved between are rewritten
+	out.Reset()
+	require.NoError(t, s.draw("title\n  one\n> two\n"))
+	assert.Equal(t, "\x1b[2;1H  one\x1b[K\x1b[3;1H> two\x1b[K", out.String())
+
+	// A shorter frame clears what is left of the longer one
+	out.Reset()
+	require.NoError(t, s.draw("title\n"))
+	assert.Equal(t, "\x1b[2;1H\x1b[J", out.String())
+}
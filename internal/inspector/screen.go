@@ -0,0 +1,42 @@
+package inspector
+
+import (
+	"fmt"
+	"io"
+	"strings"
+)
+
+// screen redraws a terminal in place. Each frame is compared line by line
+// with the one drawn before it, and only the lines that changed are
+// rewritten, so moving the cursor in one pane leaves the others alone.
+type screen struct {
+	out   io.Writer
+	lines []string // lines of the last frame drawn, nil before the first
+}
+
+// draw shows frame, rewriting the lines that differ from the last frame
+func (s *screen) draw(frame string) error {
+	lines := strings.Split(strings.TrimSuffix(frame, "\n"), "\n")
+	var b strings.Builder
+	if s.lines == nil {
+		b.WriteString("\x1b[H\x1b[2J")
+	}
+	for i, line := range lines {
+		if i < len(s.lines) && s.lines[i] == line {
+			continue
+		}
+		fmt.Fprintf(&b, "\x1b[%d;1H%s\x1b[K", i+1, line)
+	}
+	if len(lines) < len(s.lines) {
+		fmt.Fprintf(&b, "\x1b[%d;1H\x1b[J", len(lines)+1)
+	}
+	s.lines = lines
+	_, err := io.WriteString(s.out, b.String())
+	return err
+}
+
+// end moves the cursor below the last frame, so the shell prompt that
+// follows does not overwrite it
+func (s *screen) end() {
+	fmt.Fprintf(s.out, "\x1b[%d;1H\n", len(s.lines)+1)
+}
@@ -0,0 +1,11 @@
+//go:build darwin || freebsd
+
+package inspector
+
+import "syscall"
+
+// ioctl requests reading and writing terminal settings
+const (
+	ioctlGetTermios = syscall.TIOCGETA
+	ioctlSetTermios = syscall.TIOCSETA
+)
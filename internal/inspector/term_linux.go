@@ -0,0 +1,9 @@
+package inspector
+
+import "syscall"
+
+// ioctl requests reading and writing terminal settings
+const (
+	ioctlGetTermios = syscall.TCGETS
+	ioctlSetTermios = syscall.TCSETS
+)
@@ -0,0 +1,16 @@
+//go:build !(linux || darwin || freebsd)
+
+package inspector
+
+import "errors"
+
+// isTerminal reports whether fd is a terminal. Raw mode is only supported
+// on Unix, so elsewhere the inspector always reads keys line by line.
+func isTerminal(int) bool {
+	return false
+}
+
+// makeRaw is not supported outside Unix
+func makeRaw(int) (func() error, error) {
+	return nil, errors.New("raw mode is not supported on this platform")
+}
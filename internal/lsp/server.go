@@ -0,0 +1,472 @@
+// Package lsp implements a Language Server Protocol server for magic circle images.
+//
+// Documents are image files. Positions map onto pixels: Line is the row (y)
+// and Character is the column (x), so a symbol's range is its bounding box.
+// The server publishes diagnostics for every image in the workspace, keeps
+// them up to date as files change, and answers hover and definition
+// requests for the symbol under the cursor.
+package lsp
+
+import (
+	"bufio"
+	"encoding/json"
+	"errors"
+	"io"
+	"math"
+	"net/url"
+	"os"
+	"path/filepath"
+	"runtime"
+	"strings"
+	"sync"
+	"time"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/parser"
+)
+
+// diagnosticSource names the server in published diagnostics
+const diagnosticSource = "grimoire"
+
+// imageExtensions are the files the server treats as documents
+var imageExtensions = map[string]bool{
+	".png": true, ".jpg": true, ".jpeg": true, ".gif": true, ".webp": true,
+}
+
+// document is the analysis of one image
+type document struct {
+	modTime     time.Time
+	roles       []parser.SymbolRole
+	diagnostics []Diagnostic
+}
+
+// Server is a language server speaking JSON-RPC over a reader and writer
+type Server struct {
+	// PollInterval is how often the workspace is rescanned for changed
+	// images; zero disables polling
+	PollInterval time.Duration
+	// DescribeRole returns the hover text for a symbol role
+	DescribeRole func(parser.SymbolRole) string
+
+	in      *bufio.Reader
+	out     io.Writer
+	writeMu sync.Mutex
+
+	mu       sync.Mutex
+	root     string
+	docs     map[string]*document // keyed by file path
+	shutdown bool
+	stop     chan struct{}
+}
+
+// NewServer creates a server reading requests from in and writing to out
+func NewServer(in io.Reader, out io.Writer) *Server {
+	return &Server{
+		PollInterval: time.Second,
+		DescribeRole: func(role parser.SymbolRole) string { return string(role.Kind) },
+		in:           bufio.NewReader(in),
+		out:          out,
+		docs:         make(map[string]*document),
+		stop:         make(chan struct{}),
+	}
+}
+
+// Run serves requests until the client sends exit or closes the input
+func (s *Server) Run() error {
+	defer s.stopWatching()
+
+	for {
+		body, err := readMessage(s.in)
+		if err == io.EOF {
+			return nil
+		}
+		if err != nil {
+			return err
+		}
+
+		var msg message
+		if err := json.Unmarshal(body, &msg); err != nil {
+			s.replyError(nil, codeParseError, err.Error())
+			continue
+		}
+		if msg.Method == "exit" {
+			s.mu.Lock()
+			shutdown := s.shutdown
+			s.mu.Unlock()
+			if !shutdown {
+				return errors.New("exit received before shutdown")
+			}
+			return nil
+		}
+		s.handle(msg)
+	}
+}
+
+// handle dispatches a request or notification
+func (s *Server) handle(msg message) {
+	switch msg.Method {
+	case "initialize":
+		var params initializeParams
+		_ = json.Unmarshal(msg.Params, &params)
+		s.mu.Lock()
+		s.root = params.RootPath
+		if params.RootURI != "" {
+			s.root = uriToPath(params.RootURI)
+		}
+		s.mu.Unlock()
+		s.reply(msg.ID, map[string]interface{}{
+			"capabilities": map[string]interface{}{
+				"textDocumentSync":   map[string]interface{}{"openClose": true, "save": true},
+				"hoverProvider":      true,
+				"definitionProvider": true,
+			},
+			"serverInfo": map[string]string{"name": "grimoire"},
+		})
+	case "initialized":
+		s.scanWorkspace()
+		if s.PollInterval > 0 {
+			go s.watch()
+		}
+	case "shutdown":
+		s.mu.Lock()
+		s.shutdown = true
+		s.mu.Unlock()
+		s.stopWatching()
+		s.reply(msg.ID, nil)
+	case "textDocument/didOpen", "textDocument/didSave":
+		var params textDocumentParams
+		if err := json.Unmarshal(msg.Params, &params); err == nil {
+			s.analyze(uriToPath(params.TextDocument.URI), true)
+		}
+	case "workspace/didChangeWatchedFiles":
+		var params didChangeWatchedFilesParams
+		if err := json.Unmarshal(msg.Params, &params); err == nil {
+			for _, change := range params.Changes {
+				if change.Type == fileDeleted {
+					s.forget(uriToPath(change.URI))
+				} else {
+					s.analyze(uriToPath(change.URI), true)
+				}
+			}
+		}
+	case "textDocument/hover":
+		s.handlePositionRequest(msg, s.hover)
+	case "textDocument/definition":
+		s.handlePositionRequest(msg, s.definition)
+	default:
+		// Notifications we do not handle (didChange, didClose, $/...) are ignored
+		if msg.ID != nil {
+			s.replyError(msg.ID, codeMethodNotFound, "method not found: "+msg.Method)
+		}
+	}
+}
+
+// handlePositionRequest decodes position parameters and replies with the result of fn
+func (s *Server) handlePositionRequest(msg message, fn func(path string, pos Position) interface{}) {
+	var params textDocumentPositionParams
+	if err := json.Unmarshal(msg.Params, &params); err != nil {
+		s.replyError(msg.ID, codeInvalidParams, err.Error())
+		return
+	}
+	path := uriToPath(params.TextDocument.URI)
+	s.analyze(path, false)
+	s.reply(msg.ID, fn(path, params.Position))
+}
+
+// hover describes the symbol at pos
+func (s *Server) hover(path string, pos Position) interface{} {
+	role, ok := s.roleAt(path, pos)
+	if !ok {
+		return nil
+	}
+
+	value := "**" + string(role.Symbol.Type) + "**"
+	if role.Symbol.Pattern != "" && role.Symbol.Pattern != detector.PatternEmpty {
+		value += " (" + role.Symbol.Pattern + ")"
+	}
+	value += "\n\n" + s.DescribeRole(role)
+
+	r := symbolRange(role.Symbol)
+	return Hover{Contents: MarkupContent{Kind: "markdown", Value: value}, Range: &r}
+}
+
+// definition locates the function or main entry that defines the symbol at pos
+func (s *Server) definition(path string, pos Position) interface{} {
+	role, ok := s.roleAt(path, pos)
+	if !ok {
+		return nil
+	}
+
+	definition := role.Function
+	if role.Kind == parser.RoleFunction || role.Kind == parser.RoleMainEntry {
+		definition = &role
+	}
+	if definition == nil {
+		return nil
+	}
+	return Location{URI: pathToURI(path), Range: symbolRange(definition.Symbol)}
+}
+
+// roleAt returns the innermost symbol whose bounding box contains pos
+func (s *Server) roleAt(path string, pos Position) (parser.SymbolRole, bool) {
+	s.mu.Lock()
+	doc := s.docs[path]
+	s.mu.Unlock()
+	if doc == nil {
+		return parser.SymbolRole{}, false
+	}
+
+	var best parser.SymbolRole
+	found := false
+	for _, role := range doc.roles {
+		r := symbolRange(role.Symbol)
+		if pos.Line < r.Start.Line || pos.Line > r.End.Line ||
+			pos.Character < r.Start.Character || pos.Character > r.End.Character {
+			continue
+		}
+		if !found || role.Symbol.Size < best.Symbol.Size {
+			best, found = role, true
+		}
+	}
+	return best, found
+}
+
+// scanWorkspace analyzes every image under the root and publishes diagnostics
+// for new or changed images and for images that disappeared
+func (s *Server) scanWorkspace() {
+	s.mu.Lock()
+	root := s.root
+	s.mu.Unlock()
+	if root == "" {
+		return
+	}
+
+	seen := make(map[string]bool)
+	_ = filepath.Walk(root, func(path string, info os.FileInfo, err error) error {
+		if err != nil {
+			return nil
+		}
+		if info.IsDir() {
+			if path != root && strings.HasPrefix(info.Name(), ".") {
+				return filepath.SkipDir
+			}
+			return nil
+		}
+		if imageExtensions[strings.ToLower(filepath.Ext(path))] {
+			seen[path] = true
+			s.analyze(path, false)
+		}
+		return nil
+	})
+
+	s.mu.Lock()
+	var removed []string
+	for path := range s.docs {
+		if !seen[path] && isUnder(path, root) {
+			removed = append(removed, path)
+		}
+	}
+	s.mu.Unlock()
+	for _, path := range removed {
+		s.forget(path)
+	}
+}
+
+// watch rescans the workspace until the server shuts down
+func (s *Server) watch() {
+	ticker := time.NewTicker(s.PollInterval)
+	defer ticker.Stop()
+	for {
+		select {
+		case <-s.stop:
+			return
+		case <-ticker.C:
+			s.scanWorkspace()
+		}
+	}
+}
+
+// stopWatching stops the workspace watcher; it is safe to call more than once
+func (s *Server) stopWatching() {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	select {
+	case <-s.stop:
+	default:
+		close(s.stop)
+	}
+}
+
+// analyze detects and parses the image at path and publishes its diagnostics.
+// Unless force is set, images unchanged since the last analysis are skipped.
+func (s *Server) analyze(path string, force bool) {
+	info, err := os.Stat(path)
+	if err != nil {
+		s.forget(path)
+		return
+	}
+
+	s.mu.Lock()
+	doc := s.docs[path]
+	s.mu.Unlock()
+	if doc != nil && !force && doc.modTime.Equal(info.ModTime()) {
+		return
+	}
+
+	doc = &document{modTime: info.ModTime()}
+	symbols, connections, err := detector.DetectSymbols(path)
+	if err != nil {
+		doc.diagnostics = errorDiagnostics(err)
+	} else {
+		p := parser.NewParser()
+		_, parseErr := p.Parse(symbols, connections)
+		doc.roles = p.Roles()
+		doc.diagnostics = append(errorDiagnostics(parseErr), symbolDiagnostics(symbols, connections)...)
+	}
+
+	s.mu.Lock()
+	s.docs[path] = doc
+	s.mu.Unlock()
+	s.publish(path, doc.diagnostics)
+}
+
+// forget drops a document and clears its diagnostics
+func (s *Server) forget(path string) {
+	s.mu.Lock()
+	_, known := s.docs[path]
+	delete(s.docs, path)
+	s.mu.Unlock()
+	if known {
+		s.publish(path, nil)
+	}
+}
+
+// errorDiagnostics converts a pipeline error to diagnostics, using each
+// error's image region when it has one
+func errorDiagnostics(err error) []Diagnostic {
+	var diags []Diagnostic
+	for _, d := range grimoireErrors.Diagnostics(err) {
+		message := d.Message
+		if d.Help != "" {
+			message += "\n" + d.Help
+		}
+		var r Range
+		if d.Region != nil {
+			r = Range{
+				Start: Position{Line: d.Region.Y, Character: d.Region.X},
+				End:   Position{Line: d.Region.Y + d.Region.Height, Character: d.Region.X + d.Region.Width},
+			}
+		}
+		diags = append(diags, Diagnostic{
+			Range:    r,
+			Severity: severityError,
+			Code:     string(d.Code),
+			Source:   diagnosticSource,
+			Message:  message,
+		})
+	}
+	return diags
+}
+
+// symbolDiagnostics reports unrecognized symbols and symbols without connections
+func symbolDiagnostics(symbols []*detector.Symbol, connections []detector.Connection) []Diagnostic {
+	connected := make(map[*detector.Symbol]bool)
+	for _, c := range connections {
+		connected[c.From] = true
+		connected[c.To] = true
+	}
+
+	var diags []Diagnostic
+	for _, symbol := range symbols {
+		switch {
+		case symbol.Type == detector.Unknown:
+			diags = append(diags, Diagnostic{
+				Range:    symbolRange(symbol),
+				Severity: severityError,
+				Source:   diagnosticSource,
+				Message:  i18n.T("lsp.invalid_symbol"),
+			})
+		case symbol.Type != detector.OuterCircle && !connected[symbol]:
+			diags = append(diags, Diagnostic{
+				Range:    symbolRange(symbol),
+				Severity: severityWarning,
+				Source:   diagnosticSource,
+				Message:  i18n.Tf("lsp.unconnected_symbol", symbol.Type),
+			})
+		}
+	}
+	return diags
+}
+
+// publish sends the diagnostics of a document to the client
+func (s *Server) publish(path string, diags []Diagnostic) {
+	if diags == nil {
+		diags = []Diagnostic{}
+	}
+	s.send(notification{
+		JSONRPC: "2.0",
+		Method:  "textDocument/publishDiagnostics",
+		Params:  PublishDiagnosticsParams{URI: pathToURI(path), Diagnostics: diags},
+	})
+}
+
+func (s *Server) reply(id *json.RawMessage, result interface{}) {
+	s.send(response{JSONRPC: "2.0", ID: id, Result: result})
+}
+
+func (s *Server) replyError(id *json.RawMessage, code int, message string) {
+	s.send(errorResponse{JSONRPC: "2.0", ID: id, Error: responseError{Code: code, Message: message}})
+}
+
+// send writes a message; the watcher and the request loop share the writer
+func (s *Server) send(v interface{}) {
+	s.writeMu.Lock()
+	defer s.writeMu.Unlock()
+	_ = writeMessage(s.out, v)
+}
+
+// symbolRange returns the bounding box of a symbol
+func symbolRange(symbol *detector.Symbol) Range {
+	half := symbol.Size / 2
+	return Range{
+		Start: Position{
+			Line:      int(math.Round(symbol.Position.Y - half)),
+			Character: int(math.Round(symbol.Position.X - half)),
+		},
+		End: Position{
+			Line:      int(math.Round(symbol.Position.Y + half)),
+			Character: int(math.Round(symbol.Position.X + half)),
+		},
+	}
+}
+
+// uriToPath converts a file URI to a local path
+func uriToPath(uri string) string {
+	u, err := url.Parse(uri)
+	if err != nil || u.Scheme != "file" {
+		return uri
+	}
+	path := u.Path
+	// file:///C:/dir on Windows
+	if runtime.GOOS == "windows" && len(path) > 2 && path[0] == '/' && path[2] == ':' {
+		path = path[1:]
+	}
+	return filepath.FromSlash(path)
+}
+
+// pathToURI converts a local path to a file URI
+func pathToURI(path string) string {
+	path = filepath.ToSlash(path)
+	if !strings.HasPrefix(path, "/") {
+		path = "/" + path
+	}
+	return (&url.URL{Scheme: "file", Path: path}).String()
+}
+
+// isUnder reports whether path is inside dir
+func isUnder(path, dir string) bool {
+	rel, err := filepath.Rel(dir, path)
+	return err == nil && !strings.HasPrefix(rel, "..")
+}
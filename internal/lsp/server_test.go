@@ -0,0 +1,187 @@
+package lsp
+
+import (
+	"bufio"
+	"bytes"
+	"encoding/json"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// request builds a framed JSON-RPC message
+func request(t *testing.T, id int, method string, params interface{}) []byte {
+	t.Helper()
+	msg := map[string]interface{}{"jsonrpc": "2.0", "method": method}
+	if id > 0 {
+		msg["id"] = id
+	}
+	if params != nil {
+		msg["params"] = params
+	}
+	var buf bytes.Buffer
+	require.NoError(t, writeMessage(&buf, msg))
+	return buf.Bytes()
+}
+
+// incoming is an outgoing server message as seen by the client
+type incoming struct {
+	ID     *int            `json:"id"`
+	Method string          `json:"method"`
+	Params json.RawMessage `json:"params"`
+	Result json.RawMessage `json:"result"`
+	Error  *responseError  `json:"error"`
+}
+
+// runSession runs the server over the given requests and returns everything it sent
+func runSession(t *testing.T, requests ...[]byte) ([]incoming, error) {
+	t.Helper()
+	var out bytes.Buffer
+	server := NewServer(bytes.NewReader(bytes.Join(requests, nil)), &out)
+	server.PollInterval = 0
+	err := server.Run()
+
+	var messages []incoming
+	r := bufio.NewReader(&out)
+	for {
+		body, readErr := readMessage(r)
+		if readErr != nil {
+			break
+		}
+		var msg incoming
+		require.NoError(t, json.Unmarshal(body, &msg))
+		messages = append(messages, msg)
+	}
+	return messages, err
+}
+
+// responseTo finds the response to the request with the given id
+func responseTo(t *testing.T, messages []incoming, id int) incoming {
+	t.Helper()
+	for _, msg := range messages {
+		if msg.ID != nil && *msg.ID == id {
+			return msg
+		}
+	}
+	t.Fatalf("no response to request %d", id)
+	return incoming{}
+}
+
+func TestReadWriteMessage(t *testing.T) {
+	var buf bytes.Buffer
+	require.NoError(t, writeMessage(&buf, map[string]string{"hello": "world"}))
+	assert.Equal(t, "Content-Length: 17\r\n\r\n{\"hello\":\"world\"}", buf.String())
+
+	body, err := readMessage(bufio.NewReader(&buf))
+	require.NoError(t, err)
+	assert.JSONEq(t, `{"hello":"world"}`, string(body))
+
+	_, err = readMessage(bufio.NewReader(bytes.NewBufferString("Content-Length: x\r\n\r\n")))
+	assert.Error(t, err)
+}
+
+func TestURIConversion(t *testing.T) {
+	dir := t.TempDir()
+	path := filepath.Join(dir, "magic circle.png")
+	uri := pathToURI(path)
+	assert.Contains(t, uri, "file://")
+	assert.Contains(t, uri, "magic%20circle.png")
+	assert.Equal(t, path, uriToPath(uri))
+}
+
+func TestServer_Session(t *testing.T) {
+	data, err := os.ReadFile(filepath.Join("..", "..", "examples", "images", "hello_world.png"))
+	if err != nil {
+		t.Skip("Example image not found")
+	}
+	dir := t.TempDir()
+	imagePath := filepath.Join(dir, "hello.png")
+	require.NoError(t, os.WriteFile(imagePath, data, 0o644))
+	uri := pathToURI(imagePath)
+
+	symbols, _, err := detector.DetectSymbols(imagePath)
+	require.NoError(t, err)
+	var star *detector.Symbol
+	for _, s := range symbols {
+		if s.Type == detector.Star {
+			star = s
+		}
+	}
+	require.NotNil(t, star, "hello_world.png should contain an output star")
+	starPosition := map[string]int{"line": int(star.Position.Y), "character": int(star.Position.X)}
+	document := map[string]interface{}{"uri": uri}
+
+	messages, err := runSession(t,
+		request(t, 1, "initialize", map[string]interface{}{"rootUri": pathToURI(dir)}),
+		request(t, 0, "initialized", map[string]interface{}{}),
+		request(t, 2, "textDocument/hover", map[string]interface{}{"textDocument": document, "position": starPosition}),
+		request(t, 3, "textDocument/definition", map[string]interface{}{"textDocument": document, "position": starPosition}),
+		request(t, 4, "textDocument/hover", map[string]interface{}{
+			"textDocument": document, "position": map[string]int{"line": -100, "character": -100},
+		}),
+		request(t, 5, "unknown/method", nil),
+		request(t, 6, "shutdown", nil),
+		request(t, 0, "exit", nil),
+	)
+	require.NoError(t, err)
+
+	var initResult struct {
+		Capabilities map[string]interface{} `json:"capabilities"`
+	}
+	require.NoError(t, json.Unmarshal(responseTo(t, messages, 1).Result, &initResult))
+	assert.Equal(t, true, initResult.Capabilities["hoverProvider"])
+	assert.Equal(t, true, initResult.Capabilities["definitionProvider"])
+
+	// The workspace scan publishes diagnostics for the image
+	published := false
+	for _, msg := range messages {
+		if msg.Method == "textDocument/publishDiagnostics" {
+			var params PublishDiagnosticsParams
+			require.NoError(t, json.Unmarshal(msg.Params, &params))
+			assert.Equal(t, uri, params.URI)
+			published = true
+		}
+	}
+	assert.True(t, published)
+
+	var hover Hover
+	require.NoError(t, json.Unmarshal(responseTo(t, messages, 2).Result, &hover))
+	assert.Equal(t, "markdown", hover.Contents.Kind)
+	assert.Contains(t, hover.Contents.Value, "**star**")
+	assert.Contains(t, hover.Contents.Value, "output")
+	assert.Equal(t, symbolRange(star), *hover.Range)
+
+	var location Location
+	require.NoError(t, json.Unmarshal(responseTo(t, messages, 3).Result, &location))
+	assert.Equal(t, uri, location.URI)
+
+	assert.Equal(t, "null", string(responseTo(t, messages, 4).Result))
+	assert.Equal(t, codeMethodNotFound, responseTo(t, messages, 5).Error.Code)
+	assert.Equal(t, "null", string(responseTo(t, messages, 6).Result))
+}
+
+func TestServer_ExitWithoutShutdown(t *testing.T) {
+	_, err := runSession(t, request(t, 0, "exit", nil))
+	assert.Error(t, err)
+}
+
+func TestSymbolDiagnostics(t *testing.T) {
+	outer := &detector.Symbol{Type: detector.OuterCircle, Position: detector.Position{X: 100, Y: 100}, Size: 180}
+	entry := &detector.Symbol{Type: detector.DoubleCircle, Position: detector.Position{X: 100, Y: 50}, Size: 20}
+	star := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 100, Y: 100}, Size: 20}
+	lonely := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 150, Y: 60}, Size: 20}
+	unknown := &detector.Symbol{Type: detector.Unknown, Position: detector.Position{X: 40, Y: 140}, Size: 10}
+	connections := []detector.Connection{{From: entry, To: star}}
+
+	diags := symbolDiagnostics([]*detector.Symbol{outer, entry, star, lonely, unknown}, connections)
+	require.Len(t, diags, 2)
+
+	assert.Equal(t, severityWarning, diags[0].Severity)
+	assert.Equal(t, Range{Start: Position{Line: 50, Character: 140}, End: Position{Line: 70, Character: 160}}, diags[0].Range)
+	assert.Equal(t, severityError, diags[1].Severity)
+	assert.Equal(t, Range{Start: Position{Line: 135, Character: 35}, End: Position{Line: 145, Character: 45}}, diags[1].Range)
+}
@@ -0,0 +1,96 @@
+package parser
+
+import (
+	"sort"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+)
+
+// maxAmbiguityTrials is how many alternative readings are tried at most
+// before giving up on an invalid program
+const maxAmbiguityTrials = 32
+
+// Resolution records that an ambiguous symbol was read as one of its
+// alternatives because its detected type did not make a valid program
+type Resolution struct {
+	// Symbol is the symbol that was reread, now of the alternative's type
+	Symbol *detector.Symbol
+	// From and FromConfidence are the type the symbol was detected as and
+	// its confidence
+	From           detector.SymbolType
+	FromConfidence float64
+	// To is the alternative the symbol was read as
+	To detector.Alternative
+}
+
+// Warning describes the resolution as an error located at the symbol, for
+// reporting as a warning diagnostic
+func (r Resolution) Warning() *grimoireErrors.GrimoireError {
+	return grimoireErrors.NewError(grimoireErrors.InvalidSymbolShape,
+		i18n.Tf("parser.ambiguous", r.From, r.To.Type)).
+		WithDetails(i18n.Tf("parser.ambiguous_detail", r.From, r.FromConfidence, r.To.Type, r.To.Confidence)).
+		WithSuggestion(i18n.T("parser.ambiguous_suggestion")).
+		WithRegion(r.Symbol.Position.X, r.Symbol.Position.Y, r.Symbol.Size)
+}
+
+// ParseResolvingAmbiguity parses like Parse, and when the program does not
+// parse or type check, tries the alternatives of ambiguous symbols one at a
+// time, from the most confident, keeping the first that makes the program
+// valid. The symbol is changed to that alternative and the choice is
+// reported by Resolutions. When no alternative helps, the result is that of
+// Parse.
+func (p *Parser) ParseResolvingAmbiguity(symbols []*detector.Symbol, connections []detector.Connection) (*Program, error) {
+	p.resolutions = nil
+	program, err := p.Parse(symbols, connections)
+	if err == nil && TypeCheck(program, p.Roles()) == nil {
+		return program, nil
+	}
+
+	readings := ambiguousReadings(symbols)
+	if len(readings) == 0 {
+		return program, err
+	}
+	for trial, reading := range readings {
+		if trial == maxAmbiguityTrials {
+			break
+		}
+		symbol := reading.Symbol
+		from, fromConfidence := symbol.Type, symbol.Confidence
+		symbol.Type, symbol.Confidence = reading.To.Type, reading.To.Confidence
+		if alt, altErr := p.Parse(symbols, connections); altErr == nil && TypeCheck(alt, p.Roles()) == nil {
+			reading.From, reading.FromConfidence = from, fromConfidence
+			p.resolutions = []Resolution{reading}
+			return alt, nil
+		}
+		symbol.Type, symbol.Confidence = from, fromConfidence
+	}
+
+	// Parse the drawing as detected again, so that the roles describe it
+	return p.Parse(symbols, connections)
+}
+
+// Resolutions returns the ambiguous symbols the last ParseResolvingAmbiguity
+// call read as one of their alternatives
+func (p *Parser) Resolutions() []Resolution {
+	return p.resolutions
+}
+
+// ambiguousReadings returns every alternative reading of the symbols, the
+// most confident first
+func ambiguousReadings(symbols []*detector.Symbol) []Resolution {
+	var readings []Resolution
+	for _, symbol := range symbols {
+		if symbol == nil {
+			continue
+		}
+		for _, alt := range symbol.Alternatives {
+			readings = append(readings, Resolution{Symbol: symbol, To: alt})
+		}
+	}
+	sort.SliceStable(readings, func(i, j int) bool {
+		return readings[i].To.Confidence > readings[j].To.Confidence
+	})
+	return readings
+}
@@ -0,0 +1,79 @@
+package parser
+
+import (
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// ambiguousSubtraction returns the subtraction of ports_test.go with its
+// second operand detected as a pentagon, which is not an operand
+func ambiguousSubtraction(alternatives ...detector.Alternative) ([]*detector.Symbol, []detector.Connection, *detector.Symbol) {
+	symbols, connections := subtraction(nil, nil)
+	two := symbols[2]
+	two.Type, two.Confidence, two.Alternatives = detector.Pentagon, 0.6, alternatives
+	return symbols, connections, two
+}
+
+func TestParseResolvingAmbiguity(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	symbols, connections, two := ambiguousSubtraction(
+		detector.Alternative{Type: detector.Hexagon, Confidence: 0.58},
+		detector.Alternative{Type: detector.Square, Confidence: 0.55},
+	)
+	_, err := Parse(symbols, connections)
+	require.Error(t, err)
+
+	p := NewParser()
+	program, err := p.ParseResolvingAmbiguity(symbols, connections)
+	require.NoError(t, err)
+	op := outputOperation(t, program)
+	assert.Equal(t, 2, op.Right.(*Literal).Value)
+
+	// The hexagon, though more confident, does not parse either
+	require.Len(t, p.Resolutions(), 1)
+	resolution := p.Resolutions()[0]
+	assert.Equal(t, two, resolution.Symbol)
+	assert.Equal(t, detector.Pentagon, resolution.From)
+	assert.Equal(t, 0.6, resolution.FromConfidence)
+	assert.Equal(t, detector.Square, resolution.To.Type)
+	assert.Equal(t, detector.Square, two.Type)
+	assert.Equal(t, 0.55, two.Confidence)
+
+	warning := resolution.Warning()
+	assert.Equal(t, grimoireErrors.InvalidSymbolShape, warning.Type)
+	assert.Contains(t, warning.Message, "Ambiguous pentagon read as square")
+	assert.NotNil(t, warning.Region)
+}
+
+func TestParseResolvingAmbiguityWithoutValidReading(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	symbols, connections, two := ambiguousSubtraction(detector.Alternative{Type: detector.Hexagon, Confidence: 0.58})
+
+	p := NewParser()
+	_, err := p.ParseResolvingAmbiguity(symbols, connections)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "requires two operands")
+	assert.Empty(t, p.Resolutions())
+	assert.Equal(t, detector.Pentagon, two.Type, "the symbol keeps its detected type")
+	assert.Equal(t, 0.6, two.Confidence)
+}
+
+func TestParseResolvingAmbiguityKeepsValidPrograms(t *testing.T) {
+	symbols, connections := subtraction(nil, nil)
+	symbols[2].Alternatives = []detector.Alternative{{Type: detector.Hexagon, Confidence: 0.9}}
+
+	p := NewParser()
+	_, err := p.ParseResolvingAmbiguity(symbols, connections)
+	require.NoError(t, err)
+	assert.Empty(t, p.Resolutions())
+	assert.Equal(t, detector.Square, symbols[2].Type)
+}
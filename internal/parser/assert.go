@@ -0,0 +1,25 @@
+package parser
+
+// isCondition reports whether node was parsed as the condition of an
+// if-branch
+func (p *Parser) isCondition(node *symbolNode) bool {
+	role, ok := p.roles[node.symbol]
+	return ok && role.Kind == RoleCondition
+}
+
+// parseAssertion parses an equals sign drawn as a statement, which asserts
+// that the operands feeding its two ports are equal
+func (p *Parser) parseAssertion(node *symbolNode) *Assertion {
+	p.recordRole(node, RoleAssertion)
+
+	operands := p.parseBinaryOp(node)
+	stmt := &Assertion{
+		Left:  operands.Left,
+		Right: operands.Right,
+		X:     node.symbol.Position.X,
+		Y:     node.symbol.Position.Y,
+	}
+	// The operands were read as an operation, which is not part of the AST
+	p.roles[node.symbol].Node = stmt
+	return stmt
+}
@@ -0,0 +1,70 @@
+package parser
+
+import (
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseAssertion(t *testing.T) {
+	entry := &detector.Symbol{Type: detector.DoubleCircle, Position: detector.Position{X: 200, Y: 60}}
+	one := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 150, Y: 100}, Pattern: detector.PatternDot}
+	two := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 250, Y: 100}, Pattern: detector.PatternDoubleDot}
+	equal := &detector.Symbol{Type: detector.Equal, Position: detector.Position{X: 200, Y: 150}}
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180},
+		entry, one, two, equal,
+	}
+	connections := []detector.Connection{
+		{From: entry, To: equal, ConnectionType: detector.ConnectionTypeSolid},
+		{From: one, To: equal, ConnectionType: detector.ConnectionTypeSolid},
+		{From: two, To: equal, ConnectionType: detector.ConnectionTypeSolid},
+	}
+
+	p := NewParser()
+	program, err := p.Parse(symbols, connections)
+	require.NoError(t, err)
+	require.NotNil(t, program.MainEntry)
+	require.Len(t, program.MainEntry.Body, 1)
+
+	assertion, ok := program.MainEntry.Body[0].(*Assertion)
+	require.True(t, ok, "statement is %T", program.MainEntry.Body[0])
+	assert.Equal(t, 1, assertion.Left.(*Literal).Value)
+	assert.Equal(t, 2, assertion.Right.(*Literal).Value)
+	assert.Equal(t, 200.0, assertion.X)
+	assert.Equal(t, 150.0, assertion.Y)
+
+	role := p.Roles()[4]
+	assert.Equal(t, RoleAssertion, role.Kind)
+	assert.Same(t, assertion, role.Node)
+}
+
+func TestConditionIsNotAssertion(t *testing.T) {
+	entry := &detector.Symbol{Type: detector.DoubleCircle, Position: detector.Position{X: 200, Y: 60}}
+	branch := &detector.Symbol{Type: detector.Triangle, Position: detector.Position{X: 200, Y: 120}}
+	cond := &detector.Symbol{Type: detector.Equal, Position: detector.Position{X: 220, Y: 150}}
+	left := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 240, Y: 100}, Pattern: detector.PatternDot}
+	right := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 280, Y: 100}, Pattern: detector.PatternDoubleDot}
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180},
+		entry, branch, cond, left, right,
+	}
+	connections := []detector.Connection{
+		{From: entry, To: branch},
+		{From: branch, To: cond},
+		{From: left, To: cond},
+		{From: right, To: cond},
+	}
+
+	program, err := Parse(symbols, connections)
+	require.NoError(t, err)
+	require.NotNil(t, program.MainEntry)
+	require.Len(t, program.MainEntry.Body, 1)
+
+	branchStmt, ok := program.MainEntry.Body[0].(*IfStatement)
+	require.True(t, ok, "statement is %T", program.MainEntry.Body[0])
+	assert.IsType(t, &BinaryOp{}, branchStmt.Condition)
+	assert.Empty(t, branchStmt.ElseBranch, "the condition is not also an assertion")
+}
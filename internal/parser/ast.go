@@ -10,6 +10,7 @@ const (
 	Boolean DataType = "boolean"
 	Array   DataType = "array"
 	Map     DataType = "map"
+	Quote   DataType = "quote"
 	Void    DataType = "void"
 )
 
@@ -49,6 +50,7 @@ type ASTNode interface {
 // Program is the root node
 type Program struct {
 	HasOuterCircle bool
+	Header         *ProgramHeader // nil when the outer circle carries no glyphs
 	MainEntry      *FunctionDef
 	Functions      []*FunctionDef
 	Globals        []Statement
@@ -56,6 +58,18 @@ type Program struct {
 
 func (*Program) node() {}
 
+// ProgramHeader is the metadata declared by glyphs on the outer circle
+type ProgramHeader struct {
+	// Version is the language version the program is written for, or 0
+	// when it declares none
+	Version int
+	// Capabilities lists the features the program requires, in the order
+	// their glyphs sit clockwise from the top of the circle
+	Capabilities []string
+}
+
+func (*ProgramHeader) node() {}
+
 // FunctionDef represents a function definition
 type FunctionDef struct {
 	Name       string
@@ -143,6 +157,48 @@ type ParallelBlock struct {
 func (*ParallelBlock) node()      {}
 func (*ParallelBlock) statement() {}
 
+// Block represents the statements connected below a rectangle, which run
+// in order where the block is
+type Block struct {
+	Body []Statement
+}
+
+func (*Block) node()      {}
+func (*Block) statement() {}
+
+// Stage represents the statements drawn in one band between the rings
+// inside the outer circle. Stages run in order of Number.
+type Stage struct {
+	Number int // 1 for the innermost band
+	Body   []Statement
+}
+
+func (*Stage) node()      {}
+func (*Stage) statement() {}
+
+// Mirror applies its body to each operand in turn, bound to Item. It is
+// built from a statement fed by a mirrored pair of symbols.
+type Mirror struct {
+	Item     *Identifier
+	Operands []Expression
+	Body     []Statement
+}
+
+func (*Mirror) node()      {}
+func (*Mirror) statement() {}
+
+// Assertion fails the program when Left and Right differ. It is built from
+// an equals sign drawn as a statement; X and Y locate the sign in the image.
+type Assertion struct {
+	Left  Expression
+	Right Expression
+	X     float64
+	Y     float64
+}
+
+func (*Assertion) node()      {}
+func (*Assertion) statement() {}
+
 // ReturnStatement represents a return statement
 type ReturnStatement struct {
 	Value Expression
@@ -230,3 +286,13 @@ type MapLiteral struct {
 func (*MapLiteral) node()          {}
 func (*MapLiteral) expression()    {}
 func (*MapLiteral) Type() DataType { return Map }
+
+// Quoted is a program drawn inside another, kept as a value to be
+// evaluated later instead of run in place
+type Quoted struct {
+	Program *Program
+}
+
+func (*Quoted) node()          {}
+func (*Quoted) expression()    {}
+func (*Quoted) Type() DataType { return Quote }
@@ -0,0 +1,655 @@
+package parser
+
+import (
+	"encoding/json"
+	"fmt"
+	"math"
+)
+
+// ASTSchemaVersion is the version of the JSON written by EncodeJSON. It is
+// increased whenever the encoding changes incompatibly; adding fields does
+// not change it.
+const ASTSchemaVersion = 1
+
+// EncodeJSON encodes an AST as indented JSON. Every node becomes an object
+// whose "node" field names its type; keys are sorted so the output is stable
+// and suitable for golden files. The root object also records the
+// "schemaVersion".
+func EncodeJSON(node ASTNode) ([]byte, error) {
+	encoded := nodeToJSON(node)
+	if root, ok := encoded.(map[string]interface{}); ok {
+		root["schemaVersion"] = ASTSchemaVersion
+	}
+	return json.MarshalIndent(encoded, "", "  ")
+}
+
+// nodeToJSON converts an AST node to a JSON-encodable value
+func nodeToJSON(node ASTNode) interface{} {
+	switch n := node.(type) {
+	case nil:
+		return nil
+	case *Program:
+		if n == nil {
+			return nil
+		}
+		program := map[string]interface{}{
+			"node":           "Program",
+			"hasOuterCircle": n.HasOuterCircle,
+			"mainEntry":      functionToJSON(n.MainEntry),
+			"functions":      functionsToJSON(n.Functions),
+			"globals":        statementsToJSON(n.Globals),
+		}
+		// Only programs with a header record one, so older golden files
+		// still match
+		if n.Header != nil {
+			program["header"] = nodeToJSON(n.Header)
+		}
+		return program
+	case *ProgramHeader:
+		capabilities := make([]interface{}, 0, len(n.Capabilities))
+		for _, capability := range n.Capabilities {
+			capabilities = append(capabilities, capability)
+		}
+		return map[string]interface{}{
+			"node":         "ProgramHeader",
+			"version":      n.Version,
+			"capabilities": capabilities,
+		}
+	case *FunctionDef:
+		return functionToJSON(n)
+	case *Assignment:
+		return map[string]interface{}{
+			"node":   "Assignment",
+			"target": nodeToJSON(n.Target),
+			"value":  expressionToJSON(n.Value),
+		}
+	case *OutputStatement:
+		return map[string]interface{}{
+			"node":  "OutputStatement",
+			"value": expressionToJSON(n.Value),
+		}
+	case *IfStatement:
+		return map[string]interface{}{
+			"node":       "IfStatement",
+			"condition":  expressionToJSON(n.Condition),
+			"thenBranch": statementsToJSON(n.ThenBranch),
+			"elseBranch": statementsToJSON(n.ElseBranch),
+		}
+	case *ForLoop:
+		return map[string]interface{}{
+			"node":    "ForLoop",
+			"counter": nodeToJSON(n.Counter),
+			"start":   expressionToJSON(n.Start),
+			"end":     expressionToJSON(n.End),
+			"step":    expressionToJSON(n.Step),
+			"body":    statementsToJSON(n.Body),
+		}
+	case *WhileLoop:
+		return map[string]interface{}{
+			"node":      "WhileLoop",
+			"condition": expressionToJSON(n.Condition),
+			"body":      statementsToJSON(n.Body),
+		}
+	case *ParallelBlock:
+		branches := make([]interface{}, 0, len(n.Branches))
+		for _, branch := range n.Branches {
+			branches = append(branches, statementsToJSON(branch))
+		}
+		return map[string]interface{}{
+			"node":     "ParallelBlock",
+			"branches": branches,
+		}
+	case *Block:
+		return map[string]interface{}{
+			"node": "Block",
+			"body": statementsToJSON(n.Body),
+		}
+	case *Stage:
+		return map[string]interface{}{
+			"node":   "Stage",
+			"number": n.Number,
+			"body":   statementsToJSON(n.Body),
+		}
+	case *Mirror:
+		operands := make([]interface{}, 0, len(n.Operands))
+		for _, operand := range n.Operands {
+			operands = append(operands, expressionToJSON(operand))
+		}
+		return map[string]interface{}{
+			"node":     "Mirror",
+			"item":     nodeToJSON(n.Item),
+			"operands": operands,
+			"body":     statementsToJSON(n.Body),
+		}
+	case *Assertion:
+		return map[string]interface{}{
+			"node":  "Assertion",
+			"left":  expressionToJSON(n.Left),
+			"right": expressionToJSON(n.Right),
+			"x":     n.X,
+			"y":     n.Y,
+		}
+	case *ReturnStatement:
+		return map[string]interface{}{
+			"node":  "ReturnStatement",
+			"value": expressionToJSON(n.Value),
+		}
+	case *ExpressionStatement:
+		return map[string]interface{}{
+			"node":       "ExpressionStatement",
+			"expression": expressionToJSON(n.Expression),
+		}
+	case *BinaryOp:
+		return map[string]interface{}{
+			"node":     "BinaryOp",
+			"operator": string(n.Operator),
+			"left":     expressionToJSON(n.Left),
+			"right":    expressionToJSON(n.Right),
+			"dataType": string(n.DataType),
+		}
+	case *UnaryOp:
+		return map[string]interface{}{
+			"node":     "UnaryOp",
+			"operator": string(n.Operator),
+			"operand":  expressionToJSON(n.Operand),
+			"dataType": string(n.DataType),
+		}
+	case *Literal:
+		return map[string]interface{}{
+			"node":  "Literal",
+			"type":  string(n.LiteralType),
+			"value": n.Value,
+		}
+	case *Identifier:
+		if n == nil {
+			return nil
+		}
+		return map[string]interface{}{
+			"node":     "Identifier",
+			"name":     n.Name,
+			"dataType": string(n.DataType),
+		}
+	case *FunctionCall:
+		args := make([]interface{}, 0, len(n.Arguments))
+		for _, arg := range n.Arguments {
+			args = append(args, expressionToJSON(arg))
+		}
+		return map[string]interface{}{
+			"node":      "FunctionCall",
+			"function":  nodeToJSON(n.Function),
+			"arguments": args,
+			"dataType":  string(n.DataType),
+		}
+	case *ArrayLiteral:
+		elements := make([]interface{}, 0, len(n.Elements))
+		for _, elem := range n.Elements {
+			elements = append(elements, expressionToJSON(elem))
+		}
+		return map[string]interface{}{
+			"node":     "ArrayLiteral",
+			"elements": elements,
+		}
+	case *MapLiteral:
+		pairs := make([]interface{}, 0, len(n.Pairs))
+		for _, pair := range n.Pairs {
+			pairs = append(pairs, []interface{}{expressionToJSON(pair[0]), expressionToJSON(pair[1])})
+		}
+		return map[string]interface{}{
+			"node":  "MapLiteral",
+			"pairs": pairs,
+		}
+	case *Quoted:
+		return map[string]interface{}{
+			"node":    "Quoted",
+			"program": nodeToJSON(n.Program),
+		}
+	default:
+		return map[string]interface{}{
+			"node": fmt.Sprintf("%T", node),
+		}
+	}
+}
+
+// functionToJSON converts a function definition, keeping nil as null
+func functionToJSON(fn *FunctionDef) interface{} {
+	if fn == nil {
+		return nil
+	}
+	params := make([]interface{}, 0, len(fn.Parameters))
+	for _, p := range fn.Parameters {
+		params = append(params, map[string]interface{}{
+			"name":         p.Name,
+			"dataType":     string(p.DataType),
+			"defaultValue": expressionToJSON(p.DefaultValue),
+		})
+	}
+	return map[string]interface{}{
+		"node":       "FunctionDef",
+		"name":       fn.Name,
+		"parameters": params,
+		"body":       statementsToJSON(fn.Body),
+		"returnType": string(fn.ReturnType),
+		"isMain":     fn.IsMain,
+	}
+}
+
+// functionsToJSON converts a list of function definitions
+func functionsToJSON(fns []*FunctionDef) []interface{} {
+	result := make([]interface{}, 0, len(fns))
+	for _, fn := range fns {
+		result = append(result, functionToJSON(fn))
+	}
+	return result
+}
+
+// statementsToJSON converts a statement list
+func statementsToJSON(stmts []Statement) []interface{} {
+	result := make([]interface{}, 0, len(stmts))
+	for _, stmt := range stmts {
+		result = append(result, statementToJSON(stmt))
+	}
+	return result
+}
+
+// expressionToJSON converts an expression, keeping nil as null
+func expressionToJSON(expr Expression) interface{} {
+	if expr == nil {
+		return nil
+	}
+	return nodeToJSON(expr)
+}
+
+// statementToJSON converts a statement, keeping nil as null
+func statementToJSON(stmt Statement) interface{} {
+	if stmt == nil {
+		return nil
+	}
+	return nodeToJSON(stmt)
+}
+
+// DecodeJSON decodes an AST encoded by EncodeJSON, including ASTs written
+// before the schema was versioned. Newer schema versions are rejected.
+// Empty lists decode as nil, and integer literals decode as int.
+func DecodeJSON(data []byte) (ASTNode, error) {
+	var raw interface{}
+	if err := json.Unmarshal(data, &raw); err != nil {
+		return nil, err
+	}
+	if root, ok := raw.(map[string]interface{}); ok {
+		if version, _ := root["schemaVersion"].(float64); version > ASTSchemaVersion {
+			return nil, fmt.Errorf("unsupported AST schema version %v (supported: %d)", version, ASTSchemaVersion)
+		}
+	}
+	return nodeFromJSON(raw)
+}
+
+// jsonObject is a decoded JSON object with typed accessors
+type jsonObject map[string]interface{}
+
+func (o jsonObject) str(key string) string {
+	s, _ := o[key].(string)
+	return s
+}
+
+func (o jsonObject) boolean(key string) bool {
+	b, _ := o[key].(bool)
+	return b
+}
+
+func (o jsonObject) list(key string) []interface{} {
+	l, _ := o[key].([]interface{})
+	return l
+}
+
+// nodeFromJSON converts a decoded JSON value back into an AST node
+func nodeFromJSON(v interface{}) (ASTNode, error) {
+	if v == nil {
+		return nil, nil
+	}
+	m, ok := v.(map[string]interface{})
+	if !ok {
+		return nil, fmt.Errorf("expected an AST node object, found %T", v)
+	}
+	o := jsonObject(m)
+
+	switch kind := o.str("node"); kind {
+	case "Program":
+		mainEntry, err := functionFromJSON(o["mainEntry"])
+		if err != nil {
+			return nil, err
+		}
+		functions, err := functionsFromJSON(o.list("functions"))
+		if err != nil {
+			return nil, err
+		}
+		globals, err := statementsFromJSON(o.list("globals"))
+		if err != nil {
+			return nil, err
+		}
+		header, err := nodeFromJSON(o["header"])
+		if err != nil {
+			return nil, err
+		}
+		programHeader, ok := header.(*ProgramHeader)
+		if header != nil && !ok {
+			return nil, fmt.Errorf("expected a program header, found %T", header)
+		}
+		return &Program{
+			HasOuterCircle: o.boolean("hasOuterCircle"),
+			Header:         programHeader,
+			MainEntry:      mainEntry,
+			Functions:      functions,
+			Globals:        globals,
+		}, nil
+	case "ProgramHeader":
+		version, _ := o["version"].(float64)
+		header := &ProgramHeader{Version: int(version)}
+		for _, capability := range o.list("capabilities") {
+			if s, ok := capability.(string); ok {
+				header.Capabilities = append(header.Capabilities, s)
+			}
+		}
+		return header, nil
+	case "FunctionDef":
+		return functionFromJSON(m)
+	case "Assignment":
+		target, err := identifierFromJSON(o["target"])
+		if err != nil {
+			return nil, err
+		}
+		value, err := expressionFromJSON(o["value"])
+		if err != nil {
+			return nil, err
+		}
+		return &Assignment{Target: target, Value: value}, nil
+	case "OutputStatement":
+		value, err := expressionFromJSON(o["value"])
+		if err != nil {
+			return nil, err
+		}
+		return &OutputStatement{Value: value}, nil
+	case "IfStatement":
+		condition, err := expressionFromJSON(o["condition"])
+		if err != nil {
+			return nil, err
+		}
+		thenBranch, err := statementsFromJSON(o.list("thenBranch"))
+		if err != nil {
+			return nil, err
+		}
+		elseBranch, err := statementsFromJSON(o.list("elseBranch"))
+		if err != nil {
+			return nil, err
+		}
+		return &IfStatement{Condition: condition, ThenBranch: thenBranch, ElseBranch: elseBranch}, nil
+	case "ForLoop":
+		counter, err := identifierFromJSON(o["counter"])
+		if err != nil {
+			return nil, err
+		}
+		bounds, err := expressionsFromJSON([]interface{}{o["start"], o["end"], o["step"]})
+		if err != nil {
+			return nil, err
+		}
+		body, err := statementsFromJSON(o.list("body"))
+		if err != nil {
+			return nil, err
+		}
+		return &ForLoop{Counter: counter, Start: bounds[0], End: bounds[1], Step: bounds[2], Body: body}, nil
+	case "WhileLoop":
+		condition, err := expressionFromJSON(o["condition"])
+		if err != nil {
+			return nil, err
+		}
+		body, err := statementsFromJSON(o.list("body"))
+		if err != nil {
+			return nil, err
+		}
+		return &WhileLoop{Condition: condition, Body: body}, nil
+	case "ParallelBlock":
+		block := &ParallelBlock{}
+		for _, branch := range o.list("branches") {
+			list, _ := branch.([]interface{})
+			stmts, err := statementsFromJSON(list)
+			if err != nil {
+				return nil, err
+			}
+			block.Branches = append(block.Branches, stmts)
+		}
+		return block, nil
+	case "Block":
+		body, err := statementsFromJSON(o.list("body"))
+		if err != nil {
+			return nil, err
+		}
+		return &Block{Body: body}, nil
+	case "Stage":
+		number, _ := o["number"].(float64)
+		body, err := statementsFromJSON(o.list("body"))
+		if err != nil {
+			return nil, err
+		}
+		return &Stage{Number: int(number), Body: body}, nil
+	case "Mirror":
+		item, err := identifierFromJSON(o["item"])
+		if err != nil {
+			return nil, err
+		}
+		operands, err := expressionsFromJSON(o.list("operands"))
+		if err != nil {
+			return nil, err
+		}
+		body, err := statementsFromJSON(o.list("body"))
+		if err != nil {
+			return nil, err
+		}
+		return &Mirror{Item: item, Operands: operands, Body: body}, nil
+	case "Assertion":
+		operands, err := expressionsFromJSON([]interface{}{o["left"], o["right"]})
+		if err != nil {
+			return nil, err
+		}
+		x, _ := o["x"].(float64)
+		y, _ := o["y"].(float64)
+		return &Assertion{Left: operands[0], Right: operands[1], X: x, Y: y}, nil
+	case "ReturnStatement":
+		value, err := expressionFromJSON(o["value"])
+		if err != nil {
+			return nil, err
+		}
+		return &ReturnStatement{Value: value}, nil
+	case "ExpressionStatement":
+		expr, err := expressionFromJSON(o["expression"])
+		if err != nil {
+			return nil, err
+		}
+		return &ExpressionStatement{Expression: expr}, nil
+	case "BinaryOp":
+		operands, err := expressionsFromJSON([]interface{}{o["left"], o["right"]})
+		if err != nil {
+			return nil, err
+		}
+		return &BinaryOp{
+			Left:     operands[0],
+			Operator: OperatorType(o.str("operator")),
+			Right:    operands[1],
+			DataType: DataType(o.str("dataType")),
+		}, nil
+	case "UnaryOp":
+		operand, err := expressionFromJSON(o["operand"])
+		if err != nil {
+			return nil, err
+		}
+		return &UnaryOp{
+			Operator: OperatorType(o.str("operator")),
+			Operand:  operand,
+			DataType: DataType(o.str("dataType")),
+		}, nil
+	case "Literal":
+		return literalFromJSON(o), nil
+	case "Identifier":
+		return &Identifier{Name: o.str("name"), DataType: DataType(o.str("dataType"))}, nil
+	case "FunctionCall":
+		function, err := identifierFromJSON(o["function"])
+		if err != nil {
+			return nil, err
+		}
+		args, err := expressionsFromJSON(o.list("arguments"))
+		if err != nil {
+			return nil, err
+		}
+		return &FunctionCall{Function: function, Arguments: args, DataType: DataType(o.str("dataType"))}, nil
+	case "ArrayLiteral":
+		elements, err := expressionsFromJSON(o.list("elements"))
+		if err != nil {
+			return nil, err
+		}
+		return &ArrayLiteral{Elements: elements}, nil
+	case "MapLiteral":
+		literal := &MapLiteral{}
+		for _, pair := range o.list("pairs") {
+			list, _ := pair.([]interface{})
+			if len(list) != 2 {
+				return nil, fmt.Errorf("map literal pair has %d elements", len(list))
+			}
+			kv, err := expressionsFromJSON(list)
+			if err != nil {
+				return nil, err
+			}
+			literal.Pairs = append(literal.Pairs, [2]Expression{kv[0], kv[1]})
+		}
+		return literal, nil
+	case "Quoted":
+		node, err := nodeFromJSON(o["program"])
+		if err != nil {
+			return nil, err
+		}
+		program, ok := node.(*Program)
+		if node != nil && !ok {
+			return nil, fmt.Errorf("expected a quoted program, found %T", node)
+		}
+		return &Quoted{Program: program}, nil
+	default:
+		return nil, fmt.Errorf("unknown AST node %q", kind)
+	}
+}
+
+// literalFromJSON converts a literal, restoring integers decoded as float64
+func literalFromJSON(o jsonObject) *Literal {
+	literal := &Literal{Value: o["value"], LiteralType: DataType(o.str("type"))}
+	if f, ok := literal.Value.(float64); ok && literal.LiteralType == Integer && f == math.Trunc(f) {
+		literal.Value = int(f)
+	}
+	return literal
+}
+
+// functionFromJSON converts a function definition, keeping null as nil
+func functionFromJSON(v interface{}) (*FunctionDef, error) {
+	if v == nil {
+		return nil, nil
+	}
+	m, ok := v.(map[string]interface{})
+	if !ok {
+		return nil, fmt.Errorf("expected a function object, found %T", v)
+	}
+	o := jsonObject(m)
+
+	fn := &FunctionDef{
+		Name:       o.str("name"),
+		ReturnType: DataType(o.str("returnType")),
+		IsMain:     o.boolean("isMain"),
+	}
+	for _, p := range o.list("parameters") {
+		pm, _ := p.(map[string]interface{})
+		param := jsonObject(pm)
+		defaultValue, err := expressionFromJSON(param["defaultValue"])
+		if err != nil {
+			return nil, err
+		}
+		fn.Parameters = append(fn.Parameters, &Parameter{
+			Name:         param.str("name"),
+			DataType:     DataType(param.str("dataType")),
+			DefaultValue: defaultValue,
+		})
+	}
+
+	body, err := statementsFromJSON(o.list("body"))
+	if err != nil {
+		return nil, err
+	}
+	fn.Body = body
+	return fn, nil
+}
+
+// functionsFromJSON converts a list of function definitions
+func functionsFromJSON(list []interface{}) ([]*FunctionDef, error) {
+	var fns []*FunctionDef
+	for _, v := range list {
+		fn, err := functionFromJSON(v)
+		if err != nil {
+			return nil, err
+		}
+		fns = append(fns, fn)
+	}
+	return fns, nil
+}
+
+// statementsFromJSON converts a statement list
+func statementsFromJSON(list []interface{}) ([]Statement, error) {
+	var stmts []Statement
+	for _, v := range list {
+		node, err := nodeFromJSON(v)
+		if err != nil {
+			return nil, err
+		}
+		if node == nil {
+			stmts = append(stmts, nil)
+			continue
+		}
+		stmt, ok := node.(Statement)
+		if !ok {
+			return nil, fmt.Errorf("expected a statement, found %T", node)
+		}
+		stmts = append(stmts, stmt)
+	}
+	return stmts, nil
+}
+
+// expressionsFromJSON converts a list of expressions, keeping null as nil
+func expressionsFromJSON(list []interface{}) ([]Expression, error) {
+	var exprs []Expression
+	for _, v := range list {
+		expr, err := expressionFromJSON(v)
+		if err != nil {
+			return nil, err
+		}
+		exprs = append(exprs, expr)
+	}
+	return exprs, nil
+}
+
+// expressionFromJSON converts an expression, keeping null as nil
+func expressionFromJSON(v interface{}) (Expression, error) {
+	node, err := nodeFromJSON(v)
+	if err != nil || node == nil {
+		return nil, err
+	}
+	expr, ok := node.(Expression)
+	if !ok {
+		return nil, fmt.Errorf("expected an expression, found %T", node)
+	}
+	return expr, nil
+}
+
+// identifierFromJSON converts an identifier, keeping null as nil
+func identifierFromJSON(v interface{}) (*Identifier, error) {
+	node, err := nodeFromJSON(v)
+	if err != nil || node == nil {
+		return nil, err
+	}
+	ident, ok := node.(*Identifier)
+	if !ok {
+		return nil, fmt.Errorf("expected an identifier, found %T", node)
+	}
+	return ident, nil
+}
@@ -0,0 +1,186 @@
+package parser
+
+import (
+	"encoding/json"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestEncodeJSON(t *testing.T) {
+	program := &Program{
+		HasOuterCircle: true,
+		MainEntry: &FunctionDef{
+			IsMain: true,
+			Body: []Statement{
+				&OutputStatement{
+					Value: &BinaryOp{
+						Left:     &Literal{Value: 1, LiteralType: Integer},
+						Operator: Add,
+						Right:    &Literal{Value: 2, LiteralType: Integer},
+						DataType: Integer,
+					},
+				},
+			},
+		},
+	}
+
+	data, err := EncodeJSON(program)
+	require.NoError(t, err)
+
+	var decoded map[string]interface{}
+	require.NoError(t, json.Unmarshal(data, &decoded))
+
+	assert.Equal(t, "Program", decoded["node"])
+	entry := decoded["mainEntry"].(map[string]interface{})
+	assert.Equal(t, true, entry["isMain"])
+
+	body := entry["body"].([]interface{})
+	require.Len(t, body, 1)
+	output := body[0].(map[string]interface{})
+	assert.Equal(t, "OutputStatement", output["node"])
+
+	value := output["value"].(map[string]interface{})
+	assert.Equal(t, "add", value["operator"])
+	assert.Equal(t, float64(1), value["left"].(map[string]interface{})["value"])
+}
+
+func TestEncodeJSON_Deterministic(t *testing.T) {
+	outer := &detector.Symbol{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180}
+	entry := &detector.Symbol{Type: detector.DoubleCircle, Position: detector.Position{X: 200, Y: 60}}
+	data := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 200, Y: 120}, Pattern: detector.PatternDoubleDot}
+	symbols := []*detector.Symbol{outer, entry, data}
+	connections := []detector.Connection{{From: entry, To: data}}
+
+	encode := func() string {
+		// Copy symbols so every run sees different pointers
+		copied := make([]*detector.Symbol, len(symbols))
+		index := make(map[*detector.Symbol]*detector.Symbol)
+		for i, sym := range symbols {
+			c := *sym
+			copied[i] = &c
+			index[sym] = &c
+		}
+		conns := make([]detector.Connection, len(connections))
+		for i, conn := range connections {
+			conns[i] = detector.Connection{From: index[conn.From], To: index[conn.To]}
+		}
+
+		program, err := Parse(copied, conns)
+		require.NoError(t, err)
+		out, err := EncodeJSON(program)
+		require.NoError(t, err)
+		return string(out)
+	}
+
+	first := encode()
+	assert.Equal(t, first, encode())
+	assert.Contains(t, first, `"var_2"`)
+}
+
+func TestDecodeJSON_RoundTrip(t *testing.T) {
+	counter := &Identifier{Name: "i", DataType: Integer}
+	program := &Program{
+		HasOuterCircle: true,
+		MainEntry: &FunctionDef{
+			IsMain: true,
+			Body: []Statement{
+				&Assignment{Target: &Identifier{Name: "x", DataType: Float}, Value: &Literal{Value: 1.5, LiteralType: Float}},
+				&IfStatement{
+					Condition:  &BinaryOp{Left: &Identifier{Name: "x"}, Operator: GreaterThan, Right: &Literal{Value: 1, LiteralType: Integer}, DataType: Boolean},
+					ThenBranch: []Statement{&OutputStatement{Value: &Literal{Value: "big", LiteralType: String}}},
+				},
+				&ForLoop{Counter: counter, Start: &Literal{Value: 0, LiteralType: Integer}, End: &Literal{Value: 3, LiteralType: Integer},
+					Body: []Statement{&OutputStatement{Value: counter}}},
+				&WhileLoop{Condition: &UnaryOp{Operator: Not, Operand: &Literal{Value: true, LiteralType: Boolean}, DataType: Boolean}},
+				&Block{Body: []Statement{&OutputStatement{Value: &Literal{Value: "in", LiteralType: String}}}},
+				&ParallelBlock{Branches: [][]Statement{{&ExpressionStatement{Expression: &ArrayLiteral{Elements: []Expression{&Literal{Value: 2, LiteralType: Integer}}}}}}},
+				&OutputStatement{Value: &MapLiteral{Pairs: [][2]Expression{{&Literal{Value: "k", LiteralType: String}, &Literal{Value: 1, LiteralType: Integer}}}}},
+				&Assertion{Left: &Identifier{Name: "x"}, Right: &Literal{Value: 1.5, LiteralType: Float}, X: 200, Y: 150},
+				&Assignment{Target: &Identifier{Name: "q"}, Value: &Quoted{Program: &Program{
+					HasOuterCircle: true,
+					MainEntry:      &FunctionDef{IsMain: true, Body: []Statement{&OutputStatement{Value: &Literal{Value: "quoted", LiteralType: String}}}},
+				}}},
+			},
+		},
+		Functions: []*FunctionDef{{
+			Name:       "double",
+			Parameters: []*Parameter{{Name: "n", DataType: Integer, DefaultValue: &Literal{Value: 0, LiteralType: Integer}}},
+			Body: []Statement{&ReturnStatement{Value: &FunctionCall{
+				Function:  &Identifier{Name: "add"},
+				Arguments: []Expression{&Identifier{Name: "n"}, &Identifier{Name: "n"}},
+				DataType:  Integer,
+			}}},
+			ReturnType: Integer,
+		}},
+	}
+
+	data, err := EncodeJSON(program)
+	require.NoError(t, err)
+
+	decoded, err := DecodeJSON(data)
+	require.NoError(t, err)
+	assert.Equal(t, program, decoded)
+
+	again, err := EncodeJSON(decoded)
+	require.NoError(t, err)
+	assert.Equal(t, string(data), string(again))
+}
+
+func TestDecodeJSON_Errors(t *testing.T) {
+	_, err := DecodeJSON([]byte(`{"node": "Teleport"}`))
+	assert.ErrorContains(t, err, "Teleport")
+
+	_, err = DecodeJSON([]byte(`{"node": "OutputStatement", "value": {"node": "ReturnStatement"}}`))
+	assert.ErrorContains(t, err, "expected an expression")
+
+	_, err = DecodeJSON([]byte(`not json`))
+	assert.Error(t, err)
+}
+
+// TestJSONSchemaSnapshot guards the version 1 AST schema. If it fails,
+// either restore compatibility or increase ASTSchemaVersion and add a new
+// snapshot, keeping the old one decodable.
+func TestJSONSchemaSnapshot(t *testing.T) {
+	snapshot, err := os.ReadFile(filepath.Join("testdata", "ast.v1.json"))
+	require.NoError(t, err)
+
+	program := &Program{
+		HasOuterCircle: true,
+		MainEntry: &FunctionDef{
+			IsMain: true,
+			Body: []Statement{
+				&OutputStatement{
+					Value: &BinaryOp{
+						Left:     &Literal{Value: 1, LiteralType: Integer},
+						Operator: Add,
+						Right:    &Literal{Value: 2, LiteralType: Integer},
+						DataType: Integer,
+					},
+				},
+			},
+		},
+	}
+
+	data, err := EncodeJSON(program)
+	require.NoError(t, err)
+	assert.JSONEq(t, string(snapshot), string(data))
+
+	decoded, err := DecodeJSON(snapshot)
+	require.NoError(t, err)
+	assert.Equal(t, program, decoded)
+}
+
+func TestDecodeJSON_SchemaVersions(t *testing.T) {
+	// Written before the schema was versioned
+	node, err := DecodeJSON([]byte(`{"node": "Program", "hasOuterCircle": true}`))
+	require.NoError(t, err)
+	assert.Equal(t, &Program{HasOuterCircle: true}, node)
+
+	_, err = DecodeJSON([]byte(`{"node": "Program", "schemaVersion": 2}`))
+	assert.ErrorContains(t, err, "schema version 2")
+}
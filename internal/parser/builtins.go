@@ -0,0 +1,131 @@
+package parser
+
+import (
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+)
+
+// An eight-pointed star calls a built-in function, chosen by the pattern
+// drawn inside it. Lines call env, which reads the environment variable
+// named by the argument of the star, and triple lines call read_file, which
+// reads the file it names; a cross or a half circle calls has_capability to
+// ask whether the program may read the environment or use the file system.
+// An empty star fed by a quote calls eval, running the quoted program. Dots
+// call a library linked with the program instead (see WithLibraries). Call
+// circles keep printing whatever is drawn inside them. A built-in call below
+// the main entry runs as a statement of its own.
+
+// Built-in functions an eight-pointed star can call
+const (
+	builtinEnv           = "env"
+	builtinReadFile      = "read_file"
+	builtinHasCapability = "has_capability"
+	builtinEval          = "eval"
+)
+
+// capabilityPatterns maps the pattern of a has_capability star to the
+// capability it asks for
+var capabilityPatterns = map[string]string{
+	detector.PatternCross:      "env",
+	detector.PatternHalfCircle: "fs",
+}
+
+// isBuiltinCall reports whether node is a star calling a built-in function
+func isBuiltinCall(node *symbolNode) bool {
+	return node.symbol.Type == detector.EightPointedStar
+}
+
+// parseBuiltinStatement parses a built-in call reached as a statement
+func (p *Parser) parseBuiltinStatement(node *symbolNode) Statement {
+	if node.visited {
+		return nil
+	}
+	call := p.parseBuiltinCall(node)
+	if call == nil {
+		return nil
+	}
+	return &ExpressionStatement{Expression: call}
+}
+
+// parseBuiltinCall parses the call an eight-pointed star makes with the
+// arguments drawn into it. A star reached as an expression is visited on
+// the way here, and still needs its arguments.
+func (p *Parser) parseBuiltinCall(node *symbolNode) Expression {
+	if call, ok := node.astNode.(*FunctionCall); ok {
+		return call
+	}
+	node.visited = true
+	p.recordRole(node, RoleFunctionCall)
+
+	arguments := []Expression{}
+	for _, parent := range p.getParents(node) {
+		if !parent.visited {
+			if arg := p.parseExpression(parent); arg != nil {
+				arguments = append(arguments, arg)
+			}
+		}
+	}
+
+	symbol := node.symbol
+	if call, ok := p.libraryCall(symbol.Pattern); ok {
+		if call == nil {
+			p.errors = append(p.errors, grimoireErrors.NewError(grimoireErrors.SyntaxError,
+				i18n.Tf("parser.unknown_library", symbol.Pattern, symbol.Position.X, symbol.Position.Y, len(p.libraries))).
+				WithSuggestion(i18n.T("parser.unknown_library_suggestion")).
+				WithRegion(symbol.Position.X, symbol.Position.Y, symbol.Size))
+			return nil
+		}
+		p.attachNode(node, call)
+		return call
+	}
+	call := builtinCall(symbol.Pattern, arguments)
+	if call == nil {
+		p.errors = append(p.errors, grimoireErrors.NewError(grimoireErrors.SyntaxError,
+			i18n.Tf("parser.unknown_builtin", symbol.Pattern, symbol.Position.X, symbol.Position.Y)).
+			WithSuggestion(i18n.T("parser.unknown_builtin_suggestion")).
+			WithRegion(symbol.Position.X, symbol.Position.Y, symbol.Size))
+		return nil
+	}
+	p.attachNode(node, call)
+	return call
+}
+
+// builtinCall returns the call a star with pattern makes with the arguments
+// drawn into it, or nil when the pattern names no built-in function
+func builtinCall(pattern string, arguments []Expression) *FunctionCall {
+	if capability, ok := capabilityPatterns[pattern]; ok {
+		return &FunctionCall{
+			Function:  &Identifier{Name: builtinHasCapability},
+			Arguments: []Expression{&Literal{Value: capability, LiteralType: String}},
+			DataType:  Boolean,
+		}
+	}
+	switch pattern {
+	case detector.PatternLines:
+		return &FunctionCall{
+			Function:  &Identifier{Name: builtinEnv},
+			Arguments: arguments,
+			DataType:  String,
+		}
+	case detector.PatternTripleLine:
+		return &FunctionCall{
+			Function:  &Identifier{Name: builtinReadFile},
+			Arguments: arguments,
+			DataType:  String,
+		}
+	case detector.PatternEmpty, "":
+		if len(arguments) != 1 {
+			return nil
+		}
+		if _, ok := arguments[0].(*Quoted); !ok {
+			return nil
+		}
+		return &FunctionCall{
+			Function:  &Identifier{Name: builtinEval},
+			Arguments: arguments,
+			DataType:  Void,
+		}
+	}
+	return nil
+}
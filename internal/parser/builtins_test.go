@@ -0,0 +1,153 @@
+package parser
+
+import (
+	"math"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseBuiltinCalls(t *testing.T) {
+	at := func(symbolType detector.SymbolType, x, y float64, pattern string) *detector.Symbol {
+		return &detector.Symbol{Type: symbolType, Position: detector.Position{X: x, Y: y}, Size: 20, Pattern: pattern}
+	}
+	entry := at(detector.DoubleCircle, 200, 100, detector.PatternDot)
+	name := at(detector.Square, 120, 200, detector.PatternLines)
+	env := at(detector.EightPointedStar, 200, 200, detector.PatternLines)
+	star := at(detector.Star, 200, 300, detector.PatternEmpty)
+	canReadFiles := at(detector.EightPointedStar, 300, 200, detector.PatternHalfCircle)
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180 * math.Sqrt(math.Pi)},
+		entry, name, env, star, canReadFiles,
+	}
+	connections := []detector.Connection{
+		{From: entry, To: star, ConnectionType: detector.ConnectionTypeSolid},
+		{From: entry, To: canReadFiles, ConnectionType: detector.ConnectionTypeSolid},
+		{From: name, To: env, ConnectionType: detector.ConnectionTypeSolid},
+		{From: env, To: star, ConnectionType: detector.ConnectionTypeSolid},
+	}
+
+	p := NewParser()
+	program, err := p.Parse(symbols, connections)
+	require.NoError(t, err)
+	require.NotNil(t, program.MainEntry)
+	require.Len(t, program.MainEntry.Body, 2)
+	assert.Empty(t, program.Functions, "built-in calls are not functions")
+
+	// Lines in a star read the environment variable its argument names
+	output, ok := program.MainEntry.Body[0].(*OutputStatement)
+	require.True(t, ok, "statement %T", program.MainEntry.Body[0])
+	call, ok := output.Value.(*FunctionCall)
+	require.True(t, ok, "output of %T", output.Value)
+	assert.Equal(t, builtinEnv, call.Function.Name)
+	assert.Equal(t, String, call.DataType)
+	require.Len(t, call.Arguments, 1)
+	assert.Equal(t, "Text", call.Arguments[0].(*Literal).Value)
+
+	// A half circle asks for the fs capability, as a statement of its own
+	statement, ok := program.MainEntry.Body[1].(*ExpressionStatement)
+	require.True(t, ok, "statement %T", program.MainEntry.Body[1])
+	call, ok = statement.Expression.(*FunctionCall)
+	require.True(t, ok)
+	assert.Equal(t, builtinHasCapability, call.Function.Name)
+	require.Len(t, call.Arguments, 1)
+	assert.Equal(t, "fs", call.Arguments[0].(*Literal).Value)
+
+	roles := p.Roles()
+	assert.Equal(t, RoleFunctionCall, roles[3].Kind)
+	assert.Equal(t, RoleFunctionCall, roles[5].Kind)
+}
+
+func TestBuiltinCall(t *testing.T) {
+	args := []Expression{&Literal{Value: "HOME", LiteralType: String}}
+	tests := []struct {
+		pattern  string
+		function string
+		args     []Expression
+	}{
+		{detector.PatternLines, builtinEnv, args},
+		{detector.PatternTripleLine, builtinReadFile, args},
+		{detector.PatternCross, builtinHasCapability, []Expression{&Literal{Value: "env", LiteralType: String}}},
+		{detector.PatternHalfCircle, builtinHasCapability, []Expression{&Literal{Value: "fs", LiteralType: String}}},
+	}
+	for _, tt := range tests {
+		t.Run(tt.pattern, func(t *testing.T) {
+			call := builtinCall(tt.pattern, args)
+			require.NotNil(t, call)
+			assert.Equal(t, tt.function, call.Function.Name)
+			assert.Equal(t, tt.args, call.Arguments)
+		})
+	}
+
+	// An empty star only evaluates a quote, and other patterns call nothing
+	assert.Nil(t, builtinCall(detector.PatternEmpty, args))
+	assert.Nil(t, builtinCall(detector.PatternTripleDot, args))
+}
+
+func TestParseUnknownBuiltin(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	entry := &detector.Symbol{Type: detector.DoubleCircle, Position: detector.Position{X: 200, Y: 100}, Size: 20}
+	star := &detector.Symbol{Type: detector.EightPointedStar, Position: detector.Position{X: 200, Y: 200}, Size: 20, Pattern: detector.PatternEmpty}
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180 * math.Sqrt(math.Pi)},
+		entry, star,
+	}
+	connections := []detector.Connection{{From: entry, To: star, ConnectionType: detector.ConnectionTypeSolid}}
+
+	_, err := NewParser().Parse(symbols, connections)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "No built-in function is called by an eight-pointed star with a empty pattern")
+}
+
+// TestExamplesCallNoBuiltins guards the meaning of the bundled examples:
+// the patterns drawn inside their circles do not change what they parse
+// to, and none of them calls a built-in function
+func TestExamplesCallNoBuiltins(t *testing.T) {
+	images, err := filepath.Glob(filepath.Join("..", "..", "examples", "images", "*.png"))
+	require.NoError(t, err)
+	if len(images) == 0 {
+		t.Skip("Example images not found")
+	}
+
+	for _, imagePath := range images {
+		t.Run(filepath.Base(imagePath), func(t *testing.T) {
+			symbols, connections, err := detector.DetectSymbols(imagePath)
+			require.NoError(t, err)
+			program, err := Parse(symbols, connections)
+			require.NoError(t, err)
+			data, err := EncodeJSON(program)
+			require.NoError(t, err)
+			for _, name := range []string{builtinEnv, builtinReadFile, builtinHasCapability, builtinEval} {
+				assert.NotContains(t, string(data), `"`+name+`"`)
+			}
+
+			// Clearing the patterns inside circles leaves the program as it was
+			cleared := make(map[*detector.Symbol]*detector.Symbol, len(symbols))
+			clearedSymbols := make([]*detector.Symbol, len(symbols))
+			for i, symbol := range symbols {
+				copied := *symbol
+				if copied.Type == detector.Circle {
+					copied.Pattern = detector.PatternEmpty
+				}
+				cleared[symbol] = &copied
+				clearedSymbols[i] = &copied
+			}
+			clearedConnections := make([]detector.Connection, len(connections))
+			for i, c := range connections {
+				c.From, c.To = cleared[c.From], cleared[c.To]
+				clearedConnections[i] = c
+			}
+			clearedProgram, err := Parse(clearedSymbols, clearedConnections)
+			require.NoError(t, err)
+			clearedData, err := EncodeJSON(clearedProgram)
+			require.NoError(t, err)
+			assert.JSONEq(t, string(data), string(clearedData))
+		})
+	}
+}
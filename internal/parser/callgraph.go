@@ -0,0 +1,210 @@
+package parser
+
+import (
+	"fmt"
+	"sort"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// isCall reports whether node, reached from a statement of a function, is
+// a function circle the function calls
+func (p *Parser) isCall(node *symbolNode) bool {
+	return node.symbol.Type == detector.Circle && p.function != nil && p.function.Kind == RoleFunction
+}
+
+// parseCall parses a line from a function to a function circle, which
+// calls that function. The called function is parsed first if it has not
+// been yet; a function calling itself, directly or through others, gets
+// the definition still being parsed, so recursion ends the parse.
+func (p *Parser) parseCall(node *symbolNode) Statement {
+	// The called function is not part of the calling statement
+	statements, function := p.statements, p.function
+	p.statements, p.function = nil, nil
+	fn := p.parseFunctionDef(node, false)
+	p.statements, p.function = statements, function
+	if fn == nil {
+		return nil
+	}
+
+	// Calls need a name to refer to the function by
+	if fn.Name == "" {
+		fn.Name = fmt.Sprintf("function_%d", p.roles[node.symbol].Ordinal)
+	}
+	return &ExpressionStatement{Expression: &FunctionCall{
+		Function:  &Identifier{Name: fn.Name},
+		Arguments: []Expression{},
+		DataType:  fn.ReturnType,
+	}}
+}
+
+// CallGraph maps the name of each function of a program to the names of
+// the functions it calls, in the order of their first call
+type CallGraph map[string][]string
+
+// BuildCallGraph returns the calls between the named functions of program.
+// Calls to functions the program does not define, such as print, are left out.
+func BuildCallGraph(program *Program) CallGraph {
+	graph := make(CallGraph)
+	if program == nil {
+		return graph
+	}
+	for _, fn := range program.Functions {
+		if fn.Name != "" {
+			graph[fn.Name] = nil
+		}
+	}
+
+	for _, fn := range program.Functions {
+		if fn.Name == "" {
+			continue
+		}
+		called := make(map[string]bool)
+		walkCalls(fn.Body, func(call *FunctionCall) {
+			if call.Function == nil {
+				return
+			}
+			name := call.Function.Name
+			if _, defined := graph[name]; defined && !called[name] {
+				called[name] = true
+				graph[fn.Name] = append(graph[fn.Name], name)
+			}
+		})
+	}
+	return graph
+}
+
+// Cycles returns the groups of functions that call each other, directly or
+// through other functions, including functions that call themselves. Each
+// group is sorted by name, and groups by their first name.
+func (g CallGraph) Cycles() [][]string {
+	names := make([]string, 0, len(g))
+	for name := range g {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+
+	// Tarjan's strongly connected components
+	index := make(map[string]int)
+	lowlink := make(map[string]int)
+	onStack := make(map[string]bool)
+	var stack []string
+	var cycles [][]string
+
+	var visit func(name string)
+	visit = func(name string) {
+		index[name] = len(index)
+		lowlink[name] = index[name]
+		stack = append(stack, name)
+		onStack[name] = true
+
+		selfCall := false
+		for _, callee := range g[name] {
+			if callee == name {
+				selfCall = true
+			}
+			if _, seen := index[callee]; !seen {
+				visit(callee)
+				lowlink[name] = min(lowlink[name], lowlink[callee])
+			} else if onStack[callee] {
+				lowlink[name] = min(lowlink[name], index[callee])
+			}
+		}
+
+		if lowlink[name] != index[name] {
+			return
+		}
+		var component []string
+		for {
+			top := stack[len(stack)-1]
+			stack = stack[:len(stack)-1]
+			onStack[top] = false
+			component = append(component, top)
+			if top == name {
+				break
+			}
+		}
+		if len(component) > 1 || selfCall {
+			sort.Strings(component)
+			cycles = append(cycles, component)
+		}
+	}
+	for _, name := range names {
+		if _, seen := index[name]; !seen {
+			visit(name)
+		}
+	}
+
+	sort.Slice(cycles, func(i, j int) bool { return cycles[i][0] < cycles[j][0] })
+	return cycles
+}
+
+// walkCalls calls visit for every function call in stmts, including calls
+// nested in other statements and expressions
+func walkCalls(stmts []Statement, visit func(*FunctionCall)) {
+	var expression func(expr Expression)
+	expression = func(expr Expression) {
+		switch e := expr.(type) {
+		case *FunctionCall:
+			visit(e)
+			for _, arg := range e.Arguments {
+				expression(arg)
+			}
+		case *BinaryOp:
+			expression(e.Left)
+			expression(e.Right)
+		case *UnaryOp:
+			expression(e.Operand)
+		case *ArrayLiteral:
+			for _, elem := range e.Elements {
+				expression(elem)
+			}
+		case *MapLiteral:
+			for _, pair := range e.Pairs {
+				expression(pair[0])
+				expression(pair[1])
+			}
+		}
+	}
+
+	for _, stmt := range stmts {
+		switch s := stmt.(type) {
+		case *OutputStatement:
+			expression(s.Value)
+		case *Assignment:
+			expression(s.Value)
+		case *IfStatement:
+			expression(s.Condition)
+			walkCalls(s.ThenBranch, visit)
+			walkCalls(s.ElseBranch, visit)
+		case *ForLoop:
+			expression(s.Start)
+			expression(s.End)
+			expression(s.Step)
+			walkCalls(s.Body, visit)
+		case *WhileLoop:
+			expression(s.Condition)
+			walkCalls(s.Body, visit)
+		case *ParallelBlock:
+			for _, branch := range s.Branches {
+				walkCalls(branch, visit)
+			}
+		case *Block:
+			walkCalls(s.Body, visit)
+		case *Stage:
+			walkCalls(s.Body, visit)
+		case *Mirror:
+			for _, operand := range s.Operands {
+				expression(operand)
+			}
+			walkCalls(s.Body, visit)
+		case *Assertion:
+			expression(s.Left)
+			expression(s.Right)
+		case *ReturnStatement:
+			expression(s.Value)
+		case *ExpressionStatement:
+			expression(s.Expression)
+		}
+	}
+}
@@ -0,0 +1,106 @@
+package parser
+
+import (
+	"math"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// callTo returns a statement calling the named function
+func callTo(name string) Statement {
+	return &ExpressionStatement{Expression: &FunctionCall{
+		Function:  &Identifier{Name: name},
+		Arguments: []Expression{},
+		DataType:  Void,
+	}}
+}
+
+func TestBuildCallGraph(t *testing.T) {
+	program := &Program{Functions: []*FunctionDef{
+		{Name: "a", Body: []Statement{callTo("b"), callTo("print"), callTo("b")}},
+		{Name: "b", Body: []Statement{&IfStatement{
+			Condition:  &Literal{Value: true, LiteralType: Boolean},
+			ThenBranch: []Statement{&OutputStatement{Value: &FunctionCall{Function: &Identifier{Name: "c"}}}},
+		}}},
+		{Name: "c"},
+		{Name: "", Body: []Statement{callTo("a")}},
+	}}
+
+	graph := BuildCallGraph(program)
+	assert.Equal(t, CallGraph{"a": {"b"}, "b": {"c"}, "c": nil}, graph)
+	assert.Empty(t, graph.Cycles())
+	assert.Empty(t, BuildCallGraph(nil))
+}
+
+func TestCallGraphCycles(t *testing.T) {
+	graph := CallGraph{
+		"fact":  {"fact"},
+		"even":  {"odd"},
+		"odd":   {"even", "print"},
+		"main":  {"fact", "even"},
+		"print": nil,
+		"x":     {"y"},
+		"y":     {"z"},
+		"z":     {"x"},
+	}
+	assert.Equal(t, [][]string{{"even", "odd"}, {"fact"}, {"x", "y", "z"}}, graph.Cycles())
+}
+
+// recursive returns a function circle whose if-branch draws a line back
+// to the circle, calling the function again
+func recursive() ([]*detector.Symbol, []detector.Connection) {
+	circle := &detector.Symbol{Type: detector.Circle, Position: detector.Position{X: 150, Y: 100}}
+	triangle := &detector.Symbol{Type: detector.Triangle, Position: detector.Position{X: 200, Y: 200}}
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180 * math.Sqrt(math.Pi)},
+		circle, triangle,
+	}
+	connections := []detector.Connection{
+		{From: circle, To: triangle, ConnectionType: detector.ConnectionTypeSolid},
+		{From: triangle, To: circle, ConnectionType: detector.ConnectionTypeSolid},
+	}
+	return symbols, connections
+}
+
+func TestParseRecursiveFunction(t *testing.T) {
+	program, err := Parse(recursive())
+	require.NoError(t, err)
+	require.Len(t, program.Functions, 1)
+
+	fn := program.Functions[0]
+	assert.Equal(t, "function_1", fn.Name, "a function that is called is named")
+	require.Len(t, fn.Body, 1)
+	branch, ok := fn.Body[0].(*IfStatement)
+	require.True(t, ok)
+	assert.Equal(t, []Statement{callTo("function_1")}, branch.ThenBranch)
+	assert.Equal(t, [][]string{{"function_1"}}, BuildCallGraph(program).Cycles())
+}
+
+func TestParseMutuallyRecursiveFunctions(t *testing.T) {
+	first := &detector.Symbol{Type: detector.Circle, Position: detector.Position{X: 120, Y: 150}}
+	second := &detector.Symbol{Type: detector.Circle, Position: detector.Position{X: 280, Y: 150}, Pattern: detector.PatternDot}
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180 * math.Sqrt(math.Pi)},
+		first, second,
+	}
+	connections := []detector.Connection{
+		{From: first, To: second, ConnectionType: detector.ConnectionTypeSolid},
+		{From: second, To: first, ConnectionType: detector.ConnectionTypeSolid},
+	}
+
+	p := NewParser()
+	program, err := p.Parse(symbols, connections)
+	require.NoError(t, err)
+	require.Len(t, program.Functions, 2, "a function parsed for a call is still defined once")
+	assert.Equal(t, "function_1", program.Functions[0].Name)
+	assert.Equal(t, "function_2", program.Functions[1].Name)
+	assert.Equal(t, []Statement{callTo("function_2")}, program.Functions[0].Body)
+	assert.Equal(t, []Statement{callTo("function_1")}, program.Functions[1].Body)
+	assert.Equal(t, [][]string{{"function_1", "function_2"}}, BuildCallGraph(program).Cycles())
+
+	roles := p.Roles()
+	assert.Nil(t, roles[2].Function, "a called function is not part of its caller")
+}
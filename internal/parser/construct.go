@@ -0,0 +1,151 @@
+package parser
+
+import (
+	"sort"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// unbounded is the MaxIn or MaxOut of a construct that takes any number of
+// connections
+const unbounded = -1
+
+// maxSuggestions is how many constructs a ConstructIssue suggests at most
+const maxSuggestions = 3
+
+// Construct is a statement or expression the parser builds from one symbol,
+// with the connections it takes
+type Construct struct {
+	// Name identifies the construct, such as "if" or "loop"
+	Name string
+	// Type is the shape the construct is drawn with
+	Type detector.SymbolType
+	// MinIn and MaxIn bound the incoming connections, MinOut and MaxOut the
+	// outgoing ones. A maximum of unbounded takes any number.
+	MinIn, MaxIn   int
+	MinOut, MaxOut int
+}
+
+// constructs are the constructs whose connections are checked. Symbols of
+// other types, such as function circles, take any connections.
+var constructs = []Construct{
+	{Name: "if", Type: detector.Triangle, MinIn: 0, MaxIn: 1, MinOut: 1, MaxOut: 2},
+	{Name: "loop", Type: detector.Pentagon, MinIn: 0, MaxIn: 1, MinOut: 1, MaxOut: unbounded},
+	{Name: "parallel", Type: detector.Hexagon, MinIn: 0, MaxIn: 1, MinOut: 2, MaxOut: unbounded},
+	{Name: "output", Type: detector.Star, MinIn: 0, MaxIn: 1, MinOut: 0, MaxOut: 1},
+	{Name: "add", Type: detector.Convergence, MinIn: 2, MaxIn: 2, MinOut: 0, MaxOut: unbounded},
+	{Name: "subtract", Type: detector.Divergence, MinIn: 2, MaxIn: 2, MinOut: 0, MaxOut: unbounded},
+	{Name: "multiply", Type: detector.Amplification, MinIn: 2, MaxIn: 2, MinOut: 0, MaxOut: unbounded},
+	{Name: "divide", Type: detector.Distribution, MinIn: 2, MaxIn: 2, MinOut: 0, MaxOut: unbounded},
+}
+
+// Suggestion is a valid construct near what was drawn, with the nearest
+// connection counts it takes
+type Suggestion struct {
+	Construct Construct
+	In, Out   int
+	// Edits is how many changes turn the drawing into the construct: one
+	// for each connection added or removed, and one for redrawing the
+	// shape, or two when a statement shape becomes an operator or the
+	// other way round
+	Edits int
+}
+
+// ConstructIssue is a symbol whose connections no construct of its shape
+// takes, with the nearest valid constructs, best first
+type ConstructIssue struct {
+	Symbol      *detector.Symbol
+	In, Out     int
+	Suggestions []Suggestion
+}
+
+// CheckConstructs reports the symbols whose connections no construct of
+// their shape takes, such as a triangle with three outgoing connections, and
+// suggests the constructs the fewest edits away. A suggestion that keeps the
+// connections as drawn ranks before one that changes them.
+func CheckConstructs(symbols []*detector.Symbol, connections []detector.Connection) []ConstructIssue {
+	in := make(map[*detector.Symbol]int)
+	out := make(map[*detector.Symbol]int)
+	for _, conn := range connections {
+		if conn.From == conn.To {
+			continue
+		}
+		out[conn.From]++
+		in[conn.To]++
+	}
+
+	var issues []ConstructIssue
+	for _, symbol := range symbols {
+		if symbol == nil || symbol.OnRing || symbol.StageRing || symbol.ListFrame {
+			continue
+		}
+		checked, valid := false, false
+		for _, c := range constructs {
+			if c.Type == symbol.Type {
+				checked = true
+				valid = valid || c.takes(in[symbol], out[symbol])
+			}
+		}
+		if !checked || valid {
+			continue
+		}
+		issues = append(issues, ConstructIssue{
+			Symbol:      symbol,
+			In:          in[symbol],
+			Out:         out[symbol],
+			Suggestions: suggestConstructs(symbol.Type, in[symbol], out[symbol]),
+		})
+	}
+	return issues
+}
+
+// takes reports whether the construct takes in incoming and out outgoing
+// connections
+func (c Construct) takes(in, out int) bool {
+	return in == clampCount(in, c.MinIn, c.MaxIn) && out == clampCount(out, c.MinOut, c.MaxOut)
+}
+
+// suggestConstructs returns the constructs the fewest edits away from a
+// symbol of symbolType with in incoming and out outgoing connections
+func suggestConstructs(symbolType detector.SymbolType, in, out int) []Suggestion {
+	suggestions := make([]Suggestion, 0, len(constructs))
+	for _, c := range constructs {
+		s := Suggestion{Construct: c, In: clampCount(in, c.MinIn, c.MaxIn), Out: clampCount(out, c.MinOut, c.MaxOut)}
+		s.Edits = max(s.In-in, in-s.In) + max(s.Out-out, out-s.Out)
+		switch {
+		case c.Type == symbolType:
+		case isOperator(c.Type) == isOperator(symbolType):
+			s.Edits++
+		default:
+			// An operator drawn for a statement, or the other way round,
+			// is further off than one shape for another
+			s.Edits += 2
+		}
+		suggestions = append(suggestions, s)
+	}
+	keepsConnections := func(s Suggestion) bool { return s.In == in && s.Out == out }
+	sort.SliceStable(suggestions, func(i, j int) bool {
+		if suggestions[i].Edits != suggestions[j].Edits {
+			return suggestions[i].Edits < suggestions[j].Edits
+		}
+		return keepsConnections(suggestions[i]) && !keepsConnections(suggestions[j])
+	})
+
+	// Only the nearest are suggested
+	n := 1
+	for n < len(suggestions) && n < maxSuggestions && suggestions[n].Edits == suggestions[0].Edits {
+		n++
+	}
+	return suggestions[:n]
+}
+
+// clampCount returns the count nearest n from lo to hi
+func clampCount(n, lo, hi int) int {
+	if n < lo {
+		return lo
+	}
+	if hi != unbounded && n > hi {
+		return hi
+	}
+	return n
+}
@@ -0,0 +1,68 @@
+package parser
+
+import (
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// fanOut returns a symbol of symbolType with an incoming connection and
+// outs outgoing ones
+func fanOut(symbolType detector.SymbolType, outs int) ([]*detector.Symbol, []detector.Connection) {
+	symbol := &detector.Symbol{Type: symbolType}
+	condition := &detector.Symbol{Type: detector.Equal}
+	symbols := []*detector.Symbol{condition, symbol}
+	connections := []detector.Connection{{From: condition, To: symbol}}
+	for i := 0; i < outs; i++ {
+		child := &detector.Symbol{Type: detector.Star}
+		symbols = append(symbols, child)
+		connections = append(connections, detector.Connection{From: symbol, To: child})
+	}
+	return symbols, connections
+}
+
+func TestCheckConstructs(t *testing.T) {
+	symbols, connections := fanOut(detector.Triangle, 2)
+	assert.Empty(t, CheckConstructs(symbols, connections), "a triangle takes a then and an else branch")
+
+	// A triangle with three outgoing connections
+	symbols, connections = fanOut(detector.Triangle, 3)
+	issues := CheckConstructs(symbols, connections)
+	require.Len(t, issues, 1)
+	issue := issues[0]
+	assert.Equal(t, symbols[1], issue.Symbol)
+	assert.Equal(t, 1, issue.In)
+	assert.Equal(t, 3, issue.Out)
+
+	// Redrawing the shape keeps the connections, so ranks before removing one
+	var names []string
+	for _, s := range issue.Suggestions {
+		assert.Equal(t, 1, s.Edits)
+		names = append(names, s.Construct.Name)
+	}
+	assert.Equal(t, []string{"loop", "parallel", "if"}, names)
+	assert.Equal(t, 2, issue.Suggestions[2].Out)
+}
+
+func TestCheckConstructsOperands(t *testing.T) {
+	one := &detector.Symbol{Type: detector.Square}
+	plus := &detector.Symbol{Type: detector.Convergence}
+	star := &detector.Symbol{Type: detector.Star}
+	issues := CheckConstructs([]*detector.Symbol{one, plus, star}, []detector.Connection{
+		{From: one, To: plus},
+		{From: plus, To: star},
+	})
+	require.Len(t, issues, 1)
+	require.NotEmpty(t, issues[0].Suggestions)
+	nearest := issues[0].Suggestions[0]
+	assert.Equal(t, "add", nearest.Construct.Name)
+	assert.Equal(t, 2, nearest.In)
+	assert.Equal(t, 1, nearest.Edits)
+}
+
+func TestCheckConstructsSkipsFreeShapes(t *testing.T) {
+	symbols, connections := fanOut(detector.Circle, 5)
+	assert.Empty(t, CheckConstructs(symbols, connections))
+}
@@ -0,0 +1,56 @@
+package parser
+
+import (
+	"sort"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// A hexagon drawn around pairs of symbols, each key joined to its value by
+// a short line, is a map of them rather than a parallel block. Pairs are
+// read from top to bottom.
+
+// enclosedBy reports whether symbol lies inside the outline of hexagon
+func enclosedBy(hexagon, symbol *detector.Symbol) bool {
+	return symbol != hexagon && distance(hexagon.Position, symbol.Position) < hexagon.Radius()
+}
+
+// mapEntries returns the key and value of each pair enclosed by node, from
+// top to bottom, or nil when node encloses no pairs
+func (p *Parser) mapEntries(node *symbolNode) [][2]*symbolNode {
+	var entries [][2]*symbolNode
+	for i, symbol := range p.symbols {
+		if symbol.Type == detector.OuterCircle || !enclosedBy(node.symbol, symbol) {
+			continue
+		}
+		key := p.symbolGraph[i]
+		for _, value := range key.children {
+			if enclosedBy(node.symbol, value.symbol) {
+				entries = append(entries, [2]*symbolNode{key, value})
+			}
+		}
+	}
+	sort.SliceStable(entries, func(i, j int) bool {
+		a, b := entries[i][0].symbol.Position, entries[j][0].symbol.Position
+		if a.Y != b.Y {
+			return a.Y < b.Y
+		}
+		return a.X < b.X
+	})
+	return entries
+}
+
+// parseMap parses the map of the pairs enclosed by node (a hexagon)
+func (p *Parser) parseMap(node *symbolNode, entries [][2]*symbolNode) *MapLiteral {
+	p.recordRole(node, RoleMap)
+
+	m := &MapLiteral{Pairs: [][2]Expression{}}
+	for _, entry := range entries {
+		key, value := p.parseExpression(entry[0]), p.parseExpression(entry[1])
+		if key != nil && value != nil {
+			m.Pairs = append(m.Pairs, [2]Expression{key, value})
+		}
+	}
+	p.attachNode(node, m)
+	return m
+}
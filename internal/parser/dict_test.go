@@ -0,0 +1,68 @@
+package parser
+
+import (
+	"math"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseMap(t *testing.T) {
+	hexagon := &detector.Symbol{Type: detector.Hexagon, Position: detector.Position{X: 200, Y: 150}, Size: 60 * math.Sqrt(math.Pi)}
+	key1 := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 170, Y: 130}, Pattern: detector.PatternDot}
+	value1 := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 225, Y: 130}, Pattern: detector.PatternCross}
+	key2 := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 170, Y: 170}, Pattern: detector.PatternDoubleDot}
+	value2 := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 225, Y: 170}, Pattern: detector.PatternHalfCircle}
+	star := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 200, Y: 280}}
+
+	// The lower pair is detected first
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180 * math.Sqrt(math.Pi)},
+		hexagon, key2, value2, key1, value1, star,
+	}
+	connections := []detector.Connection{
+		{From: key2, To: value2, ConnectionType: detector.ConnectionTypeSolid},
+		{From: key1, To: value1, ConnectionType: detector.ConnectionTypeSolid},
+		{From: hexagon, To: star, ConnectionType: detector.ConnectionTypeSolid},
+	}
+
+	p := NewParser()
+	program, err := p.Parse(symbols, connections)
+	require.NoError(t, err)
+	require.Len(t, program.MainEntry.Body, 1)
+	output, ok := program.MainEntry.Body[0].(*OutputStatement)
+	require.True(t, ok)
+	m, ok := output.Value.(*MapLiteral)
+	require.True(t, ok, "output of %T", output.Value)
+
+	require.Len(t, m.Pairs, 2)
+	assert.Equal(t, 1, m.Pairs[0][0].(*Literal).Value)
+	assert.Equal(t, true, m.Pairs[0][1].(*Literal).Value)
+	assert.Equal(t, 2, m.Pairs[1][0].(*Literal).Value)
+	assert.Equal(t, false, m.Pairs[1][1].(*Literal).Value)
+
+	roles := p.Roles()
+	assert.Equal(t, RoleMap, roles[1].Kind)
+	assert.Same(t, m, roles[1].Node)
+}
+
+func TestHexagonWithoutPairsIsParallel(t *testing.T) {
+	hexagon := &detector.Symbol{Type: detector.Hexagon, Position: detector.Position{X: 200, Y: 150}, Size: 30 * math.Sqrt(math.Pi)}
+	main := &detector.Symbol{Type: detector.DoubleCircle, Position: detector.Position{X: 200, Y: 60}}
+	star := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 240, Y: 250}}
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180 * math.Sqrt(math.Pi)},
+		main, hexagon, star,
+	}
+	connections := []detector.Connection{
+		{From: main, To: hexagon, ConnectionType: detector.ConnectionTypeSolid},
+		{From: hexagon, To: star, ConnectionType: detector.ConnectionTypeSolid},
+	}
+
+	program, err := Parse(symbols, connections)
+	require.NoError(t, err)
+	require.Len(t, program.MainEntry.Body, 1)
+	assert.IsType(t, &ParallelBlock{}, program.MainEntry.Body[0])
+}
@@ -0,0 +1,461 @@
+package parser
+
+import (
+	"bytes"
+	"image"
+	"math"
+	"regexp"
+	"strconv"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/ident"
+)
+
+// extractionGap is the room, in pixels, left between the extracted symbols,
+// the main entry feeding them and the outer circle around them
+const extractionGap = 40.0
+
+// Extraction is a run of statements lifted out of a program into a
+// function of their own
+type Extraction struct {
+	// Name is the name of the function, as Link names the main entry of a
+	// library
+	Name string
+	// Statements are the statements lifted out, in order
+	Statements []Statement
+	// Symbols and Connections draw the function as a library image: the
+	// symbols of the statements where they were, fed by a new main entry,
+	// inside a new outer circle
+	Symbols     []*detector.Symbol
+	Connections []detector.Connection
+}
+
+// Extract lifts the statements drawn inside region out of program, parsed
+// with roles from symbols joined by connections, into a function named
+// after libraryPath, and rewrites program in place to call the function
+// where the statements were. The statements must follow one another in one
+// block, with every symbol they are drawn with inside the region. They may
+// not read variables assigned outside the region, nor assign variables
+// read outside it, since the function shares no variables with its caller.
+func Extract(program *Program, roles []SymbolRole, connections []detector.Connection, region image.Rectangle, libraryPath string) (*Extraction, error) {
+	if program == nil {
+		return nil, grimoireErrors.NewError(grimoireErrors.SyntaxError, i18n.T("parser.extract_nil_program"))
+	}
+	inside := func(s *detector.Symbol) bool {
+		return image.Pt(int(math.Round(s.Position.X)), int(math.Round(s.Position.Y))).In(region)
+	}
+
+	// The statements drawn inside the region, and those not nested in
+	// another of them
+	selected := make(map[*detector.Symbol]Statement)
+	for _, role := range roles {
+		if stmt, ok := role.Node.(Statement); ok && inside(role.Symbol) {
+			selected[role.Symbol] = stmt
+		}
+	}
+	if len(selected) == 0 {
+		return nil, grimoireErrors.NewError(grimoireErrors.ValidationError, i18n.T("parser.extract_empty")).
+			WithSuggestion(i18n.T("parser.extract_empty_suggestion"))
+	}
+	outermost := func(role SymbolRole) *detector.Symbol {
+		var owner *detector.Symbol
+		if _, ok := selected[role.Symbol]; ok {
+			owner = role.Symbol
+		}
+		for c := role.Context; c != nil; c = c.Context {
+			if _, ok := selected[c.Symbol]; ok {
+				owner = c.Symbol
+			}
+		}
+		return owner
+	}
+
+	// Every symbol of the statements must be inside the region, and every
+	// symbol inside it part of the statements
+	heads := make(map[Statement]bool)
+	extracted := make(map[*detector.Symbol]bool)
+	var errs []error
+	for _, role := range roles {
+		owner := outermost(role)
+		switch {
+		case owner == role.Symbol:
+			heads[selected[owner]] = true
+		case owner == nil:
+			if inside(role.Symbol) && !isContainer(role.Kind) {
+				errs = append(errs, extractionError(role.Symbol,
+					"parser.extract_symbol_not_statement"))
+			}
+			continue
+		}
+		if !inside(role.Symbol) {
+			errs = append(errs, extractionError(role.Symbol,
+				"parser.extract_symbol_outside"))
+			continue
+		}
+		extracted[role.Symbol] = true
+	}
+	if len(errs) > 0 {
+		return nil, extractionErrors("The region does not enclose whole statements", errs)
+	}
+
+	body, from, to, err := locateRun(program, heads)
+	if err != nil {
+		return nil, err
+	}
+	name := ident.NewNamer().Name(libraryLabel(libraryPath))
+	statements := append([]Statement(nil), (*body)[from:to]...)
+	call := &ExpressionStatement{Expression: &FunctionCall{
+		Function:  &Identifier{Name: name},
+		Arguments: []Expression{},
+		DataType:  Void,
+	}}
+	original := *body
+	rewritten := make([]Statement, 0, len(original)-len(statements)+1)
+	rewritten = append(append(append(rewritten, original[:from]...), call), original[to:]...)
+	*body = rewritten
+
+	if err := checkVariables(program, statements); err != nil {
+		*body = original
+		return nil, err
+	}
+
+	extraction := &Extraction{Name: name, Statements: statements}
+	extraction.draw(roles, connections, extracted, region)
+	if err := extraction.check(); err != nil {
+		*body = original
+		return nil, err
+	}
+	return extraction, nil
+}
+
+// isContainer reports whether symbols of kind hold statements rather than
+// being part of one, so that they may be inside the region without being
+// extracted
+func isContainer(kind RoleKind) bool {
+	switch kind {
+	case RoleProgramBoundary, RoleHeader, RoleMainEntry, RoleFunction, RoleStage, RoleUnused:
+		return true
+	}
+	return false
+}
+
+// locateRun returns the block holding the statements of heads and where
+// they run in it
+func locateRun(program *Program, heads map[Statement]bool) (*[]Statement, int, int, error) {
+	var found *[]Statement
+	var indices []int
+	for _, body := range programBodies(program) {
+		for i, stmt := range *body {
+			if !heads[stmt] {
+				continue
+			}
+			if found != nil && found != body {
+				return nil, 0, 0, grimoireErrors.NewError(grimoireErrors.ValidationError,
+					i18n.T("parser.extract_different_blocks")).
+					WithSuggestion(i18n.T("parser.extract_different_blocks_suggestion"))
+			}
+			found = body
+			indices = append(indices, i)
+		}
+	}
+	if found == nil || len(indices) != len(heads) {
+		return nil, 0, 0, grimoireErrors.NewError(grimoireErrors.ValidationError,
+			i18n.T("parser.extract_not_in_program"))
+	}
+	for k := 1; k < len(indices); k++ {
+		if indices[k] != indices[k-1]+1 {
+			return nil, 0, 0, grimoireErrors.NewError(grimoireErrors.ValidationError,
+				i18n.T("parser.extract_separated")).
+				WithSuggestion(i18n.T("parser.extract_separated_suggestion"))
+		}
+	}
+	return found, indices[0], indices[len(indices)-1] + 1, nil
+}
+
+// programBodies returns every block of statements of program, nested ones
+// included
+func programBodies(program *Program) []*[]Statement {
+	var bodies []*[]Statement
+	var add func(body *[]Statement)
+	add = func(body *[]Statement) {
+		bodies = append(bodies, body)
+		for _, stmt := range *body {
+			switch s := stmt.(type) {
+			case *IfStatement:
+				add(&s.ThenBranch)
+				add(&s.ElseBranch)
+			case *ForLoop:
+				add(&s.Body)
+			case *WhileLoop:
+				add(&s.Body)
+			case *ParallelBlock:
+				for i := range s.Branches {
+					add(&s.Branches[i])
+				}
+			case *Block:
+				add(&s.Body)
+			case *Stage:
+				add(&s.Body)
+			case *Mirror:
+				add(&s.Body)
+			}
+		}
+	}
+
+	add(&program.Globals)
+	if program.MainEntry != nil {
+		add(&program.MainEntry.Body)
+	}
+	for _, fn := range program.Functions {
+		add(&fn.Body)
+	}
+	return bodies
+}
+
+// checkVariables returns an error when statements, already replaced by a
+// call in program, read a variable they do not assign or assign one the
+// rest of program reads
+func checkVariables(program *Program, statements []Statement) error {
+	assigned, read := variables(statements)
+	var errs []error
+	for _, name := range read {
+		if !containsString(assigned, name) {
+			errs = append(errs, grimoireErrors.NewError(grimoireErrors.ValidationError,
+				i18n.Tf("parser.extract_reads_outside", name)))
+		}
+	}
+
+	var rest []Statement
+	rest = append(rest, program.Globals...)
+	if program.MainEntry != nil {
+		rest = append(rest, program.MainEntry.Body...)
+	}
+	for _, fn := range program.Functions {
+		rest = append(rest, fn.Body...)
+	}
+	_, readOutside := variables(rest)
+	for _, name := range assigned {
+		if containsString(readOutside, name) {
+			errs = append(errs, grimoireErrors.NewError(grimoireErrors.ValidationError,
+				i18n.Tf("parser.extract_assigns_read_outside", name)))
+		}
+	}
+	if len(errs) > 0 {
+		return extractionErrors(i18n.T("parser.extract_shared_variables"), errs)
+	}
+	return nil
+}
+
+// variables returns the names stmts assign and read, each once, in the
+// order they first appear
+func variables(stmts []Statement) (assigned, read []string) {
+	assign := func(id *Identifier) {
+		if id != nil && !containsString(assigned, id.Name) {
+			assigned = append(assigned, id.Name)
+		}
+	}
+	var expression func(expr Expression)
+	expression = func(expr Expression) {
+		switch e := expr.(type) {
+		case *Identifier:
+			if !containsString(read, e.Name) {
+				read = append(read, e.Name)
+			}
+		case *FunctionCall:
+			for _, arg := range e.Arguments {
+				expression(arg)
+			}
+		case *BinaryOp:
+			expression(e.Left)
+			expression(e.Right)
+		case *UnaryOp:
+			expression(e.Operand)
+		case *ArrayLiteral:
+			for _, elem := range e.Elements {
+				expression(elem)
+			}
+		case *MapLiteral:
+			for _, pair := range e.Pairs {
+				expression(pair[0])
+				expression(pair[1])
+			}
+		}
+	}
+	var statements func(stmts []Statement)
+	statements = func(stmts []Statement) {
+		for _, stmt := range stmts {
+			switch s := stmt.(type) {
+			case *OutputStatement:
+				expression(s.Value)
+			case *Assignment:
+				expression(s.Value)
+				assign(s.Target)
+			case *IfStatement:
+				expression(s.Condition)
+				statements(s.ThenBranch)
+				statements(s.ElseBranch)
+			case *ForLoop:
+				expression(s.Start)
+				expression(s.End)
+				expression(s.Step)
+				assign(s.Counter)
+				statements(s.Body)
+			case *WhileLoop:
+				expression(s.Condition)
+				statements(s.Body)
+			case *ParallelBlock:
+				for _, branch := range s.Branches {
+					statements(branch)
+				}
+			case *Block:
+				statements(s.Body)
+			case *Stage:
+				statements(s.Body)
+			case *Mirror:
+				for _, operand := range s.Operands {
+					expression(operand)
+				}
+				assign(s.Item)
+				statements(s.Body)
+			case *Assertion:
+				expression(s.Left)
+				expression(s.Right)
+			case *ReturnStatement:
+				expression(s.Value)
+			case *ExpressionStatement:
+				expression(s.Expression)
+			}
+		}
+	}
+	statements(stmts)
+	return assigned, read
+}
+
+// draw lays out the extracted symbols as a library image: where they were,
+// inside an outer circle around the region, with a main entry feeding the
+// statements in place of whatever fed them
+func (e *Extraction) draw(roles []SymbolRole, connections []detector.Connection, extracted map[*detector.Symbol]bool, region image.Rectangle) {
+	center := detector.Position{
+		X: float64(region.Min.X+region.Max.X) / 2,
+		Y: float64(region.Min.Y+region.Max.Y) / 2,
+	}
+	extent := 0.0
+	for _, role := range roles {
+		if extracted[role.Symbol] {
+			e.Symbols = append(e.Symbols, role.Symbol)
+			extent = max(extent, math.Hypot(role.Symbol.Position.X-center.X, role.Symbol.Position.Y-center.Y)+role.Symbol.Radius())
+		}
+	}
+
+	// The main entry sits on the side the statements were fed from
+	direction := detector.Position{X: 0, Y: -1}
+	for _, conn := range connections {
+		if !extracted[conn.From] && extracted[conn.To] {
+			dx, dy := conn.From.Position.X-center.X, conn.From.Position.Y-center.Y
+			if d := math.Hypot(dx, dy); d > 0 {
+				direction = detector.Position{X: dx / d, Y: dy / d}
+			}
+			break
+		}
+	}
+	entrySize := 36.0
+	entryDistance := extent + extractionGap + entrySize/2
+	entry := &detector.Symbol{
+		Type:       detector.DoubleCircle,
+		Position:   detector.Position{X: center.X + direction.X*entryDistance, Y: center.Y + direction.Y*entryDistance},
+		Size:       entrySize,
+		Confidence: 1,
+		Pattern:    detector.PatternEmpty,
+		Properties: make(map[string]interface{}),
+	}
+	radius := entryDistance + entrySize/2 + extractionGap
+	outer := &detector.Symbol{
+		Type:       detector.OuterCircle,
+		Position:   center,
+		Size:       radius * math.Sqrt(math.Pi),
+		Confidence: 1,
+		Pattern:    detector.PatternEmpty,
+		Properties: make(map[string]interface{}),
+	}
+	e.Symbols = append([]*detector.Symbol{outer, entry}, e.Symbols...)
+
+	fed := make(map[*detector.Symbol]bool)
+	for _, conn := range connections {
+		switch {
+		case extracted[conn.From] && extracted[conn.To]:
+			e.Connections = append(e.Connections, conn)
+		case extracted[conn.To] && !fed[conn.To]:
+			fed[conn.To] = true
+			e.Connections = append(e.Connections, detector.Connection{
+				From:           entry,
+				To:             conn.To,
+				ConnectionType: conn.ConnectionType,
+				Properties:     make(map[string]interface{}),
+			})
+		}
+	}
+}
+
+// check returns an error unless the drawing of the function parses to the
+// extracted statements
+func (e *Extraction) check() error {
+	program, err := Parse(e.Symbols, e.Connections)
+	if err == nil && program.MainEntry != nil {
+		var drawn, lifted []byte
+		drawn, err = statementsJSON(program.MainEntry.Body)
+		if err == nil {
+			lifted, err = statementsJSON(e.Statements)
+		}
+		if err == nil && bytes.Equal(drawn, lifted) {
+			return nil
+		}
+	}
+	failure := grimoireErrors.NewError(grimoireErrors.ValidationError,
+		i18n.T("parser.extract_not_self_contained")).
+		WithSuggestion(i18n.T("parser.extract_not_self_contained_suggestion"))
+	if err != nil {
+		failure = failure.WithInnerError(err)
+	}
+	return failure
+}
+
+// variableNumber matches the names the parser gives variables
+var variableNumber = regexp.MustCompile(`var_(0x[0-9a-f]+|[0-9]+)`)
+
+// statementsJSON encodes stmts as the body of a main entry, with the
+// variables renumbered in order of appearance, since their names depend on
+// where the symbols are listed
+func statementsJSON(stmts []Statement) ([]byte, error) {
+	encoded, err := EncodeJSON(&Program{HasOuterCircle: true, MainEntry: &FunctionDef{IsMain: true, Body: stmts}})
+	if err != nil {
+		return nil, err
+	}
+	numbers := make(map[string]string)
+	return variableNumber.ReplaceAllFunc(encoded, func(name []byte) []byte {
+		number, ok := numbers[string(name)]
+		if !ok {
+			number = "var_" + strconv.Itoa(len(numbers))
+			numbers[string(name)] = number
+		}
+		return []byte(number)
+	}), nil
+}
+
+// extractionError describes a symbol that stops the extraction. The
+// message id takes the type of the symbol and its position.
+func extractionError(symbol *detector.Symbol, id string) *grimoireErrors.GrimoireError {
+	return grimoireErrors.NewError(grimoireErrors.ValidationError,
+		i18n.Tf(id, symbol.Type, symbol.Position.X, symbol.Position.Y)).
+		WithRegion(symbol.Position.X, symbol.Position.Y, symbol.Size)
+}
+
+// extractionErrors combines the errors that stop the extraction
+func extractionErrors(message string, errs []error) error {
+	if len(errs) == 1 {
+		return errs[0]
+	}
+	return grimoireErrors.NewError(grimoireErrors.ValidationError, message).
+		WithRelated(errs...)
+}
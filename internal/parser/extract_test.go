@@ -0,0 +1,101 @@
+package parser
+
+import (
+	"image"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// parseSubtraction parses the subtraction of ports_test.go
+func parseSubtraction(t *testing.T) (*Program, []SymbolRole, []detector.Connection) {
+	t.Helper()
+	symbols, connections := subtraction(nil, nil)
+	p := NewParser()
+	program, err := p.Parse(symbols, connections)
+	require.NoError(t, err)
+	return program, p.Roles(), connections
+}
+
+func TestExtract(t *testing.T) {
+	program, roles, connections := parseSubtraction(t)
+	output := program.MainEntry.Body[0]
+
+	extraction, err := Extract(program, roles, connections, image.Rect(120, 80, 280, 280), "lib/helper.png")
+	require.NoError(t, err)
+	assert.Equal(t, "helper", extraction.Name)
+	assert.Equal(t, []Statement{output}, extraction.Statements)
+
+	// The program calls the function in place of the statement
+	require.Len(t, program.MainEntry.Body, 1)
+	call, ok := program.MainEntry.Body[0].(*ExpressionStatement)
+	require.True(t, ok)
+	assert.Equal(t, "helper", call.Expression.(*FunctionCall).Function.Name)
+
+	// The function is drawn with a main entry inside a circle of its own
+	require.Len(t, extraction.Symbols, 6)
+	outer, entry := extraction.Symbols[0], extraction.Symbols[1]
+	assert.Equal(t, detector.OuterCircle, outer.Type)
+	assert.Equal(t, detector.DoubleCircle, entry.Type)
+	for _, s := range extraction.Symbols[1:] {
+		dx, dy := s.Position.X-outer.Position.X, s.Position.Y-outer.Position.Y
+		assert.Less(t, dx*dx+dy*dy, outer.Radius()*outer.Radius(), s.Type)
+	}
+	assert.Len(t, extraction.Connections, 3)
+}
+
+func TestExtractRegionCuttingStatement(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	program, roles, connections := parseSubtraction(t)
+	output := program.MainEntry.Body[0]
+
+	// The operands of the subtraction are left out
+	_, err := Extract(program, roles, connections, image.Rect(180, 160, 220, 280), "helper.png")
+	require.Error(t, err)
+	var gErr *grimoireErrors.GrimoireError
+	require.ErrorAs(t, err, &gErr)
+	assert.Len(t, gErr.Related, 2)
+	assert.Contains(t, err.Error(), "drawn outside it")
+	assert.Equal(t, []Statement{output}, program.MainEntry.Body)
+}
+
+func TestExtractEmptyRegion(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	program, roles, connections := parseSubtraction(t)
+	_, err := Extract(program, roles, connections, image.Rect(0, 0, 50, 50), "helper.png")
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "No statement is drawn inside the region")
+}
+
+func TestCheckVariables(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	assign := &Assignment{Target: &Identifier{Name: "var_1"}, Value: &Literal{Value: 1, LiteralType: Integer}}
+	output := &OutputStatement{Value: &Identifier{Name: "var_1"}}
+	call := &ExpressionStatement{Expression: &FunctionCall{Function: &Identifier{Name: "helper"}}}
+
+	// The assignment is lifted out, but the output still reads it
+	program := &Program{MainEntry: &FunctionDef{IsMain: true, Body: []Statement{call, output}}}
+	err := checkVariables(program, []Statement{assign})
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "assign var_1, which is read outside it")
+
+	// The output is lifted out without the assignment it reads
+	program = &Program{MainEntry: &FunctionDef{IsMain: true, Body: []Statement{assign, call}}}
+	err = checkVariables(program, []Statement{output})
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "read var_1, which is assigned outside it")
+
+	// Both are lifted out together
+	program = &Program{MainEntry: &FunctionDef{IsMain: true, Body: []Statement{call}}}
+	assert.NoError(t, checkVariables(program, []Statement{assign, output}))
+}
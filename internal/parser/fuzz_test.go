@@ -0,0 +1,78 @@
+package parser
+
+import (
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// maxFuzzSymbols bounds the symbols built from one fuzz input
+const maxFuzzSymbols = 64
+
+// fuzzSymbols builds symbols from data, four bytes per symbol: type,
+// x, y and pattern. Type bytes past the known types give unknown types.
+func fuzzSymbols(data []byte) []*detector.Symbol {
+	types := detector.SymbolTypes()
+	patterns := detector.Patterns()
+
+	var symbols []*detector.Symbol
+	for i := 0; i+3 < len(data) && len(symbols) < maxFuzzSymbols; i += 4 {
+		symbolType := detector.Unknown
+		if int(data[i]) < len(types) {
+			symbolType = types[data[i]]
+		}
+		pattern := ""
+		if int(data[i+3]) < len(patterns) {
+			pattern = patterns[data[i+3]]
+		}
+		symbols = append(symbols, &detector.Symbol{
+			Type:     symbolType,
+			Position: detector.Position{X: float64(data[i+1]) * 4, Y: float64(data[i+2]) * 4},
+			Size:     20,
+			Pattern:  pattern,
+		})
+	}
+	return symbols
+}
+
+// fuzzConnections builds connections from data, two bytes per connection
+// naming the symbols at each end. Indexes past the symbols give nil ends.
+func fuzzConnections(data []byte, symbols []*detector.Symbol) []detector.Connection {
+	end := func(b byte) *detector.Symbol {
+		if i := int(b) % (len(symbols) + 1); i < len(symbols) {
+			return symbols[i]
+		}
+		return nil
+	}
+
+	var connections []detector.Connection
+	for i := 0; i+1 < len(data); i += 2 {
+		connections = append(connections, detector.Connection{
+			From:           end(data[i]),
+			To:             end(data[i+1]),
+			ConnectionType: "solid",
+		})
+	}
+	return connections
+}
+
+// FuzzParse feeds arbitrary symbol and connection lists to the parser,
+// which must return a program or an error without panicking
+func FuzzParse(f *testing.F) {
+	// Outer circle, main entry, a literal and an output
+	f.Add([]byte{0, 50, 50, 0, 2, 50, 20, 0, 3, 50, 35, 1, 7, 50, 45, 0}, []byte{})
+	// Operator cycle feeding an output
+	f.Add([]byte{0, 50, 50, 0, 10, 45, 30, 0, 12, 55, 30, 0, 7, 50, 60, 0}, []byte{1, 2, 2, 1, 1, 3})
+	// Operator connected to itself and a nil end
+	f.Add([]byte{0, 50, 50, 0, 10, 45, 30, 0, 7, 50, 60, 0}, []byte{1, 1, 1, 2, 3, 0})
+	// Control structures without connections
+	f.Add([]byte{0, 50, 50, 0, 4, 50, 30, 0, 5, 50, 40, 0, 6, 50, 50, 0, 7, 40, 60, 0, 7, 60, 60, 0}, []byte{})
+
+	f.Fuzz(func(t *testing.T, symbolData, connectionData []byte) {
+		symbols := fuzzSymbols(symbolData)
+		program, err := Parse(symbols, fuzzConnections(connectionData, symbols))
+		if err == nil && program == nil {
+			t.Fatal("Parse returned neither a program nor an error")
+		}
+	})
+}
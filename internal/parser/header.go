@@ -0,0 +1,119 @@
+package parser
+
+import (
+	"sort"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+)
+
+// LanguageVersion is the newest language version a program header may
+// declare
+const LanguageVersion = 1
+
+// Capabilities a program header can require
+const (
+	CapabilityFunctions = "functions"
+	CapabilityLoops     = "loops"
+	CapabilityParallel  = "parallel"
+)
+
+// capabilityGlyphs maps the shape of a header glyph to the capability it
+// requires. A square declares the language version instead, by its dots.
+var capabilityGlyphs = map[detector.SymbolType]string{
+	detector.Circle:   CapabilityFunctions,
+	detector.Pentagon: CapabilityLoops,
+	detector.Hexagon:  CapabilityParallel,
+}
+
+// splitHeader separates the glyphs on the outer circle from the symbols of
+// the program inside it
+func splitHeader(symbols []*detector.Symbol) (program, glyphs []*detector.Symbol) {
+	program = make([]*detector.Symbol, 0, len(symbols))
+	for _, symbol := range symbols {
+		if symbol.OnRing {
+			glyphs = append(glyphs, symbol)
+		} else {
+			program = append(program, symbol)
+		}
+	}
+	return program, glyphs
+}
+
+// parseHeader reads the header declared by glyphs on outerCircle, or
+// returns nil when there are none. Glyphs are read clockwise from the top.
+// A version this parser does not support or a glyph it does not know is a
+// parse error, so the program is rejected before it is compiled.
+func (p *Parser) parseHeader(outerCircle *detector.Symbol, glyphs []*detector.Symbol) *ProgramHeader {
+	if len(glyphs) == 0 {
+		return nil
+	}
+
+	glyphs = append([]*detector.Symbol(nil), glyphs...)
+	sort.SliceStable(glyphs, func(i, j int) bool {
+		return Polar(outerCircle, glyphs[i], 1).Angle < Polar(outerCircle, glyphs[j], 1).Angle
+	})
+
+	header := &ProgramHeader{}
+	for _, glyph := range glyphs {
+		node := &symbolNode{symbol: glyph}
+		p.recordRole(node, RoleHeader)
+		p.attachNode(node, header)
+
+		if glyph.Type == detector.Square {
+			p.parseVersionGlyph(header, glyph)
+			continue
+		}
+		capability, ok := capabilityGlyphs[glyph.Type]
+		if !ok {
+			p.errors = append(p.errors, headerError(grimoireErrors.UnexpectedSymbol, glyph,
+				i18n.Tf("parser.header_unrecognized_glyph", glyph.Type)).
+				WithSuggestion(i18n.T("parser.header_unrecognized_glyph_suggestion")))
+			continue
+		}
+		if !containsString(header.Capabilities, capability) {
+			header.Capabilities = append(header.Capabilities, capability)
+		}
+	}
+	return header
+}
+
+// parseVersionGlyph sets the language version of header from the dots in
+// a square glyph
+func (p *Parser) parseVersionGlyph(header *ProgramHeader, glyph *detector.Symbol) {
+	version, _ := literalFromPattern(glyph.Pattern).Value.(int)
+	switch {
+	case version <= 0:
+		p.errors = append(p.errors, headerError(grimoireErrors.SyntaxError, glyph,
+			i18n.T("parser.header_version_no_dots")).
+			WithSuggestion(i18n.T("parser.header_version_no_dots_suggestion")))
+	case header.Version != 0 && header.Version != version:
+		p.errors = append(p.errors, headerError(grimoireErrors.SyntaxError, glyph,
+			i18n.Tf("parser.header_versions_conflict", header.Version, version)).
+			WithSuggestion(i18n.T("parser.header_versions_conflict_suggestion")))
+	case version > LanguageVersion:
+		p.errors = append(p.errors, headerError(grimoireErrors.UnsupportedOperation, glyph,
+			i18n.Tf("parser.header_version_unsupported", version, LanguageVersion)).
+			WithSuggestion(i18n.T("parser.header_version_unsupported_suggestion")))
+	default:
+		header.Version = version
+	}
+}
+
+// headerError returns an error located at a header glyph
+func headerError(errorType grimoireErrors.ErrorType, glyph *detector.Symbol, message string) *grimoireErrors.GrimoireError {
+	return grimoireErrors.NewError(errorType, message).
+		WithDetails(i18n.Tf("detail.at_position", glyph.Position.X, glyph.Position.Y)).
+		WithRegion(glyph.Position.X, glyph.Position.Y, glyph.Size)
+}
+
+// containsString reports whether list contains s
+func containsString(list []string, s string) bool {
+	for _, item := range list {
+		if item == s {
+			return true
+		}
+	}
+	return false
+}
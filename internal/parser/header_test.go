@@ -0,0 +1,108 @@
+package parser
+
+import (
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// withHeader adds glyphs on the outer circle of the subtraction program.
+// The outer circle is centered at (200, 200).
+func withHeader(glyphs ...*detector.Symbol) ([]*detector.Symbol, []detector.Connection) {
+	symbols, connections := subtraction(nil, nil)
+	for _, glyph := range glyphs {
+		glyph.OnRing = true
+	}
+	return append(symbols, glyphs...), connections
+}
+
+func TestParseHeader(t *testing.T) {
+	p := NewParser()
+	program, err := p.Parse(withHeader(
+		&detector.Symbol{Type: detector.Hexagon, Position: detector.Position{X: 300, Y: 200}},
+		&detector.Symbol{Type: detector.Pentagon, Position: detector.Position{X: 100, Y: 200}},
+		&detector.Symbol{Type: detector.Square, Position: detector.Position{X: 200, Y: 100}, Pattern: detector.PatternDot},
+	))
+	require.NoError(t, err)
+
+	// Glyphs are read clockwise from the top
+	require.NotNil(t, program.Header)
+	assert.Equal(t, 1, program.Header.Version)
+	assert.Equal(t, []string{CapabilityParallel, CapabilityLoops}, program.Header.Capabilities)
+
+	// The glyphs stay out of the program
+	op := outputOperation(t, program)
+	assert.Equal(t, 1, op.Left.(*Literal).Value)
+	assert.Equal(t, 2, op.Right.(*Literal).Value)
+
+	roles := p.Roles()
+	require.Len(t, roles, 8)
+	for _, role := range roles[5:] {
+		assert.Equal(t, RoleHeader, role.Kind)
+		assert.Same(t, program.Header, role.Node)
+	}
+}
+
+func TestParseWithoutHeader(t *testing.T) {
+	program, err := Parse(withHeader())
+	require.NoError(t, err)
+	assert.Nil(t, program.Header)
+}
+
+func TestParseHeaderErrors(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	tests := []struct {
+		name   string
+		glyphs []*detector.Symbol
+		want   string
+	}{
+		{
+			name: "newer version",
+			glyphs: []*detector.Symbol{
+				{Type: detector.Square, Position: detector.Position{X: 200, Y: 100}, Pattern: detector.PatternDoubleDot},
+			},
+			want: "requires language version 2, but this compiler supports up to version 1",
+		},
+		{
+			name: "version without dots",
+			glyphs: []*detector.Symbol{
+				{Type: detector.Square, Position: detector.Position{X: 200, Y: 100}, Pattern: detector.PatternEmpty},
+			},
+			want: "Version square on the outer circle has no dots",
+		},
+		{
+			name: "unknown glyph",
+			glyphs: []*detector.Symbol{
+				{Type: detector.Star, Position: detector.Position{X: 300, Y: 200}},
+			},
+			want: "Unrecognized header glyph star",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			_, err := Parse(withHeader(tt.glyphs...))
+			require.Error(t, err)
+			assert.Contains(t, err.Error(), tt.want)
+		})
+	}
+}
+
+func TestHeaderJSONRoundTrip(t *testing.T) {
+	program, err := Parse(withHeader(
+		&detector.Symbol{Type: detector.Square, Position: detector.Position{X: 200, Y: 100}, Pattern: detector.PatternDot},
+		&detector.Symbol{Type: detector.Circle, Position: detector.Position{X: 300, Y: 200}},
+	))
+	require.NoError(t, err)
+
+	data, err := EncodeJSON(program)
+	require.NoError(t, err)
+	decoded, err := DecodeJSON(data)
+	require.NoError(t, err)
+	assert.Equal(t, program.Header, decoded.(*Program).Header)
+}
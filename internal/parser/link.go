@@ -0,0 +1,148 @@
+package parser
+
+import (
+	"fmt"
+	"path/filepath"
+	"strings"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/ident"
+)
+
+// Library is a parsed image linked into another program
+type Library struct {
+	Name    string // Source of the library, usually its file path
+	Program *Program
+}
+
+// libraryPatterns maps the pattern of an eight-pointed star calling a
+// library to the position of the library among those linked
+var libraryPatterns = map[string]int{
+	detector.PatternDot:       0,
+	detector.PatternDoubleDot: 1,
+	detector.PatternTripleDot: 2,
+}
+
+// WithLibraries sets the sources of the libraries the program will be
+// linked with, in the order given to Link. An eight-pointed star with one,
+// two or three dots inside calls the main entry of the first, second or
+// third library.
+func (p *Parser) WithLibraries(names ...string) *Parser {
+	p.libraries = LibraryNames(names)
+	return p
+}
+
+// LibraryNames returns the names Link gives the main entries of libraries
+// from the sources given
+func LibraryNames(sources []string) []string {
+	namer := ident.NewNamer()
+	names := make([]string, len(sources))
+	for i, source := range sources {
+		names[i] = namer.Name(libraryLabel(source))
+	}
+	return names
+}
+
+// libraryCall returns the call a star with pattern makes to a library, and
+// whether the pattern calls a library at all. The call is nil when no
+// library was given for the pattern.
+func (p *Parser) libraryCall(pattern string) (*FunctionCall, bool) {
+	index, ok := libraryPatterns[pattern]
+	if !ok {
+		return nil, false
+	}
+	if index >= len(p.libraries) {
+		return nil, true
+	}
+	return &FunctionCall{
+		Function:  &Identifier{Name: p.libraries[index]},
+		Arguments: []Expression{},
+		DataType:  Void,
+	}, true
+}
+
+// Link merges the function definitions of libraries into program. Each
+// library's main entry becomes a function named after its file, mangled
+// into an identifier (see ident.Mangle and LibraryNames), and its
+// other functions are named after the library with a numeric suffix.
+// Library functions are placed before the program's own functions so they
+// are defined by the time the program calls them. A name defined by more
+// than one image is reported as a DUPLICATE_DEFINITION error.
+func Link(program *Program, libraries []Library) (*Program, error) {
+	if program == nil {
+		return nil, grimoireErrors.NewError(grimoireErrors.SyntaxError, i18n.T("parser.link_nil_program"))
+	}
+	if len(libraries) == 0 {
+		return program, nil
+	}
+
+	linked := *program
+	linked.Functions = nil
+
+	origins := make(map[string]string)
+	var errs []error
+	define := func(fn *FunctionDef, origin string) {
+		if first, ok := origins[fn.Name]; ok {
+			errs = append(errs, grimoireErrors.DuplicateDefinitionError(fn.Name, first, origin))
+			return
+		}
+		origins[fn.Name] = origin
+		linked.Functions = append(linked.Functions, fn)
+	}
+
+	// Libraries named alike are duplicates, while distinct names that
+	// mangle to the same identifier are told apart by a suffix
+	sources := make([]string, len(libraries))
+	for i, lib := range libraries {
+		sources[i] = lib.Name
+	}
+	names := LibraryNames(sources)
+	for i, lib := range libraries {
+		if lib.Program == nil {
+			continue
+		}
+		name := names[i]
+
+		if lib.Program.MainEntry != nil {
+			entry := *lib.Program.MainEntry
+			entry.Name = name
+			entry.IsMain = false
+			define(&entry, lib.Name)
+		}
+		for i, fn := range lib.Program.Functions {
+			copied := *fn
+			copied.Name = fmt.Sprintf("%s_%d", name, i+1)
+			define(&copied, lib.Name)
+		}
+	}
+
+	for _, fn := range program.Functions {
+		if fn.Name == "" {
+			linked.Functions = append(linked.Functions, fn)
+			continue
+		}
+		define(fn, "main program")
+	}
+
+	switch len(errs) {
+	case 0:
+		return &linked, nil
+	case 1:
+		return nil, errs[0]
+	}
+
+	errorMsg := i18n.T("parser.link_errors")
+	for _, err := range errs {
+		errorMsg += "\n  - " + err.Error()
+	}
+	return nil, grimoireErrors.NewError(grimoireErrors.DuplicateDefinition, errorMsg).
+		WithRelated(errs...)
+}
+
+// libraryLabel returns the file name of a library without its extension
+func libraryLabel(path string) string {
+	base := filepath.Base(path)
+	return strings.TrimSuffix(base, filepath.Ext(base))
+}
@@ -0,0 +1,149 @@
+package parser
+
+import (
+	"math"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/ayutaz/grimoire/internal/ident"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestLink(t *testing.T) {
+	libEntry := &FunctionDef{IsMain: true, Body: []Statement{&OutputStatement{Value: &Literal{Value: 1, LiteralType: Integer}}}}
+	libFunc := &FunctionDef{}
+	lib := &Program{HasOuterCircle: true, MainEntry: libEntry, Functions: []*FunctionDef{libFunc}}
+
+	ownFunc := &FunctionDef{}
+	program := &Program{HasOuterCircle: true, MainEntry: &FunctionDef{IsMain: true}, Functions: []*FunctionDef{ownFunc}}
+
+	linked, err := Link(program, []Library{{Name: "lib/math-utils.png", Program: lib}})
+	require.NoError(t, err)
+
+	require.Len(t, linked.Functions, 3)
+	assert.Equal(t, "math_utils", linked.Functions[0].Name)
+	assert.False(t, linked.Functions[0].IsMain)
+	assert.Equal(t, libEntry.Body, linked.Functions[0].Body)
+	assert.Equal(t, "math_utils_1", linked.Functions[1].Name)
+	assert.Same(t, ownFunc, linked.Functions[2])
+	assert.Same(t, program.MainEntry, linked.MainEntry)
+
+	// The inputs are left untouched
+	assert.True(t, libEntry.IsMain)
+	assert.Empty(t, libFunc.Name)
+	assert.Len(t, program.Functions, 1)
+}
+
+func TestLinkWithoutLibraries(t *testing.T) {
+	program := &Program{HasOuterCircle: true}
+	linked, err := Link(program, nil)
+	require.NoError(t, err)
+	assert.Same(t, program, linked)
+}
+
+func TestLinkDuplicateDefinition(t *testing.T) {
+	lib := func() *Program {
+		return &Program{HasOuterCircle: true, MainEntry: &FunctionDef{IsMain: true}}
+	}
+	program := &Program{HasOuterCircle: true, MainEntry: &FunctionDef{IsMain: true}}
+
+	_, err := Link(program, []Library{
+		{Name: "a/util.png", Program: lib()},
+		{Name: "b/util.png", Program: lib()},
+	})
+	require.Error(t, err)
+
+	var gErr *grimoireErrors.GrimoireError
+	require.ErrorAs(t, err, &gErr)
+	assert.Equal(t, grimoireErrors.DuplicateDefinition, gErr.Type)
+	assert.Contains(t, gErr.Message, "util")
+	assert.Contains(t, gErr.Message, "a/util.png")
+	assert.Contains(t, gErr.Message, "b/util.png")
+
+	_, err = Link(program, []Library{
+		{Name: "util.png", Program: lib()},
+		{Name: "util.png", Program: lib()},
+		{Name: "util.png", Program: lib()},
+	})
+	require.ErrorAs(t, err, &gErr)
+	assert.Len(t, gErr.Related, 2)
+}
+
+func TestLibraryFunctionName(t *testing.T) {
+	tests := map[string]string{
+		"lib/helpers.png":     "helpers",
+		"my-lib.v2.png":       "my_lib_v2",
+		"2fast.png":           "_2fast",
+		"/tmp/my spell.png":   "my_spell",
+		"nested/dir/Loop.jpg": "Loop",
+		"spells/あいさつ.png":     "aisatsu",
+		"spells/挨拶.png":       "u6328_u62f6",
+	}
+	for path, expected := range tests {
+		assert.Equal(t, expected, ident.Mangle(libraryLabel(path)), path)
+	}
+}
+
+func TestLinkMangledNameCollision(t *testing.T) {
+	lib := func() *Program {
+		return &Program{HasOuterCircle: true, MainEntry: &FunctionDef{IsMain: true}}
+	}
+	program := &Program{HasOuterCircle: true, MainEntry: &FunctionDef{IsMain: true}}
+
+	// Distinct names that mangle alike are not duplicates
+	linked, err := Link(program, []Library{
+		{Name: "挨 拶.png", Program: lib()},
+		{Name: "挨_拶.png", Program: lib()},
+	})
+	require.NoError(t, err)
+	require.Len(t, linked.Functions, 2)
+	assert.Equal(t, "u6328_u62f6", linked.Functions[0].Name)
+	assert.Equal(t, "u6328_u62f6_2", linked.Functions[1].Name)
+}
+
+func TestParseLibraryCalls(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	at := func(symbolType detector.SymbolType, x, y float64, pattern string) *detector.Symbol {
+		return &detector.Symbol{Type: symbolType, Position: detector.Position{X: x, Y: y}, Size: 20, Pattern: pattern}
+	}
+	entry := at(detector.DoubleCircle, 200, 100, detector.PatternEmpty)
+	first := at(detector.EightPointedStar, 200, 200, detector.PatternDot)
+	second := at(detector.EightPointedStar, 200, 300, detector.PatternDoubleDot)
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180 * math.Sqrt(math.Pi)},
+		entry, first, second,
+	}
+	connections := []detector.Connection{
+		{From: entry, To: first, ConnectionType: detector.ConnectionTypeSolid},
+	}
+
+	program, err := NewParser().WithLibraries("lib/greet.png", "lib/farewell.png").Parse(symbols, connections)
+	require.NoError(t, err)
+	require.NotNil(t, program.MainEntry)
+	var called []string
+	walkCalls(program.MainEntry.Body, func(call *FunctionCall) {
+		called = append(called, call.Function.Name)
+	})
+	assert.Equal(t, []string{"greet", "farewell"}, called)
+
+	// The calls resolve to the functions Link defines
+	library := func() *Program { return &Program{HasOuterCircle: true, MainEntry: &FunctionDef{IsMain: true}} }
+	linked, err := Link(program, []Library{
+		{Name: "lib/greet.png", Program: library()},
+		{Name: "lib/farewell.png", Program: library()},
+	})
+	require.NoError(t, err)
+	require.Len(t, linked.Functions, 2)
+	assert.Equal(t, "greet", linked.Functions[0].Name)
+	assert.Equal(t, "farewell", linked.Functions[1].Name)
+
+	// Without a library for the dots, the star calls nothing
+	_, err = NewParser().WithLibraries("lib/greet.png").Parse(symbols, connections)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "calls a library image, but 1 were given")
+}
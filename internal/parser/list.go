@@ -0,0 +1,63 @@
+package parser
+
+import (
+	"math"
+	"sort"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// A wide rectangle drawn around a row of symbols is a list of them, read
+// from left to right. A symbol belongs to the innermost frame around it, so
+// a frame inside another is a list nested in it.
+
+// framedBy reports whether symbol lies inside the list frame
+func framedBy(frame, symbol *detector.Symbol) bool {
+	return symbol != frame &&
+		math.Abs(symbol.Position.X-frame.Position.X) < frame.Width/2 &&
+		math.Abs(symbol.Position.Y-frame.Position.Y) < frame.Height/2
+}
+
+// frameOf returns the innermost list frame around symbol, or nil when
+// symbol is not in a list
+func frameOf(symbols []*detector.Symbol, symbol *detector.Symbol) *detector.Symbol {
+	var innermost *detector.Symbol
+	for _, frame := range symbols {
+		if !frame.ListFrame || !framedBy(frame, symbol) {
+			continue
+		}
+		if innermost == nil || frame.Width*frame.Height < innermost.Width*innermost.Height {
+			innermost = frame
+		}
+	}
+	return innermost
+}
+
+// listElements returns the nodes of the symbols in the list framed by node,
+// from left to right
+func (p *Parser) listElements(node *symbolNode) []*symbolNode {
+	var elements []*symbolNode
+	for i, symbol := range p.symbols {
+		if symbol.Type != detector.OuterCircle && frameOf(p.symbols, symbol) == node.symbol {
+			elements = append(elements, p.symbolGraph[i])
+		}
+	}
+	sort.SliceStable(elements, func(i, j int) bool {
+		return elements[i].symbol.Position.X < elements[j].symbol.Position.X
+	})
+	return elements
+}
+
+// parseList parses the list framed by node (a wide rectangle)
+func (p *Parser) parseList(node *symbolNode) *ArrayLiteral {
+	p.recordRole(node, RoleList)
+
+	list := &ArrayLiteral{Elements: []Expression{}}
+	for _, element := range p.listElements(node) {
+		if expr := p.parseExpression(element); expr != nil {
+			list.Elements = append(list.Elements, expr)
+		}
+	}
+	p.attachNode(node, list)
+	return list
+}
@@ -0,0 +1,94 @@
+package parser
+
+import (
+	"math"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// listFrame returns a list frame centered at (x, y)
+func listFrame(x, y, width, height float64) *detector.Symbol {
+	return &detector.Symbol{
+		Type: detector.Square, Position: detector.Position{X: x, Y: y}, Pattern: detector.PatternEmpty,
+		ListFrame: true, Width: width, Height: height,
+	}
+}
+
+func TestParseList(t *testing.T) {
+	frame := listFrame(200, 150, 220, 50)
+	star := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 200, Y: 260}}
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180 * math.Sqrt(math.Pi)},
+		frame,
+		{Type: detector.Square, Position: detector.Position{X: 250, Y: 150}, Pattern: detector.PatternTripleDot},
+		{Type: detector.Square, Position: detector.Position{X: 130, Y: 150}, Pattern: detector.PatternDot},
+		{Type: detector.Square, Position: detector.Position{X: 190, Y: 150}, Pattern: detector.PatternDoubleDot},
+		star,
+	}
+	connections := []detector.Connection{{From: frame, To: star, ConnectionType: detector.ConnectionTypeSolid}}
+
+	p := NewParser()
+	program, err := p.Parse(symbols, connections)
+	require.NoError(t, err)
+	require.Len(t, program.MainEntry.Body, 1)
+	output, ok := program.MainEntry.Body[0].(*OutputStatement)
+	require.True(t, ok)
+	list, ok := output.Value.(*ArrayLiteral)
+	require.True(t, ok, "output of %T", output.Value)
+
+	// Elements are read from left to right
+	var values []interface{}
+	for _, element := range list.Elements {
+		values = append(values, element.(*Literal).Value)
+	}
+	assert.Equal(t, []interface{}{1, 2, 3}, values)
+
+	roles := p.Roles()
+	assert.Equal(t, RoleList, roles[1].Kind)
+	assert.Same(t, list, roles[1].Node)
+	for _, role := range roles[2:5] {
+		assert.Equal(t, RoleLiteral, role.Kind)
+	}
+}
+
+func TestFrameOf(t *testing.T) {
+	outer := listFrame(200, 100, 300, 80)
+	inner := listFrame(250, 100, 120, 40)
+	at := func(x, y float64) *detector.Symbol {
+		return &detector.Symbol{Type: detector.Square, Position: detector.Position{X: x, Y: y}}
+	}
+	symbols := []*detector.Symbol{outer, inner}
+
+	assert.Same(t, outer, frameOf(symbols, at(100, 100)))
+	assert.Same(t, inner, frameOf(symbols, at(260, 100)), "the innermost frame claims a symbol")
+	assert.Same(t, outer, frameOf(symbols, inner), "a frame inside another is one of its elements")
+	assert.Nil(t, frameOf(symbols, outer))
+	assert.Nil(t, frameOf(symbols, at(200, 160)))
+}
+
+func TestParseNestedList(t *testing.T) {
+	outer := listFrame(200, 150, 300, 80)
+	inner := listFrame(250, 150, 120, 40)
+	star := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 200, Y: 260}}
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180 * math.Sqrt(math.Pi)},
+		outer, inner,
+		{Type: detector.Square, Position: detector.Position{X: 100, Y: 150}, Pattern: detector.PatternDot},
+		{Type: detector.Square, Position: detector.Position{X: 270, Y: 150}, Pattern: detector.PatternCross},
+		star,
+	}
+	connections := []detector.Connection{{From: outer, To: star, ConnectionType: detector.ConnectionTypeSolid}}
+
+	program, err := Parse(symbols, connections)
+	require.NoError(t, err)
+	list := program.MainEntry.Body[0].(*OutputStatement).Value.(*ArrayLiteral)
+	require.Len(t, list.Elements, 2)
+	assert.Equal(t, 1, list.Elements[0].(*Literal).Value)
+	nested, ok := list.Elements[1].(*ArrayLiteral)
+	require.True(t, ok)
+	require.Len(t, nested.Elements, 1)
+	assert.Equal(t, true, nested.Elements[0].(*Literal).Value)
+}
@@ -0,0 +1,92 @@
+package parser
+
+import (
+	"math"
+	"sort"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// Two like symbols drawn as mirror images across the vertical axis of the
+// outer circle form a mirrored pair. An output fed by both of them is
+// applied to each in turn, left first, rather than to one of them.
+
+// mirrorTolerance is how far a symbol may lie from the reflection of its
+// pair, as a fraction of the radius of the outer circle
+const mirrorTolerance = 0.05
+
+// mirrorPairs matches symbols with their mirror image across the vertical
+// axis through the center of outerCircle, mapping each symbol of a pair to
+// the other. A symbol pairs with the closest symbol of its type near its
+// reflection, and at most once. Symbols on the axis have no pair.
+func mirrorPairs(outerCircle *detector.Symbol, symbols []*detector.Symbol) map[*detector.Symbol]*detector.Symbol {
+	type candidate struct {
+		left, right *detector.Symbol
+		offset      float64
+	}
+
+	axis := outerCircle.Position.X
+	tolerance := outerCircle.Radius()*mirrorTolerance + 3
+	var candidates []candidate
+	for _, left := range symbols {
+		if left.Type == detector.OuterCircle || left.Position.X >= axis-tolerance {
+			continue
+		}
+		for _, right := range symbols {
+			if right.Type != left.Type || right.Position.X <= axis+tolerance {
+				continue
+			}
+			offset := math.Hypot(left.Position.X+right.Position.X-2*axis, left.Position.Y-right.Position.Y)
+			if offset <= tolerance {
+				candidates = append(candidates, candidate{left, right, offset})
+			}
+		}
+	}
+
+	// Closest reflections claim their symbols first
+	sort.SliceStable(candidates, func(i, j int) bool {
+		return candidates[i].offset < candidates[j].offset
+	})
+	pairs := make(map[*detector.Symbol]*detector.Symbol)
+	for _, c := range candidates {
+		if pairs[c.left] == nil && pairs[c.right] == nil {
+			pairs[c.left] = c.right
+			pairs[c.right] = c.left
+		}
+	}
+	return pairs
+}
+
+// mirroredInputs returns the mirrored pair among the inputs of node, left
+// first, or nil when there is none
+func (p *Parser) mirroredInputs(node *symbolNode) (left, right *symbolNode) {
+	parents := p.getParents(node)
+	for _, a := range parents {
+		for _, b := range parents {
+			if p.mirrors[a.symbol] == b.symbol && a.symbol.Position.X < b.symbol.Position.X {
+				return a, b
+			}
+		}
+	}
+	return nil, nil
+}
+
+// parseMirror parses an output (star) fed by a mirrored pair, applying it
+// to both
+func (p *Parser) parseMirror(node, left, right *symbolNode) *Mirror {
+	p.recordRole(node, RoleOutput)
+
+	operands := []Expression{p.parseExpression(left), p.parseExpression(right)}
+	item := &Identifier{Name: "mirrored"}
+	if operands[0] != nil && operands[1] != nil && operands[0].Type() == operands[1].Type() {
+		item.DataType = operands[0].Type()
+	}
+
+	mirror := &Mirror{
+		Item:     item,
+		Operands: operands,
+		Body:     []Statement{&OutputStatement{Value: item}},
+	}
+	p.attachNode(node, mirror)
+	return mirror
+}
@@ -0,0 +1,65 @@
+package parser
+
+import (
+	"math"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestMirrorPairs(t *testing.T) {
+	// An outer circle of radius 180 around (200, 200)
+	outerCircle := &detector.Symbol{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180 * math.Sqrt(math.Pi)}
+	left := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 150, Y: 150}}
+	right := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 250, Y: 150}}
+	farther := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 252, Y: 158}}
+	star := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 120, Y: 300}}
+	triangle := &detector.Symbol{Type: detector.Triangle, Position: detector.Position{X: 280, Y: 300}}
+	onAxis := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 200, Y: 50}}
+
+	pairs := mirrorPairs(outerCircle, []*detector.Symbol{outerCircle, farther, left, right, star, triangle, onAxis})
+
+	assert.Same(t, right, pairs[left], "the closest reflection wins")
+	assert.Same(t, left, pairs[right])
+	assert.Nil(t, pairs[farther], "a symbol pairs at most once")
+	assert.Nil(t, pairs[star], "mirrored symbols are alike")
+	assert.Nil(t, pairs[onAxis])
+	assert.Len(t, pairs, 2)
+}
+
+func TestParseMirror(t *testing.T) {
+	one := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 150, Y: 100}, Pattern: detector.PatternDot}
+	two := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 250, Y: 100}, Pattern: detector.PatternDoubleDot}
+	star := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 200, Y: 200}}
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180},
+		two, star, one,
+	}
+	connections := []detector.Connection{
+		{From: two, To: star, ConnectionType: detector.ConnectionTypeSolid},
+		{From: one, To: star, ConnectionType: detector.ConnectionTypeSolid},
+	}
+
+	// Parse several times, since parents are found in map order
+	for i := 0; i < 20; i++ {
+		p := NewParser()
+		program, err := p.Parse(symbols, connections)
+		require.NoError(t, err)
+		require.NotNil(t, program.MainEntry)
+		require.Len(t, program.MainEntry.Body, 1)
+
+		mirror, ok := program.MainEntry.Body[0].(*Mirror)
+		require.True(t, ok, "statement is %T", program.MainEntry.Body[0])
+		require.Len(t, mirror.Operands, 2)
+		assert.Equal(t, 1, mirror.Operands[0].(*Literal).Value, "the left operand comes first")
+		assert.Equal(t, 2, mirror.Operands[1].(*Literal).Value)
+		assert.Equal(t, Integer, mirror.Item.DataType)
+		assert.Equal(t, []Statement{&OutputStatement{Value: mirror.Item}}, mirror.Body)
+
+		role := p.Roles()[2]
+		assert.Equal(t, RoleOutput, role.Kind)
+		assert.Same(t, mirror, role.Node)
+	}
+}
@@ -7,23 +7,38 @@ import (
 
 	"github.com/ayutaz/grimoire/internal/detector"
 	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
 )
 
 // symbolNode wraps a symbol with parsing metadata
 type symbolNode struct {
 	symbol   *detector.Symbol
 	visited  bool
+	parsing  bool // Set while the node's expression is being parsed
 	astNode  ASTNode
 	parent   *symbolNode
 	children []*symbolNode
+	inputs   map[*symbolNode]*detector.Anchor // Where each parent's line meets the symbol
 }
 
 // Parser converts detected symbols to AST
 type Parser struct {
-	symbols     []*detector.Symbol
+	detected    []*detector.Symbol // symbols as passed to Parse
+	symbols     []*detector.Symbol // symbols of the program, without header glyphs
 	connections []detector.Connection
+	quotes      map[*detector.Symbol][]*detector.Symbol // symbols of the program each quote holds
+	depth       int                                     // how many quotes the program is nested in
 	symbolGraph map[int]*symbolNode
 	errors      []error
+	roles       map[*detector.Symbol]*SymbolRole
+	roleCounts  map[RoleKind]int
+	statements  []*symbolNode // statements being parsed, innermost last
+	mirrors     map[*detector.Symbol]*detector.Symbol
+	function    *SymbolRole   // function whose body is being parsed
+	resolutions []Resolution  // ambiguous symbols read as an alternative
+	strictness  detector.Strictness
+	warnings    []*grimoireErrors.GrimoireError // what permissive mode ignored
+	libraries   []string                        // names of the main entries of linked libraries
 }
 
 // NewParser creates a new parser
@@ -41,16 +56,49 @@ func Parse(symbols []*detector.Symbol, connections []detector.Connection) (*Prog
 
 // Parse performs the parsing
 func (p *Parser) Parse(symbols []*detector.Symbol, connections []detector.Connection) (*Program, error) {
+	p.detected = symbols
 	p.symbols = symbols
 	p.connections = connections
+	p.roles = make(map[*detector.Symbol]*SymbolRole)
+	p.roleCounts = make(map[RoleKind]int)
+	p.function = nil
+	p.errors = nil
+	p.warnings = nil
 
 	// Validate input
 	if len(symbols) == 0 {
-		return nil, grimoireErrors.NewError(grimoireErrors.SyntaxError, "No symbols to parse").
-			WithDetails("The input contains no detected symbols")
+		return nil, grimoireErrors.NewError(grimoireErrors.SyntaxError, i18n.T("parser.no_symbols")).
+			WithDetails(i18n.T("parser.no_symbols_detail"))
 	}
+	for i, symbol := range symbols {
+		if symbol == nil {
+			return nil, grimoireErrors.NewError(grimoireErrors.SyntaxError, i18n.T("parser.nil_symbol")).
+				WithDetails(i18n.Tf("parser.nil_symbol_detail", i))
+		}
+	}
+
+	// Unknown symbols are rejected or ignored, as the strictness asks
+	symbols, connections = p.unrecognized(symbols, connections)
+	if err := p.combinedErrors(); err != nil {
+		return nil, err
+	}
+	p.symbols = symbols
+	p.connections = connections
+
+	// Glyphs on the outer circle form the header, not the program
+	var glyphs []*detector.Symbol
+	p.symbols, glyphs = splitHeader(symbols)
+
+	// Rings inside it divide the program into stages
+	var rings []*detector.Symbol
+	p.symbols, rings = splitStageRings(p.symbols)
+
+	// Programs quoted inside squares are parsed on their own
+	p.symbols, p.quotes = splitQuotes(p.symbols)
+	symbols = p.symbols
 
 	// Build symbol graph
+	p.symbolGraph = make(map[int]*symbolNode)
 	p.buildSymbolGraph()
 
 	// Debug: print symbol graph
@@ -71,13 +119,19 @@ func (p *Parser) Parse(symbols []*detector.Symbol, connections []detector.Connec
 				}
 			}
 		}
+		fmt.Printf("Polar layout:\n")
+		for i, pos := range PolarLayout(p.symbols, DefaultSectors) {
+			fmt.Printf("[%d] %s: r=%.2f angle=%.0f° sector=%d\n",
+				i, p.symbols[i].Type, pos.Radius, pos.Angle*180/math.Pi, pos.Sector)
+		}
 	}
 
 	// Find outer circle
 	var outerCircle *detector.Symbol
-	for _, symbol := range symbols {
+	for i, symbol := range symbols {
 		if symbol.Type == detector.OuterCircle {
 			outerCircle = symbol
+			p.recordRole(p.symbolGraph[i], RoleProgramBoundary)
 			break
 		}
 	}
@@ -85,6 +139,8 @@ func (p *Parser) Parse(symbols []*detector.Symbol, connections []detector.Connec
 	if outerCircle == nil {
 		return nil, grimoireErrors.NoOuterCircleError()
 	}
+	header := p.parseHeader(outerCircle, glyphs)
+	p.mirrors = mirrorPairs(outerCircle, symbols)
 
 	// Find main entry (double circle)
 	var mainEntry *FunctionDef
@@ -133,22 +189,40 @@ func (p *Parser) Parse(symbols []*detector.Symbol, connections []detector.Connec
 		}
 	}
 
+	if mainEntry != nil {
+		mainEntry.Body = p.parseStages(rings, mainEntry.Body)
+	}
+
 	// Check if we have any errors
-	if len(p.errors) > 0 {
-		// Combine all errors into a single error message
-		errorMsg := "Parser encountered errors:"
-		for _, err := range p.errors {
-			errorMsg += "\n  - " + err.Error()
-		}
-		return nil, grimoireErrors.NewError(grimoireErrors.SyntaxError, errorMsg)
+	if err := p.combinedErrors(); err != nil {
+		return nil, err
 	}
 
-	return &Program{
+	program := &Program{
 		HasOuterCircle: true,
+		Header:         header,
 		MainEntry:      mainEntry,
 		Functions:      functions,
 		Globals:        globals,
-	}, nil
+	}
+	if role, ok := p.roles[outerCircle]; ok {
+		role.Node = program
+	}
+	return program, nil
+}
+
+// combinedErrors combines the errors found while parsing into a single
+// error, or returns nil when there are none
+func (p *Parser) combinedErrors() error {
+	if len(p.errors) == 0 {
+		return nil
+	}
+	errorMsg := i18n.T("parser.errors")
+	for _, err := range p.errors {
+		errorMsg += "\n  - " + err.Error()
+	}
+	return grimoireErrors.NewError(grimoireErrors.SyntaxError, errorMsg).
+		WithRelated(p.errors...)
 }
 
 // buildSymbolGraph builds a graph of symbols and their connections
@@ -255,18 +329,28 @@ func (p *Parser) parseFunctionDef(node *symbolNode, isMain bool) *FunctionDef {
 
 	node.visited = true
 
-	// Parse function body
-	body := p.parseStatementSequence(node.children)
+	if isMain {
+		p.recordRole(node, RoleMainEntry)
+	} else {
+		p.recordRole(node, RoleFunction)
+	}
 
+	// The function is defined before its body is parsed, so that calls in
+	// the body back to the function resolve to it
 	fn := &FunctionDef{
 		Name:       "",
 		Parameters: []*Parameter{},
-		Body:       body,
 		ReturnType: Void,
 		IsMain:     isMain,
 	}
-
 	node.astNode = fn
+	p.attachNode(node, fn)
+
+	// Parse function body
+	enclosing := p.function
+	p.function = p.roles[node.symbol]
+	fn.Body = p.parseStatementSequence(node.children)
+	p.function = enclosing
 	return fn
 }
 
@@ -274,14 +358,13 @@ func (p *Parser) parseFunctionDef(node *symbolNode, isMain bool) *FunctionDef {
 func (p *Parser) parseFunctions() []*FunctionDef {
 	functions := []*FunctionDef{}
 
+	// Functions already parsed because another function calls them are
+	// returned as they were parsed
 	for i, symbol := range p.symbols {
 		if symbol.Type == detector.Circle {
-			node := p.symbolGraph[i]
-			if !node.visited {
-				fn := p.parseFunctionDef(node, false)
-				if fn != nil {
-					functions = append(functions, fn)
-				}
+			fn := p.parseFunctionDef(p.symbolGraph[i], false)
+			if fn != nil {
+				functions = append(functions, fn)
 			}
 		}
 	}
@@ -331,7 +414,7 @@ func (p *Parser) parseStatementSequence(nodes []*symbolNode) []Statement {
 	stmts := []Statement{}
 
 	for _, node := range nodes {
-		if !node.visited {
+		if !node.visited || p.isCall(node) {
 			stmt := p.parseStatement(node)
 			if stmt != nil {
 				stmts = append(stmts, stmt)
@@ -344,6 +427,13 @@ func (p *Parser) parseStatementSequence(nodes []*symbolNode) []Statement {
 
 // parseStatement parses a statement from a symbol
 func (p *Parser) parseStatement(node *symbolNode) Statement {
+	if isBuiltinCall(node) {
+		return p.parseBuiltinStatement(node)
+	}
+	if p.isCall(node) {
+		return p.parseCall(node)
+	}
+
 	if node.visited && node.symbol.Type != detector.Star {
 		return nil
 	}
@@ -351,24 +441,36 @@ func (p *Parser) parseStatement(node *symbolNode) Statement {
 	node.visited = true
 	symbol := node.symbol
 
+	p.statements = append(p.statements, node)
+	defer func() { p.statements = p.statements[:len(p.statements)-1] }()
+
 	// Track parsing errors
 	defer func() {
 		if r := recover(); r != nil {
-			err := grimoireErrors.NewError(grimoireErrors.SyntaxError, fmt.Sprintf("Panic during parsing: %v", r)).
-				WithDetails(fmt.Sprintf("Symbol: %s at (%.0f, %.0f)", symbol.Type, symbol.Position.X, symbol.Position.Y))
+			err := grimoireErrors.NewError(grimoireErrors.SyntaxError, i18n.Tf("parser.panic", r)).
+				WithDetails(i18n.Tf("detail.symbol_type_at_position", symbol.Type, symbol.Position.X, symbol.Position.Y))
 			p.errors = append(p.errors, err)
 		}
 	}()
 
 	switch symbol.Type {
 	case detector.Star:
+		if left, right := p.mirroredInputs(node); left != nil {
+			return p.parseMirror(node, left, right)
+		}
 		return p.parseOutputStatement(node)
 	case detector.Triangle:
 		return p.parseIfStatement(node)
 	case detector.Pentagon:
 		return p.parseLoop(node)
 	case detector.Hexagon, detector.SixPointedStar:
+		// A hexagon around key-value pairs is a map, used as an expression
+		if symbol.Type == detector.Hexagon && len(p.mapEntries(node)) > 0 {
+			return nil
+		}
 		return p.parseParallelBlock(node)
+	case detector.Rectangle:
+		return p.parseBlock(node)
 	case detector.Square:
 		// Check if it's an assignment or part of expression
 		if hasOperatorChild(node) {
@@ -376,6 +478,11 @@ func (p *Parser) parseStatement(node *symbolNode) Statement {
 		}
 		return p.parseAssignment(node)
 	default:
+		// An equals sign that is not the condition of an if-branch asserts
+		// that its operands are equal
+		if symbol.Type == detector.Equal && !p.isCondition(node) {
+			return p.parseAssertion(node)
+		}
 		// Skip operators and other symbols
 		if isOperator(symbol.Type) {
 			// Mark children as visited
@@ -396,23 +503,30 @@ func (p *Parser) parseStatement(node *symbolNode) Statement {
 		// Report unexpected symbol
 		err := grimoireErrors.UnexpectedSymbolError(
 			string(symbol.Type), "statement symbol",
-			symbol.Position.X, symbol.Position.Y)
-		p.errors = append(p.errors, err)
+			symbol.Position.X, symbol.Position.Y).
+			WithRegion(symbol.Position.X, symbol.Position.Y, symbol.Size)
+		p.unexpected(err)
 		return nil
 	}
 }
 
 // parseOutputStatement parses an output statement (star)
 func (p *Parser) parseOutputStatement(node *symbolNode) *OutputStatement {
+	p.recordRole(node, RoleOutput)
+
 	// Find expression from parent
 	expr := p.parseExpressionFromParent(node)
-	return &OutputStatement{
+	stmt := &OutputStatement{
 		Value: expr,
 	}
+	p.attachNode(node, stmt)
+	return stmt
 }
 
 // parseIfStatement parses a conditional (triangle)
 func (p *Parser) parseIfStatement(node *symbolNode) *IfStatement {
+	p.recordRole(node, RoleIfBranch)
+
 	// Find condition
 	condition := p.parseCondition(node)
 
@@ -432,15 +546,19 @@ func (p *Parser) parseIfStatement(node *symbolNode) *IfStatement {
 		elseBranch = p.parseStatementSequence(rightChildren)
 	}
 
-	return &IfStatement{
+	stmt := &IfStatement{
 		Condition:  condition,
 		ThenBranch: thenBranch,
 		ElseBranch: elseBranch,
 	}
+	p.attachNode(node, stmt)
+	return stmt
 }
 
 // parseLoop parses a loop (pentagon)
 func (p *Parser) parseLoop(node *symbolNode) Statement {
+	p.recordRole(node, RoleLoop)
+
 	// Look for counter in parent
 	var counterNode *symbolNode
 	for _, parent := range p.getParents(node) {
@@ -449,6 +567,9 @@ func (p *Parser) parseLoop(node *symbolNode) Statement {
 			break
 		}
 	}
+	if counterNode != nil {
+		p.recordRole(counterNode, RoleLoopCounter)
+	}
 
 	body := p.parseStatementSequence(node.children)
 
@@ -459,13 +580,15 @@ func (p *Parser) parseLoop(node *symbolNode) Statement {
 		end := p.parseLiteral(counterNode)
 		step := &Literal{Value: 1, LiteralType: Integer}
 
-		return &ForLoop{
+		loop := &ForLoop{
 			Counter: counter,
 			Start:   start,
 			End:     end,
 			Step:    step,
 			Body:    body,
 		}
+		p.attachNode(node, loop)
+		return loop
 	}
 
 	// While loop
@@ -478,14 +601,18 @@ func (p *Parser) parseLoop(node *symbolNode) Statement {
 		return nil
 	}
 
-	return &WhileLoop{
+	loop := &WhileLoop{
 		Condition: condition,
 		Body:      body,
 	}
+	p.attachNode(node, loop)
+	return loop
 }
 
 // parseParallelBlock parses a parallel block (hexagon)
 func (p *Parser) parseParallelBlock(node *symbolNode) *ParallelBlock {
+	p.recordRole(node, RoleParallel)
+
 	// Group children by angle
 	groups := p.groupChildrenByAngle(node)
 
@@ -497,15 +624,34 @@ func (p *Parser) parseParallelBlock(node *symbolNode) *ParallelBlock {
 		}
 	}
 
-	return &ParallelBlock{
+	block := &ParallelBlock{
 		Branches: branches,
 	}
+	p.attachNode(node, block)
+	return block
+}
+
+// parseBlock parses a block (rectangle) of the statements connected below it
+func (p *Parser) parseBlock(node *symbolNode) *Block {
+	p.recordRole(node, RoleBlock)
+
+	block := &Block{
+		Body: p.parseStatementSequence(node.children),
+	}
+	p.attachNode(node, block)
+	return block
 }
 
 // parseAssignment parses an assignment statement
 func (p *Parser) parseAssignment(node *symbolNode) *Assignment {
-	varName := fmt.Sprintf("var_%p", node.symbol)
-	target := &Identifier{Name: varName}
+	p.recordRole(node, RoleAssignment)
+
+	varName := p.variableName(node.symbol)
+	stmt := &Assignment{
+		Target: &Identifier{Name: varName},
+	}
+	// Attach before parsing the value, which may reuse this symbol as a literal
+	p.attachNode(node, stmt)
 
 	// Look for value in children
 	var value Expression
@@ -517,14 +663,19 @@ func (p *Parser) parseAssignment(node *symbolNode) *Assignment {
 	}
 
 	if value == nil {
-		// Use literal from properties
-		value = p.parseLiteral(node)
+		// Use the list the symbol frames, the program it quotes, or the
+		// literal from properties
+		if node.symbol.ListFrame {
+			value = p.parseList(node)
+		} else if p.quotes[node.symbol] != nil {
+			value = p.parseQuoted(node)
+		} else {
+			value = p.parseLiteral(node)
+		}
 	}
 
-	return &Assignment{
-		Target: target,
-		Value:  value,
-	}
+	stmt.Value = value
+	return stmt
 }
 
 // parseExpression parses an expression from a symbol
@@ -535,18 +686,38 @@ func (p *Parser) parseExpression(node *symbolNode) Expression {
 		}
 	}
 
+	// A connection cycle leads back to an expression still being parsed
+	if node.parsing {
+		return nil
+	}
+	node.parsing = true
+	defer func() { node.parsing = false }()
+
 	node.visited = true
 	symbol := node.symbol
 
 	switch symbol.Type {
 	case detector.Square:
+		if symbol.ListFrame {
+			return p.parseList(node)
+		}
+		if p.quotes[symbol] != nil {
+			return p.parseQuoted(node)
+		}
 		return p.parseLiteral(node)
 	case detector.Circle:
 		return p.parseFunctionCall(node)
+	case detector.EightPointedStar:
+		return p.parseBuiltinCall(node)
 	case detector.Convergence, detector.Divergence, detector.Amplification, detector.Distribution:
 		return p.parseBinaryOp(node)
 	case detector.Transfer:
 		return p.parseAssignmentOp(node)
+	case detector.Hexagon:
+		if entries := p.mapEntries(node); len(entries) > 0 {
+			return p.parseMap(node, entries)
+		}
+		return nil
 	default:
 		return nil
 	}
@@ -554,9 +725,14 @@ func (p *Parser) parseExpression(node *symbolNode) Expression {
 
 // parseLiteral parses a literal from symbol properties
 func (p *Parser) parseLiteral(node *symbolNode) *Literal {
-	symbol := node.symbol
-	pattern := symbol.Pattern
+	p.recordRole(node, RoleLiteral)
+	lit := literalFromPattern(node.symbol.Pattern)
+	p.attachNode(node, lit)
+	return lit
+}
 
+// literalFromPattern maps an internal pattern to its literal value
+func literalFromPattern(pattern string) *Literal {
 	switch pattern {
 	case "dot":
 		return &Literal{Value: 1, LiteralType: Integer}
@@ -588,6 +764,7 @@ func (p *Parser) parseFunctionCall(node *symbolNode) *FunctionCall {
 	}
 
 	node.visited = true
+	p.recordRole(node, RoleFunctionCall)
 
 	// Get arguments from parents
 	arguments := []Expression{}
@@ -600,15 +777,18 @@ func (p *Parser) parseFunctionCall(node *symbolNode) *FunctionCall {
 		}
 	}
 
-	return &FunctionCall{
+	call := &FunctionCall{
 		Function:  &Identifier{Name: "print"},
 		Arguments: arguments,
 		DataType:  Void,
 	}
+	p.attachNode(node, call)
+	return call
 }
 
 // parseBinaryOp parses a binary operation
 func (p *Parser) parseBinaryOp(node *symbolNode) *BinaryOp {
+	p.recordRole(node, RoleOperator)
 	symbol := node.symbol
 	var op OperatorType
 	switch symbol.Type {
@@ -624,52 +804,69 @@ func (p *Parser) parseBinaryOp(node *symbolNode) *BinaryOp {
 		op = Add
 	}
 
-	// Find operands from parents
-	operands := []Expression{}
+	// Find operands from parents, each feeding a port
+	var inputs []*symbolNode
+	operands := make(map[*symbolNode]Expression)
 	for _, parent := range p.getParents(node) {
-		if parent.symbol.Type == detector.Square {
-			literal := p.parseLiteral(parent)
-			if literal != nil {
-				operands = append(operands, literal)
-			}
+		var expr Expression
+		if parent.symbol.Type == detector.Square && !parent.symbol.ListFrame {
+			expr = p.parseLiteral(parent)
 		} else {
-			expr := p.parseExpression(parent)
-			if expr != nil {
-				operands = append(operands, expr)
-			}
+			expr = p.parseExpression(parent)
+		}
+		if expr != nil {
+			inputs = append(inputs, parent)
+			operands[parent] = expr
 		}
 	}
+	ports := p.operandPorts(node, inputs)
 
 	// Validate operands
-	if len(operands) < 2 {
+	if len(inputs) < 2 {
 		err := grimoireErrors.NewError(grimoireErrors.UnbalancedExpression,
-			fmt.Sprintf("Binary operator %s requires two operands, found %d", symbol.Type, len(operands))).
-			WithDetails(fmt.Sprintf("At position (%.0f, %.0f)", symbol.Position.X, symbol.Position.Y)).
-			WithSuggestion("Ensure the operator is connected to two operand symbols")
+			i18n.Tf("parser.missing_operands", symbol.Type, len(inputs), unconnectedPorts(ports))).
+			WithDetails(i18n.Tf("detail.at_position", symbol.Position.X, symbol.Position.Y)).
+			WithSuggestion(i18n.T("parser.missing_operands_suggestion")).
+			WithRegion(symbol.Position.X, symbol.Position.Y, symbol.Size)
 		p.errors = append(p.errors, err)
 	}
 
-	// Ensure we have two operands
+	// Fill each port, defaulting unconnected ones to zero
 	var left Expression = &Literal{Value: 0, LiteralType: Integer}
 	var right Expression = &Literal{Value: 0, LiteralType: Integer}
-
-	if len(operands) > 0 {
-		left = operands[0]
-	}
-	if len(operands) > 1 {
-		right = operands[1]
+	for i, input := range inputs {
+		switch ports[i] {
+		case PortLeft:
+			left = operands[input]
+		case PortRight:
+			right = operands[input]
+		}
+		p.recordPort(input, ports[i])
 	}
 
-	return &BinaryOp{
+	binOp := &BinaryOp{
 		Left:     left,
 		Operator: op,
 		Right:    right,
 		DataType: Integer,
 	}
+	p.attachNode(node, binOp)
+	return binOp
 }
 
 // Helper functions
 
+// variableName returns the variable name for a data symbol. Names derive from
+// the symbol's position in the detector output so they are stable across runs.
+func (p *Parser) variableName(symbol *detector.Symbol) string {
+	for i, sym := range p.symbols {
+		if sym == symbol {
+			return fmt.Sprintf("var_%d", i)
+		}
+	}
+	return fmt.Sprintf("var_%p", symbol)
+}
+
 // getParents returns all parents of a node
 func (p *Parser) getParents(node *symbolNode) []*symbolNode {
 	parents := []*symbolNode{}
@@ -717,6 +914,7 @@ func (p *Parser) parseCondition(node *symbolNode) Expression {
 	// Look for comparison operators in children
 	for _, child := range node.children {
 		if isComparisonOperator(child.symbol.Type) {
+			p.recordRole(child, RoleCondition)
 			return p.parseBinaryOp(child)
 		}
 	}
@@ -724,6 +922,7 @@ func (p *Parser) parseCondition(node *symbolNode) Expression {
 	// Look in parents too
 	for _, parent := range p.getParents(node) {
 		if isComparisonOperator(parent.symbol.Type) {
+			p.recordRole(parent, RoleCondition)
 			return p.parseBinaryOp(parent)
 		}
 	}
@@ -744,7 +943,7 @@ func (p *Parser) parseAssignmentOp(node *symbolNode) Expression {
 	if len(parents) > 0 {
 		parent := parents[0]
 		if parent.symbol.Type == detector.Square {
-			varName := fmt.Sprintf("var_%p", parent.symbol)
+			varName := p.variableName(parent.symbol)
 			target = &Identifier{Name: varName}
 		}
 	}
@@ -864,10 +1063,7 @@ func (p *Parser) applyConnections() {
 		}
 
 		if fromIdx >= 0 && toIdx >= 0 {
-			fromNode := p.symbolGraph[fromIdx]
-			toNode := p.symbolGraph[toIdx]
-			fromNode.children = append(fromNode.children, toNode)
-			toNode.parent = fromNode
+			connectNodes(p.symbolGraph[fromIdx], p.symbolGraph[toIdx], conn)
 		}
 	}
 }
@@ -5,6 +5,7 @@ import (
 	"testing"
 
 	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/i18n"
 	"github.com/stretchr/testify/assert"
 )
 
@@ -1210,6 +1211,9 @@ func TestGroupChildrenByAngle(t *testing.T) {
 
 // TestParse tests the main Parse function
 func TestParse(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
 	tests := []struct {
 		name        string
 		symbols     []*detector.Symbol
@@ -1491,6 +1495,9 @@ func TestParseWithDebugOutput(t *testing.T) {
 
 // TestParseErrors tests error aggregation in Parse function
 func TestParseErrors(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
 	p := NewParser()
 	p.symbols = []*detector.Symbol{
 		{
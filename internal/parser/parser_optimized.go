@@ -6,6 +6,7 @@ import (
 	"sync"
 
 	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/i18n"
 )
 
 // OptimizedParser uses optimized algorithms for parsing
@@ -40,7 +41,7 @@ func (p *OptimizedParser) Parse(symbols []*detector.Symbol, connections []detect
 
 	// Validate input
 	if len(symbols) == 0 {
-		return nil, p.createError("No symbols to parse", "The input contains no detected symbols")
+		return nil, p.createError(i18n.T("parser.no_symbols"), i18n.T("parser.no_symbols_detail"))
 	}
 
 	// Build spatial index for fast lookups
@@ -159,10 +160,7 @@ func (p *OptimizedParser) applyConnectionsOptimized() {
 	for _, conn := range p.connections {
 		if fromIdx, ok := symbolToIndex[conn.From]; ok {
 			if toIdx, ok := symbolToIndex[conn.To]; ok {
-				fromNode := p.symbolGraph[fromIdx]
-				toNode := p.symbolGraph[toIdx]
-				fromNode.children = append(fromNode.children, toNode)
-				toNode.parent = fromNode
+				connectNodes(p.symbolGraph[fromIdx], p.symbolGraph[toIdx], conn)
 			}
 		}
 	}
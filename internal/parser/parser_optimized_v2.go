@@ -6,6 +6,7 @@ import (
 
 	"github.com/ayutaz/grimoire/internal/detector"
 	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
 )
 
 // OptimizedParserV2 is an improved version of the optimized parser
@@ -66,13 +67,17 @@ func (p *OptimizedParserV2) Parse(symbols []*detector.Symbol, connections []dete
 		return p.Parser.Parse(symbols, connections)
 	}
 
-	p.symbols = symbols
+	// Glyphs on the outer circle form the header, not the program
+	var glyphs, rings []*detector.Symbol
+	p.detected = symbols
+	p.symbols, glyphs = splitHeader(symbols)
+	p.symbols, rings = splitStageRings(p.symbols)
 	p.connections = connections
 
 	// Validate input
 	if len(symbols) == 0 {
-		return nil, grimoireErrors.NewError(grimoireErrors.SyntaxError, "No symbols to parse").
-			WithDetails("The input contains no detected symbols")
+		return nil, grimoireErrors.NewError(grimoireErrors.SyntaxError, i18n.T("parser.no_symbols")).
+			WithDetails(i18n.T("parser.no_symbols_detail"))
 	}
 
 	// Find outer circle first
@@ -86,9 +91,13 @@ func (p *OptimizedParserV2) Parse(symbols []*detector.Symbol, connections []dete
 		}
 	}
 	if outerCircle == nil {
-		return nil, grimoireErrors.NewError(grimoireErrors.SyntaxError, "No outer circle found").
-			WithDetails("All Grimoire programs must be enclosed in a magic circle")
+		return nil, grimoireErrors.NoOuterCircleError()
 	}
+	header := p.parseHeader(outerCircle.symbol, glyphs)
+	if err := p.combinedErrors(); err != nil {
+		return nil, err
+	}
+	p.mirrors = mirrorPairs(outerCircle.symbol, p.symbols)
 
 	// Build adaptive spatial index
 	p.buildAdaptiveSpatialIndex()
@@ -111,6 +120,8 @@ func (p *OptimizedParserV2) Parse(symbols []*detector.Symbol, connections []dete
 		return nil, err
 	}
 
+	program.Header = header
+	program.Globals = p.parseStages(rings, program.Globals)
 	return program, nil
 }
 
@@ -395,8 +406,8 @@ func (p *OptimizedParserV2) buildASTParallel(outerCircle *symbolNode) (*Program,
 	}
 
 	if len(topLevelNodes) == 0 {
-		return nil, grimoireErrors.NewError(grimoireErrors.SyntaxError, "Empty program").
-			WithDetails("No symbols found inside the outer circle")
+		return nil, grimoireErrors.NewError(grimoireErrors.SyntaxError, i18n.T("parser.empty_program")).
+			WithDetails(i18n.T("parser.empty_program_detail"))
 	}
 
 	// Process top-level nodes sequentially to avoid race conditions
@@ -439,10 +450,7 @@ func (p *OptimizedParserV2) applyConnections() {
 	for _, conn := range p.connections {
 		if fromIdx, ok := symbolToIndex[conn.From]; ok {
 			if toIdx, ok := symbolToIndex[conn.To]; ok {
-				fromNode := p.symbolGraph[fromIdx]
-				toNode := p.symbolGraph[toIdx]
-				fromNode.children = append(fromNode.children, toNode)
-				toNode.parent = fromNode
+				connectNodes(p.symbolGraph[fromIdx], p.symbolGraph[toIdx], conn)
 			}
 		}
 	}
@@ -4,6 +4,7 @@ import (
 	"testing"
 
 	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/i18n"
 	"github.com/stretchr/testify/assert"
 	"github.com/stretchr/testify/require"
 )
@@ -291,6 +292,41 @@ func TestParse_ParallelBlock(t *testing.T) {
 	require.NotEmpty(t, ast.MainEntry.Body)
 }
 
+// TestParse_Block tests that a rectangle groups the statements below it
+func TestParse_Block(t *testing.T) {
+	entry := &detector.Symbol{Type: detector.DoubleCircle, Position: detector.Position{X: 200, Y: 60}}
+	rectangle := &detector.Symbol{Type: detector.Rectangle, Position: detector.Position{X: 200, Y: 140}, Width: 80, Height: 40}
+	first := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 150, Y: 240}}
+	second := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 250, Y: 240}}
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 380},
+		entry, rectangle, first, second,
+	}
+	connections := []detector.Connection{
+		{From: entry, To: rectangle, ConnectionType: detector.ConnectionTypeSolid},
+		{From: rectangle, To: first, ConnectionType: detector.ConnectionTypeSolid},
+		{From: rectangle, To: second, ConnectionType: detector.ConnectionTypeSolid},
+	}
+
+	p := NewParser()
+	ast, err := p.Parse(symbols, connections)
+	require.NoError(t, err)
+	require.NotNil(t, ast.MainEntry)
+	require.Len(t, ast.MainEntry.Body, 1)
+
+	block, ok := ast.MainEntry.Body[0].(*Block)
+	require.True(t, ok, "statement is %T", ast.MainEntry.Body[0])
+	require.Len(t, block.Body, 2)
+	for _, stmt := range block.Body {
+		assert.IsType(t, &OutputStatement{}, stmt)
+	}
+
+	role := p.Roles()[2]
+	assert.Equal(t, RoleBlock, role.Kind)
+	assert.Equal(t, 1, role.Ordinal)
+	assert.Same(t, block, role.Node)
+}
+
 // TestParse_EdgeCases tests edge cases
 func TestParse_EdgeCases(t *testing.T) {
 	tests := []struct {
@@ -347,3 +383,63 @@ func TestParse_EdgeCases(t *testing.T) {
 		})
 	}
 }
+
+// TestParse_ConnectionCycle tests that operators connected in a cycle are
+// reported as errors instead of recursing forever
+func TestParse_ConnectionCycle(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	outer := &detector.Symbol{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 380}
+	first := &detector.Symbol{Type: detector.Convergence, Position: detector.Position{X: 180, Y: 150}}
+	second := &detector.Symbol{Type: detector.Amplification, Position: detector.Position{X: 220, Y: 150}}
+	star := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 200, Y: 250}}
+	symbols := []*detector.Symbol{outer, first, second, star}
+
+	tests := []struct {
+		name        string
+		connections []detector.Connection
+	}{
+		{
+			name: "Two operators",
+			connections: []detector.Connection{
+				{From: first, To: second},
+				{From: second, To: first},
+				{From: first, To: star},
+			},
+		},
+		{
+			name: "Operator connected to itself",
+			connections: []detector.Connection{
+				{From: first, To: first},
+				{From: first, To: star},
+			},
+		},
+	}
+
+	for _, tc := range tests {
+		t.Run(tc.name, func(t *testing.T) {
+			ast, err := Parse(symbols, tc.connections)
+			require.Error(t, err)
+			assert.Nil(t, ast)
+			assert.Contains(t, err.Error(), "requires two operands")
+		})
+	}
+}
+
+// TestParse_NilSymbol tests that a nil symbol is an error, not a panic
+func TestParse_NilSymbol(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 380},
+		nil,
+	}
+
+	ast, err := Parse(symbols, []detector.Connection{{From: symbols[0], To: nil}})
+
+	require.Error(t, err)
+	assert.Nil(t, ast)
+	assert.Contains(t, err.Error(), "nil symbol")
+}
@@ -0,0 +1,76 @@
+package parser
+
+import (
+	"math"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// DefaultSectors divides the outer circle like a clock face: sector 0 is
+// centered on the top and sector 3 on the right
+const DefaultSectors = 12
+
+// PolarPosition is where a symbol sits relative to the outer circle
+type PolarPosition struct {
+	// Radius is the distance from the center as a fraction of the radius of
+	// the outer circle
+	Radius float64
+	// Angle is measured clockwise from the top, in radians from 0 up to 2π
+	Angle float64
+	// Sector is the sector Angle falls in, counted clockwise from the one
+	// centered on the top
+	Sector int
+}
+
+// Polar returns the position of symbol relative to outerCircle, with its
+// angle quantized to the given number of equal sectors. Fewer than one
+// sector is taken as one.
+func Polar(outerCircle, symbol *detector.Symbol, sectors int) PolarPosition {
+	if sectors < 1 {
+		sectors = 1
+	}
+	p := Normalization(outerCircle).Point(symbol.Position)
+
+	var radius float64
+	if outerCircle.Radius() > 0 {
+		radius = math.Hypot(p.X, p.Y)
+	}
+	angle := clockwiseAngle(p.X, p.Y)
+	width := 2 * math.Pi / float64(sectors)
+	return PolarPosition{
+		Radius: radius,
+		Angle:  angle,
+		Sector: int(math.Floor(angle/width+0.5)) % sectors,
+	}
+}
+
+// PolarLayout returns the polar position of each symbol relative to the
+// first outer circle among them, in order, or nil when there is none
+func PolarLayout(symbols []*detector.Symbol, sectors int) []PolarPosition {
+	var outerCircle *detector.Symbol
+	for _, symbol := range symbols {
+		if symbol.Type == detector.OuterCircle {
+			outerCircle = symbol
+			break
+		}
+	}
+	if outerCircle == nil {
+		return nil
+	}
+
+	layout := make([]PolarPosition, len(symbols))
+	for i, symbol := range symbols {
+		layout[i] = Polar(outerCircle, symbol, sectors)
+	}
+	return layout
+}
+
+// clockwiseAngle returns the angle of the offset (dx, dy) in image
+// coordinates, clockwise from the top, from 0 up to 2π
+func clockwiseAngle(dx, dy float64) float64 {
+	angle := math.Atan2(dx, -dy)
+	if angle < 0 {
+		angle += 2 * math.Pi
+	}
+	return angle
+}
@@ -0,0 +1,55 @@
+package parser
+
+import (
+	"math"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+)
+
+func TestPolar(t *testing.T) {
+	// An outer circle of radius 100 around (200, 200)
+	outerCircle := &detector.Symbol{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 100 * math.Sqrt(math.Pi)}
+
+	tests := []struct {
+		name    string
+		x, y    float64
+		sectors int
+		radius  float64
+		angle   float64
+		sector  int
+	}{
+		{"top", 200, 150, DefaultSectors, 0.5, 0, 0},
+		{"right", 300, 200, DefaultSectors, 1, math.Pi / 2, 3},
+		{"bottom", 200, 280, DefaultSectors, 0.8, math.Pi, 6},
+		{"left", 150, 200, DefaultSectors, 0.5, 3 * math.Pi / 2, 9},
+		{"just left of top", 199, 100, DefaultSectors, math.Hypot(1, 100) / 100, 2*math.Pi - math.Atan2(1, 100), 0},
+		{"quadrants", 250, 160, 4, math.Hypot(50, 40) / 100, math.Atan2(50, 40), 1},
+		{"one sector", 150, 200, 0, 0.5, 3 * math.Pi / 2, 0},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			pos := Polar(outerCircle, &detector.Symbol{Position: detector.Position{X: tt.x, Y: tt.y}}, tt.sectors)
+			assert.InDelta(t, tt.radius, pos.Radius, 1e-9)
+			assert.InDelta(t, tt.angle, pos.Angle, 1e-9)
+			assert.Equal(t, tt.sector, pos.Sector)
+		})
+	}
+}
+
+func TestPolarLayout(t *testing.T) {
+	symbols := []*detector.Symbol{
+		{Type: detector.Star, Position: detector.Position{X: 200, Y: 250}},
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 100 * math.Sqrt(math.Pi)},
+	}
+
+	layout := PolarLayout(symbols, DefaultSectors)
+	assert.Len(t, layout, 2)
+	assert.Equal(t, 6, layout[0].Sector)
+	assert.InDelta(t, 0.5, layout[0].Radius, 1e-9)
+	assert.Zero(t, layout[1].Radius, "the outer circle sits at its own center")
+
+	assert.Nil(t, PolarLayout(symbols[:1], DefaultSectors), "no layout without an outer circle")
+}
@@ -0,0 +1,118 @@
+package parser
+
+import (
+	"math"
+	"sort"
+	"strings"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/i18n"
+)
+
+// Ports of a binary operator. Subtraction and division depend on operand
+// order, so each operand feeds the port on the side of the operator where
+// its line arrives.
+const (
+	PortLeft  = "left"
+	PortRight = "right"
+)
+
+// binaryPorts are the ports of a binary operator in operand order
+var binaryPorts = []string{PortLeft, PortRight}
+
+// connectNodes records conn from one node to another in the symbol graph
+func connectNodes(from, to *symbolNode, conn detector.Connection) {
+	from.children = append(from.children, to)
+	to.parent = from
+	if conn.ToAnchor != nil {
+		if to.inputs == nil {
+			to.inputs = make(map[*symbolNode]*detector.Anchor)
+		}
+		to.inputs[from] = conn.ToAnchor
+	}
+}
+
+// operandSide returns where the line from operand arrives at operator, from
+// -1 at the far left to 1 at the far right. Connections without an anchor
+// arrive from the direction of the operand symbol.
+func operandSide(operator, operand *symbolNode) float64 {
+	if anchor := operator.inputs[operand]; anchor != nil {
+		return math.Cos(anchor.Angle)
+	}
+	dx := operand.symbol.Position.X - operator.symbol.Position.X
+	dy := operand.symbol.Position.Y - operator.symbol.Position.Y
+	if dx == 0 && dy == 0 {
+		return 0
+	}
+	return dx / math.Hypot(dx, dy)
+}
+
+// operandPorts orders the operands of operator from left to right by where
+// they arrive, ties in detector order, and returns the port each feeds.
+// A single operand feeds the port on its side, left when it arrives
+// straight from above or below.
+func (p *Parser) operandPorts(operator *symbolNode, operands []*symbolNode) []string {
+	index := make(map[*symbolNode]int, len(operands))
+	for _, operand := range operands {
+		index[operand] = p.symbolIndex(operand.symbol)
+	}
+	sort.SliceStable(operands, func(i, j int) bool {
+		si, sj := operandSide(operator, operands[i]), operandSide(operator, operands[j])
+		if si != sj {
+			return si < sj
+		}
+		return index[operands[i]] < index[operands[j]]
+	})
+
+	if len(operands) == 1 {
+		if operandSide(operator, operands[0]) > 0 {
+			return []string{PortRight}
+		}
+		return []string{PortLeft}
+	}
+	ports := make([]string, len(operands))
+	for i := range operands {
+		if i < len(binaryPorts) {
+			ports[i] = binaryPorts[i]
+		}
+	}
+	return ports
+}
+
+// unconnectedPorts describes the binary operator ports missing from ports
+func unconnectedPorts(ports []string) string {
+	var missing []string
+	for _, port := range binaryPorts {
+		connected := false
+		for _, p := range ports {
+			connected = connected || p == port
+		}
+		if !connected {
+			missing = append(missing, port)
+		}
+	}
+	for i, port := range missing {
+		missing[i] = i18n.T("parser.port_" + port)
+	}
+	if len(missing) == 1 {
+		return i18n.Tf("parser.port_unconnected", missing[0])
+	}
+	return i18n.Tf("parser.ports_unconnected", strings.Join(missing, i18n.T("parser.ports_and")))
+}
+
+// recordPort notes on the role of operand the operator port it feeds
+func (p *Parser) recordPort(operand *symbolNode, port string) {
+	if role, ok := p.roles[operand.symbol]; ok && role.Port == "" {
+		role.Port = port
+	}
+}
+
+// symbolIndex returns the position of symbol in the detector output
+func (p *Parser) symbolIndex(symbol *detector.Symbol) int {
+	for i, sym := range p.symbols {
+		if sym == symbol {
+			return i
+		}
+	}
+	return len(p.symbols)
+}
@@ -0,0 +1,106 @@
+package parser
+
+import (
+	"math"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// subtraction returns a program that outputs one square minus another.
+// one holds 1 and sits left of the operator, two holds 2 and sits right.
+func subtraction(oneAnchor, twoAnchor *detector.Anchor) ([]*detector.Symbol, []detector.Connection) {
+	one := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 150, Y: 100}, Pattern: detector.PatternDot}
+	two := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 250, Y: 100}, Pattern: detector.PatternDoubleDot}
+	minus := &detector.Symbol{Type: detector.Divergence, Position: detector.Position{X: 200, Y: 180}, Size: 30}
+	star := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 200, Y: 260}}
+
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180},
+		one, two, minus, star,
+	}
+	connections := []detector.Connection{
+		{From: one, To: minus, ConnectionType: detector.ConnectionTypeSolid, ToAnchor: oneAnchor},
+		{From: two, To: minus, ConnectionType: detector.ConnectionTypeSolid, ToAnchor: twoAnchor},
+		{From: minus, To: star, ConnectionType: detector.ConnectionTypeSolid},
+	}
+	return symbols, connections
+}
+
+// outputOperation returns the binary operation output by program
+func outputOperation(t *testing.T, program *Program) *BinaryOp {
+	t.Helper()
+	require.NotNil(t, program.MainEntry)
+	require.Len(t, program.MainEntry.Body, 1)
+	output, ok := program.MainEntry.Body[0].(*OutputStatement)
+	require.True(t, ok)
+	op, ok := output.Value.(*BinaryOp)
+	require.True(t, ok, "output value is %T", output.Value)
+	return op
+}
+
+func TestOperandPortsFromPositions(t *testing.T) {
+	// Without anchors, operands feed the port on their side. Parse several
+	// times, since the symbol graph is walked in map order.
+	for i := 0; i < 20; i++ {
+		program, err := Parse(subtraction(nil, nil))
+		require.NoError(t, err)
+
+		op := outputOperation(t, program)
+		assert.Equal(t, Subtract, op.Operator)
+		assert.Equal(t, 1, op.Left.(*Literal).Value)
+		assert.Equal(t, 2, op.Right.(*Literal).Value)
+	}
+}
+
+func TestOperandPortsFromAnchors(t *testing.T) {
+	// The lines cross over, so each arrives on the side away from its square
+	program, err := Parse(subtraction(&detector.Anchor{Angle: -math.Pi / 4}, &detector.Anchor{Angle: -3 * math.Pi / 4}))
+	require.NoError(t, err)
+
+	op := outputOperation(t, program)
+	assert.Equal(t, 2, op.Left.(*Literal).Value)
+	assert.Equal(t, 1, op.Right.(*Literal).Value)
+}
+
+func TestOperandPortRoles(t *testing.T) {
+	p := NewParser()
+	_, err := p.Parse(subtraction(nil, nil))
+	require.NoError(t, err)
+
+	roles := p.Roles()
+	assert.Equal(t, PortLeft, roles[1].Port)
+	assert.Equal(t, PortRight, roles[2].Port)
+	assert.Empty(t, roles[3].Port, "the operator feeds no port")
+}
+
+func TestUnconnectedPort(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	symbols, connections := subtraction(nil, &detector.Anchor{Angle: -math.Pi / 4})
+	_, err := Parse(symbols, connections[1:])
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "requires two operands, found 1; its left port is unconnected")
+
+	// An operator with no inputs names both ports
+	_, err = Parse(symbols, connections[2:])
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "its left and right ports are unconnected")
+}
+
+func TestOperandSide(t *testing.T) {
+	operator := &symbolNode{symbol: &detector.Symbol{Position: detector.Position{X: 100, Y: 100}}}
+	above := &symbolNode{symbol: &detector.Symbol{Position: detector.Position{X: 100, Y: 0}}}
+	left := &symbolNode{symbol: &detector.Symbol{Position: detector.Position{X: 0, Y: 100}}}
+
+	assert.InDelta(t, 0, operandSide(operator, above), 1e-9)
+	assert.InDelta(t, -1, operandSide(operator, left), 1e-9)
+
+	// An anchor takes precedence over the operand's position
+	operator.inputs = map[*symbolNode]*detector.Anchor{left: {Angle: 0}}
+	assert.InDelta(t, 1, operandSide(operator, left), 1e-9)
+}
@@ -0,0 +1,150 @@
+package parser
+
+import (
+	"math"
+	"sort"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+)
+
+// A miniature magic circle drawn inside a square quotes a program: instead
+// of a literal, the square holds the program drawn in the circle, which is
+// parsed on its own with the circle as its outer circle and can be
+// evaluated later. Quoted programs can themselves quote others.
+
+// MaxQuoteDepth is how many quoted programs can nest one inside another.
+// Evaluating quoted programs is bounded by the same depth.
+const MaxQuoteDepth = 4
+
+// quotedCircle returns the largest circle drawn wholly inside square, which
+// makes the square a quote, or nil when there is none
+func quotedCircle(symbols []*detector.Symbol, square *detector.Symbol) *detector.Symbol {
+	if square.Type != detector.Square || square.ListFrame {
+		return nil
+	}
+	var largest *detector.Symbol
+	for _, symbol := range symbols {
+		if symbol.Type != detector.Circle {
+			continue
+		}
+		reach := symbol.Radius()
+		if math.Abs(symbol.Position.X-square.Position.X)+reach > square.Size/2 ||
+			math.Abs(symbol.Position.Y-square.Position.Y)+reach > square.Size/2 {
+			continue
+		}
+		if largest == nil || symbol.Size > largest.Size {
+			largest = symbol
+		}
+	}
+	return largest
+}
+
+// splitQuotes separates the symbols of quoted programs from the other
+// symbols of the program. Each quote maps to the symbols of its program,
+// its circle first. A quote inside another belongs to the program of the
+// outer one, so quotes are claimed from the largest square down.
+func splitQuotes(symbols []*detector.Symbol) (program []*detector.Symbol, quotes map[*detector.Symbol][]*detector.Symbol) {
+	var squares []*detector.Symbol
+	for _, symbol := range symbols {
+		if quotedCircle(symbols, symbol) != nil {
+			squares = append(squares, symbol)
+		}
+	}
+	if len(squares) == 0 {
+		return symbols, nil
+	}
+	sort.SliceStable(squares, func(i, j int) bool {
+		return squares[i].Size > squares[j].Size
+	})
+
+	quotes = make(map[*detector.Symbol][]*detector.Symbol)
+	quoted := make(map[*detector.Symbol]bool)
+	for _, square := range squares {
+		if quoted[square] {
+			continue
+		}
+		circle := quotedCircle(symbols, square)
+		members := []*detector.Symbol{circle}
+		quoted[circle] = true
+		for _, symbol := range symbols {
+			if symbol == square || quoted[symbol] {
+				continue
+			}
+			if math.Hypot(symbol.Position.X-circle.Position.X, symbol.Position.Y-circle.Position.Y) < circle.Radius() {
+				members = append(members, symbol)
+				quoted[symbol] = true
+			}
+		}
+		quotes[square] = members
+	}
+
+	program = make([]*detector.Symbol, 0, len(symbols))
+	for _, symbol := range symbols {
+		if !quoted[symbol] {
+			program = append(program, symbol)
+		}
+	}
+	return program, quotes
+}
+
+// parseQuoted parses the program quoted by node (a square around a circle)
+func (p *Parser) parseQuoted(node *symbolNode) *Quoted {
+	p.recordRole(node, RoleQuote)
+	quoted := &Quoted{}
+	p.attachNode(node, quoted)
+
+	symbol := node.symbol
+	if p.depth >= MaxQuoteDepth {
+		err := grimoireErrors.NewError(grimoireErrors.SyntaxError, i18n.Tf("parser.quote_too_deep", MaxQuoteDepth)).
+			WithDetails(i18n.Tf("detail.at_position", symbol.Position.X, symbol.Position.Y)).
+			WithSuggestion(i18n.T("parser.quote_too_deep_suggestion")).
+			WithRegion(symbol.Position.X, symbol.Position.Y, symbol.Size)
+		p.errors = append(p.errors, err)
+		return quoted
+	}
+
+	// The circle of the quote is the outer circle of its program
+	members := p.quotes[symbol]
+	circle := *members[0]
+	circle.Type = detector.OuterCircle
+	local := map[*detector.Symbol]*detector.Symbol{members[0]: &circle}
+	symbols := []*detector.Symbol{&circle}
+	for _, member := range members[1:] {
+		local[member] = member
+		symbols = append(symbols, member)
+	}
+	var connections []detector.Connection
+	for _, conn := range p.connections {
+		from, to := local[conn.From], local[conn.To]
+		if from != nil && to != nil {
+			conn.From, conn.To = from, to
+			connections = append(connections, conn)
+		}
+	}
+
+	sub := NewParser()
+	sub.depth = p.depth + 1
+	sub.strictness = p.strictness
+	program, err := sub.Parse(symbols, connections)
+	if err != nil {
+		p.errors = append(p.errors, err)
+	}
+	quoted.Program = program
+	p.warnings = append(p.warnings, sub.warnings...)
+
+	// The symbols of the quoted program keep the roles they have in it
+	for _, member := range members {
+		role, ok := sub.roles[local[member]]
+		if !ok || p.roles[member] != nil {
+			continue
+		}
+		role.Symbol = member
+		if role.Context == nil {
+			role.Context = p.roles[symbol]
+		}
+		p.roles[member] = role
+	}
+	return quoted
+}
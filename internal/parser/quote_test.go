@@ -0,0 +1,144 @@
+package parser
+
+import (
+	"math"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/i18n"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// quote returns a square centered at (x, y) around a circle of the given
+// radius, which quotes the program drawn in the circle
+func quote(x, y, side, radius float64) (square, circle *detector.Symbol) {
+	square = &detector.Symbol{Type: detector.Square, Position: detector.Position{X: x, Y: y}, Size: side, Pattern: detector.PatternEmpty}
+	circle = &detector.Symbol{Type: detector.Circle, Position: detector.Position{X: x, Y: y}, Size: radius * math.Sqrt(math.Pi)}
+	return square, circle
+}
+
+// quotedProgram returns a program printing a quoted program, which prints
+// "Hello, World!" when evaluated
+func quotedProgram() ([]*detector.Symbol, []detector.Connection) {
+	square, circle := quote(200, 140, 160, 60)
+	entry := &detector.Symbol{Type: detector.DoubleCircle, Position: detector.Position{X: 200, Y: 110}, Size: 20}
+	inner := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 200, Y: 170}, Size: 20}
+	star := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 200, Y: 300}, Size: 20}
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180 * math.Sqrt(math.Pi)},
+		square, circle, entry, inner, star,
+	}
+	connections := []detector.Connection{
+		{From: square, To: star, ConnectionType: detector.ConnectionTypeSolid},
+		{From: entry, To: inner, ConnectionType: detector.ConnectionTypeSolid},
+	}
+	return symbols, connections
+}
+
+func TestParseQuoted(t *testing.T) {
+	symbols, connections := quotedProgram()
+
+	p := NewParser()
+	program, err := p.Parse(symbols, connections)
+	require.NoError(t, err)
+	require.Len(t, program.MainEntry.Body, 1)
+	output, ok := program.MainEntry.Body[0].(*OutputStatement)
+	require.True(t, ok)
+	quoted, ok := output.Value.(*Quoted)
+	require.True(t, ok, "output of %T", output.Value)
+
+	// The quoted program is parsed on its own, its circle as the outer one
+	require.NotNil(t, quoted.Program)
+	assert.True(t, quoted.Program.HasOuterCircle)
+	require.NotNil(t, quoted.Program.MainEntry)
+	require.Len(t, quoted.Program.MainEntry.Body, 1)
+	inner, ok := quoted.Program.MainEntry.Body[0].(*OutputStatement)
+	require.True(t, ok)
+	assert.Equal(t, "Hello, World!", inner.Value.(*Literal).Value)
+	assert.Empty(t, program.Functions, "the circle of the quote is not a function")
+
+	roles := p.Roles()
+	assert.Equal(t, RoleQuote, roles[1].Kind)
+	assert.Same(t, quoted, roles[1].Node)
+	assert.Equal(t, RoleProgramBoundary, roles[2].Kind)
+	assert.Equal(t, RoleMainEntry, roles[3].Kind)
+	assert.Equal(t, RoleOutput, roles[4].Kind)
+	assert.Same(t, inner, roles[4].Node)
+	assert.Equal(t, RoleOutput, roles[5].Kind)
+}
+
+// evalProgram returns a program evaluating a quoted program, which prints
+// "Hello, World!": the quote is drawn into an empty eight-pointed star below
+// the main entry
+func evalProgram() ([]*detector.Symbol, []detector.Connection) {
+	square, circle := quote(200, 140, 160, 60)
+	entry := &detector.Symbol{Type: detector.DoubleCircle, Position: detector.Position{X: 200, Y: 110}, Size: 20}
+	inner := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 200, Y: 170}, Size: 20}
+	main := &detector.Symbol{Type: detector.DoubleCircle, Position: detector.Position{X: 80, Y: 300}, Size: 20}
+	eval := &detector.Symbol{Type: detector.EightPointedStar, Position: detector.Position{X: 200, Y: 300}, Size: 20, Pattern: detector.PatternEmpty}
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180 * math.Sqrt(math.Pi)},
+		square, circle, entry, inner, main, eval,
+	}
+	connections := []detector.Connection{
+		{From: entry, To: inner, ConnectionType: detector.ConnectionTypeSolid},
+		{From: main, To: eval, ConnectionType: detector.ConnectionTypeSolid},
+		{From: square, To: eval, ConnectionType: detector.ConnectionTypeSolid},
+	}
+	return symbols, connections
+}
+
+func TestParseEval(t *testing.T) {
+	symbols, connections := evalProgram()
+
+	p := NewParser()
+	program, err := p.Parse(symbols, connections)
+	require.NoError(t, err)
+	assert.Empty(t, program.Functions, "the eval star is not a function")
+	require.NotNil(t, program.MainEntry)
+	assert.Equal(t, RoleMainEntry, p.Roles()[5].Kind, "the main entry is the one outside the quote")
+	require.Len(t, program.MainEntry.Body, 1)
+
+	statement, ok := program.MainEntry.Body[0].(*ExpressionStatement)
+	require.True(t, ok, "statement %T", program.MainEntry.Body[0])
+	call, ok := statement.Expression.(*FunctionCall)
+	require.True(t, ok)
+	assert.Equal(t, builtinEval, call.Function.Name)
+	require.Len(t, call.Arguments, 1)
+	quoted, ok := call.Arguments[0].(*Quoted)
+	require.True(t, ok, "argument %T", call.Arguments[0])
+	require.NotNil(t, quoted.Program)
+	require.NotNil(t, quoted.Program.MainEntry)
+	assert.Len(t, quoted.Program.MainEntry.Body, 1)
+	assert.Equal(t, RoleFunctionCall, p.Roles()[6].Kind)
+}
+
+func TestParseQuotedTooDeep(t *testing.T) {
+	defer i18n.SetLanguage(i18n.GetLanguage())
+	i18n.SetLanguage(i18n.English)
+
+	symbols, connections := quotedProgram()
+
+	p := NewParser()
+	p.depth = MaxQuoteDepth
+	_, err := p.Parse(symbols, connections)
+	require.Error(t, err)
+	assert.Contains(t, err.Error(), "nested")
+}
+
+func TestSplitQuotes(t *testing.T) {
+	outer, outerCircle := quote(200, 200, 200, 90)
+	inner, innerCircle := quote(200, 200, 80, 30)
+	inside := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 200, Y: 140}}
+	outside := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 200, Y: 350}}
+	// A circle beside a square does not quote it
+	beside, besideCircle := quote(400, 200, 60, 20)
+	besideCircle.Position.X = 440
+
+	program, quotes := splitQuotes([]*detector.Symbol{inner, innerCircle, outer, outerCircle, inside, outside, beside, besideCircle})
+	assert.Equal(t, []*detector.Symbol{outer, outside, beside, besideCircle}, program)
+	require.Len(t, quotes, 1)
+	// A quote inside another belongs to the program of the outer one
+	assert.Equal(t, []*detector.Symbol{outerCircle, inner, innerCircle, inside}, quotes[outer])
+}
@@ -0,0 +1,108 @@
+package parser
+
+import (
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// RoleKind describes the semantic role the parser assigned to a symbol
+type RoleKind string
+
+// Symbol roles
+const (
+	RoleProgramBoundary RoleKind = "program_boundary"
+	RoleHeader          RoleKind = "header"
+	RoleMainEntry       RoleKind = "main_entry"
+	RoleFunction        RoleKind = "function"
+	RoleOutput          RoleKind = "output"
+	RoleIfBranch        RoleKind = "if_branch"
+	RoleCondition       RoleKind = "condition"
+	RoleLoop            RoleKind = "loop"
+	RoleLoopCounter     RoleKind = "loop_counter"
+	RoleParallel        RoleKind = "parallel"
+	RoleBlock           RoleKind = "block"
+	RoleStage           RoleKind = "stage"
+	RoleAssignment      RoleKind = "assignment"
+	RoleLiteral         RoleKind = "literal"
+	RoleList            RoleKind = "list"
+	RoleMap             RoleKind = "map"
+	RoleQuote           RoleKind = "quote"
+	RoleAssertion       RoleKind = "assertion"
+	RoleOperator        RoleKind = "operator"
+	RoleFunctionCall    RoleKind = "function_call"
+	RoleUnused          RoleKind = "unused"
+)
+
+// SymbolRole records how the parser interpreted a single detected symbol.
+// It maps symbols in the image to the AST nodes built from them.
+type SymbolRole struct {
+	// Symbol is the detected symbol
+	Symbol *detector.Symbol
+	// Index is the position of the symbol in the detector output
+	Index int
+	// Kind is the role assigned to the symbol
+	Kind RoleKind
+	// Ordinal numbers roles of the same kind in parse order, starting at 1
+	Ordinal int
+	// Node is the AST node built from the symbol, if any
+	Node ASTNode
+	// Context is the role of the enclosing statement (e.g. the if-branch
+	// a condition belongs to), or nil at the top level
+	Context *SymbolRole
+	// Function is the role of the function or main entry whose body
+	// contains the symbol, or nil outside any function
+	Function *SymbolRole
+	// Port is the operator port an operand feeds (PortLeft or PortRight),
+	// or empty for symbols that are not binary operands
+	Port string
+}
+
+// Roles returns the role of every symbol passed to the last Parse call, in
+// detector order. Symbols the parser did not use are reported as RoleUnused.
+// Roles are available even when Parse returned an error.
+func (p *Parser) Roles() []SymbolRole {
+	roles := make([]SymbolRole, 0, len(p.detected))
+	for i, symbol := range p.detected {
+		if role, ok := p.roles[symbol]; ok {
+			role.Index = i
+			roles = append(roles, *role)
+			continue
+		}
+		roles = append(roles, SymbolRole{Symbol: symbol, Index: i, Kind: RoleUnused})
+	}
+	return roles
+}
+
+// recordRole assigns a role to the symbol of node. The first role recorded
+// for a symbol wins, so callers that know more (e.g. a loop claiming its
+// counter) must record before delegating to generic expression parsing.
+func (p *Parser) recordRole(node *symbolNode, kind RoleKind) {
+	if p.roles == nil {
+		p.roles = make(map[*detector.Symbol]*SymbolRole)
+		p.roleCounts = make(map[RoleKind]int)
+	}
+	if _, exists := p.roles[node.symbol]; exists {
+		return
+	}
+
+	p.roleCounts[kind]++
+	role := &SymbolRole{
+		Symbol:   node.symbol,
+		Kind:     kind,
+		Ordinal:  p.roleCounts[kind],
+		Function: p.function,
+	}
+	for i := len(p.statements) - 1; i >= 0; i-- {
+		if enclosing := p.statements[i]; enclosing != node {
+			role.Context = p.roles[enclosing.symbol]
+			break
+		}
+	}
+	p.roles[node.symbol] = role
+}
+
+// attachNode links the AST node built from a symbol to its recorded role
+func (p *Parser) attachNode(node *symbolNode, astNode ASTNode) {
+	if role, ok := p.roles[node.symbol]; ok && role.Node == nil {
+		role.Node = astNode
+	}
+}
@@ -0,0 +1,121 @@
+package parser
+
+import (
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParserRoles(t *testing.T) {
+	outer := &detector.Symbol{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180}
+	entry := &detector.Symbol{Type: detector.DoubleCircle, Position: detector.Position{X: 200, Y: 60}}
+	branch := &detector.Symbol{Type: detector.Triangle, Position: detector.Position{X: 200, Y: 120}}
+	cond := &detector.Symbol{Type: detector.Equal, Position: detector.Position{X: 220, Y: 150}}
+	output := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 150, Y: 180}}
+	stray := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 320, Y: 320}, Pattern: detector.PatternDot}
+	left := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 240, Y: 100}, Pattern: detector.PatternDot}
+	right := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 280, Y: 100}, Pattern: detector.PatternDoubleDot}
+
+	symbols := []*detector.Symbol{outer, entry, branch, cond, output, stray, left, right}
+	connections := []detector.Connection{
+		{From: entry, To: branch},
+		{From: branch, To: cond},
+		{From: branch, To: output},
+		{From: left, To: cond},
+		{From: right, To: cond},
+	}
+
+	p := NewParser()
+	program, err := p.Parse(symbols, connections)
+	require.NoError(t, err)
+
+	roles := p.Roles()
+	require.Len(t, roles, len(symbols))
+
+	for i, role := range roles {
+		assert.Equal(t, i, role.Index)
+		assert.Same(t, symbols[i], role.Symbol)
+	}
+
+	assert.Equal(t, RoleProgramBoundary, roles[0].Kind)
+	assert.Same(t, program, roles[0].Node)
+
+	assert.Equal(t, RoleMainEntry, roles[1].Kind)
+	assert.Same(t, program.MainEntry, roles[1].Node)
+
+	assert.Equal(t, RoleIfBranch, roles[2].Kind)
+	assert.Equal(t, 1, roles[2].Ordinal)
+	assert.IsType(t, &IfStatement{}, roles[2].Node)
+	require.NotNil(t, roles[2].Function)
+	assert.Same(t, entry, roles[2].Function.Symbol)
+	assert.Nil(t, roles[1].Function)
+
+	assert.Equal(t, RoleCondition, roles[3].Kind)
+	require.NotNil(t, roles[3].Context)
+	assert.Equal(t, RoleIfBranch, roles[3].Context.Kind)
+	assert.IsType(t, &BinaryOp{}, roles[3].Node)
+
+	assert.Equal(t, RoleOutput, roles[4].Kind)
+	require.NotNil(t, roles[4].Context)
+	assert.Equal(t, RoleIfBranch, roles[4].Context.Kind)
+	require.NotNil(t, roles[4].Function)
+	assert.Equal(t, RoleMainEntry, roles[4].Function.Kind)
+
+	assert.Equal(t, RoleUnused, roles[5].Kind)
+	assert.Nil(t, roles[5].Node)
+
+	for _, operand := range roles[6:] {
+		assert.Equal(t, RoleLiteral, operand.Kind)
+		assert.IsType(t, &Literal{}, operand.Node)
+	}
+}
+
+func TestParserRoles_LoopCounter(t *testing.T) {
+	outer := &detector.Symbol{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180}
+	entry := &detector.Symbol{Type: detector.DoubleCircle, Position: detector.Position{X: 200, Y: 60}}
+	counter := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 200, Y: 100}, Pattern: detector.PatternTripleDot}
+	loop := &detector.Symbol{Type: detector.Pentagon, Position: detector.Position{X: 200, Y: 150}}
+	output := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 200, Y: 220}}
+
+	symbols := []*detector.Symbol{outer, entry, counter, loop, output}
+	connections := []detector.Connection{
+		{From: entry, To: loop},
+		{From: counter, To: loop},
+		{From: loop, To: output},
+	}
+
+	p := NewParser()
+	_, err := p.Parse(symbols, connections)
+	require.NoError(t, err)
+
+	roles := p.Roles()
+	require.Len(t, roles, len(symbols))
+
+	assert.Equal(t, RoleLoopCounter, roles[2].Kind)
+	require.NotNil(t, roles[2].Context)
+	assert.Equal(t, RoleLoop, roles[2].Context.Kind)
+	lit, ok := roles[2].Node.(*Literal)
+	require.True(t, ok)
+	assert.Equal(t, 3, lit.Value)
+
+	assert.Equal(t, RoleLoop, roles[3].Kind)
+	assert.IsType(t, &ForLoop{}, roles[3].Node)
+
+	assert.Equal(t, RoleOutput, roles[4].Kind)
+}
+
+func TestParserRoles_PartialOnError(t *testing.T) {
+	symbols := []*detector.Symbol{
+		{Type: detector.Star, Position: detector.Position{X: 100, Y: 100}},
+	}
+
+	p := NewParser()
+	_, err := p.Parse(symbols, nil)
+	require.Error(t, err)
+
+	roles := p.Roles()
+	require.Len(t, roles, 1)
+	assert.Equal(t, RoleUnused, roles[0].Kind)
+}
@@ -0,0 +1,91 @@
+package parser
+
+import (
+	"math"
+	"sort"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// Rings drawn inside the outer circle around its center divide the program
+// into bands. Each band is a stage, and the stages run one after another
+// from the innermost band outward.
+
+// splitStageRings separates the stage rings from the other symbols of the
+// program
+func splitStageRings(symbols []*detector.Symbol) (program, rings []*detector.Symbol) {
+	program = make([]*detector.Symbol, 0, len(symbols))
+	for _, symbol := range symbols {
+		if symbol.StageRing {
+			rings = append(rings, symbol)
+		} else {
+			program = append(program, symbol)
+		}
+	}
+	return program, rings
+}
+
+// parseStages groups top-level statements into stages by the band their
+// symbols lie in, innermost first. Statements keep their order within a
+// stage, and bands without statements are left out. Without rings the
+// statements are returned as they are.
+func (p *Parser) parseStages(rings []*detector.Symbol, stmts []Statement) []Statement {
+	if len(rings) == 0 {
+		return stmts
+	}
+
+	rings = append([]*detector.Symbol(nil), rings...)
+	sort.SliceStable(rings, func(i, j int) bool {
+		return rings[i].Radius() < rings[j].Radius()
+	})
+
+	symbols := make(map[ASTNode]*detector.Symbol)
+	for symbol, role := range p.roles {
+		if role.Node != nil {
+			symbols[role.Node] = symbol
+		}
+	}
+
+	// A statement not traced to a symbol runs in the outermost stage
+	stages := make([]*Stage, len(rings)+1)
+	for _, stmt := range stmts {
+		band := len(rings)
+		if symbol, ok := symbols[stmt]; ok {
+			band = stageBand(rings, symbol)
+		}
+		if stages[band] == nil {
+			stages[band] = &Stage{Number: band + 1}
+		}
+		stages[band].Body = append(stages[band].Body, stmt)
+	}
+
+	// Each ring is numbered after the stage it closes
+	for i, ring := range rings {
+		node := &symbolNode{symbol: ring}
+		p.recordRole(node, RoleStage)
+		if stages[i] != nil {
+			p.attachNode(node, stages[i])
+		}
+	}
+
+	staged := make([]Statement, 0, len(stages))
+	for _, stage := range stages {
+		if stage != nil {
+			staged = append(staged, stage)
+		}
+	}
+	return staged
+}
+
+// stageBand returns the band symbol lies in, counting the rings it is
+// outside of. rings must be sorted from the innermost.
+func stageBand(rings []*detector.Symbol, symbol *detector.Symbol) int {
+	band := 0
+	for _, ring := range rings {
+		dist := math.Hypot(symbol.Position.X-ring.Position.X, symbol.Position.Y-ring.Position.Y)
+		if dist > ring.Radius() {
+			band++
+		}
+	}
+	return band
+}
@@ -0,0 +1,80 @@
+package parser
+
+import (
+	"math"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// staged returns a program with one ring of radius 80 inside the outer
+// circle. It outputs 2 from outside the ring before 1 from inside it, in
+// detector order.
+func staged() ([]*detector.Symbol, []detector.Connection) {
+	ring := &detector.Symbol{Type: detector.Circle, Position: detector.Position{X: 200, Y: 200}, Size: 80 * math.Sqrt(math.Pi), StageRing: true}
+	two := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 300, Y: 100}, Pattern: detector.PatternDoubleDot}
+	outerStar := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 300, Y: 160}}
+	one := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 180, Y: 180}, Pattern: detector.PatternDot}
+	innerStar := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 220, Y: 220}}
+
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 180 * math.Sqrt(math.Pi)},
+		ring, two, outerStar, one, innerStar,
+	}
+	connections := []detector.Connection{
+		{From: two, To: outerStar, ConnectionType: detector.ConnectionTypeSolid},
+		{From: one, To: innerStar, ConnectionType: detector.ConnectionTypeSolid},
+	}
+	return symbols, connections
+}
+
+func TestParseStages(t *testing.T) {
+	p := NewParser()
+	program, err := p.Parse(staged())
+	require.NoError(t, err)
+	require.NotNil(t, program.MainEntry)
+	require.Len(t, program.MainEntry.Body, 2)
+
+	// The inner stage runs first
+	for i, want := range []int{1, 2} {
+		stage, ok := program.MainEntry.Body[i].(*Stage)
+		require.True(t, ok, "statement %d is %T", i, program.MainEntry.Body[i])
+		assert.Equal(t, i+1, stage.Number)
+		require.Len(t, stage.Body, 1)
+		output, ok := stage.Body[0].(*OutputStatement)
+		require.True(t, ok)
+		assert.Equal(t, want, output.Value.(*Literal).Value)
+	}
+
+	roles := p.Roles()
+	assert.Equal(t, RoleStage, roles[1].Kind)
+	assert.Equal(t, 1, roles[1].Ordinal)
+	assert.Same(t, program.MainEntry.Body[0], roles[1].Node)
+	assert.Equal(t, RoleLiteral, roles[2].Kind, "the ring does not displace the symbols in it")
+}
+
+func TestStageBand(t *testing.T) {
+	center := detector.Position{X: 0, Y: 0}
+	rings := []*detector.Symbol{
+		{Position: center, Size: 50 * math.Sqrt(math.Pi)},
+		{Position: center, Size: 100 * math.Sqrt(math.Pi)},
+	}
+	at := func(x float64) *detector.Symbol { return &detector.Symbol{Position: detector.Position{X: x}} }
+
+	assert.Equal(t, 0, stageBand(rings, at(20)))
+	assert.Equal(t, 1, stageBand(rings, at(-70)))
+	assert.Equal(t, 2, stageBand(rings, at(150)))
+}
+
+func TestParseWithoutStageRings(t *testing.T) {
+	symbols, connections := staged()
+	symbols = append(symbols[:1], symbols[2:]...)
+
+	program, err := Parse(symbols, connections)
+	require.NoError(t, err)
+	for _, stmt := range program.MainEntry.Body {
+		assert.IsType(t, &OutputStatement{}, stmt)
+	}
+}
@@ -0,0 +1,193 @@
+package parser
+
+import (
+	"encoding/json"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// bytesPerInstruction is the size of a CPython instruction
+const bytesPerInstruction = 2
+
+// StatsSchemaVersion is the version of the JSON written by EncodeStatsJSON.
+// It is increased whenever the encoding changes incompatibly; adding fields
+// does not change it.
+const StatsSchemaVersion = 1
+
+// Stats summarize a parsed program, for teaching and for keeping programs
+// within complexity budgets
+type Stats struct {
+	// Symbols counts the detected symbols by type
+	Symbols map[detector.SymbolType]int `json:"symbols"`
+	// Functions counts the function definitions, not the main entry
+	Functions int `json:"functions"`
+	// Statements counts the statements, nested ones included
+	Statements int `json:"statements"`
+	// MaxDepth is how deeply blocks (branches, loops, parallel blocks and
+	// mirrors) nest, 0 for a program without any
+	MaxDepth int `json:"maxDepth"`
+	// Complexity is the cyclomatic complexity: for the main entry and each
+	// function, one plus a decision for every branch, loop, mirror and
+	// short-circuiting and/or, plus the decisions outside any function
+	Complexity int `json:"complexity"`
+	// BytecodeBytes is a rough estimate of the size of the program compiled
+	// to CPython bytecode
+	BytecodeBytes int `json:"bytecodeBytes"`
+}
+
+// ComputeStats summarizes program, parsed from symbols
+func ComputeStats(program *Program, symbols []*detector.Symbol) Stats {
+	stats := Stats{Symbols: make(map[detector.SymbolType]int)}
+	for _, symbol := range symbols {
+		if symbol != nil {
+			stats.Symbols[symbol.Type]++
+		}
+	}
+	if program == nil {
+		return stats
+	}
+
+	c := &statsCounter{}
+	instructions := c.statements(program.Globals, 0)
+	stats.Complexity = c.decisions
+	bodies := program.Functions
+	if program.MainEntry != nil {
+		bodies = append([]*FunctionDef{program.MainEntry}, bodies...)
+	}
+	for _, fn := range bodies {
+		c.decisions = 0
+		// Defining the function, and returning None at its end
+		instructions += 4 + c.statements(fn.Body, 0) + 2
+		stats.Complexity += 1 + c.decisions
+	}
+
+	stats.Functions = len(program.Functions)
+	stats.Statements = c.count
+	stats.MaxDepth = c.maxDepth
+	stats.BytecodeBytes = instructions * bytesPerInstruction
+	return stats
+}
+
+// statsJSON is the encoded form of stats
+type statsJSON struct {
+	SchemaVersion int `json:"schemaVersion"`
+	Stats
+}
+
+// EncodeStatsJSON encodes stats as indented JSON, recording the
+// "schemaVersion" next to the statistics
+func EncodeStatsJSON(stats Stats) ([]byte, error) {
+	if stats.Symbols == nil {
+		stats.Symbols = map[detector.SymbolType]int{}
+	}
+	return json.MarshalIndent(statsJSON{SchemaVersion: StatsSchemaVersion, Stats: stats}, "", "  ")
+}
+
+// statsCounter walks statements, counting them, their nesting and their
+// decisions, and estimating the instructions they compile to
+type statsCounter struct {
+	count     int
+	maxDepth  int
+	decisions int
+}
+
+// statements returns the estimated instructions of stmts, nested in depth
+// blocks
+func (c *statsCounter) statements(stmts []Statement, depth int) int {
+	instructions := 0
+	for _, stmt := range stmts {
+		instructions += c.statement(stmt, depth)
+	}
+	return instructions
+}
+
+// statement returns the estimated instructions of stmt
+func (c *statsCounter) statement(stmt Statement, depth int) int {
+	c.count++
+	block := func(body []Statement) int {
+		c.maxDepth = max(c.maxDepth, depth+1)
+		return c.statements(body, depth+1)
+	}
+
+	switch s := stmt.(type) {
+	case *OutputStatement:
+		// Loading print, calling it and discarding the result
+		return c.expression(s.Value) + 4
+	case *Assignment:
+		return c.expression(s.Value) + 1
+	case *IfStatement:
+		c.decisions++
+		instructions := c.expression(s.Condition) + 1 + block(s.ThenBranch)
+		if len(s.ElseBranch) > 0 {
+			instructions += 1 + block(s.ElseBranch)
+		}
+		return instructions
+	case *ForLoop:
+		c.decisions++
+		// Calling range, iterating, storing the counter and jumping back
+		return c.expression(s.Start) + c.expression(s.End) + c.expression(s.Step) + 6 + block(s.Body)
+	case *WhileLoop:
+		c.decisions++
+		return c.expression(s.Condition) + 2 + block(s.Body)
+	case *ParallelBlock:
+		instructions := 0
+		for _, branch := range s.Branches {
+			// Starting and joining a thread
+			instructions += 6 + block(branch)
+		}
+		return instructions
+	case *Block:
+		return c.statements(s.Body, depth)
+	case *Stage:
+		return c.statements(s.Body, depth)
+	case *Mirror:
+		c.decisions++
+		instructions := 4 + block(s.Body)
+		for _, operand := range s.Operands {
+			instructions += c.expression(operand)
+		}
+		return instructions
+	case *Assertion:
+		// Comparing, jumping past the failure and raising it
+		return c.expression(s.Left) + c.expression(s.Right) + 4
+	case *ReturnStatement:
+		return c.expression(s.Value) + 1
+	case *ExpressionStatement:
+		return c.expression(s.Expression) + 1
+	}
+	return 0
+}
+
+// expression returns the estimated instructions of expr
+func (c *statsCounter) expression(expr Expression) int {
+	switch e := expr.(type) {
+	case *Literal, *Identifier, *Quoted:
+		return 1
+	case *BinaryOp:
+		if e.Operator == And || e.Operator == Or {
+			c.decisions++
+		}
+		return c.expression(e.Left) + c.expression(e.Right) + 1
+	case *UnaryOp:
+		return c.expression(e.Operand) + 1
+	case *FunctionCall:
+		instructions := 3
+		for _, arg := range e.Arguments {
+			instructions += c.expression(arg)
+		}
+		return instructions
+	case *ArrayLiteral:
+		instructions := 1
+		for _, elem := range e.Elements {
+			instructions += c.expression(elem)
+		}
+		return instructions
+	case *MapLiteral:
+		instructions := 1
+		for _, pair := range e.Pairs {
+			instructions += c.expression(pair[0]) + c.expression(pair[1])
+		}
+		return instructions
+	}
+	return 0
+}
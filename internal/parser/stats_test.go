@@ -0,0 +1,78 @@
+package parser
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestComputeStats(t *testing.T) {
+	one := &Literal{Value: 1, LiteralType: Integer}
+	flag := &Literal{Value: true, LiteralType: Boolean}
+	output := &OutputStatement{Value: one}
+
+	program := &Program{
+		HasOuterCircle: true,
+		MainEntry: &FunctionDef{IsMain: true, Body: []Statement{
+			&IfStatement{
+				Condition:  &BinaryOp{Left: flag, Operator: And, Right: flag},
+				ThenBranch: []Statement{&ForLoop{Counter: &Identifier{Name: "i"}, Start: one, End: one, Step: one, Body: []Statement{output}}},
+				ElseBranch: []Statement{output},
+			},
+		}},
+		Functions: []*FunctionDef{{Name: "f", Body: []Statement{&Assignment{Target: &Identifier{Name: "x"}, Value: one}}}},
+	}
+	symbols := []*detector.Symbol{
+		{Type: detector.OuterCircle}, {Type: detector.DoubleCircle}, {Type: detector.Star}, {Type: detector.Star},
+	}
+
+	stats := ComputeStats(program, symbols)
+	assert.Equal(t, map[detector.SymbolType]int{detector.OuterCircle: 1, detector.DoubleCircle: 1, detector.Star: 2}, stats.Symbols)
+	assert.Equal(t, 1, stats.Functions)
+	assert.Equal(t, 5, stats.Statements)
+	assert.Equal(t, 2, stats.MaxDepth, "the loop nests in the branch")
+	// The main entry decides at the branch, its and, and the loop; f does not
+	assert.Equal(t, 4+1, stats.Complexity)
+	assert.Positive(t, stats.BytecodeBytes)
+	assert.Zero(t, stats.BytecodeBytes%bytesPerInstruction)
+}
+
+func TestComputeStatsGrowsWithTheProgram(t *testing.T) {
+	output := &OutputStatement{Value: &Literal{Value: "Hello", LiteralType: String}}
+	small := ComputeStats(&Program{MainEntry: &FunctionDef{IsMain: true, Body: []Statement{output}}}, nil)
+	large := ComputeStats(&Program{MainEntry: &FunctionDef{IsMain: true, Body: []Statement{output, output}}}, nil)
+
+	assert.Equal(t, 1, small.Complexity)
+	assert.Zero(t, small.MaxDepth)
+	assert.Greater(t, large.BytecodeBytes, small.BytecodeBytes)
+}
+
+func TestComputeStatsWithoutProgram(t *testing.T) {
+	stats := ComputeStats(nil, []*detector.Symbol{{Type: detector.Star}})
+	assert.Equal(t, 1, stats.Symbols[detector.Star])
+	assert.Zero(t, stats.Complexity)
+}
+
+// TestStatsSchemaSnapshot guards the version 1 stats schema. If it fails,
+// either restore compatibility or increase StatsSchemaVersion and add a new
+// snapshot.
+func TestStatsSchemaSnapshot(t *testing.T) {
+	snapshot, err := os.ReadFile(filepath.Join("testdata", "stats.v1.json"))
+	require.NoError(t, err)
+
+	program := &Program{
+		HasOuterCircle: true,
+		MainEntry: &FunctionDef{IsMain: true, Body: []Statement{
+			&OutputStatement{Value: &Literal{Value: "Hello", LiteralType: String}},
+		}},
+	}
+	symbols := []*detector.Symbol{{Type: detector.OuterCircle}, {Type: detector.Star}}
+
+	data, err := EncodeStatsJSON(ComputeStats(program, symbols))
+	require.NoError(t, err)
+	assert.JSONEq(t, string(snapshot), string(data))
+}
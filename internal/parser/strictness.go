@@ -0,0 +1,70 @@
+package parser
+
+import (
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+)
+
+// WithStrictness sets what the parser makes of symbols it cannot place:
+// detector.Strict fails on any Unknown symbol, and detector.Permissive
+// ignores Unknown symbols and symbols out of place, reporting each through
+// Warnings. By default Unknown symbols are read as parts of expressions and
+// symbols out of place are errors.
+func (p *Parser) WithStrictness(strictness detector.Strictness) *Parser {
+	p.strictness = strictness
+	return p
+}
+
+// Warnings returns what the last Parse call ignored in permissive mode
+func (p *Parser) Warnings() []*grimoireErrors.GrimoireError {
+	return p.warnings
+}
+
+// unrecognized applies the strictness to the Unknown symbols: in strict
+// mode each is an error, and in permissive mode each is dropped with its
+// connections and a warning
+func (p *Parser) unrecognized(symbols []*detector.Symbol, connections []detector.Connection) ([]*detector.Symbol, []detector.Connection) {
+	if p.strictness != detector.Strict && p.strictness != detector.Permissive {
+		return symbols, connections
+	}
+
+	kept := make([]*detector.Symbol, 0, len(symbols))
+	dropped := make(map[*detector.Symbol]bool)
+	for _, symbol := range symbols {
+		if symbol.Type != detector.Unknown {
+			kept = append(kept, symbol)
+			continue
+		}
+		err := grimoireErrors.UnexpectedSymbolError(
+			string(symbol.Type), "recognized symbol",
+			symbol.Position.X, symbol.Position.Y).
+			WithRegion(symbol.Position.X, symbol.Position.Y, symbol.Size)
+		if p.strictness == detector.Strict {
+			p.errors = append(p.errors, err)
+			continue
+		}
+		p.warnings = append(p.warnings, err)
+		dropped[symbol] = true
+	}
+	if len(dropped) == 0 {
+		return symbols, connections
+	}
+
+	joined := make([]detector.Connection, 0, len(connections))
+	for _, conn := range connections {
+		if !dropped[conn.From] && !dropped[conn.To] {
+			joined = append(joined, conn)
+		}
+	}
+	return kept, joined
+}
+
+// unexpected records err about a symbol out of place, as a warning in
+// permissive mode and otherwise as an error
+func (p *Parser) unexpected(err *grimoireErrors.GrimoireError) {
+	if p.strictness == detector.Permissive {
+		p.warnings = append(p.warnings, err)
+		return
+	}
+	p.errors = append(p.errors, err)
+}
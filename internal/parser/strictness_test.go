@@ -0,0 +1,69 @@
+package parser
+
+import (
+	"errors"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// withUnknown returns the subtraction of ports_test.go with a shape the
+// detector could not classify, 20 pixels across at (300, 300)
+func withUnknown() ([]*detector.Symbol, []detector.Connection) {
+	symbols, connections := subtraction(nil, nil)
+	unknown := &detector.Symbol{Type: detector.Unknown, Position: detector.Position{X: 300, Y: 300}, Size: 20}
+	return append(symbols, unknown), connections
+}
+
+func TestParseLenient(t *testing.T) {
+	symbols, connections := withUnknown()
+	p := NewParser()
+	_, err := p.Parse(symbols, connections)
+	require.NoError(t, err)
+	assert.Empty(t, p.Warnings())
+}
+
+func TestParseStrict(t *testing.T) {
+	symbols, connections := withUnknown()
+	_, err := NewParser().WithStrictness(detector.Strict).Parse(symbols, connections)
+	require.Error(t, err)
+
+	var gErr *grimoireErrors.GrimoireError
+	require.True(t, errors.As(err, &gErr))
+	require.Len(t, gErr.Related, 1)
+	var related *grimoireErrors.GrimoireError
+	require.True(t, errors.As(gErr.Related[0], &related))
+	assert.Equal(t, grimoireErrors.UnexpectedSymbol, related.Type)
+	require.NotNil(t, related.Region)
+	assert.Equal(t, 290, related.Region.X)
+
+	// Without unknown symbols strict mode parses as usual
+	symbols, connections = subtraction(nil, nil)
+	program, err := NewParser().WithStrictness(detector.Strict).Parse(symbols, connections)
+	require.NoError(t, err)
+	outputOperation(t, program)
+}
+
+func TestParsePermissive(t *testing.T) {
+	symbols, connections := withUnknown()
+	p := NewParser().WithStrictness(detector.Permissive)
+	program, err := p.Parse(symbols, connections)
+	require.NoError(t, err)
+	op := outputOperation(t, program)
+	assert.Equal(t, Subtract, op.Operator)
+
+	require.Len(t, p.Warnings(), 1)
+	warning := p.Warnings()[0]
+	assert.Equal(t, grimoireErrors.UnexpectedSymbol, warning.Type)
+	require.NotNil(t, warning.Region)
+	assert.Equal(t, 290, warning.Region.Y)
+
+	// The warnings describe the last parse only
+	symbols, connections = subtraction(nil, nil)
+	_, err = p.Parse(symbols, connections)
+	require.NoError(t, err)
+	assert.Empty(t, p.Warnings())
+}
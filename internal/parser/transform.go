@@ -0,0 +1,126 @@
+package parser
+
+import (
+	"math"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// Transform maps the coordinate space of detection results: a point is
+// scaled by Scale, rotated clockwise by Rotation radians about the origin
+// and then moved by Offset. Scale must be positive. Angles follow image
+// coordinates, where y points down, so a positive rotation turns the
+// drawing clockwise on screen.
+type Transform struct {
+	Scale    float64
+	Rotation float64
+	Offset   detector.Position
+}
+
+// Identity returns the transform leaving every point where it is
+func Identity() Transform {
+	return Transform{Scale: 1}
+}
+
+// Translate returns the transform moving points by (dx, dy)
+func Translate(dx, dy float64) Transform {
+	return Transform{Scale: 1, Offset: detector.Position{X: dx, Y: dy}}
+}
+
+// Rotate returns the transform turning points clockwise about the origin by
+// angle radians
+func Rotate(angle float64) Transform {
+	return Transform{Scale: 1, Rotation: angle}
+}
+
+// ScaleBy returns the transform scaling points from the origin by factor
+func ScaleBy(factor float64) Transform {
+	return Transform{Scale: factor}
+}
+
+// Normalization returns the transform moving the center of outerCircle to
+// the origin and scaling its radius to 1. An outer circle without a radius
+// is only moved.
+func Normalization(outerCircle *detector.Symbol) Transform {
+	t := Translate(-outerCircle.Position.X, -outerCircle.Position.Y)
+	if r := outerCircle.Radius(); r > 0 {
+		t = t.Then(ScaleBy(1 / r))
+	}
+	return t
+}
+
+// Then returns the transform applying t and then next
+func (t Transform) Then(next Transform) Transform {
+	return Transform{
+		Scale:    t.Scale * next.Scale,
+		Rotation: t.Rotation + next.Rotation,
+		Offset:   next.Point(t.Offset),
+	}
+}
+
+// Inverse returns the transform undoing t
+func (t Transform) Inverse() Transform {
+	undo := Transform{Scale: 1 / t.Scale, Rotation: -t.Rotation}
+	offset := undo.Point(t.Offset)
+	undo.Offset = detector.Position{X: -offset.X, Y: -offset.Y}
+	return undo
+}
+
+// Point returns where t maps p
+func (t Transform) Point(p detector.Position) detector.Position {
+	sin, cos := math.Sincos(t.Rotation)
+	return detector.Position{
+		X: t.Scale*(p.X*cos-p.Y*sin) + t.Offset.X,
+		Y: t.Scale*(p.X*sin+p.Y*cos) + t.Offset.Y,
+	}
+}
+
+// Angle returns the direction an angle in image coordinates points in
+// after t, in radians from -π to π
+func (t Transform) Angle(angle float64) float64 {
+	return math.Remainder(angle+t.Rotation, 2*math.Pi)
+}
+
+// Apply returns copies of symbols and connections with their coordinates
+// mapped by t: positions move, sizes scale and anchors turn with the
+// drawing. A list frame keeps an upright extent, growing to hold its
+// rotated rectangle. The originals are left unchanged.
+func (t Transform) Apply(symbols []*detector.Symbol, connections []detector.Connection) ([]*detector.Symbol, []detector.Connection) {
+	sin, cos := math.Sincos(t.Rotation)
+	sin, cos = math.Abs(sin), math.Abs(cos)
+
+	mapped := make(map[*detector.Symbol]*detector.Symbol, len(symbols))
+	transformed := make([]*detector.Symbol, len(symbols))
+	for i, s := range symbols {
+		c := *s
+		c.Position = t.Point(s.Position)
+		c.Size = s.Size * t.Scale
+		c.Width = t.Scale * (s.Width*cos + s.Height*sin)
+		c.Height = t.Scale * (s.Width*sin + s.Height*cos)
+		mapped[s] = &c
+		transformed[i] = &c
+	}
+
+	moved := make([]detector.Connection, len(connections))
+	for i, conn := range connections {
+		c := conn
+		if from, ok := mapped[conn.From]; ok {
+			c.From = from
+		}
+		if to, ok := mapped[conn.To]; ok {
+			c.To = to
+		}
+		c.FromAnchor = t.anchor(conn.FromAnchor)
+		c.ToAnchor = t.anchor(conn.ToAnchor)
+		moved[i] = c
+	}
+	return transformed, moved
+}
+
+// anchor returns a copy of a turned with the drawing, or nil for nil
+func (t Transform) anchor(a *detector.Anchor) *detector.Anchor {
+	if a == nil {
+		return nil
+	}
+	return &detector.Anchor{Angle: t.Angle(a.Angle)}
+}
@@ -0,0 +1,71 @@
+package parser
+
+import (
+	"math"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func assertPosition(t *testing.T, want, got detector.Position) {
+	t.Helper()
+	assert.InDelta(t, want.X, got.X, 1e-9, "x")
+	assert.InDelta(t, want.Y, got.Y, 1e-9, "y")
+}
+
+func TestTransformPoint(t *testing.T) {
+	p := detector.Position{X: 10, Y: 0}
+	assertPosition(t, p, Identity().Point(p))
+	assertPosition(t, detector.Position{X: 13, Y: -4}, Translate(3, -4).Point(p))
+	assertPosition(t, detector.Position{X: 25, Y: 0}, ScaleBy(2.5).Point(p))
+	// Clockwise on screen: right turns to down
+	assertPosition(t, detector.Position{X: 0, Y: 10}, Rotate(math.Pi/2).Point(p))
+
+	// Then applies the transforms in order
+	moved := Rotate(math.Pi / 2).Then(Translate(5, 0)).Then(ScaleBy(2))
+	assertPosition(t, detector.Position{X: 10, Y: 20}, moved.Point(p))
+	assertPosition(t, p, moved.Inverse().Point(moved.Point(p)))
+	assertPosition(t, p, moved.Then(moved.Inverse()).Point(p))
+}
+
+func TestNormalization(t *testing.T) {
+	outerCircle := &detector.Symbol{Type: detector.OuterCircle, Position: detector.Position{X: 200, Y: 200}, Size: 100 * math.Sqrt(math.Pi)}
+	n := Normalization(outerCircle)
+	assertPosition(t, detector.Position{}, n.Point(outerCircle.Position))
+	assertPosition(t, detector.Position{X: 0, Y: -1}, n.Point(detector.Position{X: 200, Y: 100}))
+	assertPosition(t, detector.Position{X: 0.5, Y: 0}, n.Point(detector.Position{X: 250, Y: 200}))
+
+	// Without a radius the circle is only moved
+	assertPosition(t, detector.Position{X: 50, Y: 0}, Normalization(&detector.Symbol{Position: detector.Position{X: 200, Y: 200}}).Point(detector.Position{X: 250, Y: 200}))
+}
+
+func TestTransformApply(t *testing.T) {
+	star := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 10, Y: 0}, Size: 4}
+	frame := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 0, Y: 10}, Size: 6, ListFrame: true, Width: 30, Height: 10}
+	connections := []detector.Connection{{
+		From: star, To: frame, ConnectionType: "solid",
+		FromAnchor: &detector.Anchor{Angle: math.Pi / 2},
+	}}
+
+	symbols, moved := Rotate(math.Pi / 2).Then(ScaleBy(2)).Apply([]*detector.Symbol{star, frame}, connections)
+	require.Len(t, symbols, 2)
+	require.Len(t, moved, 1)
+
+	assertPosition(t, detector.Position{X: 0, Y: 20}, symbols[0].Position)
+	assert.InDelta(t, 8, symbols[0].Size, 1e-9)
+	assertPosition(t, detector.Position{X: -20, Y: 0}, symbols[1].Position)
+	assert.InDelta(t, 20, symbols[1].Width, 1e-9, "the frame turned upright")
+	assert.InDelta(t, 60, symbols[1].Height, 1e-9)
+
+	assert.Same(t, symbols[0], moved[0].From)
+	assert.Same(t, symbols[1], moved[0].To)
+	require.NotNil(t, moved[0].FromAnchor)
+	assert.InDelta(t, math.Pi, math.Abs(moved[0].FromAnchor.Angle), 1e-9, "the anchor turned from the bottom to the left")
+	assert.Nil(t, moved[0].ToAnchor)
+
+	// The originals are unchanged
+	assertPosition(t, detector.Position{X: 10, Y: 0}, star.Position)
+	assert.InDelta(t, math.Pi/2, connections[0].FromAnchor.Angle, 1e-9)
+}
@@ -0,0 +1,179 @@
+package parser
+
+import (
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+)
+
+// typeChecker walks a program looking for operations the generated Python
+// would reject at runtime
+type typeChecker struct {
+	symbols map[ASTNode]*detector.Symbol
+	errors  []error
+}
+
+// TypeCheck reports operations whose operand types cannot be combined, such
+// as subtracting text from a number. Roles, when given, locate each error in
+// the image. Operands of unknown type are assumed to be valid.
+func TypeCheck(program *Program, roles []SymbolRole) error {
+	if program == nil {
+		return nil
+	}
+
+	c := &typeChecker{symbols: make(map[ASTNode]*detector.Symbol)}
+	for _, role := range roles {
+		if role.Node != nil {
+			c.symbols[role.Node] = role.Symbol
+		}
+	}
+
+	c.statements(program.Globals)
+	if program.MainEntry != nil {
+		c.statements(program.MainEntry.Body)
+	}
+	for _, fn := range program.Functions {
+		c.statements(fn.Body)
+	}
+
+	switch len(c.errors) {
+	case 0:
+		return nil
+	case 1:
+		return c.errors[0]
+	}
+
+	errorMsg := i18n.T("parser.type_errors")
+	for _, err := range c.errors {
+		errorMsg += "\n  - " + err.Error()
+	}
+	return grimoireErrors.NewError(grimoireErrors.TypeError, errorMsg).
+		WithRelated(c.errors...)
+}
+
+// statements checks a statement list
+func (c *typeChecker) statements(stmts []Statement) {
+	for _, stmt := range stmts {
+		c.statement(stmt)
+	}
+}
+
+// statement checks a single statement and the statements nested in it
+func (c *typeChecker) statement(stmt Statement) {
+	switch s := stmt.(type) {
+	case *OutputStatement:
+		c.expression(s.Value)
+	case *Assignment:
+		c.expression(s.Value)
+	case *IfStatement:
+		c.expression(s.Condition)
+		c.statements(s.ThenBranch)
+		c.statements(s.ElseBranch)
+	case *ForLoop:
+		for _, bound := range []Expression{s.Start, s.End, s.Step} {
+			c.expression(bound)
+			if bound != nil && isOneOf(bound.Type(), Float, String, Void, Array, Map) {
+				c.report(s, i18n.Tf("parser.loop_bounds_type", bound.Type()))
+			}
+		}
+		c.statements(s.Body)
+	case *WhileLoop:
+		c.expression(s.Condition)
+		c.statements(s.Body)
+	case *ParallelBlock:
+		for _, branch := range s.Branches {
+			c.statements(branch)
+		}
+	case *Block:
+		c.statements(s.Body)
+	case *Stage:
+		c.statements(s.Body)
+	case *Mirror:
+		for _, operand := range s.Operands {
+			c.expression(operand)
+		}
+		c.statements(s.Body)
+	case *Assertion:
+		c.expression(s.Left)
+		c.expression(s.Right)
+	case *ReturnStatement:
+		c.expression(s.Value)
+	case *ExpressionStatement:
+		c.expression(s.Expression)
+	}
+}
+
+// expression checks an expression and its operands
+func (c *typeChecker) expression(expr Expression) {
+	switch e := expr.(type) {
+	case *BinaryOp:
+		c.expression(e.Left)
+		c.expression(e.Right)
+		if e.Left != nil && e.Right != nil && !operandsCompatible(e.Operator, e.Left.Type(), e.Right.Type()) {
+			c.report(e, i18n.Tf("parser.operand_types", e.Operator, e.Left.Type(), e.Right.Type()))
+		}
+	case *UnaryOp:
+		c.expression(e.Operand)
+	case *FunctionCall:
+		for _, arg := range e.Arguments {
+			c.expression(arg)
+		}
+	case *ArrayLiteral:
+		for _, elem := range e.Elements {
+			c.expression(elem)
+		}
+	case *MapLiteral:
+		for _, pair := range e.Pairs {
+			c.expression(pair[0])
+			c.expression(pair[1])
+		}
+	}
+}
+
+// report records a type error, locating it at the node's symbol when known
+func (c *typeChecker) report(node ASTNode, message string) {
+	err := grimoireErrors.NewError(grimoireErrors.TypeError, message).
+		WithSuggestion(i18n.T("parser.operand_types_suggestion"))
+	if symbol, ok := c.symbols[node]; ok {
+		err = err.WithDetails(i18n.Tf("detail.at_position", symbol.Position.X, symbol.Position.Y)).
+			WithRegion(symbol.Position.X, symbol.Position.Y, symbol.Size)
+	}
+	c.errors = append(c.errors, err)
+}
+
+// operandsCompatible reports whether operator accepts operands of the given
+// types, following Python semantics
+func operandsCompatible(operator OperatorType, left, right DataType) bool {
+	if !isOneOf(left, Integer, Float, Boolean, String, Void) ||
+		!isOneOf(right, Integer, Float, Boolean, String, Void) {
+		return true
+	}
+	if left == Void || right == Void {
+		return false
+	}
+
+	numeric := isOneOf(left, Integer, Float, Boolean) && isOneOf(right, Integer, Float, Boolean)
+	switch operator {
+	case Add:
+		return numeric || left == String && right == String
+	case Subtract, Divide:
+		return numeric
+	case Multiply:
+		// Repeating text by a count is allowed
+		return numeric ||
+			left == String && isOneOf(right, Integer, Boolean) ||
+			right == String && isOneOf(left, Integer, Boolean)
+	default:
+		return true
+	}
+}
+
+// isOneOf reports whether t is any of types
+func isOneOf(t DataType, types ...DataType) bool {
+	for _, candidate := range types {
+		if t == candidate {
+			return true
+		}
+	}
+	return false
+}
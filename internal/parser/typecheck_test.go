@@ -0,0 +1,81 @@
+package parser
+
+import (
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestTypeCheck(t *testing.T) {
+	one := &Literal{Value: 1, LiteralType: Integer}
+	text := &Literal{Value: "Text", LiteralType: String}
+	flag := &Literal{Value: true, LiteralType: Boolean}
+	unknown := &Identifier{Name: "var_0"}
+	none := &FunctionCall{Function: &Identifier{Name: "print"}, DataType: Void}
+
+	tests := []struct {
+		name  string
+		stmt  Statement
+		valid bool
+	}{
+		{name: "integer addition", stmt: &OutputStatement{Value: &BinaryOp{Left: one, Operator: Add, Right: flag}}, valid: true},
+		{name: "text concatenation", stmt: &OutputStatement{Value: &BinaryOp{Left: text, Operator: Add, Right: text}}, valid: true},
+		{name: "text repetition", stmt: &OutputStatement{Value: &BinaryOp{Left: one, Operator: Multiply, Right: text}}, valid: true},
+		{name: "unknown operand", stmt: &OutputStatement{Value: &BinaryOp{Left: unknown, Operator: Subtract, Right: text}}, valid: true},
+		{name: "text plus integer", stmt: &OutputStatement{Value: &BinaryOp{Left: text, Operator: Add, Right: one}}},
+		{name: "text divided", stmt: &Assignment{Target: unknown, Value: &BinaryOp{Left: text, Operator: Divide, Right: text}}},
+		{name: "void operand", stmt: &OutputStatement{Value: &BinaryOp{Left: none, Operator: Add, Right: one}}},
+		{
+			name: "nested in loop",
+			stmt: &ForLoop{Start: one, End: one, Step: one, Body: []Statement{
+				&OutputStatement{Value: &BinaryOp{Left: one, Operator: Subtract, Right: text}},
+			}},
+		},
+		{name: "text loop bound", stmt: &ForLoop{Start: one, End: text, Step: one}},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			program := &Program{HasOuterCircle: true, MainEntry: &FunctionDef{IsMain: true, Body: []Statement{tt.stmt}}}
+			err := TypeCheck(program, nil)
+			if tt.valid {
+				assert.NoError(t, err)
+				return
+			}
+
+			var gErr *grimoireErrors.GrimoireError
+			require.ErrorAs(t, err, &gErr)
+			assert.Equal(t, grimoireErrors.TypeError, gErr.Type)
+		})
+	}
+}
+
+func TestTypeCheckRegions(t *testing.T) {
+	op := &BinaryOp{
+		Left:     &Literal{Value: "Text", LiteralType: String},
+		Operator: Subtract,
+		Right:    &Literal{Value: 1, LiteralType: Integer},
+	}
+	symbol := &detector.Symbol{Type: detector.Divergence, Position: detector.Position{X: 100, Y: 50}, Size: 20}
+	roles := []SymbolRole{{Symbol: symbol, Kind: RoleOperator, Node: op}}
+
+	program := &Program{
+		HasOuterCircle: true,
+		MainEntry:      &FunctionDef{IsMain: true},
+		Functions:      []*FunctionDef{{Body: []Statement{&OutputStatement{Value: op}}}},
+		Globals:        []Statement{&OutputStatement{Value: op}},
+	}
+
+	err := TypeCheck(program, roles)
+	var gErr *grimoireErrors.GrimoireError
+	require.ErrorAs(t, err, &gErr)
+	require.Len(t, gErr.Related, 2)
+
+	var related *grimoireErrors.GrimoireError
+	require.ErrorAs(t, gErr.Related[0], &related)
+	require.NotNil(t, related.Region)
+	assert.Equal(t, grimoireErrors.Region{X: 90, Y: 40, Width: 20, Height: 20}, *related.Region)
+}
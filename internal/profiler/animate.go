@@ -0,0 +1,116 @@
+package profiler
+
+import (
+	"image"
+	"image/color"
+	"image/draw"
+	"image/gif"
+
+	"github.com/ayutaz/grimoire/internal/compiler"
+	"github.com/ayutaz/grimoire/internal/parser"
+)
+
+const (
+	// FrameDelay is how long each frame of an animation shows, in
+	// hundredths of a second
+	FrameDelay = 40
+	// MaxFrames is how many frames an animation has at most; a longer run
+	// is cut short
+	MaxFrames = 500
+)
+
+// span is the lines a compiled statement was generated as
+type span struct {
+	node  parser.ASTNode
+	lines compiler.LineRange
+}
+
+// Animate returns an animation of the run recorded in lines, stepping
+// through execution on img faded: each frame lights up the symbols of the
+// statement that ran. Lines of the same statement running one after the
+// other make a single frame.
+func Animate(img image.Image, roles []parser.SymbolRole, sourceMap map[parser.ASTNode]compiler.LineRange, lines *Lines) *gif.GIF {
+	var spans []span
+	symbols := make(map[parser.ASTNode][]parser.SymbolRole)
+	for _, role := range roles {
+		node, r, ok := statementOf(&role, sourceMap)
+		if !ok || role.Symbol == nil {
+			continue
+		}
+		if _, seen := symbols[node]; !seen {
+			spans = append(spans, span{node: node, lines: r})
+		}
+		symbols[node] = append(symbols[node], role)
+	}
+
+	base := fade(img)
+	palette := animationPalette()
+	anim := &gif.GIF{}
+	addFrame := func(frame *image.RGBA) {
+		paletted := image.NewPaletted(frame.Bounds(), palette)
+		draw.Draw(paletted, paletted.Bounds(), frame, image.Point{}, draw.Src)
+		anim.Image = append(anim.Image, paletted)
+		anim.Delay = append(anim.Delay, FrameDelay)
+	}
+
+	var previous parser.ASTNode
+	for _, line := range lines.Steps {
+		if len(anim.Image) == MaxFrames {
+			break
+		}
+		node := innermost(spans, line)
+		if node == nil || node == previous {
+			continue
+		}
+		previous = node
+
+		frame := image.NewRGBA(base.Bounds())
+		copy(frame.Pix, base.Pix)
+		for _, role := range symbols[node] {
+			drawGlow(frame, Entry{Role: role}, 1)
+		}
+		addFrame(frame)
+	}
+	if len(anim.Image) == 0 {
+		// Nothing ran; the animation is the still drawing
+		addFrame(base)
+	}
+	return anim
+}
+
+// innermost returns the statement with the narrowest span containing line,
+// or nil when no statement does
+func innermost(spans []span, line int) parser.ASTNode {
+	var node parser.ASTNode
+	narrowest := -1
+	for _, s := range spans {
+		if line < s.lines.Start || line > s.lines.End {
+			continue
+		}
+		if width := s.lines.End - s.lines.Start; narrowest < 0 || width < narrowest {
+			node, narrowest = s.node, width
+		}
+	}
+	return node
+}
+
+// animationPalette returns the colors of an animation frame: the grays of
+// a faded drawing, each tinted toward GlowColor in steps
+func animationPalette() color.Palette {
+	const levels = 16
+	darkest := 255 - 255/fadeFactor
+	palette := make(color.Palette, 0, levels*levels)
+	for g := 0; g < levels; g++ {
+		v := uint8(darkest + (255-darkest)*g/(levels-1))
+		for a := 0; a < levels; a++ {
+			alpha := float64(a) / (levels - 1)
+			palette = append(palette, color.RGBA{
+				R: blend(v, GlowColor.R, alpha),
+				G: blend(v, GlowColor.G, alpha),
+				B: blend(v, GlowColor.B, alpha),
+				A: 255,
+			})
+		}
+	}
+	return palette
+}
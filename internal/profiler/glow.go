@@ -0,0 +1,93 @@
+package profiler
+
+import (
+	"image"
+	"image/color"
+	"math"
+)
+
+// GlowColor is the color hot symbols glow in
+var GlowColor = color.RGBA{R: 255, G: 120, B: 0, A: 255}
+
+const (
+	// fadeFactor controls how much the drawing is lightened so the glow stands out
+	fadeFactor = 3
+	// glowMargin is how far, in pixels, the glow reaches past a symbol
+	glowMargin = 12
+)
+
+// Glow draws img faded and lights up each profiled symbol: the more time
+// spent in it, the brighter its glow. When no time was measured, execution
+// counts are used instead. Symbols that never ran do not glow.
+func Glow(img image.Image, entries []Entry) *image.RGBA {
+	canvas := fade(img)
+
+	heat := func(e Entry) float64 { return e.Seconds }
+	hottest := 0.0
+	for _, e := range entries {
+		hottest = math.Max(hottest, e.Seconds)
+	}
+	if hottest == 0 {
+		heat = func(e Entry) float64 { return float64(e.Count) }
+		for _, e := range entries {
+			hottest = math.Max(hottest, float64(e.Count))
+		}
+	}
+	if hottest == 0 {
+		return canvas
+	}
+
+	for _, e := range entries {
+		if e.Count > 0 && e.Role.Symbol != nil {
+			drawGlow(canvas, e, heat(e)/hottest)
+		}
+	}
+	return canvas
+}
+
+// fade returns a gray copy of img, lightened so that glows stand out
+func fade(img image.Image) *image.RGBA {
+	bounds := img.Bounds()
+	canvas := image.NewRGBA(image.Rect(0, 0, bounds.Dx(), bounds.Dy()))
+	for y := 0; y < bounds.Dy(); y++ {
+		for x := 0; x < bounds.Dx(); x++ {
+			v := color.GrayModel.Convert(img.At(bounds.Min.X+x, bounds.Min.Y+y)).(color.Gray).Y
+			v = 255 - (255-v)/fadeFactor
+			canvas.SetRGBA(x, y, color.RGBA{R: v, G: v, B: v, A: 255})
+		}
+	}
+	return canvas
+}
+
+// drawGlow tints the area around the symbol of e, strongest at its center
+func drawGlow(canvas *image.RGBA, e Entry, strength float64) {
+	center := e.Role.Symbol.Position
+	radius := e.Role.Symbol.Size/2 + glowMargin
+	x0, x1 := int(math.Floor(center.X-radius)), int(math.Ceil(center.X+radius))
+	y0, y1 := int(math.Floor(center.Y-radius)), int(math.Ceil(center.Y+radius))
+
+	for y := y0; y <= y1; y++ {
+		for x := x0; x <= x1; x++ {
+			if !image.Pt(x, y).In(canvas.Bounds()) {
+				continue
+			}
+			d := math.Hypot(float64(x)-center.X, float64(y)-center.Y)
+			if d > radius {
+				continue
+			}
+			alpha := strength * (1 - d/radius)
+			c := canvas.RGBAAt(x, y)
+			canvas.SetRGBA(x, y, color.RGBA{
+				R: blend(c.R, GlowColor.R, alpha),
+				G: blend(c.G, GlowColor.G, alpha),
+				B: blend(c.B, GlowColor.B, alpha),
+				A: 255,
+			})
+		}
+	}
+}
+
+// blend mixes from toward to by alpha, between 0 and 1
+func blend(from, to uint8, alpha float64) uint8 {
+	return uint8(math.Round(float64(from) + (float64(to)-float64(from))*alpha))
+}
@@ -0,0 +1,183 @@
+// Package profiler implements `grimoire run --profile`.
+//
+// The generated Python runs under a line tracer that counts how often each
+// line runs and how long it takes. The compiler's source map turns those
+// lines back into statements, and the parser's roles turn statements into
+// the symbols they were read from, so the profile is reported per symbol of
+// the magic circle and can be drawn onto the image itself.
+package profiler
+
+import (
+	"encoding/json"
+	"io"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"sort"
+	"strconv"
+
+	"github.com/ayutaz/grimoire/internal/compiler"
+	"github.com/ayutaz/grimoire/internal/parser"
+)
+
+// MaxSteps is how many executed lines the tracer records in order
+const MaxSteps = 5000
+
+// Tracer is a Python script that runs the program whose path is its first
+// argument and writes the resulting Lines, as JSON, to its second argument.
+// Its third argument is how many steps to record.
+const Tracer = `import json
+import sys
+import threading
+import time
+
+path, out, max_steps = sys.argv[1], sys.argv[2], int(sys.argv[3])
+counts, calls, times, steps = {}, {}, {}, []
+last = [None, time.perf_counter()]
+
+
+def account(now):
+    if last[0] is not None:
+        times[last[0]] = times.get(last[0], 0.0) + now - last[1]
+    last[1] = now
+
+
+def trace(frame, event, arg):
+    if frame.f_code.co_filename != path:
+        return None
+    account(time.perf_counter())
+    if event == "call":
+        line = frame.f_code.co_firstlineno
+        calls[line] = calls.get(line, 0) + 1
+    elif event == "line":
+        counts[frame.f_lineno] = counts.get(frame.f_lineno, 0) + 1
+        last[0] = frame.f_lineno
+        if len(steps) < max_steps:
+            steps.append(frame.f_lineno)
+    return trace
+
+
+with open(path) as source:
+    code = compile(source.read(), path, "exec")
+sys.settrace(trace)
+threading.settrace(trace)
+try:
+    exec(code, {"__name__": "__main__", "__file__": path})
+finally:
+    sys.settrace(None)
+    threading.settrace(None)
+    account(time.perf_counter())
+    with open(out, "w") as stats:
+        json.dump({"counts": counts, "calls": calls, "times": times, "steps": steps}, stats)
+`
+
+// Lines is what the tracer records about the generated lines, by 1-based
+// line number
+type Lines struct {
+	Counts map[int]int     `json:"counts"` // times each line ran
+	Calls  map[int]int     `json:"calls"`  // calls to the function defined at each line
+	Times  map[int]float64 `json:"times"`  // seconds spent on each line
+	Steps  []int           `json:"steps"`  // the first MaxSteps lines to run, in order
+}
+
+// ReadLines decodes the output of the tracer
+func ReadLines(r io.Reader) (*Lines, error) {
+	lines := &Lines{}
+	if err := json.NewDecoder(r).Decode(lines); err != nil {
+		return nil, err
+	}
+	return lines, nil
+}
+
+// Run runs code with python3 under the tracer, copying its output to
+// stdout and stderr. The environment entries given are added to those of
+// this process. The lines are returned whenever the tracer recorded them,
+// even if the program itself failed.
+func Run(code string, stdout, stderr io.Writer, env ...string) (*Lines, error) {
+	dir, err := os.MkdirTemp("", "grimoire_profile_")
+	if err != nil {
+		return nil, err
+	}
+	defer os.RemoveAll(dir)
+
+	programPath := filepath.Join(dir, "program.py")
+	statsPath := filepath.Join(dir, "profile.json")
+	if err := os.WriteFile(programPath, []byte(code), 0o600); err != nil {
+		return nil, err
+	}
+
+	cmd := exec.Command("python3", "-c", Tracer, programPath, statsPath, strconv.Itoa(MaxSteps))
+	cmd.Env = append(os.Environ(), env...)
+	cmd.Stdout = stdout
+	cmd.Stderr = stderr
+	runErr := cmd.Run()
+
+	stats, err := os.Open(statsPath)
+	if err != nil {
+		if runErr == nil {
+			runErr = err
+		}
+		return nil, runErr
+	}
+	defer stats.Close()
+	lines, err := ReadLines(stats)
+	if err != nil {
+		return nil, err
+	}
+	return lines, runErr
+}
+
+// Entry is the profile of one symbol
+type Entry struct {
+	// Role is the role of the symbol
+	Role parser.SymbolRole
+	// Count is how many times the statement the symbol belongs to ran, or
+	// for a function, how many times it was called
+	Count int
+	// Seconds is the time spent in the statement, including the statements
+	// nested in it but not the functions it calls
+	Seconds float64
+}
+
+// Attribute returns the profile of every symbol that belongs to a compiled
+// statement, hottest first. A symbol inside an expression, such as an
+// operand, shares the profile of its statement.
+func Attribute(roles []parser.SymbolRole, sourceMap map[parser.ASTNode]compiler.LineRange, lines *Lines) []Entry {
+	var entries []Entry
+	for _, role := range roles {
+		node, r, ok := statementOf(&role, sourceMap)
+		if !ok {
+			continue
+		}
+		entry := Entry{Role: role, Count: lines.Counts[r.Start]}
+		if fn, isFunction := node.(*parser.FunctionDef); isFunction && !fn.IsMain {
+			entry.Count = lines.Calls[r.Start]
+		}
+		for line := r.Start; line <= r.End; line++ {
+			entry.Seconds += lines.Times[line]
+		}
+		entries = append(entries, entry)
+	}
+
+	sort.SliceStable(entries, func(i, j int) bool {
+		if entries[i].Seconds != entries[j].Seconds {
+			return entries[i].Seconds > entries[j].Seconds
+		}
+		return entries[i].Count > entries[j].Count
+	})
+	return entries
+}
+
+// statementOf returns the compiled node the symbol of role belongs to: its
+// own node, or that of the closest enclosing statement
+func statementOf(role *parser.SymbolRole, sourceMap map[parser.ASTNode]compiler.LineRange) (parser.ASTNode, compiler.LineRange, bool) {
+	for r := role; r != nil; r = r.Context {
+		if r.Node == nil {
+			continue
+		}
+		if lines, ok := sourceMap[r.Node]; ok {
+			return r.Node, lines, true
+		}
+	}
+	return nil, compiler.LineRange{}, false
+}
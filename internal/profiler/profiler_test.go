@@ -0,0 +1,135 @@
+package profiler
+
+import (
+	"image"
+	"image/color"
+	"io"
+	"os/exec"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/compiler"
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func symbolAt(x, y float64) *detector.Symbol {
+	return &detector.Symbol{Type: detector.Star, Position: detector.Position{X: x, Y: y}, Size: 4}
+}
+
+// TestAttribute tests that line statistics are attributed to symbols
+func TestAttribute(t *testing.T) {
+	fn := &parser.FunctionDef{Name: "f"}
+	output := &parser.OutputStatement{}
+	outputRole := parser.SymbolRole{Symbol: symbolAt(0, 0), Index: 1, Kind: parser.RoleOutput, Node: output}
+	roles := []parser.SymbolRole{
+		{Symbol: symbolAt(0, 0), Index: 0, Kind: parser.RoleFunction, Node: fn},
+		outputRole,
+		{Symbol: symbolAt(0, 0), Index: 2, Kind: parser.RoleLiteral, Node: &parser.Literal{}, Context: &outputRole},
+		{Symbol: symbolAt(0, 0), Index: 3, Kind: parser.RoleUnused},
+	}
+	sourceMap := map[parser.ASTNode]compiler.LineRange{
+		fn:     {Start: 4, End: 8},
+		output: {Start: 8, End: 8},
+	}
+	lines := &Lines{
+		Counts: map[int]int{4: 1, 8: 2},
+		Calls:  map[int]int{4: 2},
+		Times:  map[int]float64{5: 0.001, 8: 0.002},
+	}
+
+	entries := Attribute(roles, sourceMap, lines)
+
+	require.Len(t, entries, 3, "unused symbols are not profiled")
+	assert.Equal(t, 0, entries[0].Role.Index, "hottest first")
+	assert.Equal(t, 2, entries[0].Count, "functions count their calls")
+	assert.InDelta(t, 0.003, entries[0].Seconds, 1e-9, "nested lines count towards their statement")
+	assert.Equal(t, 1, entries[1].Role.Index)
+	assert.Equal(t, 2, entries[1].Count)
+	assert.InDelta(t, 0.002, entries[1].Seconds, 1e-9)
+	assert.Equal(t, 2, entries[2].Role.Index)
+	assert.Equal(t, 2, entries[2].Count, "operands share the profile of their statement")
+}
+
+// TestGlow tests that hotter symbols glow brighter and idle ones not at all
+func TestGlow(t *testing.T) {
+	img := image.NewGray(image.Rect(0, 0, 60, 60))
+	for i := range img.Pix {
+		img.Pix[i] = 255
+	}
+	entries := []Entry{
+		{Role: parser.SymbolRole{Symbol: symbolAt(10, 10)}, Count: 1, Seconds: 1},
+		{Role: parser.SymbolRole{Symbol: symbolAt(45, 45)}, Count: 5, Seconds: 0.1},
+		{Role: parser.SymbolRole{Symbol: symbolAt(45, 10)}},
+	}
+
+	canvas := Glow(img, entries)
+
+	hot, cool := canvas.RGBAAt(10, 10), canvas.RGBAAt(45, 45)
+	assert.Less(t, hot.B, cool.B, "more time glows brighter")
+	assert.Less(t, cool.B, uint8(255), "every symbol that ran glows")
+	assert.Equal(t, color.RGBA{R: 255, G: 255, B: 255, A: 255}, canvas.RGBAAt(45, 10), "idle symbols do not glow")
+}
+
+// TestTracer tests that the tracer counts and times the lines of a program
+func TestTracer(t *testing.T) {
+	if _, err := exec.LookPath("python3"); err != nil {
+		t.Skip("Python3 not available")
+	}
+
+	program := "# generated\ndef f():\n    for i in range(3):\n        pass\n\nf()\nf()\n"
+	lines, err := Run(program, io.Discard, io.Discard)
+	require.NoError(t, err)
+
+	assert.Equal(t, 2, lines.Calls[2])
+	assert.Equal(t, 6, lines.Counts[4])
+	assert.Equal(t, 1, lines.Counts[6])
+	assert.Greater(t, lines.Times[3], 0.0)
+
+	steps := 0
+	for _, count := range lines.Counts {
+		steps += count
+	}
+	assert.Len(t, lines.Steps, steps, "every line run is a step")
+	assert.Equal(t, 2, lines.Steps[0])
+}
+
+// TestAnimate tests that each frame lights up the statement that ran
+func TestAnimate(t *testing.T) {
+	img := image.NewGray(image.Rect(0, 0, 60, 60))
+	for i := range img.Pix {
+		img.Pix[i] = 255
+	}
+	main := &parser.FunctionDef{IsMain: true}
+	first, second := &parser.OutputStatement{}, &parser.OutputStatement{}
+	roles := []parser.SymbolRole{
+		{Symbol: symbolAt(30, 5), Kind: parser.RoleMainEntry, Node: main},
+		{Symbol: symbolAt(10, 30), Kind: parser.RoleOutput, Node: first},
+		{Symbol: symbolAt(50, 30), Kind: parser.RoleOutput, Node: second},
+	}
+	sourceMap := map[parser.ASTNode]compiler.LineRange{
+		main:   {Start: 1, End: 3},
+		first:  {Start: 2, End: 2},
+		second: {Start: 3, End: 3},
+	}
+	lines := &Lines{Steps: []int{1, 2, 2, 3, 2, 9}}
+
+	anim := Animate(img, roles, sourceMap, lines)
+
+	require.Len(t, anim.Image, 4, "repeated lines of a statement are one frame")
+	require.Len(t, anim.Delay, 4)
+	lit := func(frame, x, y int) bool {
+		_, _, b, _ := anim.Image[frame].At(x, y).RGBA()
+		return b < 0x8000
+	}
+	assert.True(t, lit(0, 30, 5))
+	assert.False(t, lit(0, 10, 30))
+	assert.True(t, lit(1, 10, 30), "the innermost statement lights up")
+	assert.False(t, lit(1, 30, 5))
+	assert.True(t, lit(2, 50, 30))
+	assert.True(t, lit(3, 10, 30))
+
+	still := Animate(img, roles, sourceMap, &Lines{})
+	assert.Len(t, still.Image, 1)
+}
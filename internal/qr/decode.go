@@ -0,0 +1,387 @@
+package qr
+
+import (
+	"bytes"
+	"errors"
+	"image"
+	"image/color"
+	"math"
+	"sort"
+)
+
+// ErrNotFound is returned when an image contains no readable QR code
+var ErrNotFound = errors.New("no QR code found")
+
+// maxFinderCandidates bounds the finder patterns tried when pairing them up
+const maxFinderCandidates = 30
+
+// finder is a candidate finder pattern center, in pixel edge coordinates
+type finder struct {
+	x, y   float64
+	module float64 // Estimated module size in pixels
+	hits   int     // Number of scan lines that found it
+}
+
+// bitmap is a thresholded image; true is dark
+type bitmap struct {
+	width, height int
+	dark          []bool
+}
+
+// Decode finds a QR code in img and returns the data it holds
+func Decode(img image.Image) ([]byte, error) {
+	b := newBitmap(img)
+	finders := b.findFinders()
+
+	// Any three finders forming an axis-aligned L may be a symbol; the
+	// format information and error correction reject false matches
+	for _, tl := range finders {
+		for _, tr := range finders {
+			for _, bl := range finders {
+				if data, ok := b.decodeAt(tl, tr, bl); ok {
+					return data, nil
+				}
+			}
+		}
+	}
+	return nil, ErrNotFound
+}
+
+func newBitmap(img image.Image) *bitmap {
+	bounds := img.Bounds()
+	b := &bitmap{width: bounds.Dx(), height: bounds.Dy()}
+	b.dark = make([]bool, b.width*b.height)
+	for y := 0; y < b.height; y++ {
+		for x := 0; x < b.width; x++ {
+			gray := color.GrayModel.Convert(img.At(bounds.Min.X+x, bounds.Min.Y+y)).(color.Gray)
+			b.dark[y*b.width+x] = gray.Y < 128
+		}
+	}
+	return b
+}
+
+func (b *bitmap) at(x, y int) bool {
+	if x < 0 || x >= b.width || y < 0 || y >= b.height {
+		return false
+	}
+	return b.dark[y*b.width+x]
+}
+
+// majority reports whether most pixels within radius of x, y are dark, so
+// that a stray pixel at the center of a module does not decide its color
+func (b *bitmap) majority(x, y, radius int) bool {
+	dark, total := 0, 0
+	for dy := -radius; dy <= radius; dy++ {
+		for dx := -radius; dx <= radius; dx++ {
+			if b.at(x+dx, y+dy) {
+				dark++
+			}
+			total++
+		}
+	}
+	return dark*2 > total
+}
+
+func (b *bitmap) row(y int) []bool {
+	return b.dark[y*b.width : (y+1)*b.width]
+}
+
+func (b *bitmap) column(x int) []bool {
+	col := make([]bool, b.height)
+	for y := range col {
+		col[y] = b.dark[y*b.width+x]
+	}
+	return col
+}
+
+// findFinders scans every row for the 1:1:3:1:1 dark-light-dark-light-dark
+// ratio of a finder pattern and confirms each match along its column
+func (b *bitmap) findFinders() []finder {
+	var finders []finder
+	for y := 0; y < b.height; y++ {
+		line := b.row(y)
+		for x := 0; x < b.width; x++ {
+			if !line[x] {
+				continue
+			}
+			cx, hCounts, ok := finderAt(line, x)
+			// Continue after this dark run
+			for x+1 < b.width && line[x+1] {
+				x++
+			}
+			if !ok {
+				continue
+			}
+
+			cy, vCounts, ok := finderAt(b.column(int(cx)), y)
+			if !ok {
+				continue
+			}
+			module := float64(sum(hCounts)+sum(vCounts)) / 14
+			finders = mergeFinder(finders, finder{x: cx, y: cy, module: module, hits: 1})
+		}
+	}
+
+	sort.SliceStable(finders, func(i, j int) bool { return finders[i].hits > finders[j].hits })
+	if len(finders) > maxFinderCandidates {
+		finders = finders[:maxFinderCandidates]
+	}
+	return finders
+}
+
+// finderAt checks whether the dark run containing line[pos] is the center of
+// a finder pattern, returning the center of the run and the run lengths
+func finderAt(line []bool, pos int) (float64, [5]int, bool) {
+	var counts [5]int
+	if pos < 0 || pos >= len(line) || !line[pos] {
+		return 0, counts, false
+	}
+
+	start, end := pos, pos
+	for start > 0 && line[start-1] {
+		start--
+	}
+	for end+1 < len(line) && line[end+1] {
+		end++
+	}
+	counts[2] = end - start + 1
+
+	i := start - 1
+	for ; i >= 0 && !line[i]; i-- {
+		counts[1]++
+	}
+	for ; i >= 0 && line[i]; i-- {
+		counts[0]++
+	}
+	i = end + 1
+	for ; i < len(line) && !line[i]; i++ {
+		counts[3]++
+	}
+	for ; i < len(line) && line[i]; i++ {
+		counts[4]++
+	}
+
+	if !finderRatio(counts) {
+		return 0, counts, false
+	}
+	return float64(start+end+1) / 2, counts, true
+}
+
+// finderRatio reports whether run lengths are close to 1:1:3:1:1
+func finderRatio(counts [5]int) bool {
+	total := sum(counts)
+	if total < 7 {
+		return false
+	}
+	module := float64(total) / 7
+	tolerance := module / 2
+	for i, count := range counts {
+		expected := 1.0
+		if i == 2 {
+			expected = 3
+		}
+		if math.Abs(float64(count)-module*expected) >= tolerance*expected {
+			return false
+		}
+	}
+	return true
+}
+
+// mergeFinder adds f to finders, averaging it into an existing candidate
+// with the same center
+func mergeFinder(finders []finder, f finder) []finder {
+	for i := range finders {
+		existing := &finders[i]
+		if math.Abs(existing.x-f.x) <= existing.module && math.Abs(existing.y-f.y) <= existing.module {
+			n := float64(existing.hits)
+			existing.x = (existing.x*n + f.x) / (n + 1)
+			existing.y = (existing.y*n + f.y) / (n + 1)
+			existing.module = (existing.module*n + f.module) / (n + 1)
+			existing.hits++
+			return finders
+		}
+	}
+	return append(finders, f)
+}
+
+// decodeAt reads the symbol whose top-left, top-right and bottom-left
+// finder patterns are tl, tr and bl
+func (b *bitmap) decodeAt(tl, tr, bl finder) ([]byte, bool) {
+	module := (tl.module + tr.module + bl.module) / 3
+	for _, f := range []finder{tl, tr, bl} {
+		if math.Abs(f.module-module) > module/2 {
+			return nil, false
+		}
+	}
+
+	width, height := tr.x-tl.x, bl.y-tl.y
+	if width <= 0 || height <= 0 ||
+		math.Abs(tr.y-tl.y) > 2*module || math.Abs(bl.x-tl.x) > 2*module ||
+		math.Abs(width-height) > width/10+2*module {
+		return nil, false
+	}
+
+	// The finder centers are seven modules closer together than the symbol is wide
+	estimate := int(math.Round(((width+height)/2/module + 7 - 17) / 4))
+	for version := estimate - 1; version <= estimate+1; version++ {
+		if version < minVersion || version > maxVersion {
+			continue
+		}
+		size := version*4 + 17
+		modX := width / float64(size-7)
+		modY := height / float64(size-7)
+
+		g := newGrid(version)
+		for y := 0; y < size; y++ {
+			for x := 0; x < size; x++ {
+				px := int(math.Floor(tl.x + float64(x-3)*modX))
+				py := int(math.Floor(tl.y + float64(y-3)*modY))
+				g.modules[y][x] = b.majority(px, py, int(module/4))
+			}
+		}
+		if data, err := g.decode(); err == nil {
+			return data, true
+		}
+	}
+	return nil, false
+}
+
+// decode reads the data of a sampled symbol
+func (g *grid) decode() ([]byte, error) {
+	level, mask, err := g.readFormat()
+	if err != nil {
+		return nil, err
+	}
+
+	// Mark the function modules without disturbing the sampled ones
+	sampled := g.modules
+	g.modules = make([][]bool, g.size)
+	for i := range g.modules {
+		g.modules[i] = make([]bool, g.size)
+	}
+	g.drawFunctionPatterns()
+	g.modules = sampled
+
+	raw := make([]byte, numRawDataModules(g.version)/8)
+	i := 0
+	g.codewordPositions(func(x, y int) {
+		if i < len(raw)*8 && g.modules[y][x] != masked(mask, x, y) {
+			raw[i>>3] |= 1 << (7 - i&7)
+		}
+		i++
+	})
+
+	data, err := deinterleave(raw, g.version, level)
+	if err != nil {
+		return nil, err
+	}
+	return readByteSegment(data, g.version)
+}
+
+// readFormat reads the error correction level and mask, accepting the
+// valid format closest to either copy if it is within three bits
+func (g *grid) readFormat() (Level, int, error) {
+	first, second := g.formatPositions()
+	for _, positions := range [][15]image.Point{first, second} {
+		bits := 0
+		for i, p := range positions {
+			if g.modules[p.Y][p.X] {
+				bits |= 1 << i
+			}
+		}
+
+		for _, level := range []Level{LevelL, LevelM} {
+			for mask := 0; mask < 8; mask++ {
+				if hammingDistance(bits, formatBits(level, mask)) <= 3 {
+					return level, mask, nil
+				}
+			}
+		}
+	}
+	return 0, 0, errors.New("unreadable format information")
+}
+
+// deinterleave splits raw codewords into blocks, checks each block's error
+// correction codewords and returns the data codewords
+func deinterleave(raw []byte, version int, level Level) ([]byte, error) {
+	layout := layoutFor(version, level)
+	blocks := make([][]byte, layout.blocks)
+	for j := range blocks {
+		blocks[j] = make([]byte, layout.shortLen+1)
+	}
+
+	k := 0
+	for i := 0; i <= layout.shortLen; i++ {
+		for j := range blocks {
+			if i != layout.shortLen-layout.eccLen || j >= layout.shortBlocks {
+				blocks[j][i] = raw[k]
+				k++
+			}
+		}
+	}
+
+	divisor := reedSolomonDivisor(layout.eccLen)
+	var data []byte
+	for j, block := range blocks {
+		n := layout.dataLen(j)
+		ecc := block[len(block)-layout.eccLen:]
+		if !bytes.Equal(reedSolomonRemainder(block[:n], divisor), ecc) {
+			return nil, errors.New("error correction check failed")
+		}
+		data = append(data, block[:n]...)
+	}
+	return data, nil
+}
+
+// readByteSegment reads the byte mode segment at the start of data
+func readByteSegment(data []byte, version int) ([]byte, error) {
+	r := bitReader{data: data}
+	if mode := r.read(4); mode != 0x4 {
+		return nil, errors.New("unsupported QR code mode")
+	}
+	length := r.read(charCountBits(version))
+	if r.pos+length*8 > len(data)*8 {
+		return nil, errors.New("QR code segment overruns its data")
+	}
+
+	result := make([]byte, length)
+	for i := range result {
+		result[i] = byte(r.read(8))
+	}
+	return result, nil
+}
+
+// bitReader reads bits most significant first
+type bitReader struct {
+	data []byte
+	pos  int
+}
+
+func (r *bitReader) read(n int) int {
+	value := 0
+	for i := 0; i < n; i++ {
+		bit := 0
+		if r.pos < len(r.data)*8 && getBit(int(r.data[r.pos>>3]), 7-r.pos&7) {
+			bit = 1
+		}
+		value = value<<1 | bit
+		r.pos++
+	}
+	return value
+}
+
+func hammingDistance(a, b int) int {
+	n := 0
+	for x := a ^ b; x != 0; x &= x - 1 {
+		n++
+	}
+	return n
+}
+
+func sum(counts [5]int) int {
+	total := 0
+	for _, c := range counts {
+		total += c
+	}
+	return total
+}
@@ -0,0 +1,545 @@
+// Package qr encodes and decodes QR codes in byte mode. Decoding expects an
+// axis-aligned symbol such as one drawn by Encode, at any scale; each module
+// is read from the pixels around its center, so scattered noise is
+// tolerated. It verifies the error correction codewords but does not
+// repair damaged symbols.
+package qr
+
+import (
+	"errors"
+	"image"
+	"image/color"
+	"image/draw"
+)
+
+// Level is an error correction level
+type Level int
+
+// Supported error correction levels
+const (
+	LevelL Level = iota // Recovers about 7% of codewords
+	LevelM              // Recovers about 15% of codewords
+)
+
+// QuietZone is the width, in modules, of the light border around a symbol
+const QuietZone = 4
+
+// Version limits
+const (
+	minVersion = 1
+	maxVersion = 40
+)
+
+// ErrTooLarge is returned when data does not fit in the largest symbol
+var ErrTooLarge = errors.New("data too large for a QR code")
+
+// eccCodewordsPerBlock and numBlocks are indexed by level, then version
+var (
+	eccCodewordsPerBlock = [2][41]int{
+		{-1, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28, 30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30},
+		{-1, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28},
+	}
+	numBlocks = [2][41]int{
+		{-1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13, 14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25},
+		{-1, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21, 23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49},
+	}
+)
+
+// formatLevelBits are the two format bits identifying each level
+var formatLevelBits = [2]int{1, 0}
+
+// Symbol is an encoded QR code
+type Symbol struct {
+	Version int
+	Size    int
+	modules [][]bool
+}
+
+// Dark reports whether the module at column x, row y is dark
+func (s *Symbol) Dark(x, y int) bool {
+	return s.modules[y][x]
+}
+
+// PixelSize returns the width of the symbol, including its quiet zone, when
+// drawn with the given number of pixels per module
+func (s *Symbol) PixelSize(scale int) int {
+	return (s.Size + 2*QuietZone) * scale
+}
+
+// Draw renders the symbol and its quiet zone onto dst with its top-left
+// corner at at, using scale pixels per module
+func (s *Symbol) Draw(dst draw.Image, at image.Point, scale int) {
+	size := s.PixelSize(scale)
+	draw.Draw(dst, image.Rect(at.X, at.Y, at.X+size, at.Y+size), image.NewUniform(color.White), image.Point{}, draw.Src)
+
+	black := image.NewUniform(color.Black)
+	for y := 0; y < s.Size; y++ {
+		for x := 0; x < s.Size; x++ {
+			if !s.modules[y][x] {
+				continue
+			}
+			px := at.X + (x+QuietZone)*scale
+			py := at.Y + (y+QuietZone)*scale
+			draw.Draw(dst, image.Rect(px, py, px+scale, py+scale), black, image.Point{}, draw.Src)
+		}
+	}
+}
+
+// Encode encodes data in byte mode using the smallest version that fits
+func Encode(data []byte, level Level) (*Symbol, error) {
+	version := 0
+	for v := minVersion; v <= maxVersion; v++ {
+		ccBits := charCountBits(v)
+		if len(data) < 1<<ccBits && 4+ccBits+8*len(data) <= numDataCodewords(v, level)*8 {
+			version = v
+			break
+		}
+	}
+	if version == 0 {
+		return nil, ErrTooLarge
+	}
+
+	// Mode indicator, character count and data, then terminator and padding
+	var bits bitBuffer
+	bits.appendBits(0x4, 4)
+	bits.appendBits(len(data), charCountBits(version))
+	for _, b := range data {
+		bits.appendBits(int(b), 8)
+	}
+	capacity := numDataCodewords(version, level) * 8
+	bits.appendBits(0, min(4, capacity-len(bits)))
+	bits.appendBits(0, (8-len(bits)%8)%8)
+	for pad := 0xEC; len(bits) < capacity; pad ^= 0xEC ^ 0x11 {
+		bits.appendBits(pad, 8)
+	}
+
+	g := newGrid(version)
+	g.drawFunctionPatterns()
+	g.drawCodewords(addECCAndInterleave(bits.bytes(), version, level))
+
+	// Use the mask with the lowest penalty
+	bestMask, bestPenalty := 0, -1
+	for mask := 0; mask < 8; mask++ {
+		g.applyMask(mask)
+		g.drawFormatBits(level, mask)
+		if penalty := g.penalty(); bestPenalty < 0 || penalty < bestPenalty {
+			bestMask, bestPenalty = mask, penalty
+		}
+		g.applyMask(mask) // Masking is its own inverse
+	}
+	g.applyMask(bestMask)
+	g.drawFormatBits(level, bestMask)
+
+	return &Symbol{Version: version, Size: g.size, modules: g.modules}, nil
+}
+
+// charCountBits is the width of the byte mode character count field
+func charCountBits(version int) int {
+	if version < 10 {
+		return 8
+	}
+	return 16
+}
+
+// numRawDataModules is the number of modules available for codewords,
+// including remainder bits
+func numRawDataModules(version int) int {
+	result := (16*version+128)*version + 64
+	if version >= 2 {
+		numAlign := version/7 + 2
+		result -= (25*numAlign-10)*numAlign - 55
+		if version >= 7 {
+			result -= 36
+		}
+	}
+	return result
+}
+
+// numDataCodewords is the number of data codewords a symbol holds
+func numDataCodewords(version int, level Level) int {
+	return numRawDataModules(version)/8 - eccCodewordsPerBlock[level][version]*numBlocks[level][version]
+}
+
+// blockLayout describes how codewords are split into blocks
+type blockLayout struct {
+	blocks      int // Number of blocks
+	eccLen      int // Error correction codewords per block
+	shortBlocks int // Number of blocks with one fewer data codeword
+	shortLen    int // Total codewords in a short block
+}
+
+func layoutFor(version int, level Level) blockLayout {
+	blocks := numBlocks[level][version]
+	raw := numRawDataModules(version) / 8
+	return blockLayout{
+		blocks:      blocks,
+		eccLen:      eccCodewordsPerBlock[level][version],
+		shortBlocks: blocks - raw%blocks,
+		shortLen:    raw / blocks,
+	}
+}
+
+// dataLen returns the number of data codewords in block i
+func (l blockLayout) dataLen(i int) int {
+	n := l.shortLen - l.eccLen
+	if i >= l.shortBlocks {
+		n++
+	}
+	return n
+}
+
+// addECCAndInterleave splits data into blocks, appends error correction
+// codewords to each and interleaves the result
+func addECCAndInterleave(data []byte, version int, level Level) []byte {
+	layout := layoutFor(version, level)
+	divisor := reedSolomonDivisor(layout.eccLen)
+
+	blocks := make([][]byte, layout.blocks)
+	k := 0
+	for i := range blocks {
+		dat := data[k : k+layout.dataLen(i)]
+		k += len(dat)
+		block := append([]byte{}, dat...)
+		if i < layout.shortBlocks {
+			block = append(block, 0) // Placeholder keeps columns aligned
+		}
+		blocks[i] = append(block, reedSolomonRemainder(dat, divisor)...)
+	}
+
+	var result []byte
+	for i := 0; i < len(blocks[0]); i++ {
+		for j, block := range blocks {
+			if i != layout.shortLen-layout.eccLen || j >= layout.shortBlocks {
+				result = append(result, block[i])
+			}
+		}
+	}
+	return result
+}
+
+// grid holds the modules of a symbol while it is built or read
+type grid struct {
+	version  int
+	size     int
+	modules  [][]bool
+	function [][]bool // Modules reserved for function patterns
+}
+
+func newGrid(version int) *grid {
+	size := version*4 + 17
+	g := &grid{version: version, size: size}
+	g.modules = make([][]bool, size)
+	g.function = make([][]bool, size)
+	for i := range g.modules {
+		g.modules[i] = make([]bool, size)
+		g.function[i] = make([]bool, size)
+	}
+	return g
+}
+
+func (g *grid) setFunction(x, y int, dark bool) {
+	g.modules[y][x] = dark
+	g.function[y][x] = true
+}
+
+// drawFunctionPatterns draws finder, timing and alignment patterns and
+// reserves the format and version areas
+func (g *grid) drawFunctionPatterns() {
+	for i := 0; i < g.size; i++ {
+		g.setFunction(6, i, i%2 == 0)
+		g.setFunction(i, 6, i%2 == 0)
+	}
+
+	g.drawFinder(3, 3)
+	g.drawFinder(g.size-4, 3)
+	g.drawFinder(3, g.size-4)
+
+	positions := alignmentPositions(g.version)
+	last := len(positions) - 1
+	for i, x := range positions {
+		for j, y := range positions {
+			// Skip the corners occupied by finder patterns
+			if i == 0 && j == 0 || i == 0 && j == last || i == last && j == 0 {
+				continue
+			}
+			for dy := -2; dy <= 2; dy++ {
+				for dx := -2; dx <= 2; dx++ {
+					g.setFunction(x+dx, y+dy, max(abs(dx), abs(dy)) != 1)
+				}
+			}
+		}
+	}
+
+	g.drawFormatBits(LevelL, 0) // Reserved now, overwritten once the mask is known
+	g.drawVersion()
+}
+
+// drawFinder draws a finder pattern and its separator centered on x, y
+func (g *grid) drawFinder(x, y int) {
+	for dy := -4; dy <= 4; dy++ {
+		for dx := -4; dx <= 4; dx++ {
+			xx, yy := x+dx, y+dy
+			if xx < 0 || xx >= g.size || yy < 0 || yy >= g.size {
+				continue
+			}
+			dist := max(abs(dx), abs(dy))
+			g.setFunction(xx, yy, dist != 2 && dist != 4)
+		}
+	}
+}
+
+// alignmentPositions returns the row and column centers of alignment patterns
+func alignmentPositions(version int) []int {
+	if version == 1 {
+		return nil
+	}
+	numAlign := version/7 + 2
+	step := (version*8 + numAlign*3 + 5) / (numAlign*4 - 4) * 2
+	result := make([]int, numAlign)
+	result[0] = 6
+	for i, pos := numAlign-1, version*4+17-7; i >= 1; i, pos = i-1, pos-step {
+		result[i] = pos
+	}
+	return result
+}
+
+// formatBits returns the 15-bit format information for a level and mask
+func formatBits(level Level, mask int) int {
+	data := formatLevelBits[level]<<3 | mask
+	rem := data
+	for i := 0; i < 10; i++ {
+		rem = (rem << 1) ^ ((rem >> 9) * 0x537)
+	}
+	return (data<<10 | rem) ^ 0x5412
+}
+
+// formatPositions returns the module coordinates of each format bit in the
+// first and second copies
+func (g *grid) formatPositions() (first, second [15]image.Point) {
+	for i := 0; i <= 5; i++ {
+		first[i] = image.Pt(8, i)
+	}
+	first[6] = image.Pt(8, 7)
+	first[7] = image.Pt(8, 8)
+	first[8] = image.Pt(7, 8)
+	for i := 9; i < 15; i++ {
+		first[i] = image.Pt(14-i, 8)
+	}
+
+	for i := 0; i < 8; i++ {
+		second[i] = image.Pt(g.size-1-i, 8)
+	}
+	for i := 8; i < 15; i++ {
+		second[i] = image.Pt(8, g.size-15+i)
+	}
+	return first, second
+}
+
+func (g *grid) drawFormatBits(level Level, mask int) {
+	bits := formatBits(level, mask)
+	first, second := g.formatPositions()
+	for i := 0; i < 15; i++ {
+		dark := getBit(bits, i)
+		g.setFunction(first[i].X, first[i].Y, dark)
+		g.setFunction(second[i].X, second[i].Y, dark)
+	}
+	g.setFunction(8, g.size-8, true) // Always dark
+}
+
+// drawVersion draws the version information of versions 7 and up
+func (g *grid) drawVersion() {
+	if g.version < 7 {
+		return
+	}
+	rem := g.version
+	for i := 0; i < 12; i++ {
+		rem = (rem << 1) ^ ((rem >> 11) * 0x1F25)
+	}
+	bits := g.version<<12 | rem
+	for i := 0; i < 18; i++ {
+		dark := getBit(bits, i)
+		a, b := g.size-11+i%3, i/3
+		g.setFunction(a, b, dark)
+		g.setFunction(b, a, dark)
+	}
+}
+
+// codewordPositions calls fn for every data module in placement order
+func (g *grid) codewordPositions(fn func(x, y int)) {
+	for right := g.size - 1; right >= 1; right -= 2 {
+		if right == 6 {
+			right = 5 // Skip the vertical timing pattern
+		}
+		for vert := 0; vert < g.size; vert++ {
+			for j := 0; j < 2; j++ {
+				x := right - j
+				y := vert
+				if (right+1)&2 == 0 {
+					y = g.size - 1 - vert // Upward column
+				}
+				if !g.function[y][x] {
+					fn(x, y)
+				}
+			}
+		}
+	}
+}
+
+func (g *grid) drawCodewords(data []byte) {
+	i := 0
+	g.codewordPositions(func(x, y int) {
+		if i < len(data)*8 {
+			g.modules[y][x] = getBit(int(data[i>>3]), 7-i&7)
+		}
+		i++
+	})
+}
+
+// masked reports whether mask inverts the module at x, y
+func masked(mask, x, y int) bool {
+	switch mask {
+	case 0:
+		return (x+y)%2 == 0
+	case 1:
+		return y%2 == 0
+	case 2:
+		return x%3 == 0
+	case 3:
+		return (x+y)%3 == 0
+	case 4:
+		return (x/3+y/2)%2 == 0
+	case 5:
+		return x*y%2+x*y%3 == 0
+	case 6:
+		return (x*y%2+x*y%3)%2 == 0
+	default:
+		return ((x+y)%2+x*y%3)%2 == 0
+	}
+}
+
+func (g *grid) applyMask(mask int) {
+	for y := 0; y < g.size; y++ {
+		for x := 0; x < g.size; x++ {
+			if !g.function[y][x] && masked(mask, x, y) {
+				g.modules[y][x] = !g.modules[y][x]
+			}
+		}
+	}
+}
+
+// penalty scores how hard the symbol is to read: long runs, 2x2 blocks and
+// an unbalanced dark ratio all add to it
+func (g *grid) penalty() int {
+	result := 0
+	for i := 0; i < g.size; i++ {
+		rowRun, colRun := 1, 1
+		for j := 1; j < g.size; j++ {
+			rowRun = runPenalty(&result, rowRun, g.modules[i][j] == g.modules[i][j-1])
+			colRun = runPenalty(&result, colRun, g.modules[j][i] == g.modules[j-1][i])
+		}
+		runPenalty(&result, rowRun, false)
+		runPenalty(&result, colRun, false)
+	}
+
+	dark := 0
+	for y := 0; y < g.size; y++ {
+		for x := 0; x < g.size; x++ {
+			if g.modules[y][x] {
+				dark++
+			}
+			if x > 0 && y > 0 {
+				c := g.modules[y][x]
+				if c == g.modules[y][x-1] && c == g.modules[y-1][x] && c == g.modules[y-1][x-1] {
+					result += 3
+				}
+			}
+		}
+	}
+
+	total := g.size * g.size
+	k := (abs(dark*20-total*10)+total-1)/total - 1
+	return result + k*10
+}
+
+// runPenalty extends a run of same-colored modules, adding the penalty for
+// runs of five or more once the run ends
+func runPenalty(result *int, run int, same bool) int {
+	if same {
+		return run + 1
+	}
+	if run >= 5 {
+		*result += 3 + run - 5
+	}
+	return 1
+}
+
+// reedSolomonDivisor returns the generator polynomial of the given degree
+func reedSolomonDivisor(degree int) []byte {
+	result := make([]byte, degree)
+	result[degree-1] = 1
+	root := byte(1)
+	for i := 0; i < degree; i++ {
+		for j := range result {
+			result[j] = gfMultiply(result[j], root)
+			if j+1 < len(result) {
+				result[j] ^= result[j+1]
+			}
+		}
+		root = gfMultiply(root, 0x02)
+	}
+	return result
+}
+
+// reedSolomonRemainder returns the error correction codewords for data
+func reedSolomonRemainder(data, divisor []byte) []byte {
+	result := make([]byte, len(divisor))
+	for _, b := range data {
+		factor := b ^ result[0]
+		copy(result, result[1:])
+		result[len(result)-1] = 0
+		for i, d := range divisor {
+			result[i] ^= gfMultiply(d, factor)
+		}
+	}
+	return result
+}
+
+// gfMultiply multiplies in GF(2^8) modulo x^8 + x^4 + x^3 + x^2 + 1
+func gfMultiply(x, y byte) byte {
+	z := 0
+	for i := 7; i >= 0; i-- {
+		z = (z << 1) ^ ((z >> 7) * 0x11D)
+		z ^= int((y>>i)&1) * int(x)
+	}
+	return byte(z)
+}
+
+// bitBuffer is a sequence of bits, most significant first
+type bitBuffer []bool
+
+func (b *bitBuffer) appendBits(value, length int) {
+	for i := length - 1; i >= 0; i-- {
+		*b = append(*b, getBit(value, i))
+	}
+}
+
+func (b bitBuffer) bytes() []byte {
+	result := make([]byte, (len(b)+7)/8)
+	for i, bit := range b {
+		if bit {
+			result[i>>3] |= 1 << (7 - i&7)
+		}
+	}
+	return result
+}
+
+func getBit(x, i int) bool {
+	return (x>>i)&1 != 0
+}
+
+func abs(x int) int {
+	if x < 0 {
+		return -x
+	}
+	return x
+}
@@ -0,0 +1,149 @@
+package qr
+
+import (
+	"image"
+	"image/color"
+	"image/draw"
+	"math/rand"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestReedSolomonRemainder(t *testing.T) {
+	// Version 1-M "HELLO WORLD" from the QR code specification walkthrough
+	data := []byte{32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17}
+	expected := []byte{196, 35, 39, 119, 235, 215, 231, 226, 93, 23}
+	assert.Equal(t, expected, reedSolomonRemainder(data, reedSolomonDivisor(10)))
+}
+
+func TestFormatBits(t *testing.T) {
+	assert.Equal(t, 0b110011000101111, formatBits(LevelL, 4))
+}
+
+func TestDataCapacity(t *testing.T) {
+	assert.Equal(t, 16, numDataCodewords(1, LevelM))
+	assert.Equal(t, 19, numDataCodewords(1, LevelL))
+	assert.Equal(t, 216, numDataCodewords(10, LevelM))
+	assert.Equal(t, 2334, numDataCodewords(40, LevelM))
+	assert.Equal(t, []int{6, 22, 38}, alignmentPositions(7))
+	assert.Equal(t, []int{6, 34, 60, 86, 112, 138}, alignmentPositions(32))
+}
+
+func TestEncodeDecodeRoundTrip(t *testing.T) {
+	rng := rand.New(rand.NewSource(1))
+	tests := []struct {
+		name   string
+		length int
+		level  Level
+		scale  int
+	}{
+		{name: "version 1", length: 5, level: LevelM, scale: 1},
+		{name: "small scaled", length: 40, level: LevelL, scale: 2},
+		{name: "version info", length: 200, level: LevelM, scale: 3},
+		{name: "many blocks", length: 1000, level: LevelM, scale: 1},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			data := make([]byte, tt.length)
+			rng.Read(data)
+
+			symbol, err := Encode(data, tt.level)
+			require.NoError(t, err)
+			assert.Equal(t, symbol.Version*4+17, symbol.Size)
+
+			// Draw the symbol off-center on a larger canvas
+			size := symbol.PixelSize(tt.scale)
+			img := image.NewRGBA(image.Rect(0, 0, size+37, size+50))
+			draw.Draw(img, img.Bounds(), image.NewUniform(color.White), image.Point{}, draw.Src)
+			symbol.Draw(img, image.Pt(30, 41), tt.scale)
+
+			decoded, err := Decode(img)
+			require.NoError(t, err)
+			assert.Equal(t, data, decoded)
+		})
+	}
+}
+
+func TestDecodeBesideOtherShapes(t *testing.T) {
+	symbol, err := Encode([]byte("print('hello')"), LevelM)
+	require.NoError(t, err)
+
+	img := image.NewRGBA(image.Rect(0, 0, 400, 300))
+	draw.Draw(img, img.Bounds(), image.NewUniform(color.White), image.Point{}, draw.Src)
+	// A ring and some blocks that are not part of the symbol
+	for y := 0; y < 300; y++ {
+		for x := 0; x < 250; x++ {
+			dx, dy := x-125, y-150
+			if d := dx*dx + dy*dy; d > 100*100 && d < 106*106 {
+				img.Set(x, y, color.Black)
+			}
+		}
+	}
+	draw.Draw(img, image.Rect(100, 120, 130, 150), image.NewUniform(color.Black), image.Point{}, draw.Src)
+	symbol.Draw(img, image.Pt(400-symbol.PixelSize(3), 300-symbol.PixelSize(3)), 3)
+
+	decoded, err := Decode(img)
+	require.NoError(t, err)
+	assert.Equal(t, "print('hello')", string(decoded))
+}
+
+func TestDecodeRescaledNoisy(t *testing.T) {
+	const program = "# grimoire\nprint('Hello, World!')\n"
+	symbol, err := Encode([]byte(program), LevelM)
+	require.NoError(t, err)
+	size := symbol.PixelSize(3)
+	img := image.NewRGBA(image.Rect(0, 0, size+40, size+40))
+	draw.Draw(img, img.Bounds(), image.NewUniform(color.White), image.Point{}, draw.Src)
+	symbol.Draw(img, image.Pt(20, 20), 3)
+
+	// Scaled by 5/3 with nearest neighbors, so modules are 5 pixels wide
+	// and unevenly placed, then with one pixel in a hundred flipped
+	rng := rand.New(rand.NewSource(7))
+	bounds := img.Bounds()
+	scaled := image.NewGray(image.Rect(0, 0, bounds.Dx()*5/3, bounds.Dy()*5/3))
+	for y := 0; y < scaled.Bounds().Dy(); y++ {
+		for x := 0; x < scaled.Bounds().Dx(); x++ {
+			gray := color.GrayModel.Convert(img.At(x*3/5, y*3/5)).(color.Gray)
+			if rng.Intn(100) == 0 {
+				gray.Y = 255 - gray.Y
+			}
+			scaled.SetGray(x, y, gray)
+		}
+	}
+
+	decoded, err := Decode(scaled)
+	require.NoError(t, err)
+	assert.Equal(t, program, string(decoded))
+}
+
+func TestDecodeErrors(t *testing.T) {
+	blank := image.NewRGBA(image.Rect(0, 0, 100, 100))
+	draw.Draw(blank, blank.Bounds(), image.NewUniform(color.White), image.Point{}, draw.Src)
+	_, err := Decode(blank)
+	assert.ErrorIs(t, err, ErrNotFound)
+
+	// Corrupting data modules must fail the error correction check
+	symbol, err := Encode([]byte("damaged"), LevelM)
+	require.NoError(t, err)
+	g := newGrid(symbol.Version)
+	g.drawFunctionPatterns()
+	flipped := 0
+	g.codewordPositions(func(x, y int) {
+		if flipped < 8 && x > 12 {
+			symbol.modules[y][x] = !symbol.modules[y][x]
+			flipped++
+		}
+	})
+	img := image.NewRGBA(image.Rect(0, 0, symbol.PixelSize(2), symbol.PixelSize(2)))
+	symbol.Draw(img, image.Point{}, 2)
+	_, err = Decode(img)
+	assert.ErrorIs(t, err, ErrNotFound)
+}
+
+func TestEncodeTooLarge(t *testing.T) {
+	_, err := Encode(make([]byte, 3000), LevelM)
+	assert.ErrorIs(t, err, ErrTooLarge)
+}
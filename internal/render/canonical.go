@@ -0,0 +1,137 @@
+package render
+
+import (
+	"math"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// Canonical layout
+const (
+	// CanonicalRadius is the radius, in pixels, of the outer circle
+	CanonicalRadius = 250
+	// Grid is the spacing, in pixels, that symbol centers are snapped to
+	Grid = 10
+)
+
+// canonicalSizes are the sizes symbols of each type are drawn at. Symbols
+// of other types keep their size, scaled with the drawing.
+var canonicalSizes = map[detector.SymbolType]float64{
+	detector.Circle:           30,
+	detector.DoubleCircle:     36,
+	detector.Square:           30,
+	detector.Triangle:         40,
+	detector.Pentagon:         40,
+	detector.Hexagon:          40,
+	detector.Star:             40,
+	detector.SixPointedStar:   44,
+	detector.EightPointedStar: 48,
+	detector.Convergence:      32,
+	detector.Divergence:       32,
+	detector.Amplification:    36,
+	detector.Distribution:     36,
+	detector.Transfer:         40,
+	detector.Equal:            32,
+	detector.LessThan:         36,
+	detector.GreaterThan:      36,
+}
+
+// Canonical returns copies of symbols and connections laid out in the
+// canonical style. The outer circle gets CanonicalRadius, with the margin
+// of opts around it. Every other symbol keeps its place relative to the
+// center of the circle, scaled with it and snapped to the grid, and gets
+// the canonical size of its type. Glyphs on the ring stay on it, stage
+// rings stay concentric and list frames keep enclosing their row. The
+// connections join the copies and lose their anchors, so they are drawn
+// straight.
+func Canonical(symbols []*detector.Symbol, connections []detector.Connection, opts Options) ([]*detector.Symbol, []detector.Connection) {
+	// Without an outer circle, positions are only snapped
+	var origin, center detector.Position
+	scale := 1.0
+	for _, s := range symbols {
+		if s.Type == detector.OuterCircle {
+			origin = s.Position
+			center = detector.Position{X: opts.Margin + CanonicalRadius, Y: opts.Margin + CanonicalRadius}
+			scale = CanonicalRadius / s.Radius()
+			break
+		}
+	}
+
+	copies := make(map[*detector.Symbol]*detector.Symbol, len(symbols))
+	laidOut := make([]*detector.Symbol, len(symbols))
+	for i, s := range symbols {
+		c := copySymbol(s)
+		dx, dy := (s.Position.X-origin.X)*scale, (s.Position.Y-origin.Y)*scale
+
+		switch {
+		case s.Type == detector.OuterCircle:
+			c.Position = center
+			c.Size = CanonicalRadius * math.Sqrt(math.Pi)
+		case s.StageRing:
+			c.Position = center
+			c.Size = snap(s.Radius()*scale) * math.Sqrt(math.Pi)
+		case s.OnRing:
+			angle := math.Atan2(dy, dx)
+			c.Position = offset(center, CanonicalRadius*math.Cos(angle), CanonicalRadius*math.Sin(angle))
+			c.Size = canonicalSize(s, scale)
+		case s.ListFrame:
+			c.Position = offset(center, snap(dx), snap(dy))
+			// Members move by up to half the grid, so frames grow by a grid
+			c.Width = math.Ceil(s.Width*scale/Grid)*Grid + Grid
+			c.Height = math.Ceil(s.Height*scale/Grid)*Grid + Grid
+			c.Size = math.Sqrt(c.Width * c.Height)
+		case s.Type == detector.Rectangle:
+			c.Position = offset(center, snap(dx), snap(dy))
+			c.Width, c.Height = snap(s.Width*scale), snap(s.Height*scale)
+			c.Size = math.Sqrt(c.Width * c.Height)
+		default:
+			c.Position = offset(center, snap(dx), snap(dy))
+			c.Size = canonicalSize(s, scale)
+		}
+		copies[s] = c
+		laidOut[i] = c
+	}
+	return laidOut, join(connections, copies)
+}
+
+// copySymbol returns a copy of s with its own properties
+func copySymbol(s *detector.Symbol) *detector.Symbol {
+	c := *s
+	c.Properties = make(map[string]interface{}, len(s.Properties))
+	for k, v := range s.Properties {
+		c.Properties[k] = v
+	}
+	return &c
+}
+
+// join returns straight connections between the copies of the symbols
+// connections join, leaving out those with a symbol that was not copied
+func join(connections []detector.Connection, copies map[*detector.Symbol]*detector.Symbol) []detector.Connection {
+	joined := make([]detector.Connection, 0, len(connections))
+	for _, conn := range connections {
+		from, to := copies[conn.From], copies[conn.To]
+		if from == nil || to == nil {
+			continue
+		}
+		joined = append(joined, detector.Connection{
+			From:           from,
+			To:             to,
+			ConnectionType: conn.ConnectionType,
+			Properties:     conn.Properties,
+		})
+	}
+	return joined
+}
+
+// canonicalSize returns the size s is drawn at in a drawing scaled by scale
+func canonicalSize(s *detector.Symbol, scale float64) float64 {
+	if size, ok := canonicalSizes[s.Type]; ok {
+		return size
+	}
+	return s.Size * scale
+}
+
+// snap rounds v to the nearest multiple of Grid
+func snap(v float64) float64 {
+	return math.Round(v/Grid) * Grid
+}
@@ -0,0 +1,233 @@
+package render
+
+import (
+	"math"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// up is the angle pointing to the top of the image
+const up = -math.Pi / 2
+
+// Proportions of the glyphs that are not regular shapes, relative to the
+// size of the symbol
+const (
+	equalWidth   = 1.5 // the bars of an equals sign
+	equalHeight  = 0.4
+	arrowLength  = 1.4 // a transfer arrow
+	arrowHead    = 0.5
+	arrowWidth   = 0.8
+	arrowShaft   = 0.3
+	wedgeLength  = 1.2 // a less-than or greater-than sign
+	wedgeOpening = 0.7
+)
+
+// starPoints and starRatios give the number of points of each star-shaped
+// symbol and the ratio of its inner radius to its outer one
+var (
+	starPoints = map[detector.SymbolType]int{
+		detector.Star:             5,
+		detector.SixPointedStar:   6,
+		detector.EightPointedStar: 8,
+		detector.Amplification:    4,
+		detector.Distribution:     8,
+	}
+	starRatios = map[detector.SymbolType]float64{
+		detector.Star:             0.4,
+		detector.SixPointedStar:   0.55,
+		detector.EightPointedStar: 0.5,
+		detector.Amplification:    0.4,
+		detector.Distribution:     0.7,
+	}
+)
+
+// polygonSides gives the number of corners of each polygon-shaped symbol
+var polygonSides = map[detector.SymbolType]int{
+	detector.Triangle: 3,
+	detector.Pentagon: 5,
+	detector.Hexagon:  6,
+}
+
+// glyph is the drawing of a symbol: a closed outline, open strokes, or a
+// circle around center when there is neither
+type glyph struct {
+	outline []detector.Position
+	filled  bool // the outline is filled instead of stroked
+	strokes [][2]detector.Position
+	center  detector.Position
+	radius  float64
+}
+
+// glyphOf returns the drawing of s, sized so that its outline encloses
+// about the area the detector measured for it
+func glyphOf(s *detector.Symbol) glyph {
+	at, size := s.Position, s.Size
+	if s.ListFrame || (s.Type == detector.Rectangle && s.Width > 0 && s.Height > 0) {
+		return glyph{outline: rectangle(at, s.Width, s.Height)}
+	}
+	if n, ok := polygonSides[s.Type]; ok {
+		return glyph{outline: polygon(at, n, polygonRadius(n, size), up)}
+	}
+	if n, ok := starPoints[s.Type]; ok {
+		ratio := starRatios[s.Type]
+		outer := size / math.Sqrt(float64(n)*ratio*math.Sin(math.Pi/float64(n)))
+		return glyph{outline: star(at, n, outer, outer*ratio, up)}
+	}
+
+	h := size / 2
+	switch s.Type {
+	case detector.Square, detector.Rectangle:
+		return glyph{outline: rectangle(at, size, size)}
+	case detector.Equal:
+		return glyph{outline: rectangle(at, equalWidth*size, equalHeight*size)}
+	case detector.Convergence:
+		// Two arms meeting in a stem below them
+		return glyph{strokes: [][2]detector.Position{
+			{offset(at, -h, -h), at}, {offset(at, h, -h), at}, {at, offset(at, 0, h)},
+		}}
+	case detector.Divergence:
+		// A stem parting into two arms below it
+		return glyph{strokes: [][2]detector.Position{
+			{offset(at, 0, -h), at}, {at, offset(at, -h, h)}, {at, offset(at, h, h)},
+		}}
+	case detector.Transfer:
+		l, head := arrowLength*size/2, arrowHead*size
+		w, shaft := arrowWidth*size/2, arrowShaft*size/2
+		return glyph{filled: true, outline: []detector.Position{
+			offset(at, -l, -shaft), offset(at, l-head, -shaft), offset(at, l-head, -w), offset(at, l, 0),
+			offset(at, l-head, w), offset(at, l-head, shaft), offset(at, -l, shaft),
+		}}
+	case detector.LessThan, detector.GreaterThan:
+		l, w := wedgeLength*size/2, wedgeOpening*size/2
+		if s.Type == detector.GreaterThan {
+			l = -l
+		}
+		return glyph{outline: []detector.Position{offset(at, -l, 0), offset(at, l, -w), offset(at, l, w)}}
+	}
+	return glyph{center: at, radius: s.Radius()}
+}
+
+// reach returns the distance from center to the farthest point of g
+func (g glyph) reach(center detector.Position) float64 {
+	reach := g.radius
+	for _, p := range g.outline {
+		reach = math.Max(reach, math.Hypot(p.X-center.X, p.Y-center.Y))
+	}
+	for _, stroke := range g.strokes {
+		for _, p := range stroke {
+			reach = math.Max(reach, math.Hypot(p.X-center.X, p.Y-center.Y))
+		}
+	}
+	return reach
+}
+
+// glyph draws g with strokes of the given width
+func (c canvas) glyph(g glyph, width float64) {
+	switch {
+	case g.filled:
+		c.fill(g.outline)
+	case len(g.outline) > 0:
+		c.outline(g.outline, width)
+	case len(g.strokes) > 0:
+		for _, stroke := range g.strokes {
+			c.line(stroke[0], stroke[1], width)
+		}
+	default:
+		c.ring(g.center, g.radius, width)
+	}
+}
+
+// patternOf returns the pattern drawn inside s. The detector reads a
+// circle with a dot in it as a double circle.
+func patternOf(s *detector.Symbol) string {
+	if s.Type == detector.DoubleCircle {
+		return detector.PatternDot
+	}
+	if s.Type == detector.OuterCircle || s.ListFrame {
+		return detector.PatternEmpty
+	}
+	return s.Pattern
+}
+
+// pattern draws the marks inside a symbol of the given size at center that
+// encode its literal value
+func (c canvas) pattern(center detector.Position, pattern string, size, width float64) {
+	dots := 0
+	switch pattern {
+	case detector.PatternDot:
+		dots = 1
+	case detector.PatternDoubleDot:
+		dots = 2
+	case detector.PatternTripleDot:
+		dots = 3
+	case detector.PatternLines:
+		// A grid, so that neither direction dominates
+		q := size / 6
+		for _, d := range []float64{-q, q} {
+			c.line(offset(center, -2*q, d), offset(center, 2*q, d), width)
+			c.line(offset(center, d, -2*q), offset(center, d, 2*q), width)
+		}
+	case detector.PatternTripleLine:
+		q := size / 5
+		for _, d := range []float64{-q, 0, q} {
+			c.line(offset(center, -q*1.5, d), offset(center, q*1.5, d), width)
+		}
+	case detector.PatternCross:
+		q := size / 3
+		c.line(offset(center, -q, 0), offset(center, q, 0), size/4)
+		c.line(offset(center, 0, -q), offset(center, 0, q), size/4)
+	case detector.PatternHalfCircle:
+		c.halfDisk(center, size/4)
+	}
+
+	spacing := size / 4
+	for i := 0; i < dots; i++ {
+		dx := (float64(i) - float64(dots-1)/2) * spacing
+		c.disk(offset(center, dx, 0), math.Max(2, size/12))
+	}
+}
+
+// polygonRadius returns the radius of the regular polygon with n corners
+// whose area is size squared
+func polygonRadius(n int, size float64) float64 {
+	return size * math.Sqrt(2/(float64(n)*math.Sin(2*math.Pi/float64(n))))
+}
+
+// polygon returns the corners of the regular polygon with n corners at
+// radius around center, the first at angle
+func polygon(center detector.Position, n int, radius, angle float64) []detector.Position {
+	points := make([]detector.Position, n)
+	for i := range points {
+		theta := angle + 2*math.Pi*float64(i)/float64(n)
+		points[i] = offset(center, radius*math.Cos(theta), radius*math.Sin(theta))
+	}
+	return points
+}
+
+// star returns the corners of a star with n points at radius outer and
+// notches at radius inner, the first point at angle
+func star(center detector.Position, n int, outer, inner, angle float64) []detector.Position {
+	points := make([]detector.Position, 2*n)
+	for i := range points {
+		r := outer
+		if i%2 == 1 {
+			r = inner
+		}
+		theta := angle + math.Pi*float64(i)/float64(n)
+		points[i] = offset(center, r*math.Cos(theta), r*math.Sin(theta))
+	}
+	return points
+}
+
+// rectangle returns the corners of an axis-aligned rectangle
+func rectangle(center detector.Position, width, height float64) []detector.Position {
+	w, h := width/2, height/2
+	return []detector.Position{
+		offset(center, -w, -h), offset(center, w, -h), offset(center, w, h), offset(center, -w, h),
+	}
+}
+
+// offset returns p moved by dx, dy
+func offset(p detector.Position, dx, dy float64) detector.Position {
+	return detector.Position{X: p.X + dx, Y: p.Y + dy}
+}
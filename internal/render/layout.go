@@ -0,0 +1,341 @@
+package render
+
+import (
+	"math"
+	"math/rand"
+	"sort"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// LayoutOptions control how Layout places symbols
+type LayoutOptions struct {
+	// Seed shuffles the orderings tried after the first; the same seed
+	// always gives the same layout
+	Seed int64
+	// Restarts is how many orderings of the rings are tried. The first
+	// keeps the order of the symbols, the others are shuffled.
+	Restarts int
+	// Sweeps is how many times each ordering is refined before its
+	// crossings are counted
+	Sweeps int
+	// Fill is the share of the radius of the outer circle the rings use
+	Fill float64
+	// Spacing is the least distance, in pixels, between neighbors on a
+	// ring. Crowded rings grow outward, up to Fill.
+	Spacing float64
+}
+
+// DefaultLayoutOptions returns the options Layout places symbols with
+func DefaultLayoutOptions() LayoutOptions {
+	return LayoutOptions{Seed: 1, Restarts: 8, Sweeps: 4, Fill: 0.8, Spacing: 60}
+}
+
+// Layout returns copies of symbols and connections with the symbols placed
+// anew inside an outer circle of CanonicalRadius, for circles that were
+// generated rather than drawn. Each symbol goes on a ring by the length of
+// the longest chain of connections reaching it, so that the program flows
+// outward from the middle, and takes a sector of its ring. The order of
+// each ring is chosen to cross as few connections as possible; of the
+// orderings tried, the first with the fewest crossings wins.
+//
+// An outer circle is added when there is none. Glyphs on the ring stay on
+// it and stage rings stay concentric, as in Canonical. A list frame moves
+// with the symbols inside it.
+func Layout(symbols []*detector.Symbol, connections []detector.Connection, opts Options) ([]*detector.Symbol, []detector.Connection) {
+	center := detector.Position{X: opts.Margin + CanonicalRadius, Y: opts.Margin + CanonicalRadius}
+	var origin detector.Position
+	scale, outer := 1.0, false
+	for _, s := range symbols {
+		if s.Type == detector.OuterCircle {
+			origin, scale, outer = s.Position, CanonicalRadius/s.Radius(), true
+			break
+		}
+	}
+
+	// The symbols placed on rings, and the one each symbol moves with
+	var units []*detector.Symbol
+	unitOf := make(map[*detector.Symbol]int, len(symbols))
+	for _, s := range symbols {
+		if s.Type != detector.OuterCircle && !s.StageRing && !s.OnRing && frameOf(s, symbols) == nil {
+			unitOf[s] = len(units)
+			units = append(units, s)
+		}
+	}
+	for _, s := range symbols {
+		if frame := frameOf(s, symbols); frame != nil {
+			if u, ok := unitOf[frame]; ok {
+				unitOf[s] = u
+			}
+		}
+	}
+
+	var edges [][2]int
+	for _, conn := range connections {
+		from, fromOK := unitOf[conn.From]
+		to, toOK := unitOf[conn.To]
+		if fromOK && toOK && from != to {
+			edges = append(edges, [2]int{from, to})
+		}
+	}
+	at := arrange(len(units), edges, center, opts.Layout)
+
+	copies := make(map[*detector.Symbol]*detector.Symbol, len(symbols))
+	laidOut := make([]*detector.Symbol, 0, len(symbols)+1)
+	if !outer {
+		laidOut = append(laidOut, &detector.Symbol{
+			Type:       detector.OuterCircle,
+			Position:   center,
+			Size:       CanonicalRadius * math.Sqrt(math.Pi),
+			Properties: map[string]interface{}{},
+		})
+	}
+	for _, s := range symbols {
+		c := copySymbol(s)
+		dx, dy := (s.Position.X-origin.X)*scale, (s.Position.Y-origin.Y)*scale
+		u, placed := unitOf[s]
+
+		switch {
+		case s.Type == detector.OuterCircle:
+			c.Position = center
+			c.Size = CanonicalRadius * math.Sqrt(math.Pi)
+		case s.StageRing:
+			c.Position = center
+			c.Size = snap(s.Radius()*scale) * math.Sqrt(math.Pi)
+		case s.OnRing:
+			angle := math.Atan2(dy, dx)
+			c.Position = offset(center, CanonicalRadius*math.Cos(angle), CanonicalRadius*math.Sin(angle))
+			c.Size = canonicalSize(s, scale)
+		case !placed:
+			// Inside a frame that stays where it is
+		case units[u] != s:
+			// Inside a frame, keeping its place in the row
+			frame := units[u]
+			c.Position = offset(at[u], s.Position.X-frame.Position.X, s.Position.Y-frame.Position.Y)
+		case s.ListFrame:
+			c.Position = at[u]
+		default:
+			c.Position = at[u]
+			c.Size = canonicalSize(s, 1)
+		}
+		copies[s] = c
+		laidOut = append(laidOut, c)
+	}
+	return laidOut, join(connections, copies)
+}
+
+// frameOf returns the list frame among symbols that s is drawn inside, or
+// nil when there is none
+func frameOf(s *detector.Symbol, symbols []*detector.Symbol) *detector.Symbol {
+	if s.ListFrame || s.StageRing || s.OnRing || s.Type == detector.OuterCircle {
+		return nil
+	}
+	for _, frame := range symbols {
+		if frame.ListFrame &&
+			math.Abs(s.Position.X-frame.Position.X) <= frame.Width/2 &&
+			math.Abs(s.Position.Y-frame.Position.Y) <= frame.Height/2 {
+			return frame
+		}
+	}
+	return nil
+}
+
+// arrange returns the position of each of n units joined by edges, on
+// rings around center
+func arrange(n int, edges [][2]int, center detector.Position, opts LayoutOptions) []detector.Position {
+	if n == 0 {
+		return nil
+	}
+	layer := layers(n, edges)
+	depth := 0
+	for _, l := range layer {
+		depth = max(depth, l+1)
+	}
+	rings := make([][]int, depth)
+	for v, l := range layer {
+		rings[l] = append(rings[l], v)
+	}
+	adjacent := make([][]int, n)
+	for _, e := range edges {
+		adjacent[e[0]] = append(adjacent[e[0]], e[1])
+		adjacent[e[1]] = append(adjacent[e[1]], e[0])
+	}
+
+	rng := rand.New(rand.NewSource(opts.Seed))
+	var best []detector.Position
+	fewest := -1
+	for restart := 0; restart < max(1, opts.Restarts); restart++ {
+		order := make([][]int, depth)
+		for d, ring := range rings {
+			order[d] = append([]int(nil), ring...)
+			if restart > 0 {
+				rng.Shuffle(len(order[d]), func(i, j int) {
+					order[d][i], order[d][j] = order[d][j], order[d][i]
+				})
+			}
+		}
+		for i := 0; i < opts.Sweeps; i++ {
+			sweep(order, layer, adjacent)
+		}
+		at := place(order, n, center, opts)
+		if crossed := crossings(edges, at); fewest < 0 || crossed < fewest {
+			best, fewest = at, crossed
+		}
+	}
+	return best
+}
+
+// layers returns the ring of each of n units: the length of the longest
+// chain of edges reaching it. Cycles are broken at the first unit still
+// waiting for one of its predecessors, leaving out the edges that close them.
+func layers(n int, edges [][2]int) []int {
+	waiting := make([]int, n)
+	next := make([][]int, n)
+	for _, e := range edges {
+		next[e[0]] = append(next[e[0]], e[1])
+		waiting[e[1]]++
+	}
+
+	layer := make([]int, n)
+	done := make([]bool, n)
+	for range layer {
+		v := -1
+		for u := range waiting {
+			if !done[u] && (waiting[u] == 0 || v < 0) {
+				v = u
+				if waiting[u] == 0 {
+					break
+				}
+			}
+		}
+		done[v] = true
+		for _, w := range next[v] {
+			if !done[w] {
+				waiting[w]--
+				layer[w] = max(layer[w], layer[v]+1)
+			}
+		}
+	}
+	return layer
+}
+
+// sweep reorders each ring by where its neighbors on the rings inside it
+// are, going outward, then by its neighbors on the rings outside it, going
+// inward: the barycenter heuristic for fewer crossings
+func sweep(order [][]int, layer []int, adjacent [][]int) {
+	for d := 1; d < len(order); d++ {
+		reorder(order, d, layer, adjacent, -1)
+	}
+	for d := len(order) - 2; d >= 0; d-- {
+		reorder(order, d, layer, adjacent, 1)
+	}
+}
+
+// reorder sorts ring d of order by the mean turn of each unit's neighbors
+// on the rings toward the given side, -1 for inside and 1 for outside.
+// Units without such neighbors keep their turn.
+func reorder(order [][]int, d int, layer []int, adjacent [][]int, toward int) {
+	turn := make([]float64, len(layer))
+	for _, ring := range order {
+		for i, v := range ring {
+			turn[v] = float64(i) / float64(len(ring))
+		}
+	}
+
+	key := make(map[int]float64, len(order[d]))
+	for _, v := range order[d] {
+		// Turns wrap around, so they are averaged as angles
+		var x, y float64
+		for _, w := range adjacent[v] {
+			if (layer[w]-d)*toward > 0 {
+				x += math.Cos(2 * math.Pi * turn[w])
+				y += math.Sin(2 * math.Pi * turn[w])
+			}
+		}
+		key[v] = turn[v]
+		if x != 0 || y != 0 {
+			key[v] = math.Atan2(y, x) / (2 * math.Pi)
+			if key[v] < 0 {
+				key[v]++
+			}
+		}
+	}
+	sort.SliceStable(order[d], func(i, j int) bool {
+		return key[order[d][i]] < key[order[d][j]]
+	})
+}
+
+// place returns the position of each of n units, with the rings of order
+// spread evenly from the middle to Fill of the outer circle. A lone unit
+// on the first ring sits in the middle. Each ring starts at the top.
+func place(order [][]int, n int, center detector.Position, opts LayoutOptions) []detector.Position {
+	outermost := opts.Fill * CanonicalRadius
+	inner := 1
+	if len(order[0]) == 1 {
+		inner = 0
+	}
+	steps := len(order) - 1 + inner
+
+	at := make([]detector.Position, n)
+	for d, ring := range order {
+		radius := 0.0
+		if steps > 0 {
+			radius = outermost * float64(d+inner) / float64(steps)
+		}
+		if len(ring) > 1 {
+			radius = math.Min(outermost, math.Max(radius, float64(len(ring))*opts.Spacing/(2*math.Pi)))
+		}
+		for i, v := range ring {
+			angle := up + 2*math.Pi*float64(i)/float64(len(ring))
+			at[v] = offset(center, snap(radius*math.Cos(angle)), snap(radius*math.Sin(angle)))
+		}
+	}
+	return at
+}
+
+// Crossings counts the pairs of connections whose straight lines cross.
+// Connections sharing a symbol do not cross each other.
+func Crossings(connections []detector.Connection) int {
+	index := make(map[*detector.Symbol]int)
+	var at []detector.Position
+	edges := make([][2]int, len(connections))
+	for i, conn := range connections {
+		for j, s := range []*detector.Symbol{conn.From, conn.To} {
+			if _, ok := index[s]; !ok {
+				index[s] = len(at)
+				at = append(at, s.Position)
+			}
+			edges[i][j] = index[s]
+		}
+	}
+	return crossings(edges, at)
+}
+
+// crossings counts the pairs of edges between units at the given
+// positions that cross
+func crossings(edges [][2]int, at []detector.Position) int {
+	crossed := 0
+	for i, e := range edges {
+		for _, f := range edges[i+1:] {
+			if e[0] == f[0] || e[0] == f[1] || e[1] == f[0] || e[1] == f[1] {
+				continue
+			}
+			if segmentsCross(at[e[0]], at[e[1]], at[f[0]], at[f[1]]) {
+				crossed++
+			}
+		}
+	}
+	return crossed
+}
+
+// segmentsCross reports whether the segments ab and cd cross at a point
+// inside both
+func segmentsCross(a, b, c, d detector.Position) bool {
+	return side(a, b, c)*side(a, b, d) < 0 && side(c, d, a)*side(c, d, b) < 0
+}
+
+// side is positive when p is on one side of the line through a and b,
+// negative on the other and zero on the line
+func side(a, b, p detector.Position) float64 {
+	return (b.X-a.X)*(p.Y-a.Y) - (b.Y-a.Y)*(p.X-a.X)
+}
@@ -0,0 +1,108 @@
+package render
+
+import (
+	"math"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// tree returns a root with three children, each with a child of its own,
+// listed so that the grandchildren come in an order that crosses
+func tree() ([]*detector.Symbol, []detector.Connection) {
+	names := "ABCDEGF"
+	symbols := make(map[byte]*detector.Symbol, len(names))
+	ordered := make([]*detector.Symbol, len(names))
+	for i := range names {
+		s := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: float64(i * 10), Y: 0}, Size: 10}
+		symbols[names[i]] = s
+		ordered[i] = s
+	}
+	var connections []detector.Connection
+	for _, pair := range []string{"AB", "AC", "AD", "BE", "CF", "DG"} {
+		connections = append(connections, detector.Connection{
+			From: symbols[pair[0]], To: symbols[pair[1]], ConnectionType: detector.ConnectionTypeSolid,
+		})
+	}
+	return ordered, connections
+}
+
+func TestLayout(t *testing.T) {
+	symbols, connections := tree()
+	laidOut, joined := Layout(symbols, connections, DefaultOptions())
+
+	require.Len(t, laidOut, 8, "an outer circle is added")
+	outer := laidOut[0]
+	assert.Equal(t, detector.OuterCircle, outer.Type)
+	assert.Equal(t, detector.Position{X: 300, Y: 300}, outer.Position)
+
+	// The root sits in the middle and each generation on a ring further out
+	distance := func(s *detector.Symbol) float64 {
+		return math.Hypot(s.Position.X-outer.Position.X, s.Position.Y-outer.Position.Y)
+	}
+	assert.Equal(t, 0.0, distance(laidOut[1]))
+	for _, s := range laidOut[2:5] {
+		assert.InDelta(t, 100, distance(s), Grid)
+	}
+	for _, s := range laidOut[5:] {
+		assert.InDelta(t, 200, distance(s), Grid)
+		assert.Equal(t, 30.0, s.Size)
+	}
+
+	assert.Equal(t, 0, Crossings(joined))
+	assert.Equal(t, detector.Position{X: 10, Y: 0}, symbols[1].Position, "the input is not changed")
+}
+
+func TestLayout_Sweeps(t *testing.T) {
+	symbols, connections := tree()
+	opts := DefaultOptions()
+	opts.Layout.Restarts = 1
+	opts.Layout.Sweeps = 0
+
+	_, joined := Layout(symbols, connections, opts)
+	assert.Equal(t, 1, Crossings(joined), "the grandchildren are placed in the order given")
+
+	opts.Layout.Sweeps = 1
+	_, joined = Layout(symbols, connections, opts)
+	assert.Equal(t, 0, Crossings(joined))
+}
+
+func TestLayout_Deterministic(t *testing.T) {
+	symbols, connections := tree()
+	// A cycle back to the root
+	connections = append(connections, detector.Connection{From: symbols[6], To: symbols[0]})
+
+	first, _ := Layout(symbols, connections, DefaultOptions())
+	second, _ := Layout(symbols, connections, DefaultOptions())
+	for i := range first {
+		assert.Equal(t, first[i].Position, second[i].Position)
+	}
+}
+
+func TestLayout_Frame(t *testing.T) {
+	frame := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 100, Y: 100}, Size: 40, ListFrame: true, Width: 80, Height: 40}
+	member := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 80, Y: 100}, Size: 20}
+	target := &detector.Symbol{Type: detector.Circle, Position: detector.Position{X: 100, Y: 300}, Size: 20}
+	connections := []detector.Connection{{From: member, To: target}}
+
+	laidOut, joined := Layout([]*detector.Symbol{frame, member, target}, connections, DefaultOptions())
+
+	require.Len(t, laidOut, 4)
+	assert.Equal(t, laidOut[1].Position.X-20, laidOut[2].Position.X, "members move with their frame")
+	assert.Equal(t, laidOut[1].Position.Y, laidOut[2].Position.Y)
+	assert.Equal(t, 80.0, laidOut[1].Width)
+	require.Len(t, joined, 1)
+	assert.Same(t, laidOut[2], joined[0].From)
+}
+
+func TestCrossings(t *testing.T) {
+	at := func(x, y float64) *detector.Symbol {
+		return &detector.Symbol{Position: detector.Position{X: x, Y: y}}
+	}
+	a, b, c, d := at(0, 0), at(10, 10), at(0, 10), at(10, 0)
+	assert.Equal(t, 1, Crossings([]detector.Connection{{From: a, To: b}, {From: c, To: d}}))
+	assert.Equal(t, 0, Crossings([]detector.Connection{{From: a, To: c}, {From: d, To: b}}))
+	assert.Equal(t, 0, Crossings([]detector.Connection{{From: a, To: b}, {From: b, To: c}}), "connections sharing a symbol")
+}
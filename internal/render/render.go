@@ -0,0 +1,230 @@
+// Package render draws magic circles from symbols and connections, in the
+// shapes the detector recognizes.
+package render
+
+import (
+	"image"
+	"image/color"
+	"image/draw"
+	"math"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// Options control how a magic circle is drawn
+type Options struct {
+	// Stroke is the width, in pixels, of symbol outlines and connections
+	Stroke float64
+	// RingStroke is the width, in pixels, of the outer circle
+	RingStroke float64
+	// Margin is the space, in pixels, between the drawing and the edges of
+	// the image
+	Margin float64
+	// Gap is the space, in pixels, left between a connection and the
+	// symbols it joins, so that lines do not merge with outlines
+	Gap float64
+	// Layout controls where Layout places symbols
+	Layout LayoutOptions
+}
+
+// DefaultOptions returns the options grimoire fmt draws with
+func DefaultOptions() Options {
+	return Options{Stroke: 2, RingStroke: 6, Margin: 50, Gap: 4, Layout: DefaultLayoutOptions()}
+}
+
+// ink is the color everything is drawn in
+var ink = color.RGBA{A: 255}
+
+// dash and dashGap are the lengths, in pixels, of the strokes and spaces of
+// dashed connections
+const (
+	dash    = 8
+	dashGap = 6
+)
+
+// Render draws symbols and connections in black on a white image. Symbols
+// are drawn where they are; the image reaches from the origin to the
+// farthest ink plus the margin.
+func Render(symbols []*detector.Symbol, connections []detector.Connection, opts Options) *image.RGBA {
+	img := image.NewRGBA(bounds(symbols, opts))
+	draw.Draw(img, img.Bounds(), image.White, image.Point{}, draw.Src)
+	c := canvas{raster{img}}
+	for _, conn := range connections {
+		c.connection(conn, opts)
+	}
+	for _, s := range symbols {
+		c.symbol(s, opts)
+	}
+	return img
+}
+
+// bounds returns the image symbols are drawn on: from the origin to the
+// farthest ink plus the margin
+func bounds(symbols []*detector.Symbol, opts Options) image.Rectangle {
+	width, height := opts.Margin, opts.Margin
+	for _, s := range symbols {
+		reach := glyphOf(s).reach(s.Position)
+		width = math.Max(width, s.Position.X+reach+opts.Margin)
+		height = math.Max(height, s.Position.Y+reach+opts.Margin)
+	}
+	return image.Rect(0, 0, int(math.Ceil(width)), int(math.Ceil(height)))
+}
+
+// pen draws the shapes glyphs, patterns and connections are made of
+type pen interface {
+	// line draws a straight stroke from a to b with rounded ends
+	line(a, b detector.Position, width float64)
+	// outline draws the closed polygon through points
+	outline(points []detector.Position, width float64)
+	// fill draws the inside of the polygon through points
+	fill(points []detector.Position)
+	// ring draws a circle of the given radius around center
+	ring(center detector.Position, radius, width float64)
+	// disk draws a filled circle of the given radius around center
+	disk(center detector.Position, radius float64)
+	// halfDisk draws the lower half of a disk
+	halfDisk(center detector.Position, radius float64)
+}
+
+// canvas draws symbols and connections with a pen
+type canvas struct {
+	pen
+}
+
+// symbol draws s, with the thicker stroke for the outer circle
+func (c canvas) symbol(s *detector.Symbol, opts Options) {
+	stroke := opts.Stroke
+	if s.Type == detector.OuterCircle {
+		stroke = opts.RingStroke
+	}
+	c.glyph(glyphOf(s), stroke)
+	c.pattern(s.Position, patternOf(s), s.Size, stroke)
+}
+
+// raster inks pixels of an image, without antialiasing so that outlines
+// binarize the same way everywhere
+type raster struct {
+	img *image.RGBA
+}
+
+// plot inks the pixels of area whose centers inside reports true for
+func (r raster) plot(area image.Rectangle, inside func(x, y float64) bool) {
+	area = area.Intersect(r.img.Bounds())
+	for y := area.Min.Y; y < area.Max.Y; y++ {
+		for x := area.Min.X; x < area.Max.X; x++ {
+			if inside(float64(x)+0.5, float64(y)+0.5) {
+				r.img.SetRGBA(x, y, ink)
+			}
+		}
+	}
+}
+
+// line inks a straight stroke from a to b with rounded ends
+func (r raster) line(a, b detector.Position, width float64) {
+	r.plot(around(width, a, b), func(x, y float64) bool {
+		return segmentDistance(x, y, a, b) <= width/2
+	})
+}
+
+// outline inks the closed polygon through points
+func (r raster) outline(points []detector.Position, width float64) {
+	for i, p := range points {
+		r.line(p, points[(i+1)%len(points)], width)
+	}
+}
+
+// fill inks the inside of the polygon through points
+func (r raster) fill(points []detector.Position) {
+	r.plot(around(0, points...), func(x, y float64) bool {
+		return contains(points, x, y)
+	})
+}
+
+// ring inks a circle of the given radius around center
+func (r raster) ring(center detector.Position, radius, width float64) {
+	r.plot(around(radius+width, center), func(x, y float64) bool {
+		return math.Abs(math.Hypot(x-center.X, y-center.Y)-radius) <= width/2
+	})
+}
+
+// disk inks a filled circle of the given radius around center
+func (r raster) disk(center detector.Position, radius float64) {
+	r.plot(around(radius, center), func(x, y float64) bool {
+		return math.Hypot(x-center.X, y-center.Y) <= radius
+	})
+}
+
+// halfDisk inks the lower half of a disk of the given radius around center
+func (r raster) halfDisk(center detector.Position, radius float64) {
+	r.plot(around(radius, center), func(x, y float64) bool {
+		return y >= center.Y && math.Hypot(x-center.X, y-center.Y) <= radius
+	})
+}
+
+// connection draws a straight line between the symbols of conn, stopping
+// short of both. Dashed and dotted connections keep their style.
+func (c canvas) connection(conn detector.Connection, opts Options) {
+	if conn.From == nil || conn.To == nil {
+		return
+	}
+	from, to := conn.From.Position, conn.To.Position
+	length := math.Hypot(to.X-from.X, to.Y-from.Y)
+	start := glyphOf(conn.From).reach(from) + opts.Gap
+	end := length - glyphOf(conn.To).reach(to) - opts.Gap
+	if end <= start {
+		return
+	}
+	at := func(d float64) detector.Position {
+		return detector.Position{X: from.X + (to.X-from.X)*d/length, Y: from.Y + (to.Y-from.Y)*d/length}
+	}
+
+	switch conn.ConnectionType {
+	case detector.ConnectionTypeDashed:
+		for d := start; d < end; d += dash + dashGap {
+			c.line(at(d), at(math.Min(d+dash, end)), opts.Stroke)
+		}
+	case detector.ConnectionTypeDotted:
+		for d := start; d <= end; d += 3 * opts.Stroke {
+			c.disk(at(d), opts.Stroke/2)
+		}
+	default:
+		c.line(at(start), at(end), opts.Stroke)
+	}
+}
+
+// around returns the pixels within r of the box spanned by points
+func around(r float64, points ...detector.Position) image.Rectangle {
+	minX, minY := math.Inf(1), math.Inf(1)
+	maxX, maxY := math.Inf(-1), math.Inf(-1)
+	for _, p := range points {
+		minX, maxX = math.Min(minX, p.X), math.Max(maxX, p.X)
+		minY, maxY = math.Min(minY, p.Y), math.Max(maxY, p.Y)
+	}
+	return image.Rect(
+		int(math.Floor(minX-r)), int(math.Floor(minY-r)),
+		int(math.Ceil(maxX+r))+1, int(math.Ceil(maxY+r))+1,
+	)
+}
+
+// segmentDistance returns the distance from (x, y) to the segment from a to b
+func segmentDistance(x, y float64, a, b detector.Position) float64 {
+	dx, dy := b.X-a.X, b.Y-a.Y
+	t := 0.0
+	if length := dx*dx + dy*dy; length > 0 {
+		t = math.Max(0, math.Min(1, ((x-a.X)*dx+(y-a.Y)*dy)/length))
+	}
+	return math.Hypot(x-(a.X+t*dx), y-(a.Y+t*dy))
+}
+
+// contains reports whether (x, y) is inside the polygon through points, by
+// the even-odd rule
+func contains(points []detector.Position, x, y float64) bool {
+	inside := false
+	for i, a := range points {
+		b := points[(i+len(points)-1)%len(points)]
+		if (a.Y > y) != (b.Y > y) && x < a.X+(y-a.Y)*(b.X-a.X)/(b.Y-a.Y) {
+			inside = !inside
+		}
+	}
+	return inside
+}
@@ -0,0 +1,103 @@
+package render
+
+import (
+	"image"
+	"math"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// inked reports whether the pixel at x, y is drawn
+func inked(img *image.RGBA, x, y int) bool {
+	return img.RGBAAt(x, y).R < 128
+}
+
+func TestRender(t *testing.T) {
+	square := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 100, Y: 100}, Size: 30, Pattern: detector.PatternDoubleDot}
+	star := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 100, Y: 250}, Size: 40}
+	connections := []detector.Connection{{From: square, To: star, ConnectionType: detector.ConnectionTypeSolid}}
+
+	img := Render([]*detector.Symbol{square, star}, connections, DefaultOptions())
+
+	// The star reaches about 37 pixels from its center, plus the margin
+	assert.Equal(t, image.Rect(0, 0, 187, 337), img.Bounds())
+	assert.True(t, inked(img, 85, 100), "left side of the square")
+	assert.True(t, inked(img, 96, 100), "first dot")
+	assert.True(t, inked(img, 103, 100), "second dot")
+	assert.False(t, inked(img, 100, 100), "between the dots")
+	assert.True(t, inked(img, 100, 170), "the connection")
+	assert.False(t, inked(img, 100, 122), "the connection stops short of the square")
+	assert.False(t, inked(img, 30, 30))
+}
+
+func TestRender_Dashed(t *testing.T) {
+	from := &detector.Symbol{Type: detector.Circle, Position: detector.Position{X: 50, Y: 100}, Size: 20}
+	to := &detector.Symbol{Type: detector.Circle, Position: detector.Position{X: 250, Y: 100}, Size: 20}
+	connections := []detector.Connection{{From: from, To: to, ConnectionType: detector.ConnectionTypeDashed}}
+
+	img := Render([]*detector.Symbol{from, to}, connections, DefaultOptions())
+
+	inks, gaps := 0, 0
+	for x := 80; x < 220; x++ {
+		if inked(img, x, 100) {
+			inks++
+		} else {
+			gaps++
+		}
+	}
+	assert.Greater(t, inks, 60)
+	assert.Greater(t, gaps, 20)
+}
+
+func TestGlyphArea(t *testing.T) {
+	// Outlines enclose the area the detector measured, size squared
+	for _, symbolType := range []detector.SymbolType{detector.Triangle, detector.Hexagon, detector.Star, detector.Amplification} {
+		g := glyphOf(&detector.Symbol{Type: symbolType, Size: 40})
+		area := 0.0
+		for i, a := range g.outline {
+			b := g.outline[(i+1)%len(g.outline)]
+			area += a.X*b.Y - b.X*a.Y
+		}
+		assert.InDelta(t, 1600, math.Abs(area)/2, 1, "%s", symbolType)
+	}
+}
+
+func TestCanonical(t *testing.T) {
+	outer := &detector.Symbol{Type: detector.OuterCircle, Position: detector.Position{X: 150, Y: 150}, Size: 100 * math.Sqrt(math.Pi)}
+	square := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 183, Y: 121}, Size: 12, Pattern: detector.PatternDot}
+	star := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 151, Y: 195}, Size: 25}
+	header := &detector.Symbol{Type: detector.Circle, Position: detector.Position{X: 80, Y: 80}, Size: 10, OnRing: true}
+	connections := []detector.Connection{{
+		From: square, To: star, ConnectionType: detector.ConnectionTypeSolid,
+		FromAnchor: &detector.Anchor{Angle: 1.2}, ToAnchor: &detector.Anchor{Angle: -1.9},
+	}}
+	symbols := []*detector.Symbol{outer, square, star, header}
+
+	laidOut, joined := Canonical(symbols, connections, DefaultOptions())
+
+	require.Len(t, laidOut, 4)
+	assert.Equal(t, detector.Position{X: 300, Y: 300}, laidOut[0].Position)
+	assert.InDelta(t, CanonicalRadius, laidOut[0].Radius(), 1e-9)
+
+	// Offsets are scaled 2.5 times, to (82.5, -72.5) and (2.5, 112.5), then snapped
+	assert.Equal(t, detector.Position{X: 380, Y: 230}, laidOut[1].Position)
+	assert.Equal(t, 30.0, laidOut[1].Size)
+	assert.Equal(t, detector.PatternDot, laidOut[1].Pattern)
+	assert.Equal(t, detector.Position{X: 300, Y: 410}, laidOut[2].Position)
+	assert.Equal(t, 40.0, laidOut[2].Size)
+
+	ring := laidOut[3].Position
+	assert.InDelta(t, CanonicalRadius, math.Hypot(ring.X-300, ring.Y-300), 1e-9, "glyphs on the ring stay on it")
+	assert.InDelta(t, ring.X, ring.Y, 1e-9)
+
+	require.Len(t, joined, 1)
+	assert.Same(t, laidOut[1], joined[0].From)
+	assert.Same(t, laidOut[2], joined[0].To)
+	assert.Nil(t, joined[0].FromAnchor, "connections are straightened")
+
+	assert.Equal(t, detector.Position{X: 183, Y: 121}, square.Position, "the input is not changed")
+	assert.Same(t, square, connections[0].From)
+}
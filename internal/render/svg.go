@@ -0,0 +1,110 @@
+package render
+
+import (
+	"bytes"
+	"encoding/xml"
+	"fmt"
+	"html"
+	"io"
+	"math"
+	"strconv"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// SVG writes symbols and connections to w as an SVG document of the size
+// Render draws, so that web viewers can make the circle interactive. Each
+// symbol is a group with the id symbol-<index> and its type in data-type;
+// when nodes names the AST node built from a symbol, the name goes in
+// data-node. Each connection is a group with the ids of the symbols it
+// joins in data-from and data-to.
+func SVG(w io.Writer, symbols []*detector.Symbol, connections []detector.Connection, nodes map[*detector.Symbol]string, opts Options) error {
+	ids := make(map[*detector.Symbol]string, len(symbols))
+	for i, s := range symbols {
+		ids[s] = fmt.Sprintf("symbol-%d", i)
+	}
+
+	var buf bytes.Buffer
+	size := bounds(symbols, opts)
+	buf.WriteString(xml.Header)
+	fmt.Fprintf(&buf, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"%d\" height=\"%d\" viewBox=\"0 0 %d %d\" "+
+		"fill=\"none\" stroke=\"black\" stroke-linecap=\"round\" stroke-linejoin=\"round\">\n",
+		size.Dx(), size.Dy(), size.Dx(), size.Dy())
+	buf.WriteString("  <rect width=\"100%\" height=\"100%\" fill=\"white\" stroke=\"none\"/>\n")
+
+	c := canvas{vector{&buf}}
+	for _, conn := range connections {
+		if ids[conn.From] == "" || ids[conn.To] == "" {
+			continue
+		}
+		fmt.Fprintf(&buf, "  <g class=\"connection\" data-from=\"%s\" data-to=\"%s\" data-connection-type=\"%s\">\n",
+			ids[conn.From], ids[conn.To], html.EscapeString(conn.ConnectionType))
+		c.connection(conn, opts)
+		buf.WriteString("  </g>\n")
+	}
+	for _, s := range symbols {
+		fmt.Fprintf(&buf, "  <g class=\"symbol\" id=\"%s\" data-type=\"%s\"", ids[s], html.EscapeString(string(s.Type)))
+		if node := nodes[s]; node != "" {
+			fmt.Fprintf(&buf, " data-node=\"%s\"", html.EscapeString(node))
+		}
+		buf.WriteString(">\n")
+		c.symbol(s, opts)
+		buf.WriteString("  </g>\n")
+	}
+	buf.WriteString("</svg>\n")
+
+	_, err := w.Write(buf.Bytes())
+	return err
+}
+
+// vector writes the shapes it draws as SVG elements. Strokes are black and
+// round unless an element says otherwise.
+type vector struct {
+	buf *bytes.Buffer
+}
+
+func (v vector) line(a, b detector.Position, width float64) {
+	fmt.Fprintf(v.buf, "    <line x1=\"%s\" y1=\"%s\" x2=\"%s\" y2=\"%s\" stroke-width=\"%s\"/>\n",
+		num(a.X), num(a.Y), num(b.X), num(b.Y), num(width))
+}
+
+func (v vector) outline(points []detector.Position, width float64) {
+	fmt.Fprintf(v.buf, "    <polygon points=\"%s\" stroke-width=\"%s\"/>\n", pointList(points), num(width))
+}
+
+func (v vector) fill(points []detector.Position) {
+	fmt.Fprintf(v.buf, "    <polygon points=\"%s\" fill=\"black\" stroke=\"none\"/>\n", pointList(points))
+}
+
+func (v vector) ring(center detector.Position, radius, width float64) {
+	fmt.Fprintf(v.buf, "    <circle cx=\"%s\" cy=\"%s\" r=\"%s\" stroke-width=\"%s\"/>\n",
+		num(center.X), num(center.Y), num(radius), num(width))
+}
+
+func (v vector) disk(center detector.Position, radius float64) {
+	fmt.Fprintf(v.buf, "    <circle cx=\"%s\" cy=\"%s\" r=\"%s\" fill=\"black\" stroke=\"none\"/>\n",
+		num(center.X), num(center.Y), num(radius))
+}
+
+func (v vector) halfDisk(center detector.Position, radius float64) {
+	// From the left end of the diameter around the bottom to the right end
+	fmt.Fprintf(v.buf, "    <path d=\"M %s %s A %s %s 0 0 0 %s %s Z\" fill=\"black\" stroke=\"none\"/>\n",
+		num(center.X-radius), num(center.Y), num(radius), num(radius), num(center.X+radius), num(center.Y))
+}
+
+// pointList returns points in the form of the points attribute of a polygon
+func pointList(points []detector.Position) string {
+	var buf bytes.Buffer
+	for i, p := range points {
+		if i > 0 {
+			buf.WriteByte(' ')
+		}
+		fmt.Fprintf(&buf, "%s,%s", num(p.X), num(p.Y))
+	}
+	return buf.String()
+}
+
+// num formats a coordinate to a hundredth of a pixel
+func num(v float64) string {
+	return strconv.FormatFloat(math.Round(v*100)/100, 'f', -1, 64)
+}
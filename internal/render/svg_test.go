@@ -0,0 +1,41 @@
+package render
+
+import (
+	"bytes"
+	"encoding/xml"
+	"io"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestSVG(t *testing.T) {
+	square := &detector.Symbol{Type: detector.Square, Position: detector.Position{X: 100, Y: 100}, Size: 30, Pattern: detector.PatternHalfCircle}
+	star := &detector.Symbol{Type: detector.Star, Position: detector.Position{X: 100, Y: 250}, Size: 40}
+	symbols := []*detector.Symbol{square, star}
+	connections := []detector.Connection{{From: square, To: star, ConnectionType: detector.ConnectionTypeSolid}}
+	nodes := map[*detector.Symbol]string{square: "literal-1"}
+
+	var buf bytes.Buffer
+	require.NoError(t, SVG(&buf, symbols, connections, nodes, DefaultOptions()))
+	svg := buf.String()
+
+	assert.Contains(t, svg, `width="187" height="337"`, "the size Render draws")
+	assert.Contains(t, svg, `<g class="symbol" id="symbol-0" data-type="square" data-node="literal-1">`)
+	assert.Contains(t, svg, `<g class="symbol" id="symbol-1" data-type="star">`)
+	assert.Contains(t, svg, `<g class="connection" data-from="symbol-0" data-to="symbol-1" data-connection-type="solid">`)
+	assert.Contains(t, svg, `<polygon points="85,85 115,85 115,115 85,115" stroke-width="2"/>`)
+	assert.Contains(t, svg, `<path d="M 92.5 100 A 7.5 7.5 0 0 0 107.5 100 Z"`)
+
+	// The document is well formed
+	decoder := xml.NewDecoder(&buf)
+	for {
+		_, err := decoder.Token()
+		if err == io.EOF {
+			break
+		}
+		require.NoError(t, err)
+	}
+}
@@ -0,0 +1,368 @@
+package render
+
+import (
+	"image"
+	"image/color"
+	"image/draw"
+	"math"
+	"os"
+	"path/filepath"
+	"strings"
+	"unicode"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"golang.org/x/image/font"
+	"golang.org/x/image/font/gofont/goregular"
+	"golang.org/x/image/font/opentype"
+	"golang.org/x/image/font/sfnt"
+	"golang.org/x/image/math/fixed"
+)
+
+// FontsEnv lists font files, separated like PATH, to draw the characters
+// the bundled font lacks before the fonts of the system are tried
+const FontsEnv = "GRIMOIRE_FONTS"
+
+// DefaultTextSize is the size, in pixels, labels are drawn at
+const DefaultTextSize = 13
+
+// systemFonts are fonts with Japanese and other CJK characters that ship
+// with common systems, tried in order for what the bundled font lacks
+var systemFonts = []string{
+	// Linux
+	"/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+	"/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+	"/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+	"/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
+	"/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+	// macOS
+	"/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc",
+	"/System/Library/Fonts/Hiragino Sans GB.ttc",
+	"/Library/Fonts/Arial Unicode.ttf",
+	// Windows
+	`C:\Windows\Fonts\YuGothR.ttc`,
+	`C:\Windows\Fonts\meiryo.ttc`,
+	`C:\Windows\Fonts\msgothic.ttc`,
+}
+
+// Text draws labels in the bundled Go font, falling back to the fonts in
+// GRIMOIRE_FONTS and then to the CJK fonts of the system for the characters
+// it lacks. Text is split into grapheme clusters, and each cluster is drawn
+// whole in the first font that has every character of it, so an accent is
+// never drawn in a different font from its letter. A cluster no font has is
+// drawn as an empty box. A Text is not safe for concurrent use.
+type Text struct {
+	size  float64
+	fonts []*fallback
+}
+
+// fallback is a font of the chain, read and parsed the first time a
+// character is looked up in it
+type fallback struct {
+	path   string
+	data   []byte // the font itself when it is bundled
+	loaded bool
+	font   *sfnt.Font
+	face   font.Face
+	buf    sfnt.Buffer
+}
+
+// NewText returns a Text drawing at size pixels
+func NewText(size float64) *Text {
+	t := &Text{size: size, fonts: []*fallback{{data: goregular.TTF}}}
+	for _, path := range filepath.SplitList(os.Getenv(FontsEnv)) {
+		if path != "" {
+			t.fonts = append(t.fonts, &fallback{path: path})
+		}
+	}
+	for _, path := range systemFonts {
+		t.fonts = append(t.fonts, &fallback{path: path})
+	}
+	return t
+}
+
+// Measure returns the width, in pixels, of label
+func (t *Text) Measure(label string) int {
+	var width fixed.Int26_6
+	for _, cluster := range Clusters(label) {
+		width += t.advance(cluster)
+	}
+	return width.Ceil()
+}
+
+// Ascent returns how far, in pixels, text reaches above its baseline
+func (t *Text) Ascent() int {
+	if face := t.primary(); face != nil {
+		return face.Metrics().Ascent.Ceil()
+	}
+	return int(t.size)
+}
+
+// Height returns the height, in pixels, of a line of text
+func (t *Text) Height() int {
+	if face := t.primary(); face != nil {
+		return face.Metrics().Height.Ceil()
+	}
+	return int(t.size * 1.2)
+}
+
+// Draw draws label in c with the left end of its baseline at dot
+func (t *Text) Draw(dst draw.Image, label string, dot image.Point, c color.Color) {
+	at := fixed.P(dot.X, dot.Y)
+	src := image.NewUniform(c)
+	for _, cluster := range Clusters(label) {
+		face := t.faceOf(cluster)
+		if face == nil {
+			t.box(dst, at, c)
+			at.X += t.boxAdvance()
+			continue
+		}
+		d := font.Drawer{Dst: dst, Src: src, Face: face, Dot: at}
+		d.DrawString(visible(cluster))
+		at.X = d.Dot.X
+	}
+}
+
+// Label draws label in c centered on top.X with its top at top.Y, moved
+// inside dst when it would run off an edge
+func (t *Text) Label(dst draw.Image, label string, top image.Point, c color.Color) {
+	bounds := dst.Bounds()
+	width := t.Measure(label)
+	x := top.X - width/2
+	x = max(min(x, bounds.Max.X-width), bounds.Min.X)
+	y := min(top.Y, bounds.Max.Y-t.Height()) + t.Ascent()
+	t.Draw(dst, label, image.Pt(x, max(y, bounds.Min.Y+t.Ascent())), c)
+}
+
+// primary returns the face of the bundled font
+func (t *Text) primary() font.Face {
+	if t.fonts[0].load(t.size) {
+		return t.fonts[0].face
+	}
+	return nil
+}
+
+// faceOf returns the face of the first font that has every character of
+// cluster, or nil when none does
+func (t *Text) faceOf(cluster string) font.Face {
+	for _, f := range t.fonts {
+		if f.load(t.size) && f.covers(cluster) {
+			return f.face
+		}
+	}
+	return nil
+}
+
+// advance returns how far the dot moves past cluster
+func (t *Text) advance(cluster string) fixed.Int26_6 {
+	face := t.faceOf(cluster)
+	if face == nil {
+		return t.boxAdvance()
+	}
+	return font.MeasureString(face, visible(cluster))
+}
+
+// boxAdvance is the advance of the box drawn for a missing character
+func (t *Text) boxAdvance() fixed.Int26_6 {
+	return fixed.I(int(t.size*0.6) + 1)
+}
+
+// box outlines the box drawn for a missing character, standing on the
+// baseline at dot
+func (t *Text) box(dst draw.Image, dot fixed.Point26_6, c color.Color) {
+	x0, x1 := dot.X.Round()+1, (dot.X + t.boxAdvance()).Round()-2
+	y0, y1 := dot.Y.Round()-int(t.size*0.7), dot.Y.Round()
+	for x := x0; x <= x1; x++ {
+		dst.Set(x, y0, c)
+		dst.Set(x, y1, c)
+	}
+	for y := y0; y <= y1; y++ {
+		dst.Set(x0, y, c)
+		dst.Set(x1, y, c)
+	}
+}
+
+// load reads and parses the font, the first of a collection, once. It
+// reports whether the font can be drawn with.
+func (f *fallback) load(size float64) bool {
+	if f.loaded {
+		return f.face != nil
+	}
+	f.loaded = true
+
+	data := f.data
+	if data == nil {
+		var err error
+		if data, err = os.ReadFile(f.path); err != nil {
+			return false
+		}
+	}
+	collection, err := opentype.ParseCollection(data)
+	if err != nil || collection.NumFonts() == 0 {
+		return false
+	}
+	parsed, err := collection.Font(0)
+	if err != nil {
+		return false
+	}
+	face, err := opentype.NewFace(parsed, &opentype.FaceOptions{Size: size, DPI: 72, Hinting: font.HintingFull})
+	if err != nil {
+		return false
+	}
+	f.font, f.face = parsed, face
+	return true
+}
+
+// covers reports whether the font has a glyph for every character of
+// cluster that is drawn
+func (f *fallback) covers(cluster string) bool {
+	for _, r := range cluster {
+		if ignorable(r) {
+			continue
+		}
+		if index, err := f.font.GlyphIndex(&f.buf, r); err != nil || index == 0 {
+			return false
+		}
+	}
+	return true
+}
+
+// visible returns cluster without the characters that only join or select
+// the look of others, which fonts draw as missing
+func visible(cluster string) string {
+	return strings.Map(func(r rune) rune {
+		if ignorable(r) {
+			return -1
+		}
+		return r
+	}, cluster)
+}
+
+// ignorable reports whether r is a zero width joiner or variation selector
+func ignorable(r rune) bool {
+	return r == zwj || (r >= 0xFE00 && r <= 0xFE0F) || (r >= 0xE0100 && r <= 0xE01EF)
+}
+
+// zwj is the zero width joiner, which joins emoji into one
+const zwj = '\u200D'
+
+// Clusters splits s into grapheme clusters, the characters a reader sees:
+// a letter with its combining marks, a pair of regional indicators making a
+// flag, emoji joined by zero width joiners, or a Hangul syllable spelled in
+// jamo. It follows the extended grapheme cluster rules of Unicode, except
+// that anything after a zero width joiner is joined rather than only emoji.
+func Clusters(s string) []string {
+	var clusters []string
+	start, prev, regional := 0, rune(-1), 0
+	for i, r := range s {
+		if i > start && !joins(prev, r, regional) {
+			clusters = append(clusters, s[start:i])
+			start, regional = i, 0
+		}
+		if isRegional(r) {
+			regional++
+		}
+		prev = r
+	}
+	if start < len(s) {
+		clusters = append(clusters, s[start:])
+	}
+	return clusters
+}
+
+// joins reports whether r belongs to the same cluster as prev, which ends
+// a cluster with regional regional indicators in it
+func joins(prev, r rune, regional int) bool {
+	switch {
+	case prev == '\r' && r == '\n':
+		return true
+	case isControl(prev) || isControl(r):
+		return false
+	case extends(r), prev == zwj:
+		return true
+	case isRegional(prev) && isRegional(r):
+		return regional%2 == 1
+	}
+	return hangulJoins(hangulKind(prev), hangulKind(r))
+}
+
+// isControl reports whether r always stands alone
+func isControl(r rune) bool {
+	return unicode.IsControl(r) || r == '\u2028' || r == '\u2029'
+}
+
+// extends reports whether r attaches to the character before it
+func extends(r rune) bool {
+	return unicode.In(r, unicode.Mn, unicode.Me, unicode.Mc) || r == zwj ||
+		r == '\uFF9E' || r == '\uFF9F' || // halfwidth katakana voiced sound marks
+		(r >= 0x1F3FB && r <= 0x1F3FF) || // emoji skin tones
+		(r >= 0xE0020 && r <= 0xE007F) // emoji tag sequences
+}
+
+// isRegional reports whether r is a regional indicator, a pair of which
+// spells a flag
+func isRegional(r rune) bool {
+	return r >= 0x1F1E6 && r <= 0x1F1FF
+}
+
+// Kinds of Hangul jamo and syllables, which join into syllable blocks
+const (
+	hangulNone = iota
+	hangulL    // leading consonant
+	hangulV    // vowel
+	hangulT    // trailing consonant
+	hangulLV   // syllable without a trailing consonant
+	hangulLVT  // syllable with one
+)
+
+// hangulKind returns the kind of Hangul jamo or syllable r is
+func hangulKind(r rune) int {
+	switch {
+	case (r >= 0x1100 && r <= 0x115F) || (r >= 0xA960 && r <= 0xA97C):
+		return hangulL
+	case (r >= 0x1160 && r <= 0x11A7) || (r >= 0xD7B0 && r <= 0xD7C6):
+		return hangulV
+	case (r >= 0x11A8 && r <= 0x11FF) || (r >= 0xD7CB && r <= 0xD7FB):
+		return hangulT
+	case r >= 0xAC00 && r <= 0xD7A3:
+		if (r-0xAC00)%28 == 0 {
+			return hangulLV
+		}
+		return hangulLVT
+	}
+	return hangulNone
+}
+
+// hangulJoins reports whether Hangul of kind next continues the syllable
+// ending in kind prev
+func hangulJoins(prev, next int) bool {
+	switch prev {
+	case hangulL:
+		return next == hangulL || next == hangulV || next == hangulLV || next == hangulLVT
+	case hangulLV, hangulV:
+		return next == hangulV || next == hangulT
+	case hangulLVT, hangulT:
+		return next == hangulT
+	}
+	return false
+}
+
+// LabelColor is the color Annotate writes labels in
+var LabelColor = color.RGBA{R: 200, G: 0, B: 120, A: 255}
+
+// Annotate returns a copy of img with each label written below its symbol,
+// symbols[i] being labeled labels[i]. Empty labels are skipped.
+func Annotate(img image.Image, symbols []*detector.Symbol, labels []string) *image.RGBA {
+	bounds := img.Bounds()
+	canvas := image.NewRGBA(image.Rect(0, 0, bounds.Dx(), bounds.Dy()))
+	draw.Draw(canvas, canvas.Bounds(), img, bounds.Min, draw.Src)
+
+	text := NewText(DefaultTextSize)
+	for i, s := range symbols {
+		if i >= len(labels) || labels[i] == "" {
+			continue
+		}
+		below := s.Position.Y + glyphOf(s).reach(s.Position) + 2
+		text.Label(canvas, labels[i], image.Pt(int(math.Round(s.Position.X)), int(math.Round(below))), LabelColor)
+	}
+	return canvas
+}
@@ -0,0 +1,95 @@
+package render
+
+import (
+	"image"
+	"image/color"
+	"image/draw"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"golang.org/x/image/font/gofont/goregular"
+)
+
+func TestClusters(t *testing.T) {
+	tests := []struct {
+		name string
+		text string
+		want []string
+	}{
+		{"ascii", "ab", []string{"a", "b"}},
+		{"combining accent", "e\u0301x", []string{"e\u0301", "x"}},
+		{"voiced kana mark", "\u304b\u3099ん", []string{"\u304b\u3099", "ん"}},
+		{"halfwidth voiced kana", "\uff76\uff9e", []string{"\uff76\uff9e"}},
+		{"flags", "\U0001f1ef\U0001f1f5\U0001f1fa\U0001f1f8", []string{"\U0001f1ef\U0001f1f5", "\U0001f1fa\U0001f1f8"}},
+		{"joined emoji", "\U0001f469\u200d\U0001f4bb!", []string{"\U0001f469\u200d\U0001f4bb", "!"}},
+		{"skin tone", "\U0001f44d\U0001f3fd", []string{"\U0001f44d\U0001f3fd"}},
+		{"variation selector", "\u2764\ufe0f", []string{"\u2764\ufe0f"}},
+		{"hangul jamo", "\u1100\u1161\u11a8\u1100", []string{"\u1100\u1161\u11a8", "\u1100"}},
+		{"hangul syllable and trailing jamo", "\uac01\uac01\u11a8", []string{"\uac01", "\uac01\u11a8"}},
+		{"crlf", "a\r\nb", []string{"a", "\r\n", "b"}},
+		{"mark after control", "\n\u0301", []string{"\n", "\u0301"}},
+		{"empty", "", nil},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			assert.Equal(t, tt.want, Clusters(tt.text))
+		})
+	}
+}
+
+// bundledText returns a Text with no fallback fonts, so tests do not
+// depend on the fonts of the system
+func bundledText() *Text {
+	return &Text{size: DefaultTextSize, fonts: []*fallback{{data: goregular.TTF}}}
+}
+
+// blank returns a white image of the given size
+func blank(width, height int) *image.RGBA {
+	img := image.NewRGBA(image.Rect(0, 0, width, height))
+	draw.Draw(img, img.Bounds(), image.White, image.Point{}, draw.Src)
+	return img
+}
+
+// inkedIn reports whether any pixel of area is drawn
+func inkedIn(img *image.RGBA, area image.Rectangle) bool {
+	for y := area.Min.Y; y < area.Max.Y; y++ {
+		for x := area.Min.X; x < area.Max.X; x++ {
+			if inked(img, x, y) {
+				return true
+			}
+		}
+	}
+	return false
+}
+
+func TestText(t *testing.T) {
+	text := bundledText()
+	assert.Greater(t, text.Ascent(), 0)
+	assert.GreaterOrEqual(t, text.Height(), text.Ascent())
+
+	width := text.Measure("square")
+	assert.Greater(t, width, text.Measure("sq"))
+	img := blank(100, 30)
+	text.Draw(img, "square", image.Pt(10, 20), color.Black)
+	assert.True(t, inkedIn(img, image.Rect(10, 20-text.Ascent(), 10+width, 21)))
+	assert.False(t, inkedIn(img, image.Rect(12+width, 0, 100, 30)), "nothing is drawn past the measured width")
+
+	// A character no font has is drawn as a box, one per cluster
+	assert.Equal(t, text.boxAdvance().Ceil(), text.Measure("魔"))
+	assert.Equal(t, text.Measure("e"), text.Measure("e\ufe0f"), "variation selectors are not drawn")
+	img = blank(30, 30)
+	text.Draw(img, "魔", image.Pt(5, 20), color.Black)
+	assert.True(t, inked(img, 6, 20), "bottom left corner of the box")
+	assert.False(t, inked(img, 9, 17), "inside the box")
+}
+
+func TestTextLabel(t *testing.T) {
+	text := bundledText()
+	img := blank(60, 60)
+
+	// A label that would run off the right and bottom edges is moved inside
+	text.Label(img, "hexagon", image.Pt(58, 58), color.Black)
+	width := text.Measure("hexagon")
+	assert.True(t, inkedIn(img, image.Rect(60-width, 60-text.Height(), 60, 60)))
+	assert.False(t, inkedIn(img, image.Rect(0, 0, 60-width-1, 60)))
+}
@@ -1,17 +1,28 @@
 package security
 
 import (
+	"bytes"
+	"errors"
 	"fmt"
 	"image"
+	"image/color"
 	_ "image/gif"  // Register GIF decoder
 	_ "image/jpeg" // Register JPEG decoder
 	_ "image/png"  // Register PNG decoder
+	"io"
 	"os"
 
 	_ "golang.org/x/image/webp" // Register WebP decoder
 )
 
-// SafeImageDecoder provides secure image decoding with size validation
+// ErrImageTooLarge is wrapped by the errors for images whose dimensions or
+// decoded size exceed the validator's limits
+var ErrImageTooLarge = errors.New("image too large")
+
+// SafeImageDecoder provides secure image decoding with size validation.
+// The limits are checked against the image header before any pixels are
+// decoded. The standard decoders cannot decode part of an image, so an
+// image that passes is decoded whole.
 type SafeImageDecoder struct {
 	validator *ImageValidator
 }
@@ -44,22 +55,8 @@ func (d *SafeImageDecoder) DecodeImage(filePath string) (image.Image, error) {
 		return nil, fmt.Errorf("failed to decode image config: %w", err)
 	}
 
-	// Validate image dimensions
-	if config.Width > d.validator.MaxImageWidth {
-		return nil, fmt.Errorf("image width (%d) exceeds maximum allowed width (%d)",
-			config.Width, d.validator.MaxImageWidth)
-	}
-
-	if config.Height > d.validator.MaxImageHeight {
-		return nil, fmt.Errorf("image height (%d) exceeds maximum allowed height (%d)",
-			config.Height, d.validator.MaxImageHeight)
-	}
-
-	// Check for potential memory usage (rough estimate)
-	// 4 bytes per pixel (RGBA) + overhead
-	estimatedMemory := int64(config.Width) * int64(config.Height) * 4
-	if estimatedMemory > d.validator.MaxFileSize*2 { // Use 2x file size as memory limit
-		return nil, fmt.Errorf("estimated memory usage (%d bytes) exceeds safe limits", estimatedMemory)
+	if err := d.validateConfig(config); err != nil {
+		return nil, err
 	}
 
 	// Reset file position for actual decoding
@@ -81,3 +78,91 @@ func (d *SafeImageDecoder) DecodeImage(filePath string) (image.Image, error) {
 
 	return img, nil
 }
+
+// DecodeReader safely decodes an image read from r (e.g. stdin).
+// The format is sniffed from the data since there is no file extension to check.
+func (d *SafeImageDecoder) DecodeReader(r io.Reader) (image.Image, error) {
+	// Read at most one byte over the limit so oversized input can be detected
+	data, err := io.ReadAll(io.LimitReader(r, d.validator.MaxFileSize+1))
+	if err != nil {
+		return nil, fmt.Errorf("failed to read image data: %w", err)
+	}
+
+	if int64(len(data)) > d.validator.MaxFileSize {
+		return nil, fmt.Errorf("file size exceeds maximum allowed size (%d bytes)", d.validator.MaxFileSize)
+	}
+
+	if _, err := d.validator.SniffImageFormat(data); err != nil {
+		return nil, err
+	}
+
+	config, format, err := image.DecodeConfig(bytes.NewReader(data))
+	if err != nil {
+		return nil, fmt.Errorf("failed to decode image config: %w", err)
+	}
+
+	if err := d.validateConfig(config); err != nil {
+		return nil, err
+	}
+
+	img, decodedFormat, err := image.Decode(bytes.NewReader(data))
+	if err != nil {
+		return nil, fmt.Errorf("failed to decode image: %w", err)
+	}
+
+	if format != decodedFormat {
+		return nil, fmt.Errorf("format mismatch: config returned %s, decode returned %s",
+			format, decodedFormat)
+	}
+
+	return img, nil
+}
+
+// validateConfig checks image dimensions and estimated memory usage before decoding
+func (d *SafeImageDecoder) validateConfig(config image.Config) error {
+	v := d.validator
+	if v.AllowHuge {
+		return nil
+	}
+
+	// Validate image dimensions
+	if config.Width > v.MaxImageWidth {
+		return fmt.Errorf("%w: image width (%d) exceeds maximum allowed width (%d)",
+			ErrImageTooLarge, config.Width, v.MaxImageWidth)
+	}
+
+	if config.Height > v.MaxImageHeight {
+		return fmt.Errorf("%w: image height (%d) exceeds maximum allowed height (%d)",
+			ErrImageTooLarge, config.Height, v.MaxImageHeight)
+	}
+
+	pixels := int64(config.Width) * int64(config.Height)
+	if v.MaxPixels > 0 && pixels > v.MaxPixels {
+		return fmt.Errorf("%w: image has %d pixels (%dx%d), more than the maximum of %d",
+			ErrImageTooLarge, pixels, config.Width, config.Height, v.MaxPixels)
+	}
+
+	// Check the memory the decoded pixels will take
+	estimatedMemory := pixels * bytesPerPixel(config.ColorModel)
+	if v.MaxDecodedBytes > 0 && estimatedMemory > v.MaxDecodedBytes {
+		return fmt.Errorf("%w: estimated memory usage (%d bytes) exceeds safe limits (%d bytes)",
+			ErrImageTooLarge, estimatedMemory, v.MaxDecodedBytes)
+	}
+
+	return nil
+}
+
+// bytesPerPixel is the size of one pixel of an image decoded with model
+func bytesPerPixel(model color.Model) int64 {
+	switch model {
+	case color.GrayModel, color.AlphaModel:
+		return 1
+	case color.Gray16Model, color.Alpha16Model:
+		return 2
+	case color.RGBA64Model, color.NRGBA64Model:
+		return 8
+	default:
+		// RGBA, NRGBA, paletted and YCbCr images take at most 4 bytes
+		return 4
+	}
+}
@@ -325,3 +325,167 @@ func TestImageValidator_FileHeaders(t *testing.T) {
 		})
 	}
 }
+
+func TestSafeImageDecoder_DecodeReader(t *testing.T) {
+	validator := NewImageValidator()
+	validator.MaxImageWidth = 100
+	validator.MaxImageHeight = 100
+	validator.MaxFileSize = 10 * 1024 // 10KB
+
+	decoder := NewSafeImageDecoder(validator)
+
+	smallPNG, err := createTestPNG(50, 50)
+	require.NoError(t, err)
+
+	largePNG, err := createTestPNG(200, 200)
+	require.NoError(t, err)
+
+	smallGIF, err := createTestGIF(50, 50)
+	require.NoError(t, err)
+
+	tests := []struct {
+		name        string
+		data        []byte
+		expectError bool
+		errorMsg    string
+	}{
+		{
+			name: "Valid PNG",
+			data: smallPNG,
+		},
+		{
+			name: "Valid GIF",
+			data: smallGIF,
+		},
+		{
+			name:        "Image exceeds width limit",
+			data:        largePNG,
+			expectError: true,
+			errorMsg:    "exceeds maximum allowed width",
+		},
+		{
+			name:        "Input exceeds size limit",
+			data:        append(append([]byte{}, smallPNG...), make([]byte, 10*1024)...),
+			expectError: true,
+			errorMsg:    "exceeds maximum allowed size",
+		},
+		{
+			name:        "Unrecognized data",
+			data:        []byte("definitely not an image"),
+			expectError: true,
+			errorMsg:    "unsupported file format",
+		},
+		{
+			name:        "Empty input",
+			data:        []byte{},
+			expectError: true,
+			errorMsg:    "too small",
+		},
+	}
+
+	for _, tc := range tests {
+		t.Run(tc.name, func(t *testing.T) {
+			img, err := decoder.DecodeReader(bytes.NewReader(tc.data))
+
+			if tc.expectError {
+				assert.Error(t, err)
+				assert.Contains(t, err.Error(), tc.errorMsg)
+				assert.Nil(t, img)
+			} else {
+				assert.NoError(t, err)
+				require.NotNil(t, img)
+				assert.Equal(t, 50, img.Bounds().Dx())
+			}
+		})
+	}
+}
+
+func TestImageValidator_SniffImageFormat(t *testing.T) {
+	validator := NewImageValidator()
+
+	tests := []struct {
+		name     string
+		header   []byte
+		expected string
+	}{
+		{"PNG", []byte{0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00}, ".png"},
+		{"JPEG", []byte{0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, 0x4A, 0x46, 0x49, 0x46}, ".jpg"},
+		{"GIF87a", []byte("GIF87a\x00\x00"), ".gif"},
+		{"GIF89a", []byte("GIF89a\x00\x00"), ".gif"},
+		{"WebP", []byte{'R', 'I', 'F', 'F', 0x00, 0x00, 0x00, 0x00, 'W', 'E', 'B', 'P'}, ".webp"},
+		{"RIFF without WEBP", []byte{'R', 'I', 'F', 'F', 0x00, 0x00, 0x00, 0x00, 'W', 'A', 'V', 'E'}, ""},
+		{"Unknown", []byte("plain text data"), ""},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			ext, err := validator.SniffImageFormat(tt.header)
+			if tt.expected == "" {
+				assert.Error(t, err)
+			} else {
+				assert.NoError(t, err)
+				assert.Equal(t, tt.expected, ext)
+			}
+		})
+	}
+}
+
+func TestSafeImageDecoder_DecodedSizeLimits(t *testing.T) {
+	rgba, err := createTestPNG(20, 20)
+	require.NoError(t, err)
+	var grayPNG bytes.Buffer
+	require.NoError(t, png.Encode(&grayPNG, image.NewGray(image.Rect(0, 0, 20, 20))))
+
+	tests := []struct {
+		name     string
+		data     []byte
+		setup    func(v *ImageValidator)
+		errorMsg string
+	}{
+		{
+			name:     "Too many pixels",
+			data:     rgba,
+			setup:    func(v *ImageValidator) { v.MaxPixels = 399 },
+			errorMsg: "more than the maximum of 399",
+		},
+		{
+			name:     "Decoded size too large",
+			data:     rgba,
+			setup:    func(v *ImageValidator) { v.MaxDecodedBytes = 1599 },
+			errorMsg: "estimated memory usage (1600 bytes)",
+		},
+		{
+			name:  "Gray pixels take one byte",
+			data:  grayPNG.Bytes(),
+			setup: func(v *ImageValidator) { v.MaxDecodedBytes = 400 },
+		},
+		{
+			name: "AllowHuge lifts the limits",
+			data: rgba,
+			setup: func(v *ImageValidator) {
+				v.MaxImageWidth = 10
+				v.MaxPixels = 1
+				v.MaxDecodedBytes = 1
+				v.AllowHuge = true
+			},
+		},
+	}
+
+	for _, tc := range tests {
+		t.Run(tc.name, func(t *testing.T) {
+			validator := NewImageValidator()
+			tc.setup(validator)
+			img, err := NewSafeImageDecoder(validator).DecodeReader(bytes.NewReader(tc.data))
+
+			if tc.errorMsg != "" {
+				require.Error(t, err)
+				assert.ErrorIs(t, err, ErrImageTooLarge)
+				assert.Contains(t, err.Error(), tc.errorMsg)
+				assert.Nil(t, img)
+				return
+			}
+			require.NoError(t, err)
+			assert.Equal(t, 20, img.Bounds().Dx())
+		})
+	}
+}
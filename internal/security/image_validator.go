@@ -17,6 +17,13 @@ type ImageValidator struct {
 	MaxImageWidth int
 	// MaxImageHeight is the maximum allowed image height in pixels (default: 10000)
 	MaxImageHeight int
+	// MaxPixels is the maximum allowed width × height (default: 25 million)
+	MaxPixels int64
+	// MaxDecodedBytes is the maximum memory the decoded pixels may take (default: 100MB)
+	MaxDecodedBytes int64
+	// AllowHuge lifts the dimension, pixel and decoded memory limits for
+	// trusted images. The file size limit still applies.
+	AllowHuge bool
 	// AllowedExtensions contains the allowed file extensions
 	AllowedExtensions []string
 	// WorkingDirectory is the base directory for file operations
@@ -29,6 +36,8 @@ func NewImageValidator() *ImageValidator {
 		MaxFileSize:       50 * 1024 * 1024, // 50MB
 		MaxImageWidth:     10000,
 		MaxImageHeight:    10000,
+		MaxPixels:         25_000_000,
+		MaxDecodedBytes:   100 * 1024 * 1024, // 100MB
 		AllowedExtensions: []string{".png", ".jpg", ".jpeg", ".gif", ".webp"},
 		WorkingDirectory:  ".",
 	}
@@ -172,6 +181,27 @@ func (v *ImageValidator) ValidateFileHeader(filePath string) error {
 	return nil
 }
 
+// SniffImageFormat detects the image format from the leading bytes of the data
+// and returns the matching file extension (e.g. ".png")
+func (v *ImageValidator) SniffImageFormat(header []byte) (string, error) {
+	if len(header) < 8 {
+		return "", fmt.Errorf("data too small to be a valid image")
+	}
+
+	switch {
+	case bytes.HasPrefix(header, []byte{0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A}):
+		return ".png", nil
+	case bytes.HasPrefix(header, []byte{0xFF, 0xD8, 0xFF}):
+		return ".jpg", nil
+	case bytes.HasPrefix(header, []byte("GIF87a")), bytes.HasPrefix(header, []byte("GIF89a")):
+		return ".gif", nil
+	case len(header) >= 12 && bytes.HasPrefix(header, []byte("RIFF")) && bytes.Equal(header[8:12], []byte("WEBP")):
+		return ".webp", nil
+	}
+
+	return "", fmt.Errorf("unsupported file format: unrecognized image data")
+}
+
 // ValidateImage performs all validation checks on the image file
 func (v *ImageValidator) ValidateImage(inputPath string) (string, error) {
 	// Step 1: Validate and sanitize the path
@@ -0,0 +1,170 @@
+package server
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"fmt"
+	"image"
+	"image/png"
+	"io"
+	"net"
+	"net/http"
+	"os"
+	"path/filepath"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/i18n"
+)
+
+// SocketEnv overrides the default daemon socket
+const SocketEnv = "GRIMOIRE_SOCKET"
+
+// socketName is the file name of the default daemon socket
+const socketName = "grimoire.sock"
+
+// DefaultSocket returns $GRIMOIRE_SOCKET, or grimoire.sock in the user's
+// runtime directory $XDG_RUNTIME_DIR. Without one, the socket is put in a
+// directory of the user's cache directory that Listen creates for the user
+// alone, rather than in the shared temporary directory.
+func DefaultSocket() string {
+	if socket := os.Getenv(SocketEnv); socket != "" {
+		return socket
+	}
+	if dir := os.Getenv("XDG_RUNTIME_DIR"); dir != "" {
+		return filepath.Join(dir, socketName)
+	}
+	dir, err := os.UserCacheDir()
+	if err != nil {
+		return filepath.Join(os.TempDir(), fmt.Sprintf("grimoire-%d", os.Getuid()), socketName)
+	}
+	return filepath.Join(dir, "grimoire", "daemon", socketName)
+}
+
+// Listen listens on the unix socket at path, replacing a socket left behind
+// by a daemon that has stopped. A missing directory for the socket is
+// created accessible to the user only.
+func Listen(path string) (net.Listener, error) {
+	if err := os.MkdirAll(filepath.Dir(path), 0o700); err != nil {
+		return nil, err
+	}
+	if conn, err := net.Dial("unix", path); err == nil {
+		conn.Close()
+		return nil, fmt.Errorf("a daemon is already listening on %s", path)
+	}
+	if info, err := os.Lstat(path); err == nil && info.Mode()&os.ModeSocket != 0 {
+		if err := os.Remove(path); err != nil {
+			return nil, err
+		}
+	}
+	return net.Listen("unix", path)
+}
+
+// Client is a detection backend that detects through a daemon listening on
+// a unix socket, so that repeated detections share its warm state
+type Client struct {
+	socket string
+	http   *http.Client
+}
+
+// NewClient creates a client of the daemon listening on socket. The client
+// only connects to a socket owned by the current user, so that images are
+// never sent to a daemon another user started in its place.
+func NewClient(socket string) *Client {
+	var dialer net.Dialer
+	return &Client{
+		socket: socket,
+		http: &http.Client{Transport: &http.Transport{
+			DialContext: func(ctx context.Context, _, _ string) (net.Conn, error) {
+				if err := checkSocketOwner(socket, os.Getuid()); err != nil {
+					return nil, err
+				}
+				return dialer.DialContext(ctx, "unix", socket)
+			},
+		}},
+	}
+}
+
+// Detect detects the symbols and connections in the image file at imagePath
+func (c *Client) Detect(imagePath string) ([]*detector.Symbol, []detector.Connection, error) {
+	data, err := os.ReadFile(imagePath)
+	if os.IsNotExist(err) {
+		return nil, nil, grimoireErrors.FileNotFoundError(imagePath)
+	}
+	if err != nil {
+		return nil, nil, grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("error.file_read_error")).
+			WithInnerError(err).
+			WithLocation(imagePath, 0, 0)
+	}
+	return c.detect(data, imagePath)
+}
+
+// DetectFromReader detects the symbols and connections in encoded image data
+// read from r
+func (c *Client) DetectFromReader(r io.Reader, source string) ([]*detector.Symbol, []detector.Connection, error) {
+	data, err := io.ReadAll(r)
+	if err != nil {
+		return nil, nil, grimoireErrors.NewError(grimoireErrors.FileReadError, i18n.T("error.file_read_error")).
+			WithInnerError(err).
+			WithLocation(source, 0, 0)
+	}
+	return c.detect(data, source)
+}
+
+// DetectImage detects the symbols and connections in a decoded image, which
+// is sent to the daemon as PNG
+func (c *Client) DetectImage(img image.Image) ([]*detector.Symbol, []detector.Connection, error) {
+	var buf bytes.Buffer
+	if err := png.Encode(&buf, img); err != nil {
+		return nil, nil, grimoireErrors.NewError(grimoireErrors.ImageProcessingError, err.Error())
+	}
+	return c.detect(buf.Bytes(), "<image>")
+}
+
+// detect posts encoded image data to the daemon. Errors the daemon reports
+// are rebuilt from their diagnostics, located at source.
+func (c *Client) detect(data []byte, source string) ([]*detector.Symbol, []detector.Connection, error) {
+	resp, err := c.http.Post("http://grimoire/detect", "application/octet-stream", bytes.NewReader(data))
+	if err != nil {
+		return nil, nil, grimoireErrors.NewError(grimoireErrors.IOError, i18n.Tf("remote.unreachable", c.socket)).
+			WithInnerError(err)
+	}
+	defer resp.Body.Close()
+	body, err := io.ReadAll(resp.Body)
+	if err != nil {
+		return nil, nil, grimoireErrors.NewError(grimoireErrors.IOError, i18n.Tf("remote.unreachable", c.socket)).
+			WithInnerError(err)
+	}
+
+	if resp.StatusCode != http.StatusOK {
+		var report struct {
+			Diagnostics []grimoireErrors.Diagnostic `json:"diagnostics"`
+		}
+		if err := json.Unmarshal(body, &report); err != nil || len(report.Diagnostics) == 0 {
+			return nil, nil, grimoireErrors.NewError(grimoireErrors.IOError, i18n.Tf("remote.failed", resp.Status))
+		}
+		return nil, nil, diagnosticsError(report.Diagnostics, source)
+	}
+	return detector.DecodeJSON(body)
+}
+
+// diagnosticsError rebuilds the error diagnostics were made from
+func diagnosticsError(diags []grimoireErrors.Diagnostic, source string) error {
+	errs := make([]error, 0, len(diags))
+	for _, d := range diags {
+		errs = append(errs, &grimoireErrors.GrimoireError{
+			Type:       d.Type,
+			Message:    d.Message,
+			Details:    d.Details,
+			Suggestion: d.Help,
+			FileName:   source,
+			Region:     d.Region,
+		})
+	}
+	if len(errs) == 1 {
+		return errs[0]
+	}
+	first := diags[0]
+	return grimoireErrors.NewError(first.Type, first.Message).WithLocation(source, 0, 0).WithRelated(errs...)
+}
@@ -0,0 +1,118 @@
+package server
+
+import (
+	"net/http"
+	"os"
+	"path/filepath"
+	"runtime"
+	"strings"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// startDaemon serves a server on a socket in a temporary directory
+func startDaemon(t *testing.T) string {
+	t.Helper()
+	s, err := New(detector.BackendSequential, detector.Config{})
+	require.NoError(t, err)
+	socket := filepath.Join(t.TempDir(), "grimoire.sock")
+	listener, err := Listen(socket)
+	require.NoError(t, err)
+
+	httpServer := &http.Server{Handler: s.Handler()}
+	go func() { _ = httpServer.Serve(listener) }()
+	t.Cleanup(func() { _ = httpServer.Close() })
+	return socket
+}
+
+func TestClientDetect(t *testing.T) {
+	path := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(path); err != nil {
+		t.Skip("Example image not found")
+	}
+	socket := startDaemon(t)
+
+	want, wantConnections, err := detector.NewDetector(detector.Config{}).Detect(path)
+	require.NoError(t, err)
+	symbols, connections, err := NewClient(socket).Detect(path)
+	require.NoError(t, err)
+	require.Len(t, symbols, len(want))
+	assert.Len(t, connections, len(wantConnections))
+	for i := range want {
+		assert.Equal(t, want[i].Type, symbols[i].Type)
+		assert.Equal(t, want[i].Position, symbols[i].Position)
+	}
+}
+
+func TestClientError(t *testing.T) {
+	socket := startDaemon(t)
+	path := filepath.Join(t.TempDir(), "broken.png")
+	require.NoError(t, os.WriteFile(path, []byte("not an image"), 0o644))
+
+	_, _, err := NewClient(socket).Detect(path)
+	gErr, ok := err.(*grimoireErrors.GrimoireError)
+	require.True(t, ok, "expected a GrimoireError, got %T", err)
+	assert.Equal(t, path, gErr.FileName)
+	assert.NotEmpty(t, gErr.Type)
+
+	_, _, err = NewClient(socket).Detect(filepath.Join(t.TempDir(), "missing.png"))
+	gErr, ok = err.(*grimoireErrors.GrimoireError)
+	require.True(t, ok)
+	assert.Equal(t, grimoireErrors.FileNotFound, gErr.Type)
+}
+
+func TestClientWithoutDaemon(t *testing.T) {
+	socket := filepath.Join(t.TempDir(), "grimoire.sock")
+	_, _, err := NewClient(socket).DetectFromReader(strings.NewReader(""), "<stdin>")
+	gErr, ok := err.(*grimoireErrors.GrimoireError)
+	require.True(t, ok)
+	assert.Equal(t, grimoireErrors.IOError, gErr.Type)
+}
+
+func TestListenTwice(t *testing.T) {
+	socket := startDaemon(t)
+	_, err := Listen(socket)
+	assert.Error(t, err)
+}
+
+func TestDefaultSocket(t *testing.T) {
+	t.Setenv(SocketEnv, "/tmp/custom.sock")
+	assert.Equal(t, "/tmp/custom.sock", DefaultSocket())
+
+	t.Setenv(SocketEnv, "")
+	t.Setenv("XDG_RUNTIME_DIR", "/run/user/1000")
+	assert.Equal(t, filepath.Join("/run/user/1000", "grimoire.sock"), DefaultSocket())
+
+	// Without a runtime directory the socket is kept out of the shared
+	// temporary directory
+	t.Setenv("XDG_RUNTIME_DIR", "")
+	assert.NotEqual(t, filepath.Join(os.TempDir(), "grimoire.sock"), DefaultSocket())
+}
+
+func TestListenCreatesPrivateDirectory(t *testing.T) {
+	if runtime.GOOS == "windows" {
+		t.Skip("File modes are not enforced on Windows")
+	}
+	dir := filepath.Join(t.TempDir(), "daemon")
+	listener, err := Listen(filepath.Join(dir, "grimoire.sock"))
+	require.NoError(t, err)
+	defer listener.Close()
+
+	info, err := os.Stat(dir)
+	require.NoError(t, err)
+	assert.Equal(t, os.FileMode(0o700), info.Mode().Perm())
+}
+
+func TestCheckSocketOwner(t *testing.T) {
+	if runtime.GOOS == "windows" {
+		t.Skip("Sockets have no owning user ID on Windows")
+	}
+	socket := startDaemon(t)
+	assert.NoError(t, checkSocketOwner(socket, os.Getuid()))
+	assert.Error(t, checkSocketOwner(socket, os.Getuid()+1), "a socket of another user is refused")
+	assert.NoError(t, checkSocketOwner(filepath.Join(t.TempDir(), "missing.sock"), os.Getuid()))
+}
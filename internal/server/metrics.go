@@ -0,0 +1,179 @@
+package server
+
+import (
+	"bytes"
+	"fmt"
+	"net/http"
+	"sort"
+	"strconv"
+	"strings"
+	"sync"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+)
+
+// latencyBuckets are the upper bounds, in seconds, of the latency histograms
+var latencyBuckets = []float64{0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1, 2.5, 5, 10}
+
+// registry holds the metrics a server exports
+type registry struct {
+	requests *counter
+	errors   *counter
+	latency  *histogram
+	stages   *histogram
+}
+
+func newRegistry() *registry {
+	return &registry{
+		requests: newCounter("grimoire_http_requests_total", "HTTP requests served, by path and status code.", "path", "code"),
+		errors:   newCounter("grimoire_compile_errors_total", "Errors compiling posted images, by error type.", "type"),
+		latency: newHistogram("grimoire_http_request_duration_seconds", "Time to answer HTTP requests, by path.",
+			latencyBuckets, "path"),
+		stages: newHistogram("grimoire_detection_stage_duration_seconds", "Time spent in each stage of detection.",
+			latencyBuckets, "stage"),
+	}
+}
+
+// observeDetection records the stage timings of one detection
+func (r *registry) observeDetection(m detector.Metrics) {
+	for _, s := range m.Stages {
+		r.stages.observe(s.Duration.Seconds(), string(s.Stage))
+	}
+}
+
+// serve writes every metric in the Prometheus text format
+func (r *registry) serve(w http.ResponseWriter, _ *http.Request) {
+	var buf bytes.Buffer
+	r.requests.write(&buf)
+	r.errors.write(&buf)
+	r.latency.write(&buf)
+	r.stages.write(&buf)
+	w.Header().Set("Content-Type", "text/plain; version=0.0.4; charset=utf-8")
+	_, _ = w.Write(buf.Bytes())
+}
+
+// counter is a count for each combination of label values
+type counter struct {
+	name, help string
+	labels     []string
+
+	mu     sync.Mutex
+	values map[string]float64 // keyed by labelKey
+}
+
+func newCounter(name, help string, labels ...string) *counter {
+	return &counter{name: name, help: help, labels: labels, values: make(map[string]float64)}
+}
+
+// inc adds one to the count of the label values
+func (c *counter) inc(values ...string) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	c.values[labelKey(values)]++
+}
+
+func (c *counter) write(buf *bytes.Buffer) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	fmt.Fprintf(buf, "# HELP %s %s\n# TYPE %s counter\n", c.name, c.help, c.name)
+	for _, key := range sortedKeys(c.values) {
+		fmt.Fprintf(buf, "%s%s %s\n", c.name, labelSet(c.labels, key, ""), formatValue(c.values[key]))
+	}
+}
+
+// histogram counts observations into buckets for each combination of label
+// values
+type histogram struct {
+	name, help string
+	labels     []string
+	buckets    []float64
+
+	mu     sync.Mutex
+	series map[string]*series // keyed by labelKey
+}
+
+// series are the observations of one combination of label values
+type series struct {
+	counts []uint64 // per bucket, not cumulative
+	count  uint64
+	sum    float64
+}
+
+func newHistogram(name, help string, buckets []float64, labels ...string) *histogram {
+	return &histogram{name: name, help: help, labels: labels, buckets: buckets, series: make(map[string]*series)}
+}
+
+// observe records v under the label values
+func (h *histogram) observe(v float64, values ...string) {
+	h.mu.Lock()
+	defer h.mu.Unlock()
+	key := labelKey(values)
+	s := h.series[key]
+	if s == nil {
+		s = &series{counts: make([]uint64, len(h.buckets))}
+		h.series[key] = s
+	}
+	if i := sort.SearchFloat64s(h.buckets, v); i < len(h.buckets) {
+		s.counts[i]++
+	}
+	s.count++
+	s.sum += v
+}
+
+func (h *histogram) write(buf *bytes.Buffer) {
+	h.mu.Lock()
+	defer h.mu.Unlock()
+	fmt.Fprintf(buf, "# HELP %s %s\n# TYPE %s histogram\n", h.name, h.help, h.name)
+	for _, key := range sortedKeys(h.series) {
+		s := h.series[key]
+		var cumulative uint64
+		for i, bound := range h.buckets {
+			cumulative += s.counts[i]
+			fmt.Fprintf(buf, "%s_bucket%s %d\n", h.name, labelSet(h.labels, key, formatValue(bound)), cumulative)
+		}
+		fmt.Fprintf(buf, "%s_bucket%s %d\n", h.name, labelSet(h.labels, key, "+Inf"), s.count)
+		fmt.Fprintf(buf, "%s_sum%s %s\n", h.name, labelSet(h.labels, key, ""), formatValue(s.sum))
+		fmt.Fprintf(buf, "%s_count%s %d\n", h.name, labelSet(h.labels, key, ""), s.count)
+	}
+}
+
+// labelKey joins label values into a map key
+func labelKey(values []string) string {
+	return strings.Join(values, "\x00")
+}
+
+// labelEscaper escapes label values as the text format requires
+var labelEscaper = strings.NewReplacer(`\`, `\\`, `"`, `\"`, "\n", `\n`)
+
+// labelSet returns the labels of key in braces, followed by le when it is
+// not empty
+func labelSet(names []string, key, le string) string {
+	var pairs []string
+	if len(names) > 0 {
+		for i, value := range strings.Split(key, "\x00") {
+			pairs = append(pairs, fmt.Sprintf(`%s="%s"`, names[i], labelEscaper.Replace(value)))
+		}
+	}
+	if le != "" {
+		pairs = append(pairs, fmt.Sprintf(`le="%s"`, le))
+	}
+	if len(pairs) == 0 {
+		return ""
+	}
+	return "{" + strings.Join(pairs, ",") + "}"
+}
+
+// sortedKeys returns the keys of m in order, so that output is stable
+func sortedKeys[V any](m map[string]V) []string {
+	keys := make([]string, 0, len(m))
+	for key := range m {
+		keys = append(keys, key)
+	}
+	sort.Strings(keys)
+	return keys
+}
+
+// formatValue formats a sample value in its shortest form
+func formatValue(v float64) string {
+	return strconv.FormatFloat(v, 'g', -1, 64)
+}
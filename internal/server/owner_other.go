@@ -0,0 +1,9 @@
+//go:build !unix
+
+package server
+
+// checkSocketOwner accepts every socket where file ownership is not
+// reported as a user ID
+func checkSocketOwner(string, int) error {
+	return nil
+}
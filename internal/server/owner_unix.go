@@ -0,0 +1,29 @@
+//go:build unix
+
+package server
+
+import (
+	"fmt"
+	"os"
+	"syscall"
+)
+
+// checkSocketOwner returns an error unless the socket at path is owned by
+// the user with uid. A missing socket passes, leaving the dial to fail.
+func checkSocketOwner(path string, uid int) error {
+	info, err := os.Lstat(path)
+	if os.IsNotExist(err) {
+		return nil
+	}
+	if err != nil {
+		return err
+	}
+	stat, ok := info.Sys().(*syscall.Stat_t)
+	if !ok {
+		return nil
+	}
+	if int(stat.Uid) != uid {
+		return fmt.Errorf("%s is owned by user %d, not by the current user", path, stat.Uid)
+	}
+	return nil
+}
@@ -0,0 +1,164 @@
+// Package server serves compilation over HTTP and exports metrics about it.
+//
+// POST /compile takes an image as the request body and answers with the
+// Python program, or with the diagnostics as JSON when the image does not
+// compile. POST /detect answers with the symbols and connections as JSON,
+// which is how a Client detects through a running daemon.
+//
+// GET /metrics exports request counts, errors and latencies, and the time
+// spent in each stage of detection, in the Prometheus text format.
+package server
+
+import (
+	"bytes"
+	"io"
+	"net/http"
+	"strconv"
+	"time"
+
+	"github.com/ayutaz/grimoire/internal/compiler"
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/ayutaz/grimoire/internal/security"
+)
+
+// requestName names the request body in diagnostics
+const requestName = "<request>"
+
+// Server compiles images posted to it
+type Server struct {
+	backend detector.Backend
+	metrics *registry
+}
+
+// New creates a server detecting with the named backend. cfg.Metrics is
+// replaced so that the server can export the time spent in each stage.
+func New(backendName string, cfg detector.Config) (*Server, error) {
+	s := &Server{metrics: newRegistry()}
+	cfg.Metrics = s.metrics.observeDetection
+	backend, err := detector.NewBackend(backendName, cfg)
+	if err != nil {
+		return nil, err
+	}
+	s.backend = backend
+	return s, nil
+}
+
+// Handler returns the handler serving the endpoints of the server
+func (s *Server) Handler() http.Handler {
+	mux := http.NewServeMux()
+	mux.Handle("/compile", s.instrument("/compile", http.HandlerFunc(s.compile)))
+	mux.Handle("/detect", s.instrument("/detect", http.HandlerFunc(s.detect)))
+	mux.Handle("/metrics", http.HandlerFunc(s.metrics.serve))
+	mux.Handle("/healthz", s.instrument("/healthz", http.HandlerFunc(func(w http.ResponseWriter, _ *http.Request) {
+		w.WriteHeader(http.StatusNoContent)
+	})))
+	return mux
+}
+
+// compile detects, parses and compiles the image in the request body
+func (s *Server) compile(w http.ResponseWriter, r *http.Request) {
+	data, ok := s.readImage(w, r)
+	if !ok {
+		return
+	}
+	code, err := s.compileImage(data)
+	if err != nil {
+		s.fail(w, http.StatusUnprocessableEntity, err)
+		return
+	}
+	w.Header().Set("Content-Type", "text/x-python; charset=utf-8")
+	_, _ = io.WriteString(w, code)
+}
+
+// detect detects the image in the request body
+func (s *Server) detect(w http.ResponseWriter, r *http.Request) {
+	data, ok := s.readImage(w, r)
+	if !ok {
+		return
+	}
+	symbols, connections, err := s.backend.DetectFromReader(bytes.NewReader(data), requestName)
+	if err == nil {
+		data, err = detector.EncodeJSON(symbols, connections)
+	}
+	if err != nil {
+		s.fail(w, http.StatusUnprocessableEntity, err)
+		return
+	}
+	w.Header().Set("Content-Type", "application/json")
+	_, _ = w.Write(data)
+}
+
+// readImage reads the image posted in the request body. It answers the
+// request itself and returns false when there is none.
+func (s *Server) readImage(w http.ResponseWriter, r *http.Request) ([]byte, bool) {
+	if r.Method != http.MethodPost {
+		w.Header().Set("Allow", http.MethodPost)
+		http.Error(w, http.StatusText(http.StatusMethodNotAllowed), http.StatusMethodNotAllowed)
+		return nil, false
+	}
+
+	// Read one byte past the size limit so the decoder can still reject oversized input
+	limit := security.NewImageValidator().MaxFileSize + 1
+	data, err := io.ReadAll(io.LimitReader(r.Body, limit))
+	if err != nil {
+		s.fail(w, http.StatusBadRequest, grimoireErrors.NewError(grimoireErrors.FileReadError, err.Error()))
+		return nil, false
+	}
+	return data, true
+}
+
+// compileImage detects, parses and compiles an encoded image
+func (s *Server) compileImage(data []byte) (string, error) {
+	symbols, connections, err := s.backend.DetectFromReader(bytes.NewReader(data), requestName)
+	if err != nil {
+		return "", err
+	}
+	program, err := parser.Parse(symbols, connections)
+	if err != nil {
+		return "", err
+	}
+	return compiler.Compile(program)
+}
+
+// fail answers with the diagnostics of err and counts it by type
+func (s *Server) fail(w http.ResponseWriter, status int, err error) {
+	diags := grimoireErrors.Diagnostics(err)
+	for _, d := range diags {
+		s.metrics.errors.inc(errorLabel(d.Type))
+	}
+	w.Header().Set("Content-Type", "application/json")
+	w.WriteHeader(status)
+	_ = grimoireErrors.WriteDiagnosticsJSON(w, diags)
+}
+
+// errorLabel names an error type in metrics
+func errorLabel(t grimoireErrors.ErrorType) string {
+	if t == "" {
+		return "UNKNOWN"
+	}
+	return string(t)
+}
+
+// instrument counts the requests to path by status code and times them
+func (s *Server) instrument(path string, next http.Handler) http.Handler {
+	return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		started := time.Now()
+		rec := &statusRecorder{ResponseWriter: w, status: http.StatusOK}
+		next.ServeHTTP(rec, r)
+		s.metrics.requests.inc(path, strconv.Itoa(rec.status))
+		s.metrics.latency.observe(time.Since(started).Seconds(), path)
+	})
+}
+
+// statusRecorder remembers the status code written through it
+type statusRecorder struct {
+	http.ResponseWriter
+	status int
+}
+
+func (r *statusRecorder) WriteHeader(status int) {
+	r.status = status
+	r.ResponseWriter.WriteHeader(status)
+}
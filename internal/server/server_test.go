@@ -0,0 +1,115 @@
+package server
+
+import (
+	"bytes"
+	"encoding/json"
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func newTestServer(t *testing.T) *httptest.Server {
+	t.Helper()
+	s, err := New(detector.BackendSequential, detector.Config{})
+	require.NoError(t, err)
+	ts := httptest.NewServer(s.Handler())
+	t.Cleanup(ts.Close)
+	return ts
+}
+
+func get(t *testing.T, url string) (int, string) {
+	t.Helper()
+	resp, err := http.Get(url)
+	require.NoError(t, err)
+	defer resp.Body.Close()
+	body, err := io.ReadAll(resp.Body)
+	require.NoError(t, err)
+	return resp.StatusCode, string(body)
+}
+
+func TestCompile(t *testing.T) {
+	data, err := os.ReadFile(filepath.Join("..", "..", "examples", "images", "hello_world.png"))
+	if err != nil {
+		t.Skip("Example image not found")
+	}
+	ts := newTestServer(t)
+
+	resp, err := http.Post(ts.URL+"/compile", "image/png", bytes.NewReader(data))
+	require.NoError(t, err)
+	defer resp.Body.Close()
+	code, err := io.ReadAll(resp.Body)
+	require.NoError(t, err)
+	assert.Equal(t, http.StatusOK, resp.StatusCode)
+	assert.Contains(t, string(code), "print")
+
+	_, metrics := get(t, ts.URL+"/metrics")
+	assert.Contains(t, metrics, `grimoire_http_requests_total{path="/compile",code="200"} 1`)
+	assert.Contains(t, metrics, `grimoire_detection_stage_duration_seconds_count{stage="preprocess"} 1`)
+	assert.Contains(t, metrics, `grimoire_detection_stage_duration_seconds_count{stage="connections"} 1`)
+}
+
+func TestCompileError(t *testing.T) {
+	ts := newTestServer(t)
+
+	resp, err := http.Post(ts.URL+"/compile", "image/png", strings.NewReader("not an image"))
+	require.NoError(t, err)
+	defer resp.Body.Close()
+	assert.Equal(t, http.StatusUnprocessableEntity, resp.StatusCode)
+	assert.Equal(t, "application/json", resp.Header.Get("Content-Type"))
+
+	var report struct {
+		Diagnostics []struct {
+			Type string `json:"type"`
+		} `json:"diagnostics"`
+	}
+	require.NoError(t, json.NewDecoder(resp.Body).Decode(&report))
+	require.NotEmpty(t, report.Diagnostics)
+
+	_, metrics := get(t, ts.URL+"/metrics")
+	assert.Contains(t, metrics, `grimoire_http_requests_total{path="/compile",code="422"} 1`)
+	assert.Contains(t, metrics, "grimoire_compile_errors_total{type=")
+}
+
+func TestCompileRequiresPost(t *testing.T) {
+	ts := newTestServer(t)
+
+	status, _ := get(t, ts.URL+"/compile")
+	assert.Equal(t, http.StatusMethodNotAllowed, status)
+	status, _ = get(t, ts.URL+"/healthz")
+	assert.Equal(t, http.StatusNoContent, status)
+
+	_, metrics := get(t, ts.URL+"/metrics")
+	assert.Contains(t, metrics, `grimoire_http_requests_total{path="/compile",code="405"} 1`)
+	assert.Contains(t, metrics, `grimoire_http_requests_total{path="/healthz",code="204"} 1`)
+}
+
+func TestHistogramFormat(t *testing.T) {
+	h := newHistogram("test_seconds", "Test.", []float64{0.1, 1}, "stage")
+	h.observe(0.05, "a")
+	h.observe(0.5, "a")
+	h.observe(5, "a")
+
+	var buf bytes.Buffer
+	h.write(&buf)
+	assert.Equal(t, `# HELP test_seconds Test.
+# TYPE test_seconds histogram
+test_seconds_bucket{stage="a",le="0.1"} 1
+test_seconds_bucket{stage="a",le="1"} 2
+test_seconds_bucket{stage="a",le="+Inf"} 3
+test_seconds_sum{stage="a"} 5.55
+test_seconds_count{stage="a"} 3
+`, buf.String())
+}
+
+func TestLabelEscaping(t *testing.T) {
+	assert.Equal(t, `{type="a\"b\\c\n"}`, labelSet([]string{"type"}, "a\"b\\c\n", ""))
+	assert.Equal(t, "", labelSet(nil, "", ""))
+}
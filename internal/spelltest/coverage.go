@@ -0,0 +1,168 @@
+package spelltest
+
+import (
+	"bytes"
+	"fmt"
+	"image"
+	"image/color"
+	"image/draw"
+	"math"
+	"sort"
+	"strings"
+
+	"github.com/ayutaz/grimoire/internal/profiler"
+)
+
+// CoverageSchemaVersion is the version of the JSON encoding of a
+// CoverageSummary. It is increased whenever the encoding changes
+// incompatibly; adding fields does not change it.
+const CoverageSchemaVersion = 1
+
+// Tracer runs generated code, recording the lines that ran, and returns
+// what it wrote to stdout
+type Tracer func(code string) (string, *profiler.Lines, error)
+
+// SymbolCoverage records how often the statement of one symbol ran
+type SymbolCoverage struct {
+	Index int     `json:"index"`
+	Type  string  `json:"type"`
+	Role  string  `json:"role"`
+	X     float64 `json:"x"`
+	Y     float64 `json:"y"`
+	Size  float64 `json:"size"`
+	Count int     `json:"count"`
+}
+
+// Coverage records which symbols of a case ran. Only symbols that belong to
+// a compiled statement are counted.
+type Coverage struct {
+	Symbols []SymbolCoverage
+}
+
+// Covered returns the number of symbols that ran
+func (c *Coverage) Covered() int {
+	covered := 0
+	for _, s := range c.Symbols {
+		if s.Count > 0 {
+			covered++
+		}
+	}
+	return covered
+}
+
+// Percent returns the share of symbols that ran, or 100 when there are none
+func (c *Coverage) Percent() float64 {
+	return percent(c.Covered(), len(c.Symbols))
+}
+
+// newCoverage builds the coverage of a case from its profile
+func newCoverage(entries []profiler.Entry) *Coverage {
+	coverage := &Coverage{Symbols: make([]SymbolCoverage, 0, len(entries))}
+	for _, e := range entries {
+		coverage.Symbols = append(coverage.Symbols, SymbolCoverage{
+			Index: e.Role.Index,
+			Type:  string(e.Role.Symbol.Type),
+			Role:  string(e.Role.Kind),
+			X:     e.Role.Symbol.Position.X,
+			Y:     e.Role.Symbol.Position.Y,
+			Size:  e.Role.Symbol.Size,
+			Count: e.Count,
+		})
+	}
+	sort.Slice(coverage.Symbols, func(i, j int) bool { return coverage.Symbols[i].Index < coverage.Symbols[j].Index })
+	return coverage
+}
+
+// uncoveredGray is the color the ink of uncovered symbols is drawn in
+var uncoveredGray = color.RGBA{R: 190, G: 190, B: 190, A: 255}
+
+// coverageMargin is the space, in pixels, around a symbol that is grayed with it
+const coverageMargin = 4
+
+// Image returns a copy of img, the image of the case, with the ink of every
+// symbol that never ran drawn in gray
+func (c *Coverage) Image(img image.Image) *image.RGBA {
+	bounds := img.Bounds()
+	canvas := image.NewRGBA(image.Rect(0, 0, bounds.Dx(), bounds.Dy()))
+	draw.Draw(canvas, canvas.Bounds(), img, bounds.Min, draw.Src)
+
+	for _, s := range c.Symbols {
+		if s.Count > 0 {
+			continue
+		}
+		half := s.Size/2 + coverageMargin
+		area := image.Rect(
+			int(math.Floor(s.X-half)), int(math.Floor(s.Y-half)),
+			int(math.Ceil(s.X+half))+1, int(math.Ceil(s.Y+half))+1,
+		).Intersect(canvas.Bounds())
+		for y := area.Min.Y; y < area.Max.Y; y++ {
+			for x := area.Min.X; x < area.Max.X; x++ {
+				if color.GrayModel.Convert(canvas.At(x, y)).(color.Gray).Y < 128 {
+					canvas.SetRGBA(x, y, uncoveredGray)
+				}
+			}
+		}
+	}
+	return canvas
+}
+
+// CaseCoverage is the coverage of one case in a CoverageSummary
+type CaseCoverage struct {
+	Name    string           `json:"name"`
+	Image   string           `json:"image"`
+	Covered int              `json:"covered"`
+	Total   int              `json:"total"`
+	Percent float64          `json:"percent"`
+	Symbols []SymbolCoverage `json:"symbols"`
+}
+
+// CoverageSummary is the coverage of a whole suite, as written to JSON
+type CoverageSummary struct {
+	SchemaVersion int            `json:"schemaVersion"`
+	Cases         []CaseCoverage `json:"cases"`
+	Covered       int            `json:"covered"`
+	Total         int            `json:"total"`
+	Percent       float64        `json:"percent"`
+}
+
+// Summarize collects the coverage of the results that have one
+func Summarize(results []Result) CoverageSummary {
+	summary := CoverageSummary{SchemaVersion: CoverageSchemaVersion, Cases: []CaseCoverage{}}
+	for _, result := range results {
+		c := result.Coverage
+		if c == nil {
+			continue
+		}
+		summary.Cases = append(summary.Cases, CaseCoverage{
+			Name:    result.Case.Name,
+			Image:   result.Case.ImagePath,
+			Covered: c.Covered(),
+			Total:   len(c.Symbols),
+			Percent: c.Percent(),
+			Symbols: c.Symbols,
+		})
+		summary.Covered += c.Covered()
+		summary.Total += len(c.Symbols)
+	}
+	summary.Percent = percent(summary.Covered, summary.Total)
+	return summary
+}
+
+// percent returns covered as a percentage of total, or 100 when total is 0
+func percent(covered, total int) float64 {
+	if total == 0 {
+		return 100
+	}
+	return float64(covered) * 100 / float64(total)
+}
+
+// TracePython runs Python code with python3 under the profiler's tracer,
+// adding the environment entries given to those of this process
+func TracePython(code string, env ...string) (string, *profiler.Lines, error) {
+	var stdout, stderr bytes.Buffer
+	lines, err := profiler.Run(code, &stdout, &stderr, env...)
+	if err != nil {
+		return stdout.String(), lines, fmt.Errorf("%w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	return stdout.String(), lines, nil
+}
@@ -0,0 +1,314 @@
+// Package spelltest runs golden tests over a directory of magic circle images.
+//
+// Each image may have expectation files next to it that share its base name:
+//
+//	hello.png        the program
+//	hello.out        expected stdout of the executed program
+//	hello.ast.json   expected AST, as produced by parser.EncodeJSON
+//
+// An image is a test case when at least one expectation file exists. An
+// image whose name ends in _test is a self-testing circle: it is a case
+// even without expectation files, and it fails when any of its assertions
+// does.
+package spelltest
+
+import (
+	"bytes"
+	"encoding/json"
+	"fmt"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"sort"
+	"strings"
+
+	"github.com/ayutaz/grimoire/internal/compiler"
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/ayutaz/grimoire/internal/profiler"
+)
+
+// Expectation file suffixes
+const (
+	OutputSuffix = ".out"
+	ASTSuffix    = ".ast.json"
+)
+
+// SelfTestSuffix ends the name of self-testing circles
+const SelfTestSuffix = "_test"
+
+// Failure kinds
+const (
+	FailureOutput    = "output"
+	FailureAST       = "ast"
+	FailureAssertion = "assertion"
+)
+
+// imageExtensions are the image formats considered when discovering cases
+var imageExtensions = map[string]bool{
+	".png": true, ".jpg": true, ".jpeg": true, ".gif": true, ".webp": true,
+}
+
+// Case is a single image with its expectation files
+type Case struct {
+	Name       string
+	ImagePath  string
+	OutputPath string // empty if there is no expected output
+	ASTPath    string // empty if there is no expected AST
+	SelfTest   bool   // run for its assertions even without an expected output
+}
+
+// Failure is a mismatch between expected and actual results. For failed
+// assertions, Diff holds their messages, one per line.
+type Failure struct {
+	Kind string
+	Diff string
+}
+
+// Result is the outcome of running a case
+type Result struct {
+	Case     Case
+	Failures []Failure
+	// Err is set when the case could not be run to completion
+	Err error
+	// Updated lists expectation files rewritten in update mode
+	Updated []string
+	// Coverage records which symbols ran, when the runner tracks coverage
+	// and the case has an expected output
+	Coverage *Coverage
+}
+
+// Passed reports whether the case ran and matched all expectations
+func (r Result) Passed() bool {
+	return r.Err == nil && len(r.Failures) == 0
+}
+
+// Executor runs generated code and returns what it wrote to stdout
+type Executor func(code string) (string, error)
+
+// Runner runs spell test cases
+type Runner struct {
+	// Execute runs the generated program for output expectations
+	Execute Executor
+	// Update rewrites expectation files with actual results instead of comparing
+	Update bool
+	// Coverage runs programs with Trace instead of Execute to record which
+	// symbols ran
+	Coverage bool
+	// Trace runs the generated program for output expectations when
+	// Coverage is set
+	Trace Tracer
+}
+
+// NewRunner creates a runner that executes programs with python3. The
+// environment entries given are added to those of this process.
+func NewRunner(env ...string) *Runner {
+	return &Runner{
+		Execute: func(code string) (string, error) { return ExecutePython(code, env...) },
+		Trace:   func(code string) (string, *profiler.Lines, error) { return TracePython(code, env...) },
+	}
+}
+
+// Discover finds the test cases in dir, sorted by name. When includeUnpaired
+// is set, images without expectation files are included with an expected
+// output path so that update mode can create it.
+func Discover(dir string, includeUnpaired bool) ([]Case, error) {
+	entries, err := os.ReadDir(dir)
+	if err != nil {
+		return nil, err
+	}
+
+	var cases []Case
+	for _, entry := range entries {
+		if entry.IsDir() || !imageExtensions[strings.ToLower(filepath.Ext(entry.Name()))] {
+			continue
+		}
+
+		imagePath := filepath.Join(dir, entry.Name())
+		base := strings.TrimSuffix(imagePath, filepath.Ext(imagePath))
+		c := Case{
+			Name:      strings.TrimSuffix(entry.Name(), filepath.Ext(entry.Name())),
+			ImagePath: imagePath,
+		}
+		c.SelfTest = strings.HasSuffix(c.Name, SelfTestSuffix)
+		if fileExists(base + OutputSuffix) {
+			c.OutputPath = base + OutputSuffix
+		}
+		if fileExists(base + ASTSuffix) {
+			c.ASTPath = base + ASTSuffix
+		}
+
+		if c.OutputPath == "" && c.ASTPath == "" && !c.SelfTest {
+			if !includeUnpaired {
+				continue
+			}
+			c.OutputPath = base + OutputSuffix
+		}
+		cases = append(cases, c)
+	}
+
+	sort.Slice(cases, func(i, j int) bool { return cases[i].Name < cases[j].Name })
+	return cases, nil
+}
+
+// Run runs a single case
+func (r *Runner) Run(c Case) Result {
+	result := Result{Case: c}
+
+	symbols, connections, err := detector.DetectSymbols(c.ImagePath)
+	if err != nil {
+		result.Err = err
+		return result
+	}
+	p := parser.NewParser()
+	program, err := p.Parse(symbols, connections)
+	if err != nil {
+		result.Err = err
+		return result
+	}
+
+	if c.ASTPath != "" {
+		actual, err := parser.EncodeJSON(program)
+		if err != nil {
+			result.Err = err
+			return result
+		}
+		r.check(&result, FailureAST, c.ASTPath, string(actual)+"\n", normalizeJSON)
+	}
+
+	if c.OutputPath != "" || c.SelfTest {
+		comp := compiler.NewCompiler()
+		code, err := comp.Compile(program)
+		if err != nil {
+			result.Err = err
+			return result
+		}
+		var actual string
+		if r.Coverage {
+			var lines *profiler.Lines
+			actual, lines, err = r.Trace(code)
+			if lines != nil {
+				result.Coverage = newCoverage(profiler.Attribute(p.Roles(), comp.SourceMap(), lines))
+			}
+		} else {
+			actual, err = r.Execute(code)
+		}
+		// A program stopped by failed assertions ran to completion
+		if failed := assertionFailures(err); len(failed) > 0 {
+			result.Failures = append(result.Failures, Failure{
+				Kind: FailureAssertion,
+				Diff: strings.Join(failed, "\n") + "\n",
+			})
+			err = nil
+		}
+		if err != nil {
+			result.Err = err
+			return result
+		}
+		if c.OutputPath != "" {
+			r.check(&result, FailureOutput, c.OutputPath, actual, normalizeNewlines)
+		}
+	}
+
+	return result
+}
+
+// RunAll runs every case in order
+func (r *Runner) RunAll(cases []Case) []Result {
+	results := make([]Result, 0, len(cases))
+	for _, c := range cases {
+		results = append(results, r.Run(c))
+	}
+	return results
+}
+
+// check compares actual against the expectation file, or rewrites the file in update mode
+func (r *Runner) check(result *Result, kind, path, actual string, normalize func(string) string) {
+	if r.Update {
+		if err := os.WriteFile(path, []byte(actual), 0o644); err != nil {
+			result.Err = err
+			return
+		}
+		result.Updated = append(result.Updated, path)
+		return
+	}
+
+	expectedData, err := os.ReadFile(path)
+	if err != nil {
+		result.Err = err
+		return
+	}
+
+	expected := normalize(string(expectedData))
+	got := normalize(actual)
+	if expected != got {
+		result.Failures = append(result.Failures, Failure{Kind: kind, Diff: Diff(expected, got)})
+	}
+}
+
+// ExecutePython runs Python code with python3 and returns its stdout. The
+// environment entries given are added to those of this process.
+func ExecutePython(code string, env ...string) (string, error) {
+	tmpFile, err := os.CreateTemp("", "grimoire_test_*.py")
+	if err != nil {
+		return "", err
+	}
+	defer os.Remove(tmpFile.Name())
+
+	if _, err := tmpFile.WriteString(code); err != nil {
+		tmpFile.Close()
+		return "", err
+	}
+	tmpFile.Close()
+
+	var stdout, stderr bytes.Buffer
+	cmd := exec.Command("python3", tmpFile.Name())
+	cmd.Env = append(os.Environ(), env...)
+	cmd.Stdout = &stdout
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		return stdout.String(), fmt.Errorf("%w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	return stdout.String(), nil
+}
+
+// assertionFailures returns the messages of the failed assertions reported
+// in the error of a run
+func assertionFailures(err error) []string {
+	if err == nil {
+		return nil
+	}
+	var failed []string
+	for _, line := range strings.Split(err.Error(), "\n") {
+		if i := strings.Index(line, compiler.AssertionPrefix); i >= 0 {
+			failed = append(failed, strings.TrimSpace(line[i:]))
+		}
+	}
+	return failed
+}
+
+// normalizeNewlines makes line endings and the trailing newline irrelevant
+func normalizeNewlines(s string) string {
+	s = strings.ReplaceAll(s, "\r\n", "\n")
+	return strings.TrimRight(s, "\n") + "\n"
+}
+
+// normalizeJSON re-indents JSON so formatting differences are ignored.
+// Invalid JSON is compared as text.
+func normalizeJSON(s string) string {
+	var v interface{}
+	if err := json.Unmarshal([]byte(s), &v); err != nil {
+		return normalizeNewlines(s)
+	}
+	out, err := json.MarshalIndent(v, "", "  ")
+	if err != nil {
+		return normalizeNewlines(s)
+	}
+	return string(out) + "\n"
+}
+
+// fileExists reports whether path exists and is a regular file
+func fileExists(path string) bool {
+	info, err := os.Stat(path)
+	return err == nil && info.Mode().IsRegular()
+}
@@ -0,0 +1,288 @@
+package spelltest
+
+import (
+	"encoding/json"
+	"errors"
+	"image"
+	"image/color"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/ayutaz/grimoire/internal/profiler"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// copyExample copies an example image into dir under the given name
+func copyExample(t *testing.T, dir, example, name string) string {
+	t.Helper()
+	src := filepath.Join("..", "..", "examples", "images", example)
+	data, err := os.ReadFile(src)
+	if err != nil {
+		t.Skip("Example image not found")
+	}
+	dst := filepath.Join(dir, name)
+	require.NoError(t, os.WriteFile(dst, data, 0o644))
+	return dst
+}
+
+// fakeExecutor returns a fixed stdout regardless of the code
+func fakeExecutor(stdout string) Executor {
+	return func(string) (string, error) { return stdout, nil }
+}
+
+func TestDiff(t *testing.T) {
+	tests := []struct {
+		name     string
+		expected string
+		actual   string
+		want     string
+	}{
+		{
+			name:     "identical",
+			expected: "a\nb\n",
+			actual:   "a\nb\n",
+			want:     "  a\n  b\n",
+		},
+		{
+			name:     "changed line",
+			expected: "a\nb\nc\n",
+			actual:   "a\nx\nc\n",
+			want:     "  a\n- b\n+ x\n  c\n",
+		},
+		{
+			name:     "added line",
+			expected: "a\n",
+			actual:   "a\nb\n",
+			want:     "  a\n+ b\n",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			assert.Equal(t, tt.want, Diff(tt.expected, tt.actual))
+		})
+	}
+}
+
+func TestDiscover(t *testing.T) {
+	dir := t.TempDir()
+	for _, name := range []string{"a.png", "b.png", "c.png", "notes.txt"} {
+		require.NoError(t, os.WriteFile(filepath.Join(dir, name), nil, 0o644))
+	}
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "b.out"), nil, 0o644))
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "a.ast.json"), nil, 0o644))
+
+	cases, err := Discover(dir, false)
+	require.NoError(t, err)
+	require.Len(t, cases, 2)
+	assert.Equal(t, "a", cases[0].Name)
+	assert.Equal(t, filepath.Join(dir, "a.ast.json"), cases[0].ASTPath)
+	assert.Empty(t, cases[0].OutputPath)
+	assert.Equal(t, "b", cases[1].Name)
+	assert.Equal(t, filepath.Join(dir, "b.out"), cases[1].OutputPath)
+
+	cases, err = Discover(dir, true)
+	require.NoError(t, err)
+	require.Len(t, cases, 3)
+	assert.Equal(t, filepath.Join(dir, "c.out"), cases[2].OutputPath)
+
+	_, err = Discover(filepath.Join(dir, "missing"), false)
+	assert.Error(t, err)
+}
+
+func TestRunner_Run(t *testing.T) {
+	dir := t.TempDir()
+	copyExample(t, dir, "hello_world.png", "hello.png")
+	outPath := filepath.Join(dir, "hello.out")
+	require.NoError(t, os.WriteFile(outPath, []byte("Hello, World!\r\n"), 0o644))
+
+	cases, err := Discover(dir, false)
+	require.NoError(t, err)
+	require.Len(t, cases, 1)
+
+	t.Run("matching output", func(t *testing.T) {
+		runner := &Runner{Execute: fakeExecutor("Hello, World!\n")}
+		result := runner.Run(cases[0])
+		require.NoError(t, result.Err)
+		assert.True(t, result.Passed())
+	})
+
+	t.Run("mismatched output", func(t *testing.T) {
+		runner := &Runner{Execute: fakeExecutor("Goodbye\n")}
+		result := runner.Run(cases[0])
+		require.NoError(t, result.Err)
+		require.Len(t, result.Failures, 1)
+		assert.Equal(t, FailureOutput, result.Failures[0].Kind)
+		assert.Equal(t, "- Hello, World!\n+ Goodbye\n", result.Failures[0].Diff)
+	})
+}
+
+func TestRunner_SelfTest(t *testing.T) {
+	dir := t.TempDir()
+	copyExample(t, dir, "hello_world.png", "hello_test.png")
+
+	cases, err := Discover(dir, false)
+	require.NoError(t, err)
+	require.Len(t, cases, 1, "self-testing circles need no expectation files")
+	assert.True(t, cases[0].SelfTest)
+	assert.Empty(t, cases[0].OutputPath)
+
+	t.Run("assertions hold", func(t *testing.T) {
+		runner := &Runner{Execute: fakeExecutor("Hello, World!\n")}
+		result := runner.Run(cases[0])
+		require.NoError(t, result.Err)
+		assert.True(t, result.Passed())
+	})
+
+	t.Run("assertions fail", func(t *testing.T) {
+		runner := &Runner{Execute: func(string) (string, error) {
+			return "", errors.New("exit status 1: assertion failed at (200, 150): 1 != 2\n" +
+				"assertion failed at (300, 150): 'a' != 'b'")
+		}}
+		result := runner.Run(cases[0])
+		require.NoError(t, result.Err)
+		require.Len(t, result.Failures, 1)
+		assert.Equal(t, FailureAssertion, result.Failures[0].Kind)
+		assert.Equal(t, "assertion failed at (200, 150): 1 != 2\nassertion failed at (300, 150): 'a' != 'b'\n",
+			result.Failures[0].Diff)
+	})
+
+	t.Run("other errors", func(t *testing.T) {
+		runner := &Runner{Execute: func(string) (string, error) {
+			return "", errors.New("exit status 1: NameError")
+		}}
+		result := runner.Run(cases[0])
+		assert.Error(t, result.Err)
+		assert.Empty(t, result.Failures)
+	})
+}
+
+func TestNewRunner_Environment(t *testing.T) {
+	if _, err := exec.LookPath("python3"); err != nil {
+		t.Skip("python3 not found")
+	}
+	t.Setenv("GRIMOIRE_CAPABILITIES", "env,fs")
+
+	code := "import os\nprint(os.environ.get('GRIMOIRE_CAPABILITIES'))\n"
+	runner := NewRunner("GRIMOIRE_CAPABILITIES=")
+	stdout, err := runner.Execute(code)
+	require.NoError(t, err)
+	assert.Equal(t, "\n", stdout, "capabilities inherited from the environment are replaced")
+
+	stdout, _, err = runner.Trace(code)
+	require.NoError(t, err)
+	assert.Equal(t, "\n", stdout)
+}
+
+func TestRunner_UpdateAndAST(t *testing.T) {
+	dir := t.TempDir()
+	copyExample(t, dir, "hello_world.png", "hello.png")
+	astPath := filepath.Join(dir, "hello.ast.json")
+	require.NoError(t, os.WriteFile(astPath, []byte("{}"), 0o644))
+
+	cases, err := Discover(dir, false)
+	require.NoError(t, err)
+	require.Len(t, cases, 1)
+
+	// The placeholder AST does not match
+	runner := &Runner{Execute: fakeExecutor("")}
+	result := runner.Run(cases[0])
+	require.NoError(t, result.Err)
+	require.Len(t, result.Failures, 1)
+	assert.Equal(t, FailureAST, result.Failures[0].Kind)
+
+	// Updating rewrites the golden file, after which the case passes
+	runner.Update = true
+	result = runner.Run(cases[0])
+	require.NoError(t, result.Err)
+	assert.Equal(t, []string{astPath}, result.Updated)
+
+	runner.Update = false
+	result = runner.Run(cases[0])
+	assert.True(t, result.Passed())
+
+	data, err := os.ReadFile(astPath)
+	require.NoError(t, err)
+	assert.Contains(t, string(data), `"node": "Program"`)
+}
+
+func TestRunner_Coverage(t *testing.T) {
+	dir := t.TempDir()
+	copyExample(t, dir, "hello_world.png", "hello.png")
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "hello.out"), []byte("Hello, World!\n"), 0o644))
+	cases, err := Discover(dir, false)
+	require.NoError(t, err)
+	require.Len(t, cases, 1)
+
+	// Only the output line runs, as if the main block had been skipped
+	trace := func(code string) (string, *profiler.Lines, error) {
+		lines := &profiler.Lines{Counts: map[int]int{}}
+		for i, line := range strings.Split(code, "\n") {
+			if strings.Contains(line, "print(") {
+				lines.Counts[i+1] = 1
+			}
+		}
+		return "Hello, World!\n", lines, nil
+	}
+	runner := &Runner{Coverage: true, Trace: trace}
+	result := runner.Run(cases[0])
+	require.NoError(t, result.Err)
+	assert.True(t, result.Passed())
+	require.NotNil(t, result.Coverage)
+
+	counts := make(map[string]int)
+	for _, s := range result.Coverage.Symbols {
+		counts[s.Role] = s.Count
+	}
+	assert.Equal(t, 1, counts[string(parser.RoleOutput)])
+	assert.Equal(t, 0, counts[string(parser.RoleMainEntry)])
+	assert.Less(t, result.Coverage.Percent(), 100.0)
+
+	summary := Summarize([]Result{result, {Case: Case{Name: "ast only"}}})
+	require.Len(t, summary.Cases, 1, "cases that did not run have no coverage")
+	assert.Equal(t, "hello", summary.Cases[0].Name)
+	assert.Equal(t, result.Coverage.Covered(), summary.Covered)
+	assert.Equal(t, len(result.Coverage.Symbols), summary.Total)
+}
+
+// TestCoverageSchemaSnapshot guards the version 1 schema of coverage.json.
+// If it fails, either restore compatibility or increase
+// CoverageSchemaVersion and add a new snapshot.
+func TestCoverageSchemaSnapshot(t *testing.T) {
+	snapshot, err := os.ReadFile(filepath.Join("testdata", "coverage.v1.json"))
+	require.NoError(t, err)
+
+	coverage := &Coverage{Symbols: []SymbolCoverage{
+		{Index: 0, Type: "outer_circle", Role: string(parser.RoleMainEntry), X: 200, Y: 200, Size: 180},
+		{Index: 2, Type: "star", Role: string(parser.RoleOutput), X: 200, Y: 250, Size: 20, Count: 1},
+	}}
+	summary := Summarize([]Result{{Case: Case{Name: "hello", ImagePath: "hello.png"}, Coverage: coverage}})
+
+	data, err := json.Marshal(summary)
+	require.NoError(t, err)
+	assert.JSONEq(t, string(snapshot), string(data))
+}
+
+func TestCoverage_Image(t *testing.T) {
+	img := image.NewGray(image.Rect(0, 0, 40, 20))
+	for i := range img.Pix {
+		img.Pix[i] = 255
+	}
+	img.SetGray(10, 10, color.Gray{Y: 0})
+	img.SetGray(30, 10, color.Gray{Y: 0})
+	coverage := &Coverage{Symbols: []SymbolCoverage{
+		{X: 10, Y: 10, Size: 4, Count: 1},
+		{X: 30, Y: 10, Size: 4},
+	}}
+
+	canvas := coverage.Image(img)
+
+	assert.Equal(t, color.RGBA{A: 255}, canvas.RGBAAt(10, 10), "covered symbols keep their ink")
+	assert.Equal(t, uncoveredGray, canvas.RGBAAt(30, 10), "uncovered symbols are gray")
+	assert.Equal(t, color.RGBA{R: 255, G: 255, B: 255, A: 255}, canvas.RGBAAt(20, 10))
+}
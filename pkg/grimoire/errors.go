@@ -0,0 +1,67 @@
+package grimoire
+
+import (
+	"errors"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+)
+
+// Error types. Every error returned by this package is an *Error whose
+// Type is one of these, so callers can branch on the kind of failure:
+//
+//	if grimoire.IsErrorType(err, grimoire.NoOuterCircle) {
+//		// ask the user to draw the outer circle
+//	}
+const (
+	// File errors
+	FileNotFound      = grimoireErrors.FileNotFound
+	UnsupportedFormat = grimoireErrors.UnsupportedFormat
+	FileReadError     = grimoireErrors.FileReadError
+	FileWriteError    = grimoireErrors.FileWriteError
+
+	// Detection errors
+	NoSymbolsDetected    = grimoireErrors.NoSymbolsDetected
+	NoOuterCircle        = grimoireErrors.NoOuterCircle
+	InvalidSymbolShape   = grimoireErrors.InvalidSymbolShape
+	ImageProcessingError = grimoireErrors.ImageProcessingError
+
+	// Parser errors
+	SyntaxError          = grimoireErrors.SyntaxError
+	UnexpectedSymbol     = grimoireErrors.UnexpectedSymbol
+	MissingMainEntry     = grimoireErrors.MissingMainEntry
+	InvalidConnection    = grimoireErrors.InvalidConnection
+	UnbalancedExpression = grimoireErrors.UnbalancedExpression
+	DuplicateDefinition  = grimoireErrors.DuplicateDefinition
+	TypeError            = grimoireErrors.TypeError
+
+	// Compiler errors
+	CompilationError     = grimoireErrors.CompilationError
+	UnsupportedOperation = grimoireErrors.UnsupportedOperation
+
+	// Runtime errors
+	ExecutionError = grimoireErrors.ExecutionError
+
+	// Validation errors
+	ValidationError = grimoireErrors.ValidationError
+
+	// I/O errors
+	IOError = grimoireErrors.IOError
+
+	// Cancellation
+	Cancelled = grimoireErrors.Cancelled
+)
+
+// ErrorTypeOf returns the type of the first *Error in err's chain
+func ErrorTypeOf(err error) (ErrorType, bool) {
+	var gErr *Error
+	if errors.As(err, &gErr) {
+		return gErr.Type, true
+	}
+	return "", false
+}
+
+// IsErrorType reports whether err is an *Error of the given type
+func IsErrorType(err error, errorType ErrorType) bool {
+	t, ok := ErrorTypeOf(err)
+	return ok && t == errorType
+}
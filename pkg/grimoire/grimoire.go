@@ -0,0 +1,461 @@
+// Package grimoire is the public API of the Grimoire compiler. It turns an
+// image of a magic circle into Python code in three steps:
+//
+//	symbols, connections, err := grimoire.Detect("spell.png")
+//	program, err := grimoire.Parse(symbols, connections)
+//	code, err := grimoire.Compile(program)
+//
+// CompileImage runs all three at once. The types exported here are stable;
+// packages under internal/ may change between releases.
+package grimoire
+
+import (
+	"bytes"
+	"context"
+	"image"
+	"io"
+
+	"github.com/ayutaz/grimoire/internal/compiler"
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/parser"
+)
+
+// Detection results
+type (
+	// Symbol is a shape detected in an image
+	Symbol = detector.Symbol
+	// SymbolType identifies the kind of a Symbol
+	SymbolType = detector.SymbolType
+	// Position is a point in image pixel coordinates
+	Position = detector.Position
+	// Connection is a line drawn between two symbols
+	Connection = detector.Connection
+	// Alternative is another reading of an ambiguous Symbol
+	Alternative = detector.Alternative
+	// Backend is a detection implementation created by NewBackend
+	Backend = detector.Backend
+	// Stage names a step of detection reported to a ProgressFunc
+	Stage = detector.Stage
+	// ProgressFunc receives the stage detection is entering and a rough percentage done
+	ProgressFunc = detector.ProgressFunc
+	// Contour is an outline found in the binarized image
+	Contour = detector.Contour
+	// SymbolRecognizer classifies contours the built-in classifier does not know
+	SymbolRecognizer = detector.SymbolRecognizer
+	// RecognizerFunc adapts a function to a SymbolRecognizer
+	RecognizerFunc = detector.RecognizerFunc
+	// SymbolOrder selects when DetectEach yields symbols
+	SymbolOrder = detector.SymbolOrder
+	// DetectorConfig configures a backend created by NewBackendConfig
+	DetectorConfig = detector.Config
+	// Metrics describe how one detection went: time per stage and counts
+	Metrics = detector.Metrics
+	// StageTiming is the time spent in one stage of detection
+	StageTiming = detector.StageTiming
+	// PreprocessStep is one step of a preprocessing chain set in
+	// DetectorConfig.Preprocessing
+	PreprocessStep = detector.PreprocessStep
+	// InkLayer is the part of an image drawn in one ink
+	InkLayer = detector.InkLayer
+	// StrokeDrawing is a magic circle recorded as pen strokes
+	StrokeDrawing = detector.StrokeDrawing
+	// Stroke is the path of the pen from putting it down to lifting it
+	Stroke = detector.Stroke
+	// StrokePoint is a sample of the pen
+	StrokePoint = detector.StrokePoint
+	// MetricsFunc receives the Metrics of each detection that finishes
+	MetricsFunc = detector.MetricsFunc
+	// Strictness decides what becomes of shapes that match no symbol
+	Strictness = detector.Strictness
+	// Complexity is what is measured of the contours of an image against
+	// DetectorConfig.MaxContours and DetectorConfig.MaxContourPoints
+	Complexity = detector.Complexity
+	// ComplexityError is the cause of a TooComplex error, holding the
+	// Complexity measured and the limits it exceeds
+	ComplexityError = detector.ComplexityError
+)
+
+// Streaming
+type (
+	// FrameSource supplies successive frames to a StreamDetector
+	FrameSource = detector.FrameSource
+	// StreamDetector tracks symbols across frames, giving each a stable ID
+	StreamDetector = detector.StreamDetector
+	// Event reports a symbol that appeared, moved or was removed
+	Event = detector.Event
+	// EventKind is what happened to a tracked symbol
+	EventKind = detector.EventKind
+	// TrackedSymbol is a symbol in view and its stable ID
+	TrackedSymbol = detector.TrackedSymbol
+)
+
+// Geometry
+type (
+	// Transform rotates, scales and moves the coordinates of detection
+	// results; see Normalization
+	Transform = parser.Transform
+)
+
+// Syntax tree
+type (
+	// Program is the root of a parsed magic circle
+	Program = parser.Program
+	// FunctionDef is a function, including the main entry
+	FunctionDef = parser.FunctionDef
+	// Statement is a statement node
+	Statement = parser.Statement
+	// Expression is an expression node
+	Expression = parser.Expression
+	// Library is a parsed image linked into another program
+	Library = parser.Library
+	// Resolution records an ambiguous symbol read as one of its alternatives
+	Resolution = parser.Resolution
+)
+
+// Errors
+type (
+	// Error is the error type returned by every function in this package
+	Error = grimoireErrors.GrimoireError
+	// ErrorType classifies an Error; see the FileNotFound group of constants
+	ErrorType = grimoireErrors.ErrorType
+)
+
+// Symbol types
+const (
+	OuterCircle      = detector.OuterCircle
+	Circle           = detector.Circle
+	DoubleCircle     = detector.DoubleCircle
+	Square           = detector.Square
+	Rectangle        = detector.Rectangle
+	Triangle         = detector.Triangle
+	Pentagon         = detector.Pentagon
+	Hexagon          = detector.Hexagon
+	Star             = detector.Star
+	SixPointedStar   = detector.SixPointedStar
+	EightPointedStar = detector.EightPointedStar
+	Convergence      = detector.Convergence
+	Divergence       = detector.Divergence
+	Amplification    = detector.Amplification
+	Distribution     = detector.Distribution
+	Transfer         = detector.Transfer
+	Seal             = detector.Seal
+	Circulation      = detector.Circulation
+	Equal            = detector.Equal
+	NotEqual         = detector.NotEqual
+	LessThan         = detector.LessThan
+	GreaterThan      = detector.GreaterThan
+	LessEqual        = detector.LessEqual
+	GreaterEqual     = detector.GreaterEqual
+	LogicalAnd       = detector.LogicalAnd
+	LogicalOr        = detector.LogicalOr
+	LogicalNot       = detector.LogicalNot
+	LogicalXor       = detector.LogicalXor
+)
+
+// Detection backends
+const (
+	BackendSequential = detector.BackendSequential
+	BackendParallel   = detector.BackendParallel
+	BackendAuto       = detector.BackendAuto
+)
+
+// Strictness levels, set in DetectorConfig.Strictness and passed to
+// ParseWithStrictness
+const (
+	Lenient    = detector.Lenient
+	Strict     = detector.Strict
+	Permissive = detector.Permissive
+)
+
+// Symbol orders for DetectEach
+const (
+	OrderFinal     = detector.OrderFinal
+	OrderStreaming = detector.OrderStreaming
+)
+
+// Event kinds
+const (
+	SymbolAppeared = detector.SymbolAppeared
+	SymbolMoved    = detector.SymbolMoved
+	SymbolRemoved  = detector.SymbolRemoved
+)
+
+// Detection stages, in the order they are reported
+const (
+	StagePreprocess  = detector.StagePreprocess
+	StageOuterCircle = detector.StageOuterCircle
+	StageContours    = detector.StageContours
+	StageSymbols     = detector.StageSymbols
+	StageConnections = detector.StageConnections
+	StageDone        = detector.StageDone
+)
+
+// SymbolTypes returns every symbol type the detector can recognize
+func SymbolTypes() []SymbolType {
+	return detector.SymbolTypes()
+}
+
+// Detect finds the symbols in the image at imagePath and the connections
+// between them
+func Detect(imagePath string) ([]*Symbol, []Connection, error) {
+	return detector.DetectSymbols(imagePath)
+}
+
+// DetectReader is like Detect but reads the image from r. The format is
+// sniffed from the data; name is only used in error messages.
+func DetectReader(r io.Reader, name string) ([]*Symbol, []Connection, error) {
+	d := detector.NewDetector(detector.Config{Debug: false})
+	return d.DetectFromReader(r, name)
+}
+
+// DetectBytes is like Detect but takes the encoded image data directly, for
+// callers that never write the image to disk
+func DetectBytes(data []byte, name string) ([]*Symbol, []Connection, error) {
+	return DetectReader(bytes.NewReader(data), name)
+}
+
+// DetectImage is like Detect but takes an already decoded image, such as a
+// camera frame
+func DetectImage(img image.Image) ([]*Symbol, []Connection, error) {
+	d := detector.NewDetector(detector.Config{Debug: false})
+	return d.DetectImage(img)
+}
+
+// DetectContext is like Detect but reports each stage to progress, which may
+// be nil, and stops with a Cancelled error once ctx is done
+func DetectContext(ctx context.Context, imagePath string, progress ProgressFunc) ([]*Symbol, []Connection, error) {
+	d := detector.NewDetector(detector.Config{Progress: progress})
+	return d.DetectContext(ctx, imagePath)
+}
+
+// DetectImageContext is like DetectImage but reports each stage to progress,
+// which may be nil, and stops with a Cancelled error once ctx is done
+func DetectImageContext(ctx context.Context, img image.Image, progress ProgressFunc) ([]*Symbol, []Connection, error) {
+	d := detector.NewDetector(detector.Config{Progress: progress})
+	return d.DetectImageContext(ctx, img)
+}
+
+// IsPartial reports whether err was returned along with the symbols found
+// before detection ran past DetectorConfig.Timeout
+func IsPartial(err error) bool {
+	return detector.IsPartial(err)
+}
+
+// ParsePreprocessing parses a preprocessing chain such as
+// "grayscale, adaptive_threshold{block=31}, close{r=2}" for
+// DetectorConfig.Preprocessing
+func ParsePreprocessing(chain string) ([]PreprocessStep, error) {
+	return detector.ParsePreprocessing(chain)
+}
+
+// MaxInks is how many inks SeparateInks and DetectInks split an image into
+// at most
+const MaxInks = detector.MaxInks
+
+// SeparateInks splits img into one layer per ink, from the darkest
+func SeparateInks(img image.Image, inks int) []InkLayer {
+	return detector.SeparateInks(img, inks)
+}
+
+// DetectInks detects each ink of img apart with backend and merges the
+// results, tagging each symbol with its ink. source names img in errors.
+func DetectInks(backend Backend, img image.Image, inks int, source string) ([]*Symbol, []Connection, error) {
+	return detector.DetectInks(backend, img, inks, source)
+}
+
+// DecodeStrokes reads a stroke drawing encoded as JSON
+func DecodeStrokes(r io.Reader) (*StrokeDrawing, error) {
+	return detector.DecodeStrokes(r)
+}
+
+// DecodeAnnotations reads symbols and connections labeled by hand in a
+// LabelMe or COCO annotation file, in place of detecting them. image names
+// the image of a COCO file labeling several; it may be empty otherwise.
+func DecodeAnnotations(r io.Reader, image string) ([]*Symbol, []Connection, error) {
+	return detector.DecodeAnnotations(r, image)
+}
+
+// DetectStrokes builds symbols and connections from the geometry of pen
+// strokes instead of from an image. source names the drawing in errors.
+func DetectStrokes(drawing *StrokeDrawing, cfg DetectorConfig, source string) ([]*Symbol, []Connection, error) {
+	return detector.NewDetector(cfg).DetectStrokes(drawing, source)
+}
+
+// DetectWithMetrics is like Detect but also returns the time spent in each
+// stage, the contours and symbols found and the peak heap in use
+func DetectWithMetrics(imagePath string) ([]*Symbol, []Connection, Metrics, error) {
+	var metrics Metrics
+	d := detector.NewDetector(detector.Config{Metrics: func(m Metrics) { metrics = m }})
+	symbols, connections, err := d.Detect(imagePath)
+	return symbols, connections, metrics, err
+}
+
+// DetectEach calls yield with each symbol in img, either after detection in
+// the final order or, with OrderStreaming, as soon as each is classified.
+// Returning false from yield stops detection. An image without symbols
+// yields nothing and is not an error.
+func DetectEach(ctx context.Context, img image.Image, order SymbolOrder, yield func(*Symbol) bool) error {
+	d := detector.NewDetector(detector.Config{Debug: false})
+	return d.DetectEach(ctx, img, order, yield)
+}
+
+// NewStreamDetector creates a detector for successive frames, such as those
+// of a camera. The recognizers, if any, are consulted as in NewBackend.
+func NewStreamDetector(recognizers ...SymbolRecognizer) *StreamDetector {
+	return detector.NewStreamDetector(detector.Config{Recognizers: recognizers})
+}
+
+// ReconcileIDs renames symbols so that each one matching a symbol of the
+// same type in previous, within maxDistance pixels, keeps that symbol's ID.
+// Detection already gives every symbol an ID from its type and position;
+// reconciling keeps IDs stable when an edit moves a symbol further.
+func ReconcileIDs(symbols, previous []*Symbol, maxDistance float64) {
+	detector.ReconcileIDs(symbols, previous, maxDistance)
+}
+
+// Normalization returns the Transform moving the center of outerCircle to
+// the origin and scaling its radius to 1, so that drawings of any size and
+// placement share one coordinate space. Combine it with Rotate, ScaleBy and
+// Translate through Transform.Then, for example to turn the drawing upright.
+func Normalization(outerCircle *Symbol) Transform {
+	return parser.Normalization(outerCircle)
+}
+
+// Translate returns the Transform moving points by (dx, dy)
+func Translate(dx, dy float64) Transform {
+	return parser.Translate(dx, dy)
+}
+
+// Rotate returns the Transform turning points clockwise on screen about the
+// origin by angle radians
+func Rotate(angle float64) Transform {
+	return parser.Rotate(angle)
+}
+
+// ScaleBy returns the Transform scaling points from the origin by factor
+func ScaleBy(factor float64) Transform {
+	return parser.ScaleBy(factor)
+}
+
+// Backends returns the names accepted by NewBackend
+func Backends() []string {
+	return detector.Backends()
+}
+
+// NewBackend creates the named detection backend. Detect and DetectReader
+// use the sequential backend; auto picks the parallel one when more than
+// one CPU is available. The recognizers, if any, are consulted in order
+// before the built-in shape classifier. A backend may be shared between
+// goroutines, so a server can create one and use it for every request.
+func NewBackend(name string, recognizers ...SymbolRecognizer) (Backend, error) {
+	return detector.NewBackend(name, detector.Config{Recognizers: recognizers})
+}
+
+// NewBackendConfig creates the named detection backend with the given
+// configuration, for example to accept images larger than the default limits
+func NewBackendConfig(name string, cfg DetectorConfig) (Backend, error) {
+	return detector.NewBackend(name, cfg)
+}
+
+// Parse builds a program from detected symbols and connections
+func Parse(symbols []*Symbol, connections []Connection) (*Program, error) {
+	return parser.Parse(symbols, connections)
+}
+
+// ParseResolvingAmbiguity is like Parse, but when the program is invalid
+// it reads ambiguous symbols as their alternatives until one makes it
+// valid, and reports the choice it made
+func ParseResolvingAmbiguity(symbols []*Symbol, connections []Connection) (*Program, []Resolution, error) {
+	p := parser.NewParser()
+	program, err := p.ParseResolvingAmbiguity(symbols, connections)
+	return program, p.Resolutions(), err
+}
+
+// ParseWithStrictness is like Parse, with strictness deciding what becomes
+// of Unknown symbols. In permissive mode it also returns a warning for each
+// symbol it ignored.
+func ParseWithStrictness(symbols []*Symbol, connections []Connection, strictness Strictness) (*Program, []*Error, error) {
+	p := parser.NewParser().WithStrictness(strictness)
+	program, err := p.Parse(symbols, connections)
+	return program, p.Warnings(), err
+}
+
+// Link merges the function definitions of libraries into program. Each
+// library's main entry becomes a function named after its file.
+func Link(program *Program, libraries []Library) (*Program, error) {
+	return parser.Link(program, libraries)
+}
+
+// ParseLinked is like Parse followed by Link. Eight-pointed stars with
+// one, two or three dots inside call the first, second or third library.
+func ParseLinked(symbols []*Symbol, connections []Connection, libraries []Library) (*Program, error) {
+	names := make([]string, len(libraries))
+	for i, lib := range libraries {
+		names[i] = lib.Name
+	}
+	program, err := parser.NewParser().WithLibraries(names...).Parse(symbols, connections)
+	if err != nil {
+		return nil, err
+	}
+	return parser.Link(program, libraries)
+}
+
+// Compile generates Python code from a program
+func Compile(program *Program) (string, error) {
+	return compiler.Compile(program)
+}
+
+// CompileOptimized is like Compile but optimizes at the given level. Level 0
+// is the same as Compile; level 1 and above inline small functions called
+// from a single place and compute operations on constants.
+func CompileOptimized(program *Program, level int) (string, error) {
+	c := compiler.NewCompiler()
+	c.SetOptimization(level)
+	return c.Compile(program)
+}
+
+// CompileImage detects, parses and compiles the image at imagePath
+func CompileImage(imagePath string) (string, error) {
+	return compileImage(context.Background(), imagePath)
+}
+
+// CompileImageContext is like CompileImage but returns a Cancelled error as
+// soon as ctx is done, so a server can abandon a request without waiting for
+// detection to finish. The abandoned work stops at its next stage.
+func CompileImageContext(ctx context.Context, imagePath string) (string, error) {
+	if err := ctx.Err(); err != nil {
+		return "", grimoireErrors.CancelledError(err)
+	}
+
+	type result struct {
+		code string
+		err  error
+	}
+	// Buffered so the goroutine can finish after the caller has gone
+	done := make(chan result, 1)
+	go func() {
+		code, err := compileImage(ctx, imagePath)
+		done <- result{code: code, err: err}
+	}()
+
+	select {
+	case r := <-done:
+		return r.code, r.err
+	case <-ctx.Done():
+		return "", grimoireErrors.CancelledError(ctx.Err())
+	}
+}
+
+// compileImage detects, parses and compiles the image at imagePath
+func compileImage(ctx context.Context, imagePath string) (string, error) {
+	symbols, connections, err := DetectContext(ctx, imagePath, nil)
+	if err != nil {
+		return "", err
+	}
+	program, err := Parse(symbols, connections)
+	if err != nil {
+		return "", err
+	}
+	return Compile(program)
+}
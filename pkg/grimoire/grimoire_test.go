@@ -0,0 +1,311 @@
+package grimoire_test
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"image"
+	"image/png"
+	"math"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"testing"
+
+	"github.com/ayutaz/grimoire/pkg/grimoire"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+var helloWorld = filepath.Join("..", "..", "examples", "images", "hello_world.png")
+
+func TestCompileImage(t *testing.T) {
+	if _, err := os.Stat(helloWorld); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+
+	symbols, connections, err := grimoire.Detect(helloWorld)
+	require.NoError(t, err)
+	assert.NotEmpty(t, symbols)
+
+	hasOuterCircle := false
+	for _, s := range symbols {
+		if s.Type == grimoire.OuterCircle {
+			hasOuterCircle = true
+		}
+	}
+	assert.True(t, hasOuterCircle)
+
+	program, err := grimoire.Parse(symbols, connections)
+	require.NoError(t, err)
+	code, err := grimoire.Compile(program)
+	require.NoError(t, err)
+
+	direct, err := grimoire.CompileImage(helloWorld)
+	require.NoError(t, err)
+	assert.Equal(t, code, direct)
+}
+
+func TestDetectReader(t *testing.T) {
+	data, err := os.ReadFile(helloWorld)
+	if err != nil {
+		t.Skip("Example image not found")
+	}
+
+	expected, _, err := grimoire.Detect(helloWorld)
+	require.NoError(t, err)
+
+	symbols, _, err := grimoire.DetectReader(bytes.NewReader(data), "hello_world.png")
+	require.NoError(t, err)
+	assert.Len(t, symbols, len(expected))
+
+	symbols, _, err = grimoire.DetectBytes(data, "hello_world.png")
+	require.NoError(t, err)
+	assert.Len(t, symbols, len(expected))
+
+	img, err := png.Decode(bytes.NewReader(data))
+	require.NoError(t, err)
+	symbols, _, err = grimoire.DetectImage(img)
+	require.NoError(t, err)
+	assert.Len(t, symbols, len(expected))
+}
+
+func TestDetectContext(t *testing.T) {
+	if _, err := os.Stat(helloWorld); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+
+	var stages []grimoire.Stage
+	symbols, _, err := grimoire.DetectContext(context.Background(), helloWorld, func(stage grimoire.Stage, percent int) {
+		stages = append(stages, stage)
+	})
+	require.NoError(t, err)
+	assert.NotEmpty(t, symbols)
+	require.NotEmpty(t, stages)
+	assert.Equal(t, grimoire.StagePreprocess, stages[0])
+	assert.Equal(t, grimoire.StageDone, stages[len(stages)-1])
+
+	ctx, cancel := context.WithCancel(context.Background())
+	cancel()
+	_, _, err = grimoire.DetectContext(ctx, helloWorld, nil)
+	assert.True(t, grimoire.IsErrorType(err, grimoire.Cancelled), "%v", err)
+}
+
+func TestCompileImageContext(t *testing.T) {
+	if _, err := os.Stat(helloWorld); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+
+	expected, err := grimoire.CompileImage(helloWorld)
+	require.NoError(t, err)
+	code, err := grimoire.CompileImageContext(context.Background(), helloWorld)
+	require.NoError(t, err)
+	assert.Equal(t, expected, code)
+
+	ctx, cancel := context.WithCancel(context.Background())
+	cancel()
+	_, err = grimoire.CompileImageContext(ctx, helloWorld)
+	assert.True(t, grimoire.IsErrorType(err, grimoire.Cancelled), "%v", err)
+
+	// Errors from the work itself are returned unchanged
+	_, err = grimoire.CompileImageContext(context.Background(), filepath.Join(t.TempDir(), "missing.png"))
+	assert.True(t, grimoire.IsErrorType(err, grimoire.FileNotFound), "%v", err)
+}
+
+func TestNewBackendWithRecognizer(t *testing.T) {
+	if _, err := os.Stat(helloWorld); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+
+	expected, _, err := grimoire.Detect(helloWorld)
+	require.NoError(t, err)
+
+	calls := 0
+	backend, err := grimoire.NewBackend(grimoire.BackendSequential,
+		grimoire.RecognizerFunc(func(grimoire.Contour, *image.Gray) (grimoire.SymbolType, bool) {
+			calls++
+			return "", false
+		}))
+	require.NoError(t, err)
+
+	symbols, _, err := backend.Detect(helloWorld)
+	require.NoError(t, err)
+	assert.Equal(t, expected, symbols)
+	assert.Positive(t, calls)
+}
+
+func TestErrors(t *testing.T) {
+	_, err := grimoire.CompileImage(filepath.Join(t.TempDir(), "missing.png"))
+	require.Error(t, err)
+
+	var gErr *grimoire.Error
+	require.True(t, errors.As(err, &gErr))
+	assert.Equal(t, grimoire.ErrorType("FILE_NOT_FOUND"), gErr.Type)
+	assert.True(t, grimoire.IsErrorType(err, grimoire.FileNotFound))
+	assert.False(t, grimoire.IsErrorType(err, grimoire.NoOuterCircle))
+
+	// The type is found through wrapping
+	errorType, ok := grimoire.ErrorTypeOf(fmt.Errorf("compiling spell: %w", err))
+	assert.True(t, ok)
+	assert.Equal(t, grimoire.FileNotFound, errorType)
+
+	_, ok = grimoire.ErrorTypeOf(errors.New("plain"))
+	assert.False(t, ok)
+
+	white := image.NewGray(image.Rect(0, 0, 100, 100))
+	for i := range white.Pix {
+		white.Pix[i] = 0xff
+	}
+	var blank bytes.Buffer
+	require.NoError(t, png.Encode(&blank, white))
+	_, _, err = grimoire.DetectReader(&blank, "blank.png")
+	assert.True(t, grimoire.IsErrorType(err, grimoire.NoSymbolsDetected), "%v", err)
+}
+
+func TestDocumentRoundTrip(t *testing.T) {
+	if _, err := os.Stat(helloWorld); os.IsNotExist(err) {
+		t.Skip("Example image not found")
+	}
+
+	symbols, connections, err := grimoire.Detect(helloWorld)
+	require.NoError(t, err)
+	program, err := grimoire.Parse(symbols, connections)
+	require.NoError(t, err)
+	_, missingErr := grimoire.Detect(filepath.Join(t.TempDir(), "missing.png"))
+
+	doc := grimoire.Document{
+		Symbols:     symbols,
+		Connections: connections,
+		Program:     program,
+		Diagnostics: grimoire.Diagnostics(missingErr),
+	}
+	data, err := json.Marshal(doc)
+	require.NoError(t, err)
+	assert.Contains(t, string(data), `"schemaVersion":1`)
+
+	var decoded grimoire.Document
+	require.NoError(t, json.Unmarshal(data, &decoded))
+	assert.Len(t, decoded.Symbols, len(symbols))
+	assert.Len(t, decoded.Connections, len(connections))
+	assert.Equal(t, doc.Diagnostics, decoded.Diagnostics)
+
+	// The decoded program compiles to the same code
+	expected, err := grimoire.Compile(program)
+	require.NoError(t, err)
+	actual, err := grimoire.Compile(decoded.Program)
+	require.NoError(t, err)
+	assert.Equal(t, expected, actual)
+
+	again, err := json.Marshal(decoded)
+	require.NoError(t, err)
+	assert.JSONEq(t, string(data), string(again))
+}
+
+func TestDocumentSchemaVersion(t *testing.T) {
+	var doc grimoire.Document
+	assert.NoError(t, json.Unmarshal([]byte(`{"schemaVersion": 1}`), &doc))
+	assert.Error(t, json.Unmarshal([]byte(`{"schemaVersion": 2}`), &doc))
+	assert.Error(t, json.Unmarshal([]byte(`{}`), &doc))
+}
+
+// TestDocumentSchemaSnapshot guards the version 1 document schema, which
+// nests the detection, AST and diagnostics schemas. If it fails, either
+// restore compatibility or increase SchemaVersion and add a new snapshot,
+// keeping the old one decodable.
+func TestDocumentSchemaSnapshot(t *testing.T) {
+	snapshot, err := os.ReadFile(filepath.Join("testdata", "document.v1.json"))
+	require.NoError(t, err)
+
+	var doc grimoire.Document
+	require.NoError(t, json.Unmarshal(snapshot, &doc))
+	require.Len(t, doc.Symbols, 2)
+	assert.Equal(t, grimoire.OuterCircle, doc.Symbols[0].Type)
+	require.Len(t, doc.Connections, 1)
+	assert.Same(t, doc.Symbols[1], doc.Connections[0].To)
+	require.NotNil(t, doc.Program)
+	assert.True(t, doc.Program.HasOuterCircle)
+	require.Len(t, doc.Diagnostics, 1)
+	assert.Equal(t, grimoire.NoOuterCircle, doc.Diagnostics[0].Type)
+
+	// Encoding the decoded document reproduces the snapshot
+	data, err := json.Marshal(doc)
+	require.NoError(t, err)
+	assert.JSONEq(t, string(snapshot), string(data))
+}
+
+// TestRunDrawnEval runs a quoted program from the symbols of a drawing: a
+// circle inside a square quotes a program printing "Hello, World!", and a
+// line from the square into an empty eight-pointed star below the main
+// entry runs it
+func TestRunDrawnEval(t *testing.T) {
+	python, err := exec.LookPath("python3")
+	if err != nil {
+		t.Skip("Python3 not available")
+	}
+	symbol := func(symbolType grimoire.SymbolType, x, y, size float64) *grimoire.Symbol {
+		return &grimoire.Symbol{Type: symbolType, Position: grimoire.Position{X: x, Y: y}, Size: size, Pattern: "empty"}
+	}
+	square := symbol(grimoire.Square, 200, 140, 160)
+	quoteCircle := symbol(grimoire.Circle, 200, 140, 60*math.Sqrt(math.Pi))
+	quotedEntry := symbol(grimoire.DoubleCircle, 200, 110, 20)
+	quotedStar := symbol(grimoire.Star, 200, 170, 20)
+	main := symbol(grimoire.DoubleCircle, 80, 300, 20)
+	eval := symbol(grimoire.EightPointedStar, 200, 300, 20)
+	symbols := []*grimoire.Symbol{
+		symbol(grimoire.OuterCircle, 200, 200, 180*math.Sqrt(math.Pi)),
+		square, quoteCircle, quotedEntry, quotedStar, main, eval,
+	}
+	connections := []grimoire.Connection{
+		{From: quotedEntry, To: quotedStar, ConnectionType: "solid"},
+		{From: main, To: eval, ConnectionType: "solid"},
+		{From: square, To: eval, ConnectionType: "solid"},
+	}
+
+	program, err := grimoire.Parse(symbols, connections)
+	require.NoError(t, err)
+	code, err := grimoire.Compile(program)
+	require.NoError(t, err)
+	assert.Contains(t, code, "grimoire_eval(GrimoireQuote(")
+
+	out, err := exec.Command(python, "-c", code).CombinedOutput()
+	require.NoError(t, err, string(out))
+	assert.Equal(t, "Hello, World!\n", string(out))
+}
+
+// TestRunLinkedLibrary runs a program whose main entry calls the hello
+// world example, linked as a library, through an eight-pointed star with a
+// dot inside
+func TestRunLinkedLibrary(t *testing.T) {
+	python, err := exec.LookPath("python3")
+	if err != nil {
+		t.Skip("Python3 not available")
+	}
+	libraryPath := filepath.Join("..", "..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(libraryPath); err != nil {
+		t.Skip("Example image not found")
+	}
+	librarySymbols, libraryConnections, err := grimoire.Detect(libraryPath)
+	require.NoError(t, err)
+	library, err := grimoire.Parse(librarySymbols, libraryConnections)
+	require.NoError(t, err)
+
+	symbol := func(symbolType grimoire.SymbolType, x, y, size float64, pattern string) *grimoire.Symbol {
+		return &grimoire.Symbol{Type: symbolType, Position: grimoire.Position{X: x, Y: y}, Size: size, Pattern: pattern}
+	}
+	main := symbol(grimoire.DoubleCircle, 200, 100, 20, "empty")
+	call := symbol(grimoire.EightPointedStar, 200, 200, 20, "dot")
+	symbols := []*grimoire.Symbol{symbol(grimoire.OuterCircle, 200, 200, 180*math.Sqrt(math.Pi), "empty"), main, call}
+	connections := []grimoire.Connection{{From: main, To: call, ConnectionType: "solid"}}
+
+	program, err := grimoire.ParseLinked(symbols, connections, []grimoire.Library{{Name: libraryPath, Program: library}})
+	require.NoError(t, err)
+	code, err := grimoire.Compile(program)
+	require.NoError(t, err)
+	assert.Contains(t, code, "def hello_world(")
+
+	out, err := exec.Command(python, "-c", code).CombinedOutput()
+	require.NoError(t, err, string(out))
+	assert.Equal(t, "Hello, World!\n", string(out), "the library runs when the program calls it")
+}
@@ -0,0 +1,95 @@
+package grimoire
+
+import (
+	"encoding/json"
+	"fmt"
+
+	"github.com/ayutaz/grimoire/internal/detector"
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/parser"
+)
+
+// SchemaVersion is the version of the JSON written by Document. It is
+// increased whenever the encoding changes incompatibly.
+const SchemaVersion = 1
+
+// Diagnostic is a structured description of an error, as shown by the CLI
+type Diagnostic = grimoireErrors.Diagnostic
+
+// Diagnostics converts an error returned by this package into diagnostics,
+// one per underlying problem
+func Diagnostics(err error) []Diagnostic {
+	return grimoireErrors.Diagnostics(err)
+}
+
+// Document bundles the results of the compiler stages for storage or
+// transfer as JSON. Any field may be left empty.
+type Document struct {
+	Symbols     []*Symbol
+	Connections []Connection
+	Program     *Program
+	Diagnostics []Diagnostic
+}
+
+// documentJSON is the encoded form of a Document
+type documentJSON struct {
+	SchemaVersion int             `json:"schemaVersion"`
+	Detection     json.RawMessage `json:"detection,omitempty"`
+	Program       json.RawMessage `json:"program,omitempty"`
+	Diagnostics   []Diagnostic    `json:"diagnostics,omitempty"`
+}
+
+// MarshalJSON encodes the document with the current SchemaVersion
+func (d Document) MarshalJSON() ([]byte, error) {
+	encoded := documentJSON{SchemaVersion: SchemaVersion, Diagnostics: d.Diagnostics}
+	if d.Symbols != nil || d.Connections != nil {
+		detection, err := detector.EncodeJSON(d.Symbols, d.Connections)
+		if err != nil {
+			return nil, err
+		}
+		encoded.Detection = detection
+	}
+	if d.Program != nil {
+		program, err := parser.EncodeJSON(d.Program)
+		if err != nil {
+			return nil, err
+		}
+		encoded.Program = program
+	}
+	return json.Marshal(encoded)
+}
+
+// UnmarshalJSON decodes a document, rejecting schema versions newer than
+// SchemaVersion
+func (d *Document) UnmarshalJSON(data []byte) error {
+	var decoded documentJSON
+	if err := json.Unmarshal(data, &decoded); err != nil {
+		return err
+	}
+	if decoded.SchemaVersion < 1 || decoded.SchemaVersion > SchemaVersion {
+		return fmt.Errorf("unsupported schema version %d (supported: %d)", decoded.SchemaVersion, SchemaVersion)
+	}
+
+	*d = Document{Diagnostics: decoded.Diagnostics}
+	if decoded.Detection != nil {
+		symbols, connections, err := detector.DecodeJSON(decoded.Detection)
+		if err != nil {
+			return err
+		}
+		d.Symbols, d.Connections = symbols, connections
+	}
+	if decoded.Program != nil {
+		node, err := parser.DecodeJSON(decoded.Program)
+		if err != nil {
+			return err
+		}
+		if node != nil {
+			program, ok := node.(*parser.Program)
+			if !ok {
+				return fmt.Errorf("expected a program, found %T", node)
+			}
+			d.Program = program
+		}
+	}
+	return nil
+}
@@ -0,0 +1,505 @@
+package test
+
+import (
+	"bytes"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"image"
+	"image/color"
+	"image/png"
+	"io"
+	"net"
+	"net/http"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"runtime"
+	"testing"
+	"time"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/ayutaz/grimoire/internal/history"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// cliResult is what one invocation of the binary wrote and how it exited
+type cliResult struct {
+	stdout   string
+	stderr   string
+	exitCode int
+}
+
+// cliCase is one invocation of the binary and what it must produce
+type cliCase struct {
+	name  string
+	args  []string
+	stdin string // path of a file to pipe to stdin
+	// env is added to the environment shared by every case
+	env  []string
+	exit int
+	// setup prepares what the invocation reads
+	setup func(t *testing.T)
+	// check inspects the result beyond the exit code
+	check func(t *testing.T, r cliResult)
+}
+
+// buildCLI builds the grimoire binary into a temporary directory and
+// returns its path
+func buildCLI(t *testing.T) string {
+	t.Helper()
+	binary := filepath.Join(t.TempDir(), "grimoire")
+	if runtime.GOOS == "windows" {
+		binary += ".exe"
+	}
+	buildCmd := exec.Command("go", "build", "-ldflags", "-X main.version=e2e-version", "-o", binary, "../cmd/grimoire")
+	output, err := buildCmd.CombinedOutput()
+	require.NoError(t, err, "Failed to build grimoire binary: %s", output)
+	return binary
+}
+
+// runCLI runs the binary in English with args, keeping stdout and stderr
+// apart, and returns what it wrote and its exit code
+func runCLI(t *testing.T, binary string, env []string, stdin string, args ...string) cliResult {
+	t.Helper()
+	cmd := exec.Command(binary, append(args, "--lang", "en")...)
+	cmd.Env = append(os.Environ(), env...)
+	var stdout, stderr bytes.Buffer
+	cmd.Stdout = &stdout
+	cmd.Stderr = &stderr
+	if stdin != "" {
+		file, err := os.Open(stdin)
+		require.NoError(t, err)
+		defer file.Close()
+		cmd.Stdin = file
+	}
+
+	err := cmd.Run()
+	result := cliResult{stdout: stdout.String(), stderr: stderr.String()}
+	var exitErr *exec.ExitError
+	if errors.As(err, &exitErr) {
+		result.exitCode = exitErr.ExitCode()
+	} else {
+		require.NoError(t, err)
+	}
+	return result
+}
+
+// writeBlankPNG writes a white image with nothing drawn on it
+func writeBlankPNG(t *testing.T, path string) {
+	t.Helper()
+	img := image.NewRGBA(image.Rect(0, 0, 200, 200))
+	for y := 0; y < 200; y++ {
+		for x := 0; x < 200; x++ {
+			img.Set(x, y, color.White)
+		}
+	}
+	f, err := os.Create(path)
+	require.NoError(t, err)
+	defer f.Close()
+	require.NoError(t, png.Encode(f, img))
+}
+
+// copyFile copies the file at src to dst
+func copyFile(t *testing.T, src, dst string) {
+	t.Helper()
+	data, err := os.ReadFile(src)
+	require.NoError(t, err)
+	require.NoError(t, os.WriteFile(dst, data, 0o644))
+}
+
+// lspMessages frames JSON-RPC messages as a language client sends them
+func lspMessages(bodies ...string) string {
+	var buf bytes.Buffer
+	for _, body := range bodies {
+		fmt.Fprintf(&buf, "Content-Length: %d\r\n\r\n%s", len(body), body)
+	}
+	return buf.String()
+}
+
+// startCLI starts the binary in English with args for a command that runs
+// until it is stopped, stopping it when the test ends
+func startCLI(t *testing.T, binary string, env []string, args ...string) *exec.Cmd {
+	t.Helper()
+	cmd := exec.Command(binary, append(args, "--lang", "en")...)
+	cmd.Env = append(os.Environ(), env...)
+	require.NoError(t, cmd.Start())
+	t.Cleanup(func() {
+		if cmd.ProcessState == nil {
+			_ = cmd.Process.Kill()
+			_ = cmd.Wait()
+		}
+	})
+	return cmd
+}
+
+// waitFor polls ready until it reports true, failing the test after ten
+// seconds
+func waitFor(t *testing.T, what string, ready func() bool) {
+	t.Helper()
+	deadline := time.Now().Add(10 * time.Second)
+	for !ready() {
+		if time.Now().After(deadline) {
+			t.Fatalf("Timed out waiting for %s", what)
+		}
+		time.Sleep(50 * time.Millisecond)
+	}
+}
+
+// Checks shared by the cases of TestE2E_CLISurface
+var (
+	quietStderr = func(t *testing.T, r cliResult) {
+		assert.NotContains(t, r.stderr, "Error", "errors go to stderr only on failure")
+	}
+	silentStdout = func(t *testing.T, r cliResult) {
+		assert.Empty(t, r.stdout, "failures write nothing to stdout")
+		assert.NotEmpty(t, r.stderr, "failures are reported on stderr")
+	}
+	jsonStdout = func(t *testing.T, r cliResult) {
+		assert.True(t, json.Valid([]byte(r.stdout)), "stdout is JSON: %s", r.stdout)
+		quietStderr(t, r)
+	}
+)
+
+// TestE2E_CLISurface runs every subcommand of the binary but serve and
+// daemon, which run until stopped and are covered by TestE2E_Serve and
+// TestE2E_Daemon, against fixture images, checking the exit code and that
+// results go to stdout while diagnostics go to stderr
+func TestE2E_CLISurface(t *testing.T) {
+	if testing.Short() {
+		t.Skip("Skipping E2E test in short mode")
+	}
+	helloWorld := filepath.Join("..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(helloWorld); os.IsNotExist(err) {
+		t.Skip("Skipping test: examples/images/hello_world.png not found")
+	}
+	binary := buildCLI(t)
+
+	// History and the cache are kept in the temporary directory, away from
+	// those of the user
+	tmpDir := t.TempDir()
+	cacheDir := filepath.Join(tmpDir, "cache")
+	env := []string{
+		"GRIMOIRE_HISTORY=", "GRIMOIRE_HISTORY_DIR=" + filepath.Join(tmpDir, "history"),
+		"GRIMOIRE_CACHE_DIR=" + cacheDir,
+	}
+	blankImage := filepath.Join(tmpDir, "blank.png")
+	writeBlankPNG(t, blankImage)
+	noMainImage := filepath.Join(tmpDir, "no_main.png")
+	require.NoError(t, createPerformanceTestImage(noMainImage, "simple"))
+	fixturesDir := filepath.Join(tmpDir, "fixtures")
+	require.NoError(t, os.Mkdir(fixturesDir, 0o755))
+	copyFile(t, helloWorld, filepath.Join(fixturesDir, "hello.png"))
+
+	// The program every other command is compared against
+	compiled := runCLI(t, binary, env, "", "compile", helloWorld)
+	require.Equal(t, grimoireErrors.ExitOK, compiled.exitCode, compiled.stderr)
+	code := compiled.stdout
+	require.Contains(t, code, "print(")
+	sameCode := func(t *testing.T, r cliResult) {
+		assert.Equal(t, code, r.stdout)
+	}
+	outputPath := filepath.Join(tmpDir, "out.py")
+	qrPath := filepath.Join(tmpDir, "printable.png")
+	bundlePath := filepath.Join(tmpDir, "hello.grimoire")
+	keyPath := filepath.Join(tmpDir, "signing.key")
+	overlayPath := filepath.Join(tmpDir, "overlay.png")
+	formattedPath := filepath.Join(tmpDir, "formatted.png")
+	optimizedPath := filepath.Join(tmpDir, "optimized.py")
+	buildDir := filepath.Join(tmpDir, "build")
+	datasetDir := filepath.Join(tmpDir, "dataset")
+
+	// What is read from stdin by inspect and lsp
+	inspectInput := filepath.Join(tmpDir, "inspect.txt")
+	require.NoError(t, os.WriteFile(inspectInput, []byte("s\nq\n"), 0o644))
+	lspInput := filepath.Join(tmpDir, "lsp.txt")
+	require.NoError(t, os.WriteFile(lspInput, []byte(lspMessages(
+		`{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}`,
+		`{"jsonrpc":"2.0","id":2,"method":"shutdown"}`,
+		`{"jsonrpc":"2.0","method":"exit"}`,
+	)), 0o644))
+
+	// A compilation recorded in a history of its own, for replay
+	replayDir := filepath.Join(tmpDir, "replay")
+	require.NoError(t, history.New(replayDir).Record(&history.Entry{
+		ID: "e2e0replay", Command: "compile", Image: helloWorld, Target: "python", Outcome: history.OutcomeOK, Code: code,
+	}))
+
+	tests := []cliCase{
+		{name: "version", args: []string{"--version"}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "e2e-version")
+			assert.Empty(t, r.stderr)
+		}},
+		{name: "symbols", args: []string{"symbols"}, check: quietStderr},
+		{name: "compile from stdin", args: []string{"compile", "-"}, stdin: helloWorld, check: sameCode},
+		{name: "compile to file", args: []string{"compile", helloWorld, "-o", outputPath}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, outputPath)
+			written, err := os.ReadFile(outputPath)
+			require.NoError(t, err)
+			assert.Equal(t, code, string(written))
+		}},
+		{name: "compile optimized", args: []string{"compile", helloWorld, "-O", "2"}, check: quietStderr},
+		{name: "compile to qr", args: []string{"compile", helloWorld, "--emit", "qr", "-o", qrPath}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, qrPath)
+			assert.FileExists(t, qrPath)
+		}},
+		{name: "compile to bundle", args: []string{"compile", helloWorld, "--bundle", "-o", bundlePath}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, bundlePath)
+			assert.FileExists(t, bundlePath)
+		}},
+		{name: "compile to unknown target", args: []string{"compile", helloWorld, "--emit", "cobol"},
+			exit: grimoireErrors.ExitValidation, check: silentStdout},
+		{name: "compile missing file", args: []string{"compile", filepath.Join(tmpDir, "missing.png")},
+			exit: grimoireErrors.ExitIOError, check: silentStdout},
+		{name: "compile blank image", args: []string{"compile", blankImage},
+			exit: grimoireErrors.ExitNoOuterCircle, check: silentStdout},
+		{name: "compile with json diagnostics", args: []string{"compile", blankImage, "--format", "json"},
+			exit: grimoireErrors.ExitNoOuterCircle, check: func(t *testing.T, r cliResult) {
+				silentStdout(t, r)
+				assert.True(t, json.Valid([]byte(r.stderr)), "stderr is JSON: %s", r.stderr)
+			}},
+		{name: "sign", args: []string{"sign", bundlePath, "--key", keyPath, "--generate-key"}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "Signed "+bundlePath)
+			assert.FileExists(t, keyPath+".pub")
+		}},
+		{name: "verify", args: []string{"verify", bundlePath, "--key", keyPath + ".pub"}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "signature OK")
+			quietStderr(t, r)
+		}},
+		{name: "debug", args: []string{"debug", helloWorld}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "Debug Information for hello_world.png")
+			quietStderr(t, r)
+		}},
+		{name: "parse", args: []string{"parse", helloWorld}, check: jsonStdout},
+		{name: "correct", args: []string{"correct", helloWorld}, check: jsonStdout},
+		{name: "stats as json", args: []string{"stats", helloWorld, "--format", "json"}, check: jsonStdout},
+		{name: "check", args: []string{"check", helloWorld}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "no problems found")
+		}},
+		{name: "explain", args: []string{"explain", helloWorld}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "Interpretation of hello_world.png")
+		}},
+		{name: "validate", args: []string{"validate", helloWorld}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "Magic circle is valid")
+			quietStderr(t, r)
+		}},
+		{name: "validate without main entry", args: []string{"validate", noMainImage},
+			exit: grimoireErrors.ExitValidation, check: func(t *testing.T, r cliResult) {
+				assert.NotEmpty(t, r.stderr)
+			}},
+		{name: "format", args: []string{"format", helloWorld}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "Analyzing magic circle structure")
+			quietStderr(t, r)
+		}},
+		{name: "fmt", args: []string{"fmt", helloWorld, "-o", formattedPath}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "Formatted magic circle written to "+formattedPath)
+			assert.FileExists(t, formattedPath)
+		}},
+		{name: "extract without library image", args: []string{"extract", helloWorld, "--region", "0,0,100,100"},
+			exit: grimoireErrors.ExitValidation, check: silentStdout},
+		{name: "optimize", args: []string{"optimize", helloWorld, "-o", optimizedPath}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "Analyzing program for optimization opportunities")
+			assert.FileExists(t, optimizedPath)
+		}},
+		{name: "inspect", args: []string{"inspect", helloWorld}, stdin: inspectInput, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "=== Inspecting hello_world.png ===")
+			quietStderr(t, r)
+		}},
+		{name: "diff of an image with itself", args: []string{"diff", helloWorld, helloWorld}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "No differences")
+			quietStderr(t, r)
+		}},
+		{name: "diff with overlay", args: []string{"diff", noMainImage, helloWorld, "--overlay", overlayPath}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "Overlay written to "+overlayPath)
+			assert.FileExists(t, overlayPath)
+		}},
+		{name: "conform", args: []string{"conform", fixturesDir}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "CONFORM hello.png")
+			assert.Contains(t, r.stdout, "1 conform, 0 diverge, 1 total")
+		}},
+		{name: "bench", args: []string{"bench", fixturesDir, "--iterations", "1"}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "detect/hello.png")
+			assert.Contains(t, r.stdout, "compile/hello.png")
+		}},
+		{name: "batch", args: []string{"batch", fixturesDir, "-o", buildDir}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "OK    hello.png")
+			written, err := os.ReadFile(filepath.Join(buildDir, "hello.py"))
+			require.NoError(t, err)
+			assert.Equal(t, code, string(written))
+		}},
+		{name: "dataset extract", args: []string{"dataset", "extract", fixturesDir, "--out", datasetDir}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "hello.png:")
+			assert.Contains(t, r.stdout, "(detected)")
+			assert.FileExists(t, filepath.Join(datasetDir, "labels.csv"))
+		}},
+		{name: "examples list", args: []string{"examples", "list"}, check: quietStderr},
+		{name: "cache stats before running", args: []string{"cache", "stats"}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "Cache directory: "+cacheDir)
+			assert.Contains(t, r.stdout, "Entries: 0\n")
+		}},
+		{name: "history list", args: []string{"history", "list"}, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, "No runs are recorded")
+		}},
+		{name: "history replay of a compilation", args: []string{"history", "replay", "e2e0"},
+			env: []string{"GRIMOIRE_HISTORY_DIR=" + replayDir}, check: sameCode},
+		{name: "history replay of an unknown entry", args: []string{"history", "replay", "ffff"},
+			env: []string{"GRIMOIRE_HISTORY_DIR=" + replayDir}, exit: grimoireErrors.ExitValidation, check: silentStdout},
+		{name: "lsp", args: []string{"lsp"}, stdin: lspInput, check: func(t *testing.T, r cliResult) {
+			assert.Contains(t, r.stdout, `"capabilities"`)
+			quietStderr(t, r)
+		}},
+		{name: "unknown command", args: []string{"transmute"}, exit: grimoireErrors.ExitFailure, check: silentStdout},
+	}
+
+	// Running needs python3; the output of run is what the compiled
+	// program prints
+	if python, err := exec.LookPath("python3"); err == nil {
+		require.NoError(t, os.WriteFile(outputPath+".expected.py", []byte(code), 0o644))
+		expected, err := exec.Command(python, outputPath+".expected.py").Output()
+		require.NoError(t, err)
+		sameOutput := func(t *testing.T, r cliResult) {
+			assert.Equal(t, string(expected), r.stdout)
+		}
+		cacheEntries := func(n int) func(t *testing.T, r cliResult) {
+			return func(t *testing.T, r cliResult) {
+				assert.Contains(t, r.stdout, fmt.Sprintf("Entries: %d\n", n))
+			}
+		}
+
+		// A golden test of the image, expecting what it prints
+		specDir := filepath.Join(tmpDir, "spells")
+		require.NoError(t, os.Mkdir(specDir, 0o755))
+		copyFile(t, helloWorld, filepath.Join(specDir, "hello.png"))
+		require.NoError(t, os.WriteFile(filepath.Join(specDir, "hello.out"), expected, 0o644))
+
+		tests = append(tests,
+			cliCase{name: "run", args: []string{"run", helloWorld, "--no-cache"}, check: sameOutput},
+			cliCase{name: "cache stats after running uncached", args: []string{"cache", "stats"}, check: cacheEntries(0)},
+			cliCase{name: "run filling the cache", args: []string{"run", helloWorld}, check: sameOutput},
+			cliCase{name: "cache stats after filling", args: []string{"cache", "stats"}, check: cacheEntries(1)},
+			// The cached program is replaced, so only a cache hit prints it
+			cliCase{name: "run cached", args: []string{"run", helloWorld}, setup: func(t *testing.T) {
+				entries, err := filepath.Glob(filepath.Join(cacheDir, "*.cache"))
+				require.NoError(t, err)
+				require.Len(t, entries, 1)
+				require.NoError(t, os.WriteFile(entries[0], []byte("print('from the cache')\n"), 0o644))
+			}, check: func(t *testing.T, r cliResult) {
+				assert.Equal(t, "from the cache\n", r.stdout)
+			}},
+			cliCase{name: "cache stats after a hit", args: []string{"cache", "stats"}, check: cacheEntries(1)},
+			cliCase{name: "cache clear", args: []string{"cache", "clear"}, check: func(t *testing.T, r cliResult) {
+				assert.Contains(t, r.stdout, "Removed 1 cached program(s) from "+cacheDir)
+			}},
+			cliCase{name: "cache stats after clearing", args: []string{"cache", "stats"}, check: cacheEntries(0)},
+			cliCase{name: "run from stdin", args: []string{"run", "-"}, stdin: helloWorld, check: sameOutput},
+			cliCase{name: "run bundle", args: []string{"run", bundlePath}, check: sameOutput},
+			cliCase{name: "run signed bundle", args: []string{"run", bundlePath, "--trusted-key", keyPath + ".pub"}, check: sameOutput},
+			cliCase{name: "run qr", args: []string{"run", qrPath, "--from-qr"}, check: sameOutput},
+			cliCase{name: "examples run", args: []string{"examples", "run", "hello-world"}, check: sameOutput},
+			cliCase{name: "test", args: []string{"test", specDir}, check: func(t *testing.T, r cliResult) {
+				assert.Contains(t, r.stdout, "PASS hello")
+				assert.Contains(t, r.stdout, "1 passed, 0 failed, 1 total")
+			}},
+		)
+	}
+
+	// Cases run in order, since later cases use what earlier ones wrote
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if tt.setup != nil {
+				tt.setup(t)
+			}
+			r := runCLI(t, binary, append(append([]string{}, env...), tt.env...), tt.stdin, tt.args...)
+			t.Logf("stdout: %s\nstderr: %s", r.stdout, r.stderr)
+			assert.NotContains(t, r.stdout+r.stderr, "panic", "Should not panic")
+			require.Equal(t, tt.exit, r.exitCode)
+			if tt.check != nil {
+				tt.check(t, r)
+			}
+		})
+	}
+}
+
+// TestE2E_Serve compiles an image posted to the HTTP server
+func TestE2E_Serve(t *testing.T) {
+	if testing.Short() {
+		t.Skip("Skipping E2E test in short mode")
+	}
+	helloWorld := filepath.Join("..", "examples", "images", "hello_world.png")
+	data, err := os.ReadFile(helloWorld)
+	if err != nil {
+		t.Skip("Skipping test: examples/images/hello_world.png not found")
+	}
+	binary := buildCLI(t)
+
+	// Find a free port for the server to listen on
+	listener, err := net.Listen("tcp", "127.0.0.1:0")
+	require.NoError(t, err)
+	addr := listener.Addr().String()
+	require.NoError(t, listener.Close())
+
+	startCLI(t, binary, nil, "serve", "--addr", addr)
+	base := "http://" + addr
+	waitFor(t, "the server to listen", func() bool {
+		resp, err := http.Get(base + "/healthz")
+		if err != nil {
+			return false
+		}
+		resp.Body.Close()
+		return resp.StatusCode == http.StatusNoContent
+	})
+
+	resp, err := http.Post(base+"/compile", "image/png", bytes.NewReader(data))
+	require.NoError(t, err)
+	defer resp.Body.Close()
+	body, err := io.ReadAll(resp.Body)
+	require.NoError(t, err)
+	require.Equal(t, http.StatusOK, resp.StatusCode, string(body))
+	assert.Contains(t, string(body), "print(")
+}
+
+// TestE2E_Daemon compiles an image detecting through a daemon, which
+// removes its socket when interrupted
+func TestE2E_Daemon(t *testing.T) {
+	if testing.Short() {
+		t.Skip("Skipping E2E test in short mode")
+	}
+	if runtime.GOOS == "windows" {
+		t.Skip("Skipping test: the daemon listens on a unix socket")
+	}
+	helloWorld := filepath.Join("..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(helloWorld); os.IsNotExist(err) {
+		t.Skip("Skipping test: examples/images/hello_world.png not found")
+	}
+	binary := buildCLI(t)
+
+	// Unix socket paths are limited in length, so the socket is not put in
+	// t.TempDir()
+	socketDir, err := os.MkdirTemp("", "grimoire-e2e")
+	require.NoError(t, err)
+	t.Cleanup(func() { os.RemoveAll(socketDir) })
+	socket := filepath.Join(socketDir, "daemon.sock")
+	env := []string{"GRIMOIRE_HISTORY=", "GRIMOIRE_SOCKET=" + socket}
+
+	compiled := runCLI(t, binary, env, "", "compile", helloWorld)
+	require.Equal(t, grimoireErrors.ExitOK, compiled.exitCode, compiled.stderr)
+
+	daemon := startCLI(t, binary, env, "daemon", "--socket", socket)
+	waitFor(t, "the daemon to listen", func() bool {
+		conn, err := net.Dial("unix", socket)
+		if err != nil {
+			return false
+		}
+		conn.Close()
+		return true
+	})
+
+	remote := runCLI(t, binary, env, "", "compile", helloWorld, "--remote")
+	require.Equal(t, grimoireErrors.ExitOK, remote.exitCode, remote.stderr)
+	assert.Equal(t, compiled.stdout, remote.stdout)
+
+	require.NoError(t, daemon.Process.Signal(os.Interrupt))
+	require.NoError(t, daemon.Wait())
+	assert.NoFileExists(t, socket)
+}
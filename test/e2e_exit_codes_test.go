@@ -0,0 +1,121 @@
+package test
+
+import (
+	"errors"
+	"image"
+	"image/color"
+	"image/png"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"runtime"
+	"testing"
+
+	grimoireErrors "github.com/ayutaz/grimoire/internal/errors"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// TestE2E_ExitCodes tests that each class of failure exits with its documented code
+func TestE2E_ExitCodes(t *testing.T) {
+	if testing.Short() {
+		t.Skip("Skipping E2E test in short mode")
+	}
+
+	// Build the binary
+	binaryFile := "grimoire_exit_test"
+	if runtime.GOOS == "windows" {
+		binaryFile = "grimoire_exit_test.exe"
+	}
+	buildCmd := exec.Command("go", "build", "-o", binaryFile, "../cmd/grimoire")
+	buildCmd.Dir = "."
+	err := buildCmd.Run()
+	require.NoError(t, err, "Failed to build grimoire binary")
+	defer os.Remove(binaryFile)
+
+	binaryName, err := filepath.Abs(binaryFile)
+	require.NoError(t, err)
+
+	tmpDir := t.TempDir()
+
+	// A blank image has no outer circle
+	blankImage := filepath.Join(tmpDir, "blank.png")
+	img := image.NewRGBA(image.Rect(0, 0, 200, 200))
+	for y := 0; y < 200; y++ {
+		for x := 0; x < 200; x++ {
+			img.Set(x, y, color.White)
+		}
+	}
+	f, err := os.Create(blankImage)
+	require.NoError(t, err)
+	require.NoError(t, png.Encode(f, img))
+	f.Close()
+
+	// A magic circle without a main entry fails validation
+	noMainImage := filepath.Join(tmpDir, "no_main.png")
+	require.NoError(t, createPerformanceTestImage(noMainImage, "simple"))
+
+	type exitCodeTest struct {
+		name     string
+		args     []string
+		env      []string
+		expected int
+	}
+
+	tests := []exitCodeTest{
+		{
+			name:     "success",
+			args:     []string{"symbols"},
+			expected: grimoireErrors.ExitOK,
+		},
+		{
+			name:     "usage error",
+			args:     []string{"invalid-command"},
+			expected: grimoireErrors.ExitFailure,
+		},
+		{
+			name:     "missing file",
+			args:     []string{"compile", filepath.Join(tmpDir, "missing.png")},
+			expected: grimoireErrors.ExitIOError,
+		},
+		{
+			name:     "no outer circle",
+			args:     []string{"compile", blankImage},
+			expected: grimoireErrors.ExitNoOuterCircle,
+		},
+		{
+			name:     "validation error",
+			args:     []string{"validate", noMainImage},
+			expected: grimoireErrors.ExitValidation,
+		},
+	}
+
+	// Without python3 on the PATH the generated program cannot run
+	helloWorld := filepath.Join("..", "examples", "images", "hello_world.png")
+	if _, err := os.Stat(helloWorld); err == nil && runtime.GOOS != "windows" {
+		tests = append(tests, exitCodeTest{
+			name:     "runtime error",
+			args:     []string{"run", helloWorld},
+			env:      []string{"PATH=" + tmpDir},
+			expected: grimoireErrors.ExitRuntimeError,
+		})
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			cmd := exec.Command(binaryName, tt.args...)
+			cmd.Env = append(os.Environ(), tt.env...)
+			output, err := cmd.CombinedOutput()
+			t.Logf("Output: %s", output)
+
+			exitCode := 0
+			var exitErr *exec.ExitError
+			if errors.As(err, &exitErr) {
+				exitCode = exitErr.ExitCode()
+			} else {
+				require.NoError(t, err)
+			}
+			assert.Equal(t, tt.expected, exitCode)
+		})
+	}
+}
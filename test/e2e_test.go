@@ -12,6 +12,18 @@ import (
 	"github.com/stretchr/testify/require"
 )
 
+// TestMain keeps the programs cached by run out of the user's cache directory
+func TestMain(m *testing.M) {
+	dir, err := os.MkdirTemp("", "grimoire-cache-*")
+	if err != nil {
+		panic(err)
+	}
+	os.Setenv("GRIMOIRE_CACHE_DIR", dir)
+	code := m.Run()
+	os.RemoveAll(dir)
+	os.Exit(code)
+}
+
 // TestE2E_HelloWorld tests end-to-end hello world execution
 func TestE2E_HelloWorld(t *testing.T) {
 	if testing.Short() {
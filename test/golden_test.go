@@ -0,0 +1,137 @@
+package test
+
+import (
+	"bytes"
+	"encoding/json"
+	"flag"
+	"math"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+	"testing"
+
+	"github.com/ayutaz/grimoire/internal/compiler"
+	"github.com/ayutaz/grimoire/internal/detector"
+	"github.com/ayutaz/grimoire/internal/parser"
+	"github.com/ayutaz/grimoire/internal/spelltest"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// Golden fixtures are images in goldenDir with expectation files that share
+// their base name:
+//
+//	hello_world.png           the canonical image
+//	hello_world.symbols.json  expected symbols, in the detection JSON format
+//	hello_world.py            expected generated code
+//
+// Symbols are compared by type, position and size within the tolerances
+// below; confidence, pattern and connections are ignored. Run with -update
+// to rewrite the expectation files from the current detector.
+const (
+	goldenDir = "testdata/golden"
+
+	goldenSymbolsSuffix = ".symbols.json"
+	goldenCodeSuffix    = ".py"
+
+	// goldenPositionTolerance is the distance, in pixels, a symbol may move
+	goldenPositionTolerance = 5.0
+	// goldenSizeTolerance is the fraction by which a symbol's size may change
+	goldenSizeTolerance = 0.15
+)
+
+var updateGolden = flag.Bool("update", false, "rewrite golden expectation files with actual results")
+
+func TestGoldenImages(t *testing.T) {
+	images, err := filepath.Glob(filepath.Join(goldenDir, "*.png"))
+	require.NoError(t, err)
+	require.NotEmpty(t, images, "no golden fixtures in %s", goldenDir)
+
+	for _, imagePath := range images {
+		base := strings.TrimSuffix(imagePath, filepath.Ext(imagePath))
+		t.Run(filepath.Base(base), func(t *testing.T) {
+			symbols, connections, err := detector.DetectSymbols(imagePath)
+			require.NoError(t, err)
+			program, err := parser.Parse(symbols, connections)
+			require.NoError(t, err)
+			code, err := compiler.Compile(program)
+			require.NoError(t, err)
+
+			if *updateGolden {
+				writeGoldenSymbols(t, base+goldenSymbolsSuffix, symbols)
+				require.NoError(t, os.WriteFile(base+goldenCodeSuffix, []byte(code), 0o644))
+				return
+			}
+
+			data, err := os.ReadFile(base + goldenSymbolsSuffix)
+			require.NoError(t, err)
+			expected, _, err := detector.DecodeJSON(data)
+			require.NoError(t, err)
+			compareGoldenSymbols(t, expected, symbols)
+
+			expectedCode, err := os.ReadFile(base + goldenCodeSuffix)
+			require.NoError(t, err)
+			if string(expectedCode) != code {
+				t.Errorf("generated code differs from %s:\n%s",
+					base+goldenCodeSuffix, spelltest.Diff(string(expectedCode), code))
+			}
+		})
+	}
+}
+
+// compareGoldenSymbols pairs expected and actual symbols of the same type,
+// closest pairs first, and checks each pair against the tolerances
+func compareGoldenSymbols(t *testing.T, expected, actual []*detector.Symbol) {
+	t.Helper()
+	assert.Equal(t, goldenTypeCounts(expected), goldenTypeCounts(actual), "symbol types differ")
+
+	type pair struct {
+		expected, actual *detector.Symbol
+		distance         float64
+	}
+	var pairs []pair
+	for _, e := range expected {
+		for _, a := range actual {
+			if e.Type == a.Type {
+				d := math.Hypot(e.Position.X-a.Position.X, e.Position.Y-a.Position.Y)
+				pairs = append(pairs, pair{expected: e, actual: a, distance: d})
+			}
+		}
+	}
+	sort.SliceStable(pairs, func(i, j int) bool { return pairs[i].distance < pairs[j].distance })
+
+	claimed := make(map[*detector.Symbol]bool)
+	for _, p := range pairs {
+		if claimed[p.expected] || claimed[p.actual] {
+			continue
+		}
+		claimed[p.expected], claimed[p.actual] = true, true
+
+		assert.LessOrEqual(t, p.distance, goldenPositionTolerance,
+			"%s expected at (%.0f, %.0f) was detected at (%.0f, %.0f)", p.expected.Type,
+			p.expected.Position.X, p.expected.Position.Y, p.actual.Position.X, p.actual.Position.Y)
+		assert.InDelta(t, p.expected.Size, p.actual.Size, p.expected.Size*goldenSizeTolerance,
+			"size of %s at (%.0f, %.0f)", p.expected.Type, p.expected.Position.X, p.expected.Position.Y)
+	}
+}
+
+func goldenTypeCounts(symbols []*detector.Symbol) map[detector.SymbolType]int {
+	counts := make(map[detector.SymbolType]int)
+	for _, s := range symbols {
+		counts[s.Type]++
+	}
+	return counts
+}
+
+// writeGoldenSymbols writes symbols as indented detection JSON, without
+// connections since they are not compared
+func writeGoldenSymbols(t *testing.T, path string, symbols []*detector.Symbol) {
+	t.Helper()
+	data, err := detector.EncodeJSON(symbols, nil)
+	require.NoError(t, err)
+	var indented bytes.Buffer
+	require.NoError(t, json.Indent(&indented, data, "", "  "))
+	indented.WriteString("\n")
+	require.NoError(t, os.WriteFile(path, indented.Bytes(), 0o644))
+}
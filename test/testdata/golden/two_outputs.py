@@ -0,0 +1,6 @@
+#!/usr/bin/env python3
+# Generated by Grimoire
+
+if __name__ == "__main__":
+    print("Hello, World!")
+    print("Hello, World!")
@@ -141,6 +141,8 @@ async function runTests() {
         assert(typeof global.processGrimoireImage === 'function', 'processGrimoireImage should be registered');
         assert(typeof global.validateGrimoireCode === 'function', 'validateGrimoireCode should be registered');
         assert(typeof global.formatGrimoireCode === 'function', 'formatGrimoireCode should be registered');
+        assert(typeof global.grimoireDetect === 'function', 'grimoireDetect should be registered');
+        assert(typeof global.grimoireCompile === 'function', 'grimoireCompile should be registered');
         
     } catch (error) {
         console.error('WASM initialization error:', error);